@@ -40,7 +40,7 @@ pub fn this_plic_context() -> usize {
     this_cpu() as usize * 2 + 1
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CpuSet {
     bitset: BitVec,
 }
@@ -165,7 +165,10 @@ impl UserContext {
 }
 
 impl UserContextApiInternal for UserContext {
-    fn execute(&mut self) -> crate::user::UserEvent {
+    fn execute<F>(&mut self, mut has_kernel_event: F) -> crate::user::UserEvent
+    where
+        F: FnMut() -> bool,
+    {
         // return when it is syscall or is cpu exception.
         let ret = loop {
             self.user_context.run();
@@ -173,6 +176,7 @@ impl UserContextApiInternal for UserContext {
             let scause = riscv::register::scause::read();
             match scause.cause() {
                 Trap::Interrupt(SupervisorExternal) => call_irq_callback_functions(&self.as_trap_frame()),
+                Trap::Interrupt(SupervisorTimer) => crate::arch::timer::timer_callback(),
                 Trap::Interrupt(_) => unimplemented!(),
                 Trap::Exception(Exception::UserEnvCall) => break UserEvent::Syscall,
                 Trap::Exception(e) => {
@@ -184,6 +188,10 @@ impl UserContextApiInternal for UserContext {
                     break UserEvent::Exception
                 }
             }
+            crate::arch::irq::enable_local();
+            if has_kernel_event() {
+                break UserEvent::KernelEvent;
+            }
         };
 
         crate::arch::irq::enable_local();
@@ -31,6 +31,20 @@ pub(crate) fn enable_local() {
     unsafe { riscv::interrupt::enable() }
 }
 
+/// Enables the local IRQs and halts the CPU until the next interrupt arrives.
+pub(crate) fn enable_local_and_halt() {
+    // A pending interrupt wakes up the hart from WFI even if it is disabled, so it will not
+    // be missed before the hart is halted.
+    unsafe { riscv::asm::wfi() };
+    enable_local();
+}
+
+/// Wakes up the given CPU if it is halted.
+pub(crate) fn wake_up_cpu(cpu_id: u32) {
+    // FIXME: only one CPU is started now, which never needs to be woken up by other CPUs.
+    let _ = cpu_id;
+}
+
 pub(crate) fn disable_local() {
     riscv::interrupt::disable();
 }
@@ -17,6 +17,9 @@ extern "C" fn trap_handler(f: &mut TrapFrame) {
         Trap::Interrupt(SupervisorExternal) => {
             call_irq_callback_functions(f);
         }
+        Trap::Interrupt(SupervisorTimer) => {
+            super::timer::timer_callback();
+        }
         Trap::Interrupt(_) => unimplemented!(),
         Trap::Exception(e) => {
             let stval = riscv::register::stval::read();
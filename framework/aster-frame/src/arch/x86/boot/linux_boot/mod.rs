@@ -136,6 +136,9 @@ fn init_memory_regions(memory_regions: &'static Once<Vec<MemoryRegion>>) {
     // Add the kernel region.
     regions.push(MemoryRegion::kernel());
 
+    // Reserve the page for the boot trampoline of the application processors.
+    regions.push(super::smp::ap_boot_region());
+
     // Add the initramfs region.
     regions.push(MemoryRegion::new(
         boot_params.hdr.ramdisk_image as usize,
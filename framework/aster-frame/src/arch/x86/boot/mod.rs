@@ -21,6 +21,7 @@
 mod linux_boot;
 mod multiboot;
 mod multiboot2;
+pub(crate) mod smp;
 
 use core::arch::global_asm;
 
@@ -139,6 +139,9 @@ fn init_memory_regions(memory_regions: &'static Once<Vec<MemoryRegion>>) {
     // Add the kernel region.
     regions.push(MemoryRegion::kernel());
 
+    // Reserve the page for the boot trampoline of the application processors.
+    regions.push(super::smp::ap_boot_region());
+
     // Add the initramfs area.
     if info.mods_count != 0 {
         let modules_addr = info.mods_addr as usize;
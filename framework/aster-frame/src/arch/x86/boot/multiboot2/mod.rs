@@ -143,6 +143,9 @@ fn init_memory_regions(memory_regions: &'static Once<Vec<MemoryRegion>>) {
     // Add the kernel region since Grub does not specify it.
     regions.push(MemoryRegion::kernel());
 
+    // Reserve the page for the boot trampoline of the application processors.
+    regions.push(super::smp::ap_boot_region());
+
     // Add the boot module region since Grub does not specify it.
     let mb2_module_tag = mb2_info.module_tags();
     for module in mb2_module_tag {
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Booting the application processors (APs).
+//!
+//! The bootstrap processor (BSP) wakes up each AP with the INIT-SIPI-SIPI sequence. The APs
+//! start in real mode at the boot trampoline, which is copied to a reserved page in the low
+//! memory since the startup IPI can only specify such an address. The trampoline switches the
+//! AP to long mode and jumps to [`ap_early_entry`], which initializes the AP and runs its idle
+//! task.
+
+use core::{
+    arch::global_asm,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+};
+
+use acpi::{platform::ProcessorState, PlatformInfo};
+use log::{info, warn};
+use x86::{
+    cpuid::cpuid,
+    msr::{wrmsr, IA32_TSC_AUX},
+};
+
+use crate::{
+    arch::x86::{
+        cpu::{num_cpus, set_num_cpus},
+        kernel::{
+            acpi::ACPI_TABLES,
+            apic::{
+                self, APIC_INSTANCE, ICR_DELIVERY_MODE_INIT, ICR_DELIVERY_MODE_STARTUP,
+                ICR_LEVEL_ASSERT,
+            },
+        },
+        mm::current_page_table_paddr,
+        read_tsc, timer, tsc_freq,
+    },
+    boot::memory_region::{MemoryRegion, MemoryRegionType},
+    cpu::MAX_CPUS,
+    vm::{paddr_to_vaddr, Paddr, VmAllocOptions, PAGE_SIZE},
+};
+
+global_asm!(include_str!("ap_boot.S"));
+
+/// The physical address where the boot trampoline is copied to.
+///
+/// It must be consistent with the one in `ap_boot.S`.
+const AP_BOOT_START_PA: Paddr = 0x8000;
+
+/// The number of pages of the boot stack of each AP.
+///
+/// The boot stack is never freed since the AP does not return to its boot context.
+const AP_BOOT_STACK_PAGES: usize = 16;
+
+/// The arguments passed to the AP being booted, which are read by the boot trampoline.
+#[repr(C)]
+struct ApBootInfo {
+    page_table_pa: AtomicU64,
+    stack_top: AtomicU64,
+    cpu_id: AtomicU32,
+}
+
+#[no_mangle]
+static AP_BOOT_INFO: ApBootInfo = ApBootInfo {
+    page_table_pa: AtomicU64::new(0),
+    stack_top: AtomicU64::new(0),
+    cpu_id: AtomicU32::new(0),
+};
+
+/// Whether the AP being booted has finished its initialization.
+static AP_ONLINE: AtomicBool = AtomicBool::new(false);
+
+/// The memory region occupied by the boot trampoline, which must not be used by the frame
+/// allocator.
+pub(super) fn ap_boot_region() -> MemoryRegion {
+    MemoryRegion::new(AP_BOOT_START_PA, PAGE_SIZE, MemoryRegionType::Reserved)
+}
+
+/// Boots all the enabled APs reported by the ACPI tables one by one.
+///
+/// The CPU IDs are assigned sequentially, with that of the BSP being zero. This function should
+/// be called on the BSP after the kernel page table is activated.
+pub(crate) fn boot_all_aps() {
+    if !APIC_INSTANCE.is_completed() || tsc_freq() == 0 || !is_rdtscp_supported() {
+        return;
+    }
+    let Some(processor_info) = ACPI_TABLES
+        .get()
+        .and_then(|tables| PlatformInfo::new(&*tables.lock()).ok())
+        .and_then(|platform_info| platform_info.processor_info)
+    else {
+        return;
+    };
+
+    extern "C" {
+        fn __ap_boot_start();
+        fn __ap_boot_end();
+    }
+    let trampoline_len = __ap_boot_end as usize - __ap_boot_start as usize;
+    assert!(trampoline_len <= PAGE_SIZE);
+    // Safety: the destination page is reserved for the trampoline and is never used by others.
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            __ap_boot_start as *const u8,
+            paddr_to_vaddr(AP_BOOT_START_PA) as *mut u8,
+            trampoline_len,
+        );
+    }
+
+    // Safety: the BSP is CPU 0, which is read by `this_cpu` after more CPUs are online.
+    unsafe { wrmsr(IA32_TSC_AUX, 0) };
+    apic::set_apic_id(0, processor_info.boot_processor.local_apic_id);
+    AP_BOOT_INFO
+        .page_table_pa
+        .store(current_page_table_paddr() as u64, Ordering::Relaxed);
+
+    for processor in processor_info.application_processors.iter() {
+        if matches!(processor.state, ProcessorState::Disabled) {
+            continue;
+        }
+        let cpu_id = num_cpus();
+        if cpu_id as usize >= MAX_CPUS {
+            warn!("[SMP]: Too many CPUs, only {} of them are used", MAX_CPUS);
+            break;
+        }
+        boot_ap(processor.local_apic_id, cpu_id);
+    }
+
+    info!("[SMP]: {} CPU(s) online", num_cpus());
+}
+
+fn boot_ap(apic_id: u32, cpu_id: u32) {
+    let Ok(stack) = VmAllocOptions::new(AP_BOOT_STACK_PAGES)
+        .is_contiguous(true)
+        .alloc_contiguous()
+    else {
+        warn!("[SMP]: Failed to allocate the boot stack of CPU {}", cpu_id);
+        return;
+    };
+    AP_BOOT_INFO
+        .stack_top
+        .store(paddr_to_vaddr(stack.end_paddr()) as u64, Ordering::Relaxed);
+    core::mem::forget(stack);
+    AP_BOOT_INFO.cpu_id.store(cpu_id, Ordering::Relaxed);
+    AP_ONLINE.store(false, Ordering::Relaxed);
+
+    // The CPU-local data of the AP is selected by its CPU ID once the AP is counted.
+    apic::set_apic_id(cpu_id, apic_id);
+    set_num_cpus(cpu_id + 1);
+
+    const SIPI_VECTOR: u32 = (AP_BOOT_START_PA / PAGE_SIZE) as u32;
+    let send_ipi = |icr_low: u32| {
+        APIC_INSTANCE
+            .get()
+            .unwrap()
+            .lock_irq_disabled()
+            .send_ipi(apic_id, icr_low);
+    };
+    send_ipi(ICR_DELIVERY_MODE_INIT | ICR_LEVEL_ASSERT);
+    delay_us(10_000);
+    send_ipi(ICR_DELIVERY_MODE_STARTUP | ICR_LEVEL_ASSERT | SIPI_VECTOR);
+    delay_us(200);
+    if !AP_ONLINE.load(Ordering::Acquire) {
+        send_ipi(ICR_DELIVERY_MODE_STARTUP | ICR_LEVEL_ASSERT | SIPI_VECTOR);
+    }
+
+    // Wait for the AP for at most one second.
+    let deadline = read_tsc() + tsc_freq();
+    while !AP_ONLINE.load(Ordering::Acquire) {
+        if read_tsc() > deadline {
+            warn!(
+                "[SMP]: CPU {} (APIC ID {}) does not respond",
+                cpu_id, apic_id
+            );
+            set_num_cpus(cpu_id);
+            return;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+/// The Rust entry point of the APs, which is called by the boot trampoline.
+#[no_mangle]
+extern "C" fn ap_early_entry(cpu_id: u32) -> ! {
+    // Safety: the CPU ID is unique, and is set before any CPU-local data is accessed.
+    unsafe { wrmsr(IA32_TSC_AUX, cpu_id as u64) };
+
+    super::super::enable_common_cpu_features();
+    crate::trap::init();
+    apic::init_ap();
+    timer::init_ap();
+
+    AP_ONLINE.store(true, Ordering::Release);
+    crate::task::run_idle_task();
+}
+
+fn is_rdtscp_supported() -> bool {
+    const RDTSCP_SUPPORT: u32 = 1 << 27;
+    let cpuid = cpuid!(0x8000_0001);
+    (cpuid.edx & RDTSCP_SUPPORT) > 0
+}
+
+fn delay_us(us: u64) {
+    let deadline = read_tsc() + tsc_freq() * us / 1_000_000;
+    while read_tsc() < deadline {
+        core::hint::spin_loop();
+    }
+}
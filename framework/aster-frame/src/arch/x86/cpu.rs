@@ -2,10 +2,14 @@
 
 //! CPU.
 
-use alloc::vec::Vec;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{
     arch::x86_64::{_fxrstor, _fxsave},
     fmt::Debug,
+    sync::atomic::{AtomicU32, Ordering},
 };
 
 use bitflags::bitflags;
@@ -17,6 +21,7 @@ use log::debug;
 #[cfg(feature = "intel_tdx")]
 use tdx_guest::tdcall;
 use trapframe::{GeneralRegs, UserContext as RawUserContext};
+use x86::cpuid::{cpuid, CpuIdResult};
 use x86_64::registers::rflags::RFlags;
 
 #[cfg(feature = "intel_tdx")]
@@ -26,19 +31,37 @@ use crate::{
     user::{UserContextApi, UserContextApiInternal, UserEvent},
 };
 
+/// The number of CPUs that have been started.
+static NUM_CPUS: AtomicU32 = AtomicU32::new(1);
+
 /// Returns the number of CPUs.
 pub fn num_cpus() -> u32 {
-    // FIXME: we only start one cpu now.
-    1
+    NUM_CPUS.load(Ordering::Acquire)
+}
+
+/// Sets the number of CPUs that have been started.
+///
+/// Before an application processor (AP) is started, the number must include it and the ID
+/// of the bootstrap processor (BSP) must have been stored in its `IA32_TSC_AUX` MSR.
+pub(crate) fn set_num_cpus(num_cpus: u32) {
+    NUM_CPUS.store(num_cpus, Ordering::Release);
 }
 
 /// Returns the ID of this CPU.
 pub fn this_cpu() -> u32 {
-    // FIXME: we only start one cpu now.
-    0
+    if num_cpus() == 1 {
+        return 0;
+    }
+
+    // Each CPU stores its ID in the `IA32_TSC_AUX` MSR when it is started, which can be read
+    // by `rdtscp` without trapping into the hypervisor.
+    let mut cpu_id = 0;
+    // Safety: `rdtscp` is available since it is required to start the APs.
+    unsafe { core::arch::x86_64::__rdtscp(&mut cpu_id) };
+    cpu_id
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CpuSet {
     bitset: BitVec,
 }
@@ -95,6 +118,243 @@ impl CpuSet {
     }
 }
 
+/// The information of a CPU, which is read by the `cpuid` instruction.
+#[derive(Debug, Clone)]
+pub struct CpuInfo {
+    /// The vendor ID, e.g., `GenuineIntel`.
+    pub vendor_id: String,
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
+    /// The brand string, e.g., `Intel(R) Core(TM) i7-8700 CPU @ 3.20GHz`.
+    pub model_name: String,
+    /// The names of the supported features, which are the same as the ones used by Linux.
+    pub features: Vec<&'static str>,
+    /// The number of bits in a physical address.
+    pub phys_addr_bits: u32,
+    /// The number of bits in a virtual address.
+    pub virt_addr_bits: u32,
+}
+
+/// Returns the information of this CPU.
+pub fn cpu_info() -> CpuInfo {
+    let leaf_0 = cpuid!(0);
+    let max_leaf = leaf_0.eax;
+    let vendor_id = cpuid_string(&[leaf_0.ebx, leaf_0.edx, leaf_0.ecx]);
+
+    let leaf_1 = cpuid!(1);
+    let base_family = (leaf_1.eax >> 8) & 0xf;
+    let base_model = (leaf_1.eax >> 4) & 0xf;
+    let family = if base_family == 0xf {
+        base_family + ((leaf_1.eax >> 20) & 0xff)
+    } else {
+        base_family
+    };
+    let model = if base_family == 0x6 || base_family == 0xf {
+        base_model | ((leaf_1.eax >> 16) & 0xf) << 4
+    } else {
+        base_model
+    };
+    let stepping = leaf_1.eax & 0xf;
+    let leaf_7 = if max_leaf >= 7 {
+        cpuid!(7, 0)
+    } else {
+        EMPTY_CPUID_RESULT
+    };
+
+    let max_ext_leaf = cpuid!(0x8000_0000).eax;
+    let ext_leaf = |leaf: u32| {
+        if max_ext_leaf >= leaf {
+            cpuid!(leaf)
+        } else {
+            EMPTY_CPUID_RESULT
+        }
+    };
+    let model_name = {
+        let regs: Vec<u32> = (0x8000_0002..=0x8000_0004)
+            .map(ext_leaf)
+            .flat_map(|res| [res.eax, res.ebx, res.ecx, res.edx])
+            .collect();
+        cpuid_string(&regs).trim().to_string()
+    };
+    let ext_leaf_1 = ext_leaf(0x8000_0001);
+    let (phys_addr_bits, virt_addr_bits) = if max_ext_leaf >= 0x8000_0008 {
+        let eax = cpuid!(0x8000_0008).eax;
+        (eax & 0xff, (eax >> 8) & 0xff)
+    } else {
+        (36, 48)
+    };
+
+    // The features are listed in the same order as Linux.
+    let feature_words = [
+        (leaf_1.edx, &LEAF_1_EDX_FEATURES),
+        (ext_leaf_1.edx, &EXT_LEAF_1_EDX_FEATURES),
+        (leaf_1.ecx, &LEAF_1_ECX_FEATURES),
+        (ext_leaf_1.ecx, &EXT_LEAF_1_ECX_FEATURES),
+        (leaf_7.ebx, &LEAF_7_EBX_FEATURES),
+    ];
+    let mut features = Vec::new();
+    for (word, names) in feature_words {
+        for (bit, name) in names.iter().enumerate() {
+            if !name.is_empty() && word & (1 << bit) != 0 {
+                features.push(*name);
+            }
+        }
+    }
+
+    CpuInfo {
+        vendor_id,
+        family,
+        model,
+        stepping,
+        model_name,
+        features,
+        phys_addr_bits,
+        virt_addr_bits,
+    }
+}
+
+/// The result of an unsupported `cpuid` leaf.
+const EMPTY_CPUID_RESULT: CpuIdResult = CpuIdResult {
+    eax: 0,
+    ebx: 0,
+    ecx: 0,
+    edx: 0,
+};
+
+/// Converts the registers returned by `cpuid` to a string, which ends at the first null byte.
+fn cpuid_string(regs: &[u32]) -> String {
+    let bytes: Vec<u8> = regs
+        .iter()
+        .flat_map(|reg| reg.to_le_bytes())
+        .take_while(|byte| *byte != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// The names of the features in the bits of `EDX` returned by `cpuid` leaf 1.
+const LEAF_1_EDX_FEATURES: [&str; 32] = [
+    "fpu", "vme", "de", "pse", "tsc", "msr", "pae", "mce", "cx8", "apic", "", "sep", "mtrr", "pge",
+    "mca", "cmov", "pat", "pse36", "pn", "clflush", "", "dts", "acpi", "mmx", "fxsr", "sse",
+    "sse2", "ss", "ht", "tm", "ia64", "pbe",
+];
+
+/// The names of the features in the bits of `EDX` returned by `cpuid` leaf 0x8000_0001.
+///
+/// The bits that duplicate the ones of leaf 1 are not named.
+const EXT_LEAF_1_EDX_FEATURES: [&str; 32] = [
+    "", "", "", "", "", "", "", "", "", "", "", "syscall", "", "", "", "", "", "", "", "mp", "nx",
+    "", "mmxext", "", "", "fxsr_opt", "pdpe1gb", "rdtscp", "", "lm", "3dnowext", "3dnow",
+];
+
+/// The names of the features in the bits of `ECX` returned by `cpuid` leaf 1.
+const LEAF_1_ECX_FEATURES: [&str; 32] = [
+    "pni",
+    "pclmulqdq",
+    "dtes64",
+    "monitor",
+    "ds_cpl",
+    "vmx",
+    "smx",
+    "est",
+    "tm2",
+    "ssse3",
+    "cid",
+    "sdbg",
+    "fma",
+    "cx16",
+    "xtpr",
+    "pdcm",
+    "",
+    "pcid",
+    "dca",
+    "sse4_1",
+    "sse4_2",
+    "x2apic",
+    "movbe",
+    "popcnt",
+    "tsc_deadline_timer",
+    "aes",
+    "xsave",
+    "",
+    "avx",
+    "f16c",
+    "rdrand",
+    "hypervisor",
+];
+
+/// The names of the features in the bits of `ECX` returned by `cpuid` leaf 0x8000_0001.
+const EXT_LEAF_1_ECX_FEATURES: [&str; 32] = [
+    "lahf_lm",
+    "cmp_legacy",
+    "svm",
+    "extapic",
+    "cr8_legacy",
+    "abm",
+    "sse4a",
+    "misalignsse",
+    "3dnowprefetch",
+    "osvw",
+    "ibs",
+    "xop",
+    "skinit",
+    "wdt",
+    "",
+    "lwp",
+    "fma4",
+    "tce",
+    "",
+    "nodeid_msr",
+    "",
+    "tbm",
+    "topoext",
+    "perfctr_core",
+    "perfctr_nb",
+    "",
+    "bpext",
+    "ptsc",
+    "perfctr_llc",
+    "mwaitx",
+    "",
+    "",
+];
+
+/// The names of the features in the bits of `EBX` returned by `cpuid` leaf 7.
+const LEAF_7_EBX_FEATURES: [&str; 32] = [
+    "fsgsbase",
+    "tsc_adjust",
+    "sgx",
+    "bmi1",
+    "hle",
+    "avx2",
+    "",
+    "smep",
+    "bmi2",
+    "erms",
+    "invpcid",
+    "rtm",
+    "cqm",
+    "",
+    "mpx",
+    "rdt_a",
+    "avx512f",
+    "avx512dq",
+    "rdseed",
+    "adx",
+    "smap",
+    "avx512ifma",
+    "",
+    "clflushopt",
+    "clwb",
+    "intel_pt",
+    "avx512pf",
+    "avx512er",
+    "avx512cd",
+    "sha_ni",
+    "avx512bw",
+    "avx512vl",
+];
+
 /// Cpu context, including both general-purpose registers and floating-point registers.
 #[derive(Clone, Default, Copy, Debug)]
 #[repr(C)]
@@ -235,7 +495,10 @@ impl UserContext {
 }
 
 impl UserContextApiInternal for UserContext {
-    fn execute(&mut self) -> crate::user::UserEvent {
+    fn execute<F>(&mut self, mut has_kernel_event: F) -> crate::user::UserEvent
+    where
+        F: FnMut() -> bool,
+    {
         // set interrupt flag so that in user mode it can receive external interrupts
         // set ID flag which means cpu support CPUID instruction
         self.user_context.general.rflags |= (RFlags::INTERRUPT_FLAG | RFlags::ID).bits() as usize;
@@ -267,6 +530,11 @@ impl UserContextApiInternal for UserContext {
                 }
             };
             call_irq_callback_functions(&self.as_trap_frame());
+            crate::task::preempt_in_user_mode();
+            crate::arch::irq::enable_local();
+            if has_kernel_event() {
+                return UserEvent::KernelEvent;
+            }
         }
 
         crate::arch::irq::enable_local();
@@ -35,6 +35,18 @@ pub(crate) fn enable_local() {
     x86_64::instructions::nop();
 }
 
+/// Enables the local IRQs and halts the CPU until the next interrupt arrives.
+pub(crate) fn enable_local_and_halt() {
+    // An interrupt cannot be delivered between the STI and HLT instructions, so it will not
+    // be missed before the CPU is halted.
+    x86_64::instructions::interrupts::enable_and_hlt();
+}
+
+/// Wakes up the given CPU if it is halted.
+pub(crate) fn wake_up_cpu(cpu_id: u32) {
+    super::kernel::apic::send_wake_up_ipi(cpu_id);
+}
+
 pub(crate) fn disable_local() {
     x86_64::instructions::interrupts::disable();
 }
@@ -1,17 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::{boxed::Box, sync::Arc};
+use core::sync::atomic::{AtomicU32, Ordering};
 
 use log::info;
 use spin::Once;
 
-use crate::sync::Mutex;
+use crate::{cpu::MAX_CPUS, sync::SpinLock, trap::IrqLine};
 
 pub mod ioapic;
 pub mod x2apic;
 pub mod xapic;
 
-pub static APIC_INSTANCE: Once<Arc<Mutex<Box<dyn Apic + 'static>>>> = Once::new();
+/// The local APIC.
+///
+/// The registers of the local APIC are per-CPU, although they are accessed at the same
+/// address (or MSRs) on all CPUs. So this instance can be shared by all CPUs.
+pub static APIC_INSTANCE: Once<Arc<SpinLock<Box<dyn Apic + 'static>>>> = Once::new();
 
 pub trait Apic: ApicTimer + Sync + Send {
     fn id(&self) -> u32;
@@ -20,6 +25,57 @@ pub trait Apic: ApicTimer + Sync + Send {
 
     /// End of Interrupt, this function will inform APIC that this interrupt has been processed.
     fn eoi(&mut self);
+
+    /// Sends an inter-processor interrupt (IPI) to the CPU with the given APIC ID.
+    ///
+    /// The `icr_low` is written to the lower 32 bits of the interrupt command register (ICR),
+    /// which specifies the vector, the delivery mode and so on.
+    fn send_ipi(&mut self, apic_id: u32, icr_low: u32);
+}
+
+/// The delivery mode of the interrupt command register (ICR) that delivers the vector in the
+/// ICR.
+pub const ICR_DELIVERY_MODE_FIXED: u32 = 0b000 << 8;
+/// The delivery mode of INIT in the interrupt command register (ICR).
+pub const ICR_DELIVERY_MODE_INIT: u32 = 0b101 << 8;
+/// The delivery mode of Start-Up in the interrupt command register (ICR).
+pub const ICR_DELIVERY_MODE_STARTUP: u32 = 0b110 << 8;
+/// The level of the interrupt command register (ICR), which must be asserted except for
+/// the INIT level de-assert IPI.
+pub const ICR_LEVEL_ASSERT: u32 = 1 << 14;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const APIC_ID_INIT: AtomicU32 = AtomicU32::new(0);
+/// The local APIC IDs of the CPUs, indexed by the CPU IDs.
+static APIC_IDS: [AtomicU32; MAX_CPUS] = [APIC_ID_INIT; MAX_CPUS];
+
+/// Records the local APIC ID of a CPU, which is the destination of the IPIs sent to the CPU.
+pub(crate) fn set_apic_id(cpu_id: u32, apic_id: u32) {
+    APIC_IDS[cpu_id as usize].store(apic_id, Ordering::Relaxed);
+}
+
+/// The IRQ line of the IPI that wakes up a halted CPU.
+static WAKE_UP_IRQ: Once<IrqLine> = Once::new();
+
+/// Allocates the vector of the IPI that wakes up a halted CPU.
+///
+/// The IPI needs no handling, since it is sent just to make the CPU leave the halted state.
+pub(crate) fn init_wake_up_ipi() {
+    let mut irq = IrqLine::alloc().unwrap();
+    irq.on_active(|_| {});
+    WAKE_UP_IRQ.call_once(|| irq);
+}
+
+/// Sends an IPI to wake up the given CPU if it is halted.
+pub(crate) fn send_wake_up_ipi(cpu_id: u32) {
+    let Some(irq) = WAKE_UP_IRQ.get() else {
+        return;
+    };
+    let apic_id = APIC_IDS[cpu_id as usize].load(Ordering::Relaxed);
+    APIC_INSTANCE.get().unwrap().lock_irq_disabled().send_ipi(
+        apic_id,
+        ICR_DELIVERY_MODE_FIXED | ICR_LEVEL_ASSERT | irq.num() as u32,
+    );
 }
 
 pub trait ApicTimer: Sync + Send {
@@ -71,7 +127,7 @@ pub fn init() -> Result<(), ApicInitError> {
             version & 0xff,
             (version >> 16) & 0xff
         );
-        APIC_INSTANCE.call_once(|| Arc::new(Mutex::new(Box::new(x2apic))));
+        APIC_INSTANCE.call_once(|| Arc::new(SpinLock::new(Box::new(x2apic))));
         Ok(())
     } else if let Some(mut xapic) = xapic::XApic::new() {
         xapic.enable();
@@ -82,10 +138,21 @@ pub fn init() -> Result<(), ApicInitError> {
             version & 0xff,
             (version >> 16) & 0xff
         );
-        APIC_INSTANCE.call_once(|| Arc::new(Mutex::new(Box::new(xapic))));
+        APIC_INSTANCE.call_once(|| Arc::new(SpinLock::new(Box::new(xapic))));
         Ok(())
     } else {
         log::warn!("Not found x2APIC or xAPIC");
         Err(ApicInitError::NoApic)
     }
 }
+
+/// Enables the local APIC of an application processor (AP).
+///
+/// The APIC of the same kind as that of the bootstrap processor is enabled.
+pub fn init_ap() {
+    if let Some(mut x2apic) = x2apic::X2Apic::new() {
+        x2apic.enable();
+    } else if let Some(mut xapic) = xapic::XApic::new() {
+        xapic.enable();
+    }
+}
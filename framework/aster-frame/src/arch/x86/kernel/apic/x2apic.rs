@@ -2,8 +2,8 @@
 
 use x86::msr::{
     rdmsr, wrmsr, IA32_APIC_BASE, IA32_X2APIC_APICID, IA32_X2APIC_CUR_COUNT, IA32_X2APIC_DIV_CONF,
-    IA32_X2APIC_EOI, IA32_X2APIC_INIT_COUNT, IA32_X2APIC_LVT_TIMER, IA32_X2APIC_SIVR,
-    IA32_X2APIC_VERSION,
+    IA32_X2APIC_EOI, IA32_X2APIC_ICR, IA32_X2APIC_INIT_COUNT, IA32_X2APIC_LVT_TIMER,
+    IA32_X2APIC_SIVR, IA32_X2APIC_VERSION,
 };
 
 use super::ApicTimer;
@@ -66,6 +66,14 @@ impl super::Apic for X2Apic {
             wrmsr(IA32_X2APIC_EOI, 0);
         }
     }
+
+    fn send_ipi(&mut self, apic_id: u32, icr_low: u32) {
+        // In x2APIC mode, the ICR is a single 64-bit MSR whose upper 32 bits are the
+        // destination, and there is no delivery status to be polled.
+        unsafe {
+            wrmsr(IA32_X2APIC_ICR, (apic_id as u64) << 32 | icr_low as u64);
+        }
+    }
 }
 
 impl ApicTimer for X2Apic {
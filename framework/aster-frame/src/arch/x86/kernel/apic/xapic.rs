@@ -11,6 +11,7 @@ const IA32_APIC_BASE_MSR_BSP: u32 = 0x100; // Processor is a BSP
 const IA32_APIC_BASE_MSR_ENABLE: u64 = 0x800;
 
 const APIC_LVT_MASK_BITS: u32 = 1 << 16;
+const APIC_ICR_DELIVERY_STATUS_BIT: u32 = 1 << 12;
 
 pub static XAPIC_INSTANCE: Once<Mutex<XApic>> = Once::new();
 
@@ -72,6 +73,15 @@ impl super::Apic for XApic {
     fn eoi(&mut self) {
         self.write(xapic::XAPIC_EOI, 0);
     }
+
+    fn send_ipi(&mut self, apic_id: u32, icr_low: u32) {
+        // Writing the lower half of the ICR sends the IPI, so the destination goes first.
+        self.write(xapic::XAPIC_ICR1, apic_id << 24);
+        self.write(xapic::XAPIC_ICR0, icr_low);
+        while self.read(xapic::XAPIC_ICR0) & APIC_ICR_DELIVERY_STATUS_BIT != 0 {
+            core::hint::spin_loop();
+        }
+    }
 }
 
 impl ApicTimer for XApic {
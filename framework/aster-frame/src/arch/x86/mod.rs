@@ -42,6 +42,9 @@ pub(crate) fn after_all_init() {
     }
     console::callback_init();
     timer::init();
+    if kernel::apic::APIC_INSTANCE.is_completed() {
+        kernel::apic::init_wake_up_ipi();
+    }
     #[cfg(feature = "intel_tdx")]
     if !tdx_is_enabled() {
         match iommu::init() {
@@ -61,7 +64,7 @@ pub(crate) fn after_all_init() {
 pub(crate) fn interrupts_ack() {
     kernel::pic::ack();
     if let Some(apic) = kernel::apic::APIC_INSTANCE.get() {
-        apic.lock().eoi();
+        apic.lock_irq_disabled().eoi();
     }
 }
 
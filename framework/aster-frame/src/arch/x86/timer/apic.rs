@@ -1,10 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::sync::Arc;
-use core::{
-    arch::x86_64::_rdtsc,
-    sync::atomic::{AtomicBool, AtomicU64, Ordering},
-};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use log::info;
 use spin::Once;
@@ -33,12 +29,73 @@ pub fn init() {
         info!("[Timer]: Enable APIC TSC deadline mode.");
         init_tsc_mode();
     } else {
-        info!("[Timer]: Enable APIC periodic mode.");
-        init_periodic_mode();
+        info!("[Timer]: Enable APIC one-shot mode.");
+        init_one_shot_mode();
+    }
+}
+
+/// Initializes the APIC timer of an application processor (AP) in the same mode as that of
+/// the bootstrap processor.
+pub(super) fn init_ap() {
+    let mut apic_lock = APIC_INSTANCE.get().unwrap().lock_irq_disabled();
+    match TIMER_MODE.get() {
+        Some(TimerMode::TscDeadline) => {
+            apic_lock
+                .set_lvt_timer(super::TIMER_IRQ_NUM.load(Ordering::Relaxed) as u64 | (1 << 18));
+        }
+        Some(TimerMode::OneShot) => {
+            apic_lock.set_timer_div_config(DivideConfig::Divide64);
+            apic_lock.set_lvt_timer(super::TIMER_IRQ_NUM.load(Ordering::Relaxed) as u64);
+        }
+        None => {}
+    }
+}
+
+/// Programs the APIC timer of the current CPU to fire at the given deadline in nanoseconds of
+/// the monotonic clock.
+pub(super) fn set_next_event(deadline: u64) {
+    match TIMER_MODE.get() {
+        Some(TimerMode::TscDeadline) => {
+            // The deadline is rounded up so that the timer does not fire before the deadline.
+            // Note that zero disarms the timer.
+            let tsc_freq = TSC_FREQ.load(Ordering::Relaxed) as u128;
+            let tsc_deadline = (deadline as u128 * tsc_freq).div_ceil(1_000_000_000);
+            let tsc_deadline = u64::try_from(tsc_deadline).unwrap_or(u64::MAX).max(1);
+            // Safety: writing the TSC deadline only affects the APIC timer.
+            unsafe { wrmsr(IA32_TSC_DEADLINE, tsc_deadline) };
+        }
+        Some(TimerMode::OneShot) => {
+            let delay = deadline.saturating_sub(super::read_monotonic_nanos());
+            let counts_per_tick = COUNTS_PER_TICK.load(Ordering::Relaxed) as u128;
+            // A zero initial count stops the timer, so the timer fires after at least one
+            // count. The delay is limited by the 32-bit counter.
+            let count = (delay as u128 * counts_per_tick * TIMER_FREQ as u128)
+                .div_ceil(1_000_000_000)
+                .clamp(1, u32::MAX as u128);
+            APIC_INSTANCE
+                .get()
+                .unwrap()
+                .lock_irq_disabled()
+                .set_timer_init_count(count as u64);
+        }
+        // The APIC timer is not initialized yet, in which case it will be programmed once it
+        // starts.
+        None => {}
     }
 }
 
-pub(super) static APIC_TIMER_CALLBACK: Once<Arc<dyn Fn() + Sync + Send>> = Once::new();
+/// The modes in which the APIC timer fires only once after being programmed.
+enum TimerMode {
+    /// The timer fires when the TSC reaches the deadline.
+    TscDeadline,
+    /// The timer fires when its counter decreases from the initial count to zero.
+    OneShot,
+}
+
+static TIMER_MODE: Once<TimerMode> = Once::new();
+
+/// The number of counts of the APIC timer per timer tick, which is calibrated by the PIT.
+static COUNTS_PER_TICK: AtomicU64 = AtomicU64::new(0);
 
 /// Determine if the current system supports tsc_deadline mode APIC timer
 fn is_tsc_deadline_mode_supported() -> bool {
@@ -48,23 +105,14 @@ fn is_tsc_deadline_mode_supported() -> bool {
 }
 
 fn init_tsc_mode() {
-    let mut apic_lock = APIC_INSTANCE.get().unwrap().lock();
+    let mut apic_lock = APIC_INSTANCE.get().unwrap().lock_irq_disabled();
     // Enable tsc deadline mode
     apic_lock.set_lvt_timer(super::TIMER_IRQ_NUM.load(Ordering::Relaxed) as u64 | (1 << 18));
     drop(apic_lock);
-    let tsc_step = TSC_FREQ.load(Ordering::Relaxed) / TIMER_FREQ;
-
-    let callback = move || unsafe {
-        let tsc_value = _rdtsc();
-        let next_tsc_value = tsc_step + tsc_value;
-        wrmsr(IA32_TSC_DEADLINE, next_tsc_value);
-    };
-
-    callback.call(());
-    APIC_TIMER_CALLBACK.call_once(|| Arc::new(callback));
+    TIMER_MODE.call_once(|| TimerMode::TscDeadline);
 }
 
-fn init_periodic_mode() {
+fn init_one_shot_mode() {
     // Allocate IRQ
     let mut irq = IrqLine::alloc_specific(super::TIMER_IRQ_NUM.load(Ordering::Relaxed)).unwrap();
     irq.on_active(pit_callback);
@@ -74,7 +122,7 @@ fn init_periodic_mode() {
     super::pit::enable_ioapic_line(irq.clone());
 
     // Set APIC timer count
-    let mut apic_lock = APIC_INSTANCE.get().unwrap().lock();
+    let mut apic_lock = APIC_INSTANCE.get().unwrap().lock_irq_disabled();
     apic_lock.set_timer_div_config(DivideConfig::Divide64);
     apic_lock.set_timer_init_count(0xFFFF_FFFF);
     drop(apic_lock);
@@ -86,6 +134,7 @@ fn init_periodic_mode() {
     }
     x86_64::instructions::interrupts::disable();
     drop(irq);
+    TIMER_MODE.call_once(|| TimerMode::OneShot);
 
     fn pit_callback(trap_frame: &TrapFrame) {
         static IN_TIME: AtomicU64 = AtomicU64::new(0);
@@ -95,7 +144,7 @@ fn init_periodic_mode() {
 
         if IN_TIME.load(Ordering::Relaxed) < CALLBACK_TIMES || IS_FINISH.load(Ordering::Acquire) {
             if IN_TIME.load(Ordering::Relaxed) == 0 {
-                let apic_lock = APIC_INSTANCE.get().unwrap().lock();
+                let apic_lock = APIC_INSTANCE.get().unwrap().lock_irq_disabled();
                 let remain_ticks = apic_lock.timer_current_count();
                 APIC_FIRST_COUNT.store(0xFFFF_FFFF - remain_ticks, Ordering::Relaxed);
             }
@@ -105,15 +154,15 @@ fn init_periodic_mode() {
 
         // Stop PIT and APIC Timer
         super::pit::disable_ioapic_line();
-        let mut apic_lock = APIC_INSTANCE.get().unwrap().lock();
+        let mut apic_lock = APIC_INSTANCE.get().unwrap().lock_irq_disabled();
         let remain_ticks = apic_lock.timer_current_count();
         apic_lock.set_timer_init_count(0);
 
-        // Init APIC Timer
+        // Init APIC Timer, which is programmed once the timer IRQ is ready.
         let ticks = (0xFFFF_FFFF - remain_ticks - APIC_FIRST_COUNT.load(Ordering::Relaxed))
             / CALLBACK_TIMES;
-        apic_lock.set_timer_init_count(ticks);
-        apic_lock.set_lvt_timer(super::TIMER_IRQ_NUM.load(Ordering::Relaxed) as u64 | (1 << 17));
+        COUNTS_PER_TICK.store(ticks, Ordering::Relaxed);
+        apic_lock.set_lvt_timer(super::TIMER_IRQ_NUM.load(Ordering::Relaxed) as u64);
         apic_lock.set_timer_div_config(DivideConfig::Divide64);
         info!(
             "APIC Timer ticks count:{:x}, remain ticks: {:x},Timer Freq:{} Hz",
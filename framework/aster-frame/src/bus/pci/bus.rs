@@ -1,11 +1,18 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    vec::Vec,
+};
 use core::fmt::Debug;
 
 use log::{debug, error};
 
-use super::{device_info::PciDeviceId, PciCommonDevice};
+use super::{
+    device_info::{PciDeviceId, PciDeviceLocation},
+    PciCommonDevice,
+};
 use crate::bus::BusProbeError;
 
 pub trait PciDevice: Sync + Send + Debug {
@@ -37,6 +44,8 @@ pub struct PciBus {
     common_devices: VecDeque<PciCommonDevice>,
     devices: Vec<Arc<dyn PciDevice>>,
     drivers: Vec<Arc<dyn PciDriver>>,
+    /// The IDs of all the devices found on the bus, whether claimed by drivers or not.
+    device_ids: BTreeMap<PciDeviceLocation, PciDeviceId>,
 }
 
 impl PciBus {
@@ -65,9 +74,19 @@ impl PciBus {
         self.drivers.push(driver);
     }
 
+    /// Returns the locations and the IDs of all the devices found on the bus, sorted by the
+    /// locations.
+    pub fn device_ids(&self) -> Vec<(PciDeviceLocation, PciDeviceId)> {
+        self.device_ids
+            .iter()
+            .map(|(location, device_id)| (*location, *device_id))
+            .collect()
+    }
+
     pub(super) fn register_common_device(&mut self, mut common_device: PciCommonDevice) {
         debug!("Find pci common devices:{:x?}", common_device);
         let device_id = *common_device.device_id();
+        self.device_ids.insert(*common_device.location(), device_id);
         for driver in self.drivers.iter() {
             common_device = match driver.probe(common_device) {
                 Ok(device) => {
@@ -92,6 +111,7 @@ impl PciBus {
             common_devices: VecDeque::new(),
             devices: Vec::new(),
             drivers: Vec::new(),
+            device_ids: BTreeMap::new(),
         }
     }
 }
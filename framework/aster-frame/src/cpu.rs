@@ -45,28 +45,37 @@ macro_rules! cpu_local {
 
     // multiple declarations
     ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
-        $(#[$attr])* $vis static $name: $crate::CpuLocal<$t> = unsafe { $crate::CpuLocal::new($init) };
+        $crate::cpu_local!($(#[$attr])* $vis static $name: $t = $init);
         $crate::cpu_local!($($rest)*);
     };
 
     // single declaration
     ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => (
-        // TODO: reimplement cpu-local variable to support multi-core
-        $(#[$attr])* $vis static $name: $crate::CpuLocal<$t> = $crate::CpuLocal::new($init);
+        $(#[$attr])* $vis static $name: $crate::CpuLocal<$t> = {
+            // A constant item can be repeated to initialize the copies of all CPUs even if
+            // its type is not `Copy`.
+            #[allow(clippy::declare_interior_mutable_const)]
+            const INIT: $t = $init;
+            // Safety: the object is only defined by this macro.
+            unsafe { $crate::CpuLocal::new([INIT; $crate::cpu::MAX_CPUS]) }
+        };
     );
 }
 
+/// The maximum number of CPUs that are supported.
+pub const MAX_CPUS: usize = 64;
+
 /// CPU-local objects.
 ///
 /// A CPU-local object only gives you immutable references to the underlying value.
 /// To mutate the value, one can use atomic values (e.g., `AtomicU32`) or internally mutable
 /// objects (e.g., `RefCell`).
 ///
+/// Each CPU owns a separate copy of the value, which is selected by [`this_cpu`].
+///
 /// The `CpuLocal<T: Sync>` can be used directly.
 /// Otherwise, the `CpuLocal<T>` must be used through `CpuLocal::borrow_with`.
-///
-/// TODO: re-implement `CpuLocal`
-pub struct CpuLocal<T>(UnsafeCell<T>);
+pub struct CpuLocal<T>(UnsafeCell<[T; MAX_CPUS]>);
 
 // Safety. At any given time, only one task can access the inner value T of a cpu-local variable.
 unsafe impl<T> Sync for CpuLocal<T> {}
@@ -75,8 +84,8 @@ impl<T> CpuLocal<T> {
     /// Initialize CPU-local object
     /// Developer cannot construct a valid CpuLocal object arbitrarily
     #[allow(clippy::missing_safety_doc)]
-    pub const unsafe fn new(val: T) -> Self {
-        Self(UnsafeCell::new(val))
+    pub const unsafe fn new(vals: [T; MAX_CPUS]) -> Self {
+        Self(UnsafeCell::new(vals))
     }
 
     /// Borrow an immutable reference to the underlying value and feed it to a closure.
@@ -96,7 +105,7 @@ impl<T> CpuLocal<T> {
     }
 
     unsafe fn do_borrow(&self) -> &T {
-        &*self.0.get()
+        &(*self.0.get())[this_cpu() as usize]
     }
 }
 
@@ -104,6 +113,6 @@ impl<T: Sync> Deref for CpuLocal<T> {
     type Target = T;
 
     fn deref(&self) -> &T {
-        unsafe { &*self.0.get() }
+        unsafe { self.do_borrow() }
     }
 }
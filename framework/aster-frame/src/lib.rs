@@ -83,6 +83,8 @@ pub fn init() {
             .lock()
             .activate_unchecked();
     }
+    #[cfg(target_arch = "x86_64")]
+    arch::boot::smp::boot_all_aps();
     invoke_ffi_init_funcs();
 }
 
@@ -5,8 +5,8 @@ use core::time::Duration;
 
 use super::SpinLock;
 use crate::{
-    arch::timer::{add_timeout_list, TIMER_FREQ},
     task::{add_task, current_task, schedule, Task, TaskStatus},
+    timer::{add_timeout, read_monotonic_nanos},
 };
 
 /// A wait queue.
@@ -64,19 +64,9 @@ impl WaitQueue {
         let waiter = Arc::new(Waiter::new());
 
         let timer_callback = timeout.map(|timeout| {
-            let remaining_ticks = {
-                // FIXME: We currently require 1000 to be a multiple of TIMER_FREQ, but
-                // this may not hold true in the future, because TIMER_FREQ can be greater
-                // than 1000. Then, the code need to be refactored.
-                const_assert!(1000 % TIMER_FREQ == 0);
-
-                let ms_per_tick = 1000 / TIMER_FREQ;
-
-                // The ticks should be equal to or greater than timeout
-                (timeout.as_millis() as u64 + ms_per_tick - 1) / ms_per_tick
-            };
-
-            add_timeout_list(remaining_ticks, waiter.clone(), |timer_call_back| {
+            let timeout_ns = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+            let deadline = read_monotonic_nanos().saturating_add(timeout_ns);
+            add_timeout(deadline, waiter.clone(), |timer_call_back| {
                 let waiter = timer_call_back
                     .data()
                     .downcast_ref::<Arc<Waiter>>()
@@ -165,11 +155,16 @@ impl Waiter {
         let mut task = self.task.inner_exclusive_access();
         if task.task_status == TaskStatus::Sleeping {
             task.task_status = TaskStatus::Runnable;
+            // If the task has not been switched out completely, it will either go on running
+            // or be put back into the run queue by the CPU that it runs on.
+            let is_on_cpu = task.is_on_cpu;
 
             // Avoid holding lock when doing `add_task`
             drop(task);
 
-            add_task(self.task.clone());
+            if !is_on_cpu {
+                add_task(self.task.clone());
+            }
 
             true
         } else {
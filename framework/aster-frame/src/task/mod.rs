@@ -8,9 +8,13 @@ mod scheduler;
 #[allow(clippy::module_inception)]
 mod task;
 
+pub(crate) use self::processor::{preempt_in_user_mode, run_idle_task, timer_tick};
 pub use self::{
-    priority::Priority,
-    processor::{current_task, disable_preempt, preempt, schedule, DisablePreemptGuard},
+    priority::{Priority, SchedPolicy},
+    processor::{
+        cpu_idle_time, current_task, disable_preempt, is_cpu_idle, nr_context_switches, preempt,
+        schedule, wake_up_cpu, DisablePreemptGuard,
+    },
     scheduler::{add_task, set_scheduler, Scheduler},
     task::{Task, TaskAdapter, TaskOptions, TaskStatus},
 };
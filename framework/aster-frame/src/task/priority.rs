@@ -6,7 +6,7 @@ pub const REAL_TIME_TASK_PRIORITY: u16 = 100;
 /// Similar to Linux, a larger value represents a lower priority,
 /// with a range of 0 to 139. Priorities ranging from 0 to 99 are considered real-time,
 /// while those ranging from 100 to 139 are considered normal.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Priority(u16);
 
 impl Priority {
@@ -47,3 +47,41 @@ impl Priority {
         self.0 < REAL_TIME_TASK_PRIORITY
     }
 }
+
+/// The scheduling policy of a task.
+///
+/// The real-time policies apply to tasks with real-time priorities, while the others apply
+/// to tasks with normal priorities.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SchedPolicy {
+    /// The standard time-sharing policy.
+    Normal = 0,
+    /// A real-time policy, where a task runs until it blocks, yields or is preempted by a
+    /// task with a higher priority.
+    Fifo = 1,
+    /// A real-time policy like [`SchedPolicy::Fifo`], except that the tasks with the same
+    /// priority take turns to run in fixed time slices.
+    RoundRobin = 2,
+    /// A time-sharing policy for CPU-intensive tasks.
+    Batch = 3,
+    /// A time-sharing policy for tasks with extremely low priorities.
+    Idle = 5,
+}
+
+impl SchedPolicy {
+    pub const fn is_real_time(self) -> bool {
+        matches!(self, Self::Fifo | Self::RoundRobin)
+    }
+
+    /// Converts from the value that is stored in a task.
+    pub(super) fn from_raw(val: u8) -> Self {
+        match val {
+            1 => Self::Fifo,
+            2 => Self::RoundRobin,
+            3 => Self::Batch,
+            5 => Self::Idle,
+            _ => Self::Normal,
+        }
+    }
+}
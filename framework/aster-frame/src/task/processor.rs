@@ -1,32 +1,41 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::sync::Arc;
-use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
-
-use lazy_static::lazy_static;
+use core::{
+    cell::RefCell,
+    sync::atomic::{
+        fence, AtomicBool, AtomicU64, AtomicUsize,
+        Ordering::{Relaxed, SeqCst},
+    },
+    time::Duration,
+};
 
 use super::{
     scheduler::{fetch_task, GLOBAL_SCHEDULER},
     task::{context_switch, TaskContext},
-    Task, TaskStatus,
+    Priority, Task, TaskOptions, TaskStatus,
+};
+use crate::{
+    cpu::{this_cpu, CpuLocal, MAX_CPUS},
+    cpu_local,
 };
-use crate::{cpu_local, sync::Mutex};
 
 pub struct Processor {
     current: Option<Arc<Task>>,
-    idle_task_cx: TaskContext,
+    /// The task that runs when there is no other task to run on this CPU.
+    idle_task: Option<Arc<Task>>,
+    /// The task that is being switched out, which is handled after the context switch.
+    prev_task: Option<Arc<Task>>,
 }
 
 impl Processor {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             current: None,
-            idle_task_cx: TaskContext::default(),
+            idle_task: None,
+            prev_task: None,
         }
     }
-    fn get_idle_task_cx_ptr(&mut self) -> *mut TaskContext {
-        &mut self.idle_task_cx as *mut _
-    }
     pub fn take_current(&mut self) -> Option<Arc<Task>> {
         self.current.take()
     }
@@ -38,26 +47,41 @@ impl Processor {
     }
 }
 
-lazy_static! {
-    static ref PROCESSOR: Mutex<Processor> = Mutex::new(Processor::new());
+cpu_local! {
+    static PROCESSOR: RefCell<Processor> = RefCell::new(Processor::new());
+    /// Whether the current task has been marked by the timer tick to be preempted.
+    static NEED_PREEMPT: AtomicBool = AtomicBool::new(false);
 }
 
 pub fn take_current_task() -> Option<Arc<Task>> {
-    PROCESSOR.lock().take_current()
+    CpuLocal::borrow_with(&PROCESSOR, |processor| {
+        processor.borrow_mut().take_current()
+    })
 }
 
 pub fn current_task() -> Option<Arc<Task>> {
-    PROCESSOR.lock().current()
-}
-
-pub(crate) fn get_idle_task_cx_ptr() -> *mut TaskContext {
-    PROCESSOR.lock().get_idle_task_cx_ptr()
+    CpuLocal::borrow_with(&PROCESSOR, |processor| processor.borrow().current())
 }
 
 /// call this function to switch to other task by using GLOBAL_SCHEDULER
 pub fn schedule() {
     if let Some(task) = fetch_task() {
         switch_to_task(task);
+        return;
+    }
+
+    // There are no other tasks to run. The current task goes on running unless it is going to
+    // sleep or exit, or it is no longer allowed to run on this CPU, in which case this CPU
+    // becomes idle.
+    let Some(current_task) = current_task() else {
+        return;
+    };
+    if is_idle_task(&current_task) {
+        return;
+    }
+    if current_task.status() != TaskStatus::Runnable || !current_task.can_run_on(this_cpu()) {
+        drop(current_task);
+        switch_to_task(idle_task());
     }
 }
 
@@ -67,17 +91,195 @@ pub fn preempt() {
     let Some(curr_task) = current_task() else {
         return;
     };
-    let mut scheduler = GLOBAL_SCHEDULER.lock_irq_disabled();
-    if !scheduler.should_preempt(&curr_task) {
+    // The idle task picks up the runnable tasks by itself.
+    if is_idle_task(&curr_task) {
         return;
     }
-    let Some(next_task) = scheduler.dequeue() else {
+    // A task that is no longer allowed to run on this CPU is moved to another CPU.
+    let is_migrating = !curr_task.can_run_on(this_cpu());
+    if !NEED_PREEMPT.swap(false, Relaxed)
+        && !is_migrating
+        && !GLOBAL_SCHEDULER.should_preempt(&curr_task)
+    {
         return;
+    }
+    let next_task = match GLOBAL_SCHEDULER.dequeue() {
+        Some(next_task) => next_task,
+        None if is_migrating => idle_task(),
+        None => return,
     };
-    drop(scheduler);
+    drop(curr_task);
     switch_to_task(next_task);
 }
 
+/// Notifies the scheduler of a timer tick.
+///
+/// This function is called in the timer interrupt handler. If the scheduler decides that the
+/// current task should be preempted, the preemption happens at the next preemption point.
+pub(crate) fn timer_tick() {
+    let Some(current) = current_task() else {
+        return;
+    };
+    if is_idle_task(&current) {
+        return;
+    }
+    if GLOBAL_SCHEDULER.tick(&current) || !current.can_run_on(this_cpu()) {
+        NEED_PREEMPT.store(true, Relaxed);
+    }
+}
+
+/// Preempts the current task if it has been marked by the timer tick to be preempted.
+///
+/// This function is called after handling the interrupts that interrupt the user space, so
+/// that a task that never traps into the kernel by itself can still be preempted.
+pub(crate) fn preempt_in_user_mode() {
+    if !NEED_PREEMPT.load(Relaxed) {
+        return;
+    }
+
+    crate::arch::irq::enable_local();
+    preempt();
+    // Other tasks may have activated their own VM spaces.
+    if let Some(user_space) = current_task().and_then(|task| task.user_space().cloned()) {
+        user_space.vm_space().activate();
+    }
+}
+
+/// Runs the idle task on this CPU, which has not run any task yet.
+///
+/// This function is called by each application processor once it is initialized.
+pub(crate) fn run_idle_task() -> ! {
+    switch_to_task(idle_task());
+    unreachable!()
+}
+
+/// Returns the idle task of this CPU, which is created on the first use.
+fn idle_task() -> Arc<Task> {
+    if let Some(idle_task) =
+        CpuLocal::borrow_with(&PROCESSOR, |processor| processor.borrow().idle_task.clone())
+    {
+        return idle_task;
+    }
+
+    let idle_task = TaskOptions::new(idle_loop)
+        .data(())
+        .priority(Priority::lowest())
+        .build()
+        .expect("failed to create the idle task");
+    CpuLocal::borrow_with(&PROCESSOR, |processor| {
+        processor.borrow_mut().idle_task = Some(idle_task.clone());
+    });
+    idle_task
+}
+
+fn is_idle_task(task: &Arc<Task>) -> bool {
+    CpuLocal::borrow_with(&PROCESSOR, |processor| {
+        processor
+            .borrow()
+            .idle_task
+            .as_ref()
+            .is_some_and(|idle_task| Arc::ptr_eq(idle_task, task))
+    })
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NOT_IDLE: AtomicBool = AtomicBool::new(false);
+/// Whether the CPUs are idle, indexed by the CPU IDs.
+static IS_IDLE: [AtomicBool; MAX_CPUS] = [NOT_IDLE; MAX_CPUS];
+
+/// The function executed by the idle task.
+///
+/// The idle task is never put into the run queue. Instead, it runs whenever the task on this
+/// CPU goes to sleep or exits and there is no other task to run.
+///
+/// The timer tick is stopped while the CPU is halted. Tasks may be woken up by interrupts, or
+/// be put into the run queue by other CPUs, which wake up this CPU by [`wake_up_cpu`].
+fn idle_loop() {
+    let is_idle = &IS_IDLE[this_cpu() as usize];
+    loop {
+        // The local IRQs are disabled until the CPU halts, so that the wake-up IPI sent after
+        // checking the run queue is not handled before halting.
+        crate::arch::irq::disable_local();
+        is_idle.store(true, Relaxed);
+        // Pairs with the fence in `wake_up_cpu`, so either the task put into the run queue
+        // is seen, or the idle state is seen by the CPU that puts the task.
+        fence(SeqCst);
+        if let Some(task) = fetch_task() {
+            is_idle.store(false, Relaxed);
+            crate::arch::irq::enable_local();
+            crate::timer::start_tick();
+            switch_to_task(task);
+        } else {
+            crate::timer::stop_tick();
+            crate::arch::irq::enable_local_and_halt();
+        }
+    }
+}
+
+/// Returns whether the given CPU is idle, i.e., it is running the idle task.
+pub fn is_cpu_idle(cpu_id: u32) -> bool {
+    IS_IDLE[cpu_id as usize].load(Relaxed)
+}
+
+/// The accounting of the time that a CPU spends in the idle task.
+struct IdleTime {
+    /// The total time in nanoseconds of the finished runs of the idle task.
+    total: AtomicU64,
+    /// The time in nanoseconds of the monotonic clock when the current run of the idle task
+    /// starts, or zero if the idle task is not running.
+    run_start: AtomicU64,
+}
+
+impl IdleTime {
+    const fn new() -> Self {
+        Self {
+            total: AtomicU64::new(0),
+            run_start: AtomicU64::new(0),
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_IDLE_TIME: IdleTime = IdleTime::new();
+/// The time spent in the idle task, indexed by the CPU IDs.
+static IDLE_TIMES: [IdleTime; MAX_CPUS] = [NEW_IDLE_TIME; MAX_CPUS];
+
+/// Returns the time that the given CPU has spent in the idle task since the system boots up.
+pub fn cpu_idle_time(cpu_id: u32) -> Duration {
+    let idle_time = &IDLE_TIMES[cpu_id as usize];
+    let run_start = idle_time.run_start.load(Relaxed);
+    let current_run = if run_start != 0 {
+        crate::timer::read_monotonic_nanos().saturating_sub(run_start)
+    } else {
+        0
+    };
+    Duration::from_nanos(idle_time.total.load(Relaxed) + current_run)
+}
+
+/// The number of context switches on all the CPUs.
+static NR_CONTEXT_SWITCHES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the number of context switches on all the CPUs since the system boots up.
+pub fn nr_context_switches() -> u64 {
+    NR_CONTEXT_SWITCHES.load(Relaxed)
+}
+
+/// Wakes up the given CPU if it is idle, so that it notices the tasks in its run queue.
+///
+/// The idle CPUs do not check their run queues periodically, so a scheduler that keeps a run
+/// queue for each CPU should call this function after putting a task into the run queue of
+/// another CPU.
+pub fn wake_up_cpu(cpu_id: u32) {
+    if cpu_id == this_cpu() {
+        return;
+    }
+    // Pairs with the fence in `idle_loop`.
+    fence(SeqCst);
+    if IS_IDLE[cpu_id as usize].load(Relaxed) {
+        crate::arch::irq::wake_up_cpu(cpu_id);
+    }
+}
+
 /// call this function to switch to other task
 ///
 /// if current task is none, then it will use the default task context and it will not return to this function again
@@ -94,27 +296,82 @@ fn switch_to_task(next_task: Arc<Task>) {
         //GLOBAL_SCHEDULER.lock_irq_disabled().enqueue(next_task);
         //return;
     }
-    let current_task_option = current_task();
-    let next_task_cx_ptr = &next_task.inner_ctx() as *const TaskContext;
-    let current_task: Arc<Task>;
-    let current_task_cx_ptr = match current_task_option {
-        None => PROCESSOR.lock().get_idle_task_cx_ptr(),
+
+    // The CPU time is charged to the current task until the context switch.
+    let now = crate::timer::read_monotonic_nanos();
+
+    // The context of the boot code, which is never resumed.
+    let mut boot_task_cx = TaskContext::default();
+    let current_task_cx_ptr = match current_task() {
+        None => &mut boot_task_cx as *mut TaskContext,
         Some(current_task) => {
-            if current_task.status() == TaskStatus::Runnable {
-                GLOBAL_SCHEDULER
-                    .lock_irq_disabled()
-                    .enqueue(current_task.clone());
+            let mut current_task_inner = current_task.inner_exclusive_access();
+            if let Some(run_start) = current_task_inner.run_start.take() {
+                current_task_inner.cpu_time += now.saturating_sub(run_start);
             }
-            &mut current_task.inner_exclusive_access().ctx as *mut TaskContext
+            &mut current_task_inner.ctx as *mut TaskContext
+        }
+    };
+    NR_CONTEXT_SWITCHES.fetch_add(1, Relaxed);
+    let idle_time = &IDLE_TIMES[this_cpu() as usize];
+    if is_idle_task(&next_task) {
+        idle_time.run_start.store(now, Relaxed);
+    } else {
+        let run_start = idle_time.run_start.swap(0, Relaxed);
+        if run_start != 0 {
+            idle_time
+                .total
+                .fetch_add(now.saturating_sub(run_start), Relaxed);
         }
+    }
+
+    let next_task_cx_ptr = {
+        let mut next_task_inner = next_task.inner_exclusive_access();
+        debug_assert!(!next_task_inner.is_on_cpu);
+        next_task_inner.is_on_cpu = true;
+        next_task_inner.run_start = Some(now);
+        next_task.set_cpu(this_cpu());
+        &next_task_inner.ctx as *const TaskContext
     };
 
     // change the current task to the next task
 
-    PROCESSOR.lock().current = Some(next_task.clone());
+    NEED_PREEMPT.store(false, Relaxed);
+    CpuLocal::borrow_with(&PROCESSOR, |processor| {
+        let mut processor = processor.borrow_mut();
+        processor.prev_task = processor.current.replace(next_task);
+    });
     unsafe {
         context_switch(current_task_cx_ptr, next_task_cx_ptr);
     }
+
+    finish_switch();
+}
+
+/// Handles the task that has just been switched out on this CPU.
+///
+/// This function must be called after every context switch before doing anything else,
+/// including at the entry of a new task.
+///
+/// The previous task is put back into the run queue here rather than before the context
+/// switch, otherwise another CPU may pick it up and run it before its context is saved.
+pub(crate) fn finish_switch() {
+    let Some(prev_task) = CpuLocal::borrow_with(&PROCESSOR, |processor| {
+        processor.borrow_mut().prev_task.take()
+    }) else {
+        return;
+    };
+
+    let mut prev_task_inner = prev_task.inner_exclusive_access();
+    prev_task_inner.is_on_cpu = false;
+    let is_runnable = prev_task_inner.task_status == TaskStatus::Runnable;
+    drop(prev_task_inner);
+
+    // The last reference to an exited task may be released here, which is safe since we are
+    // no longer on its stack.
+    if is_runnable && !is_idle_task(&prev_task) {
+        GLOBAL_SCHEDULER.enqueue(prev_task);
+    }
 }
 
 cpu_local! {
@@ -1,18 +1,23 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use lazy_static::lazy_static;
+use spin::Once;
 
-use crate::{prelude::*, sync::SpinLock, task::Task};
+use crate::{prelude::*, task::Task};
 
-lazy_static! {
-    pub(crate) static ref GLOBAL_SCHEDULER: SpinLock<GlobalScheduler> =
-        SpinLock::new(GlobalScheduler { scheduler: None });
-}
+pub(crate) static GLOBAL_SCHEDULER: GlobalScheduler = GlobalScheduler::new();
 
 /// A scheduler for tasks.
 ///
 /// An implementation of scheduler can attach scheduler-related information
 /// with the `TypeMap` returned from `task.data()`.
+///
+/// A scheduler may keep a run queue for each CPU. In this case, `enqueue` puts the task into
+/// the run queue of a CPU that the task is allowed to run on (see [`Task::can_run_on`]), while
+/// the other methods work on the run queue of the current CPU. Since an idle CPU does not check
+/// its run queue until it is woken up, [`wake_up_cpu`] should be called after a task is put
+/// into the run queue of another CPU.
+///
+/// [`wake_up_cpu`]: super::wake_up_cpu
 pub trait Scheduler: Sync + Send {
     fn enqueue(&self, task: Arc<Task>);
 
@@ -20,43 +25,64 @@ pub trait Scheduler: Sync + Send {
 
     /// Tells whether the given task should be preempted by other tasks in the queue.
     fn should_preempt(&self, task: &Arc<Task>) -> bool;
+
+    /// Updates the scheduling states of the current task on a timer tick.
+    ///
+    /// Returns whether the current task should be preempted, e.g., because it has used up its
+    /// time slice. This method is called in the interrupt context.
+    fn tick(&self, current: &Arc<Task>) -> bool {
+        let _ = current;
+        false
+    }
 }
 
 pub struct GlobalScheduler {
-    scheduler: Option<&'static dyn Scheduler>,
+    scheduler: Once<&'static dyn Scheduler>,
 }
 
 impl GlobalScheduler {
-    pub fn new() -> Self {
-        Self { scheduler: None }
+    pub const fn new() -> Self {
+        Self {
+            scheduler: Once::new(),
+        }
     }
 
     /// dequeue a task using scheduler
-    /// require the scheduler is not none
-    pub fn dequeue(&mut self) -> Option<Arc<Task>> {
-        self.scheduler.unwrap().dequeue()
+    /// return none if the scheduler has not been set, e.g., when an idle CPU looks for tasks
+    pub fn dequeue(&self) -> Option<Arc<Task>> {
+        self.scheduler.get()?.dequeue()
     }
     /// enqueue a task using scheduler
     /// require the scheduler is not none
-    pub fn enqueue(&mut self, task: Arc<Task>) {
-        self.scheduler.unwrap().enqueue(task)
+    pub fn enqueue(&self, task: Arc<Task>) {
+        self.scheduler.get().unwrap().enqueue(task)
     }
 
     pub fn should_preempt(&self, task: &Arc<Task>) -> bool {
-        self.scheduler.unwrap().should_preempt(task)
+        self.scheduler.get().unwrap().should_preempt(task)
+    }
+
+    /// Notifies the scheduler of a timer tick, if the scheduler has been set.
+    pub fn tick(&self, current: &Arc<Task>) -> bool {
+        self.scheduler
+            .get()
+            .is_some_and(|scheduler| scheduler.tick(current))
     }
 }
 /// Set the global task scheduler.
 ///
-/// This must be called before invoking `Task::spawn`.
+/// This must be called before invoking `Task::spawn`. Only the first call takes effect.
+///
+/// The scheduler is not protected by a global lock, so that the run queues of different CPUs
+/// can be accessed in parallel. The scheduler should synchronize its states by itself.
 pub fn set_scheduler(scheduler: &'static dyn Scheduler) {
-    GLOBAL_SCHEDULER.lock_irq_disabled().scheduler = Some(scheduler);
+    GLOBAL_SCHEDULER.scheduler.call_once(|| scheduler);
 }
 
 pub fn fetch_task() -> Option<Arc<Task>> {
-    GLOBAL_SCHEDULER.lock_irq_disabled().dequeue()
+    GLOBAL_SCHEDULER.dequeue()
 }
 
 pub fn add_task(task: Arc<Task>) {
-    GLOBAL_SCHEDULER.lock_irq_disabled().enqueue(task);
+    GLOBAL_SCHEDULER.enqueue(task);
 }
@@ -1,14 +1,19 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::{
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU8, Ordering},
+    time::Duration,
+};
+
 use intrusive_collections::{intrusive_adapter, LinkedListAtomicLink};
 
 use super::{
     add_task,
-    priority::Priority,
-    processor::{current_task, schedule},
+    priority::{Priority, SchedPolicy},
+    processor::{current_task, finish_switch, preempt, schedule},
 };
 use crate::{
-    cpu::CpuSet,
+    cpu::{this_cpu, CpuSet},
     prelude::*,
     sync::{SpinLock, SpinLockGuard},
     user::UserSpace,
@@ -104,9 +109,17 @@ pub struct Task {
     /// kernel stack, note that the top is SyscallFrame/TrapFrame
     kstack: KernelStack,
     link: LinkedListAtomicLink,
-    priority: Priority,
-    // TODO:: add multiprocessor support
-    cpu_affinity: CpuSet,
+    /// The scheduling policy.
+    sched_policy: AtomicU8,
+    /// The priority that is given by the scheduling policy.
+    base_priority: AtomicU16,
+    /// The effective priority, which can be temporarily boosted above the base priority
+    /// (e.g., by priority inheritance).
+    priority: AtomicU16,
+    /// The CPUs that the task is allowed to run on.
+    cpu_affinity: SpinLock<CpuSet>,
+    /// The CPU that the task is running on or ran on last time.
+    cpu: AtomicU32,
 }
 
 // TaskAdapter struct is implemented for building relationships between doubly linked list and Task struct
@@ -115,6 +128,15 @@ intrusive_adapter!(pub TaskAdapter = Arc<Task>: Task { link: LinkedListAtomicLin
 pub(crate) struct TaskInner {
     pub task_status: TaskStatus,
     pub ctx: TaskContext,
+    /// Whether the task is running on a CPU or its context is still being saved.
+    ///
+    /// Such a task must not be put into the run queue, otherwise another CPU may pick it up.
+    pub is_on_cpu: bool,
+    /// The CPU time in nanoseconds that the task has consumed before its current run.
+    pub cpu_time: u64,
+    /// The time in nanoseconds of the monotonic clock when the current run of the task starts,
+    /// or `None` if the task is not running.
+    pub run_start: Option<u64>,
 }
 
 impl Task {
@@ -141,9 +163,17 @@ impl Task {
         schedule();
     }
 
+    /// Puts the task into the run queue.
+    ///
+    /// The current task goes on running unless it should be preempted by the new task (e.g.,
+    /// a real-time task with a lower priority is preempted).
     pub fn run(self: &Arc<Self>) {
         add_task(self.clone());
-        schedule();
+        if current_task().is_some() {
+            preempt();
+        } else {
+            schedule();
+        }
     }
 
     /// Returns the task status.
@@ -171,8 +201,85 @@ impl Task {
         unreachable!()
     }
 
+    /// Returns the scheduling policy of the task.
+    pub fn sched_policy(&self) -> SchedPolicy {
+        SchedPolicy::from_raw(self.sched_policy.load(Ordering::Relaxed))
+    }
+
+    /// Returns the base priority of the task.
+    pub fn base_priority(&self) -> Priority {
+        Priority::new(self.base_priority.load(Ordering::Relaxed))
+    }
+
+    /// Sets the scheduling policy and the base priority of the task.
+    ///
+    /// The effective priority is reset to the base priority, so the callers should boost it
+    /// again if needed. If the task is already in the run queue, the new policy and priority
+    /// take effect the next time the task is enqueued.
+    pub fn set_sched_policy(&self, policy: SchedPolicy, priority: Priority) {
+        debug_assert_eq!(policy.is_real_time(), priority.is_real_time());
+        self.sched_policy.store(policy as u8, Ordering::Relaxed);
+        self.base_priority.store(priority.get(), Ordering::Relaxed);
+        self.priority.store(priority.get(), Ordering::Relaxed);
+    }
+
+    /// Returns the effective priority of the task.
+    pub fn priority(&self) -> Priority {
+        Priority::new(self.priority.load(Ordering::Relaxed))
+    }
+
+    /// Sets the effective priority of the task.
+    ///
+    /// If the task is already in the run queue, the new priority takes effect the next time
+    /// the task is enqueued.
+    pub fn set_priority(&self, priority: Priority) {
+        self.priority.store(priority.get(), Ordering::Relaxed);
+    }
+
+    /// Returns whether the task is scheduled as a real-time task.
+    ///
+    /// This is the case if the task has a real-time policy, or if its effective priority is
+    /// boosted to a real-time priority.
     pub fn is_real_time(&self) -> bool {
-        self.priority.is_real_time()
+        self.sched_policy().is_real_time() || self.priority().is_real_time()
+    }
+
+    /// Returns the CPUs that the task is allowed to run on.
+    pub fn cpu_affinity(&self) -> CpuSet {
+        self.cpu_affinity.lock_irq_disabled().clone()
+    }
+
+    /// Sets the CPUs that the task is allowed to run on.
+    ///
+    /// If the task is running or in the run queue, it is moved to an allowed CPU the next
+    /// time it is scheduled.
+    pub fn set_cpu_affinity(&self, cpu_affinity: CpuSet) {
+        *self.cpu_affinity.lock_irq_disabled() = cpu_affinity;
+    }
+
+    /// Tells whether the task is allowed to run on the given CPU.
+    pub fn can_run_on(&self, cpu_id: u32) -> bool {
+        self.cpu_affinity.lock_irq_disabled().contains(cpu_id)
+    }
+
+    /// Returns the ID of the CPU that the task is running on or ran on last time.
+    ///
+    /// For a task that has never run, it is the CPU that creates the task.
+    pub fn cpu(&self) -> u32 {
+        self.cpu.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_cpu(&self, cpu_id: u32) {
+        self.cpu.store(cpu_id, Ordering::Relaxed);
+    }
+
+    /// Returns the CPU time that the task has consumed, including its current run.
+    pub fn cpu_time(&self) -> Duration {
+        let inner = self.task_inner.lock_irq_disabled();
+        let current_run = inner.run_start.map_or(0, |run_start| {
+            crate::timer::read_monotonic_nanos().saturating_sub(run_start)
+        });
+        Duration::from_nanos(inner.cpu_time + current_run)
     }
 }
 
@@ -235,6 +342,9 @@ impl TaskOptions {
     }
 
     /// Sets the priority of the task.
+    ///
+    /// A task with a real-time priority is scheduled with [`SchedPolicy::Fifo`], and the
+    /// others are scheduled with [`SchedPolicy::Normal`].
     pub fn priority(mut self, priority: Priority) -> Self {
         self.priority = priority;
         self
@@ -250,11 +360,17 @@ impl TaskOptions {
         /// all task will entering this function
         /// this function is mean to executing the task_fn in Task
         extern "C" fn kernel_task_entry() {
+            finish_switch();
             let current_task = current_task()
                 .expect("no current task, it should have current task in kernel task entry");
             current_task.func.call(());
             current_task.exit();
         }
+        let sched_policy = if self.priority.is_real_time() {
+            SchedPolicy::Fifo
+        } else {
+            SchedPolicy::Normal
+        };
         let result = Task {
             func: self.func.unwrap(),
             data: self.data.unwrap(),
@@ -262,12 +378,18 @@ impl TaskOptions {
             task_inner: SpinLock::new(TaskInner {
                 task_status: TaskStatus::Runnable,
                 ctx: TaskContext::default(),
+                is_on_cpu: false,
+                cpu_time: 0,
+                run_start: None,
             }),
             exit_code: 0,
             kstack: KernelStack::new_with_guard_page()?,
             link: LinkedListAtomicLink::new(),
-            priority: self.priority,
-            cpu_affinity: self.cpu_affinity,
+            sched_policy: AtomicU8::new(sched_policy as u8),
+            base_priority: AtomicU16::new(self.priority.get()),
+            priority: AtomicU16::new(self.priority.get()),
+            cpu_affinity: SpinLock::new(self.cpu_affinity),
+            cpu: AtomicU32::new(this_cpu()),
         };
 
         result.task_inner.lock().task_status = TaskStatus::Runnable;
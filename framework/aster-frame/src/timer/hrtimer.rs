@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! High-resolution timers.
+//!
+//! Each CPU has a queue of timer callbacks, which are sorted by their deadlines in nanoseconds
+//! of the monotonic clock. The local timer of a CPU works in the one-shot mode, and is
+//! programmed to fire at the earlier one of the first deadline in the queue and the next
+//! scheduler tick.
+//!
+//! The scheduler tick is stopped while the CPU is idle, so an idle CPU is only woken up by the
+//! timers that really expire, or by other interrupts.
+
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use core::{
+    any::Any,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use super::{read_monotonic_nanos, TIMER_FREQ};
+use crate::{
+    arch::timer::set_next_event,
+    cpu::{this_cpu, MAX_CPUS},
+    sync::SpinLock,
+};
+
+/// The interval between two scheduler ticks in nanoseconds.
+const TICK_NS: u64 = 1_000_000_000 / TIMER_FREQ;
+
+/// The deadline of the next scheduler tick if the tick is stopped.
+const TICK_STOPPED: u64 = u64::MAX;
+
+/// The queue of timer callbacks of a CPU, which is keyed by the deadlines and the IDs of the
+/// callbacks.
+type TimerQueue = BTreeMap<(u64, u64), Arc<TimerCallback>>;
+
+// The queues are not CPU-local objects, since a timer callback may be cancelled on a CPU other
+// than the one that it is added to.
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_QUEUE: SpinLock<TimerQueue> = SpinLock::new(BTreeMap::new());
+static TIMER_QUEUES: [SpinLock<TimerQueue>; MAX_CPUS] = [EMPTY_QUEUE; MAX_CPUS];
+
+#[allow(clippy::declare_interior_mutable_const)]
+const TICK_STOPPED_INIT: AtomicU64 = AtomicU64::new(TICK_STOPPED);
+/// The deadlines of the next scheduler ticks of the CPUs.
+static NEXT_TICKS: [AtomicU64; MAX_CPUS] = [TICK_STOPPED_INIT; MAX_CPUS];
+
+/// The allocator of the IDs of timer callbacks, which tell apart the callbacks with the same
+/// deadline.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+pub struct TimerCallback {
+    deadline: u64,
+    id: u64,
+    cpu_id: u32,
+    data: Arc<dyn Any + Send + Sync>,
+    callback: Box<dyn Fn(&TimerCallback) + Send + Sync>,
+    is_cancelled: AtomicBool,
+}
+
+impl TimerCallback {
+    pub fn data(&self) -> &Arc<dyn Any + Send + Sync> {
+        &self.data
+    }
+
+    /// Returns the deadline in nanoseconds of the monotonic clock.
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    /// Whether the deadline is reached.
+    pub fn is_expired(&self) -> bool {
+        read_monotonic_nanos() >= self.deadline
+    }
+
+    /// Cancel a timer callback. If the callback function has not been called,
+    /// it will never be called again.
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Release);
+        TIMER_QUEUES[self.cpu_id as usize]
+            .lock_irq_disabled()
+            .remove(&(self.deadline, self.id));
+    }
+
+    // Whether the timer callback is cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.is_cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Adds a timer callback that is called once the monotonic clock reaches `deadline` (in
+/// nanoseconds).
+///
+/// The callback is called in the interrupt context of the current CPU, so it should not take
+/// too much time.
+pub fn add_timeout<F, T>(deadline: u64, data: T, callback: F) -> Arc<TimerCallback>
+where
+    F: Fn(&TimerCallback) + Send + Sync + 'static,
+    T: Any + Send + Sync,
+{
+    let _guard = crate::trap::disable_local();
+
+    let cpu_id = this_cpu();
+    let timer_callback = Arc::new(TimerCallback {
+        deadline,
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        cpu_id,
+        data: Arc::new(data),
+        callback: Box::new(callback),
+        is_cancelled: AtomicBool::new(false),
+    });
+
+    let mut queue = TIMER_QUEUES[cpu_id as usize].lock();
+    queue.insert(
+        (timer_callback.deadline, timer_callback.id),
+        timer_callback.clone(),
+    );
+    let is_first = queue
+        .first_key_value()
+        .is_some_and(|(_, first)| Arc::ptr_eq(first, &timer_callback));
+    drop(queue);
+
+    if is_first {
+        program_next_event(cpu_id);
+    }
+    timer_callback
+}
+
+/// Handles a timer interrupt on the current CPU.
+///
+/// The expired timer callbacks are called, the scheduler is notified if a tick is due, and
+/// then the local timer is programmed for the next event.
+pub(crate) fn handle_timer_interrupt() {
+    let cpu_id = this_cpu();
+    let now = read_monotonic_nanos();
+
+    let callbacks = {
+        let mut callbacks = Vec::new();
+        let mut queue = TIMER_QUEUES[cpu_id as usize].lock();
+        while let Some(entry) = queue.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            callbacks.push(entry.remove());
+        }
+        callbacks
+    };
+    // The queue is not locked here, so the callbacks can add or cancel timers.
+    for callback in callbacks {
+        if !callback.is_cancelled() {
+            (callback.callback)(&callback);
+        }
+    }
+
+    let next_tick = &NEXT_TICKS[cpu_id as usize];
+    let tick_deadline = next_tick.load(Ordering::Relaxed);
+    if tick_deadline != TICK_STOPPED && tick_deadline <= now {
+        // The ticks that are missed, e.g., because the interrupts are disabled for a long
+        // time, are not made up.
+        next_tick.store(
+            (tick_deadline + TICK_NS).max(now + TICK_NS / 2),
+            Ordering::Relaxed,
+        );
+        crate::task::timer_tick();
+    }
+
+    program_next_event(cpu_id);
+}
+
+/// Starts the scheduler tick of the current CPU if it is stopped.
+///
+/// This function is called when the CPU starts to run tasks.
+pub(crate) fn start_tick() {
+    let _guard = crate::trap::disable_local();
+
+    let cpu_id = this_cpu();
+    let next_tick = &NEXT_TICKS[cpu_id as usize];
+    if next_tick.load(Ordering::Relaxed) != TICK_STOPPED {
+        return;
+    }
+    next_tick.store(read_monotonic_nanos() + TICK_NS, Ordering::Relaxed);
+    program_next_event(cpu_id);
+}
+
+/// Stops the scheduler tick of the current CPU.
+///
+/// This function is called when the CPU becomes idle, which needs no scheduler tick until it
+/// runs tasks again.
+pub(crate) fn stop_tick() {
+    let _guard = crate::trap::disable_local();
+
+    let cpu_id = this_cpu();
+    NEXT_TICKS[cpu_id as usize].store(TICK_STOPPED, Ordering::Relaxed);
+    program_next_event(cpu_id);
+}
+
+/// Programs the local timer of the current CPU to fire at the next event.
+///
+/// The local IRQs must be disabled.
+fn program_next_event(cpu_id: u32) {
+    let first_deadline = TIMER_QUEUES[cpu_id as usize]
+        .lock()
+        .first_key_value()
+        .map_or(u64::MAX, |(&(deadline, _), _)| deadline);
+    let tick_deadline = NEXT_TICKS[cpu_id as usize].load(Ordering::Relaxed);
+    set_next_event(first_deadline.min(tick_deadline));
+}
@@ -2,14 +2,26 @@
 
 //! Timer.
 
-use core::{sync::atomic::Ordering, time::Duration};
+mod hrtimer;
 
-pub use crate::arch::timer::read_monotonic_milli_seconds;
-use crate::{
-    arch::timer::{add_timeout_list, TimerCallback, TICK, TIMER_FREQ},
-    prelude::*,
-    sync::SpinLock,
+use core::time::Duration;
+
+pub(crate) use self::hrtimer::{
+    add_timeout, handle_timer_interrupt, start_tick, stop_tick, TimerCallback,
 };
+pub use crate::arch::timer::TIMER_FREQ;
+use crate::{prelude::*, sync::SpinLock};
+
+/// Returns the time since the system boots up in nanoseconds.
+pub fn read_monotonic_nanos() -> u64 {
+    crate::arch::timer::read_monotonic_nanos()
+}
+
+/// The time since the system boots up.
+/// The currently returned results are in milliseconds.
+pub fn read_monotonic_milli_seconds() -> u64 {
+    read_monotonic_nanos() / 1_000_000
+}
 
 /// A timer invokes a callback function after a specified span of time elapsed.
 ///
@@ -25,8 +37,8 @@ pub struct Timer {
 }
 #[derive(Default)]
 struct TimerInner {
-    start_tick: u64,
-    timeout_tick: u64,
+    /// The deadline in nanoseconds of the monotonic clock.
+    deadline: u64,
     timer_callback: Option<Arc<TimerCallback>>,
 }
 
@@ -40,8 +52,6 @@ fn timer_callback(callback: &TimerCallback) {
     }
 }
 
-const NANOS_DIVIDE: u64 = 1_000_000_000 / TIMER_FREQ;
-
 impl Timer {
     /// Creates a new instance, given a callback function.
     pub fn new<F>(f: F) -> Result<Arc<Self>>
@@ -66,12 +76,10 @@ impl Timer {
             }
             None => {}
         }
-        let tick_count =
-            timeout.as_secs() * TIMER_FREQ + timeout.subsec_nanos() as u64 / NANOS_DIVIDE;
-        let tick = TICK.load(Ordering::SeqCst);
-        lock.start_tick = tick;
-        lock.timeout_tick = tick + tick_count;
-        lock.timer_callback = Some(add_timeout_list(tick_count, self.clone(), timer_callback));
+        let timeout_ns = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+        let deadline = read_monotonic_nanos().saturating_add(timeout_ns);
+        lock.deadline = deadline;
+        lock.timer_callback = Some(add_timeout(deadline, self.clone(), timer_callback));
     }
 
     /// Returns the remaining timeout value.
@@ -79,17 +87,7 @@ impl Timer {
     /// If the timer is not set, then the remaining timeout value is zero.
     pub fn remain(&self) -> Duration {
         let lock = self.inner.lock_irq_disabled();
-        let tick_remain = {
-            let tick = TICK.load(Ordering::SeqCst) as i64;
-            lock.timeout_tick as i64 - tick
-        };
-        if tick_remain <= 0 {
-            Duration::new(0, 0)
-        } else {
-            let second_count = tick_remain as u64 / TIMER_FREQ;
-            let remain_count = tick_remain as u64 % TIMER_FREQ;
-            Duration::new(second_count, (remain_count * NANOS_DIVIDE) as u32)
-        }
+        Duration::from_nanos(lock.deadline.saturating_sub(read_monotonic_nanos()))
     }
 
     /// Clear the timeout value.
@@ -98,8 +96,7 @@ impl Timer {
         if let Some(callback) = &lock.timer_callback {
             callback.cancel();
         }
-        lock.timeout_tick = 0;
-        lock.start_tick = 0;
+        lock.deadline = 0;
         lock.timer_callback = None;
     }
 }
@@ -51,7 +51,12 @@ impl UserSpace {
 /// Only visible in aster-frame
 pub(crate) trait UserContextApiInternal {
     /// Starts executing in the user mode.
-    fn execute(&mut self) -> UserEvent;
+    ///
+    /// After the interrupts that interrupt the user space are handled, `has_kernel_event` is
+    /// called to check whether the kernel has events to handle.
+    fn execute<F>(&mut self, has_kernel_event: F) -> UserEvent
+    where
+        F: FnMut() -> bool;
 
     /// Use the information inside CpuContext to build a trapframe
     fn as_trap_frame(&self) -> TrapFrame;
@@ -106,7 +111,7 @@ pub trait UserContextApi {
 /// let mut user_mode = user_space.user_mode();
 /// loop {
 ///     // Execute in the user space until some interesting user event occurs
-///     let user_event = user_mode.execute();
+///     let user_event = user_mode.execute(|| false);
 ///     todo!("handle the user event, e.g., syscall");
 /// }
 /// ```
@@ -130,17 +135,25 @@ impl<'a> UserMode<'a> {
 
     /// Starts executing in the user mode. Make sure current task is the task in `UserMode`.
     ///
-    /// The method returns for one of three possible reasons indicated by `UserEvent`.
+    /// The method returns for one of four possible reasons indicated by `UserEvent`.
     /// 1. The user invokes a system call;
     /// 2. The user triggers an exception;
-    /// 3. The user triggers a fault.
+    /// 3. The user triggers a fault;
+    /// 4. The kernel has events to handle, which is told by `has_kernel_event`.
+    ///
+    /// The `has_kernel_event` function is called in the task context with the local IRQs
+    /// enabled, after handling the interrupts that interrupt the user space. So a task that
+    /// never traps into the kernel by itself can still be stopped, e.g., to deliver signals.
     ///
     /// After handling the user event and updating the user-mode CPU context,
     /// this method can be invoked again to go back to the user space.
-    pub fn execute(&mut self) -> UserEvent {
+    pub fn execute<F>(&mut self, has_kernel_event: F) -> UserEvent
+    where
+        F: FnMut() -> bool,
+    {
         self.user_space.vm_space().activate();
         debug_assert!(Arc::ptr_eq(&self.current, &Task::current()));
-        self.context.execute()
+        self.context.execute(has_kernel_event)
     }
 
     /// Returns an immutable reference the user-mode CPU context.
@@ -165,4 +178,6 @@ impl<'a> UserMode<'a> {
 pub enum UserEvent {
     Syscall,
     Exception,
+    /// The kernel has events to handle before going back to the user space.
+    KernelEvent,
 }
@@ -226,6 +226,16 @@ impl VmFrame {
         (self.frame_index() + 1) * PAGE_SIZE
     }
 
+    /// Returns whether there are other `VmFrame`s referring to the same physical page.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.frame_index) > 1
+    }
+
+    /// Returns the number of `VmFrame`s referring to the same physical page, including this one.
+    pub fn reference_count(&self) -> usize {
+        Arc::strong_count(&self.frame_index)
+    }
+
     fn need_dealloc(&self) -> bool {
         (*self.frame_index & VmFrameFlags::NEED_DEALLOC.bits()) != 0
     }
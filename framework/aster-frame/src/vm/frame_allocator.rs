@@ -14,7 +14,44 @@ use crate::{
     vm::PAGE_SIZE,
 };
 
-pub(super) static FRAME_ALLOCATOR: Once<SpinLock<FrameAllocator>> = Once::new();
+pub(super) static FRAME_ALLOCATOR: Once<SpinLock<CountingFrameAllocator>> = Once::new();
+
+/// A frame allocator that counts the allocated frames.
+pub(super) struct CountingFrameAllocator {
+    allocator: FrameAllocator,
+    /// The total number of frames.
+    total: usize,
+    /// The number of allocated frames.
+    allocated: usize,
+}
+
+impl CountingFrameAllocator {
+    /// Allocates contiguous frames, returning the index of the first frame.
+    ///
+    /// The buddy allocator allocates a block of `count.next_power_of_two()` frames, and the
+    /// index of the first frame is always aligned to the size of the block.
+    pub fn alloc(&mut self, count: usize) -> Option<usize> {
+        let start = self.allocator.alloc(count)?;
+        self.allocated += count;
+        Some(start)
+    }
+
+    pub fn dealloc(&mut self, start: usize, count: usize) {
+        self.allocator.dealloc(start, count);
+        self.allocated -= count;
+    }
+}
+
+/// Returns the total number of frames that are managed by the frame allocator.
+pub fn total_frames() -> usize {
+    FRAME_ALLOCATOR.get().unwrap().lock().total
+}
+
+/// Returns the number of frames that are not allocated.
+pub fn free_frames() -> usize {
+    let allocator = FRAME_ALLOCATOR.get().unwrap().lock();
+    allocator.total - allocator.allocated
+}
 
 pub(crate) fn alloc(nframes: usize, flags: VmFrameFlags) -> Option<VmFrameVec> {
     FRAME_ALLOCATOR
@@ -87,6 +124,7 @@ pub(crate) unsafe fn dealloc_contiguous(start_index: usize, nframes: usize) {
 
 pub(crate) fn init(regions: &[MemoryRegion]) {
     let mut allocator = FrameAllocator::<32>::new();
+    let mut total = 0;
     for region in regions.iter() {
         if region.typ() == MemoryRegionType::Usable {
             // Make the memory region page-aligned, and skip if it is too small.
@@ -97,6 +135,7 @@ pub(crate) fn init(regions: &[MemoryRegion]) {
                 continue;
             }
             allocator.add_frame(start, end);
+            total += end - start;
             info!(
                 "Found usable region, start:{:x}, end:{:x}",
                 region.base(),
@@ -104,5 +143,11 @@ pub(crate) fn init(regions: &[MemoryRegion]) {
             );
         }
     }
-    FRAME_ALLOCATOR.call_once(|| SpinLock::new(allocator));
+    FRAME_ALLOCATOR.call_once(|| {
+        SpinLock::new(CountingFrameAllocator {
+            allocator,
+            total,
+            allocated: 0,
+        })
+    });
 }
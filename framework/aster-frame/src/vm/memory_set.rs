@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::collections::{btree_map::Entry, BTreeMap};
-use core::fmt;
+use core::{fmt, ops::Range};
 
 use align_ext::AlignExt;
 
@@ -10,6 +10,7 @@ use super::{
     page_table::{MapInfo, MapOp, MapProperty, PageTable, UserMode},
 };
 use crate::{
+    arch::mm::tlb_flush,
     prelude::*,
     vm::{
         is_page_aligned, page_table::MapStatus, VmAllocOptions, VmFrame, VmFrameVec, VmPerm,
@@ -96,6 +97,17 @@ impl MapArea {
         self.mapper.remove(&va)
     }
 
+    /// Returns the physical address of the first frame if all the frames are contiguous.
+    fn contiguous_paddr(&self) -> Option<Paddr> {
+        let start_paddr = self.mapper.get(&self.start_va)?.start_paddr();
+        let is_contiguous = self.mapper.len() == self.size / PAGE_SIZE
+            && self
+                .mapper
+                .iter()
+                .all(|(va, frame)| frame.start_paddr() == start_paddr + (va - self.start_va));
+        is_contiguous.then_some(start_paddr)
+    }
+
     pub fn write_data(&mut self, addr: usize, data: &[u8]) {
         let mut current_start_address = addr;
         let mut buf_reader: VmReader = data.into();
@@ -139,8 +151,19 @@ impl MemorySet {
             // TODO: check overlap
             if let Entry::Vacant(e) = self.areas.entry(area.start_va) {
                 let area = e.insert(area);
-                for (va, frame) in area.mapper.iter() {
-                    self.pt.map_frame(*va, frame, area.info.prop).unwrap();
+                // The contiguous frames are mapped as a whole, so that the page table can
+                // map them with huge pages if they are aligned.
+                if area.size > PAGE_SIZE
+                    && let Some(paddr) = area.contiguous_paddr()
+                {
+                    let va_range = area.start_va..area.start_va + area.size;
+                    self.pt
+                        .map(&va_range, &(paddr..paddr + area.size), area.info.prop)
+                        .unwrap();
+                } else {
+                    for (va, frame) in area.mapper.iter() {
+                        self.pt.map_frame(*va, frame, area.info.prop).unwrap();
+                    }
                 }
             } else {
                 panic!(
@@ -177,22 +200,63 @@ impl MemorySet {
         }
     }
 
-    pub fn unmap(&mut self, va: Vaddr) -> Result<()> {
-        if let Some(area) = self.areas.remove(&va) {
-            for (va, _) in area.mapper.iter() {
-                self.pt.unmap(&(*va..*va + PAGE_SIZE)).unwrap();
+    /// Unmaps the areas within the range, skipping the gaps where nothing is mapped.
+    ///
+    /// The areas that are partially covered by the range are split first.
+    pub fn unmap(&mut self, range: &Range<Vaddr>) -> Result<()> {
+        if !is_page_aligned(range.start) || !is_page_aligned(range.end) {
+            return Err(Error::InvalidArgs);
+        }
+        self.split_area(range.start);
+        self.split_area(range.end);
+        let area_vas: Vec<Vaddr> = self.areas.range(range.clone()).map(|(va, _)| *va).collect();
+        for va in area_vas {
+            let area = self.areas.remove(&va).unwrap();
+            self.pt.unmap(&(va..va + area.size)).unwrap();
+            for page_va in (va..va + area.size).step_by(PAGE_SIZE) {
+                tlb_flush(page_va);
             }
-            Ok(())
-        } else {
-            Err(Error::PageFault)
         }
+        Ok(())
+    }
+
+    /// Clears the accessed bit of the page that contains the address, returning whether
+    /// the page has been accessed since the bit was last cleared.
+    pub fn clear_accessed(&mut self, vaddr: Vaddr) -> bool {
+        let vaddr = vaddr.align_down(PAGE_SIZE);
+        let Some(result) = self
+            .pt
+            .query(&(vaddr..vaddr + PAGE_SIZE))
+            .ok()
+            .and_then(|mut i| i.next())
+        else {
+            return false;
+        };
+        if !result.info.status.contains(MapStatus::ACCESSED) {
+            return false;
+        }
+        // Re-creating the page table entries with the same property clears the status bits.
+        let len = result.va.len();
+        let start = result.va.start.align_down(len);
+        self.pt
+            .protect(&(start..start + len), |info: MapInfo| info.prop)
+            .unwrap();
+        for page_va in (start..start + len).step_by(PAGE_SIZE) {
+            tlb_flush(page_va);
+        }
+        true
+    }
+
+    /// Returns the number of pages mapped in all the areas.
+    pub fn nr_mapped_pages(&self) -> usize {
+        self.areas.values().map(|area| area.mapper.len()).sum()
     }
 
     pub fn clear(&mut self) {
-        for area in self.areas.values_mut() {
-            for (va, _) in area.mapper.iter() {
-                self.pt.unmap(&(*va..*va + PAGE_SIZE)).unwrap();
-            }
+        for area in self.areas.values() {
+            self.pt
+                .unmap(&(area.start_va..area.start_va + area.size))
+                .unwrap();
         }
         self.areas.clear();
     }
@@ -246,14 +310,47 @@ impl MemorySet {
         Err(Error::PageFault)
     }
 
-    pub fn protect(&mut self, addr: Vaddr, op: impl MapOp) {
-        let va = addr..addr + PAGE_SIZE;
-        // Temporary solution, since the `MapArea` currently only represents
-        // a single `VmFrame`.
-        if let Some(areas) = self.areas.get_mut(&addr) {
-            areas.info.prop = op(areas.info);
+    /// Applies the operation to the areas within the range, skipping the gaps where nothing
+    /// is mapped.
+    ///
+    /// The areas that are partially covered by the range are split first.
+    pub fn protect(&mut self, range: &Range<Vaddr>, op: impl MapOp) -> Result<()> {
+        if !is_page_aligned(range.start) || !is_page_aligned(range.end) {
+            return Err(Error::InvalidArgs);
+        }
+        self.split_area(range.start);
+        self.split_area(range.end);
+        for (va, area) in self.areas.range_mut(range.clone()) {
+            area.info.prop = op(area.info);
+            self.pt.protect(&(*va..*va + area.size), &op).unwrap();
+        }
+        Ok(())
+    }
+
+    /// Splits the area that contains the address in the middle into areas of a single page,
+    /// so that the address becomes the start of an area.
+    ///
+    /// The page table is not changed here. A huge page in the page table is split when it is
+    /// partially unmapped or protected.
+    fn split_area(&mut self, va: Vaddr) {
+        let Some((&start_va, area)) = self.areas.range(..va).next_back() else {
+            return;
+        };
+        if start_va + area.size <= va {
+            return;
+        }
+        let area = self.areas.remove(&start_va).unwrap();
+        for (page_va, frame) in area.mapper {
+            self.areas.insert(
+                page_va,
+                MapArea::new(
+                    page_va,
+                    PAGE_SIZE,
+                    area.info.prop,
+                    VmFrameVec::from_one_frame(frame),
+                ),
+            );
         }
-        self.pt.protect(&va, op).unwrap();
     }
 }
 
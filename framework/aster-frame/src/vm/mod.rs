@@ -29,6 +29,7 @@ pub(crate) use self::kspace::paddr_to_vaddr;
 pub use self::{
     dma::{Daddr, DmaCoherent, DmaDirection, DmaStream, DmaStreamSlice, HasDaddr},
     frame::{VmFrame, VmFrameVec, VmFrameVecIter, VmReader, VmSegment, VmWriter},
+    frame_allocator::{free_frames, total_frames},
     io::VmIo,
     kspace::vaddr_to_paddr,
     memory_set::{MapArea, MemorySet},
@@ -40,6 +41,9 @@ use crate::boot::memory_region::{MemoryRegion, MemoryRegionType};
 
 pub const PAGE_SIZE: usize = 0x1000;
 
+/// The size of a huge page, which is mapped by a single entry in the second-level page table.
+pub const HUGE_PAGE_SIZE: usize = 0x20_0000;
+
 /// The maximum virtual address of user space (non inclusive).
 ///
 /// Typicall 64-bit systems have at least 48-bit virtual address space.
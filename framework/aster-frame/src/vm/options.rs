@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{frame::VmFrameFlags, frame_allocator, VmFrame, VmFrameVec, VmSegment};
+use super::{frame::VmFrameFlags, frame_allocator, VmFrame, VmFrameVec, VmSegment, PAGE_SIZE};
 use crate::{prelude::*, Error};
 
 /// Options for allocating physical memory pages (or frames).
@@ -13,6 +13,7 @@ use crate::{prelude::*, Error};
 pub struct VmAllocOptions {
     nframes: usize,
     is_contiguous: bool,
+    align: usize,
     uninit: bool,
 }
 
@@ -22,6 +23,7 @@ impl VmAllocOptions {
         Self {
             nframes,
             is_contiguous: false,
+            align: PAGE_SIZE,
             uninit: false,
         }
     }
@@ -34,6 +36,18 @@ impl VmAllocOptions {
         self
     }
 
+    /// Sets the alignment of the physical address of the first allocated frame, in bytes.
+    ///
+    /// The alignment must be a power of two, and it is only supported for contiguous frames.
+    /// Since the frames are allocated in blocks whose sizes are powers of two, the alignment
+    /// cannot exceed the size of the frames rounded up to a power of two.
+    ///
+    /// The default value is the page size.
+    pub fn align(&mut self, align: usize) -> &mut Self {
+        self.align = align;
+        self
+    }
+
     /// Sets whether the allocated frames should be uninitialized.
     ///
     /// If `uninit` is set as `false`, the frame will be zeroed once allocated.
@@ -47,9 +61,14 @@ impl VmAllocOptions {
 
     /// Allocate a collection of page frames according to the given options.
     pub fn alloc(&self) -> Result<VmFrameVec> {
+        self.check_align()?;
         let flags = self.flags();
         let frames = if self.is_contiguous {
-            frame_allocator::alloc(self.nframes, flags).ok_or(Error::NoMemory)?
+            let frames = frame_allocator::alloc(self.nframes, flags).ok_or(Error::NoMemory)?;
+            debug_assert!(frames
+                .get(0)
+                .map_or(true, |frame| frame.start_paddr() % self.align == 0));
+            frames
         } else {
             let mut frame_list = Vec::new();
             for _ in 0..self.nframes {
@@ -87,9 +106,11 @@ impl VmAllocOptions {
         if !self.is_contiguous || self.nframes == 0 {
             return Err(Error::InvalidArgs);
         }
+        self.check_align()?;
 
         let segment =
             frame_allocator::alloc_contiguous(self.nframes, self.flags()).ok_or(Error::NoMemory)?;
+        debug_assert!(segment.start_paddr() % self.align == 0);
         if !self.uninit {
             segment.writer().fill(0);
         }
@@ -97,6 +118,20 @@ impl VmAllocOptions {
         Ok(segment)
     }
 
+    fn check_align(&self) -> Result<()> {
+        if self.align == PAGE_SIZE {
+            return Ok(());
+        }
+        if !self.align.is_power_of_two()
+            || self.align < PAGE_SIZE
+            || !self.is_contiguous
+            || self.align > self.nframes.next_power_of_two() * PAGE_SIZE
+        {
+            return Err(Error::InvalidArgs);
+        }
+        Ok(())
+    }
+
     fn flags(&self) -> VmFrameFlags {
         VmFrameFlags::empty()
     }
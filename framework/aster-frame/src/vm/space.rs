@@ -4,12 +4,12 @@ use core::ops::Range;
 
 use bitflags::bitflags;
 
-use super::{is_page_aligned, MapArea, MemorySet, VmFrameVec, VmIo};
+use super::{is_page_aligned, MapArea, MemorySet, VmFrame, VmFrameVec, VmIo};
 use crate::{
     arch::mm::PageTableFlags,
     prelude::*,
     sync::Mutex,
-    vm::{page_table::MapProperty, PAGE_SIZE},
+    vm::{page_table::MapProperty, HUGE_PAGE_SIZE, PAGE_SIZE},
     Error,
 };
 
@@ -62,8 +62,9 @@ impl VmSpace {
         // debug!("map to vm space: 0x{:x}", options.addr.unwrap());
 
         let mut memory_set = self.memory_set.lock();
-        // FIXME: This is only a hack here. The interface of MapArea cannot simply deal with unmap part of memory,
-        // so we only map MapArea of page size now.
+        // The frames are mapped page by page, except that the aligned and contiguous frames
+        // of a huge page are mapped as a whole, so that they can be mapped with a single
+        // page table entry.
 
         // Ensure that the base address is not unwrapped repeatedly
         // and the addresses used later will not overflow
@@ -71,15 +72,24 @@ impl VmSpace {
         base_addr
             .checked_add(frames.len() * PAGE_SIZE)
             .ok_or(Error::Overflow)?;
-        for (idx, frame) in frames.into_iter().enumerate() {
-            let addr = base_addr + idx * PAGE_SIZE;
-            let frames = VmFrameVec::from_one_frame(frame);
+        let prop = MapProperty::new_general(options.perm);
+        let mut frames_left = frames.0.as_slice();
+        let mut addr = base_addr;
+        while !frames_left.is_empty() {
+            let nframes = if is_huge_page(addr, frames_left) {
+                HUGE_PAGE_SIZE / PAGE_SIZE
+            } else {
+                1
+            };
+            let (area_frames, remain_frames) = frames_left.split_at(nframes);
             memory_set.map(MapArea::new(
                 addr,
-                PAGE_SIZE,
-                MapProperty::new_general(options.perm),
-                frames,
+                nframes * PAGE_SIZE,
+                prop,
+                VmFrameVec(area_frames.to_vec()),
             ));
+            frames_left = remain_frames;
+            addr += nframes * PAGE_SIZE;
         }
 
         Ok(base_addr)
@@ -111,17 +121,20 @@ impl VmSpace {
     /// are mapped.
     pub fn unmap(&self, range: &Range<Vaddr>) -> Result<()> {
         assert!(is_page_aligned(range.start) && is_page_aligned(range.end));
-        let mut start_va = range.start;
-        let num_pages = (range.end - range.start) / PAGE_SIZE;
-        let mut inner = self.memory_set.lock();
-        start_va
-            .checked_add(PAGE_SIZE * num_pages)
-            .ok_or(Error::Overflow)?;
-        for i in 0..num_pages {
-            inner.unmap(start_va)?;
-            start_va += PAGE_SIZE;
-        }
-        Ok(())
+        self.memory_set.lock().unmap(range)
+    }
+
+    /// Clears the accessed bit of the page that contains `vaddr` in the page table,
+    /// returning whether the page has been accessed since the bit was last cleared.
+    ///
+    /// This is used to find out the pages that are not recently used.
+    pub fn clear_accessed(&self, vaddr: Vaddr) -> bool {
+        self.memory_set.lock().clear_accessed(vaddr)
+    }
+
+    /// Returns the number of physical memory pages that are mapped.
+    pub fn nr_mapped_pages(&self) -> usize {
+        self.memory_set.lock().nr_mapped_pages()
     }
 
     /// clear all mappings
@@ -133,20 +146,14 @@ impl VmSpace {
 
     /// Update the VM protection permissions within the VM address range.
     ///
-    /// The entire specified VM range must have been mapped with physical
-    /// memory pages.
+    /// The range is allowed to contain gaps, where no physical memory pages
+    /// are mapped.
     pub fn protect(&self, range: &Range<Vaddr>, perm: VmPerm) -> Result<()> {
         debug_assert!(range.start % PAGE_SIZE == 0);
         debug_assert!(range.end % PAGE_SIZE == 0);
-        let start_page = range.start / PAGE_SIZE;
-        let end_page = range.end / PAGE_SIZE;
-        for page_idx in start_page..end_page {
-            let addr = page_idx * PAGE_SIZE;
-            self.memory_set
-                .lock()
-                .protect(addr, MapProperty::new_general(perm))
-        }
-        Ok(())
+        self.memory_set
+            .lock()
+            .protect(range, MapProperty::new_general(perm))
     }
 
     /// Deep-copy the current `VmSpace`.
@@ -160,6 +167,20 @@ impl VmSpace {
     }
 }
 
+/// Returns whether the frames that start from the address form an aligned huge page.
+fn is_huge_page(addr: Vaddr, frames: &[VmFrame]) -> bool {
+    let nframes = HUGE_PAGE_SIZE / PAGE_SIZE;
+    if addr % HUGE_PAGE_SIZE != 0 || frames.len() < nframes {
+        return false;
+    }
+    let start_paddr = frames[0].start_paddr();
+    start_paddr % HUGE_PAGE_SIZE == 0
+        && frames[..nframes]
+            .iter()
+            .enumerate()
+            .all(|(idx, frame)| frame.start_paddr() == start_paddr + idx * PAGE_SIZE)
+}
+
 impl Default for VmSpace {
     fn default() -> Self {
         Self::new()
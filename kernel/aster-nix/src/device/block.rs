@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_block::BlockDevice;
+use spin::Once;
+
+use super::*;
+use crate::{events::IoEvents, fs::inode_handle::FileIo, prelude::*, process::signal::Poller};
+
+/// The major number of the block device nodes.
+///
+/// The block devices are not named after the Linux conventions (e.g., `sda` or `vda`), so
+/// the major number reserved for local and experimental use is taken.
+const BLOCK_MAJOR: u32 = 240;
+
+/// The names of the block devices, indexed by the minor numbers of their device nodes.
+static BLOCK_DEVICE_NAMES: Once<Vec<String>> = Once::new();
+
+/// A device node at `/dev/<name>` for a block device, which is used to refer to the block
+/// device by a path, e.g., in `swapon`.
+///
+/// The block device cannot be read or written through the node yet.
+pub struct BlockDeviceNode {
+    minor: u32,
+}
+
+impl Device for BlockDeviceNode {
+    fn type_(&self) -> DeviceType {
+        DeviceType::BlockDevice
+    }
+
+    fn id(&self) -> DeviceId {
+        DeviceId::new(BLOCK_MAJOR, self.minor)
+    }
+}
+
+impl FileIo for BlockDeviceNode {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "block devices cannot be read directly");
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "block devices cannot be written directly");
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        let events = IoEvents::IN | IoEvents::OUT;
+        events & mask
+    }
+}
+
+/// Adds the device nodes for all the registered block devices.
+///
+/// The minor numbers are assigned when the nodes are added, and the device IDs are resolved
+/// to the devices by the names that they are registered with.
+pub fn init() -> Result<()> {
+    let names = BLOCK_DEVICE_NAMES.call_once(|| {
+        aster_block::all_devices()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    });
+    for (minor, name) in names.iter().enumerate() {
+        let node = Arc::new(BlockDeviceNode {
+            minor: minor as u32,
+        });
+        add_node(node, name)?;
+    }
+    Ok(())
+}
+
+/// Returns the name and the block device that the device ID refers to.
+pub fn get_block_device(id: DeviceId) -> Option<(String, Arc<dyn BlockDevice>)> {
+    if id.major() != BLOCK_MAJOR {
+        return None;
+    }
+    let name = BLOCK_DEVICE_NAMES.get()?.get(id.minor() as usize)?;
+    let device = aster_block::get_device(name)?;
+    Some((name.clone(), device))
+}
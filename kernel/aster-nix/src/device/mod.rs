@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub mod block;
 mod null;
 mod pty;
 mod random;
@@ -41,5 +42,6 @@ pub fn init() -> Result<()> {
     let urandom = Arc::new(urandom::Urandom);
     add_node(urandom, "urandom")?;
     pty::init()?;
+    block::init()?;
     Ok(())
 }
@@ -99,6 +99,10 @@ impl FileSystem for DevPts {
     fn flags(&self) -> FsFlags {
         FsFlags::empty()
     }
+
+    fn name(&self) -> &'static str {
+        "devpts"
+    }
 }
 
 struct RootInode {
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The event file (i.e., eventfd), which is a counter that can be used as an event wait/notify
+//! mechanism by user-space applications and by the kernel.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    file_handle::FileLike,
+    utils::{CreationFlags, StatusFlags},
+};
+use crate::{
+    events::{IoEvents, Observer},
+    prelude::*,
+    process::signal::{Pollee, Poller},
+};
+
+pub struct EventFile {
+    counter: Mutex<u64>,
+    is_semaphore: bool,
+    is_nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+impl EventFile {
+    /// The maximum value of the counter.
+    const MAX_COUNTER: u64 = u64::MAX - 1;
+
+    pub fn new(init_val: u64, flags: EventFileFlags) -> Self {
+        let pollee = Pollee::new(IoEvents::empty());
+        let event_file = Self {
+            counter: Mutex::new(init_val),
+            is_semaphore: flags.contains(EventFileFlags::EFD_SEMAPHORE),
+            is_nonblocking: AtomicBool::new(flags.contains(EventFileFlags::EFD_NONBLOCK)),
+            pollee,
+        };
+        event_file.update_io_events(init_val);
+        event_file
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn update_io_events(&self, counter: u64) {
+        if counter > 0 {
+            self.pollee.add_events(IoEvents::IN);
+        } else {
+            self.pollee.del_events(IoEvents::IN);
+        }
+
+        if counter < Self::MAX_COUNTER {
+            self.pollee.add_events(IoEvents::OUT);
+        } else {
+            self.pollee.del_events(IoEvents::OUT);
+        }
+    }
+
+    fn try_read(&self) -> Result<u64> {
+        let mut counter = self.counter.lock();
+        if *counter == 0 {
+            return_errno_with_message!(Errno::EAGAIN, "the counter is zero");
+        }
+
+        let val = if self.is_semaphore {
+            *counter -= 1;
+            1
+        } else {
+            core::mem::replace(&mut *counter, 0)
+        };
+        self.update_io_events(*counter);
+
+        Ok(val)
+    }
+
+    fn try_write(&self, val: u64) -> Result<()> {
+        let mut counter = self.counter.lock();
+        if Self::MAX_COUNTER - *counter < val {
+            return_errno_with_message!(Errno::EAGAIN, "the counter would overflow");
+        }
+
+        *counter += val;
+        self.update_io_events(*counter);
+
+        Ok(())
+    }
+
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+}
+
+impl FileLike for EventFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        const VAL_LEN: usize = core::mem::size_of::<u64>();
+        if buf.len() < VAL_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        let val = if self.is_nonblocking() {
+            self.try_read()?
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_read())?
+        };
+        buf[..VAL_LEN].copy_from_slice(&val.to_ne_bytes());
+
+        Ok(VAL_LEN)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        const VAL_LEN: usize = core::mem::size_of::<u64>();
+        if buf.len() < VAL_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        let val = u64::from_ne_bytes(buf[..VAL_LEN].try_into().unwrap());
+        if val == u64::MAX {
+            return_errno_with_message!(Errno::EINVAL, "the value is too large");
+        }
+
+        if self.is_nonblocking() {
+            self.try_write(val)?;
+        } else {
+            self.wait_events(IoEvents::OUT, || self.try_write(val))?;
+        }
+
+        Ok(VAL_LEN)
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Result<Weak<dyn Observer<IoEvents>>> {
+        self.pollee
+            .unregister_observer(observer)
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "the observer is not registered"))
+    }
+}
+
+bitflags! {
+    pub struct EventFileFlags: u32 {
+        const EFD_SEMAPHORE = 1;
+        const EFD_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+        const EFD_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+    }
+}
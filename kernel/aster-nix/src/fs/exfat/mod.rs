@@ -99,6 +99,10 @@ mod test {
             bio.complete(BioStatus::Complete);
             Ok(())
         }
+
+        fn nr_sectors(&self) -> usize {
+            self.sectors_count()
+        }
     }
     /// Exfat disk image
     static EXFAT_IMAGE: &[u8] = include_bytes!("../../../../../regression/build/exfat.img");
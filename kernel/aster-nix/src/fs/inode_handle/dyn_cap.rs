@@ -36,6 +36,7 @@ impl InodeHandle<Rights> {
             access_mode,
             status_flags: AtomicU32::new(status_flags.bits()),
         });
+        inner.dentry.fsnotify(FsEvents::OPEN);
         Ok(Self(inner, Rights::from(access_mode)))
     }
 
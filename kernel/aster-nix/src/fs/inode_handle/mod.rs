@@ -15,6 +15,7 @@ use crate::{
     fs::{
         device::Device,
         file_handle::FileLike,
+        notify::FsEvents,
         utils::{
             AccessMode, Dentry, DirentVisitor, InodeMode, InodeType, IoctlCmd, Metadata, SeekFrom,
             StatusFlags,
@@ -51,6 +52,7 @@ impl InodeHandle_ {
         } else {
             self.dentry.inode().read_at(*offset, buf)?
         };
+        self.dentry.fsnotify(FsEvents::ACCESS);
 
         *offset += len;
         Ok(len)
@@ -71,6 +73,7 @@ impl InodeHandle_ {
         } else {
             self.dentry.inode().write_at(*offset, buf)?
         };
+        self.dentry.fsnotify(FsEvents::MODIFY);
 
         *offset += len;
         Ok(len)
@@ -127,7 +130,9 @@ impl InodeHandle_ {
         if self.status_flags().contains(StatusFlags::O_APPEND) {
             return_errno_with_message!(Errno::EPERM, "can not resize append-only file");
         }
-        self.dentry.resize(new_size)
+        self.dentry.resize(new_size)?;
+        self.dentry.fsnotify(FsEvents::MODIFY);
+        Ok(())
     }
 
     pub fn access_mode(&self) -> AccessMode {
@@ -180,6 +185,17 @@ impl InodeHandle_ {
     pub fn set_group(&self, gid: Gid) -> Result<()>;
 }
 
+impl Drop for InodeHandle_ {
+    fn drop(&mut self) {
+        let events = if self.access_mode.is_writable() {
+            FsEvents::CLOSE_WRITE
+        } else {
+            FsEvents::CLOSE_NOWRITE
+        };
+        self.dentry.fsnotify(events);
+    }
+}
+
 impl Debug for InodeHandle_ {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("InodeHandle_")
@@ -2,16 +2,21 @@
 pub mod device;
 pub mod devpts;
 pub mod epoll;
+pub mod eventfd;
 pub mod exfat;
 pub mod ext2;
 pub mod file_handle;
 pub mod file_table;
 pub mod fs_resolver;
 pub mod inode_handle;
+pub mod notify;
 pub mod pipe;
 pub mod procfs;
 pub mod ramfs;
 pub mod rootfs;
+pub mod signalfd;
+pub mod sysfs;
+pub mod timerfd;
 pub mod utils;
 
 use aster_block::BlockDevice;
@@ -48,6 +53,7 @@ pub fn lazy_init() {
     //The device name is specified in qemu args as --serial={device_name}
     let ext2_device_name = "vext2";
     let exfat_device_name = "vexfat";
+    let swap_device_name = "vswap";
 
     if let Ok(block_device_ext2) = start_block_device(ext2_device_name) {
         let ext2_fs = Ext2::open(block_device_ext2).unwrap();
@@ -62,4 +68,7 @@ pub fn lazy_init() {
         println!("[kernel] Mount ExFat fs at {:?} ", target_path);
         self::rootfs::mount_fs_at(exfat_fs, &target_path).unwrap();
     }
+
+    // The swap device is not used until it is enabled by `swapon`.
+    let _ = start_block_device(swap_device_name);
 }
@@ -0,0 +1,439 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The inotify file, which reports filesystem events on the watched dentries via a file
+//! descriptor.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use super::{FsEvent, FsEvents};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{
+        file_handle::FileLike,
+        utils::{CStr256, CreationFlags, Dentry, InodeType, IoctlCmd, StatusFlags},
+    },
+    prelude::*,
+    process::signal::{Pollee, Poller},
+    util::write_val_to_user,
+};
+
+/// The maximum number of events that can be queued in an inotify file.
+///
+/// If the limit is reached, further events are dropped and an `IN_Q_OVERFLOW` event is reported.
+const MAX_QUEUED_EVENTS: usize = 16384;
+
+/// The special event that is reported when a watch is removed.
+const IN_IGNORED: u32 = 0x0000_8000;
+/// The special event that is reported when events are dropped.
+const IN_Q_OVERFLOW: u32 = 0x0000_4000;
+
+pub struct InotifyFile {
+    watches: Mutex<Watches>,
+    events: Mutex<VecDeque<InotifyEvent>>,
+    is_nonblocking: AtomicBool,
+    pollee: Pollee,
+    weak_self: Weak<Self>,
+}
+
+struct Watches {
+    /// The watches indexed by their watch descriptors.
+    table: BTreeMap<i32, Arc<InotifyWatch>>,
+    next_wd: i32,
+}
+
+impl InotifyFile {
+    pub fn new(flags: InotifyFlags) -> Arc<Self> {
+        Arc::new_cyclic(|weak_self| Self {
+            watches: Mutex::new(Watches {
+                table: BTreeMap::new(),
+                next_wd: 1,
+            }),
+            events: Mutex::new(VecDeque::new()),
+            is_nonblocking: AtomicBool::new(flags.contains(InotifyFlags::IN_NONBLOCK)),
+            pollee: Pollee::new(IoEvents::empty()),
+            weak_self: weak_self.clone(),
+        })
+    }
+
+    /// Adds a watch on `dentry`, or modifies the existing one, returning the watch descriptor.
+    pub fn add_watch(&self, dentry: &Arc<Dentry>, mask: u32) -> Result<i32> {
+        let events = FsEvents::from_bits_truncate(mask) - FsEvents::ISDIR;
+        let flags = InotifyWatchFlags::from_bits_truncate(mask);
+        if events.is_empty() {
+            return_errno_with_message!(Errno::EINVAL, "no events are specified");
+        }
+        if flags.contains(InotifyWatchFlags::IN_MASK_ADD | InotifyWatchFlags::IN_MASK_CREATE) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "IN_MASK_ADD and IN_MASK_CREATE cannot be specified together"
+            );
+        }
+        if flags.contains(InotifyWatchFlags::IN_ONLYDIR) && dentry.type_() != InodeType::Dir {
+            return_errno_with_message!(Errno::ENOTDIR, "the watched file is not a directory");
+        }
+
+        let mut watches = self.watches.lock();
+        Self::remove_ignored_watches(&mut watches);
+
+        let existing_watch = watches
+            .table
+            .values()
+            .find(|watch| Arc::ptr_eq(&watch.dentry, dentry));
+        if let Some(watch) = existing_watch {
+            if flags.contains(InotifyWatchFlags::IN_MASK_CREATE) {
+                return_errno_with_message!(Errno::EEXIST, "the file is already watched");
+            }
+            let mut mask = WatchMask::new(events, flags);
+            if flags.contains(InotifyWatchFlags::IN_MASK_ADD) {
+                mask.events |= watch.mask().events;
+            }
+            watch.set_mask(mask);
+            return Ok(watch.wd);
+        }
+
+        let wd = watches.next_wd;
+        watches.next_wd = watches.next_wd.checked_add(1).ok_or_else(|| {
+            Error::with_message(Errno::ENOSPC, "no more watch descriptors are available")
+        })?;
+        let watch = Arc::new(InotifyWatch {
+            wd,
+            dentry: dentry.clone(),
+            mask: AtomicU32::new(WatchMask::new(events, flags).as_u32()),
+            is_ignored: AtomicBool::new(false),
+            inotify: self.weak_self.clone(),
+        });
+        dentry.register_fsnotify_observer(Arc::downgrade(&watch) as _);
+        watches.table.insert(wd, watch);
+
+        Ok(wd)
+    }
+
+    /// Removes the watch with the watch descriptor `wd`.
+    pub fn remove_watch(&self, wd: i32) -> Result<()> {
+        let mut watches = self.watches.lock();
+        Self::remove_ignored_watches(&mut watches);
+
+        let Some(watch) = watches.table.remove(&wd) else {
+            return_errno_with_message!(Errno::EINVAL, "the watch descriptor is invalid");
+        };
+        watch
+            .dentry
+            .unregister_fsnotify_observer(&(Arc::downgrade(&watch) as _));
+        if !watch.is_ignored.swap(true, Ordering::Relaxed) {
+            self.push_event(InotifyEvent::new_ignored(wd));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the watches that are ignored because of `IN_ONESHOT` or the deletion of the
+    /// watched file.
+    ///
+    /// Such watches cannot be removed when reporting events, since the observers of the dentry
+    /// are locked at that time.
+    fn remove_ignored_watches(watches: &mut Watches) {
+        watches.table.retain(|_, watch| {
+            if !watch.is_ignored.load(Ordering::Relaxed) {
+                return true;
+            }
+            watch
+                .dentry
+                .unregister_fsnotify_observer(&(Arc::downgrade(watch) as _));
+            false
+        });
+    }
+
+    fn push_event(&self, event: InotifyEvent) {
+        let mut events = self.events.lock();
+
+        // Identical successive events that have not been read are coalesced into one.
+        if events.back() == Some(&event) {
+            return;
+        }
+        if events.len() >= MAX_QUEUED_EVENTS {
+            let overflow_event = InotifyEvent::new_overflow();
+            if events.back() != Some(&overflow_event) {
+                events.push_back(overflow_event);
+            }
+            return;
+        }
+
+        events.push_back(event);
+        self.pollee.add_events(IoEvents::IN);
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn try_read(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut events = self.events.lock();
+        let Some(first_event) = events.front() else {
+            return_errno_with_message!(Errno::EAGAIN, "there are no events");
+        };
+        if buf.len() < first_event.len() {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        let mut read_len = 0;
+        while let Some(event) = events.front() {
+            let event_len = event.len();
+            if buf.len() - read_len < event_len {
+                break;
+            }
+            event.write_to(&mut buf[read_len..read_len + event_len]);
+            read_len += event_len;
+            events.pop_front();
+        }
+
+        if events.is_empty() {
+            self.pollee.del_events(IoEvents::IN);
+        }
+        Ok(read_len)
+    }
+
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+}
+
+impl FileLike for InotifyFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        if self.is_nonblocking() {
+            self.try_read(buf)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_read(buf))
+        }
+    }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
+        match cmd {
+            IoctlCmd::FIONREAD => {
+                let len: usize = self.events.lock().iter().map(InotifyEvent::len).sum();
+                write_val_to_user(arg, &(len as i32))?;
+                Ok(0)
+            }
+            _ => return_errno_with_message!(Errno::EINVAL, "the ioctl command is not supported"),
+        }
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Result<Weak<dyn Observer<IoEvents>>> {
+        self.pollee
+            .unregister_observer(observer)
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "the observer is not registered"))
+    }
+}
+
+/// A watch on a dentry, which reports the interesting filesystem events to the inotify file.
+struct InotifyWatch {
+    wd: i32,
+    dentry: Arc<Dentry>,
+    mask: AtomicU32,
+    /// Whether the watch has been removed and `IN_IGNORED` has been reported.
+    is_ignored: AtomicBool,
+    inotify: Weak<InotifyFile>,
+}
+
+impl InotifyWatch {
+    fn mask(&self) -> WatchMask {
+        WatchMask::from_u32(self.mask.load(Ordering::Relaxed))
+    }
+
+    fn set_mask(&self, mask: WatchMask) {
+        self.mask.store(mask.as_u32(), Ordering::Relaxed);
+    }
+}
+
+impl Observer<FsEvent> for InotifyWatch {
+    fn on_events(&self, event: &FsEvent) {
+        if self.is_ignored.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(inotify) = self.inotify.upgrade() else {
+            return;
+        };
+
+        let mask = self.mask();
+        let reported_events = event.events() & mask.events;
+        if !reported_events.is_empty() {
+            inotify.push_event(InotifyEvent {
+                wd: self.wd,
+                mask: (reported_events | (event.events() & FsEvents::ISDIR)).bits(),
+                cookie: event.cookie(),
+                name: event.name().copied(),
+            });
+        }
+
+        let is_oneshot_done =
+            !reported_events.is_empty() && mask.flags.contains(InotifyWatchFlags::IN_ONESHOT);
+        let is_deleted = event.events().contains(FsEvents::DELETE_SELF);
+        if (is_oneshot_done || is_deleted) && !self.is_ignored.swap(true, Ordering::Relaxed) {
+            inotify.push_event(InotifyEvent::new_ignored(self.wd));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WatchMask {
+    events: FsEvents,
+    flags: InotifyWatchFlags,
+}
+
+impl WatchMask {
+    fn new(events: FsEvents, flags: InotifyWatchFlags) -> Self {
+        // Only `IN_ONESHOT` and `IN_EXCL_UNLINK` affect the watch after it is added.
+        let flags = flags & (InotifyWatchFlags::IN_ONESHOT | InotifyWatchFlags::IN_EXCL_UNLINK);
+        Self { events, flags }
+    }
+
+    fn from_u32(mask: u32) -> Self {
+        Self {
+            events: FsEvents::from_bits_truncate(mask),
+            flags: InotifyWatchFlags::from_bits_truncate(mask),
+        }
+    }
+
+    fn as_u32(&self) -> u32 {
+        self.events.bits() | self.flags.bits()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InotifyEvent {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    name: Option<CStr256>,
+}
+
+impl InotifyEvent {
+    fn new_ignored(wd: i32) -> Self {
+        Self {
+            wd,
+            mask: IN_IGNORED,
+            cookie: 0,
+            name: None,
+        }
+    }
+
+    fn new_overflow() -> Self {
+        Self {
+            wd: -1,
+            mask: IN_Q_OVERFLOW,
+            cookie: 0,
+            name: None,
+        }
+    }
+
+    /// Returns the length of the name field, which is padded with null bytes.
+    fn name_len(&self) -> usize {
+        const ALIGN: usize = core::mem::size_of::<inotify_event>();
+        self.name
+            .as_ref()
+            .map_or(0, |name| (name.len() + 1).next_multiple_of(ALIGN))
+    }
+
+    fn len(&self) -> usize {
+        core::mem::size_of::<inotify_event>() + self.name_len()
+    }
+
+    fn write_to(&self, buf: &mut [u8]) {
+        const HEADER_LEN: usize = core::mem::size_of::<inotify_event>();
+        let header = inotify_event {
+            wd: self.wd,
+            mask: self.mask,
+            cookie: self.cookie,
+            len: self.name_len() as u32,
+        };
+        buf[..HEADER_LEN].copy_from_slice(header.as_bytes());
+
+        let name_buf = &mut buf[HEADER_LEN..];
+        name_buf.fill(0);
+        if let Some(name) = self.name.as_ref() {
+            name_buf[..name.len()].copy_from_slice(name.as_bytes());
+        }
+    }
+}
+
+/// The header of an event read from an inotify file, which is followed by the name.
+///
+/// See <https://man7.org/linux/man-pages/man7/inotify.7.html>.
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct inotify_event {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    len: u32,
+}
+
+bitflags! {
+    pub struct InotifyFlags: u32 {
+        const IN_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+        const IN_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+    }
+}
+
+bitflags! {
+    /// The flags that can be specified along with the events when adding a watch.
+    pub struct InotifyWatchFlags: u32 {
+        const IN_ONLYDIR = 0x0100_0000;
+        const IN_DONT_FOLLOW = 0x0200_0000;
+        /// Events on unlinked children are always reported for now, so this flag is accepted
+        /// but has no effect.
+        const IN_EXCL_UNLINK = 0x0400_0000;
+        const IN_MASK_CREATE = 0x1000_0000;
+        const IN_MASK_ADD = 0x2000_0000;
+        const IN_ONESHOT = 0x8000_0000;
+    }
+}
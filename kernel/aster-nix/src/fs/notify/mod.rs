@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The filesystem notification (i.e., fsnotify) layer.
+//!
+//! Each dentry owns a subject of [`FsEvent`]s, which is notified when the dentry itself or one
+//! of its children changes. Notification mechanisms exposed to the user space (e.g., inotify)
+//! are built as observers of the subject.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::utils::CStr256;
+use crate::events::Events;
+
+pub mod inotify;
+
+/// A filesystem event.
+#[derive(Debug, Clone, Copy)]
+pub struct FsEvent {
+    events: FsEvents,
+    cookie: u32,
+    /// The name of the child that triggers the event, or `None` if the event is triggered by
+    /// the dentry itself.
+    name: Option<CStr256>,
+}
+
+impl FsEvent {
+    pub fn new(events: FsEvents, cookie: u32, name: Option<CStr256>) -> Self {
+        Self {
+            events,
+            cookie,
+            name,
+        }
+    }
+
+    pub fn events(&self) -> FsEvents {
+        self.events
+    }
+
+    /// Returns the cookie that associates the `IN_MOVED_FROM` and `IN_MOVED_TO` events
+    /// triggered by the same rename, or zero otherwise.
+    pub fn cookie(&self) -> u32 {
+        self.cookie
+    }
+
+    pub fn name(&self) -> Option<&CStr256> {
+        self.name.as_ref()
+    }
+}
+
+impl Events for FsEvent {}
+
+bitflags! {
+    /// The types of filesystem events.
+    ///
+    /// The values are the same as those of inotify, so they can be reported as is.
+    pub struct FsEvents: u32 {
+        const ACCESS = 0x0000_0001;
+        const MODIFY = 0x0000_0002;
+        const ATTRIB = 0x0000_0004;
+        const CLOSE_WRITE = 0x0000_0008;
+        const CLOSE_NOWRITE = 0x0000_0010;
+        const OPEN = 0x0000_0020;
+        const MOVED_FROM = 0x0000_0040;
+        const MOVED_TO = 0x0000_0080;
+        const CREATE = 0x0000_0100;
+        const DELETE = 0x0000_0200;
+        const DELETE_SELF = 0x0000_0400;
+        const MOVE_SELF = 0x0000_0800;
+        /// The subject of the event is a directory.
+        const ISDIR = 0x4000_0000;
+    }
+}
+
+impl FsEvents {
+    /// The events that are also reported to the parent directory.
+    pub const CHILD_EVENTS: Self = Self::from_bits_truncate(
+        Self::ACCESS.bits()
+            | Self::MODIFY.bits()
+            | Self::ATTRIB.bits()
+            | Self::CLOSE_WRITE.bits()
+            | Self::CLOSE_NOWRITE.bits()
+            | Self::OPEN.bits(),
+    );
+}
+
+/// Allocates a new cookie to associate the events triggered by a rename.
+pub fn alloc_rename_cookie() -> u32 {
+    static NEXT_COOKIE: AtomicU32 = AtomicU32::new(1);
+
+    loop {
+        let cookie = NEXT_COOKIE.fetch_add(1, Ordering::Relaxed);
+        // Zero means that there is no cookie.
+        if cookie != 0 {
+            return cookie;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::fmt::Write;
+
+use aster_frame::cpu::num_cpus;
+
+use super::*;
+
+/// Represents the inode at `/proc/cpuinfo`.
+pub struct CpuInfoFileOps;
+
+impl CpuInfoFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for CpuInfoFileOps {
+    #[cfg(target_arch = "x86_64")]
+    fn data(&self) -> Result<Vec<u8>> {
+        use aster_frame::{arch::tsc_freq, cpu::cpu_info};
+
+        // All the CPUs are assumed to be the same as the current one.
+        let cpu_info = cpu_info();
+        let tsc_khz = tsc_freq() / 1000;
+        let has_fpu = cpu_info.features.contains(&"fpu");
+        let num_cpus = num_cpus();
+
+        let mut cpuinfo_output = String::new();
+        for cpu_id in 0..num_cpus {
+            writeln!(cpuinfo_output, "processor\t: {}", cpu_id).unwrap();
+            writeln!(cpuinfo_output, "vendor_id\t: {}", cpu_info.vendor_id).unwrap();
+            writeln!(cpuinfo_output, "cpu family\t: {}", cpu_info.family).unwrap();
+            writeln!(cpuinfo_output, "model\t\t: {}", cpu_info.model).unwrap();
+            writeln!(cpuinfo_output, "model name\t: {}", cpu_info.model_name).unwrap();
+            writeln!(cpuinfo_output, "stepping\t: {}", cpu_info.stepping).unwrap();
+            writeln!(
+                cpuinfo_output,
+                "cpu MHz\t\t: {}.{:03}",
+                tsc_khz / 1000,
+                tsc_khz % 1000
+            )
+            .unwrap();
+            writeln!(cpuinfo_output, "physical id\t: 0").unwrap();
+            writeln!(cpuinfo_output, "siblings\t: {}", num_cpus).unwrap();
+            writeln!(cpuinfo_output, "core id\t\t: {}", cpu_id).unwrap();
+            writeln!(cpuinfo_output, "cpu cores\t: {}", num_cpus).unwrap();
+            writeln!(
+                cpuinfo_output,
+                "fpu\t\t: {}",
+                if has_fpu { "yes" } else { "no" }
+            )
+            .unwrap();
+            writeln!(cpuinfo_output, "flags\t\t: {}", cpu_info.features.join(" ")).unwrap();
+            writeln!(
+                cpuinfo_output,
+                "address sizes\t: {} bits physical, {} bits virtual",
+                cpu_info.phys_addr_bits, cpu_info.virt_addr_bits
+            )
+            .unwrap();
+            cpuinfo_output.push('\n');
+        }
+        Ok(cpuinfo_output.into_bytes())
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut cpuinfo_output = String::new();
+        for cpu_id in 0..num_cpus() {
+            writeln!(cpuinfo_output, "processor\t: {}", cpu_id).unwrap();
+            writeln!(cpuinfo_output, "hart\t\t: {}", cpu_id).unwrap();
+            cpuinfo_output.push('\n');
+        }
+        Ok(cpuinfo_output.into_bytes())
+    }
+}
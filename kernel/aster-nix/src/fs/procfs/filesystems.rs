@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::fmt::Write;
+
+use super::*;
+
+/// Represents the inode at `/proc/filesystems`.
+pub struct FileSystemsFileOps;
+
+impl FileSystemsFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for FileSystemsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut filesystems_output = String::new();
+        for (name, needs_device) in FILE_SYSTEMS {
+            let nodev = if needs_device { "" } else { "nodev" };
+            writeln!(filesystems_output, "{}\t{}", nodev, name).unwrap();
+        }
+        Ok(filesystems_output.into_bytes())
+    }
+}
+
+/// The names of the supported file system types, and whether they need block devices.
+const FILE_SYSTEMS: [(&str, bool); 6] = [
+    ("ramfs", false),
+    ("proc", false),
+    ("sysfs", false),
+    ("devpts", false),
+    ("ext2", true),
+    ("exfat", true),
+];
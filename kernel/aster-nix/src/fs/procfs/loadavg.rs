@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use super::*;
+use crate::{
+    sched::{
+        nr_running,
+        stats::{load_avg, FIXED_1, FSHIFT},
+    },
+    thread::{nr_allocated_tids, thread_table},
+};
+
+/// Represents the inode at `/proc/loadavg`.
+pub struct LoadAvgFileOps;
+
+impl LoadAvgFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for LoadAvgFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // The load averages are rounded to two decimal places, as Linux does.
+        let [load_1, load_5, load_15] = load_avg().map(|load| load + FIXED_1 / 200);
+        let load_int = |load: u64| load >> FSHIFT;
+        let load_frac = |load: u64| ((load & (FIXED_1 - 1)) * 100) >> FSHIFT;
+        // The most recently allocated PID.
+        let last_pid = nr_allocated_tids().saturating_sub(1);
+
+        let loadavg_output = format!(
+            "{}.{:02} {}.{:02} {}.{:02} {}/{} {}\n",
+            load_int(load_1),
+            load_frac(load_1),
+            load_int(load_5),
+            load_frac(load_5),
+            load_int(load_15),
+            load_frac(load_15),
+            nr_running(),
+            thread_table::nr_threads(),
+            last_pid
+        );
+        Ok(loadavg_output.into_bytes())
+    }
+}
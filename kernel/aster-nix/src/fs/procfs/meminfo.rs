@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_frame::vm::{free_frames, total_frames};
+
+use super::*;
+use crate::{fs::utils::nr_cached_pages, vm::swap::swap_pages};
+
+/// Represents the inode at `/proc/meminfo`.
+pub struct MemInfoFileOps;
+
+impl MemInfoFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for MemInfoFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let total_kb = total_frames() * PAGE_SIZE / 1024;
+        let free_kb = free_frames() * PAGE_SIZE / 1024;
+        let cached_kb = nr_cached_pages() * PAGE_SIZE / 1024;
+        let (swap_pages, free_swap_pages) = swap_pages();
+        // Only the page caches are accounted as reclaimable, since they can be evicted without
+        // swap devices. The pages swapped in are not kept in the swap space.
+        let fields = [
+            ("MemTotal", total_kb),
+            ("MemFree", free_kb),
+            ("MemAvailable", free_kb + cached_kb),
+            ("Buffers", 0),
+            ("Cached", cached_kb),
+            ("SwapCached", 0),
+            ("SwapTotal", swap_pages * PAGE_SIZE / 1024),
+            ("SwapFree", free_swap_pages * PAGE_SIZE / 1024),
+            ("Shmem", 0),
+            ("SReclaimable", 0),
+        ];
+
+        let mut meminfo_output = String::new();
+        for (name, kb) in fields {
+            meminfo_output.push_str(&format!("{:<16}{:>8} kB\n", format!("{}:", name), kb));
+        }
+        Ok(meminfo_output.into_bytes())
+    }
+}
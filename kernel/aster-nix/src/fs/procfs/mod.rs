@@ -3,9 +3,17 @@
 use core::sync::atomic::{AtomicUsize, Ordering};
 
 use self::{
+    cpuinfo::CpuInfoFileOps,
+    filesystems::FileSystemsFileOps,
+    loadavg::LoadAvgFileOps,
+    meminfo::MemInfoFileOps,
+    mounts::MountsSymOps,
     pid::PidDirOps,
     self_::SelfSymOps,
-    template::{DirOps, ProcDir, ProcDirBuilder, ProcSymBuilder, SymOps},
+    stat::StatFileOps,
+    swaps::SwapsFileOps,
+    template::{DirOps, FileOps, ProcDir, ProcDirBuilder, ProcFileBuilder, ProcSymBuilder, SymOps},
+    uptime::UptimeFileOps,
 };
 use crate::{
     events::Observer,
@@ -14,9 +22,17 @@ use crate::{
     process::{process_table, process_table::PidEvent, Pid},
 };
 
+mod cpuinfo;
+mod filesystems;
+mod loadavg;
+mod meminfo;
+mod mounts;
 mod pid;
 mod self_;
-mod template;
+mod stat;
+mod swaps;
+pub(super) mod template;
+mod uptime;
 
 /// Magic number.
 const PROC_MAGIC: u64 = 0x9fa0;
@@ -68,6 +84,10 @@ impl FileSystem for ProcFS {
     fn flags(&self) -> FsFlags {
         FsFlags::empty()
     }
+
+    fn name(&self) -> &'static str {
+        "proc"
+    }
 }
 
 /// Represents the inode at `/proc`.
@@ -92,14 +112,24 @@ impl Observer<PidEvent> for ProcDir<RootDirOps> {
 
 impl DirOps for RootDirOps {
     fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
-        let child = if name == "self" {
-            SelfSymOps::new_inode(this_ptr.clone())
-        } else if let Ok(pid) = name.parse::<Pid>() {
-            let process_ref =
-                process_table::get_process(&pid).ok_or_else(|| Error::new(Errno::ENOENT))?;
-            PidDirOps::new_inode(process_ref, this_ptr.clone())
-        } else {
-            return_errno!(Errno::ENOENT);
+        let child = match name {
+            "self" => SelfSymOps::new_inode(this_ptr.clone()),
+            "meminfo" => MemInfoFileOps::new_inode(this_ptr.clone()),
+            "cpuinfo" => CpuInfoFileOps::new_inode(this_ptr.clone()),
+            "uptime" => UptimeFileOps::new_inode(this_ptr.clone()),
+            "loadavg" => LoadAvgFileOps::new_inode(this_ptr.clone()),
+            "stat" => StatFileOps::new_inode(this_ptr.clone()),
+            "mounts" => MountsSymOps::new_inode(this_ptr.clone()),
+            "filesystems" => FileSystemsFileOps::new_inode(this_ptr.clone()),
+            "swaps" => SwapsFileOps::new_inode(this_ptr.clone()),
+            _ => {
+                let Ok(pid) = name.parse::<Pid>() else {
+                    return_errno!(Errno::ENOENT);
+                };
+                let process_ref =
+                    process_table::get_process(&pid).ok_or_else(|| Error::new(Errno::ENOENT))?;
+                PidDirOps::new_inode(process_ref, this_ptr.clone())
+            }
         };
         Ok(child)
     }
@@ -111,6 +141,22 @@ impl DirOps for RootDirOps {
         };
         let mut cached_children = this.cached_children().write();
         cached_children.put_entry_if_not_found("self", || SelfSymOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("meminfo", || MemInfoFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("cpuinfo", || CpuInfoFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("uptime", || UptimeFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("loadavg", || LoadAvgFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("stat", || StatFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("mounts", || MountsSymOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("filesystems", || {
+            FileSystemsFileOps::new_inode(this_ptr.clone())
+        });
+        cached_children
+            .put_entry_if_not_found("swaps", || SwapsFileOps::new_inode(this_ptr.clone()));
 
         for process in process_table::process_table().iter() {
             let pid = process.pid().to_string();
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::*;
+
+/// Represents the inode at `/proc/mounts`, which links to the mounts of the current process.
+pub struct MountsSymOps;
+
+impl MountsSymOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcSymBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl SymOps for MountsSymOps {
+    fn read_link(&self) -> Result<String> {
+        Ok(String::from("self/mounts"))
+    }
+}
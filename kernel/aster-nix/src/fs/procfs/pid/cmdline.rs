@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::ops::Range;
+
+use aster_frame::vm::VmIo;
+
+use super::*;
+
+/// Represents the inode at `/proc/[pid]/cmdline`.
+pub struct CmdlineFileOps(Arc<Process>);
+
+impl CmdlineFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for CmdlineFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let argv = self.0.vm().init_stack_layout().argv;
+        Ok(read_process_memory(&self.0, argv))
+    }
+}
+
+/// Reads the memory of the process in the range.
+///
+/// An empty vector is returned if the memory cannot be read, e.g., the process is a zombie.
+pub(super) fn read_process_memory(process: &Process, range: Range<Vaddr>) -> Vec<u8> {
+    let mut buf = vec![0u8; range.len()];
+    if process
+        .root_vmar()
+        .read_bytes(range.start, &mut buf)
+        .is_err()
+    {
+        return Vec::new();
+    }
+    buf
+}
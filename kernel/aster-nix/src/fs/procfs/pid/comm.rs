@@ -29,4 +29,12 @@ impl FileOps for CommFileOps {
     }
 }
 
-const TASK_COMM_LEN: usize = 16;
+pub(super) const TASK_COMM_LEN: usize = 16;
+
+/// Returns the command name of the process, which is shown in `stat` and `status`.
+pub(super) fn task_comm(process: &Process) -> String {
+    let exe_path = process.executable_path();
+    let mut comm = exe_path.rsplit('/').next().unwrap_or(&exe_path).to_string();
+    comm.truncate(TASK_COMM_LEN - 1);
+    comm
+}
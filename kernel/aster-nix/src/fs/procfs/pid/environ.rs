@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{cmdline::read_process_memory, *};
+use crate::process::{credentials, posix_thread::PosixThreadExt};
+
+/// Represents the inode at `/proc/[pid]/environ`.
+pub struct EnvironFileOps(Arc<Process>);
+
+impl EnvironFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for EnvironFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // The environment may contain secrets, so only the owner can read it.
+        // TODO: Check `CAP_SYS_PTRACE` instead once capabilities are supported.
+        let euid = credentials().euid();
+        let is_owner = self.0.main_thread().is_some_and(|thread| {
+            thread
+                .as_posix_thread()
+                .is_some_and(|posix_thread| posix_thread.credentials().euid() == euid)
+        });
+        if !euid.is_root() && !is_owner {
+            return_errno_with_message!(Errno::EACCES, "the environment of the process is private");
+        }
+
+        let envp = self.0.vm().init_stack_layout().envp;
+        Ok(read_process_memory(&self.0, envp))
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::fmt::Write;
+
+use super::*;
+use crate::process::ResourceType;
+
+/// Represents the inode at `/proc/[pid]/limits`.
+pub struct LimitsFileOps(Arc<Process>);
+
+impl LimitsFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for LimitsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let resource_limits = self.0.resource_limits().lock();
+
+        let mut limits_output = format!(
+            "{:<25} {:<20} {:<20} {:<10}\n",
+            "Limit", "Soft Limit", "Hard Limit", "Units"
+        );
+        for (resource, name, unit) in LIMITS {
+            let rlimit = resource_limits.get_rlimit(resource);
+            write!(
+                limits_output,
+                "{:<25} {:<20} {:<20} ",
+                name,
+                limit_value(rlimit.get_cur()),
+                limit_value(rlimit.get_max())
+            )
+            .unwrap();
+            if !unit.is_empty() {
+                write!(limits_output, "{:<10}", unit).unwrap();
+            }
+            limits_output.push('\n');
+        }
+        Ok(limits_output.into_bytes())
+    }
+}
+
+fn limit_value(value: u64) -> String {
+    if value == u64::MAX {
+        String::from("unlimited")
+    } else {
+        value.to_string()
+    }
+}
+
+/// The resources with their names and units, in the order of Linux.
+const LIMITS: [(ResourceType, &str, &str); 16] = [
+    (ResourceType::RLIMIT_CPU, "Max cpu time", "seconds"),
+    (ResourceType::RLIMIT_FSIZE, "Max file size", "bytes"),
+    (ResourceType::RLIMIT_DATA, "Max data size", "bytes"),
+    (ResourceType::RLIMIT_STACK, "Max stack size", "bytes"),
+    (ResourceType::RLIMIT_CORE, "Max core file size", "bytes"),
+    (ResourceType::RLIMIT_RSS, "Max resident set", "bytes"),
+    (ResourceType::RLIMIT_NPROC, "Max processes", "processes"),
+    (ResourceType::RLIMIT_NOFILE, "Max open files", "files"),
+    (ResourceType::RLIMIT_MEMLOCK, "Max locked memory", "bytes"),
+    (ResourceType::RLIMIT_AS, "Max address space", "bytes"),
+    (ResourceType::RLIMIT_LOCKS, "Max file locks", "locks"),
+    (
+        ResourceType::RLIMIT_SIGPENDING,
+        "Max pending signals",
+        "signals",
+    ),
+    (ResourceType::RLIMIT_MSGQUEUE, "Max msgqueue size", "bytes"),
+    (ResourceType::RLIMIT_NICE, "Max nice priority", ""),
+    (ResourceType::RLIMIT_RTPRIO, "Max realtime priority", ""),
+    (ResourceType::RLIMIT_RTTIME, "Max realtime timeout", "us"),
+];
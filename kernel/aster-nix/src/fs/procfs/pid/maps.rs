@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::{fmt::Write, ops::Range};
+
+use align_ext::AlignExt;
+use aster_frame::vm::VmPerm;
+
+use super::*;
+use crate::{
+    fs::device::DeviceId,
+    vm::vmar::vm_mapping::{VmMapping, VmMappingFlags},
+};
+
+/// Represents the inode at `/proc/[pid]/maps`.
+pub struct MapsFileOps(Arc<Process>);
+
+impl MapsFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for MapsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut maps_output = String::new();
+        for region in vm_regions(&self.0)? {
+            let perm = region.vm_mapping.perm();
+            let (offset, dev, ino) = match region.vm_mapping.file() {
+                Some((dentry, offset)) => {
+                    let metadata = dentry.inode().metadata();
+                    (offset, DeviceId::from(metadata.dev), metadata.ino)
+                }
+                None => (0, DeviceId::new(0, 0), 0),
+            };
+
+            let mut line = format!(
+                "{:08x}-{:08x} {}{}{}{} {:08x} {:02x}:{:02x} {} ",
+                region.range.start,
+                region.range.end,
+                if perm.contains(VmPerm::R) { 'r' } else { '-' },
+                if perm.contains(VmPerm::W) { 'w' } else { '-' },
+                if perm.contains(VmPerm::X) { 'x' } else { '-' },
+                if region.vm_mapping.is_shared() {
+                    's'
+                } else {
+                    'p'
+                },
+                offset,
+                dev.major(),
+                dev.minor(),
+                ino,
+            );
+            if let Some(name) = region.name {
+                // The names are aligned in a column, as Linux does.
+                while line.len() < MAPS_NAME_COLUMN {
+                    line.push(' ');
+                }
+                line.push_str(&name);
+            }
+            writeln!(maps_output, "{}", line).unwrap();
+        }
+        Ok(maps_output.into_bytes())
+    }
+}
+
+/// The column where the names of the regions start.
+const MAPS_NAME_COLUMN: usize = 73;
+
+/// A region in the address space of a process, as shown in `/proc/[pid]/maps`.
+pub(super) struct VmRegion {
+    pub range: Range<Vaddr>,
+    pub vm_mapping: Arc<VmMapping>,
+    /// The path of the mapped file, or a pseudo name like `[heap]`.
+    pub name: Option<String>,
+    pub is_stack: bool,
+}
+
+/// Returns the regions in the address space of the process, sorted by the address.
+pub(super) fn vm_regions(process: &Process) -> Result<Vec<VmRegion>> {
+    let stack_range = process.vm().init_stack_layout().stack;
+    let heap_range = process.user_heap().heap_range();
+
+    let mut regions = Vec::new();
+    for vm_mapping in process.root_vmar().vm_mappings()? {
+        let mut range = vm_mapping.range();
+        let is_stack = range.contains(&stack_range.start);
+        let name = if let Some((dentry, _)) = vm_mapping.file() {
+            Some(dentry.abs_path())
+        } else if is_stack {
+            Some(String::from("[stack]"))
+        } else if range.start == heap_range.start {
+            // The mapping of the heap reserves the maximum size, but only the part below the
+            // program break is in use.
+            range.end = heap_range.end.align_up(PAGE_SIZE);
+            if range.is_empty() {
+                continue;
+            }
+            Some(String::from("[heap]"))
+        } else {
+            None
+        };
+
+        regions.push(VmRegion {
+            range,
+            vm_mapping,
+            name,
+            is_stack,
+        });
+    }
+    Ok(regions)
+}
+
+/// The memory usage of a process, in bytes.
+pub(super) struct VmUsage {
+    /// The size of the address space.
+    pub size: usize,
+    /// The size of the locked regions.
+    pub locked: usize,
+    /// The size of the pages that are mapped to the memory.
+    pub rss: usize,
+    /// The size of the private writable regions except the stack.
+    pub data: usize,
+    /// The size of the stack.
+    pub stack: usize,
+}
+
+impl VmUsage {
+    pub fn of(regions: &[VmRegion]) -> Self {
+        let mut usage = Self {
+            size: 0,
+            locked: 0,
+            rss: 0,
+            data: 0,
+            stack: 0,
+        };
+        for region in regions {
+            let size = region.range.len();
+            usage.size += size;
+            if region.vm_mapping.flags().contains(VmMappingFlags::LOCKED) {
+                usage.locked += size;
+            }
+            usage.rss += region.vm_mapping.nr_mapped_pages() * PAGE_SIZE;
+            if region.is_stack {
+                usage.stack += size;
+            } else if region.vm_mapping.perm().contains(VmPerm::W) && !region.vm_mapping.is_shared()
+            {
+                usage.data += size;
+            }
+        }
+        usage
+    }
+}
@@ -1,6 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use self::{comm::CommFileOps, exe::ExeSymOps, fd::FdDirOps};
+use self::{
+    cmdline::CmdlineFileOps, comm::CommFileOps, cwd::CwdSymOps, environ::EnvironFileOps,
+    exe::ExeSymOps, fd::FdDirOps, limits::LimitsFileOps, maps::MapsFileOps, mounts::MountsFileOps,
+    oom_score::OomScoreFileOps, oom_score_adj::OomScoreAdjFileOps, root::RootSymOps,
+    stat::StatFileOps, status::StatusFileOps,
+};
 use super::template::{
     DirOps, FileOps, ProcDir, ProcDirBuilder, ProcFileBuilder, ProcSymBuilder, SymOps,
 };
@@ -14,9 +19,20 @@ use crate::{
     process::Process,
 };
 
+mod cmdline;
 mod comm;
+mod cwd;
+mod environ;
 mod exe;
 mod fd;
+mod limits;
+mod maps;
+mod mounts;
+mod oom_score;
+mod oom_score_adj;
+mod root;
+mod stat;
+mod status;
 
 /// Represents the inode at `/proc/[pid]`.
 pub struct PidDirOps(Arc<Process>);
@@ -51,6 +67,17 @@ impl DirOps for PidDirOps {
             "exe" => ExeSymOps::new_inode(self.0.clone(), this_ptr.clone()),
             "comm" => CommFileOps::new_inode(self.0.clone(), this_ptr.clone()),
             "fd" => FdDirOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "stat" => StatFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "status" => StatusFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "maps" => MapsFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "cmdline" => CmdlineFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "environ" => EnvironFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "cwd" => CwdSymOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "root" => RootSymOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "mounts" => MountsFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "limits" => LimitsFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "oom_score" => OomScoreFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "oom_score_adj" => OomScoreAdjFileOps::new_inode(self.0.clone(), this_ptr.clone()),
             _ => return_errno!(Errno::ENOENT),
         };
         Ok(inode)
@@ -70,6 +97,39 @@ impl DirOps for PidDirOps {
         });
         cached_children.put_entry_if_not_found("fd", || {
             FdDirOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("stat", || {
+            StatFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("status", || {
+            StatusFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("maps", || {
+            MapsFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("cmdline", || {
+            CmdlineFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("environ", || {
+            EnvironFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("cwd", || {
+            CwdSymOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("root", || {
+            RootSymOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("mounts", || {
+            MountsFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("limits", || {
+            LimitsFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("oom_score", || {
+            OomScoreFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("oom_score_adj", || {
+            OomScoreAdjFileOps::new_inode(self.0.clone(), this_ptr.clone())
         })
     }
 }
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use super::*;
+use crate::fs::utils::MountNode;
+
+/// Represents the inode at `/proc/[pid]/mounts`.
+pub struct MountsFileOps(Arc<Process>);
+
+impl MountsFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for MountsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let root_mount = self.0.fs().read().root().mount_node();
+        let mut mounts_output = String::new();
+        write_mounts(&root_mount, &mut mounts_output);
+        Ok(mounts_output.into_bytes())
+    }
+}
+
+/// Writes the mount node and its descendants in the pre-order.
+fn write_mounts(mount_node: &Arc<MountNode>, output: &mut String) {
+    let mountpoint = match mount_node.mountpoint_dentry() {
+        Some(dentry) => dentry.abs_path(),
+        None => String::from("/"),
+    };
+    // The file systems are not backed by named devices, so the name of the file system type is
+    // also shown as the device. All the file systems are mounted read-write.
+    let fs_name = mount_node.fs().name();
+    output.push_str(&format!("{} {} {} rw 0 0\n", fs_name, mountpoint, fs_name));
+
+    for child in mount_node.children() {
+        write_mounts(&child, output);
+    }
+}
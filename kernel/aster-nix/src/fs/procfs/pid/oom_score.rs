@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use super::*;
+use crate::vm::oom::oom_score;
+
+/// Represents the inode at `/proc/[pid]/oom_score`.
+pub struct OomScoreFileOps(Arc<Process>);
+
+impl OomScoreFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for OomScoreFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        Ok(format!("{}\n", oom_score(&self.0)).into_bytes())
+    }
+}
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::sync::atomic::Ordering;
+
+use super::*;
+use crate::{
+    process::{credentials, posix_thread::PosixThreadExt},
+    vm::oom::{OOM_SCORE_ADJ_MAX, OOM_SCORE_ADJ_MIN},
+};
+
+/// Represents the inode at `/proc/[pid]/oom_score_adj`.
+pub struct OomScoreAdjFileOps(Arc<Process>);
+
+impl OomScoreAdjFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for OomScoreAdjFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let oom_score_adj = self.0.oom_score_adj().load(Ordering::Relaxed);
+        Ok(format!("{}\n", oom_score_adj).into_bytes())
+    }
+
+    fn is_writable(&self) -> bool {
+        true
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        let new_adj: i16 = core::str::from_utf8(buf)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|value| (OOM_SCORE_ADJ_MIN..=OOM_SCORE_ADJ_MAX).contains(value))
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid oom_score_adj"))?;
+
+        // Only the owner can adjust the score, and only root can make the process less likely
+        // to be killed.
+        // TODO: Check `CAP_SYS_RESOURCE` instead once capabilities are supported.
+        let euid = credentials().euid();
+        let is_owner = self.0.main_thread().is_some_and(|thread| {
+            thread
+                .as_posix_thread()
+                .is_some_and(|posix_thread| posix_thread.credentials().euid() == euid)
+        });
+        let old_adj = self.0.oom_score_adj().load(Ordering::Relaxed);
+        if !euid.is_root() && (!is_owner || new_adj < old_adj) {
+            return_errno_with_message!(Errno::EACCES, "cannot adjust the OOM score");
+        }
+
+        self.0.oom_score_adj().store(new_adj, Ordering::Relaxed);
+        Ok(buf.len())
+    }
+}
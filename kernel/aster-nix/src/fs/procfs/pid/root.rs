@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::*;
+
+/// Represents the inode at `/proc/[pid]/root`.
+pub struct RootSymOps(Arc<Process>);
+
+impl RootSymOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcSymBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl SymOps for RootSymOps {
+    fn read_link(&self) -> Result<String> {
+        Ok(self.0.fs().read().root().abs_path())
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::sync::atomic::Ordering;
+
+use super::{
+    comm::task_comm,
+    maps::{vm_regions, VmUsage},
+    *,
+};
+use crate::{
+    process::{posix_thread::PosixThreadExt, signal::sig_mask::SigMask, ResourceType},
+    time::to_clock_ticks,
+};
+
+/// Represents the inode at `/proc/[pid]/stat`.
+pub struct StatFileOps(Arc<Process>);
+
+impl StatFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for StatFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let process = &self.0;
+
+        let ppid = process.parent().map_or(0, |parent| parent.pid());
+        let sid = process.session().map_or(0, |session| session.sid());
+        let nice = process.nice().load(Ordering::Relaxed).to_raw();
+        let num_threads = process.threads().lock().len();
+        let cpu_clock = process.cpu_clock();
+        let children_cpu_clock = process.children_cpu_clock();
+        let vm_usage = VmUsage::of(&vm_regions(process)?);
+        let rss_limit = process
+            .resource_limits()
+            .lock()
+            .get_rlimit(ResourceType::RLIMIT_RSS)
+            .get_cur();
+        let init_stack_layout = process.vm().init_stack_layout();
+        let heap_base = process.user_heap().heap_range().start;
+        let signals = SignalMasks::of(process);
+
+        let stat_output = format!(
+            "{} ({}) {} {} {} {} 0 -1 0 0 0 0 0 {} {} {} {} {} {} {} 0 0 {} {} {} 0 0 {} 0 0 {} {} {} {} 0 0 0 17 0 0 0 0 0 0 0 0 {} {} {} {} {} {}\n",
+            process.pid(),
+            task_comm(process),
+            task_state(process),
+            ppid,
+            process.pgid(),
+            sid,
+            to_clock_ticks(cpu_clock.user_time()),
+            to_clock_ticks(cpu_clock.system_time()),
+            to_clock_ticks(children_cpu_clock.user_time()),
+            to_clock_ticks(children_cpu_clock.system_time()),
+            20 + nice as i32,
+            nice,
+            num_threads,
+            vm_usage.size,
+            vm_usage.rss / PAGE_SIZE,
+            rss_limit,
+            init_stack_layout.stack.end,
+            signals.pending.as_u64(),
+            signals.blocked.as_u64(),
+            signals.ignored.as_u64(),
+            signals.caught.as_u64(),
+            heap_base,
+            init_stack_layout.argv.start,
+            init_stack_layout.argv.end,
+            init_stack_layout.envp.start,
+            init_stack_layout.envp.end,
+            process.exit_code().unwrap_or(0),
+        );
+
+        Ok(stat_output.into_bytes())
+    }
+}
+
+/// Returns the state of the process, e.g., `R` for running.
+pub(super) fn task_state(process: &Process) -> char {
+    if process.is_zombie() {
+        'Z'
+    } else if process
+        .main_thread()
+        .is_some_and(|thread| thread.status().lock().is_stopped())
+    {
+        'T'
+    } else {
+        'R'
+    }
+}
+
+/// The signal masks of a process, which are shown in `stat` and `status`.
+pub(super) struct SignalMasks {
+    pub pending: SigMask,
+    pub blocked: SigMask,
+    pub ignored: SigMask,
+    pub caught: SigMask,
+}
+
+impl SignalMasks {
+    pub fn of(process: &Process) -> Self {
+        // The pending and blocked signals are those of the main thread, as Linux does.
+        let (pending, blocked) = process
+            .main_thread()
+            .and_then(|thread| {
+                let posix_thread = thread.as_posix_thread()?;
+                Some((posix_thread.sig_pending(), *posix_thread.sig_mask().lock()))
+            })
+            .unwrap_or_default();
+        let sig_dispositions = process.sig_dispositions().lock();
+        Self {
+            pending,
+            blocked,
+            ignored: sig_dispositions.ignored_mask(),
+            caught: sig_dispositions.caught_mask(),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::fmt::Write;
+
+use align_ext::AlignExt;
+
+use super::{
+    comm::task_comm,
+    maps::{vm_regions, VmUsage},
+    stat::{task_state, SignalMasks},
+    *,
+};
+use crate::process::posix_thread::PosixThreadExt;
+
+/// Represents the inode at `/proc/[pid]/status`.
+pub struct StatusFileOps(Arc<Process>);
+
+impl StatusFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for StatusFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let process = &self.0;
+
+        let mut status_output = String::new();
+        writeln!(status_output, "Name:\t{}", task_comm(process)).unwrap();
+        writeln!(
+            status_output,
+            "Umask:\t{:04o}",
+            process.umask().read().get()
+        )
+        .unwrap();
+        let state = match task_state(process) {
+            'Z' => "Z (zombie)",
+            'T' => "T (stopped)",
+            _ => "R (running)",
+        };
+        writeln!(status_output, "State:\t{}", state).unwrap();
+        writeln!(status_output, "Tgid:\t{}", process.pid()).unwrap();
+        writeln!(status_output, "Ngid:\t0").unwrap();
+        writeln!(status_output, "Pid:\t{}", process.pid()).unwrap();
+        let ppid = process.parent().map_or(0, |parent| parent.pid());
+        writeln!(status_output, "PPid:\t{}", ppid).unwrap();
+        writeln!(status_output, "TracerPid:\t0").unwrap();
+
+        if let Some(main_thread) = process.main_thread()
+            && let Some(posix_thread) = main_thread.as_posix_thread()
+        {
+            let credentials = posix_thread.credentials();
+            writeln!(
+                status_output,
+                "Uid:\t{}\t{}\t{}\t{}",
+                credentials.ruid().as_u32(),
+                credentials.euid().as_u32(),
+                credentials.suid().as_u32(),
+                credentials.fsuid().as_u32()
+            )
+            .unwrap();
+            writeln!(
+                status_output,
+                "Gid:\t{}\t{}\t{}\t{}",
+                credentials.rgid().as_u32(),
+                credentials.egid().as_u32(),
+                credentials.sgid().as_u32(),
+                credentials.fsgid().as_u32()
+            )
+            .unwrap();
+            status_output.push_str("Groups:\t");
+            for gid in credentials.groups().iter() {
+                write!(status_output, "{} ", gid.as_u32()).unwrap();
+            }
+            status_output.push('\n');
+        }
+
+        // The file descriptor table grows in units of 64 slots, as Linux does.
+        let fd_size = process
+            .file_table()
+            .lock()
+            .fds_and_files()
+            .map(|(fd, _)| fd as usize + 1)
+            .max()
+            .unwrap_or(0)
+            .align_up(64)
+            .max(64);
+        writeln!(status_output, "FDSize:\t{}", fd_size).unwrap();
+
+        let vm_usage = VmUsage::of(&vm_regions(process)?);
+        writeln!(status_output, "VmSize:\t{:8} kB", vm_usage.size / 1024).unwrap();
+        writeln!(status_output, "VmLck:\t{:8} kB", vm_usage.locked / 1024).unwrap();
+        writeln!(status_output, "VmRSS:\t{:8} kB", vm_usage.rss / 1024).unwrap();
+        writeln!(status_output, "VmData:\t{:8} kB", vm_usage.data / 1024).unwrap();
+        writeln!(status_output, "VmStk:\t{:8} kB", vm_usage.stack / 1024).unwrap();
+
+        writeln!(
+            status_output,
+            "Threads:\t{}",
+            process.threads().lock().len()
+        )
+        .unwrap();
+
+        // The signals are always pending on threads, so there are no shared pending signals.
+        let signals = SignalMasks::of(process);
+        writeln!(status_output, "SigPnd:\t{:016x}", signals.pending.as_u64()).unwrap();
+        writeln!(status_output, "ShdPnd:\t{:016x}", 0).unwrap();
+        writeln!(status_output, "SigBlk:\t{:016x}", signals.blocked.as_u64()).unwrap();
+        writeln!(status_output, "SigIgn:\t{:016x}", signals.ignored.as_u64()).unwrap();
+        writeln!(status_output, "SigCgt:\t{:016x}", signals.caught.as_u64()).unwrap();
+
+        Ok(status_output.into_bytes())
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::fmt::Write;
+
+use aster_frame::{cpu::num_cpus, task::nr_context_switches};
+use aster_time::{read_monotonic_time, read_realtime};
+
+use super::*;
+use crate::{
+    sched::{
+        nr_running,
+        stats::{cpu_time, CpuTime},
+    },
+    thread::nr_allocated_tids,
+    time::to_clock_ticks,
+};
+
+/// Represents the inode at `/proc/stat`.
+pub struct StatFileOps;
+
+impl StatFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for StatFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let cpu_times: Vec<CpuTime> = (0..num_cpus()).map(cpu_time).collect();
+        let total_time = cpu_times
+            .iter()
+            .fold(CpuTime::default(), |total, cpu_time| CpuTime {
+                user: total.user + cpu_time.user,
+                system: total.system + cpu_time.system,
+                idle: total.idle + cpu_time.idle,
+            });
+        let boot_time = read_realtime().saturating_sub(read_monotonic_time());
+
+        let mut stat_output = String::new();
+        write_cpu_time(&mut stat_output, "cpu ", &total_time);
+        for (cpu_id, cpu_time) in cpu_times.iter().enumerate() {
+            write_cpu_time(&mut stat_output, &format!("cpu{}", cpu_id), cpu_time);
+        }
+        // The interrupts are not counted yet.
+        writeln!(stat_output, "intr 0").unwrap();
+        writeln!(stat_output, "ctxt {}", nr_context_switches()).unwrap();
+        writeln!(stat_output, "btime {}", boot_time.as_secs()).unwrap();
+        writeln!(stat_output, "processes {}", nr_allocated_tids()).unwrap();
+        writeln!(stat_output, "procs_running {}", nr_running()).unwrap();
+        writeln!(stat_output, "procs_blocked 0").unwrap();
+        Ok(stat_output.into_bytes())
+    }
+}
+
+/// Writes a line of the CPU time in clock ticks.
+///
+/// The columns are `user`, `nice`, `system`, `idle`, `iowait`, `irq`, `softirq`, `steal`,
+/// `guest` and `guest_nice`. Only `user`, `system` and `idle` are accounted.
+fn write_cpu_time(output: &mut String, name: &str, cpu_time: &CpuTime) {
+    writeln!(
+        output,
+        "{} {} 0 {} {} 0 0 0 0 0 0",
+        name,
+        to_clock_ticks(cpu_time.user),
+        to_clock_ticks(cpu_time.system),
+        to_clock_ticks(cpu_time.idle)
+    )
+    .unwrap();
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use super::*;
+use crate::vm::swap::swap_devices;
+
+/// Represents the inode at `/proc/swaps`.
+pub struct SwapsFileOps;
+
+impl SwapsFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for SwapsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // The sizes are in kilobytes, and the columns are aligned in the same way as Linux.
+        let mut swaps_output = String::from("Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n");
+        for device in swap_devices() {
+            let size_kb = device.nr_pages() * PAGE_SIZE / 1024;
+            let used_kb = device.nr_used_pages() * PAGE_SIZE / 1024;
+            swaps_output.push_str(&format!(
+                "{:<40}partition\t{}\t{}{}\t{}{}\n",
+                device.path(),
+                size_kb,
+                if size_kb < 10000000 { "\t" } else { "" },
+                used_kb,
+                if used_kb < 10000000 { "\t" } else { "" },
+                device.priority()
+            ));
+        }
+        Ok(swaps_output.into_bytes())
+    }
+}
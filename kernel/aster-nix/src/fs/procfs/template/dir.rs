@@ -5,7 +5,7 @@ use core::time::Duration;
 use aster_util::slot_vec::SlotVec;
 use inherit_methods_macro::inherit_methods;
 
-use super::{Common, ProcFS};
+use super::{alloc_ino, Common};
 use crate::{
     fs::{
         device::Device,
@@ -31,9 +31,8 @@ impl<D: DirOps> ProcDir<D> {
         is_volatile: bool,
     ) -> Arc<Self> {
         let common = {
-            let procfs = fs.downcast_ref::<ProcFS>().unwrap();
             let metadata = Metadata::new_dir(
-                procfs.alloc_id(),
+                alloc_ino(&fs),
                 InodeMode::from_bits_truncate(0o555),
                 &fs.sb(),
             );
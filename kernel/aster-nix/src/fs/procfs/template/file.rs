@@ -4,7 +4,7 @@ use core::time::Duration;
 
 use inherit_methods_macro::inherit_methods;
 
-use super::{Common, ProcFS};
+use super::{alloc_ino, Common};
 use crate::{
     fs::utils::{FileSystem, Inode, InodeMode, InodeType, IoctlCmd, Metadata},
     prelude::*,
@@ -19,10 +19,10 @@ pub struct ProcFile<F: FileOps> {
 impl<F: FileOps> ProcFile<F> {
     pub fn new(file: F, fs: Arc<dyn FileSystem>, is_volatile: bool) -> Arc<Self> {
         let common = {
-            let procfs = fs.downcast_ref::<ProcFS>().unwrap();
+            let mode = if file.is_writable() { 0o644 } else { 0o444 };
             let metadata = Metadata::new_file(
-                procfs.alloc_id(),
-                InodeMode::from_bits_truncate(0o444),
+                alloc_ino(&fs),
+                InodeMode::from_bits_truncate(mode),
                 &fs.sb(),
             );
             Common::new(metadata, Arc::downgrade(&fs), is_volatile)
@@ -72,12 +72,12 @@ impl<F: FileOps + 'static> Inode for ProcFile<F> {
         self.read_at(offset, buf)
     }
 
-    fn write_at(&self, _offset: usize, _buf: &[u8]) -> Result<usize> {
-        Err(Error::new(Errno::EPERM))
+    fn write_at(&self, _offset: usize, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
     }
 
-    fn write_direct_at(&self, _offset: usize, _buf: &[u8]) -> Result<usize> {
-        Err(Error::new(Errno::EPERM))
+    fn write_direct_at(&self, offset: usize, buf: &[u8]) -> Result<usize> {
+        self.write_at(offset, buf)
     }
 
     fn read_link(&self) -> Result<String> {
@@ -103,4 +103,14 @@ impl<F: FileOps + 'static> Inode for ProcFile<F> {
 
 pub trait FileOps: Sync + Send {
     fn data(&self) -> Result<Vec<u8>>;
+
+    /// Returns whether the file can be written by `write`.
+    fn is_writable(&self) -> bool {
+        false
+    }
+
+    /// Writes the data to the file, which is parsed as a whole regardless of the offset.
+    fn write(&self, _buf: &[u8]) -> Result<usize> {
+        return_errno!(Errno::EPERM);
+    }
 }
@@ -10,7 +10,10 @@ pub use self::{
 };
 use super::ProcFS;
 use crate::{
-    fs::utils::{FileSystem, InodeMode, Metadata},
+    fs::{
+        sysfs::SysFS,
+        utils::{FileSystem, InodeMode, Metadata},
+    },
     prelude::*,
     process::{Gid, Uid},
 };
@@ -20,6 +23,14 @@ mod dir;
 mod file;
 mod sym;
 
+/// Allocates an inode number in the file system, which is either the procfs or the sysfs.
+fn alloc_ino(fs: &Arc<dyn FileSystem>) -> usize {
+    if let Some(procfs) = fs.downcast_ref::<ProcFS>() {
+        return procfs.alloc_id();
+    }
+    fs.downcast_ref::<SysFS>().unwrap().alloc_id()
+}
+
 struct Common {
     metadata: RwLock<Metadata>,
     fs: Weak<dyn FileSystem>,
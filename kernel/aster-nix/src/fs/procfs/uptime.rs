@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::time::Duration;
+
+use aster_frame::cpu::num_cpus;
+use aster_time::read_monotonic_time;
+
+use super::*;
+use crate::sched::stats::cpu_time;
+
+/// Represents the inode at `/proc/uptime`.
+pub struct UptimeFileOps;
+
+impl UptimeFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for UptimeFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let uptime = read_monotonic_time();
+        // The idle time is the sum of the idle time of all the CPUs.
+        let idle_time: Duration = (0..num_cpus()).map(|cpu_id| cpu_time(cpu_id).idle).sum();
+
+        let uptime_output = format!(
+            "{}.{:02} {}.{:02}\n",
+            uptime.as_secs(),
+            uptime.subsec_millis() / 10,
+            idle_time.as_secs(),
+            idle_time.subsec_millis() / 10
+        );
+        Ok(uptime_output.into_bytes())
+    }
+}
@@ -83,6 +83,10 @@ impl FileSystem for RamFS {
     fn flags(&self) -> FsFlags {
         FsFlags::DENTRY_UNEVICTABLE
     }
+
+    fn name(&self) -> &'static str {
+        "ramfs"
+    }
 }
 
 struct RamInode(RwLock<Inode_>);
@@ -435,6 +439,11 @@ impl PageCacheBackend for RamInode {
     fn npages(&self) -> usize {
         self.0.read().metadata.blocks
     }
+
+    fn is_evictable(&self) -> bool {
+        // The pages are not stored anywhere else
+        false
+    }
 }
 
 impl Inode for RamInode {
@@ -9,6 +9,7 @@ use super::{
     fs_resolver::{FsPath, FsResolver},
     procfs::ProcFS,
     ramfs::RamFS,
+    sysfs::SysFS,
     utils::{FileSystem, InodeMode, InodeType, MountNode},
 };
 use crate::prelude::*;
@@ -77,6 +78,9 @@ pub fn init(initramfs_buf: &[u8]) -> Result<()> {
     // Mount ProcFS
     let proc_dentry = fs.lookup(&FsPath::try_from("/proc")?)?;
     proc_dentry.mount(ProcFS::new())?;
+    // Mount SysFS
+    let sys_dentry = fs.lookup(&FsPath::try_from("/sys")?)?;
+    sys_dentry.mount(SysFS::new())?;
     // Mount DevFS
     let dev_dentry = fs.lookup(&FsPath::try_from("/dev")?)?;
     dev_dentry.mount(RamFS::new())?;
@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The signal file (i.e., signalfd), which accepts signals targeted at the caller by reading
+//! from a file descriptor.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    file_handle::FileLike,
+    utils::{CreationFlags, StatusFlags},
+};
+use crate::{
+    events::{IoEvents, Observer},
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        signal::{
+            c_types::siginfo_t,
+            constants::{SIGKILL, SIGSTOP, SI_QUEUE, SI_TIMER, SI_TKILL, SI_USER},
+            sig_mask::SigMask,
+            signals::Signal,
+            Pollee, Poller, SigEvents, SigEventsFilter,
+        },
+    },
+    thread::Thread,
+};
+
+pub struct SignalFile {
+    /// The signals that can be accepted via the file.
+    mask: Mutex<SigMask>,
+    /// The thread whose signal queues are observed for pending signals.
+    ///
+    /// FIXME: Process-directed signals that are queued to other threads in the process cannot be
+    /// observed for now.
+    thread: Weak<Thread>,
+    is_nonblocking: AtomicBool,
+    pollee: Pollee,
+    weak_self: Weak<Self>,
+}
+
+impl SignalFile {
+    /// Creates a signal file that observes the signal queues of the current thread.
+    pub fn new(mask: SigMask, flags: SignalFileFlags) -> Arc<Self> {
+        let signal_file = Arc::new_cyclic(|weak_self| Self {
+            mask: Mutex::new(SigMask::new_empty()),
+            thread: Arc::downgrade(&current_thread!()),
+            is_nonblocking: AtomicBool::new(flags.contains(SignalFileFlags::SFD_NONBLOCK)),
+            pollee: Pollee::new(IoEvents::empty()),
+            weak_self: weak_self.clone(),
+        });
+        signal_file.set_mask(mask);
+        signal_file
+    }
+
+    /// Replaces the signals that can be accepted via the file.
+    ///
+    /// `SIGKILL` and `SIGSTOP` cannot be accepted, so they are silently ignored.
+    pub fn set_mask(&self, mut mask: SigMask) {
+        mask.remove_signal(SIGKILL);
+        mask.remove_signal(SIGSTOP);
+        *self.mask.lock() = mask;
+
+        let Some(thread) = self.thread.upgrade() else {
+            return;
+        };
+        let posix_thread = thread.as_posix_thread().unwrap();
+        // Registering an observer again only updates its filter.
+        posix_thread.register_sigqueue_observer(
+            self.weak_self.clone() as _,
+            SigEventsFilter::new(Self::blocked_signals(&mask)),
+        );
+        self.update_io_events();
+    }
+
+    /// Returns the signals that are blocked from (i.e., cannot be accepted via) the file.
+    fn blocked_signals(mask: &SigMask) -> SigMask {
+        SigMask::from(!mask.as_u64())
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn update_io_events(&self) {
+        let blocked = Self::blocked_signals(&self.mask.lock());
+        let has_pending_signal = self.thread.upgrade().is_some_and(|thread| {
+            let posix_thread = thread.as_posix_thread().unwrap();
+            posix_thread.has_pending_signal_unblocked_by(&blocked)
+        });
+
+        if has_pending_signal {
+            self.pollee.add_events(IoEvents::IN);
+        } else {
+            self.pollee.del_events(IoEvents::IN);
+        }
+    }
+
+    /// Dequeues as many signals as `buf` can hold from the signal queues of the current thread.
+    fn try_read(&self, buf: &mut [u8]) -> Result<usize> {
+        let blocked = Self::blocked_signals(&self.mask.lock());
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+
+        let mut read_len = 0;
+        for chunk in buf.chunks_exact_mut(SIGINFO_LEN) {
+            let Some(signal) = posix_thread.dequeue_signal(&blocked) else {
+                break;
+            };
+            let siginfo = signalfd_siginfo::from(signal.as_ref());
+            chunk.copy_from_slice(siginfo.as_bytes());
+            read_len += SIGINFO_LEN;
+        }
+        self.update_io_events();
+
+        if read_len == 0 {
+            return_errno_with_message!(Errno::EAGAIN, "there are no pending signals");
+        }
+        Ok(read_len)
+    }
+
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+}
+
+impl FileLike for SignalFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < SIGINFO_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        if self.is_nonblocking() {
+            self.try_read(buf)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_read(buf))
+        }
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Result<Weak<dyn Observer<IoEvents>>> {
+        self.pollee
+            .unregister_observer(observer)
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "the observer is not registered"))
+    }
+}
+
+impl Observer<SigEvents> for SignalFile {
+    fn on_events(&self, _events: &SigEvents) {
+        // The signal queues are locked when observers are notified, so the events cannot be
+        // updated by checking the queues here.
+        self.pollee.add_events(IoEvents::IN);
+    }
+}
+
+impl Drop for SignalFile {
+    fn drop(&mut self) {
+        let Some(thread) = self.thread.upgrade() else {
+            return;
+        };
+        let posix_thread = thread.as_posix_thread().unwrap();
+        posix_thread.unregiser_sigqueue_observer(&(self.weak_self.clone() as _));
+    }
+}
+
+bitflags! {
+    pub struct SignalFileFlags: u32 {
+        const SFD_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+        const SFD_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+    }
+}
+
+const SIGINFO_LEN: usize = core::mem::size_of::<signalfd_siginfo>();
+
+/// The signal information read from a signal file.
+///
+/// See <https://man7.org/linux/man-pages/man2/signalfd.2.html>.
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct signalfd_siginfo {
+    ssi_signo: u32,
+    ssi_errno: i32,
+    ssi_code: i32,
+    ssi_pid: u32,
+    ssi_uid: u32,
+    ssi_fd: i32,
+    ssi_tid: u32,
+    ssi_band: u32,
+    ssi_overrun: u32,
+    ssi_trapno: u32,
+    ssi_status: i32,
+    ssi_int: i32,
+    ssi_ptr: u64,
+    ssi_utime: u64,
+    ssi_stime: u64,
+    ssi_addr: u64,
+    ssi_addr_lsb: u16,
+    _pad2: u16,
+    ssi_syscall: i32,
+    ssi_call_addr: u64,
+    ssi_arch: u32,
+    _pad: [u8; 28],
+}
+
+impl signalfd_siginfo {
+    fn set_value(&mut self, siginfo: &siginfo_t) {
+        let value = siginfo.si_value();
+        self.ssi_int = value.as_int();
+        self.ssi_ptr = value.as_ptr() as u64;
+    }
+}
+
+impl From<&dyn Signal> for signalfd_siginfo {
+    fn from(signal: &dyn Signal) -> Self {
+        let siginfo = signal.to_info();
+        let mut info = Self {
+            ssi_signo: siginfo.si_signo as u32,
+            ssi_errno: siginfo.si_errno,
+            ssi_code: siginfo.si_code,
+            ..Self::new_zeroed()
+        };
+
+        // The signal-specific fields share the space in `siginfo_t`, so only the ones that are
+        // meaningful for the kind of the signal are filled in, as Linux does.
+        match siginfo.si_code {
+            SI_USER | SI_TKILL => {
+                info.ssi_pid = siginfo.si_pid();
+                info.ssi_uid = siginfo.si_uid().as_u32();
+            }
+            SI_TIMER => {
+                let (timer_id, overrun) = siginfo.si_timer();
+                info.ssi_tid = timer_id as u32;
+                info.ssi_overrun = overrun as u32;
+                info.set_value(&siginfo);
+            }
+            SI_QUEUE => {
+                info.ssi_pid = siginfo.si_pid();
+                info.ssi_uid = siginfo.si_uid().as_u32();
+                info.set_value(&siginfo);
+            }
+            // The signals sent by the kernel carry no sender or value.
+            _ => (),
+        }
+        info
+    }
+}
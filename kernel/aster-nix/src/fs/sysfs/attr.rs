@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::*;
+use crate::fs::procfs::template::{FileOps, ProcFileBuilder};
+
+/// The attributes of a device, each of which is shown as a file in the directory of the device.
+pub(super) trait Attrs: Send + Sync + 'static {
+    /// The names of the attributes.
+    const NAMES: &'static [&'static str];
+
+    /// Returns the content of the attribute named `name`, which is one of `NAMES`.
+    fn show(&self, name: &str) -> String;
+}
+
+/// Represents the directory of a device, which contains the attributes of the device.
+pub(super) struct AttrDirOps<A: Attrs>(Arc<A>);
+
+impl<A: Attrs> AttrDirOps<A> {
+    pub fn new_inode(attrs: A, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self(Arc::new(attrs)))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl<A: Attrs> DirOps for AttrDirOps<A> {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let Some(name) = A::NAMES.iter().find(|attr_name| **attr_name == name) else {
+            return_errno!(Errno::ENOENT);
+        };
+        Ok(AttrFileOps::new_inode(self.0.clone(), *name, this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<AttrDirOps<A>>>()
+                .unwrap()
+                .this()
+        };
+        let mut cached_children = this.cached_children().write();
+        for name in A::NAMES {
+            cached_children.put_entry_if_not_found(name, || {
+                AttrFileOps::new_inode(self.0.clone(), *name, this_ptr.clone())
+            });
+        }
+    }
+}
+
+/// Represents the file of an attribute, whose content is generated each time it is read.
+struct AttrFileOps<A: Attrs> {
+    attrs: Arc<A>,
+    name: &'static str,
+}
+
+impl<A: Attrs> AttrFileOps<A> {
+    pub fn new_inode(attrs: Arc<A>, name: &'static str, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self { attrs, name })
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl<A: Attrs> FileOps for AttrFileOps<A> {
+    fn data(&self) -> Result<Vec<u8>> {
+        Ok(self.attrs.show(self.name).into_bytes())
+    }
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_block::BlockDevice;
+
+use super::{
+    attr::{AttrDirOps, Attrs},
+    *,
+};
+
+/// Represents the inode at `/sys/block`.
+pub(super) struct BlockDirOps;
+
+impl BlockDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for BlockDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let device = aster_block::get_device(name).ok_or_else(|| Error::new(Errno::ENOENT))?;
+        let attrs = BlockDeviceAttrs {
+            name: name.to_string(),
+            device,
+        };
+        Ok(AttrDirOps::new_inode(attrs, this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<BlockDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        for (name, device) in aster_block::all_devices() {
+            cached_children.put_entry_if_not_found(&name, || {
+                let attrs = BlockDeviceAttrs {
+                    name: name.clone(),
+                    device: device.clone(),
+                };
+                AttrDirOps::new_inode(attrs, this_ptr.clone())
+            });
+        }
+    }
+}
+
+/// The attributes at `/sys/block/[name]`.
+struct BlockDeviceAttrs {
+    name: String,
+    device: Arc<dyn BlockDevice>,
+}
+
+impl Attrs for BlockDeviceAttrs {
+    const NAMES: &'static [&'static str] = &["size", "ro", "removable", "uevent"];
+
+    fn show(&self, name: &str) -> String {
+        match name {
+            // The size is always in 512-byte sectors, as Linux does.
+            "size" => format!("{}\n", self.device.nr_sectors()),
+            "ro" | "removable" => String::from("0\n"),
+            "uevent" => format!("DEVNAME={}\nDEVTYPE=disk\n", self.name),
+            _ => unreachable!(),
+        }
+    }
+}
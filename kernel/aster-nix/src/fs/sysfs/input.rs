@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_input::InputDevice;
+
+use super::{
+    attr::{AttrDirOps, Attrs},
+    *,
+};
+
+/// Represents the inode at `/sys/class/input`.
+pub(super) struct InputDirOps;
+
+impl InputDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for InputDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let Some((_, device)) = name
+            .strip_prefix("input")
+            .and_then(|idx| idx.parse::<usize>().ok())
+            .and_then(|idx| aster_input::all_devices().into_iter().nth(idx))
+        else {
+            return_errno!(Errno::ENOENT);
+        };
+        Ok(AttrDirOps::new_inode(InputDeviceAttrs(device), this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<InputDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        // The devices are named by their indexes, as Linux does.
+        for (idx, (_, device)) in aster_input::all_devices().into_iter().enumerate() {
+            cached_children.put_entry_if_not_found(&format!("input{}", idx), || {
+                AttrDirOps::new_inode(InputDeviceAttrs(device.clone()), this_ptr.clone())
+            });
+        }
+    }
+}
+
+/// The attributes at `/sys/class/input/input[N]`.
+struct InputDeviceAttrs(Arc<dyn InputDevice>);
+
+impl Attrs for InputDeviceAttrs {
+    const NAMES: &'static [&'static str] = &["name", "uevent"];
+
+    fn show(&self, name: &str) -> String {
+        match name {
+            "name" => format!("{}\n", self.0.name()),
+            "uevent" => format!("NAME=\"{}\"\n", self.0.name()),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The sysfs, which exposes the devices known to the kernel.
+//!
+//! The inodes are built with the templates of the procfs. The hierarchy is a simplified version
+//! of the one on Linux, where the entries in `/sys/block`, `/sys/bus/pci/devices` and
+//! `/sys/class/*` are directories instead of symbolic links to `/sys/devices`.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use self::{block::BlockDirOps, input::InputDirOps, net::NetDirOps, pci::PciDevicesDirOps};
+use super::procfs::template::{DirOps, ProcDir, ProcDirBuilder};
+use crate::{
+    fs::utils::{DirEntryVecExt, FileSystem, FsFlags, Inode, SuperBlock, NAME_MAX},
+    prelude::*,
+};
+
+mod attr;
+mod block;
+mod input;
+mod net;
+mod pci;
+
+/// Magic number.
+const SYSFS_MAGIC: u64 = 0x62656572;
+/// Root Inode ID.
+const SYSFS_ROOT_INO: usize = 1;
+/// Block size.
+const BLOCK_SIZE: usize = PAGE_SIZE;
+
+pub struct SysFS {
+    sb: RwLock<SuperBlock>,
+    root: RwLock<Option<Arc<dyn Inode>>>,
+    inode_allocator: AtomicUsize,
+}
+
+impl SysFS {
+    pub fn new() -> Arc<Self> {
+        let sysfs = {
+            let sb = SuperBlock::new(SYSFS_MAGIC, BLOCK_SIZE, NAME_MAX);
+            Arc::new(Self {
+                sb: RwLock::new(sb),
+                root: RwLock::new(None),
+                inode_allocator: AtomicUsize::new(SYSFS_ROOT_INO),
+            })
+        };
+
+        let root = ProcDirBuilder::new(StaticDirOps(ROOT_CHILDREN))
+            .fs(sysfs.clone())
+            .build()
+            .unwrap();
+        *sysfs.root.write() = Some(root);
+        sysfs
+    }
+
+    pub(in crate::fs) fn alloc_id(&self) -> usize {
+        self.inode_allocator.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+impl FileSystem for SysFS {
+    fn sync(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn root_inode(&self) -> Arc<dyn Inode> {
+        self.root.read().as_ref().unwrap().clone()
+    }
+
+    fn sb(&self) -> SuperBlock {
+        self.sb.read().clone()
+    }
+
+    fn flags(&self) -> FsFlags {
+        FsFlags::empty()
+    }
+
+    fn name(&self) -> &'static str {
+        "sysfs"
+    }
+}
+
+/// Creates the inode of a child in a directory.
+type NewInodeFn = fn(Weak<dyn Inode>) -> Arc<dyn Inode>;
+
+/// The children of `/sys`.
+const ROOT_CHILDREN: &[(&str, NewInodeFn)] = &[
+    ("block", BlockDirOps::new_inode),
+    ("bus", |parent| {
+        StaticDirOps::new_inode(BUS_CHILDREN, parent)
+    }),
+    ("class", |parent| {
+        StaticDirOps::new_inode(CLASS_CHILDREN, parent)
+    }),
+];
+
+/// The children of `/sys/bus`.
+const BUS_CHILDREN: &[(&str, NewInodeFn)] = &[("pci", |parent| {
+    StaticDirOps::new_inode(PCI_BUS_CHILDREN, parent)
+})];
+
+/// The children of `/sys/bus/pci`.
+const PCI_BUS_CHILDREN: &[(&str, NewInodeFn)] = &[("devices", PciDevicesDirOps::new_inode)];
+
+/// The children of `/sys/class`.
+const CLASS_CHILDREN: &[(&str, NewInodeFn)] = &[
+    ("input", InputDirOps::new_inode),
+    ("net", NetDirOps::new_inode),
+];
+
+/// Represents a directory whose children never change, e.g., `/sys` and `/sys/class`.
+struct StaticDirOps(&'static [(&'static str, NewInodeFn)]);
+
+impl StaticDirOps {
+    pub fn new_inode(
+        children: &'static [(&'static str, NewInodeFn)],
+        parent: Weak<dyn Inode>,
+    ) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self(children))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl DirOps for StaticDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let Some((_, new_inode)) = self.0.iter().find(|(child_name, _)| *child_name == name) else {
+            return_errno!(Errno::ENOENT);
+        };
+        Ok(new_inode(this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<StaticDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        for (name, new_inode) in self.0 {
+            cached_children.put_entry_if_not_found(name, || new_inode(this_ptr.clone()));
+        }
+    }
+}
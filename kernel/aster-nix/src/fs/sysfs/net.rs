@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use super::{
+    attr::{AttrDirOps, Attrs},
+    *,
+};
+use crate::net::{iface::Iface, IFACES};
+
+/// Represents the inode at `/sys/class/net`.
+pub(super) struct NetDirOps;
+
+impl NetDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for NetDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let Some(attrs) = all_iface_attrs().find(|attrs| attrs.iface.name() == name) else {
+            return_errno!(Errno::ENOENT);
+        };
+        Ok(AttrDirOps::new_inode(attrs, this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<NetDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        for attrs in all_iface_attrs() {
+            let name = attrs.iface.name().to_string();
+            cached_children.put_entry_if_not_found(&name, || {
+                AttrDirOps::new_inode(attrs.clone(), this_ptr.clone())
+            });
+        }
+    }
+}
+
+/// Returns the attributes of all the network interfaces.
+fn all_iface_attrs() -> impl Iterator<Item = IfaceAttrs> {
+    let ifaces = IFACES.get().unwrap();
+    ifaces.iter().enumerate().map(|(idx, iface)| IfaceAttrs {
+        iface: iface.clone(),
+        // The interface indexes start from 1.
+        ifindex: idx + 1,
+    })
+}
+
+/// The attributes at `/sys/class/net/[iface]`.
+#[derive(Clone)]
+struct IfaceAttrs {
+    iface: Arc<dyn Iface>,
+    ifindex: usize,
+}
+
+impl IfaceAttrs {
+    /// Returns whether the interface is the loopback interface, which is the only one that has
+    /// no MAC address.
+    fn is_loopback(&self) -> bool {
+        self.iface.mac_addr().is_none()
+    }
+}
+
+impl Attrs for IfaceAttrs {
+    const NAMES: &'static [&'static str] = &[
+        "address",
+        "addr_len",
+        "ifindex",
+        "mtu",
+        "type",
+        "operstate",
+        "flags",
+        "uevent",
+    ];
+
+    fn show(&self, name: &str) -> String {
+        match name {
+            "address" => {
+                let mac_addr = self.iface.mac_addr().map(|addr| addr.0).unwrap_or_default();
+                let octets: Vec<String> = mac_addr
+                    .iter()
+                    .map(|octet| format!("{:02x}", octet))
+                    .collect();
+                format!("{}\n", octets.join(":"))
+            }
+            "addr_len" => String::from("6\n"),
+            "ifindex" => format!("{}\n", self.ifindex),
+            "mtu" => format!("{}\n", self.iface.mtu()),
+            "type" => {
+                let type_ = if self.is_loopback() {
+                    ARPHRD_LOOPBACK
+                } else {
+                    ARPHRD_ETHER
+                };
+                format!("{}\n", type_)
+            }
+            // The state of the loopback interface is unknown on Linux, since it has no carrier.
+            "operstate" => {
+                let state = if self.is_loopback() { "unknown" } else { "up" };
+                format!("{}\n", state)
+            }
+            "flags" => {
+                let flags = if self.is_loopback() {
+                    IFF_UP | IFF_LOOPBACK
+                } else {
+                    IFF_UP | IFF_BROADCAST | IFF_MULTICAST
+                };
+                format!("{:#x}\n", flags)
+            }
+            "uevent" => format!(
+                "INTERFACE={}\nIFINDEX={}\n",
+                self.iface.name(),
+                self.ifindex
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
+
+// The hardware types of the interfaces.
+const ARPHRD_ETHER: u16 = 1;
+const ARPHRD_LOOPBACK: u16 = 772;
+
+// The flags of the interfaces.
+const IFF_UP: u32 = 0x1;
+const IFF_BROADCAST: u32 = 0x2;
+const IFF_LOOPBACK: u32 = 0x8;
+const IFF_MULTICAST: u32 = 0x1000;
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_frame::bus::pci::{PciDeviceId, PciDeviceLocation, PCI_BUS};
+
+use super::{
+    attr::{AttrDirOps, Attrs},
+    *,
+};
+
+/// Represents the inode at `/sys/bus/pci/devices`.
+pub(super) struct PciDevicesDirOps;
+
+impl PciDevicesDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for PciDevicesDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let Some((location, device_id)) = PCI_BUS
+            .lock()
+            .device_ids()
+            .into_iter()
+            .find(|(location, _)| slot_name(location) == name)
+        else {
+            return_errno!(Errno::ENOENT);
+        };
+        let attrs = PciDeviceAttrs {
+            location,
+            device_id,
+        };
+        Ok(AttrDirOps::new_inode(attrs, this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<PciDevicesDirOps>>()
+                .unwrap()
+                .this()
+        };
+        let device_ids = PCI_BUS.lock().device_ids();
+        let mut cached_children = this.cached_children().write();
+        for (location, device_id) in device_ids {
+            cached_children.put_entry_if_not_found(&slot_name(&location), || {
+                let attrs = PciDeviceAttrs {
+                    location,
+                    device_id,
+                };
+                AttrDirOps::new_inode(attrs, this_ptr.clone())
+            });
+        }
+    }
+}
+
+/// Returns the name of the PCI slot in the form of `domain:bus:device.function`.
+///
+/// Only the PCI domain 0 is supported now.
+fn slot_name(location: &PciDeviceLocation) -> String {
+    format!(
+        "0000:{:02x}:{:02x}.{:x}",
+        location.bus, location.device, location.function
+    )
+}
+
+/// The attributes at `/sys/bus/pci/devices/[slot]`.
+struct PciDeviceAttrs {
+    location: PciDeviceLocation,
+    device_id: PciDeviceId,
+}
+
+impl PciDeviceAttrs {
+    /// Returns the class code, which consists of the class, the subclass and the programming
+    /// interface.
+    fn class_code(&self) -> u32 {
+        let device_id = &self.device_id;
+        ((device_id.class as u32) << 16)
+            | ((device_id.subclass as u32) << 8)
+            | (device_id.prog_if as u32)
+    }
+}
+
+impl Attrs for PciDeviceAttrs {
+    const NAMES: &'static [&'static str] = &[
+        "vendor",
+        "device",
+        "class",
+        "revision",
+        "subsystem_vendor",
+        "subsystem_device",
+        "uevent",
+    ];
+
+    fn show(&self, name: &str) -> String {
+        let device_id = &self.device_id;
+        match name {
+            "vendor" => format!("{:#06x}\n", device_id.vendor_id),
+            "device" => format!("{:#06x}\n", device_id.device_id),
+            "class" => format!("{:#08x}\n", self.class_code()),
+            "revision" => format!("{:#04x}\n", device_id.revision_id),
+            "subsystem_vendor" => format!("{:#06x}\n", device_id.subsystem_vendor_id),
+            "subsystem_device" => format!("{:#06x}\n", device_id.subsystem_id),
+            "uevent" => format!(
+                "PCI_CLASS={:X}\nPCI_ID={:04X}:{:04X}\n\
+                 PCI_SUBSYS_ID={:04X}:{:04X}\nPCI_SLOT_NAME={}\n",
+                self.class_code(),
+                device_id.vendor_id,
+                device_id.device_id,
+                device_id.subsystem_vendor_id,
+                device_id.subsystem_id,
+                slot_name(&self.location)
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The timer file (i.e., timerfd), which delivers timer expiration notifications via a file
+//! descriptor.
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{
+    file_handle::FileLike,
+    utils::{CreationFlags, StatusFlags},
+};
+use crate::{
+    events::{IoEvents, Observer},
+    prelude::*,
+    process::signal::{Pollee, Poller},
+    thread::work_queue::{submit_work_item, work_item::WorkItem, WorkPriority},
+    time::{now_as_duration, ClockID, ClockTimer},
+};
+
+pub struct TimerFile {
+    clock_id: ClockID,
+    timer: Arc<ClockTimer>,
+    state: Mutex<TimerState>,
+    is_nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+#[derive(Default)]
+struct TimerState {
+    /// The next expiration time measured by the clock, or `None` if the timer is disarmed.
+    expire_time: Option<Duration>,
+    /// The interval of a periodic timer, or zero if the timer is one-shot.
+    interval: Duration,
+    /// The number of expirations that have not been read.
+    expirations: u64,
+}
+
+impl TimerFile {
+    pub fn new(clock_id: ClockID, flags: TimerFileFlags) -> Result<Arc<Self>> {
+        if !matches!(
+            clock_id,
+            ClockID::CLOCK_REALTIME | ClockID::CLOCK_MONOTONIC | ClockID::CLOCK_BOOTTIME
+        ) {
+            return_errno_with_message!(Errno::EINVAL, "the clock is not supported by timerfd");
+        }
+
+        let timer_file = Arc::new_cyclic(|weak_self: &Weak<Self>| {
+            let work_item = {
+                let weak_self = weak_self.clone();
+                Arc::new(WorkItem::new(Box::new(move || {
+                    if let Some(timer_file) = weak_self.upgrade() {
+                        timer_file.on_timer_expired();
+                    }
+                })))
+            };
+            // The callback of `ClockTimer` runs in the interrupt context, so the expiration is
+            // handled later in the process context.
+            let timer = ClockTimer::new(clock_id, move || {
+                submit_work_item(work_item.clone(), WorkPriority::High);
+            });
+
+            Self {
+                clock_id,
+                timer,
+                state: Mutex::new(TimerState::default()),
+                is_nonblocking: AtomicBool::new(flags.contains(TimerFileFlags::TFD_NONBLOCK)),
+                pollee: Pollee::new(IoEvents::empty()),
+            }
+        });
+        Ok(timer_file)
+    }
+
+    /// Arms or disarms the timer, returning the old setting.
+    ///
+    /// A zero `value` disarms the timer. Otherwise, the timer expires after `value`, or at
+    /// `value` if `is_abs_time` is true, and then every `interval` if `interval` is not zero.
+    pub fn set_time(
+        &self,
+        value: Duration,
+        interval: Duration,
+        is_abs_time: bool,
+    ) -> Result<(Duration, Duration)> {
+        let now = now_as_duration(&self.clock_id)?;
+
+        let mut state = self.state.lock();
+        let old_setting = Self::get_time_locked(&state, now);
+
+        state.expirations = 0;
+        self.pollee.del_events(IoEvents::IN);
+
+        if value.is_zero() {
+            state.expire_time = None;
+            state.interval = Duration::ZERO;
+            self.timer.clear();
+            return Ok(old_setting);
+        }
+
+        let expire_time = if is_abs_time {
+            value
+        } else {
+            now.checked_add(value)
+                .ok_or_else(|| Error::with_message(Errno::EINVAL, "the time is too large"))?
+        };
+        state.expire_time = Some(expire_time);
+        state.interval = interval;
+        self.timer.set_deadline(expire_time)?;
+
+        Ok(old_setting)
+    }
+
+    /// Returns the time until the next expiration and the interval.
+    pub fn get_time(&self) -> Result<(Duration, Duration)> {
+        let now = now_as_duration(&self.clock_id)?;
+        let state = self.state.lock();
+        Ok(Self::get_time_locked(&state, now))
+    }
+
+    fn get_time_locked(state: &TimerState, now: Duration) -> (Duration, Duration) {
+        let remain = state.expire_time.map_or(Duration::ZERO, |expire_time| {
+            expire_time.saturating_sub(now)
+        });
+        (remain, state.interval)
+    }
+
+    fn on_timer_expired(&self) {
+        let mut state = self.state.lock();
+        let Some(expire_time) = state.expire_time else {
+            return;
+        };
+
+        let Ok(now) = now_as_duration(&self.clock_id) else {
+            return;
+        };
+        // The real time may be slewed after the timer is armed, so the timer may fire slightly
+        // earlier than expected.
+        if now < expire_time {
+            let _ = self.timer.set_deadline(expire_time);
+            return;
+        }
+
+        if state.interval.is_zero() {
+            state.expirations += 1;
+            state.expire_time = None;
+        } else {
+            // Count all the periods that have elapsed, including the ones that were missed.
+            let interval_nanos = state.interval.as_nanos();
+            let periods = ((now - expire_time).as_nanos() / interval_nanos + 1) as u64;
+            let next_expire_time =
+                expire_time + Duration::from_nanos((interval_nanos * periods as u128) as u64);
+            state.expirations = state.expirations.saturating_add(periods);
+            state.expire_time = Some(next_expire_time);
+            let _ = self.timer.set_deadline(next_expire_time);
+        }
+
+        self.pollee.add_events(IoEvents::IN);
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn try_read(&self) -> Result<u64> {
+        let mut state = self.state.lock();
+        if state.expirations == 0 {
+            return_errno_with_message!(Errno::EAGAIN, "the timer has not expired");
+        }
+
+        let expirations = core::mem::replace(&mut state.expirations, 0);
+        self.pollee.del_events(IoEvents::IN);
+
+        Ok(expirations)
+    }
+
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+}
+
+impl FileLike for TimerFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        const VAL_LEN: usize = core::mem::size_of::<u64>();
+        if buf.len() < VAL_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        let expirations = if self.is_nonblocking() {
+            self.try_read()?
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_read())?
+        };
+        buf[..VAL_LEN].copy_from_slice(&expirations.to_ne_bytes());
+
+        Ok(VAL_LEN)
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Result<Weak<dyn Observer<IoEvents>>> {
+        self.pollee
+            .unregister_observer(observer)
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "the observer is not registered"))
+    }
+}
+
+bitflags! {
+    pub struct TimerFileFlags: u32 {
+        const TFD_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+        const TFD_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+    }
+}
+
+bitflags! {
+    pub struct TimerSetTimeFlags: u32 {
+        const TFD_TIMER_ABSTIME = 1 << 0;
+        /// Only meaningful for `CLOCK_REALTIME`. The timer still follows the clock if it is set,
+        /// but reads are not canceled when that happens for now.
+        const TFD_TIMER_CANCEL_ON_SET = 1 << 1;
+    }
+}
@@ -7,9 +7,13 @@ use core::{
 
 use inherit_methods_macro::inherit_methods;
 
-use super::{FileSystem, Inode, InodeMode, InodeType, Metadata, MountNode, NAME_MAX};
+use super::{CStr256, FileSystem, Inode, InodeMode, InodeType, Metadata, MountNode, NAME_MAX};
 use crate::{
-    fs::device::Device,
+    events::{Observer, Subject},
+    fs::{
+        device::Device,
+        notify::{alloc_rename_cookie, FsEvent, FsEvents},
+    },
     prelude::*,
     process::{Gid, Uid},
 };
@@ -26,6 +30,7 @@ pub struct Dentry {
     children: Mutex<Children>,
     mount_node: Weak<MountNode>,
     flags: AtomicU32,
+    fsnotify: Subject<FsEvent>,
 }
 
 impl Dentry {
@@ -55,6 +60,7 @@ impl Dentry {
             },
             this: weak_self.clone(),
             children: Mutex::new(Children::new()),
+            fsnotify: Subject::new(),
         })
     }
 
@@ -190,6 +196,7 @@ impl Dentry {
             children.insert_dentry(&dentry);
             dentry
         };
+        self.fsnotify_child(FsEvents::CREATE | child.isdir_event(), 0, name);
         Ok(child)
     }
 
@@ -212,6 +219,7 @@ impl Dentry {
             children.insert_dentry(&dentry);
             dentry
         };
+        self.fsnotify_child(FsEvents::CREATE, 0, name);
         Ok(child)
     }
 
@@ -268,6 +276,9 @@ impl Dentry {
             DentryOptions::Leaf((String::from(name), self.this())),
         );
         children.insert_dentry(&dentry);
+        // The link count of the inode is changed.
+        old.fsnotify_self(FsEvents::ATTRIB);
+        self.fsnotify_child(FsEvents::CREATE, 0, name);
         Ok(())
     }
 
@@ -277,9 +288,13 @@ impl Dentry {
             return_errno!(Errno::ENOTDIR);
         }
         let mut children = self.children.lock();
-        let _ = children.find_dentry_with_checking_mountpoint(name)?;
+        let child = children.find_dentry_with_checking_mountpoint(name)?;
         self.inode.unlink(name)?;
         children.delete_dentry(name);
+        if let Some(child) = child {
+            child.fsnotify_unlinked();
+        }
+        self.fsnotify_child(FsEvents::DELETE, 0, name);
         Ok(())
     }
 
@@ -289,9 +304,13 @@ impl Dentry {
             return_errno!(Errno::ENOTDIR);
         }
         let mut children = self.children.lock();
-        let _ = children.find_dentry_with_checking_mountpoint(name)?;
+        let child = children.find_dentry_with_checking_mountpoint(name)?;
         self.inode.rmdir(name)?;
         children.delete_dentry(name);
+        if let Some(child) = child {
+            child.fsnotify_unlinked();
+        }
+        self.fsnotify_child(FsEvents::DELETE | FsEvents::ISDIR, 0, name);
         Ok(())
     }
 
@@ -305,13 +324,13 @@ impl Dentry {
         }
 
         // Self and new_dir are same Dentry, just modify name
-        if Arc::ptr_eq(&self.this(), new_dir) {
+        let (old_dentry, replaced_dentry) = if Arc::ptr_eq(&self.this(), new_dir) {
             if old_name == new_name {
                 return Ok(());
             }
             let mut children = self.children.lock();
             let old_dentry = children.find_dentry_with_checking_mountpoint(old_name)?;
-            let _ = children.find_dentry_with_checking_mountpoint(new_name)?;
+            let replaced_dentry = children.find_dentry_with_checking_mountpoint(new_name)?;
             self.inode.rename(old_name, &self.inode, new_name)?;
             match old_dentry.as_ref() {
                 Some(dentry) => {
@@ -323,6 +342,7 @@ impl Dentry {
                     children.delete_dentry(new_name);
                 }
             }
+            (old_dentry, replaced_dentry)
         } else {
             // Self and new_dir are different Dentry
             if !Arc::ptr_eq(&self.mount_node(), &new_dir.mount_node()) {
@@ -331,7 +351,8 @@ impl Dentry {
             let (mut self_children, mut new_dir_children) =
                 write_lock_children_on_two_dentries(self, new_dir);
             let old_dentry = self_children.find_dentry_with_checking_mountpoint(old_name)?;
-            let _ = new_dir_children.find_dentry_with_checking_mountpoint(new_name)?;
+            let replaced_dentry =
+                new_dir_children.find_dentry_with_checking_mountpoint(new_name)?;
             self.inode.rename(old_name, &new_dir.inode, new_name)?;
             match old_dentry.as_ref() {
                 Some(dentry) => {
@@ -343,6 +364,24 @@ impl Dentry {
                     new_dir_children.delete_dentry(new_name);
                 }
             }
+            (old_dentry, replaced_dentry)
+        };
+
+        if let Some(replaced_dentry) = replaced_dentry {
+            replaced_dentry.fsnotify_unlinked();
+        }
+        let isdir_event = match old_dentry.as_ref() {
+            Some(dentry) => dentry.isdir_event(),
+            None => match new_dir.inode.lookup(new_name) {
+                Ok(inode) if inode.type_() == InodeType::Dir => FsEvents::ISDIR,
+                _ => FsEvents::empty(),
+            },
+        };
+        let cookie = alloc_rename_cookie();
+        self.fsnotify_child(FsEvents::MOVED_FROM | isdir_event, cookie, old_name);
+        new_dir.fsnotify_child(FsEvents::MOVED_TO | isdir_event, cookie, new_name);
+        if let Some(old_dentry) = old_dentry {
+            old_dentry.fsnotify_self(FsEvents::MOVE_SELF);
         }
         Ok(())
     }
@@ -385,6 +424,69 @@ impl Dentry {
         Ok(child_mount)
     }
 
+    /// Register an observer for the filesystem events on the dentry and its children.
+    ///
+    /// If the given observer has already been registered, nothing happens.
+    pub fn register_fsnotify_observer(&self, observer: Weak<dyn Observer<FsEvent>>) {
+        self.fsnotify.register_observer(observer, ());
+    }
+
+    /// Unregister an observer for the filesystem events.
+    pub fn unregister_fsnotify_observer(
+        &self,
+        observer: &Weak<dyn Observer<FsEvent>>,
+    ) -> Option<Weak<dyn Observer<FsEvent>>> {
+        self.fsnotify.unregister_observer(observer)
+    }
+
+    /// Notify the observers of the filesystem events on the dentry itself.
+    ///
+    /// The events that are related to the content or the attributes of the inode are also
+    /// notified to the observers of the parent, along with the name of the dentry.
+    pub fn fsnotify(&self, events: FsEvents) {
+        self.fsnotify_self(events);
+
+        let child_events = events & FsEvents::CHILD_EVENTS;
+        if child_events.is_empty() {
+            return;
+        }
+        let Some((name, parent)) = self.name_and_parent.read().clone() else {
+            return;
+        };
+        parent.fsnotify_child(child_events | self.isdir_event(), 0, &name);
+    }
+
+    /// Notify the observers of the filesystem events on the dentry, but not those of the parent.
+    fn fsnotify_self(&self, events: FsEvents) {
+        self.fsnotify
+            .notify_observers(&FsEvent::new(events | self.isdir_event(), 0, None));
+    }
+
+    /// Notify the observers of the filesystem events on the child named `name`.
+    fn fsnotify_child(&self, events: FsEvents, cookie: u32, name: &str) {
+        let name = CStr256::from(name);
+        self.fsnotify
+            .notify_observers(&FsEvent::new(events, cookie, Some(name)));
+    }
+
+    /// Notify the observers that the dentry has been removed from its parent.
+    fn fsnotify_unlinked(&self) {
+        if self.inode.type_() == InodeType::Dir || self.inode.metadata().nlinks == 0 {
+            self.fsnotify_self(FsEvents::DELETE_SELF);
+        } else {
+            // The link count of the inode is changed.
+            self.fsnotify_self(FsEvents::ATTRIB);
+        }
+    }
+
+    fn isdir_event(&self) -> FsEvents {
+        if self.inode.type_() == InodeType::Dir {
+            FsEvents::ISDIR
+        } else {
+            FsEvents::empty()
+        }
+    }
+
     /// Get the absolute path.
     ///
     /// It will resolve the mountpoint automatically.
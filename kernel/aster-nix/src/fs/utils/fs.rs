@@ -51,6 +51,9 @@ pub trait FileSystem: Any + Sync + Send {
     fn sb(&self) -> SuperBlock;
 
     fn flags(&self) -> FsFlags;
+
+    /// Returns the name of the file system type, e.g., `ext2`.
+    fn name(&self) -> &'static str;
 }
 
 impl dyn FileSystem {
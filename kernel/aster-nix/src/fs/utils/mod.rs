@@ -13,7 +13,7 @@ pub use fs::{FileSystem, FsFlags, SuperBlock};
 pub use inode::{Inode, InodeMode, InodeType, Metadata};
 pub use ioctl::IoctlCmd;
 pub use mount::MountNode;
-pub use page_cache::{PageCache, PageCacheBackend};
+pub use page_cache::{nr_cached_pages, reclaim_page_caches, PageCache, PageCacheBackend};
 pub use random_test::{generate_random_operation, new_fs_in_memory};
 pub use status_flags::StatusFlags;
 
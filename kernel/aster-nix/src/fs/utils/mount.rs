@@ -92,6 +92,11 @@ impl MountNode {
         self.children.lock().get(&mountpoint.key()).cloned()
     }
 
+    /// Returns the child mount nodes which are mounted on the dentries of self.
+    pub fn children(&self) -> Vec<Arc<Self>> {
+        self.children.lock().values().cloned().collect()
+    }
+
     /// Get the root dentry of this mount node.
     pub fn root_dentry(&self) -> &Arc<Dentry> {
         &self.root_dentry
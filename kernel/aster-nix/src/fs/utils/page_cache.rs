@@ -1,17 +1,77 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::ops::Range;
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use aster_block::bio::{BioStatus, BioWaiter};
 use aster_frame::vm::{VmAllocOptions, VmFrame};
 use aster_rights::Full;
 use lru::LruCache;
+use spin::Once;
 
 use crate::{
     prelude::*,
-    vm::vmo::{get_page_idx_range, Pager, Vmo, VmoFlags, VmoOptions},
+    vm::{
+        reclaim,
+        vmo::{get_page_idx_range, Pager, Vmo, VmoFlags, VmoOptions, WeakVmo},
+    },
 };
 
+/// The key of a page in the global LRU list, which consists of the ID of the page cache and
+/// the index of the page.
+type PageKey = (usize, usize);
+
+lazy_static! {
+    /// The evictable pages in all the page caches, which are ordered by their last uses.
+    static ref PAGE_CACHE_LRU: Mutex<LruCache<PageKey, Weak<PageCacheManager>>> =
+        Mutex::new(LruCache::unbounded());
+}
+
+static NEXT_PAGE_CACHE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Evicts at most `nr_pages` least recently used pages from all the page caches, returning
+/// the number of the pages evicted.
+///
+/// The dirty pages are written back before they are evicted. The pages that are locked in
+/// memory or used elsewhere (e.g., mapped by processes) are kept and moved to the most
+/// recently used end. The page caches that are being used are skipped, so this can be
+/// called when allocating memory for them.
+pub fn reclaim_page_caches(nr_pages: usize) -> usize {
+    let nr_scanned_pages = PAGE_CACHE_LRU.lock().len();
+    let mut nr_evicted = 0;
+    for _ in 0..nr_scanned_pages {
+        if nr_evicted >= nr_pages {
+            break;
+        }
+        let Some((key, manager)) = PAGE_CACHE_LRU
+            .lock()
+            .peek_lru()
+            .map(|(key, manager)| (*key, manager.clone()))
+        else {
+            break;
+        };
+        let Some(manager) = manager.upgrade() else {
+            PAGE_CACHE_LRU.lock().pop(&key);
+            continue;
+        };
+
+        let (_, idx) = key;
+        if manager.evict_page(idx) {
+            nr_evicted += 1;
+        } else {
+            PAGE_CACHE_LRU.lock().promote(&key);
+        }
+    }
+    nr_evicted
+}
+
+/// Returns the number of the evictable pages in all the page caches.
+pub fn nr_cached_pages() -> usize {
+    PAGE_CACHE_LRU.lock().len()
+}
+
 pub struct PageCache {
     pages: Vmo<Full>,
     manager: Arc<PageCacheManager>,
@@ -20,11 +80,12 @@ pub struct PageCache {
 impl PageCache {
     /// Creates an empty size page cache associated with a new backend.
     pub fn new(backend: Weak<dyn PageCacheBackend>) -> Result<Self> {
-        let manager = Arc::new(PageCacheManager::new(backend));
+        let manager = PageCacheManager::new(backend);
         let pages = VmoOptions::<Full>::new(0)
             .flags(VmoFlags::RESIZABLE)
             .pager(manager.clone())
             .alloc()?;
+        manager.vmo.call_once(|| pages.downgrade());
         Ok(Self { pages, manager })
     }
 
@@ -33,11 +94,12 @@ impl PageCache {
     /// The `capacity` is the initial cache size required by the backend.
     /// This size usually corresponds to the size of the backend.
     pub fn with_capacity(capacity: usize, backend: Weak<dyn PageCacheBackend>) -> Result<Self> {
-        let manager = Arc::new(PageCacheManager::new(backend));
+        let manager = PageCacheManager::new(backend);
         let pages = VmoOptions::<Full>::new(capacity)
             .flags(VmoFlags::RESIZABLE)
             .pager(manager.clone())
             .alloc()?;
+        manager.vmo.call_once(|| pages.downgrade());
         Ok(Self { pages, manager })
     }
 
@@ -80,16 +142,32 @@ impl Debug for PageCache {
 }
 
 struct PageCacheManager {
-    pages: Mutex<LruCache<usize, Page>>,
+    /// The ID that identifies the pages of the page cache in the global LRU list.
+    id: usize,
+    pages: Mutex<BTreeMap<usize, Page>>,
+    /// The number of times that each locked page is locked, e.g., by `mlock`.
+    locked_pages: Mutex<BTreeMap<usize, usize>>,
     backend: Weak<dyn PageCacheBackend>,
+    /// The VMO that the pages are committed to, which should be notified when they are evicted.
+    vmo: Once<WeakVmo<Full>>,
+    weak_self: Weak<Self>,
 }
 
 impl PageCacheManager {
-    pub fn new(backend: Weak<dyn PageCacheBackend>) -> Self {
-        Self {
-            pages: Mutex::new(LruCache::unbounded()),
+    pub fn new(backend: Weak<dyn PageCacheBackend>) -> Arc<Self> {
+        Arc::new_cyclic(|weak_self| Self {
+            id: NEXT_PAGE_CACHE_ID.fetch_add(1, Ordering::Relaxed),
+            pages: Mutex::new(BTreeMap::new()),
+            locked_pages: Mutex::new(BTreeMap::new()),
             backend,
-        }
+            vmo: Once::new(),
+            weak_self: weak_self.clone(),
+        })
+    }
+
+    /// Returns whether the page is locked in memory, so that it must not be evicted.
+    pub fn is_page_locked(&self, idx: usize) -> bool {
+        self.locked_pages.lock().contains_key(&idx)
     }
 
     pub fn backend(&self) -> Arc<dyn PageCacheBackend> {
@@ -125,6 +203,53 @@ impl PageCacheManager {
 
         Ok(())
     }
+
+    /// Evicts the page from the page cache after writing it back if it is dirty, returning
+    /// whether the page is evicted.
+    ///
+    /// The page is kept if it is locked in memory or still used elsewhere. It is skipped without
+    /// waiting if the page cache is being used.
+    fn evict_page(&self, idx: usize) -> bool {
+        let Some(mut pages) = self.pages.try_lock() else {
+            return false;
+        };
+        if self.is_page_locked(idx) {
+            return false;
+        }
+        let Some(page) = pages.get_mut(&idx) else {
+            // The page has been decommitted, so it should no longer be in the LRU list.
+            PAGE_CACHE_LRU.lock().pop(&(self.id, idx));
+            return false;
+        };
+        let (Some(backend), Some(vmo)) = (
+            self.backend.upgrade(),
+            self.vmo.get().and_then(|vmo| vmo.upgrade()),
+        ) else {
+            return false;
+        };
+
+        if let PageState::Dirty = page.state() {
+            if idx >= backend.npages() || backend.write_page_sync(idx, page.frame()).is_err() {
+                return false;
+            }
+            page.set_state(PageState::UpToDate);
+        }
+        if !vmo.evict_page(idx) {
+            return false;
+        }
+        pages.remove(&idx);
+        PAGE_CACHE_LRU.lock().pop(&(self.id, idx));
+        true
+    }
+}
+
+impl Drop for PageCacheManager {
+    fn drop(&mut self) {
+        let mut lru = PAGE_CACHE_LRU.lock();
+        for idx in self.pages.lock().keys() {
+            lru.pop(&(self.id, *idx));
+        }
+    }
 }
 
 impl Debug for PageCacheManager {
@@ -139,6 +264,7 @@ impl Pager for PageCacheManager {
     fn commit_page(&self, idx: usize) -> Result<VmFrame> {
         let mut pages = self.pages.lock();
         let frame = if let Some(page) = pages.get(&idx) {
+            PAGE_CACHE_LRU.lock().promote(&(self.id, idx));
             page.frame().clone()
         } else {
             let backend = self.backend();
@@ -151,7 +277,12 @@ impl Pager for PageCacheManager {
                 Page::alloc_zero()?
             };
             let frame = page.frame().clone();
-            pages.put(idx, page);
+            pages.insert(idx, page);
+            if backend.is_evictable() {
+                PAGE_CACHE_LRU
+                    .lock()
+                    .put((self.id, idx), self.weak_self.clone());
+            }
             frame
         };
 
@@ -169,9 +300,45 @@ impl Pager for PageCacheManager {
         Ok(())
     }
 
+    fn sync_page(&self, idx: usize) -> Result<()> {
+        let mut pages = self.pages.lock();
+        if let Some(page) = pages.get_mut(&idx)
+            && let PageState::Dirty = page.state()
+        {
+            let Some(backend) = self.backend.upgrade() else {
+                return Ok(());
+            };
+            if idx < backend.npages() {
+                backend.write_page_sync(idx, page.frame())?;
+                page.set_state(PageState::UpToDate);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lock_page(&self, idx: usize) -> Result<()> {
+        *self.locked_pages.lock().entry(idx).or_insert(0) += 1;
+        Ok(())
+    }
+
+    fn unlock_page(&self, idx: usize) -> Result<()> {
+        let mut locked_pages = self.locked_pages.lock();
+        let Some(nr_locks) = locked_pages.get_mut(&idx) else {
+            warn!("The page {} is not locked", idx);
+            return Ok(());
+        };
+        *nr_locks -= 1;
+        if *nr_locks == 0 {
+            locked_pages.remove(&idx);
+        }
+        Ok(())
+    }
+
     fn decommit_page(&self, idx: usize) -> Result<()> {
         let mut pages = self.pages.lock();
-        if let Some(page) = pages.pop(&idx) {
+        if let Some(page) = pages.remove(&idx) {
+            PAGE_CACHE_LRU.lock().pop(&(self.id, idx));
             if let PageState::Dirty = page.state() {
                 let Some(backend) = self.backend.upgrade() else {
                     return Ok(());
@@ -194,7 +361,7 @@ struct Page {
 
 impl Page {
     pub fn alloc() -> Result<Self> {
-        let frame = VmAllocOptions::new(1).uninit(true).alloc_single()?;
+        let frame = reclaim::alloc_page(VmAllocOptions::new(1).uninit(true))?;
         Ok(Self {
             frame,
             state: PageState::Uninit,
@@ -202,7 +369,7 @@ impl Page {
     }
 
     pub fn alloc_zero() -> Result<Self> {
-        let frame = VmAllocOptions::new(1).alloc_single()?;
+        let frame = reclaim::alloc_page(&VmAllocOptions::new(1))?;
         Ok(Self {
             frame,
             state: PageState::Dirty,
@@ -243,6 +410,12 @@ pub trait PageCacheBackend: Sync + Send {
     fn write_page(&self, idx: usize, frame: &VmFrame) -> Result<BioWaiter>;
     /// Returns the number of pages in the backend.
     fn npages(&self) -> usize;
+    /// Returns whether the pages can be evicted from the page cache under memory pressure.
+    ///
+    /// This should be false if the page cache is where the data is stored, e.g., in RamFs.
+    fn is_evictable(&self) -> bool {
+        true
+    }
 }
 
 impl dyn PageCacheBackend {
@@ -1,10 +1,14 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{Iface, IpAddress, IpEndpoint};
+use smoltcp::wire::IpProtocol;
+
+use super::{Iface, IpAddress, IpEndpoint, IpListenEndpoint, IpVersion};
 use crate::{events::Observer, prelude::*};
 
 pub type RawTcpSocket = smoltcp::socket::tcp::Socket<'static>;
 pub type RawUdpSocket = smoltcp::socket::udp::Socket<'static>;
+pub type RawIpSocket = smoltcp::socket::raw::Socket<'static>;
+pub type RawIcmpSocket = smoltcp::socket::icmp::Socket<'static>;
 pub type RawSocketHandle = smoltcp::iface::SocketHandle;
 
 pub struct AnyUnboundSocket {
@@ -16,11 +20,15 @@ pub struct AnyUnboundSocket {
 pub(super) enum AnyRawSocket {
     Tcp(RawTcpSocket),
     Udp(RawUdpSocket),
+    Raw(RawIpSocket),
+    Icmp(RawIcmpSocket),
 }
 
 pub(super) enum SocketFamily {
     Tcp,
     Udp,
+    Raw,
+    Icmp,
 }
 
 impl AnyUnboundSocket {
@@ -55,6 +63,53 @@ impl AnyUnboundSocket {
         }
     }
 
+    pub fn new_raw(
+        ip_version: IpVersion,
+        ip_protocol: IpProtocol,
+        observer: Weak<dyn Observer<()>>,
+    ) -> Self {
+        let raw_ip_socket = {
+            let metadata = smoltcp::socket::raw::PacketMetadata::EMPTY;
+            let rx_buffer = smoltcp::socket::raw::PacketBuffer::new(
+                vec![metadata; RAW_METADATA_LEN],
+                vec![0u8; RAW_RECEIVE_PAYLOAD_LEN],
+            );
+            let tx_buffer = smoltcp::socket::raw::PacketBuffer::new(
+                vec![metadata; RAW_METADATA_LEN],
+                vec![0u8; RAW_SEND_PAYLOAD_LEN],
+            );
+            RawIpSocket::new(ip_version, ip_protocol, rx_buffer, tx_buffer)
+        };
+        AnyUnboundSocket {
+            socket_family: AnyRawSocket::Raw(raw_ip_socket),
+            observer,
+        }
+    }
+
+    pub fn new_icmp(observer: Weak<dyn Observer<()>>) -> Self {
+        let raw_icmp_socket = {
+            let metadata = smoltcp::socket::icmp::PacketMetadata::EMPTY;
+            let rx_buffer = smoltcp::socket::icmp::PacketBuffer::new(
+                vec![metadata; RAW_METADATA_LEN],
+                vec![0u8; RAW_RECEIVE_PAYLOAD_LEN],
+            );
+            let tx_buffer = smoltcp::socket::icmp::PacketBuffer::new(
+                vec![metadata; RAW_METADATA_LEN],
+                vec![0u8; RAW_SEND_PAYLOAD_LEN],
+            );
+            RawIcmpSocket::new(rx_buffer, tx_buffer)
+        };
+        AnyUnboundSocket {
+            socket_family: AnyRawSocket::Icmp(raw_icmp_socket),
+            observer,
+        }
+    }
+
+    /// Returns whether the socket is a raw IP socket, which is not bound to a port.
+    pub(super) fn is_raw(&self) -> bool {
+        matches!(self.socket_family, AnyRawSocket::Raw(_))
+    }
+
     pub(super) fn into_raw(self) -> (AnyRawSocket, Weak<dyn Observer<()>>) {
         (self.socket_family, self.observer)
     }
@@ -63,6 +118,7 @@ impl AnyUnboundSocket {
 pub struct AnyBoundSocket {
     iface: Arc<dyn Iface>,
     handle: smoltcp::iface::SocketHandle,
+    ip_addr: IpAddress,
     port: u16,
     socket_family: SocketFamily,
     observer: RwLock<Weak<dyn Observer<()>>>,
@@ -73,6 +129,7 @@ impl AnyBoundSocket {
     pub(super) fn new(
         iface: Arc<dyn Iface>,
         handle: smoltcp::iface::SocketHandle,
+        ip_addr: IpAddress,
         port: u16,
         socket_family: SocketFamily,
         observer: Weak<dyn Observer<()>>,
@@ -80,6 +137,7 @@ impl AnyBoundSocket {
         Arc::new_cyclic(|weak_self| Self {
             iface,
             handle,
+            ip_addr,
             port,
             socket_family,
             observer: RwLock::new(observer),
@@ -105,12 +163,25 @@ impl AnyBoundSocket {
         self.on_iface_events();
     }
 
+    /// Returns the bound endpoint, whose address may be unspecified.
     pub fn local_endpoint(&self) -> Option<IpEndpoint> {
-        let ip_addr = {
-            let ipv4_addr = self.iface.ipv4_addr()?;
-            IpAddress::Ipv4(ipv4_addr)
+        Some(IpEndpoint::new(self.ip_addr, self.port))
+    }
+
+    /// Returns the endpoint that the raw socket should listen at.
+    ///
+    /// An unspecified address means that the socket accepts packets destined to any address of
+    /// the iface, which is expressed as `None` in smoltcp.
+    pub fn listen_endpoint(&self) -> IpListenEndpoint {
+        let addr = if self.ip_addr.is_unspecified() {
+            None
+        } else {
+            Some(self.ip_addr)
         };
-        Some(IpEndpoint::new(ip_addr, self.port))
+        IpListenEndpoint {
+            addr,
+            port: self.port,
+        }
     }
 
     pub fn raw_with<T: smoltcp::socket::AnySocket<'static>, R, F: FnMut(&mut T) -> R>(
@@ -147,6 +218,8 @@ impl AnyBoundSocket {
         match self.socket_family {
             SocketFamily::Tcp => self.raw_with(|socket: &mut RawTcpSocket| socket.close()),
             SocketFamily::Udp => self.raw_with(|socket: &mut RawUdpSocket| socket.close()),
+            // Raw and ICMP sockets have no connections or bound ports to close in smoltcp.
+            SocketFamily::Raw | SocketFamily::Icmp => (),
         }
     }
 }
@@ -169,3 +242,8 @@ pub const SEND_BUF_LEN: usize = 65536;
 const UDP_METADATA_LEN: usize = 256;
 const UDP_SEND_PAYLOAD_LEN: usize = 65536;
 const UDP_RECEIVE_PAYLOAD_LEN: usize = 65536;
+
+// For raw IP and ICMP
+const RAW_METADATA_LEN: usize = 64;
+pub const RAW_SEND_PAYLOAD_LEN: usize = 65536;
+const RAW_RECEIVE_PAYLOAD_LEN: usize = 65536;
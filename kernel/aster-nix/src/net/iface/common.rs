@@ -8,6 +8,7 @@ use keyable_arc::KeyableWeak;
 use smoltcp::{
     iface::{SocketHandle, SocketSet},
     phy::Device,
+    socket::icmp::Endpoint as IcmpEndpoint,
     wire::IpCidr,
 };
 
@@ -15,7 +16,7 @@ use super::{
     any_socket::{AnyBoundSocket, AnyRawSocket, AnyUnboundSocket, SocketFamily},
     time::get_network_timestamp,
     util::BindPortConfig,
-    Iface, Ipv4Address,
+    Iface, IpAddress, Ipv4Address, Ipv6Address,
 };
 use crate::prelude::*;
 
@@ -56,11 +57,31 @@ impl IfaceCommon {
         self.interface.lock_irq_disabled().ipv4_addr()
     }
 
+    pub(super) fn ipv6_addr(&self) -> Option<Ipv6Address> {
+        let interface = self.interface.lock_irq_disabled();
+        interface.ip_addrs().iter().find_map(|cidr| match cidr {
+            IpCidr::Ipv6(ipv6_cidr) => Some(ipv6_cidr.address()),
+            _ => None,
+        })
+    }
+
+    pub(super) fn ipv6_addrs(&self) -> Vec<Ipv6Address> {
+        let interface = self.interface.lock_irq_disabled();
+        interface
+            .ip_addrs()
+            .iter()
+            .filter_map(|cidr| match cidr {
+                IpCidr::Ipv6(ipv6_cidr) => Some(ipv6_cidr.address()),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub(super) fn netmask(&self) -> Option<Ipv4Address> {
         let interface = self.interface.lock_irq_disabled();
-        let ip_addrs = interface.ip_addrs();
-        ip_addrs.first().map(|cidr| match cidr {
-            IpCidr::Ipv4(ipv4_cidr) => ipv4_cidr.netmask(),
+        interface.ip_addrs().iter().find_map(|cidr| match cidr {
+            IpCidr::Ipv4(ipv4_cidr) => Some(ipv4_cidr.netmask()),
+            _ => None,
         })
     }
 
@@ -108,19 +129,27 @@ impl IfaceCommon {
         &self,
         iface: Arc<dyn Iface>,
         socket: Box<AnyUnboundSocket>,
+        ip_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<Arc<AnyBoundSocket>, (Error, Box<AnyUnboundSocket>)> {
-        let port = if let Some(port) = config.port() {
-            port
+        // Raw IP sockets are not identified by ports, so no port is allocated for them. Port 0 is
+        // never recorded as used, so releasing it later is harmless.
+        let port = if socket.is_raw() {
+            0
         } else {
-            match self.alloc_ephemeral_port() {
-                Ok(port) => port,
-                Err(err) => return Err((err, socket)),
+            let port = if let Some(port) = config.port() {
+                port
+            } else {
+                match self.alloc_ephemeral_port() {
+                    Ok(port) => port,
+                    Err(err) => return Err((err, socket)),
+                }
+            };
+            if let Some(err) = self.bind_port(port, config.can_reuse()).err() {
+                return Err((err, socket));
             }
+            port
         };
-        if let Some(err) = self.bind_port(port, config.can_reuse()).err() {
-            return Err((err, socket));
-        }
 
         let (handle, socket_family, observer) = match socket.into_raw() {
             (AnyRawSocket::Tcp(tcp_socket), observer) => (
@@ -133,8 +162,23 @@ impl IfaceCommon {
                 SocketFamily::Udp,
                 observer,
             ),
+            (AnyRawSocket::Raw(raw_socket), observer) => (
+                self.sockets.lock_irq_disabled().add(raw_socket),
+                SocketFamily::Raw,
+                observer,
+            ),
+            (AnyRawSocket::Icmp(mut icmp_socket), observer) => {
+                // The port is used as the identifier of ICMP echo requests.
+                icmp_socket.bind(IcmpEndpoint::Ident(port)).unwrap();
+                (
+                    self.sockets.lock_irq_disabled().add(icmp_socket),
+                    SocketFamily::Icmp,
+                    observer,
+                )
+            }
         };
-        let bound_socket = AnyBoundSocket::new(iface, handle, port, socket_family, observer);
+        let bound_socket =
+            AnyBoundSocket::new(iface, handle, ip_addr, port, socket_family, observer);
         self.insert_bound_socket(&bound_socket).unwrap();
 
         Ok(bound_socket)
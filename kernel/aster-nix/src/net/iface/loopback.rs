@@ -2,11 +2,13 @@
 
 use smoltcp::{
     iface::{Config, Routes},
-    phy::{Loopback, Medium},
+    phy::{Device, Loopback, Medium},
     wire::IpCidr,
 };
 
-use super::{common::IfaceCommon, internal::IfaceInternal, Iface, IpAddress, Ipv4Address};
+use super::{
+    common::IfaceCommon, internal::IfaceInternal, Iface, IpAddress, Ipv4Address, Ipv6Address,
+};
 use crate::prelude::*;
 
 pub const LOOPBACK_ADDRESS: IpAddress = {
@@ -15,6 +17,9 @@ pub const LOOPBACK_ADDRESS: IpAddress = {
 };
 pub const LOOPBACK_ADDRESS_PREFIX_LEN: u8 = 8; // mask: 255.0.0.0
 
+pub const LOOPBACK_IPV6_ADDRESS: IpAddress = IpAddress::Ipv6(Ipv6Address::LOOPBACK);
+pub const LOOPBACK_IPV6_ADDRESS_PREFIX_LEN: u8 = 128;
+
 pub struct IfaceLoopback {
     driver: Mutex<Loopback>,
     common: IfaceCommon,
@@ -32,10 +37,14 @@ impl IfaceLoopback {
                 debug_assert!(ip_addrs.is_empty());
                 let ip_addr = IpCidr::new(LOOPBACK_ADDRESS, LOOPBACK_ADDRESS_PREFIX_LEN);
                 ip_addrs.push(ip_addr).unwrap();
+                let ipv6_addr =
+                    IpCidr::new(LOOPBACK_IPV6_ADDRESS, LOOPBACK_IPV6_ADDRESS_PREFIX_LEN);
+                ip_addrs.push(ipv6_addr).unwrap();
             });
             interface
         };
         println!("Loopback ipaddr: {}", interface.ipv4_addr().unwrap());
+        println!("Loopback ipv6 addr: {}", Ipv6Address::LOOPBACK);
         let common = IfaceCommon::new(interface);
         Arc::new_cyclic(|weak| Self {
             driver: Mutex::new(loopback),
@@ -64,6 +73,10 @@ impl Iface for IfaceLoopback {
         None
     }
 
+    fn mtu(&self) -> usize {
+        self.driver.lock().capabilities().max_transmission_unit
+    }
+
     fn poll(&self) {
         let mut device = self.driver.lock();
         self.common.poll(&mut *device);
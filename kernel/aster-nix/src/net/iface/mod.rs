@@ -14,10 +14,14 @@ mod util;
 mod virtio;
 
 pub use any_socket::{
-    AnyBoundSocket, AnyUnboundSocket, RawTcpSocket, RawUdpSocket, RECV_BUF_LEN, SEND_BUF_LEN,
+    AnyBoundSocket, AnyUnboundSocket, RawIcmpSocket, RawIpSocket, RawTcpSocket, RawUdpSocket,
+    RAW_SEND_PAYLOAD_LEN, RECV_BUF_LEN, SEND_BUF_LEN,
 };
 pub use loopback::IfaceLoopback;
-pub use smoltcp::wire::{EthernetAddress, IpAddress, IpEndpoint, Ipv4Address};
+pub use smoltcp::wire::{
+    EthernetAddress, IpAddress, IpEndpoint, IpListenEndpoint, IpProtocol, IpVersion, Ipv4Address,
+    Ipv6Address,
+};
 pub use util::{spawn_background_poll_thread, BindPortConfig};
 pub use virtio::IfaceVirtio;
 
@@ -33,6 +37,9 @@ pub trait Iface: internal::IfaceInternal + Send + Sync {
     /// The optional mac address
     fn mac_addr(&self) -> Option<EthernetAddress>;
 
+    /// The maximum transmission unit, in bytes.
+    fn mtu(&self) -> usize;
+
     /// Transmit packets queued in the iface, and receive packets queued in the iface.
     /// It any event happens, this function will also update socket status.
     fn poll(&self);
@@ -41,13 +48,16 @@ pub trait Iface: internal::IfaceInternal + Send + Sync {
     /// If port is None, the iface will pick up an empheral port for the socket.
     /// FIXME: The reason for binding socket and interface together is because there are limitations inside smoltcp.
     /// See discussion at <https://github.com/smoltcp-rs/smoltcp/issues/779>.
+    /// The socket is bound to `ip_addr`, which is either one of the addresses of the iface or an
+    /// unspecified address.
     fn bind_socket(
         &self,
         socket: Box<AnyUnboundSocket>,
+        ip_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<Arc<AnyBoundSocket>, (Error, Box<AnyUnboundSocket>)> {
         let common = self.common();
-        common.bind_socket(self.arc_self(), socket, config)
+        common.bind_socket(self.arc_self(), socket, ip_addr, config)
     }
 
     /// The optional ipv4 address
@@ -56,6 +66,17 @@ pub trait Iface: internal::IfaceInternal + Send + Sync {
         self.common().ipv4_addr()
     }
 
+    /// The optional ipv6 address
+    /// FIXME: An interface indeed support multiple addresses
+    fn ipv6_addr(&self) -> Option<Ipv6Address> {
+        self.common().ipv6_addr()
+    }
+
+    /// All the ipv6 addresses, including the link-local and global ones
+    fn ipv6_addrs(&self) -> Vec<Ipv6Address> {
+        self.common().ipv6_addrs()
+    }
+
     /// The netmask.
     /// FIXME: The netmask and IP address should be one-to-one if there are multiple ip address
     fn netmask(&self) -> Option<Ipv4Address> {
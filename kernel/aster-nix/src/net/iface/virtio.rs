@@ -4,8 +4,13 @@ use aster_network::AnyNetworkDevice;
 use aster_virtio::device::network::DEVICE_NAME;
 use smoltcp::{
     iface::{Config, Routes, SocketHandle, SocketSet},
-    socket::dhcpv4,
-    wire::{self, IpCidr},
+    phy::ChecksumCapabilities,
+    socket::{dhcpv4, raw},
+    time::Duration,
+    wire::{
+        self, Icmpv6Packet, Icmpv6Repr, IpCidr, IpProtocol, IpVersion, Ipv6Packet, Ipv6Repr,
+        NdiscPrefixInfoFlags, NdiscRepr,
+    },
 };
 
 use super::{common::IfaceCommon, internal::IfaceInternal, Iface};
@@ -15,15 +20,22 @@ pub struct IfaceVirtio {
     driver: Arc<SpinLock<Box<dyn AnyNetworkDevice>>>,
     common: IfaceCommon,
     dhcp_handle: SocketHandle,
+    slaac_handle: SocketHandle,
+    link_local_addr: wire::Ipv6Address,
     weak_self: Weak<Self>,
 }
 
 impl IfaceVirtio {
     pub fn new() -> Arc<Self> {
         let virtio_net = aster_network::get_device(DEVICE_NAME).unwrap();
+        let mac_addr = virtio_net.lock().mac_addr();
+        let link_local_addr = link_local_ipv6_addr(mac_addr.0);
         let interface = {
-            let mac_addr = virtio_net.lock().mac_addr();
             let ip_addr = IpCidr::new(wire::IpAddress::Ipv4(wire::Ipv4Address::UNSPECIFIED), 0);
+            let link_local_cidr = IpCidr::new(
+                wire::IpAddress::Ipv6(link_local_addr),
+                LINK_LOCAL_PREFIX_LEN,
+            );
             let routes = Routes::new();
             let config = {
                 let mut config = Config::new();
@@ -36,17 +48,22 @@ impl IfaceVirtio {
             interface.update_ip_addrs(|ip_addrs| {
                 debug_assert!(ip_addrs.is_empty());
                 ip_addrs.push(ip_addr).unwrap();
+                ip_addrs.push(link_local_cidr).unwrap();
             });
+            println!("Virtio-net link-local ipv6 addr: {}", link_local_addr);
             interface
         };
         let common = IfaceCommon::new(interface);
         let mut socket_set = common.sockets();
         let dhcp_handle = init_dhcp_client(&mut socket_set);
+        let slaac_handle = init_slaac_client(&mut socket_set, &link_local_addr);
         drop(socket_set);
         Arc::new_cyclic(|weak| Self {
             driver: virtio_net,
             common,
             dhcp_handle,
+            slaac_handle,
+            link_local_addr,
             weak_self: weak.clone(),
         })
     }
@@ -68,11 +85,14 @@ impl IfaceVirtio {
         let ip_addr = IpCidr::Ipv4(config.address);
         let mut interface = self.common.interface();
         interface.update_ip_addrs(|ipaddrs| {
-            if let Some(addr) = ipaddrs.iter_mut().next() {
-                // already has ipaddrs
+            if let Some(addr) = ipaddrs
+                .iter_mut()
+                .find(|addr| matches!(addr, IpCidr::Ipv4(_)))
+            {
+                // already has an ipv4 addr
                 *addr = ip_addr
             } else {
-                // does not has ip addr
+                // does not has an ipv4 addr
                 ipaddrs.push(ip_addr).unwrap();
             }
         });
@@ -88,6 +108,70 @@ impl IfaceVirtio {
                 .unwrap();
         }
     }
+
+    /// Configures the global IPv6 address and the default IPv6 route from the router
+    /// advertisements, as described in the stateless address autoconfiguration (RFC 4862).
+    ///
+    /// TODO: Handle the lifetimes of addresses and routers. Now they are only removed when a
+    /// router advertisement with zero lifetimes is received.
+    pub fn process_slaac(&self) {
+        let mut socket_set = self.common.sockets();
+        let slaac_socket: &mut raw::Socket = socket_set.get_mut(self.slaac_handle);
+
+        while let Ok(packet) = slaac_socket.recv() {
+            let Some((router_addr, router_advert)) = parse_router_advert(packet) else {
+                continue;
+            };
+            let NdiscRepr::RouterAdvert {
+                router_lifetime,
+                prefix_info,
+                ..
+            } = router_advert
+            else {
+                continue;
+            };
+
+            let mut interface = self.common.interface();
+
+            if let Some(prefix_info) = prefix_info {
+                // Only prefixes for autonomous address configuration whose length matches
+                // that of the interface identifier can be used.
+                if prefix_info.flags.contains(NdiscPrefixInfoFlags::ADDRCONF)
+                    && prefix_info.prefix_len == LINK_LOCAL_PREFIX_LEN
+                    && !prefix_info.prefix.is_link_local()
+                {
+                    let global_addr = slaac_ipv6_addr(&prefix_info.prefix, &self.link_local_addr);
+                    let global_cidr = IpCidr::new(global_addr.into(), prefix_info.prefix_len);
+                    let is_valid = prefix_info.valid_lifetime != Duration::ZERO;
+                    interface.update_ip_addrs(|ip_addrs| {
+                        let index = ip_addrs.iter().position(|cidr| *cidr == global_cidr);
+                        match (index, is_valid) {
+                            (None, true) => {
+                                if ip_addrs.push(global_cidr).is_err() {
+                                    warn!("no room for the IPv6 address {}", global_addr);
+                                    return;
+                                }
+                                println!("SLAAC update IPv6 address: {}", global_addr);
+                            }
+                            (Some(index), false) => {
+                                ip_addrs.swap_remove(index);
+                            }
+                            _ => (),
+                        }
+                    });
+                }
+            }
+
+            if router_lifetime != Duration::ZERO {
+                interface
+                    .routes_mut()
+                    .add_default_ipv6_route(router_addr)
+                    .unwrap();
+            } else {
+                interface.routes_mut().remove_default_ipv6_route();
+            }
+        }
+    }
 }
 
 impl IfaceInternal for IfaceVirtio {
@@ -113,10 +197,18 @@ impl Iface for IfaceVirtio {
         }
     }
 
+    fn mtu(&self) -> usize {
+        self.driver
+            .lock_irq_disabled()
+            .capabilities()
+            .max_transmission_unit
+    }
+
     fn poll(&self) {
         let mut driver = self.driver.lock_irq_disabled();
         self.common.poll(&mut **driver);
         self.process_dhcp();
+        self.process_slaac();
     }
 }
 
@@ -125,3 +217,108 @@ fn init_dhcp_client(socket_set: &mut SocketSet) -> SocketHandle {
     let dhcp_socket = dhcpv4::Socket::new();
     socket_set.add(dhcp_socket)
 }
+
+/// Registers a raw ICMPv6 socket to receive router advertisements, and sends a router
+/// solicitation so that the routers advertise themselves without delay.
+fn init_slaac_client(
+    socket_set: &mut SocketSet,
+    link_local_addr: &wire::Ipv6Address,
+) -> SocketHandle {
+    let mut slaac_socket = {
+        let metadata = raw::PacketMetadata::EMPTY;
+        let rx_buffer = raw::PacketBuffer::new(
+            vec![metadata; SLAAC_METADATA_LEN],
+            vec![0u8; SLAAC_PAYLOAD_LEN],
+        );
+        let tx_buffer = raw::PacketBuffer::new(
+            vec![metadata; SLAAC_METADATA_LEN],
+            vec![0u8; SLAAC_PAYLOAD_LEN],
+        );
+        raw::Socket::new(IpVersion::Ipv6, IpProtocol::Icmpv6, rx_buffer, tx_buffer)
+    };
+
+    let router_solicit = Icmpv6Repr::Ndisc(NdiscRepr::RouterSolicit { lladdr: None });
+    let ipv6_repr = Ipv6Repr {
+        src_addr: *link_local_addr,
+        dst_addr: wire::Ipv6Address::LINK_LOCAL_ALL_ROUTERS,
+        next_header: IpProtocol::Icmpv6,
+        payload_len: router_solicit.buffer_len(),
+        hop_limit: NDISC_HOP_LIMIT,
+    };
+    let mut packet_buf = vec![0u8; ipv6_repr.buffer_len() + router_solicit.buffer_len()];
+    let mut packet = Ipv6Packet::new_unchecked(&mut packet_buf[..]);
+    ipv6_repr.emit(&mut packet);
+    router_solicit.emit(
+        &ipv6_repr.src_addr.into(),
+        &ipv6_repr.dst_addr.into(),
+        &mut Icmpv6Packet::new_unchecked(packet.payload_mut()),
+        &ChecksumCapabilities::default(),
+    );
+    slaac_socket.send_slice(&packet_buf).unwrap();
+
+    socket_set.add(slaac_socket)
+}
+
+/// Parses a router advertisement, returning it along with the address of the router.
+fn parse_router_advert(packet: &[u8]) -> Option<(wire::Ipv6Address, NdiscRepr<'_>)> {
+    let ipv6_packet = Ipv6Packet::new_checked(packet).ok()?;
+    let ipv6_repr = Ipv6Repr::parse(&ipv6_packet).ok()?;
+    // Router advertisements from other links are discarded (RFC 4861, Section 6.1.2).
+    if !ipv6_repr.src_addr.is_link_local() || ipv6_repr.hop_limit != NDISC_HOP_LIMIT {
+        return None;
+    }
+
+    let icmpv6_packet = Icmpv6Packet::new_checked(ipv6_packet.payload()).ok()?;
+    let icmpv6_repr = Icmpv6Repr::parse(
+        &ipv6_repr.src_addr.into(),
+        &ipv6_repr.dst_addr.into(),
+        &icmpv6_packet,
+        &ChecksumCapabilities::default(),
+    )
+    .ok()?;
+    match icmpv6_repr {
+        Icmpv6Repr::Ndisc(ndisc_repr @ NdiscRepr::RouterAdvert { .. }) => {
+            Some((ipv6_repr.src_addr, ndisc_repr))
+        }
+        _ => None,
+    }
+}
+
+const SLAAC_METADATA_LEN: usize = 4;
+const SLAAC_PAYLOAD_LEN: usize = 4096;
+/// The hop limit of neighbor discovery messages, which ensures that they come from the link.
+const NDISC_HOP_LIMIT: u8 = 255;
+
+const LINK_LOCAL_PREFIX_LEN: u8 = 64;
+
+/// Generates the IPv6 link-local address from the MAC address.
+///
+/// The interface identifier is the modified EUI-64 format of the MAC address, as described
+/// in RFC 4291 (Appendix A) and used by the stateless address autoconfiguration (RFC 4862).
+fn link_local_ipv6_addr(mac_addr: [u8; 6]) -> wire::Ipv6Address {
+    let mut bytes = [0u8; 16];
+    bytes[0] = 0xfe;
+    bytes[1] = 0x80;
+    // Flip the universal/local bit
+    bytes[8] = mac_addr[0] ^ 0x02;
+    bytes[9] = mac_addr[1];
+    bytes[10] = mac_addr[2];
+    bytes[11] = 0xff;
+    bytes[12] = 0xfe;
+    bytes[13] = mac_addr[3];
+    bytes[14] = mac_addr[4];
+    bytes[15] = mac_addr[5];
+    wire::Ipv6Address::from_bytes(&bytes)
+}
+
+/// Generates the global IPv6 address from the advertised prefix and the interface identifier
+/// of the link-local address.
+fn slaac_ipv6_addr(
+    prefix: &wire::Ipv6Address,
+    link_local_addr: &wire::Ipv6Address,
+) -> wire::Ipv6Address {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&prefix.as_bytes()[..8]);
+    bytes[8..].copy_from_slice(&link_local_addr.as_bytes()[8..]);
+    wire::Ipv6Address::from_bytes(&bytes)
+}
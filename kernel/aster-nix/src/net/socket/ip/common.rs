@@ -2,7 +2,11 @@
 
 use crate::{
     net::{
-        iface::{AnyBoundSocket, AnyUnboundSocket, BindPortConfig, Iface, IpAddress, IpEndpoint},
+        iface::{
+            AnyBoundSocket, AnyUnboundSocket, BindPortConfig, Iface, IpAddress, IpEndpoint,
+            IpVersion, Ipv4Address, Ipv6Address,
+        },
+        socket::SocketAddr,
         IFACES,
     },
     prelude::*,
@@ -10,16 +14,15 @@ use crate::{
 
 pub fn get_iface_to_bind(ip_addr: &IpAddress) -> Option<Arc<dyn Iface>> {
     let ifaces = IFACES.get().unwrap();
-    let IpAddress::Ipv4(ipv4_addr) = ip_addr;
+    if ip_addr.is_unspecified() {
+        // FIXME: A socket bound to an unspecified address should receive packets from all
+        // ifaces. Since a socket can only be bound to one iface in smoltcp, use the default
+        // interface for now.
+        return Some(ifaces[0].clone());
+    }
     ifaces
         .iter()
-        .find(|iface| {
-            if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
-                iface_ipv4_addr == *ipv4_addr
-            } else {
-                false
-            }
-        })
+        .find(|iface| iface_ip_addr(iface, ip_addr.version()) == Some(*ip_addr))
         .map(Clone::clone)
 }
 
@@ -28,20 +31,24 @@ pub fn get_iface_to_bind(ip_addr: &IpAddress) -> Option<Arc<dyn Iface>> {
 /// Otherwise, we will use a default interface.
 fn get_ephemeral_iface(remote_ip_addr: &IpAddress) -> Arc<dyn Iface> {
     let ifaces = IFACES.get().unwrap();
-    let IpAddress::Ipv4(remote_ipv4_addr) = remote_ip_addr;
-    if let Some(iface) = ifaces.iter().find(|iface| {
-        if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
-            iface_ipv4_addr == *remote_ipv4_addr
-        } else {
-            false
-        }
-    }) {
+    if let Some(iface) = ifaces
+        .iter()
+        .find(|iface| iface_ip_addr(iface, remote_ip_addr.version()) == Some(*remote_ip_addr))
+    {
         return iface.clone();
     }
     // FIXME: use the virtio-net as the default interface
     ifaces[0].clone()
 }
 
+/// Returns the address of the iface in the IP version.
+fn iface_ip_addr(iface: &Arc<dyn Iface>, ip_version: IpVersion) -> Option<IpAddress> {
+    match ip_version {
+        IpVersion::Ipv4 => iface.ipv4_addr().map(IpAddress::Ipv4),
+        IpVersion::Ipv6 => iface.ipv6_addr().map(IpAddress::Ipv6),
+    }
+}
+
 pub(super) fn bind_socket(
     unbound_socket: Box<AnyUnboundSocket>,
     endpoint: &IpEndpoint,
@@ -58,11 +65,84 @@ pub(super) fn bind_socket(
         Ok(config) => config,
         Err(e) => return Err((e, unbound_socket)),
     };
-    iface.bind_socket(unbound_socket, bind_port_config)
+    iface.bind_socket(unbound_socket, endpoint.addr, bind_port_config)
 }
 
-pub fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> IpEndpoint {
+pub fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> Result<IpEndpoint> {
     let iface = get_ephemeral_iface(&remote_endpoint.addr);
-    let ip_addr = iface.ipv4_addr().unwrap();
-    IpEndpoint::new(IpAddress::Ipv4(ip_addr), 0)
+    let Some(ip_addr) = iface_ip_addr(&iface, remote_endpoint.addr.version()) else {
+        return_errno_with_message!(
+            Errno::ENETUNREACH,
+            "no address of the same IP version is available"
+        );
+    };
+    Ok(IpEndpoint::new(ip_addr, 0))
+}
+
+/// Converts a socket address to an endpoint for a socket of `ip_version`.
+///
+/// For an IPv6 socket, an IPv4-mapped IPv6 address (i.e., `::ffff:a.b.c.d`) is converted to an
+/// IPv4 endpoint. The caller should check whether IPv4 endpoints are allowed via
+/// [`is_addr_allowed`].
+pub(super) fn socket_addr_to_endpoint(
+    socket_addr: SocketAddr,
+    ip_version: IpVersion,
+) -> Result<IpEndpoint> {
+    match (ip_version, socket_addr) {
+        (IpVersion::Ipv4, SocketAddr::IPv4(addr, port)) => {
+            Ok(IpEndpoint::new(IpAddress::Ipv4(addr), port))
+        }
+        (IpVersion::Ipv6, SocketAddr::IPv6(addr, port)) => {
+            let ip_addr = match ipv4_mapped_to_ipv4(&addr) {
+                Some(ipv4_addr) => IpAddress::Ipv4(ipv4_addr),
+                None => IpAddress::Ipv6(addr),
+            };
+            Ok(IpEndpoint::new(ip_addr, port))
+        }
+        _ => return_errno_with_message!(
+            Errno::EAFNOSUPPORT,
+            "the address family does not match the socket"
+        ),
+    }
+}
+
+/// Converts an endpoint to a socket address for a socket of `ip_version`.
+///
+/// For an IPv6 socket, an IPv4 endpoint is converted to an IPv4-mapped IPv6 address.
+pub(super) fn endpoint_to_socket_addr(endpoint: IpEndpoint, ip_version: IpVersion) -> SocketAddr {
+    match (ip_version, endpoint.addr) {
+        (IpVersion::Ipv6, IpAddress::Ipv4(ipv4_addr)) => {
+            SocketAddr::IPv6(ipv4_to_ipv4_mapped(&ipv4_addr), endpoint.port)
+        }
+        _ => endpoint.into(),
+    }
+}
+
+/// Returns whether a socket of `ip_version` can communicate with `ip_addr`.
+///
+/// An IPv6 socket can communicate with IPv4 addresses via IPv4-mapped IPv6 addresses, unless
+/// `IPV6_V6ONLY` is set.
+pub(super) fn is_addr_allowed(ip_version: IpVersion, is_v6only: bool, ip_addr: &IpAddress) -> bool {
+    match (ip_version, ip_addr) {
+        (IpVersion::Ipv4, IpAddress::Ipv4(_)) | (IpVersion::Ipv6, IpAddress::Ipv6(_)) => true,
+        (IpVersion::Ipv6, IpAddress::Ipv4(_)) => !is_v6only,
+        (IpVersion::Ipv4, IpAddress::Ipv6(_)) => false,
+    }
+}
+
+const IPV4_MAPPED_PREFIX: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff];
+
+fn ipv4_mapped_to_ipv4(addr: &Ipv6Address) -> Option<Ipv4Address> {
+    let bytes = addr.as_bytes();
+    if bytes[..12] != IPV4_MAPPED_PREFIX {
+        return None;
+    }
+    Some(Ipv4Address::from_bytes(&bytes[12..]))
+}
+
+fn ipv4_to_ipv4_mapped(addr: &Ipv4Address) -> Ipv6Address {
+    let mut bytes = [0u8; 16];
+    bytes[..12].copy_from_slice(&IPV4_MAPPED_PREFIX);
+    bytes[12..].copy_from_slice(addr.as_bytes());
+    Ipv6Address::from_bytes(&bytes)
 }
@@ -36,16 +36,22 @@ impl BoundDatagram {
         self.remote_endpoint = Some(*endpoint)
     }
 
+    /// Receives a datagram, returning the real length of the datagram.
+    ///
+    /// If the datagram does not fit into `buf`, the rest of the datagram is discarded.
     pub fn try_recvfrom(
         &self,
         buf: &mut [u8],
         flags: SendRecvFlags,
     ) -> Result<(usize, IpEndpoint)> {
-        let result = self
-            .bound_socket
-            .raw_with(|socket: &mut RawUdpSocket| socket.recv_slice(buf));
+        let result = self.bound_socket.raw_with(|socket: &mut RawUdpSocket| {
+            let (datagram, endpoint) = socket.recv()?;
+            let copy_len = datagram.len().min(buf.len());
+            buf[..copy_len].copy_from_slice(&datagram[..copy_len]);
+            Ok((datagram.len(), endpoint))
+        });
         match result {
-            Ok((recv_len, endpoint)) => Ok((recv_len, endpoint)),
+            Ok((datagram_len, endpoint)) => Ok((datagram_len, endpoint)),
             Err(RecvError::Exhausted) => {
                 return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
             }
@@ -5,15 +5,26 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use takeable::Takeable;
 
 use self::{bound::BoundDatagram, unbound::UnboundDatagram};
-use super::{common::get_ephemeral_endpoint, UNSPECIFIED_LOCAL_ENDPOINT};
+use super::{
+    common::{
+        endpoint_to_socket_addr, get_ephemeral_endpoint, is_addr_allowed, socket_addr_to_endpoint,
+    },
+    options::V6Only,
+    unspecified_local_endpoint,
+};
 use crate::{
     events::{IoEvents, Observer},
     fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut, match_sock_option_ref,
     net::{
-        iface::IpEndpoint,
+        iface::{IpEndpoint, IpVersion},
         poll_ifaces,
         socket::{
-            util::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr},
+            options::SocketOption,
+            util::{
+                message_header::MessageHeader, send_recv_flags::SendRecvFlags,
+                socket_addr::SocketAddr,
+            },
             Socket,
         },
     },
@@ -25,8 +36,10 @@ mod bound;
 mod unbound;
 
 pub struct DatagramSocket {
+    ip_version: IpVersion,
     inner: RwLock<Takeable<Inner>>,
     nonblocking: AtomicBool,
+    is_v6only: AtomicBool,
     pollee: Pollee,
 }
 
@@ -62,19 +75,24 @@ impl Inner {
             return Ok(bound_datagram);
         }
 
-        let endpoint = get_ephemeral_endpoint(remote_endpoint);
+        let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err((err, self)),
+        };
         self.bind(&endpoint)
     }
 }
 
 impl DatagramSocket {
-    pub fn new(nonblocking: bool) -> Arc<Self> {
+    pub fn new(ip_version: IpVersion, nonblocking: bool) -> Arc<Self> {
         Arc::new_cyclic(|me| {
             let unbound_datagram = UnboundDatagram::new(me.clone() as _);
             let pollee = Pollee::new(IoEvents::empty());
             Self {
+                ip_version,
                 inner: RwLock::new(Takeable::new(Inner::Unbound(unbound_datagram))),
                 nonblocking: AtomicBool::new(nonblocking),
+                is_v6only: AtomicBool::new(false),
                 pollee,
             }
         })
@@ -88,6 +106,27 @@ impl DatagramSocket {
         self.nonblocking.store(nonblocking, Ordering::SeqCst);
     }
 
+    fn is_v6only(&self) -> bool {
+        self.is_v6only.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the socket can communicate with the endpoint.
+    fn is_endpoint_allowed(&self, endpoint: &IpEndpoint) -> bool {
+        is_addr_allowed(self.ip_version, self.is_v6only(), &endpoint.addr)
+    }
+
+    /// Converts a remote socket address to an endpoint that the socket can send packets to.
+    fn remote_addr_to_endpoint(&self, socket_addr: SocketAddr) -> Result<IpEndpoint> {
+        let endpoint = socket_addr_to_endpoint(socket_addr, self.ip_version)?;
+        if !self.is_endpoint_allowed(&endpoint) {
+            return_errno_with_message!(
+                Errno::ENETUNREACH,
+                "an IPv6-only socket cannot communicate with an IPv4-mapped address"
+            );
+        }
+        Ok(endpoint)
+    }
+
     fn remote_endpoint(&self) -> Option<IpEndpoint> {
         let inner = self.inner.read();
 
@@ -117,6 +156,7 @@ impl DatagramSocket {
         })
     }
 
+    /// Receives a datagram, returning the real length of the datagram.
     fn try_recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
         let inner = self.inner.read();
 
@@ -124,9 +164,21 @@ impl DatagramSocket {
             return_errno_with_message!(Errno::EAGAIN, "the socket is not bound");
         };
 
-        let (recv_bytes, remote_endpoint) = bound_datagram.try_recvfrom(buf, flags)?;
+        // Datagrams from the endpoints that the socket cannot communicate with (e.g., IPv4
+        // datagrams received by an `IPV6_V6ONLY` socket) are dropped.
+        let result = loop {
+            match bound_datagram.try_recvfrom(buf, flags) {
+                Ok((_, remote_endpoint)) if !self.is_endpoint_allowed(&remote_endpoint) => (),
+                result => break result,
+            }
+        };
         bound_datagram.update_io_events(&self.pollee);
-        Ok((recv_bytes, remote_endpoint.into()))
+
+        let (recv_bytes, remote_endpoint) = result?;
+        Ok((
+            recv_bytes,
+            endpoint_to_socket_addr(remote_endpoint, self.ip_version),
+        ))
     }
 
     fn try_sendto(&self, buf: &[u8], remote: &IpEndpoint, flags: SendRecvFlags) -> Result<usize> {
@@ -141,6 +193,16 @@ impl DatagramSocket {
         Ok(sent_bytes)
     }
 
+    /// Receives a datagram, returning the real length of the datagram.
+    fn recv(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        poll_ifaces();
+        if self.is_nonblocking() {
+            self.try_recvfrom(buf, flags)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_recvfrom(buf, flags))
+        }
+    }
+
     // TODO: Support timeout
     fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
     where
@@ -214,7 +276,13 @@ impl FileLike for DatagramSocket {
 
 impl Socket for DatagramSocket {
     fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
-        let endpoint = socket_addr.try_into()?;
+        let endpoint = socket_addr_to_endpoint(socket_addr, self.ip_version)?;
+        if !self.is_endpoint_allowed(&endpoint) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "an IPv4-mapped address cannot be bound to an IPv6-only socket"
+            );
+        }
 
         let mut inner = self.inner.write();
         inner.borrow_result(|owned_inner| {
@@ -230,7 +298,7 @@ impl Socket for DatagramSocket {
     }
 
     fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
-        let endpoint = socket_addr.try_into()?;
+        let endpoint = self.remote_addr_to_endpoint(socket_addr)?;
 
         self.try_bind_empheral(&endpoint)?;
 
@@ -246,27 +314,70 @@ impl Socket for DatagramSocket {
     fn addr(&self) -> Result<SocketAddr> {
         let inner = self.inner.read();
         match inner.as_ref() {
-            Inner::Unbound(unbound_datagram) => Ok(UNSPECIFIED_LOCAL_ENDPOINT.into()),
-            Inner::Bound(bound_datagram) => Ok(bound_datagram.local_endpoint().into()),
+            Inner::Unbound(unbound_datagram) => {
+                Ok(unspecified_local_endpoint(self.ip_version).into())
+            }
+            Inner::Bound(bound_datagram) => Ok(endpoint_to_socket_addr(
+                bound_datagram.local_endpoint(),
+                self.ip_version,
+            )),
         }
     }
 
     fn peer_addr(&self) -> Result<SocketAddr> {
         self.remote_endpoint()
-            .map(|endpoint| endpoint.into())
+            .map(|endpoint| endpoint_to_socket_addr(endpoint, self.ip_version))
             .ok_or_else(|| Error::with_message(Errno::ENOTCONN, "the socket is not connected"))
     }
 
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            ipv6_v6only: V6Only => {
+                if self.ip_version != IpVersion::Ipv6 {
+                    return_errno_with_message!(Errno::ENOPROTOOPT, "the socket is not an IPv6 socket");
+                }
+                ipv6_v6only.set(self.is_v6only());
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "get unknown option")
+        });
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            ipv6_v6only: V6Only => {
+                if self.ip_version != IpVersion::Ipv6 {
+                    return_errno_with_message!(Errno::ENOPROTOOPT, "the socket is not an IPv6 socket");
+                }
+                // Like Linux, the option can only be changed before the socket is bound.
+                if !matches!(self.inner.read().as_ref(), Inner::Unbound(_)) {
+                    return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+                }
+                let is_v6only = ipv6_v6only.get().unwrap();
+                self.is_v6only.store(*is_v6only, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "set unknown option")
+        });
+        Ok(())
+    }
+
     // FIXME: respect RecvFromFlags
     fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
         debug_assert!(flags.is_all_supported());
 
-        poll_ifaces();
-        if self.is_nonblocking() {
-            self.try_recvfrom(buf, flags)
-        } else {
-            self.wait_events(IoEvents::IN, || self.try_recvfrom(buf, flags))
+        let (datagram_len, addr) = self.recv(buf, flags)?;
+        Ok((datagram_len.min(buf.len()), addr))
+    }
+
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        debug_assert!(flags.is_all_supported());
+
+        let (datagram_len, addr) = self.recv(buf, flags)?;
+        let mut message_header = MessageHeader::new(Some(addr), Vec::new());
+        if datagram_len > buf.len() {
+            message_header.set_truncated();
         }
+        Ok((datagram_len.min(buf.len()), message_header))
     }
 
     fn sendto(
@@ -279,7 +390,7 @@ impl Socket for DatagramSocket {
 
         let remote_endpoint = match remote {
             Some(remote_addr) => {
-                let endpoint = remote_addr.try_into()?;
+                let endpoint = self.remote_addr_to_endpoint(remote_addr)?;
                 self.try_bind_empheral(&endpoint)?;
                 endpoint
             }
@@ -29,9 +29,9 @@ impl UnboundDatagram {
             Err((err, unbound_socket)) => return Err((err, Self { unbound_socket })),
         };
 
-        let bound_endpoint = bound_socket.local_endpoint().unwrap();
+        let listen_endpoint = bound_socket.listen_endpoint();
         bound_socket.raw_with(|socket: &mut RawUdpSocket| {
-            socket.bind(bound_endpoint).unwrap();
+            socket.bind(listen_endpoint).unwrap();
         });
 
         Ok(BoundDatagram::new(bound_socket))
@@ -1,18 +1,26 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::net::iface::{IpAddress, IpEndpoint, Ipv4Address};
+use crate::net::iface::{IpAddress, IpEndpoint, IpVersion, Ipv4Address, Ipv6Address};
 
 mod common;
 mod datagram;
+pub mod options;
+mod raw;
 pub mod stream;
 
 pub use datagram::DatagramSocket;
+pub use raw::{PingSocket, RawSocket};
 pub use stream::StreamSocket;
 
-/// A local endpoint, which indicates that the local endpoint is unspecified.
+/// Returns a local endpoint, which indicates that the local endpoint is unspecified.
 ///
 /// According to the Linux man pages and the Linux implementation, `getsockname()` will _not_ fail
 /// even if the socket is unbound. Instead, it will return an unspecified socket address. This
 /// unspecified endpoint helps with that.
-const UNSPECIFIED_LOCAL_ENDPOINT: IpEndpoint =
-    IpEndpoint::new(IpAddress::Ipv4(Ipv4Address::UNSPECIFIED), 0);
+const fn unspecified_local_endpoint(ip_version: IpVersion) -> IpEndpoint {
+    let ip_addr = match ip_version {
+        IpVersion::Ipv4 => IpAddress::Ipv4(Ipv4Address::UNSPECIFIED),
+        IpVersion::Ipv6 => IpAddress::Ipv6(Ipv6Address::UNSPECIFIED),
+    };
+    IpEndpoint::new(ip_addr, 0)
+}
@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use smoltcp::{
+    phy::ChecksumCapabilities,
+    socket::raw::{RecvError, SendError},
+    wire::{Ipv4Packet, Ipv4Repr},
+};
+use takeable::Takeable;
+
+pub use self::ping::PingSocket;
+use super::{
+    common::{bind_socket, get_ephemeral_endpoint, socket_addr_to_endpoint},
+    unspecified_local_endpoint,
+};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    net::{
+        iface::{
+            AnyBoundSocket, AnyUnboundSocket, IpAddress, IpEndpoint, IpProtocol, IpVersion,
+            Ipv4Address, RawIpSocket, RAW_SEND_PAYLOAD_LEN,
+        },
+        poll_ifaces,
+        socket::{
+            util::{
+                message_header::MessageHeader, send_recv_flags::SendRecvFlags,
+                socket_addr::SocketAddr,
+            },
+            Socket,
+        },
+    },
+    prelude::*,
+    process::{
+        credentials,
+        signal::{Pollee, Poller},
+    },
+};
+
+/// A raw IPv4 socket (i.e., `SOCK_RAW`).
+///
+/// Received packets are returned to the user with their IPv4 headers. When sending packets, the
+/// IPv4 header is built by the kernel, unless the protocol is `IPPROTO_RAW`, in which case the
+/// user must supply the header.
+///
+/// TODO: Support raw IPv6 sockets and the `IP_HDRINCL` option.
+pub struct RawSocket {
+    ip_protocol: IpProtocol,
+    inner: RwLock<Takeable<Inner>>,
+    remote_addr: RwLock<Option<Ipv4Address>>,
+    nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+mod ping;
+
+/// The state of a raw or ping socket, which is bound to an iface either explicitly by `bind()` or
+/// implicitly when the first packet is sent (or, for raw sockets, when packets are first waited
+/// for).
+enum Inner {
+    Unbound(Box<AnyUnboundSocket>),
+    Bound(Arc<AnyBoundSocket>),
+}
+
+impl Inner {
+    fn bind(
+        self,
+        endpoint: &IpEndpoint,
+    ) -> core::result::Result<Arc<AnyBoundSocket>, (Error, Self)> {
+        let unbound_socket = match self {
+            Inner::Unbound(unbound_socket) => unbound_socket,
+            Inner::Bound(bound_socket) => {
+                return Err((
+                    Error::with_message(Errno::EINVAL, "the socket is already bound to an address"),
+                    Inner::Bound(bound_socket),
+                ));
+            }
+        };
+
+        match bind_socket(unbound_socket, endpoint, false) {
+            Ok(bound_socket) => Ok(bound_socket),
+            Err((err, unbound_socket)) => Err((err, Inner::Unbound(unbound_socket))),
+        }
+    }
+
+    fn bind_to_ephemeral_endpoint(
+        self,
+        remote_endpoint: &IpEndpoint,
+    ) -> core::result::Result<Arc<AnyBoundSocket>, (Error, Self)> {
+        if let Inner::Bound(bound_socket) = self {
+            return Ok(bound_socket);
+        }
+
+        let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err((err, self)),
+        };
+        self.bind(&endpoint)
+    }
+}
+
+impl RawSocket {
+    /// Creates a raw socket for `ip_protocol`.
+    ///
+    /// Only privileged processes can create raw sockets.
+    pub fn new(ip_protocol: IpProtocol, nonblocking: bool) -> Result<Arc<Self>> {
+        // TODO: Check `CAP_NET_RAW` instead once capabilities are supported.
+        if !credentials().euid().is_root() {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "only privileged processes can create raw sockets"
+            );
+        }
+
+        Ok(Arc::new_cyclic(|me| {
+            let unbound_socket =
+                AnyUnboundSocket::new_raw(IpVersion::Ipv4, ip_protocol, me.clone() as _);
+            Self {
+                ip_protocol,
+                inner: RwLock::new(Takeable::new(Inner::Unbound(Box::new(unbound_socket)))),
+                remote_addr: RwLock::new(None),
+                nonblocking: AtomicBool::new(nonblocking),
+                pollee: Pollee::new(IoEvents::empty()),
+            }
+        }))
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::SeqCst)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::SeqCst);
+    }
+
+    /// Returns whether the IPv4 header is supplied by the user.
+    fn is_hdrincl(&self) -> bool {
+        self.ip_protocol == IpProtocol::from(IPPROTO_RAW)
+    }
+
+    fn try_bind_ephemeral(&self, remote_addr: &Ipv4Address) -> Result<()> {
+        // Fast path
+        if let Inner::Bound(_) = self.inner.read().as_ref() {
+            return Ok(());
+        }
+
+        // Slow path
+        let remote_endpoint = IpEndpoint::new(IpAddress::Ipv4(*remote_addr), 0);
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound_socket = match owned_inner.bind_to_ephemeral_endpoint(&remote_endpoint) {
+                Ok(bound_socket) => bound_socket,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            self.pollee.reset_events();
+            update_io_events(&bound_socket, &self.pollee);
+            (Inner::Bound(bound_socket), Ok(()))
+        })
+    }
+
+    /// Binds the socket to the unspecified address if it is not bound yet.
+    ///
+    /// Like Linux, a raw socket receives packets without being bound or sending any packet
+    /// first, so it is attached to an iface before waiting for packets.
+    fn try_bind_unspecified(&self) -> Result<()> {
+        // Fast path
+        if let Inner::Bound(_) = self.inner.read().as_ref() {
+            return Ok(());
+        }
+
+        // Slow path
+        let endpoint = IpEndpoint::new(IpAddress::Ipv4(Ipv4Address::UNSPECIFIED), 0);
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            if let Inner::Bound(_) = owned_inner {
+                return (owned_inner, Ok(()));
+            }
+            let bound_socket = match owned_inner.bind(&endpoint) {
+                Ok(bound_socket) => bound_socket,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            self.pollee.reset_events();
+            update_io_events(&bound_socket, &self.pollee);
+            (Inner::Bound(bound_socket), Ok(()))
+        })
+    }
+
+    /// Receives a packet, returning the real length of the packet.
+    fn try_recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound");
+        };
+
+        let result = bound_socket.raw_with(|socket: &mut RawIpSocket| {
+            let packet = socket.recv()?;
+            // Packets are checked by smoltcp before being delivered to raw sockets, so they
+            // always contain valid IPv4 headers.
+            let src_addr = Ipv4Packet::new_unchecked(packet).src_addr();
+            let copy_len = packet.len().min(buf.len());
+            buf[..copy_len].copy_from_slice(&packet[..copy_len]);
+            Ok((packet.len(), src_addr))
+        });
+        update_io_events(bound_socket, &self.pollee);
+
+        match result {
+            Ok((packet_len, src_addr)) => Ok((packet_len, SocketAddr::IPv4(src_addr, 0))),
+            Err(RecvError::Exhausted) => {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
+            }
+        }
+    }
+
+    fn try_sendto(&self, packet: &[u8], flags: SendRecvFlags) -> Result<()> {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound")
+        };
+
+        let result = bound_socket.raw_with(|socket: &mut RawIpSocket| socket.send_slice(packet));
+        update_io_events(bound_socket, &self.pollee);
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(SendError::BufferFull) => {
+                return_errno_with_message!(Errno::EAGAIN, "the send buffer is full")
+            }
+        }
+    }
+
+    /// Builds the IPv4 packet that carries the user data in `buf`.
+    fn build_packet(&self, buf: &[u8], remote_addr: &Ipv4Address) -> Result<Vec<u8>> {
+        let local_addr = self.local_ipv4_addr();
+
+        // FIXME: smoltcp drops packets whose protocol does not match that of the raw socket, so
+        // `IPPROTO_RAW` sockets can only send packets whose protocol is `IPPROTO_RAW` for now.
+        if self.is_hdrincl() {
+            let mut packet_buf = buf.to_vec();
+            let mut packet = Ipv4Packet::new_checked(&mut packet_buf[..]).map_err(|_| {
+                Error::with_message(
+                    Errno::EINVAL,
+                    "the packet does not have a valid IPv4 header",
+                )
+            })?;
+            // Like Linux, the kernel fills in the source address if it is left unspecified.
+            if packet.src_addr().is_unspecified() {
+                packet.set_src_addr(local_addr);
+                packet.fill_checksum();
+            }
+            return Ok(packet_buf);
+        }
+
+        let ipv4_repr = Ipv4Repr {
+            src_addr: local_addr,
+            dst_addr: *remote_addr,
+            next_header: self.ip_protocol,
+            payload_len: buf.len(),
+            hop_limit: DEFAULT_HOP_LIMIT,
+        };
+        let mut packet_buf = vec![0u8; ipv4_repr.buffer_len() + buf.len()];
+        let mut packet = Ipv4Packet::new_unchecked(&mut packet_buf[..]);
+        ipv4_repr.emit(&mut packet, &ChecksumCapabilities::default());
+        packet.payload_mut().copy_from_slice(buf);
+        Ok(packet_buf)
+    }
+
+    /// Returns the local IPv4 address that is used as the source address of sent packets.
+    fn local_ipv4_addr(&self) -> Ipv4Address {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return Ipv4Address::UNSPECIFIED;
+        };
+
+        match bound_socket.local_endpoint().unwrap().addr {
+            IpAddress::Ipv4(addr) if !addr.is_unspecified() => addr,
+            _ => bound_socket
+                .iface()
+                .ipv4_addr()
+                .unwrap_or(Ipv4Address::UNSPECIFIED),
+        }
+    }
+
+    /// Receives a packet, returning the real length of the packet.
+    fn recv(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        self.try_bind_unspecified()?;
+        poll_ifaces();
+        if self.is_nonblocking() {
+            self.try_recvfrom(buf, flags)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_recvfrom(buf, flags))
+        }
+    }
+
+    // TODO: Support timeout
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+
+    fn update_io_events(&self) {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return;
+        };
+        update_io_events(bound_socket, &self.pollee);
+    }
+}
+
+/// Converts a socket address to an IPv4 address.
+///
+/// The port has no meaning for raw sockets and is the ICMP identifier for ping sockets, so it is
+/// left to the caller.
+fn addr_to_ipv4(socket_addr: SocketAddr) -> Result<(Ipv4Address, u16)> {
+    let endpoint = socket_addr_to_endpoint(socket_addr, IpVersion::Ipv4)?;
+    match endpoint.addr {
+        IpAddress::Ipv4(addr) => Ok((addr, endpoint.port)),
+        _ => unreachable!("the endpoint of an IPv4 socket should be an IPv4 endpoint"),
+    }
+}
+
+fn update_io_events(bound_socket: &AnyBoundSocket, pollee: &Pollee) {
+    bound_socket.raw_with(|socket: &mut RawIpSocket| {
+        if socket.can_recv() {
+            pollee.add_events(IoEvents::IN);
+        } else {
+            pollee.del_events(IoEvents::IN);
+        }
+
+        if socket.can_send() {
+            pollee.add_events(IoEvents::OUT);
+        } else {
+            pollee.del_events(IoEvents::OUT);
+        }
+    });
+}
+
+impl FileLike for RawSocket {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        // FIXME: respect flags
+        let flags = SendRecvFlags::empty();
+        let (recv_len, _) = self.recvfrom(buf, flags)?;
+        Ok(recv_len)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        // FIXME: set correct flags
+        let flags = SendRecvFlags::empty();
+        self.sendto(buf, None, flags)
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        // Binding to the unspecified address only fails if no iface is available, in which
+        // case no packets can be received anyway.
+        let _ = self.try_bind_unspecified();
+        self.pollee.poll(mask, poller)
+    }
+
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        if new_flags.contains(StatusFlags::O_NONBLOCK) {
+            self.set_nonblocking(true);
+        } else {
+            self.set_nonblocking(false);
+        }
+        Ok(())
+    }
+}
+
+impl Socket for RawSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let endpoint = IpEndpoint::new(IpAddress::Ipv4(addr_to_ipv4(socket_addr)?.0), 0);
+
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound_socket = match owned_inner.bind(&endpoint) {
+                Ok(bound_socket) => bound_socket,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            self.pollee.reset_events();
+            update_io_events(&bound_socket, &self.pollee);
+            (Inner::Bound(bound_socket), Ok(()))
+        })
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let remote_addr = addr_to_ipv4(socket_addr)?.0;
+
+        self.try_bind_ephemeral(&remote_addr)?;
+        *self.remote_addr.write() = Some(remote_addr);
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let inner = self.inner.read();
+        match inner.as_ref() {
+            Inner::Unbound(_) => Ok(unspecified_local_endpoint(IpVersion::Ipv4).into()),
+            Inner::Bound(bound_socket) => Ok(bound_socket.local_endpoint().unwrap().into()),
+        }
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        self.remote_addr
+            .read()
+            .map(|remote_addr| SocketAddr::IPv4(remote_addr, 0))
+            .ok_or_else(|| Error::with_message(Errno::ENOTCONN, "the socket is not connected"))
+    }
+
+    // FIXME: respect RecvFromFlags
+    fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        debug_assert!(flags.is_all_supported());
+
+        let (packet_len, addr) = self.recv(buf, flags)?;
+        Ok((packet_len.min(buf.len()), addr))
+    }
+
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        debug_assert!(flags.is_all_supported());
+
+        let (packet_len, addr) = self.recv(buf, flags)?;
+        let mut message_header = MessageHeader::new(Some(addr), Vec::new());
+        if packet_len > buf.len() {
+            message_header.set_truncated();
+        }
+        Ok((packet_len.min(buf.len()), message_header))
+    }
+
+    fn sendto(
+        &self,
+        buf: &[u8],
+        remote: Option<SocketAddr>,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        debug_assert!(flags.is_all_supported());
+
+        let remote_addr = match remote {
+            Some(remote_addr) => addr_to_ipv4(remote_addr)?.0,
+            None => self.remote_addr.read().ok_or_else(|| {
+                Error::with_message(
+                    Errno::EDESTADDRREQ,
+                    "the destination address is not specified",
+                )
+            })?,
+        };
+        self.try_bind_ephemeral(&remote_addr)?;
+
+        let packet = self.build_packet(buf, &remote_addr)?;
+        if packet.len() > RAW_SEND_PAYLOAD_LEN {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        // TODO: Block if the send buffer is full
+        self.try_sendto(&packet, flags)?;
+        poll_ifaces();
+        Ok(buf.len())
+    }
+}
+
+impl Observer<()> for RawSocket {
+    fn on_events(&self, events: &()) {
+        self.update_io_events();
+    }
+}
+
+const IPPROTO_RAW: u8 = 255;
+const DEFAULT_HOP_LIMIT: u8 = 64;
@@ -0,0 +1,366 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use smoltcp::socket::icmp::{RecvError, SendError};
+use takeable::Takeable;
+
+use super::{addr_to_ipv4, Inner};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    net::{
+        iface::{
+            AnyBoundSocket, AnyUnboundSocket, IpAddress, IpEndpoint, IpVersion, Ipv4Address,
+            RawIcmpSocket, RAW_SEND_PAYLOAD_LEN,
+        },
+        poll_ifaces,
+        socket::{
+            ip::unspecified_local_endpoint,
+            util::{
+                message_header::MessageHeader, send_recv_flags::SendRecvFlags,
+                socket_addr::SocketAddr,
+            },
+            Socket,
+        },
+    },
+    prelude::*,
+    process::signal::{Pollee, Poller},
+};
+
+/// An ICMP echo socket (i.e., `SOCK_DGRAM` with `IPPROTO_ICMP`), a.k.a. a ping socket.
+///
+/// Unlike raw sockets, ping sockets can be used by unprivileged processes. They can only send
+/// ICMP echo requests and receive the matching echo replies. The identifier of the echo requests
+/// is the port that the socket is bound to, and the checksum is computed by the kernel.
+pub struct PingSocket {
+    inner: RwLock<Takeable<Inner>>,
+    remote_addr: RwLock<Option<Ipv4Address>>,
+    nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+impl PingSocket {
+    pub fn new(nonblocking: bool) -> Arc<Self> {
+        Arc::new_cyclic(|me| {
+            let unbound_socket = AnyUnboundSocket::new_icmp(me.clone() as _);
+            Self {
+                inner: RwLock::new(Takeable::new(Inner::Unbound(Box::new(unbound_socket)))),
+                remote_addr: RwLock::new(None),
+                nonblocking: AtomicBool::new(nonblocking),
+                pollee: Pollee::new(IoEvents::empty()),
+            }
+        })
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::SeqCst)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::SeqCst);
+    }
+
+    fn try_bind_ephemeral(&self, remote_addr: &Ipv4Address) -> Result<()> {
+        // Fast path
+        if let Inner::Bound(_) = self.inner.read().as_ref() {
+            return Ok(());
+        }
+
+        // Slow path
+        let remote_endpoint = IpEndpoint::new(IpAddress::Ipv4(*remote_addr), 0);
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound_socket = match owned_inner.bind_to_ephemeral_endpoint(&remote_endpoint) {
+                Ok(bound_socket) => bound_socket,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            self.pollee.reset_events();
+            update_io_events(&bound_socket, &self.pollee);
+            (Inner::Bound(bound_socket), Ok(()))
+        })
+    }
+
+    /// Receives a packet, returning the real length of the packet.
+    fn try_recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound");
+        };
+
+        let result = bound_socket.raw_with(|socket: &mut RawIcmpSocket| loop {
+            let (packet, src_addr) = socket.recv()?;
+            // smoltcp also delivers echo requests with the same identifier, which should not be
+            // seen by ping sockets.
+            if packet.first() != Some(&ICMP_ECHO_REPLY) {
+                continue;
+            }
+            let copy_len = packet.len().min(buf.len());
+            buf[..copy_len].copy_from_slice(&packet[..copy_len]);
+            break Ok((packet.len(), src_addr));
+        });
+        update_io_events(bound_socket, &self.pollee);
+
+        match result {
+            Ok((packet_len, IpAddress::Ipv4(src_addr))) => {
+                Ok((packet_len, SocketAddr::IPv4(src_addr, 0)))
+            }
+            Ok((_, IpAddress::Ipv6(_))) => {
+                unreachable!("an IPv4 ping socket should not receive IPv6 packets")
+            }
+            Err(RecvError::Exhausted) => {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
+            }
+        }
+    }
+
+    fn try_sendto(
+        &self,
+        buf: &[u8],
+        remote_addr: &Ipv4Address,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound")
+        };
+
+        // The identifier is always overwritten with the bound port, so that the echo replies can
+        // be delivered back to this socket.
+        let ident = bound_socket.local_endpoint().unwrap().port;
+        let mut packet = buf.to_vec();
+        packet[ICMP_ECHO_IDENT_RANGE].copy_from_slice(&ident.to_be_bytes());
+
+        let result = bound_socket.raw_with(|socket: &mut RawIcmpSocket| {
+            socket.send_slice(&packet, IpAddress::Ipv4(*remote_addr))
+        });
+        update_io_events(bound_socket, &self.pollee);
+
+        match result {
+            Ok(()) => Ok(buf.len()),
+            Err(SendError::BufferFull) => {
+                return_errno_with_message!(Errno::EAGAIN, "the send buffer is full")
+            }
+            Err(SendError::Unaddressable) => {
+                return_errno_with_message!(Errno::EINVAL, "the destionation address is invalid")
+            }
+        }
+    }
+
+    /// Receives a packet, returning the real length of the packet.
+    fn recv(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        poll_ifaces();
+        if self.is_nonblocking() {
+            self.try_recvfrom(buf, flags)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_recvfrom(buf, flags))
+        }
+    }
+
+    // TODO: Support timeout
+    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    where
+        F: FnMut() -> Result<R>,
+    {
+        let poller = Poller::new();
+
+        loop {
+            match cond() {
+                Err(err) if err.error() == Errno::EAGAIN => (),
+                result => return result,
+            };
+
+            let events = self.poll(mask, Some(&poller));
+            if !events.is_empty() {
+                continue;
+            }
+
+            poller.wait()?;
+        }
+    }
+
+    fn update_io_events(&self) {
+        let inner = self.inner.read();
+        let Inner::Bound(bound_socket) = inner.as_ref() else {
+            return;
+        };
+        update_io_events(bound_socket, &self.pollee);
+    }
+}
+
+/// Checks that `buf` is an ICMP echo request, which is the only message that ping sockets can
+/// send.
+fn check_echo_request(buf: &[u8]) -> Result<()> {
+    if buf.len() < ICMP_ECHO_HEADER_LEN {
+        return_errno_with_message!(Errno::EINVAL, "the ICMP header is incomplete");
+    }
+    if buf.len() > RAW_SEND_PAYLOAD_LEN {
+        return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+    }
+    if buf[0] != ICMP_ECHO_REQUEST || buf[1] != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the message is not an ICMP echo request");
+    }
+    Ok(())
+}
+
+fn update_io_events(bound_socket: &AnyBoundSocket, pollee: &Pollee) {
+    bound_socket.raw_with(|socket: &mut RawIcmpSocket| {
+        if socket.can_recv() {
+            pollee.add_events(IoEvents::IN);
+        } else {
+            pollee.del_events(IoEvents::IN);
+        }
+
+        if socket.can_send() {
+            pollee.add_events(IoEvents::OUT);
+        } else {
+            pollee.del_events(IoEvents::OUT);
+        }
+    });
+}
+
+impl FileLike for PingSocket {
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        // FIXME: respect flags
+        let flags = SendRecvFlags::empty();
+        let (recv_len, _) = self.recvfrom(buf, flags)?;
+        Ok(recv_len)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        // FIXME: set correct flags
+        let flags = SendRecvFlags::empty();
+        self.sendto(buf, None, flags)
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        if new_flags.contains(StatusFlags::O_NONBLOCK) {
+            self.set_nonblocking(true);
+        } else {
+            self.set_nonblocking(false);
+        }
+        Ok(())
+    }
+}
+
+impl Socket for PingSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        // The port is the identifier of the echo requests. If it is zero, an ephemeral identifier
+        // will be allocated.
+        let (addr, ident) = addr_to_ipv4(socket_addr)?;
+        let endpoint = IpEndpoint::new(IpAddress::Ipv4(addr), ident);
+
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound_socket = match owned_inner.bind(&endpoint) {
+                Ok(bound_socket) => bound_socket,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            self.pollee.reset_events();
+            update_io_events(&bound_socket, &self.pollee);
+            (Inner::Bound(bound_socket), Ok(()))
+        })
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let (remote_addr, _) = addr_to_ipv4(socket_addr)?;
+
+        self.try_bind_ephemeral(&remote_addr)?;
+        *self.remote_addr.write() = Some(remote_addr);
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let inner = self.inner.read();
+        match inner.as_ref() {
+            Inner::Unbound(_) => Ok(unspecified_local_endpoint(IpVersion::Ipv4).into()),
+            Inner::Bound(bound_socket) => Ok(bound_socket.local_endpoint().unwrap().into()),
+        }
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        self.remote_addr
+            .read()
+            .map(|remote_addr| SocketAddr::IPv4(remote_addr, 0))
+            .ok_or_else(|| Error::with_message(Errno::ENOTCONN, "the socket is not connected"))
+    }
+
+    // FIXME: respect RecvFromFlags
+    fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        debug_assert!(flags.is_all_supported());
+
+        let (packet_len, addr) = self.recv(buf, flags)?;
+        Ok((packet_len.min(buf.len()), addr))
+    }
+
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        debug_assert!(flags.is_all_supported());
+
+        let (packet_len, addr) = self.recv(buf, flags)?;
+        let mut message_header = MessageHeader::new(Some(addr), Vec::new());
+        if packet_len > buf.len() {
+            message_header.set_truncated();
+        }
+        Ok((packet_len.min(buf.len()), message_header))
+    }
+
+    fn sendto(
+        &self,
+        buf: &[u8],
+        remote: Option<SocketAddr>,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        debug_assert!(flags.is_all_supported());
+
+        check_echo_request(buf)?;
+
+        let remote_addr = match remote {
+            Some(remote_addr) => addr_to_ipv4(remote_addr)?.0,
+            None => self.remote_addr.read().ok_or_else(|| {
+                Error::with_message(
+                    Errno::EDESTADDRREQ,
+                    "the destination address is not specified",
+                )
+            })?,
+        };
+        self.try_bind_ephemeral(&remote_addr)?;
+
+        // TODO: Block if the send buffer is full
+        let sent_bytes = self.try_sendto(buf, &remote_addr, flags)?;
+        poll_ifaces();
+        Ok(sent_bytes)
+    }
+}
+
+impl Observer<()> for PingSocket {
+    fn on_events(&self, events: &()) {
+        self.update_io_events();
+    }
+}
+
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMP_ECHO_REQUEST: u8 = 8;
+/// The length of the ICMP echo header (type, code, checksum, identifier and sequence number).
+const ICMP_ECHO_HEADER_LEN: usize = 8;
+const ICMP_ECHO_IDENT_RANGE: core::ops::Range<usize> = 4..6;
@@ -66,7 +66,12 @@ impl ConnectedStream {
     }
 
     pub fn local_endpoint(&self) -> IpEndpoint {
-        self.bound_socket.local_endpoint().unwrap()
+        // The bound address may be unspecified if the connection is accepted from a socket
+        // listening at an unspecified address. In that case, the raw socket knows the actual
+        // local endpoint.
+        self.bound_socket
+            .raw_with(|socket: &mut RawTcpSocket| socket.local_endpoint())
+            .unwrap_or_else(|| self.bound_socket.local_endpoint().unwrap())
     }
 
     pub fn remote_endpoint(&self) -> IpEndpoint {
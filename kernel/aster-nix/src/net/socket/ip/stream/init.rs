@@ -54,7 +54,10 @@ impl InitStream {
         self,
         remote_endpoint: &IpEndpoint,
     ) -> core::result::Result<Arc<AnyBoundSocket>, (Error, Self)> {
-        let endpoint = get_ephemeral_endpoint(remote_endpoint);
+        let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err((err, self)),
+        };
         self.bind(&endpoint)
     }
 
@@ -71,7 +74,11 @@ impl InitStream {
             .map_err(|(err, bound_socket)| (err, InitStream::Bound(bound_socket)))
     }
 
-    pub fn listen(self, backlog: usize) -> core::result::Result<ListenStream, (Error, Self)> {
+    pub fn listen(
+        self,
+        backlog: usize,
+        is_v6only: bool,
+    ) -> core::result::Result<ListenStream, (Error, Self)> {
         let InitStream::Bound(bound_socket) = self else {
             // FIXME: The socket should be bound to INADDR_ANY (i.e., 0.0.0.0) with an ephemeral
             // port. However, INADDR_ANY is not yet supported, so we need to return an error first.
@@ -82,7 +89,7 @@ impl InitStream {
             ));
         };
 
-        ListenStream::new(bound_socket, backlog)
+        ListenStream::new(bound_socket, backlog, is_v6only)
             .map_err(|(err, bound_socket)| (err, InitStream::Bound(bound_socket)))
     }
 
@@ -4,7 +4,13 @@ use smoltcp::socket::tcp::ListenError;
 use super::connected::ConnectedStream;
 use crate::{
     events::{IoEvents, Observer},
-    net::iface::{AnyBoundSocket, AnyUnboundSocket, BindPortConfig, IpEndpoint, RawTcpSocket},
+    net::{
+        iface::{
+            AnyBoundSocket, AnyUnboundSocket, BindPortConfig, Iface, IpEndpoint, IpListenEndpoint,
+            RawTcpSocket,
+        },
+        IFACES,
+    },
     prelude::*,
     process::signal::Pollee,
 };
@@ -15,17 +21,21 @@ pub struct ListenStream {
     bound_socket: Arc<AnyBoundSocket>,
     /// Backlog sockets listening at the local endpoint
     backlog_sockets: RwLock<Vec<BacklogSocket>>,
+    /// Whether only IPv6 connections are accepted (i.e., `IPV6_V6ONLY` is set)
+    is_v6only: bool,
 }
 
 impl ListenStream {
     pub fn new(
         bound_socket: Arc<AnyBoundSocket>,
         backlog: usize,
+        is_v6only: bool,
     ) -> core::result::Result<Self, (Error, Arc<AnyBoundSocket>)> {
         let listen_stream = Self {
             backlog,
             bound_socket,
             backlog_sockets: RwLock::new(Vec::new()),
+            is_v6only,
         };
         if let Err(err) = listen_stream.fill_backlog_sockets() {
             return Err((err, listen_stream.bound_socket));
@@ -38,20 +48,64 @@ impl ListenStream {
         let mut backlog_sockets = self.backlog_sockets.write();
 
         let backlog = self.backlog;
-        let current_backlog_len = backlog_sockets.len();
-        debug_assert!(backlog >= current_backlog_len);
-        if backlog == current_backlog_len {
-            return Ok(());
+        for iface in self.listen_ifaces() {
+            for listen_endpoint in self.listen_endpoints(&iface) {
+                let current_backlog_len = backlog_sockets
+                    .iter()
+                    .filter(|backlog_socket| {
+                        backlog_socket.iface().name() == iface.name()
+                            && backlog_socket.listen_endpoint() == listen_endpoint
+                    })
+                    .count();
+                debug_assert!(backlog >= current_backlog_len);
+
+                for _ in current_backlog_len..backlog {
+                    let backlog_socket =
+                        BacklogSocket::new(&self.bound_socket, &iface, listen_endpoint)?;
+                    backlog_sockets.push(backlog_socket);
+                }
+            }
         }
 
-        for _ in current_backlog_len..backlog {
-            let backlog_socket = BacklogSocket::new(&self.bound_socket)?;
-            backlog_sockets.push(backlog_socket);
+        Ok(())
+    }
+
+    /// Returns the ifaces that the backlog sockets listen at.
+    ///
+    /// If the socket is bound to an unspecified address, connections from all ifaces can be
+    /// accepted, so there are backlog sockets on every iface.
+    fn listen_ifaces(&self) -> Vec<Arc<dyn Iface>> {
+        if self.bound_socket.listen_endpoint().addr.is_some() {
+            vec![self.bound_socket.iface().clone()]
+        } else {
+            IFACES.get().unwrap().clone()
         }
+    }
 
-        Ok(())
+    /// Returns the endpoints that the backlog sockets on `iface` listen at.
+    ///
+    /// An `IPV6_V6ONLY` socket bound to an unspecified address only listens at the IPv6
+    /// addresses of each iface, so that IPv4 connections are refused before the handshake.
+    ///
+    /// FIXME: The IPv6 addresses that are configured after the socket starts listening (e.g.,
+    /// by SLAAC) are not listened at.
+    fn listen_endpoints(&self, iface: &Arc<dyn Iface>) -> Vec<IpListenEndpoint> {
+        let listen_endpoint = self.bound_socket.listen_endpoint();
+        if listen_endpoint.addr.is_some() || !self.is_v6only {
+            return vec![listen_endpoint];
+        }
+
+        iface
+            .ipv6_addrs()
+            .into_iter()
+            .map(|ipv6_addr| IpListenEndpoint {
+                addr: Some(ipv6_addr.into()),
+                port: listen_endpoint.port,
+            })
+            .collect()
     }
 
+    /// Accepts a pending connection.
     pub fn try_accept(&self) -> Result<ConnectedStream> {
         let mut backlog_sockets = self.backlog_sockets.write();
 
@@ -63,7 +117,11 @@ impl ListenStream {
             })?;
         let active_backlog_socket = backlog_sockets.remove(index);
 
-        match BacklogSocket::new(&self.bound_socket) {
+        match BacklogSocket::new(
+            &self.bound_socket,
+            active_backlog_socket.iface(),
+            active_backlog_socket.listen_endpoint(),
+        ) {
             Ok(backlog_socket) => backlog_sockets.push(backlog_socket),
             Err(err) => (),
         }
@@ -119,12 +177,17 @@ impl ListenStream {
 
 struct BacklogSocket {
     bound_socket: Arc<AnyBoundSocket>,
+    listen_endpoint: IpListenEndpoint,
 }
 
 impl BacklogSocket {
     // FIXME: All of the error codes below seem to have no Linux equivalents, and I see no reason
     // why the error may occur. Perhaps it is better to call `unwrap()` directly?
-    fn new(bound_socket: &Arc<AnyBoundSocket>) -> Result<Self> {
+    fn new(
+        bound_socket: &Arc<AnyBoundSocket>,
+        iface: &Arc<dyn Iface>,
+        listen_endpoint: IpListenEndpoint,
+    ) -> Result<Self> {
         let local_endpoint = bound_socket.local_endpoint().ok_or(Error::with_message(
             Errno::EINVAL,
             "the socket is not bound",
@@ -132,17 +195,19 @@ impl BacklogSocket {
 
         let unbound_socket = Box::new(AnyUnboundSocket::new_tcp(Weak::<()>::new()));
         let bound_socket = {
-            let iface = bound_socket.iface();
             let bind_port_config = BindPortConfig::new(local_endpoint.port, true)?;
             iface
-                .bind_socket(unbound_socket, bind_port_config)
+                .bind_socket(unbound_socket, local_endpoint.addr, bind_port_config)
                 .map_err(|(err, _)| err)?
         };
 
         let result = bound_socket
-            .raw_with(|raw_tcp_socket: &mut RawTcpSocket| raw_tcp_socket.listen(local_endpoint));
+            .raw_with(|raw_tcp_socket: &mut RawTcpSocket| raw_tcp_socket.listen(listen_endpoint));
         match result {
-            Ok(()) => Ok(Self { bound_socket }),
+            Ok(()) => Ok(Self {
+                bound_socket,
+                listen_endpoint,
+            }),
             Err(ListenError::Unaddressable) => {
                 return_errno_with_message!(Errno::EINVAL, "the listening address is invalid")
             }
@@ -162,6 +227,14 @@ impl BacklogSocket {
             .raw_with(|socket: &mut RawTcpSocket| socket.remote_endpoint())
     }
 
+    fn iface(&self) -> &Arc<dyn Iface> {
+        self.bound_socket.iface()
+    }
+
+    fn listen_endpoint(&self) -> IpListenEndpoint {
+        self.listen_endpoint
+    }
+
     fn into_bound_socket(self) -> Arc<AnyBoundSocket> {
         self.bound_socket
     }
@@ -7,16 +7,20 @@ use connecting::ConnectingStream;
 use init::InitStream;
 use listen::ListenStream;
 use options::{Congestion, MaxSegment, NoDelay, WindowClamp};
-use smoltcp::wire::IpEndpoint;
 use takeable::Takeable;
 use util::{TcpOptionSet, DEFAULT_MAXSEG};
 
-use super::UNSPECIFIED_LOCAL_ENDPOINT;
+use super::{
+    common::{endpoint_to_socket_addr, is_addr_allowed, socket_addr_to_endpoint},
+    options::V6Only,
+    unspecified_local_endpoint,
+};
 use crate::{
     events::{IoEvents, Observer},
     fs::{file_handle::FileLike, utils::StatusFlags},
     match_sock_option_mut, match_sock_option_ref,
     net::{
+        iface::{IpEndpoint, IpVersion},
         poll_ifaces,
         socket::{
             options::{
@@ -46,9 +50,11 @@ use self::connecting::NonConnectedStream;
 pub use self::util::CongestionControl;
 
 pub struct StreamSocket {
+    ip_version: IpVersion,
     options: RwLock<OptionSet>,
     state: RwLock<Takeable<State>>,
     is_nonblocking: AtomicBool,
+    is_v6only: AtomicBool,
     pollee: Pollee,
 }
 
@@ -78,28 +84,36 @@ impl OptionSet {
 }
 
 impl StreamSocket {
-    pub fn new(nonblocking: bool) -> Arc<Self> {
+    pub fn new(ip_version: IpVersion, nonblocking: bool) -> Arc<Self> {
         Arc::new_cyclic(|me| {
             let init_stream = InitStream::new(me.clone() as _);
             let pollee = Pollee::new(IoEvents::empty());
             Self {
+                ip_version,
                 options: RwLock::new(OptionSet::new()),
                 state: RwLock::new(Takeable::new(State::Init(init_stream))),
                 is_nonblocking: AtomicBool::new(nonblocking),
+                is_v6only: AtomicBool::new(false),
                 pollee,
             }
         })
     }
 
-    fn new_connected(connected_stream: ConnectedStream) -> Arc<Self> {
+    fn new_connected(
+        connected_stream: ConnectedStream,
+        ip_version: IpVersion,
+        is_v6only: bool,
+    ) -> Arc<Self> {
         Arc::new_cyclic(move |me| {
             let pollee = Pollee::new(IoEvents::empty());
             connected_stream.set_observer(me.clone() as _);
             connected_stream.init_pollee(&pollee);
             Self {
+                ip_version,
                 options: RwLock::new(OptionSet::new()),
                 state: RwLock::new(Takeable::new(State::Connected(connected_stream))),
                 is_nonblocking: AtomicBool::new(false),
+                is_v6only: AtomicBool::new(is_v6only),
                 pollee,
             }
         })
@@ -113,6 +127,15 @@ impl StreamSocket {
         self.is_nonblocking.store(nonblocking, Ordering::Relaxed);
     }
 
+    fn is_v6only(&self) -> bool {
+        self.is_v6only.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the socket can communicate with the endpoint.
+    fn is_endpoint_allowed(&self, endpoint: &IpEndpoint) -> bool {
+        is_addr_allowed(self.ip_version, self.is_v6only(), &endpoint.addr)
+    }
+
     fn start_connect(&self, remote_endpoint: &IpEndpoint) -> Result<()> {
         let mut state = self.state.write();
 
@@ -177,8 +200,12 @@ impl StreamSocket {
         listen_stream.update_io_events(&self.pollee);
 
         let remote_endpoint = connected_stream.remote_endpoint();
-        let accepted_socket = Self::new_connected(connected_stream);
-        Ok((accepted_socket, remote_endpoint.into()))
+        let accepted_socket =
+            Self::new_connected(connected_stream, self.ip_version, self.is_v6only());
+        Ok((
+            accepted_socket,
+            endpoint_to_socket_addr(remote_endpoint, self.ip_version),
+        ))
     }
 
     fn try_recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
@@ -196,7 +223,11 @@ impl StreamSocket {
 
         let recv_bytes = connected_stream.try_recvfrom(buf, flags)?;
         connected_stream.update_io_events(&self.pollee);
-        Ok((recv_bytes, connected_stream.remote_endpoint().into()))
+        let remote_endpoint = connected_stream.remote_endpoint();
+        Ok((
+            recv_bytes,
+            endpoint_to_socket_addr(remote_endpoint, self.ip_version),
+        ))
     }
 
     fn try_sendto(&self, buf: &[u8], flags: SendRecvFlags) -> Result<usize> {
@@ -327,7 +358,13 @@ impl FileLike for StreamSocket {
 
 impl Socket for StreamSocket {
     fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
-        let endpoint = socket_addr.try_into()?;
+        let endpoint = socket_addr_to_endpoint(socket_addr, self.ip_version)?;
+        if !self.is_endpoint_allowed(&endpoint) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "an IPv4-mapped address cannot be bound to an IPv6-only socket"
+            );
+        }
 
         let mut state = self.state.write();
 
@@ -355,7 +392,13 @@ impl Socket for StreamSocket {
 
     // TODO: Support nonblocking mode
     fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
-        let remote_endpoint = socket_addr.try_into()?;
+        let remote_endpoint = socket_addr_to_endpoint(socket_addr, self.ip_version)?;
+        if !self.is_endpoint_allowed(&remote_endpoint) {
+            return_errno_with_message!(
+                Errno::ENETUNREACH,
+                "an IPv6-only socket cannot connect to an IPv4-mapped address"
+            );
+        }
         self.start_connect(&remote_endpoint)?;
 
         poll_ifaces();
@@ -382,7 +425,7 @@ impl Socket for StreamSocket {
                 }
             };
 
-            let listen_stream = match init_stream.listen(backlog) {
+            let listen_stream = match init_stream.listen(backlog, self.is_v6only()) {
                 Ok(listen_stream) => listen_stream,
                 Err((err, init_stream)) => {
                     return (State::Init(init_stream), Err(err));
@@ -417,12 +460,12 @@ impl Socket for StreamSocket {
         let local_endpoint = match state.as_ref() {
             State::Init(init_stream) => init_stream
                 .local_endpoint()
-                .unwrap_or(UNSPECIFIED_LOCAL_ENDPOINT),
+                .unwrap_or(unspecified_local_endpoint(self.ip_version)),
             State::Connecting(connecting_stream) => connecting_stream.local_endpoint(),
             State::Listen(listen_stream) => listen_stream.local_endpoint(),
             State::Connected(connected_stream) => connected_stream.local_endpoint(),
         };
-        Ok(local_endpoint.into())
+        Ok(endpoint_to_socket_addr(local_endpoint, self.ip_version))
     }
 
     fn peer_addr(&self) -> Result<SocketAddr> {
@@ -434,7 +477,7 @@ impl Socket for StreamSocket {
             State::Connecting(connecting_stream) => connecting_stream.remote_endpoint(),
             State::Connected(connected_stream) => connected_stream.remote_endpoint(),
         };
-        Ok(remote_endpoint.into())
+        Ok(endpoint_to_socket_addr(remote_endpoint, self.ip_version))
     }
 
     fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
@@ -493,6 +536,13 @@ impl Socket for StreamSocket {
                 let reuse_port = options.socket.reuse_port();
                 socket_reuse_port.set(reuse_port);
             },
+            // IPv6 Options
+            ipv6_v6only: V6Only => {
+                if self.ip_version != IpVersion::Ipv6 {
+                    return_errno_with_message!(Errno::ENOPROTOOPT, "the socket is not an IPv6 socket");
+                }
+                ipv6_v6only.set(self.is_v6only());
+            },
             // Tcp Options
             tcp_no_delay: NoDelay => {
                 let no_delay = options.tcp.no_delay();
@@ -556,6 +606,18 @@ impl Socket for StreamSocket {
                 let linger = socket_linger.get().unwrap();
                 options.socket.set_linger(*linger);
             },
+            // IPv6 options
+            ipv6_v6only: V6Only => {
+                if self.ip_version != IpVersion::Ipv6 {
+                    return_errno_with_message!(Errno::ENOPROTOOPT, "the socket is not an IPv6 socket");
+                }
+                // Like Linux, the option can only be changed before the socket is bound.
+                if !matches!(self.state.read().as_ref(), State::Init(InitStream::Unbound(_))) {
+                    return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+                }
+                let is_v6only = ipv6_v6only.get().unwrap();
+                self.is_v6only.store(*is_v6only, Ordering::Relaxed);
+            },
             // Tcp options
             tcp_no_delay: NoDelay => {
                 let no_delay = tcp_no_delay.get().unwrap();
@@ -2,7 +2,10 @@
 
 use self::options::SocketOption;
 pub use self::util::{
-    options::LingerOption, send_recv_flags::SendRecvFlags, shutdown_cmd::SockShutdownCmd,
+    message_header::{ControlMessage, MessageHeader, UnixCredentials},
+    options::LingerOption,
+    send_recv_flags::SendRecvFlags,
+    shutdown_cmd::SockShutdownCmd,
     socket_addr::SocketAddr,
 };
 use crate::{fs::file_handle::FileLike, prelude::*};
@@ -12,6 +15,12 @@ pub mod options;
 pub mod unix;
 mod util;
 
+/// The maximum number of bytes that a socket can receive at a time.
+///
+/// No socket has a receive buffer larger than this, so a larger buffer in the kernel is never
+/// filled.
+pub const MAX_RECV_LEN: usize = 65536;
+
 /// Operations defined on a socket.
 pub trait Socket: FileLike + Send + Sync {
     /// Assign the address specified by socket_addr to the socket
@@ -74,4 +83,28 @@ pub trait Socket: FileLike + Send + Sync {
     ) -> Result<usize> {
         return_errno_with_message!(Errno::EOPNOTSUPP, "recvfrom() is not supported");
     }
+
+    /// Receive a message from a socket, together with its address and control messages
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        let (recv_size, addr) = self.recvfrom(buf, flags)?;
+        let message_header = MessageHeader::new(Some(addr), Vec::new());
+        Ok((recv_size, message_header))
+    }
+
+    /// Send a message on a socket, together with its control messages
+    fn sendmsg(
+        &self,
+        buf: &[u8],
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let (remote, control_messages) = message_header.into_parts();
+        if !control_messages.is_empty() {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "control messages are not supported by the socket"
+            );
+        }
+        self.sendto(buf, remote, flags)
+    }
 }
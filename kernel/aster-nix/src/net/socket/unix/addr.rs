@@ -1,17 +1,92 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::{fs::utils::Dentry, net::socket::util::socket_addr::SocketAddr, prelude::*};
+use alloc::format;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use keyable_arc::KeyableWeak;
+
+use crate::{
+    fs::{
+        fs_resolver::{split_path, FsPath},
+        utils::{Dentry, Inode, InodeMode, InodeType},
+    },
+    net::socket::util::socket_addr::SocketAddr,
+    prelude::*,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum UnixSocketAddr {
     Path(String),
-    Abstract(String),
+    /// A name in the abstract namespace, excluding the leading null byte.
+    Abstract(Vec<u8>),
+}
+
+impl UnixSocketAddr {
+    /// Binds the address, creating the socket file if the address is a path,
+    /// or reserving the name if the address is in the abstract namespace.
+    pub(super) fn bind(&self) -> Result<UnixSocketAddrBound> {
+        match self {
+            Self::Abstract(name) => {
+                let name = ABSTRACT_NAMESPACE.bind(name)?;
+                Ok(UnixSocketAddrBound::Abstract(name))
+            }
+            // Like Linux, binding to an empty path automatically binds to an unused
+            // abstract name.
+            Self::Path(path) if path.is_empty() => {
+                let name = ABSTRACT_NAMESPACE.autobind()?;
+                Ok(UnixSocketAddrBound::Abstract(name))
+            }
+            Self::Path(path) => {
+                let dentry = create_socket_file(path)?;
+                Ok(UnixSocketAddrBound::Path(dentry))
+            }
+        }
+    }
+
+    /// Looks up the bound address that this address refers to.
+    ///
+    /// The returned address does not keep the abstract name in use.
+    pub(super) fn lookup(&self) -> Result<UnixSocketAddrBound> {
+        match self {
+            Self::Abstract(name) => Ok(UnixSocketAddrBound::Abstract(Arc::from(name.as_slice()))),
+            Self::Path(path) => {
+                let dentry = lookup_socket_file(path)?;
+                Ok(UnixSocketAddrBound::Path(dentry))
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
 pub(super) enum UnixSocketAddrBound {
     Path(Arc<Dentry>),
-    Abstract(String),
+    Abstract(Arc<[u8]>),
+}
+
+impl UnixSocketAddrBound {
+    /// Returns a key that identifies the bound address in the socket tables.
+    pub(super) fn key(&self) -> UnixSocketAddrKey {
+        match self {
+            Self::Path(dentry) => {
+                let weak_inode = Arc::downgrade(dentry.inode());
+                UnixSocketAddrKey::Path(KeyableWeak::from(weak_inode))
+            }
+            Self::Abstract(name) => UnixSocketAddrKey::Abstract(name.to_vec()),
+        }
+    }
+
+    /// Returns a copy of the address that does not keep the abstract name in use.
+    ///
+    /// Only the bound socket may hold the reservation of its abstract name. Addresses that
+    /// are handed to other sockets (e.g., accepted sockets or received messages) must be
+    /// copied with this method, otherwise the name stays in use after the bound socket is
+    /// closed.
+    pub(super) fn to_unreserved(&self) -> Self {
+        match self {
+            Self::Path(dentry) => Self::Path(dentry.clone()),
+            Self::Abstract(name) => Self::Abstract(Arc::from(&name[..])),
+        }
+    }
 }
 
 impl PartialEq for UnixSocketAddrBound {
@@ -24,6 +99,16 @@ impl PartialEq for UnixSocketAddrBound {
     }
 }
 
+/// The key of a bound address.
+///
+/// A socket bound to a path is identified by the inode of the socket file, so that the
+/// socket can still be found if the file is renamed.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum UnixSocketAddrKey {
+    Path(KeyableWeak<dyn Inode>),
+    Abstract(Vec<u8>),
+}
+
 impl TryFrom<SocketAddr> for UnixSocketAddr {
     type Error = Error;
 
@@ -42,7 +127,7 @@ impl From<UnixSocketAddrBound> for UnixSocketAddr {
                 let abs_path = dentry.abs_path();
                 Self::Path(abs_path)
             }
-            UnixSocketAddrBound::Abstract(name) => Self::Abstract(name),
+            UnixSocketAddrBound::Abstract(name) => Self::Abstract(name.to_vec()),
         }
     }
 }
@@ -53,3 +138,89 @@ impl From<UnixSocketAddrBound> for SocketAddr {
         SocketAddr::Unix(unix_socket_addr)
     }
 }
+
+static ABSTRACT_NAMESPACE: AbstractNamespace = AbstractNamespace::new();
+
+/// The abstract namespace of unix sockets, which is not bound to the file system.
+///
+/// A name is in use as long as the socket bound to it is alive. The bound socket holds
+/// strong references to the name, while the namespace only holds a weak one, so the name
+/// is released automatically when the socket is closed.
+struct AbstractNamespace {
+    names: Mutex<BTreeMap<Vec<u8>, Weak<[u8]>>>,
+    next_autobind_id: AtomicU32,
+}
+
+impl AbstractNamespace {
+    const fn new() -> Self {
+        Self {
+            names: Mutex::new(BTreeMap::new()),
+            next_autobind_id: AtomicU32::new(0),
+        }
+    }
+
+    fn bind(&self, name: &[u8]) -> Result<Arc<[u8]>> {
+        let mut names = self.names.lock();
+        // Remove the names whose sockets have been closed.
+        names.retain(|_, bound_name| bound_name.strong_count() > 0);
+
+        if names.contains_key(name) {
+            return_errno_with_message!(Errno::EADDRINUSE, "the abstract name is already used");
+        }
+
+        let bound_name: Arc<[u8]> = Arc::from(name);
+        names.insert(name.to_vec(), Arc::downgrade(&bound_name));
+        Ok(bound_name)
+    }
+
+    /// Binds to an unused name that consists of five hexadecimal digits, as Linux does.
+    fn autobind(&self) -> Result<Arc<[u8]>> {
+        for _ in 0..AUTOBIND_NAME_COUNT {
+            let id = self.next_autobind_id.fetch_add(1, Ordering::Relaxed) % AUTOBIND_NAME_COUNT;
+            let name = format!("{:05x}", id);
+            match self.bind(name.as_bytes()) {
+                Err(err) if err.error() == Errno::EADDRINUSE => continue,
+                result => return result,
+            }
+        }
+
+        return_errno_with_message!(Errno::ENOSPC, "no abstract name is available");
+    }
+}
+
+/// The number of names that can be automatically bound.
+const AUTOBIND_NAME_COUNT: u32 = 0x100000;
+
+fn create_socket_file(path: &str) -> Result<Arc<Dentry>> {
+    let (parent_pathname, file_name) = split_path(path);
+    let parent = {
+        let current = current!();
+        let fs = current.fs().read();
+        let parent_path = FsPath::try_from(parent_pathname)?;
+        fs.lookup(&parent_path)?
+    };
+    let dentry = parent.create(
+        file_name,
+        InodeType::Socket,
+        InodeMode::S_IRUSR | InodeMode::S_IWUSR,
+    )?;
+    Ok(dentry)
+}
+
+fn lookup_socket_file(path: &str) -> Result<Arc<Dentry>> {
+    let dentry = {
+        let current = current!();
+        let fs = current.fs().read();
+        let fs_path = FsPath::try_from(path)?;
+        fs.lookup(&fs_path)?
+    };
+
+    if dentry.type_() != InodeType::Socket {
+        return_errno_with_message!(Errno::ENOTSOCK, "not a socket file")
+    }
+
+    if !dentry.mode()?.is_readable() || !dentry.mode()?.is_writable() {
+        return_errno_with_message!(Errno::EACCES, "the socket cannot be read or written")
+    }
+    Ok(dentry)
+}
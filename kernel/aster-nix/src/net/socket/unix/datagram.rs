@@ -0,0 +1,378 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    addr::{UnixSocketAddrBound, UnixSocketAddrKey},
+    message_queue::{pass_credentials, Message, MessageQueue, DEFAULT_QUEUE_CAPACITY},
+    UnixSocketAddr,
+};
+use crate::{
+    events::IoEvents,
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut, match_sock_option_ref,
+    net::socket::{
+        options::{PassCred, SocketOption},
+        util::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr},
+        ControlMessage, MessageHeader, SockShutdownCmd, Socket,
+    },
+    prelude::*,
+    process::signal::Poller,
+};
+
+/// A connectionless unix socket, i.e., a unix socket of type `SOCK_DGRAM`.
+pub struct UnixDatagramSocket {
+    addr: RwLock<Option<UnixSocketAddrBound>>,
+    peer: RwLock<Option<Peer>>,
+    recv_queue: Arc<MessageQueue>,
+    is_nonblocking: AtomicBool,
+    is_pass_cred: AtomicBool,
+    is_write_shutdown: AtomicBool,
+}
+
+/// The default destination of a datagram socket.
+#[derive(Clone)]
+struct Peer {
+    addr: Option<UnixSocketAddrBound>,
+    recv_queue: Weak<MessageQueue>,
+}
+
+impl UnixDatagramSocket {
+    pub fn new(nonblocking: bool) -> Self {
+        let recv_queue = Arc::new(MessageQueue::new(DEFAULT_QUEUE_CAPACITY));
+        Self::new_with_queue(recv_queue, None, nonblocking)
+    }
+
+    pub fn new_pair(nonblocking: bool) -> Result<(Arc<Self>, Arc<Self>)> {
+        let queue_a = Arc::new(MessageQueue::new(DEFAULT_QUEUE_CAPACITY));
+        let queue_b = Arc::new(MessageQueue::new(DEFAULT_QUEUE_CAPACITY));
+
+        let peer_of_a = Peer {
+            addr: None,
+            recv_queue: Arc::downgrade(&queue_b),
+        };
+        let peer_of_b = Peer {
+            addr: None,
+            recv_queue: Arc::downgrade(&queue_a),
+        };
+
+        let socket_a = Self::new_with_queue(queue_a, Some(peer_of_a), nonblocking);
+        let socket_b = Self::new_with_queue(queue_b, Some(peer_of_b), nonblocking);
+        Ok((Arc::new(socket_a), Arc::new(socket_b)))
+    }
+
+    fn new_with_queue(
+        recv_queue: Arc<MessageQueue>,
+        peer: Option<Peer>,
+        nonblocking: bool,
+    ) -> Self {
+        Self {
+            addr: RwLock::new(None),
+            peer: RwLock::new(peer),
+            recv_queue,
+            is_nonblocking: AtomicBool::new(nonblocking),
+            is_pass_cred: AtomicBool::new(false),
+            is_write_shutdown: AtomicBool::new(false),
+        }
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Acquire)
+    }
+
+    fn set_nonblocking(&self, is_nonblocking: bool) {
+        self.is_nonblocking.store(is_nonblocking, Ordering::Release);
+    }
+
+    fn send_message(
+        &self,
+        buf: &[u8],
+        remote: Option<SocketAddr>,
+        control_messages: Vec<ControlMessage>,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        if self.is_write_shutdown.load(Ordering::Acquire) {
+            return_errno_with_message!(Errno::EPIPE, "the socket is shut down for writing");
+        }
+
+        let remote_queue = match remote {
+            Some(remote_addr) => {
+                let unix_socket_addr = UnixSocketAddr::try_from(remote_addr)?;
+                let remote_addr = unix_socket_addr.lookup()?;
+                DATAGRAM_TABLE.get_queue(&remote_addr)?
+            }
+            None => {
+                let peer = self.peer.read().clone().ok_or_else(|| {
+                    Error::with_message(Errno::ENOTCONN, "the socket is not connected")
+                })?;
+                peer.recv_queue.upgrade().ok_or_else(|| {
+                    Error::with_message(Errno::ECONNREFUSED, "the peer socket is closed")
+                })?
+            }
+        };
+
+        let is_nonblocking = self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT);
+        let addr = self
+            .addr
+            .read()
+            .as_ref()
+            .map(UnixSocketAddrBound::to_unreserved);
+        let message = Message::new(buf.to_vec(), addr, control_messages);
+        remote_queue.push(message, is_nonblocking)?;
+        Ok(buf.len())
+    }
+
+    /// Receives a message, returning the real length of the message.
+    ///
+    /// If the message does not fit into `buf`, the rest of the message is discarded.
+    fn recv_message(
+        &self,
+        buf: &mut [u8],
+        flags: SendRecvFlags,
+    ) -> Result<(usize, SocketAddr, Vec<ControlMessage>)> {
+        let is_nonblocking = self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT);
+        let Some(message) = self.recv_queue.pop(is_nonblocking)? else {
+            return Ok((0, unnamed_addr(), Vec::new()));
+        };
+
+        let sender = message.credentials();
+        let (data, addr, mut control_messages) = message.into_parts();
+        let is_pass_cred = self.is_pass_cred.load(Ordering::Relaxed);
+        pass_credentials(&mut control_messages, sender, is_pass_cred);
+
+        let copy_len = data.len().min(buf.len());
+        buf[..copy_len].copy_from_slice(&data[..copy_len]);
+
+        let addr = addr.map_or_else(unnamed_addr, SocketAddr::from);
+        Ok((data.len(), addr, control_messages))
+    }
+}
+
+/// Returns the length of a received message that is reported to the user.
+///
+/// The real length of the message is reported if `MSG_TRUNC` is specified, even if the message
+/// is truncated to fit into the buffer.
+fn recv_len(msg_len: usize, buf_len: usize, flags: SendRecvFlags) -> usize {
+    if flags.contains(SendRecvFlags::MSG_TRUNC) {
+        msg_len
+    } else {
+        msg_len.min(buf_len)
+    }
+}
+
+impl FileLike for UnixDatagramSocket {
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.recvfrom(buf, SendRecvFlags::empty())
+            .map(|(read_size, _)| read_size)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        self.sendto(buf, None, SendRecvFlags::empty())
+    }
+
+    fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        let mut events = self.recv_queue.poll(mask & IoEvents::IN, poller);
+        if self.recv_queue.is_read_shutdown() {
+            events |= IoEvents::RDHUP | IoEvents::IN;
+        }
+
+        // A datagram socket is always writable unless the queue of its peer is full.
+        let peer_queue = self
+            .peer
+            .read()
+            .as_ref()
+            .and_then(|peer| peer.recv_queue.upgrade());
+        match peer_queue {
+            Some(peer_queue) => events |= peer_queue.poll(mask & IoEvents::OUT, poller),
+            None => events |= IoEvents::OUT,
+        }
+
+        events & (mask | IoEvents::ALWAYS_POLL)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.set_nonblocking(new_flags.contains(StatusFlags::O_NONBLOCK));
+        Ok(())
+    }
+}
+
+impl Socket for UnixDatagramSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr_to_bind = UnixSocketAddr::try_from(socket_addr)?;
+
+        let mut addr = self.addr.write();
+        if addr.is_some() {
+            return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+        }
+
+        let bound_addr = addr_to_bind.bind()?;
+        DATAGRAM_TABLE.add_queue(&bound_addr, &self.recv_queue)?;
+        *addr = Some(bound_addr);
+        Ok(())
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let remote_addr = {
+            let unix_socket_addr = UnixSocketAddr::try_from(socket_addr)?;
+            unix_socket_addr.lookup()?
+        };
+
+        let remote_queue = DATAGRAM_TABLE.get_queue(&remote_addr)?;
+        *self.peer.write() = Some(Peer {
+            addr: Some(remote_addr),
+            recv_queue: Arc::downgrade(&remote_queue),
+        });
+        Ok(())
+    }
+
+    fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
+        if cmd.shut_read() {
+            self.recv_queue.shutdown_read();
+        }
+
+        if cmd.shut_write() {
+            self.is_write_shutdown.store(true, Ordering::Release);
+        }
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let addr = self.addr.read().clone();
+        Ok(addr.map_or_else(unnamed_addr, SocketAddr::from))
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        let peer =
+            self.peer.read().clone().ok_or_else(|| {
+                Error::with_message(Errno::ENOTCONN, "the socket is not connected")
+            })?;
+
+        Ok(peer.addr.map_or_else(unnamed_addr, SocketAddr::from))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = self.is_pass_cred.load(Ordering::Relaxed);
+                socket_pass_cred.set(is_pass_cred);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "get unknown option")
+        });
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*is_pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "set unknown option")
+        });
+        Ok(())
+    }
+
+    fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
+        let (msg_len, addr, _) = self.recv_message(buf, flags)?;
+        Ok((recv_len(msg_len, buf.len(), flags), addr))
+    }
+
+    fn sendto(
+        &self,
+        buf: &[u8],
+        remote: Option<SocketAddr>,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        self.send_message(buf, remote, Vec::new(), flags)
+    }
+
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        let (msg_len, addr, control_messages) = self.recv_message(buf, flags)?;
+        let mut message_header = MessageHeader::new(Some(addr), control_messages);
+        if msg_len > buf.len() {
+            message_header.set_truncated();
+        }
+        Ok((recv_len(msg_len, buf.len(), flags), message_header))
+    }
+
+    fn sendmsg(
+        &self,
+        buf: &[u8],
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let (remote, control_messages) = message_header.into_parts();
+        self.send_message(buf, remote, control_messages, flags)
+    }
+}
+
+impl Drop for UnixDatagramSocket {
+    fn drop(&mut self) {
+        // Wake up the senders that are waiting for free room in the queue.
+        self.recv_queue.shutdown_read();
+
+        if let Some(addr) = self.addr.read().as_ref() {
+            DATAGRAM_TABLE.remove_queue(addr);
+        }
+    }
+}
+
+fn unnamed_addr() -> SocketAddr {
+    SocketAddr::Unix(UnixSocketAddr::Path(String::new()))
+}
+
+static DATAGRAM_TABLE: DatagramTable = DatagramTable::new();
+
+/// The receive queues of all bound datagram sockets, indexed by their addresses.
+struct DatagramTable {
+    queues: RwLock<BTreeMap<UnixSocketAddrKey, Weak<MessageQueue>>>,
+}
+
+impl DatagramTable {
+    const fn new() -> Self {
+        Self {
+            queues: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    fn add_queue(&self, addr: &UnixSocketAddrBound, queue: &Arc<MessageQueue>) -> Result<()> {
+        let key = addr.key();
+
+        let mut queues = self.queues.write();
+        if queues.contains_key(&key) {
+            return_errno_with_message!(Errno::EADDRINUSE, "the addr is already used");
+        }
+        queues.insert(key, Arc::downgrade(queue));
+        Ok(())
+    }
+
+    fn get_queue(&self, addr: &UnixSocketAddrBound) -> Result<Arc<MessageQueue>> {
+        self.queues
+            .read()
+            .get(&addr.key())
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| {
+                Error::with_message(
+                    Errno::ECONNREFUSED,
+                    "no socket is bound to the remote address",
+                )
+            })
+    }
+
+    fn remove_queue(&self, addr: &UnixSocketAddrBound) {
+        self.queues.write().remove(&addr.key());
+    }
+}
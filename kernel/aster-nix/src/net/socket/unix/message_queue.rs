@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::addr::UnixSocketAddrBound;
+use crate::{
+    events::IoEvents,
+    net::socket::{ControlMessage, UnixCredentials},
+    prelude::*,
+    process::signal::{Pollee, Poller},
+};
+
+/// A message sent through a unix socket that preserves message boundaries.
+pub(super) struct Message {
+    data: Vec<u8>,
+    addr: Option<UnixSocketAddrBound>,
+    control_messages: Vec<ControlMessage>,
+    /// The credentials of the sender, which are received with `SO_PASSCRED` even if they are
+    /// not sent explicitly.
+    credentials: UnixCredentials,
+}
+
+impl Message {
+    /// Creates a message sent by the current process.
+    pub(super) fn new(
+        data: Vec<u8>,
+        addr: Option<UnixSocketAddrBound>,
+        control_messages: Vec<ControlMessage>,
+    ) -> Self {
+        Self {
+            data,
+            addr,
+            control_messages,
+            credentials: UnixCredentials::current(),
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub(super) fn credentials(&self) -> UnixCredentials {
+        self.credentials
+    }
+
+    pub(super) fn into_parts(self) -> (Vec<u8>, Option<UnixSocketAddrBound>, Vec<ControlMessage>) {
+        (self.data, self.addr, self.control_messages)
+    }
+}
+
+/// Adjusts the credentials in the received control messages for a receiver that enables
+/// `SO_PASSCRED` or not.
+///
+/// Like Linux, a receiver with `SO_PASSCRED` always receives the credentials, which are those
+/// of the sender if the sender does not send them explicitly. A receiver without `SO_PASSCRED`
+/// never receives the credentials.
+pub(super) fn pass_credentials(
+    control_messages: &mut Vec<ControlMessage>,
+    sender: UnixCredentials,
+    is_pass_cred: bool,
+) {
+    let is_sent = |control_message: &ControlMessage| {
+        matches!(control_message, ControlMessage::Credentials(_))
+    };
+    if !is_pass_cred {
+        control_messages.retain(|control_message| !is_sent(control_message));
+    } else if !control_messages.iter().any(is_sent) {
+        control_messages.push(ControlMessage::Credentials(sender));
+    }
+}
+
+/// A bounded queue of messages, used as the receive queue of
+/// datagram and seqpacket unix sockets.
+///
+/// The pollee of the queue has `IoEvents::IN` if there are messages to receive,
+/// and `IoEvents::OUT` if there is room for more messages.
+pub(super) struct MessageQueue {
+    inner: Mutex<Inner>,
+    capacity: usize,
+    pollee: Pollee,
+    is_read_shutdown: AtomicBool,
+    is_write_shutdown: AtomicBool,
+}
+
+struct Inner {
+    messages: VecDeque<Message>,
+    total_len: usize,
+}
+
+impl MessageQueue {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                messages: VecDeque::new(),
+                total_len: 0,
+            }),
+            capacity,
+            pollee: Pollee::new(IoEvents::OUT),
+            is_read_shutdown: AtomicBool::new(false),
+            is_write_shutdown: AtomicBool::new(false),
+        }
+    }
+
+    /// Pushes a message to the queue, waiting for free room if the queue is full
+    /// and `is_nonblocking` is false.
+    pub(super) fn push(&self, message: Message, is_nonblocking: bool) -> Result<()> {
+        if message.len() > self.capacity {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        let mut message = Some(message);
+        let poller = Poller::new();
+        loop {
+            if self.is_read_shutdown() || self.is_write_shutdown() {
+                return_errno_with_message!(Errno::EPIPE, "the queue is shut down");
+            }
+
+            {
+                let mut inner = self.inner.lock();
+                let len = message.as_ref().unwrap().len();
+                if inner.total_len + len <= self.capacity {
+                    inner.total_len += len;
+                    inner.messages.push_back(message.take().unwrap());
+                    self.update_pollee(&inner);
+                    return Ok(());
+                }
+            }
+
+            if is_nonblocking {
+                return_errno_with_message!(Errno::EAGAIN, "the queue is full");
+            }
+
+            let events = self.poll(IoEvents::OUT, Some(&poller));
+            if events.is_empty() {
+                poller.wait()?;
+            }
+        }
+    }
+
+    /// Pops a message from the queue, waiting for a message if the queue is empty
+    /// and `is_nonblocking` is false.
+    ///
+    /// Returns `None` if the queue is empty and no more messages can arrive.
+    pub(super) fn pop(&self, is_nonblocking: bool) -> Result<Option<Message>> {
+        let poller = Poller::new();
+        loop {
+            {
+                let mut inner = self.inner.lock();
+                if let Some(message) = inner.messages.pop_front() {
+                    inner.total_len -= message.len();
+                    self.update_pollee(&inner);
+                    return Ok(Some(message));
+                }
+            }
+
+            if self.is_read_shutdown() || self.is_write_shutdown() {
+                return Ok(None);
+            }
+
+            if is_nonblocking {
+                return_errno_with_message!(Errno::EAGAIN, "the queue is empty");
+            }
+
+            let events = self.poll(IoEvents::IN, Some(&poller));
+            if events.is_empty() {
+                poller.wait()?;
+            }
+        }
+    }
+
+    /// Shuts down the read side. Pending and future messages are discarded.
+    pub(super) fn shutdown_read(&self) {
+        self.is_read_shutdown.store(true, Ordering::Release);
+
+        let mut inner = self.inner.lock();
+        inner.messages.clear();
+        inner.total_len = 0;
+        self.pollee.add_events(IoEvents::IN | IoEvents::OUT);
+    }
+
+    /// Shuts down the write side. Pending messages can still be received.
+    pub(super) fn shutdown_write(&self) {
+        self.is_write_shutdown.store(true, Ordering::Release);
+
+        let _inner = self.inner.lock();
+        self.pollee.add_events(IoEvents::IN | IoEvents::OUT);
+    }
+
+    pub(super) fn is_read_shutdown(&self) -> bool {
+        self.is_read_shutdown.load(Ordering::Acquire)
+    }
+
+    pub(super) fn is_write_shutdown(&self) -> bool {
+        self.is_write_shutdown.load(Ordering::Acquire)
+    }
+
+    pub(super) fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    fn update_pollee(&self, inner: &Inner) {
+        if self.is_read_shutdown() || self.is_write_shutdown() {
+            return;
+        }
+
+        if inner.messages.is_empty() {
+            self.pollee.del_events(IoEvents::IN);
+        } else {
+            self.pollee.add_events(IoEvents::IN);
+        }
+
+        if inner.total_len < self.capacity {
+            self.pollee.add_events(IoEvents::OUT);
+        } else {
+            self.pollee.del_events(IoEvents::OUT);
+        }
+    }
+}
+
+/// The default capacity of the receive queue in bytes.
+pub(super) const DEFAULT_QUEUE_CAPACITY: usize = 65536;
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod addr;
+mod datagram;
+mod message_queue;
 mod stream;
 
 pub use addr::UnixSocketAddr;
+pub use datagram::UnixDatagramSocket;
 pub use stream::UnixStreamSocket;
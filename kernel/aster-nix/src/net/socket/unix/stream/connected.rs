@@ -3,7 +3,7 @@
 use super::endpoint::Endpoint;
 use crate::{
     events::IoEvents,
-    net::socket::{unix::addr::UnixSocketAddrBound, SockShutdownCmd},
+    net::socket::{unix::addr::UnixSocketAddrBound, ControlMessage, SockShutdownCmd},
     prelude::*,
     process::signal::Poller,
 };
@@ -37,6 +37,23 @@ impl Connected {
         self.local_endpoint.read(buf)
     }
 
+    pub(super) fn write_with_control(
+        &self,
+        buf: &[u8],
+        control_messages: Vec<ControlMessage>,
+    ) -> Result<usize> {
+        self.local_endpoint
+            .write_with_control(buf, control_messages)
+    }
+
+    pub(super) fn read_with_control(
+        &self,
+        buf: &mut [u8],
+        is_pass_cred: bool,
+    ) -> Result<(usize, Vec<ControlMessage>, bool)> {
+        self.local_endpoint.read_with_control(buf, is_pass_cred)
+    }
+
     pub(super) fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
         self.local_endpoint.shutdown(cmd)
     }
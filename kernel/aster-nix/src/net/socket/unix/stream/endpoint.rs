@@ -1,9 +1,17 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use crate::{
     events::IoEvents,
     fs::utils::{Channel, Consumer, Producer, StatusFlags},
-    net::socket::{unix::addr::UnixSocketAddrBound, SockShutdownCmd},
+    net::socket::{
+        unix::{
+            addr::UnixSocketAddrBound,
+            message_queue::{pass_credentials, Message, MessageQueue, DEFAULT_QUEUE_CAPACITY},
+        },
+        ControlMessage, SockShutdownCmd, UnixCredentials,
+    },
     prelude::*,
     process::signal::Poller,
 };
@@ -12,13 +20,29 @@ pub(super) struct Endpoint(Inner);
 
 struct Inner {
     addr: RwLock<Option<UnixSocketAddrBound>>,
-    reader: Consumer<u8>,
-    writer: Producer<u8>,
+    transport: Transport,
     peer: Weak<Endpoint>,
 }
 
-impl Endpoint {
-    pub(super) fn new_pair(is_nonblocking: bool) -> Result<(Arc<Endpoint>, Arc<Endpoint>)> {
+/// The way that data is transferred between the two endpoints of a connection.
+enum Transport {
+    /// A byte stream, used by `SOCK_STREAM` sockets.
+    Stream {
+        reader: Consumer<u8>,
+        writer: Producer<u8>,
+        recv_ancillary: Arc<Mutex<AncillaryQueue>>,
+        send_ancillary: Arc<Mutex<AncillaryQueue>>,
+    },
+    /// A sequence of messages whose boundaries are preserved, used by `SOCK_SEQPACKET` sockets.
+    Seqpacket {
+        recv_queue: Arc<MessageQueue>,
+        send_queue: Arc<MessageQueue>,
+        is_nonblocking: AtomicBool,
+    },
+}
+
+impl Transport {
+    fn new_stream_pair(is_nonblocking: bool) -> Result<(Self, Self)> {
         let flags = if is_nonblocking {
             StatusFlags::O_NONBLOCK
         } else {
@@ -28,21 +52,67 @@ impl Endpoint {
             Channel::with_capacity_and_flags(DAFAULT_BUF_SIZE, flags)?.split();
         let (writer_b, reader_a) =
             Channel::with_capacity_and_flags(DAFAULT_BUF_SIZE, flags)?.split();
+        let ancillary_a_to_b = Arc::new(Mutex::new(AncillaryQueue::new()));
+        let ancillary_b_to_a = Arc::new(Mutex::new(AncillaryQueue::new()));
+
+        let transport_a = Self::Stream {
+            reader: reader_a,
+            writer: writer_a,
+            recv_ancillary: ancillary_b_to_a.clone(),
+            send_ancillary: ancillary_a_to_b.clone(),
+        };
+        let transport_b = Self::Stream {
+            reader: reader_b,
+            writer: writer_b,
+            recv_ancillary: ancillary_a_to_b,
+            send_ancillary: ancillary_b_to_a,
+        };
+        Ok((transport_a, transport_b))
+    }
+
+    fn new_seqpacket_pair(is_nonblocking: bool) -> (Self, Self) {
+        let queue_a_to_b = Arc::new(MessageQueue::new(DEFAULT_QUEUE_CAPACITY));
+        let queue_b_to_a = Arc::new(MessageQueue::new(DEFAULT_QUEUE_CAPACITY));
+
+        let transport_a = Self::Seqpacket {
+            recv_queue: queue_b_to_a.clone(),
+            send_queue: queue_a_to_b.clone(),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+        };
+        let transport_b = Self::Seqpacket {
+            recv_queue: queue_a_to_b,
+            send_queue: queue_b_to_a,
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+        };
+        (transport_a, transport_b)
+    }
+}
+
+impl Endpoint {
+    pub(super) fn new_pair(
+        is_nonblocking: bool,
+        is_seqpacket: bool,
+    ) -> Result<(Arc<Endpoint>, Arc<Endpoint>)> {
+        let (transport_a, transport_b) = if is_seqpacket {
+            Transport::new_seqpacket_pair(is_nonblocking)
+        } else {
+            Transport::new_stream_pair(is_nonblocking)?
+        };
+
         let mut endpoint_b = None;
         let endpoint_a = Arc::new_cyclic(|endpoint_a_ref| {
-            let peer = Arc::new(Endpoint::new(reader_b, writer_b, endpoint_a_ref.clone()));
-            let endpoint_a = Endpoint::new(reader_a, writer_a, Arc::downgrade(&peer));
+            let peer = Arc::new(Endpoint::new(transport_b, endpoint_a_ref.clone()));
+            let endpoint_a = Endpoint::new(transport_a, Arc::downgrade(&peer));
             endpoint_b = Some(peer);
             endpoint_a
         });
         Ok((endpoint_a, endpoint_b.unwrap()))
     }
 
-    fn new(reader: Consumer<u8>, writer: Producer<u8>, peer: Weak<Endpoint>) -> Self {
+    fn new(transport: Transport, peer: Weak<Endpoint>) -> Self {
         Self(Inner {
             addr: RwLock::new(None),
-            reader,
-            writer,
+            transport,
             peer,
         })
     }
@@ -59,31 +129,141 @@ impl Endpoint {
         self.0.peer.upgrade().and_then(|peer| peer.addr())
     }
 
+    pub(super) fn is_seqpacket(&self) -> bool {
+        matches!(self.0.transport, Transport::Seqpacket { .. })
+    }
+
     pub(super) fn is_nonblocking(&self) -> bool {
-        let reader_status = self.0.reader.is_nonblocking();
-        let writer_status = self.0.writer.is_nonblocking();
-        debug_assert!(reader_status == writer_status);
-        reader_status
+        match &self.0.transport {
+            Transport::Stream { reader, writer, .. } => {
+                let reader_status = reader.is_nonblocking();
+                let writer_status = writer.is_nonblocking();
+                debug_assert!(reader_status == writer_status);
+                reader_status
+            }
+            Transport::Seqpacket { is_nonblocking, .. } => is_nonblocking.load(Ordering::Acquire),
+        }
     }
 
     pub(super) fn set_nonblocking(&self, is_nonblocking: bool) -> Result<()> {
-        let reader_flags = self.0.reader.status_flags();
-        self.0
-            .reader
-            .set_status_flags(reader_flags | StatusFlags::O_NONBLOCK)?;
-        let writer_flags = self.0.writer.status_flags();
-        self.0
-            .writer
-            .set_status_flags(writer_flags | StatusFlags::O_NONBLOCK)?;
+        match &self.0.transport {
+            Transport::Stream { reader, writer, .. } => {
+                let reader_flags = reader.status_flags();
+                reader.set_status_flags(reader_flags | StatusFlags::O_NONBLOCK)?;
+                let writer_flags = writer.status_flags();
+                writer.set_status_flags(writer_flags | StatusFlags::O_NONBLOCK)?;
+            }
+            Transport::Seqpacket {
+                is_nonblocking: nonblocking,
+                ..
+            } => nonblocking.store(is_nonblocking, Ordering::Release),
+        }
         Ok(())
     }
 
     pub(super) fn read(&self, buf: &mut [u8]) -> Result<usize> {
-        self.0.reader.read(buf)
+        // Control messages are discarded if they are received by a plain read.
+        self.read_with_control(buf, false)
+            .map(|(read_size, ..)| read_size)
     }
 
     pub(super) fn write(&self, buf: &[u8]) -> Result<usize> {
-        self.0.writer.write(buf)
+        self.write_with_control(buf, Vec::new())
+    }
+
+    /// Reads bytes and the control messages attached to them.
+    ///
+    /// For a stream, a single read never crosses the start of the bytes that carry another
+    /// control message, so that control messages sent by different `sendmsg` calls are not
+    /// merged. For a seqpacket, a single read receives exactly one message, and the part of
+    /// the message that does not fit into `buf` is discarded.
+    ///
+    /// If `is_pass_cred` is true, the credentials of the sender are always received, and a
+    /// single read of a stream never crosses the bytes sent by different senders.
+    ///
+    /// Returns the number of bytes read, the control messages, and whether the message is
+    /// truncated, which is always false for a stream.
+    pub(super) fn read_with_control(
+        &self,
+        buf: &mut [u8],
+        is_pass_cred: bool,
+    ) -> Result<(usize, Vec<ControlMessage>, bool)> {
+        match &self.0.transport {
+            Transport::Stream {
+                reader,
+                recv_ancillary,
+                ..
+            } => {
+                let max_len = recv_ancillary.lock().max_read_len(buf.len(), is_pass_cred);
+                let read_size = reader.read(&mut buf[..max_len])?;
+                let (mut control_messages, sender) = recv_ancillary.lock().consume(read_size);
+                if let Some(sender) = sender {
+                    pass_credentials(&mut control_messages, sender, is_pass_cred);
+                }
+                Ok((read_size, control_messages, false))
+            }
+            Transport::Seqpacket { recv_queue, .. } => {
+                let Some(message) = recv_queue.pop(self.is_nonblocking())? else {
+                    return Ok((0, Vec::new(), false));
+                };
+
+                let sender = message.credentials();
+                let (data, _, mut control_messages) = message.into_parts();
+                pass_credentials(&mut control_messages, sender, is_pass_cred);
+
+                let read_size = data.len().min(buf.len());
+                buf[..read_size].copy_from_slice(&data[..read_size]);
+                Ok((read_size, control_messages, data.len() > read_size))
+            }
+        }
+    }
+
+    /// Writes bytes and attaches the control messages to the first written byte.
+    pub(super) fn write_with_control(
+        &self,
+        buf: &[u8],
+        control_messages: Vec<ControlMessage>,
+    ) -> Result<usize> {
+        match &self.0.transport {
+            Transport::Stream {
+                writer,
+                send_ancillary,
+                ..
+            } => {
+                let has_control_messages = !control_messages.is_empty();
+                // The control messages and the credentials must be visible before the bytes can
+                // be read by the peer.
+                let has_new_credentials = {
+                    let mut send_ancillary = send_ancillary.lock();
+                    if has_control_messages {
+                        send_ancillary.push(control_messages);
+                    }
+                    send_ancillary.push_credentials(UnixCredentials::current())
+                };
+
+                let res = writer.write(buf);
+
+                let mut send_ancillary = send_ancillary.lock();
+                match res {
+                    Ok(write_size) => send_ancillary.produce(write_size),
+                    Err(_) => {
+                        if has_control_messages {
+                            send_ancillary.pop_last();
+                        }
+                        if has_new_credentials {
+                            send_ancillary.pop_last_credentials();
+                        }
+                    }
+                }
+
+                res
+            }
+            Transport::Seqpacket { send_queue, .. } => {
+                let message = Message::new(buf.to_vec(), None, control_messages);
+                send_queue.push(message, self.is_nonblocking())?;
+                Ok(buf.len())
+            }
+        }
     }
 
     pub(super) fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
@@ -91,12 +271,29 @@ impl Endpoint {
             return_errno_with_message!(Errno::ENOTCONN, "The socket is not connected.");
         }
 
-        if cmd.shut_read() {
-            self.0.reader.shutdown();
-        }
+        match &self.0.transport {
+            Transport::Stream { reader, writer, .. } => {
+                if cmd.shut_read() {
+                    reader.shutdown();
+                }
 
-        if cmd.shut_write() {
-            self.0.writer.shutdown();
+                if cmd.shut_write() {
+                    writer.shutdown();
+                }
+            }
+            Transport::Seqpacket {
+                recv_queue,
+                send_queue,
+                ..
+            } => {
+                if cmd.shut_read() {
+                    recv_queue.shutdown_read();
+                }
+
+                if cmd.shut_write() {
+                    send_queue.shutdown_write();
+                }
+            }
         }
 
         Ok(())
@@ -107,21 +304,165 @@ impl Endpoint {
     }
 
     pub(super) fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
-        let mut events = IoEvents::empty();
-        // FIXME: should reader and writer use the same mask?
-        let reader_events = self.0.reader.poll(mask, poller);
-        let writer_events = self.0.writer.poll(mask, poller);
-
-        if reader_events.contains(IoEvents::HUP) || self.0.reader.is_shutdown() {
-            events |= IoEvents::RDHUP | IoEvents::IN;
-            if writer_events.contains(IoEvents::ERR) || self.0.writer.is_shutdown() {
-                events |= IoEvents::HUP | IoEvents::OUT;
+        match &self.0.transport {
+            Transport::Stream { reader, writer, .. } => {
+                let mut events = IoEvents::empty();
+                // FIXME: should reader and writer use the same mask?
+                let reader_events = reader.poll(mask, poller);
+                let writer_events = writer.poll(mask, poller);
+
+                if reader_events.contains(IoEvents::HUP) || reader.is_shutdown() {
+                    events |= IoEvents::RDHUP | IoEvents::IN;
+                    if writer_events.contains(IoEvents::ERR) || writer.is_shutdown() {
+                        events |= IoEvents::HUP | IoEvents::OUT;
+                    }
+                }
+
+                events |= (reader_events & IoEvents::IN) | (writer_events & IoEvents::OUT);
+                events
+            }
+            Transport::Seqpacket {
+                recv_queue,
+                send_queue,
+                ..
+            } => {
+                let mut events = IoEvents::empty();
+                let recv_events = recv_queue.poll(mask, poller);
+                let send_events = send_queue.poll(mask, poller);
+
+                if recv_queue.is_read_shutdown() || recv_queue.is_write_shutdown() {
+                    events |= IoEvents::RDHUP | IoEvents::IN;
+                    if send_queue.is_read_shutdown() || send_queue.is_write_shutdown() {
+                        events |= IoEvents::HUP | IoEvents::OUT;
+                    }
+                }
+
+                events |= (recv_events & IoEvents::IN) | (send_events & IoEvents::OUT);
+                events
             }
         }
+    }
+}
 
-        events |= (reader_events & IoEvents::IN) | (writer_events & IoEvents::OUT);
-        events
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        // The channels of a stream are shut down when the reader and the writer are dropped,
+        // but the message queues are shared with the peer and must be shut down explicitly.
+        if let Transport::Seqpacket {
+            recv_queue,
+            send_queue,
+            ..
+        } = &self.0.transport
+        {
+            recv_queue.shutdown_read();
+            send_queue.shutdown_write();
+        }
     }
 }
 
 const DAFAULT_BUF_SIZE: usize = 4096;
+
+/// The control messages in flight for one direction of a connection.
+///
+/// Each group of control messages is tagged with the offset of the byte it is attached to,
+/// counted from the start of the byte stream. So are the credentials of the senders, which
+/// are recorded only when the sender changes.
+struct AncillaryQueue {
+    bytes_written: usize,
+    bytes_read: usize,
+    messages: VecDeque<(usize, Vec<ControlMessage>)>,
+    credentials: VecDeque<(usize, UnixCredentials)>,
+}
+
+impl AncillaryQueue {
+    const fn new() -> Self {
+        Self {
+            bytes_written: 0,
+            bytes_read: 0,
+            messages: VecDeque::new(),
+            credentials: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, control_messages: Vec<ControlMessage>) {
+        self.messages
+            .push_back((self.bytes_written, control_messages));
+    }
+
+    fn pop_last(&mut self) {
+        self.messages.pop_back();
+    }
+
+    /// Records the credentials of the sender of the following bytes, returning whether they
+    /// differ from those of the previous sender.
+    fn push_credentials(&mut self, credentials: UnixCredentials) -> bool {
+        if self
+            .credentials
+            .back()
+            .is_some_and(|(_, last_credentials)| *last_credentials == credentials)
+        {
+            return false;
+        }
+        self.credentials
+            .push_back((self.bytes_written, credentials));
+        true
+    }
+
+    fn pop_last_credentials(&mut self) {
+        self.credentials.pop_back();
+    }
+
+    fn produce(&mut self, len: usize) {
+        self.bytes_written += len;
+    }
+
+    /// Returns the maximum number of bytes that can be read without crossing the next
+    /// boundary of control messages, or the next boundary of senders if `is_pass_cred` is true.
+    fn max_read_len(&self, len: usize, is_pass_cred: bool) -> usize {
+        let pos = self.bytes_read;
+        let next_messages = self
+            .messages
+            .iter()
+            .map(|(offset, _)| *offset)
+            .find(|offset| *offset > pos);
+        let next_credentials = self
+            .credentials
+            .iter()
+            .map(|(offset, _)| *offset)
+            .find(|offset| is_pass_cred && *offset > pos);
+        [next_messages, next_credentials]
+            .into_iter()
+            .flatten()
+            .fold(len, |len, boundary| len.min(boundary - pos))
+    }
+
+    /// Consumes `len` bytes and returns the control messages attached to them, together with
+    /// the credentials of their sender if any bytes are consumed.
+    fn consume(&mut self, len: usize) -> (Vec<ControlMessage>, Option<UnixCredentials>) {
+        // Drop the credentials of the senders whose bytes have all been read.
+        while self
+            .credentials
+            .get(1)
+            .is_some_and(|(offset, _)| *offset <= self.bytes_read)
+        {
+            self.credentials.pop_front();
+        }
+        let sender = self
+            .credentials
+            .front()
+            .filter(|_| len > 0)
+            .map(|(_, credentials)| *credentials);
+
+        self.bytes_read += len;
+
+        let mut control_messages = Vec::new();
+        while let Some((offset, _)) = self.messages.front() {
+            if *offset >= self.bytes_read {
+                break;
+            }
+            let (_, messages) = self.messages.pop_front().unwrap();
+            control_messages.extend(messages);
+        }
+        (control_messages, sender)
+    }
+}
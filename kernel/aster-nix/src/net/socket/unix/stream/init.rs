@@ -5,10 +5,6 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use super::{connected::Connected, endpoint::Endpoint, listener::push_incoming};
 use crate::{
     events::IoEvents,
-    fs::{
-        fs_resolver::{split_path, FsPath},
-        utils::{Dentry, InodeMode, InodeType},
-    },
     net::socket::unix::addr::{UnixSocketAddr, UnixSocketAddrBound},
     prelude::*,
     process::signal::{Pollee, Poller},
@@ -16,14 +12,16 @@ use crate::{
 
 pub(super) struct Init {
     is_nonblocking: AtomicBool,
+    is_seqpacket: bool,
     addr: Mutex<Option<UnixSocketAddrBound>>,
     pollee: Pollee,
 }
 
 impl Init {
-    pub(super) fn new(is_nonblocking: bool) -> Self {
+    pub(super) fn new(is_nonblocking: bool, is_seqpacket: bool) -> Self {
         Self {
             is_nonblocking: AtomicBool::new(is_nonblocking),
+            is_seqpacket,
             addr: Mutex::new(None),
             pollee: Pollee::new(IoEvents::empty()),
         }
@@ -35,14 +33,7 @@ impl Init {
             return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
         }
 
-        let bound_addr = match addr_to_bind {
-            UnixSocketAddr::Abstract(_) => todo!(),
-            UnixSocketAddr::Path(path) => {
-                let dentry = create_socket_file(path)?;
-                UnixSocketAddrBound::Path(dentry)
-            }
-        };
-
+        let bound_addr = addr_to_bind.bind()?;
         *addr = Some(bound_addr);
         Ok(())
     }
@@ -56,8 +47,8 @@ impl Init {
             }
         }
 
-        let (this_end, remote_end) = Endpoint::new_pair(self.is_nonblocking())?;
-        remote_end.set_addr(remote_addr.clone());
+        let (this_end, remote_end) = Endpoint::new_pair(self.is_nonblocking(), self.is_seqpacket)?;
+        remote_end.set_addr(remote_addr.to_unreserved());
         if let Some(addr) = addr {
             this_end.set_addr(addr.clone());
         };
@@ -74,6 +65,10 @@ impl Init {
         self.addr.lock().clone()
     }
 
+    pub(super) fn is_seqpacket(&self) -> bool {
+        self.is_seqpacket
+    }
+
     pub(super) fn is_nonblocking(&self) -> bool {
         self.is_nonblocking.load(Ordering::Acquire)
     }
@@ -86,19 +81,3 @@ impl Init {
         self.pollee.poll(mask, poller)
     }
 }
-
-fn create_socket_file(path: &str) -> Result<Arc<Dentry>> {
-    let (parent_pathname, file_name) = split_path(path);
-    let parent = {
-        let current = current!();
-        let fs = current.fs().read();
-        let parent_path = FsPath::try_from(parent_pathname)?;
-        fs.lookup(&parent_path)?
-    };
-    let dentry = parent.create(
-        file_name,
-        InodeType::Socket,
-        InodeMode::S_IRUSR | InodeMode::S_IWUSR,
-    )?;
-    Ok(dentry)
-}
@@ -2,17 +2,12 @@
 
 use core::sync::atomic::{AtomicBool, Ordering};
 
-use keyable_arc::KeyableWeak;
-
 use super::{connected::Connected, endpoint::Endpoint, UnixStreamSocket};
 use crate::{
     events::IoEvents,
-    fs::{
-        file_handle::FileLike,
-        utils::{Dentry, Inode},
-    },
+    fs::file_handle::FileLike,
     net::socket::{
-        unix::addr::{UnixSocketAddr, UnixSocketAddrBound},
+        unix::addr::{UnixSocketAddr, UnixSocketAddrBound, UnixSocketAddrKey},
         SocketAddr,
     },
     prelude::*,
@@ -29,8 +24,9 @@ impl Listener {
         addr: UnixSocketAddrBound,
         backlog: usize,
         nonblocking: bool,
+        is_seqpacket: bool,
     ) -> Result<Self> {
-        BACKLOG_TABLE.add_backlog(&addr, backlog)?;
+        BACKLOG_TABLE.add_backlog(&addr, backlog, is_seqpacket)?;
         Ok(Self {
             addr,
             is_nonblocking: AtomicBool::new(nonblocking),
@@ -78,8 +74,7 @@ impl Listener {
 static BACKLOG_TABLE: BacklogTable = BacklogTable::new();
 
 struct BacklogTable {
-    backlog_sockets: RwLock<BTreeMap<KeyableWeak<dyn Inode>, Arc<Backlog>>>,
-    // TODO: For linux, there is also abstract socket domain that a socket addr is not bound to an inode.
+    backlog_sockets: RwLock<BTreeMap<UnixSocketAddrKey, Arc<Backlog>>>,
 }
 
 impl BacklogTable {
@@ -89,34 +84,27 @@ impl BacklogTable {
         }
     }
 
-    fn add_backlog(&self, addr: &UnixSocketAddrBound, backlog: usize) -> Result<()> {
-        let inode = {
-            let UnixSocketAddrBound::Path(dentry) = addr else {
-                todo!()
-            };
-            create_keyable_inode(dentry)
-        };
+    fn add_backlog(
+        &self,
+        addr: &UnixSocketAddrBound,
+        backlog: usize,
+        is_seqpacket: bool,
+    ) -> Result<()> {
+        let key = addr.key();
 
         let mut backlog_sockets = self.backlog_sockets.write();
-        if backlog_sockets.contains_key(&inode) {
+        if backlog_sockets.contains_key(&key) {
             return_errno_with_message!(Errno::EADDRINUSE, "the addr is already used");
         }
-        let new_backlog = Arc::new(Backlog::new(backlog));
-        backlog_sockets.insert(inode, new_backlog);
+        let new_backlog = Arc::new(Backlog::new(backlog, is_seqpacket));
+        backlog_sockets.insert(key, new_backlog);
         Ok(())
     }
 
     fn get_backlog(&self, addr: &UnixSocketAddrBound) -> Result<Arc<Backlog>> {
-        let inode = {
-            let UnixSocketAddrBound::Path(dentry) = addr else {
-                todo!()
-            };
-            create_keyable_inode(dentry)
-        };
-
         let backlog_sockets = self.backlog_sockets.read();
         backlog_sockets
-            .get(&inode)
+            .get(&addr.key())
             .map(Arc::clone)
             .ok_or_else(|| Error::with_message(Errno::EINVAL, "the socket is not listened"))
     }
@@ -162,31 +150,35 @@ impl BacklogTable {
     }
 
     fn remove_backlog(&self, addr: &UnixSocketAddrBound) {
-        let UnixSocketAddrBound::Path(dentry) = addr else {
-            todo!()
-        };
-
-        let inode = create_keyable_inode(dentry);
-        self.backlog_sockets.write().remove(&inode);
+        self.backlog_sockets.write().remove(&addr.key());
     }
 }
 
 struct Backlog {
     pollee: Pollee,
     backlog: usize,
+    is_seqpacket: bool,
     incoming_endpoints: Mutex<VecDeque<Arc<Endpoint>>>,
 }
 
 impl Backlog {
-    fn new(backlog: usize) -> Self {
+    fn new(backlog: usize, is_seqpacket: bool) -> Self {
         Self {
             pollee: Pollee::new(IoEvents::empty()),
             backlog,
+            is_seqpacket,
             incoming_endpoints: Mutex::new(VecDeque::with_capacity(backlog)),
         }
     }
 
     fn push_incoming(&self, endpoint: Arc<Endpoint>) -> Result<()> {
+        if endpoint.is_seqpacket() != self.is_seqpacket {
+            return_errno_with_message!(
+                Errno::EPROTOTYPE,
+                "the socket type does not match the listening socket"
+            );
+        }
+
         let mut endpoints = self.incoming_endpoints.lock();
         if endpoints.len() >= self.backlog {
             return_errno_with_message!(Errno::ECONNREFUSED, "incoming_endpoints is full");
@@ -212,11 +204,6 @@ impl Backlog {
     }
 }
 
-fn create_keyable_inode(dentry: &Arc<Dentry>) -> KeyableWeak<dyn Inode> {
-    let weak_inode = Arc::downgrade(dentry.inode());
-    KeyableWeak::from(weak_inode)
-}
-
 pub(super) fn unregister_backlog(addr: &UnixSocketAddrBound) {
     BACKLOG_TABLE.remove_backlog(addr);
 }
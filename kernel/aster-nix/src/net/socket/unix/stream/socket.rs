@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use super::{
     connected::Connected,
     endpoint::Endpoint,
@@ -8,33 +10,45 @@ use super::{
 };
 use crate::{
     events::IoEvents,
-    fs::{
-        file_handle::FileLike,
-        fs_resolver::FsPath,
-        utils::{Dentry, InodeType, StatusFlags},
-    },
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut, match_sock_option_ref,
     net::socket::{
+        options::{PassCred, SocketOption},
         unix::{addr::UnixSocketAddrBound, UnixSocketAddr},
         util::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr},
-        SockShutdownCmd, Socket,
+        MessageHeader, SockShutdownCmd, Socket,
     },
     prelude::*,
     process::signal::Poller,
 };
 
-pub struct UnixStreamSocket(RwLock<State>);
+/// A connection-oriented unix socket.
+///
+/// The socket is either a `SOCK_STREAM` socket, which transfers a byte stream, or a
+/// `SOCK_SEQPACKET` socket, which transfers messages whose boundaries are preserved.
+pub struct UnixStreamSocket {
+    state: RwLock<State>,
+    is_pass_cred: AtomicBool,
+}
 
 impl UnixStreamSocket {
     pub(super) fn new_init(init: Init) -> Self {
-        Self(RwLock::new(State::Init(Arc::new(init))))
+        Self::new_with_state(State::Init(Arc::new(init)))
     }
 
     pub(super) fn new_listen(listen: Listener) -> Self {
-        Self(RwLock::new(State::Listen(Arc::new(listen))))
+        Self::new_with_state(State::Listen(Arc::new(listen)))
     }
 
     pub(super) fn new_connected(connected: Connected) -> Self {
-        Self(RwLock::new(State::Connected(Arc::new(connected))))
+        Self::new_with_state(State::Connected(Arc::new(connected)))
+    }
+
+    fn new_with_state(state: State) -> Self {
+        Self {
+            state: RwLock::new(state),
+            is_pass_cred: AtomicBool::new(false),
+        }
     }
 }
 
@@ -46,12 +60,25 @@ enum State {
 
 impl UnixStreamSocket {
     pub fn new(nonblocking: bool) -> Self {
-        let init = Init::new(nonblocking);
+        let init = Init::new(nonblocking, false);
+        Self::new_init(init)
+    }
+
+    pub fn new_seqpacket(nonblocking: bool) -> Self {
+        let init = Init::new(nonblocking, true);
         Self::new_init(init)
     }
 
     pub fn new_pair(nonblocking: bool) -> Result<(Arc<Self>, Arc<Self>)> {
-        let (end_a, end_b) = Endpoint::new_pair(nonblocking)?;
+        Self::new_pair_with_type(nonblocking, false)
+    }
+
+    pub fn new_seqpacket_pair(nonblocking: bool) -> Result<(Arc<Self>, Arc<Self>)> {
+        Self::new_pair_with_type(nonblocking, true)
+    }
+
+    fn new_pair_with_type(nonblocking: bool, is_seqpacket: bool) -> Result<(Arc<Self>, Arc<Self>)> {
+        let (end_a, end_b) = Endpoint::new_pair(nonblocking, is_seqpacket)?;
         let connected_a = {
             let connected = Connected::new(end_a);
             Self::new_connected(connected)
@@ -64,7 +91,7 @@ impl UnixStreamSocket {
     }
 
     fn bound_addr(&self) -> Option<UnixSocketAddrBound> {
-        let status = self.0.read();
+        let status = self.state.read();
         match &*status {
             State::Init(init) => init.addr(),
             State::Listen(listen) => Some(listen.addr().clone()),
@@ -99,7 +126,7 @@ impl FileLike for UnixStreamSocket {
     }
 
     fn poll(&self, mask: IoEvents, poller: Option<&Poller>) -> IoEvents {
-        let inner = self.0.read();
+        let inner = self.state.read();
         match &*inner {
             State::Init(init) => init.poll(mask, poller),
             State::Listen(listen) => listen.poll(mask, poller),
@@ -108,7 +135,7 @@ impl FileLike for UnixStreamSocket {
     }
 
     fn status_flags(&self) -> StatusFlags {
-        let inner = self.0.read();
+        let inner = self.state.read();
         let is_nonblocking = match &*inner {
             State::Init(init) => init.is_nonblocking(),
             State::Listen(listen) => listen.is_nonblocking(),
@@ -128,7 +155,7 @@ impl FileLike for UnixStreamSocket {
             supported_flags.contains(StatusFlags::O_NONBLOCK)
         };
 
-        let mut inner = self.0.write();
+        let mut inner = self.state.write();
         match &mut *inner {
             State::Init(init) => init.set_nonblocking(is_nonblocking),
             State::Listen(listen) => listen.set_nonblocking(is_nonblocking),
@@ -142,7 +169,7 @@ impl Socket for UnixStreamSocket {
     fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
         let addr = UnixSocketAddr::try_from(socket_addr)?;
 
-        let init = match &*self.0.read() {
+        let init = match &*self.state.read() {
             State::Init(init) => init.clone(),
             _ => return_errno_with_message!(
                 Errno::EINVAL,
@@ -157,18 +184,10 @@ impl Socket for UnixStreamSocket {
     fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
         let remote_addr = {
             let unix_socket_addr = UnixSocketAddr::try_from(socket_addr)?;
-            match unix_socket_addr {
-                UnixSocketAddr::Abstract(abstract_name) => {
-                    UnixSocketAddrBound::Abstract(abstract_name)
-                }
-                UnixSocketAddr::Path(path) => {
-                    let dentry = lookup_socket_file(&path)?;
-                    UnixSocketAddrBound::Path(dentry)
-                }
-            }
+            unix_socket_addr.lookup()?
         };
 
-        let init = match &*self.0.read() {
+        let init = match &*self.state.read() {
             State::Init(init) => init.clone(),
             State::Listen(_) => return_errno_with_message!(Errno::EINVAL, "the socket is listened"),
             State::Connected(_) => {
@@ -178,12 +197,12 @@ impl Socket for UnixStreamSocket {
 
         let connected = init.connect(&remote_addr)?;
 
-        *self.0.write() = State::Connected(Arc::new(connected));
+        *self.state.write() = State::Connected(Arc::new(connected));
         Ok(())
     }
 
     fn listen(&self, backlog: usize) -> Result<()> {
-        let init = match &*self.0.read() {
+        let init = match &*self.state.read() {
             State::Init(init) => init.clone(),
             State::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is already listening")
@@ -198,13 +217,18 @@ impl Socket for UnixStreamSocket {
             "the socket is not bound",
         ))?;
 
-        let listener = Listener::new(addr.clone(), backlog, init.is_nonblocking())?;
-        *self.0.write() = State::Listen(Arc::new(listener));
+        let listener = Listener::new(
+            addr.clone(),
+            backlog,
+            init.is_nonblocking(),
+            init.is_seqpacket(),
+        )?;
+        *self.state.write() = State::Listen(Arc::new(listener));
         Ok(())
     }
 
     fn accept(&self) -> Result<(Arc<dyn FileLike>, SocketAddr)> {
-        let listen = match &*self.0.read() {
+        let listen = match &*self.state.read() {
             State::Listen(listen) => listen.clone(),
             _ => return_errno_with_message!(Errno::EINVAL, "the socket is not listening"),
         };
@@ -213,7 +237,7 @@ impl Socket for UnixStreamSocket {
     }
 
     fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
-        let connected = match &*self.0.read() {
+        let connected = match &*self.state.read() {
             State::Connected(connected) => connected.clone(),
             _ => return_errno_with_message!(Errno::ENOTCONN, "the socked is not connected"),
         };
@@ -222,7 +246,7 @@ impl Socket for UnixStreamSocket {
     }
 
     fn addr(&self) -> Result<SocketAddr> {
-        let addr = match &*self.0.read() {
+        let addr = match &*self.state.read() {
             State::Init(init) => init.addr(),
             State::Listen(listen) => Some(listen.addr().clone()),
             State::Connected(connected) => connected.addr(),
@@ -236,7 +260,7 @@ impl Socket for UnixStreamSocket {
     }
 
     fn peer_addr(&self) -> Result<SocketAddr> {
-        let connected = match &*self.0.read() {
+        let connected = match &*self.state.read() {
             State::Connected(connected) => connected.clone(),
             _ => return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected"),
         };
@@ -247,8 +271,30 @@ impl Socket for UnixStreamSocket {
         }
     }
 
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = self.is_pass_cred.load(Ordering::Relaxed);
+                socket_pass_cred.set(is_pass_cred);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "get unknown option")
+        });
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*is_pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "set unknown option")
+        });
+        Ok(())
+    }
+
     fn recvfrom(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, SocketAddr)> {
-        let connected = match &*self.0.read() {
+        let connected = match &*self.state.read() {
             State::Connected(connected) => connected.clone(),
             _ => return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected"),
         };
@@ -266,13 +312,50 @@ impl Socket for UnixStreamSocket {
     ) -> Result<usize> {
         debug_assert!(remote.is_none());
         // TODO: deal with flags
-        let connected = match &*self.0.read() {
+        let connected = match &*self.state.read() {
             State::Connected(connected) => connected.clone(),
             _ => return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected"),
         };
 
         connected.write(buf)
     }
+
+    fn recvmsg(&self, buf: &mut [u8], flags: SendRecvFlags) -> Result<(usize, MessageHeader)> {
+        let connected = match &*self.state.read() {
+            State::Connected(connected) => connected.clone(),
+            _ => return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected"),
+        };
+
+        let peer_addr = self.peer_addr()?;
+        let is_pass_cred = self.is_pass_cred.load(Ordering::Relaxed);
+        let (read_size, control_messages, is_truncated) =
+            connected.read_with_control(buf, is_pass_cred)?;
+        let mut message_header = MessageHeader::new(Some(peer_addr), control_messages);
+        if is_truncated {
+            message_header.set_truncated();
+        }
+        Ok((read_size, message_header))
+    }
+
+    fn sendmsg(
+        &self,
+        buf: &[u8],
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        // TODO: deal with flags
+        let (remote, control_messages) = message_header.into_parts();
+        if remote.is_some() {
+            return_errno_with_message!(Errno::EISCONN, "the socket is connection-oriented");
+        }
+
+        let connected = match &*self.state.read() {
+            State::Connected(connected) => connected.clone(),
+            _ => return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected"),
+        };
+
+        connected.write_with_control(buf, control_messages)
+    }
 }
 
 impl Drop for UnixStreamSocket {
@@ -281,26 +364,8 @@ impl Drop for UnixStreamSocket {
             return;
         };
 
-        if let State::Listen(_) = &*self.0.read() {
+        if let State::Listen(_) = &*self.state.read() {
             unregister_backlog(&bound_addr);
         }
     }
 }
-
-fn lookup_socket_file(path: &str) -> Result<Arc<Dentry>> {
-    let dentry = {
-        let current = current!();
-        let fs = current.fs().read();
-        let fs_path = FsPath::try_from(path)?;
-        fs.lookup(&fs_path)?
-    };
-
-    if dentry.type_() != InodeType::Socket {
-        return_errno_with_message!(Errno::ENOTSOCK, "not a socket file")
-    }
-
-    if !dentry.mode()?.is_readable() || !dentry.mode()?.is_writable() {
-        return_errno_with_message!(Errno::EACCES, "the socket cannot be read or written")
-    }
-    Ok(dentry)
-}
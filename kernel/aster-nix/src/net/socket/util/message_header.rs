@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr};
+use crate::{
+    fs::file_handle::FileLike,
+    prelude::*,
+    process::{credentials, Gid, Pid, Uid},
+};
+
+/// The kernel-side representation of a `msghdr`, excluding the data buffers.
+///
+/// It carries the address of a message and the control messages (a.k.a. ancillary data)
+/// that are sent or received along with the message.
+#[derive(Debug)]
+pub struct MessageHeader {
+    addr: Option<SocketAddr>,
+    control_messages: Vec<ControlMessage>,
+    /// The flags that describe a received message, e.g., `MSG_TRUNC` if the message is
+    /// truncated.
+    msg_flags: SendRecvFlags,
+}
+
+impl MessageHeader {
+    pub fn new(addr: Option<SocketAddr>, control_messages: Vec<ControlMessage>) -> Self {
+        Self {
+            addr,
+            control_messages,
+            msg_flags: SendRecvFlags::empty(),
+        }
+    }
+
+    pub fn addr(&self) -> Option<&SocketAddr> {
+        self.addr.as_ref()
+    }
+
+    pub fn control_messages(&self) -> &[ControlMessage] {
+        &self.control_messages
+    }
+
+    pub fn msg_flags(&self) -> SendRecvFlags {
+        self.msg_flags
+    }
+
+    /// Marks the received message as truncated, since it does not fit into the buffer.
+    pub fn set_truncated(&mut self) {
+        self.msg_flags |= SendRecvFlags::MSG_TRUNC;
+    }
+
+    pub fn into_parts(self) -> (Option<SocketAddr>, Vec<ControlMessage>) {
+        (self.addr, self.control_messages)
+    }
+}
+
+/// A control message.
+pub enum ControlMessage {
+    /// Files passed with `SCM_RIGHTS`.
+    Rights(Vec<Arc<dyn FileLike>>),
+    /// Credentials passed with `SCM_CREDENTIALS`.
+    Credentials(UnixCredentials),
+}
+
+impl Debug for ControlMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Rights(files) => f.debug_tuple("Rights").field(&files.len()).finish(),
+            Self::Credentials(credentials) => {
+                f.debug_tuple("Credentials").field(credentials).finish()
+            }
+        }
+    }
+}
+
+/// The credentials of a process passed through a unix socket.
+///
+/// The layout is the same as `struct ucred` in Linux.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod)]
+pub struct UnixCredentials {
+    pid: Pid,
+    uid: Uid,
+    gid: Gid,
+}
+
+impl UnixCredentials {
+    pub fn new(pid: Pid, uid: Uid, gid: Gid) -> Self {
+        Self { pid, uid, gid }
+    }
+
+    /// Returns the credentials of the current process, i.e., its PID and real UID and GID,
+    /// which are sent by default.
+    pub fn current() -> Self {
+        let credentials = credentials();
+        Self::new(current!().pid(), credentials.ruid(), credentials.rgid())
+    }
+
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    pub fn uid(&self) -> Uid {
+        self.uid
+    }
+
+    pub fn gid(&self) -> Gid {
+        self.gid
+    }
+}
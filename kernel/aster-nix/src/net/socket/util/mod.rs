@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub mod message_header;
 pub mod options;
 pub mod send_recv_flags;
 pub mod shutdown_cmd;
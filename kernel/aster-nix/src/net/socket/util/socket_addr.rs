@@ -2,7 +2,7 @@
 
 use crate::{
     net::{
-        iface::{IpAddress, IpEndpoint, Ipv4Address},
+        iface::{IpAddress, IpEndpoint, Ipv4Address, Ipv6Address},
         socket::unix::UnixSocketAddr,
     },
     prelude::*,
@@ -14,7 +14,7 @@ type PortNum = u16;
 pub enum SocketAddr {
     Unix(UnixSocketAddr),
     IPv4(Ipv4Address, PortNum),
-    IPv6,
+    IPv6(Ipv6Address, PortNum),
 }
 
 impl TryFrom<SocketAddr> for IpEndpoint {
@@ -23,6 +23,7 @@ impl TryFrom<SocketAddr> for IpEndpoint {
     fn try_from(value: SocketAddr) -> Result<Self> {
         match value {
             SocketAddr::IPv4(addr, port) => Ok(IpEndpoint::new(addr.into_address(), port)),
+            SocketAddr::IPv6(addr, port) => Ok(IpEndpoint::new(IpAddress::Ipv6(addr), port)),
             _ => return_errno_with_message!(
                 Errno::EAFNOSUPPORT,
                 "the address is in an unsupported address family"
@@ -36,7 +37,7 @@ impl From<IpEndpoint> for SocketAddr {
         let port = endpoint.port;
         match endpoint.addr {
             IpAddress::Ipv4(addr) => SocketAddr::IPv4(addr, port),
-            // TODO: support IPv6
+            IpAddress::Ipv6(addr) => SocketAddr::IPv6(addr, port),
         }
     }
 }
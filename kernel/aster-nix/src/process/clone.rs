@@ -17,6 +17,7 @@ use crate::{
     current_thread,
     fs::{file_table::FileTable, fs_resolver::FsResolver, utils::FileCreationMask},
     prelude::*,
+    sched::nice::Nice,
     thread::{allocate_tid, thread_table, Thread, Tid},
     util::write_val_to_user,
     vm::vmar::Vmar,
@@ -198,6 +199,8 @@ fn clone_child_thread(parent_context: UserContext, clone_args: CloneArgs) -> Res
 
     current.threads().lock().push(child_thread.clone());
 
+    clone_sched_attrs(&current_thread!(), &child_thread);
+
     let child_posix_thread = child_thread.as_posix_thread().unwrap();
     clone_parent_settid(child_tid, clone_args.parent_tidptr, clone_flags)?;
     clone_child_cleartid(child_posix_thread, clone_args.child_tidptr, clone_flags)?;
@@ -263,7 +266,15 @@ fn clone_child_process(parent_context: UserContext, clone_args: CloneArgs) -> Re
     };
 
     // inherit parent's nice value
-    let child_nice = current.nice().load(Ordering::Relaxed);
+    let child_nice = {
+        let nice = current.nice().load(Ordering::Relaxed);
+        // A negative nice value is not inherited if `SCHED_RESET_ON_FORK` is set
+        if current_thread!().sched_reset_on_fork() && nice < Nice::default() {
+            Nice::default()
+        } else {
+            nice
+        }
+    };
 
     let child_tid = allocate_tid();
 
@@ -292,13 +303,16 @@ fn clone_child_process(parent_context: UserContext, clone_args: CloneArgs) -> Re
             .fs(child_fs)
             .umask(child_umask)
             .sig_dispositions(child_sig_dispositions)
-            .nice(child_nice);
+            .nice(child_nice)
+            .oom_score_adj(current.oom_score_adj().load(Ordering::Relaxed));
 
         process_builder.build()?
     };
 
-    // Deals with clone flags
     let child_thread = thread_table::get_thread(child_tid).unwrap();
+    clone_sched_attrs(&current_thread!(), &child_thread);
+
+    // Deals with clone flags
     let child_posix_thread = child_thread.as_posix_thread().unwrap();
     clone_parent_settid(child_tid, clone_args.parent_tidptr, clone_flags)?;
     clone_child_cleartid(child_posix_thread, clone_args.child_tidptr, clone_flags)?;
@@ -317,6 +331,22 @@ fn clone_child_process(parent_context: UserContext, clone_args: CloneArgs) -> Re
     Ok(child)
 }
 
+/// Inherits the CPU affinity and the scheduling policy from the parent thread.
+///
+/// If `SCHED_RESET_ON_FORK` is set for the parent thread, the child thread uses the normal
+/// policy instead of the real-time ones, and the flag is not inherited.
+fn clone_sched_attrs(parent_thread: &Thread, child_thread: &Thread) {
+    let parent_task = parent_thread.task();
+    let child_task = child_thread.task();
+    child_task.set_cpu_affinity(parent_task.cpu_affinity());
+
+    let policy = parent_task.sched_policy();
+    if parent_thread.sched_reset_on_fork() && policy.is_real_time() {
+        return;
+    }
+    child_task.set_sched_policy(policy, parent_task.base_priority());
+}
+
 fn clone_child_cleartid(
     child_posix_thread: &PosixThread,
     child_tidptr: Vaddr,
@@ -358,9 +388,7 @@ fn clone_vm(parent_process_vm: &ProcessVm, clone_flags: CloneFlags) -> Result<Pr
     if clone_flags.contains(CloneFlags::CLONE_VM) {
         Ok(parent_process_vm.clone())
     } else {
-        let root_vmar = Vmar::<Full>::fork_from(parent_process_vm.root_vmar())?;
-        let user_heap = parent_process_vm.user_heap().clone();
-        Ok(ProcessVm::new(user_heap, root_vmar))
+        ProcessVm::fork_from(parent_process_vm)
     }
 }
 
@@ -33,6 +33,9 @@ pub fn do_exit_group(term_status: TermStatus) {
         }
     }
 
+    // The timers are no longer needed
+    current.timers().disarm_all();
+
     // Close all files then exit the process
     let files = current.file_table().lock().close_all();
     for file in files {
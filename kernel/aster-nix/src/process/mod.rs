@@ -12,9 +12,11 @@ pub mod process_table;
 mod process_vm;
 mod program_loader;
 mod rlimit;
+mod rusage;
 pub mod signal;
 mod status;
 mod term_status;
+pub mod timer;
 mod wait;
 
 pub use clone::{clone_child, CloneArgs, CloneFlags};
@@ -28,5 +30,6 @@ pub use process::{
 pub use process_filter::ProcessFilter;
 pub use program_loader::{check_executable_file, load_program_to_vm};
 pub use rlimit::ResourceType;
+pub use rusage::{rusage_t, RusageTarget};
 pub use term_status::TermStatus;
 pub use wait::{wait_child_exit, WaitOptions};
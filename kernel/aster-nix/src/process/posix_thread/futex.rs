@@ -1,13 +1,18 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
-use aster_frame::cpu::num_cpus;
+use aster_frame::{cpu::num_cpus, task::Priority};
 
 use crate::{
     prelude::*,
-    thread::{Thread, Tid},
-    util::read_val_from_user,
+    process::signal::Pauser,
+    thread::{thread_table, Thread, Tid},
+    time::{now_as_duration, timespec_t, ClockID},
+    util::{read_val_from_user, write_val_to_user},
 };
 
 type FutexBitSet = u32;
@@ -18,8 +23,19 @@ const FUTEX_FLAGS_MASK: u32 = 0xFFFF_FFF0;
 const FUTEX_BITSET_MATCH_ANY: FutexBitSet = 0xFFFF_FFFF;
 
 /// do futex wait
-pub fn futex_wait(futex_addr: u64, futex_val: i32, timeout: &Option<FutexTimeout>) -> Result<()> {
-    futex_wait_bitset(futex_addr as _, futex_val, timeout, FUTEX_BITSET_MATCH_ANY)
+pub fn futex_wait(
+    futex_addr: Vaddr,
+    futex_val: i32,
+    timeout: &Option<FutexTimeout>,
+    is_private: bool,
+) -> Result<()> {
+    futex_wait_bitset(
+        futex_addr,
+        futex_val,
+        timeout,
+        FUTEX_BITSET_MATCH_ANY,
+        is_private,
+    )
 }
 
 /// do futex wait bitset
@@ -28,19 +44,24 @@ pub fn futex_wait_bitset(
     futex_val: i32,
     timeout: &Option<FutexTimeout>,
     bitset: FutexBitSet,
+    is_private: bool,
 ) -> Result<()> {
     debug!(
         "futex_wait_bitset addr: {:#x}, val: {}, timeout: {:?}, bitset: {:#x}",
         futex_addr, futex_val, timeout, bitset
     );
-    let futex_key = FutexKey::new(futex_addr);
+    if bitset == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the bitset cannot be zero");
+    }
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
     let (_, futex_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_key);
 
     // lock futex bucket ref here to avoid data race
     let mut futex_bucket = futex_bucket_ref.lock();
 
-    if futex_key.load_val() != futex_val {
-        return_errno_with_message!(Errno::EINVAL, "futex value does not match");
+    if load_futex_val(futex_addr)? != futex_val {
+        return_errno_with_message!(Errno::EAGAIN, "futex value does not match");
     }
     let futex_item = FutexItem::new(futex_key, bitset);
     futex_bucket.enqueue_item(futex_item.clone());
@@ -48,14 +69,28 @@ pub fn futex_wait_bitset(
     // drop lock
     drop(futex_bucket);
     // Wait on the futex item
-    futex_item.wait();
+    let res = futex_item.wait(timeout);
+    if res.is_ok() {
+        return Ok(());
+    }
 
-    Ok(())
+    // The waiting is interrupted or timed out, so the item must be removed from the bucket,
+    // unless it has been woken up in the meantime.
+    let mut futex_bucket = futex_bucket_ref.lock();
+    if futex_item.waiter.is_woken() {
+        return Ok(());
+    }
+    if !futex_bucket.dequeue_item(&futex_item) {
+        drop(futex_bucket);
+        // The item has been requeued to another bucket.
+        FUTEX_BUCKETS.dequeue_item_from_all(&futex_item);
+    }
+    res
 }
 
 /// do futex wake
-pub fn futex_wake(futex_addr: Vaddr, max_count: usize) -> Result<usize> {
-    futex_wake_bitset(futex_addr, max_count, FUTEX_BITSET_MATCH_ANY)
+pub fn futex_wake(futex_addr: Vaddr, max_count: usize, is_private: bool) -> Result<usize> {
+    futex_wake_bitset(futex_addr, max_count, FUTEX_BITSET_MATCH_ANY, is_private)
 }
 
 /// Do futex wake with bitset
@@ -63,46 +98,59 @@ pub fn futex_wake_bitset(
     futex_addr: Vaddr,
     max_count: usize,
     bitset: FutexBitSet,
+    is_private: bool,
 ) -> Result<usize> {
     debug!(
         "futex_wake_bitset addr: {:#x}, max_count: {}, bitset: {:#x}",
         futex_addr, max_count, bitset
     );
+    if bitset == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the bitset cannot be zero");
+    }
 
-    let futex_key = FutexKey::new(futex_addr);
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
     let (_, futex_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_key);
     let mut futex_bucket = futex_bucket_ref.lock();
     let res = futex_bucket.dequeue_and_wake_items(futex_key, max_count, bitset);
-    // debug!("futex wake bitset succeeds, res = {}", res);
     drop(futex_bucket);
-    // for _ in 0..res {
-    //     Thread::yield_now();
-    // }
     Ok(res)
 }
 
 /// Do futex requeue
+///
+/// If `expected_val` is `Some(_)`, the futex value is compared with it first, and `EAGAIN` is
+/// returned if they do not match (i.e., `FUTEX_CMP_REQUEUE`).
 pub fn futex_requeue(
     futex_addr: Vaddr,
     max_nwakes: usize,
     max_nrequeues: usize,
     futex_new_addr: Vaddr,
+    expected_val: Option<i32>,
+    is_private: bool,
 ) -> Result<usize> {
-    if futex_new_addr == futex_addr {
-        return futex_wake(futex_addr, max_nwakes);
-    }
-
-    let futex_key = FutexKey::new(futex_addr);
-    let futex_new_key = FutexKey::new(futex_new_addr);
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let futex_new_key = FutexKey::new(futex_new_addr, is_private)?;
     let (bucket_idx, futex_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_key);
     let (new_bucket_idx, futex_new_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_new_key);
 
+    let check_futex_val = || -> Result<()> {
+        if let Some(expected_val) = expected_val
+            && load_futex_val(futex_addr)? != expected_val
+        {
+            return_errno_with_message!(Errno::EAGAIN, "futex value does not match");
+        }
+        Ok(())
+    };
+
     let nwakes = {
         if bucket_idx == new_bucket_idx {
             let mut futex_bucket = futex_bucket_ref.lock();
+            check_futex_val()?;
             let nwakes =
                 futex_bucket.dequeue_and_wake_items(futex_key, max_nwakes, FUTEX_BITSET_MATCH_ANY);
-            futex_bucket.update_item_keys(futex_key, futex_new_key, max_nrequeues);
+            if futex_key != futex_new_key {
+                futex_bucket.update_item_keys(futex_key, futex_new_key, max_nrequeues);
+            }
             drop(futex_bucket);
             nwakes
         } else {
@@ -118,6 +166,7 @@ pub fn futex_requeue(
                     (futex_bucket, futex_new_bucket)
                 }
             };
+            check_futex_val()?;
 
             let nwakes =
                 futex_bucket.dequeue_and_wake_items(futex_key, max_nwakes, FUTEX_BITSET_MATCH_ANY);
@@ -133,19 +182,311 @@ pub fn futex_requeue(
     Ok(nwakes)
 }
 
+fn load_futex_val(futex_addr: Vaddr) -> Result<i32> {
+    // FIXME: how to implement a atomic load?
+    read_val_from_user(futex_addr)
+}
+
+/// The bit of a PI futex word that indicates there are waiters blocked in the kernel.
+pub const FUTEX_WAITERS: u32 = 0x8000_0000;
+/// The bit of a PI futex word that indicates the owner has died without unlocking it.
+pub const FUTEX_OWNER_DIED: u32 = 0x4000_0000;
+/// The bits of a PI futex word that hold the TID of the owner.
+pub const FUTEX_TID_MASK: u32 = 0x3FFF_FFFF;
+
+/// Do futex lock pi
+///
+/// The timeout is an absolute deadline.
+pub fn futex_lock_pi(
+    futex_addr: Vaddr,
+    timeout: &Option<FutexTimeout>,
+    is_private: bool,
+) -> Result<()> {
+    debug!(
+        "futex_lock_pi addr: {:#x}, timeout: {:?}",
+        futex_addr, timeout
+    );
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let current_thread = current_thread!();
+
+    let waiter = {
+        let mut pi_futexes = PI_FUTEXES.lock();
+        let Some(owner) = pi_futexes.lock_or_get_owner(futex_addr, futex_key, &current_thread)?
+        else {
+            return Ok(());
+        };
+        let waiter = Arc::new(FutexWaiter::new());
+        pi_futexes.enqueue_waiter(futex_key, &owner, current_thread.clone(), waiter.clone());
+        pi_futexes.update_priority(&owner);
+        waiter
+    };
+
+    let res = waiter.wait(timeout);
+
+    let mut pi_futexes = PI_FUTEXES.lock();
+    if waiter.is_woken() {
+        // The lock has been handed off to us.
+        return pi_futexes.fixup_owner(futex_addr, futex_key, current_thread.tid());
+    }
+    pi_futexes.dequeue_waiter(futex_key, &waiter);
+    res
+}
+
+/// Do futex trylock pi
+pub fn futex_trylock_pi(futex_addr: Vaddr, is_private: bool) -> Result<()> {
+    debug!("futex_trylock_pi addr: {:#x}", futex_addr);
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let current_thread = current_thread!();
+
+    let mut pi_futexes = PI_FUTEXES.lock();
+    if pi_futexes
+        .lock_or_get_owner(futex_addr, futex_key, &current_thread)?
+        .is_some()
+    {
+        return_errno_with_message!(Errno::EAGAIN, "the futex is locked by another thread");
+    }
+    Ok(())
+}
+
+/// Do futex unlock pi
+///
+/// If there are waiters, the lock is handed off to the one with the highest priority.
+pub fn futex_unlock_pi(futex_addr: Vaddr, is_private: bool) -> Result<()> {
+    debug!("futex_unlock_pi addr: {:#x}", futex_addr);
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let current_thread = current_thread!();
+
+    let mut pi_futexes = PI_FUTEXES.lock();
+    let futex_val = load_futex_val(futex_addr)? as u32;
+    if futex_val & FUTEX_TID_MASK != current_thread.tid() {
+        return_errno_with_message!(Errno::EPERM, "the futex is not owned by the current thread");
+    }
+
+    let new_val = match pi_futexes.hand_off(futex_key, current_thread.tid()) {
+        Some(new_owner) => new_owner.tid() | FUTEX_WAITERS,
+        None => 0,
+    };
+    write_val_to_user(futex_addr, &new_val)?;
+    pi_futexes.update_priority(&current_thread);
+    Ok(())
+}
+
+/// Do futex wait requeue pi
+///
+/// The current thread waits on the non-PI futex at `futex_addr` until it is requeued by
+/// [`futex_cmp_requeue_pi`] to the PI futex at `futex_new_addr` and acquires it. The timeout
+/// is an absolute deadline.
+pub fn futex_wait_requeue_pi(
+    futex_addr: Vaddr,
+    futex_val: i32,
+    timeout: &Option<FutexTimeout>,
+    futex_new_addr: Vaddr,
+    is_private: bool,
+) -> Result<()> {
+    debug!(
+        "futex_wait_requeue_pi addr: {:#x}, val: {}, timeout: {:?}, new_addr: {:#x}",
+        futex_addr, futex_val, timeout, futex_new_addr
+    );
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let futex_new_key = FutexKey::new(futex_new_addr, is_private)?;
+    if futex_key == futex_new_key {
+        return_errno_with_message!(Errno::EINVAL, "cannot requeue to the same futex");
+    }
+    let (_, futex_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_key);
+
+    let futex_item = {
+        let mut futex_bucket = futex_bucket_ref.lock();
+        if load_futex_val(futex_addr)? != futex_val {
+            return_errno_with_message!(Errno::EAGAIN, "futex value does not match");
+        }
+        let futex_item = FutexItem::new_requeue_pi(futex_key, futex_new_key);
+        futex_bucket.enqueue_item(futex_item.clone());
+        futex_item
+    };
+
+    // The waiting continues after the item is requeued to the PI futex, until the PI futex
+    // is handed off to us.
+    let res = futex_item.wait(timeout);
+
+    let mut pi_futexes = PI_FUTEXES.lock();
+    let waiter = futex_item.waiter();
+    if waiter.is_requeued_to_pi() {
+        if waiter.is_woken() {
+            let current_tid = current_thread!().tid();
+            return pi_futexes.fixup_owner(futex_new_addr, futex_new_key, current_tid);
+        }
+        pi_futexes.dequeue_waiter(futex_new_key, waiter);
+        return res;
+    }
+
+    let mut futex_bucket = futex_bucket_ref.lock();
+    if waiter.is_woken() {
+        return_errno_with_message!(Errno::EAGAIN, "woken up before being requeued");
+    }
+    if !futex_bucket.dequeue_item(&futex_item) {
+        drop(futex_bucket);
+        FUTEX_BUCKETS.dequeue_item_from_all(&futex_item);
+    }
+    res
+}
+
+/// Do futex cmp requeue pi
+///
+/// The waiter with the highest priority on the futex at `futex_addr` tries to acquire the PI
+/// futex at `futex_new_addr`, and is woken up if it succeeds. The rest of the waiters, up to
+/// `max_nrequeues`, are requeued to the PI futex. Returns the number of waiters that are
+/// woken up or requeued.
+pub fn futex_cmp_requeue_pi(
+    futex_addr: Vaddr,
+    max_nrequeues: usize,
+    futex_new_addr: Vaddr,
+    expected_val: i32,
+    is_private: bool,
+) -> Result<usize> {
+    debug!(
+        "futex_cmp_requeue_pi addr: {:#x}, max_nrequeues: {}, new_addr: {:#x}",
+        futex_addr, max_nrequeues, futex_new_addr
+    );
+
+    let futex_key = FutexKey::new(futex_addr, is_private)?;
+    let futex_new_key = FutexKey::new(futex_new_addr, is_private)?;
+    if futex_key == futex_new_key {
+        return_errno_with_message!(Errno::EINVAL, "cannot requeue to the same futex");
+    }
+    let (_, futex_bucket_ref) = FUTEX_BUCKETS.get_bucket(futex_key);
+
+    let mut pi_futexes = PI_FUTEXES.lock();
+    let mut futex_bucket = futex_bucket_ref.lock();
+    if load_futex_val(futex_addr)? != expected_val {
+        return_errno_with_message!(Errno::EAGAIN, "futex value does not match");
+    }
+
+    let items = futex_bucket
+        .queue
+        .iter()
+        .filter(|item| item.key == futex_key)
+        .take(max_nrequeues.saturating_add(1))
+        .cloned()
+        .collect::<Vec<_>>();
+    if items
+        .iter()
+        .any(|item| item.requeue_pi_key != Some(futex_new_key))
+    {
+        return_errno_with_message!(Errno::EINVAL, "the waiter does not wait for requeue pi");
+    }
+
+    let mut threads = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        // A waiter whose thread has gone cannot be requeued, so it is simply woken up.
+        let Some(thread) = thread_table::get_thread(item.waiter.tid) else {
+            futex_bucket.dequeue_item(item);
+            item.wake();
+            continue;
+        };
+        threads.push((item, thread));
+    }
+    let Some((top_item, top_thread)) = threads.first() else {
+        return Ok(0);
+    };
+
+    let owner = pi_futexes.lock_or_get_owner(futex_new_addr, futex_new_key, top_thread)?;
+    let mut threads = threads.iter();
+    let (owner, mut count) = match owner {
+        Some(owner) => (owner, 0),
+        None => {
+            // The PI futex is acquired on behalf of the top waiter, so it is woken up instead
+            // of being requeued.
+            futex_bucket.dequeue_item(top_item);
+            top_item.waiter.set_requeued_to_pi();
+            top_item.wake();
+            threads.next();
+            (top_thread.clone(), 1)
+        }
+    };
+
+    for (item, thread) in threads {
+        futex_bucket.dequeue_item(item);
+        item.waiter.set_requeued_to_pi();
+        pi_futexes.enqueue_waiter(futex_new_key, &owner, thread.clone(), item.waiter.clone());
+        count += 1;
+    }
+
+    if pi_futexes.futexes.contains_key(&futex_new_key) {
+        let futex_val = load_futex_val(futex_new_addr)? as u32;
+        write_val_to_user(futex_new_addr, &(futex_val | FUTEX_WAITERS))?;
+        pi_futexes.update_priority(&owner);
+    }
+    Ok(count)
+}
+
+/// Hands off the PI futexes owned by an exiting thread to their waiters.
+///
+/// The futex words are fixed up by the new owners after they are woken up, so the robust
+/// list of the thread should be handled before this to mark the futex words as dead.
+pub fn exit_pi_futexes(tid: Tid) {
+    let mut pi_futexes = PI_FUTEXES.lock();
+    let owned_keys = pi_futexes
+        .futexes
+        .iter()
+        .filter(|(_, pi_futex)| pi_futex.owner.tid() == tid)
+        .map(|(key, _)| *key)
+        .collect::<Vec<_>>();
+    for key in owned_keys {
+        if let Some(new_owner) = pi_futexes.hand_off(key, tid) {
+            pi_futexes.update_priority(&new_owner);
+        }
+    }
+}
+
+/// Boosts the priority of a thread again after its base priority is changed, if it owns
+/// contended PI futexes, and propagates the change to the owner of the PI futex it is blocked
+/// on.
+pub fn update_pi_priority(thread: &Arc<Thread>) {
+    let pi_futexes = PI_FUTEXES.lock();
+    pi_futexes.update_priority(thread);
+    if let Some(owner) = pi_futexes.blocking_owner(thread) {
+        pi_futexes.update_priority(&owner);
+    }
+}
+
 lazy_static! {
     // Use the same count as linux kernel to keep the same performance
     static ref BUCKET_COUNT: usize = ((1<<8)* num_cpus()).next_power_of_two() as _;
     static ref BUCKET_MASK: usize = *BUCKET_COUNT - 1;
     static ref FUTEX_BUCKETS: FutexBucketVec = FutexBucketVec::new(*BUCKET_COUNT);
+    static ref PI_FUTEXES: Mutex<PiFutexTable> = Mutex::new(PiFutexTable::new());
 }
 
+/// The timeout of a futex wait, which is an absolute deadline measured by a clock.
 #[derive(Debug, Clone)]
-pub struct FutexTimeout {}
+pub struct FutexTimeout {
+    clock_id: ClockID,
+    deadline: Duration,
+}
 
 impl FutexTimeout {
-    pub fn new() -> Self {
-        todo!()
+    /// Creates a timeout that expires after `timeout` if `is_abs_time` is false, or at `timeout`
+    /// measured by the clock of `clock_id` if `is_abs_time` is true.
+    ///
+    /// A relative timeout is measured by the monotonic clock, so that it is not affected if the
+    /// clock is set during the wait.
+    pub fn new(timeout: timespec_t, clock_id: ClockID, is_abs_time: bool) -> Result<Self> {
+        if !timeout.is_valid() {
+            return_errno_with_message!(Errno::EINVAL, "invalid timeout");
+        }
+
+        let timeout = Duration::from(timeout);
+        let (clock_id, deadline) = if is_abs_time {
+            (clock_id, timeout)
+        } else {
+            let now = now_as_duration(&ClockID::CLOCK_MONOTONIC)?;
+            (ClockID::CLOCK_MONOTONIC, now.saturating_add(timeout))
+        };
+        Ok(Self { clock_id, deadline })
     }
 }
 
@@ -168,13 +509,22 @@ impl FutexBucketVec {
     pub fn get_bucket(&self, key: FutexKey) -> (usize, FutexBucketRef) {
         let index = *BUCKET_MASK & {
             // The addr is the multiples of 4, so we ignore the last 2 bits
-            let addr = key.addr() >> 2;
+            let addr = key.hash_addr() >> 2;
             // simple hash
             addr / self.size()
         };
         (index, self.vec[index].clone())
     }
 
+    /// Dequeues the item from whichever bucket it is in.
+    pub fn dequeue_item_from_all(&self, item: &FutexItem) {
+        for bucket in self.vec.iter() {
+            if bucket.lock().dequeue_item(item) {
+                return;
+            }
+        }
+    }
+
     fn size(&self) -> usize {
         self.vec.len()
     }
@@ -195,13 +545,17 @@ impl FutexBucket {
         self.queue.push_back(item);
     }
 
-    pub fn dequeue_item(&mut self, item: &FutexItem) {
+    /// Dequeues the item, returning whether it is found in the bucket.
+    pub fn dequeue_item(&mut self, item: &FutexItem) -> bool {
         let item_i = self
             .queue
             .iter()
-            .position(|futex_item| *futex_item == *item);
+            .position(|futex_item| Arc::ptr_eq(&futex_item.waiter, &item.waiter));
         if let Some(item_i) = item_i {
             self.queue.remove(item_i).unwrap();
+            true
+        } else {
+            false
         }
     }
 
@@ -266,11 +620,13 @@ impl FutexBucket {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 struct FutexItem {
     key: FutexKey,
     bitset: FutexBitSet,
     waiter: FutexWaiterRef,
+    /// The key of the PI futex that the waiter expects to be requeued to, if any.
+    requeue_pi_key: Option<FutexKey>,
 }
 
 impl FutexItem {
@@ -279,6 +635,14 @@ impl FutexItem {
             key,
             bitset,
             waiter: Arc::new(FutexWaiter::new()),
+            requeue_pi_key: None,
+        }
+    }
+
+    pub fn new_requeue_pi(key: FutexKey, requeue_pi_key: FutexKey) -> Self {
+        FutexItem {
+            requeue_pi_key: Some(requeue_pi_key),
+            ..Self::new(key, FUTEX_BITSET_MATCH_ANY)
         }
     }
 
@@ -287,10 +651,8 @@ impl FutexItem {
         self.waiter.wake();
     }
 
-    pub fn wait(&self) {
-        // debug!("wait on futex item, key = {:?}", self.key);
-        self.waiter.wait();
-        // debug!("wait finished, key = {:?}", self.key);
+    pub fn wait(&self, timeout: &Option<FutexTimeout>) -> Result<()> {
+        self.waiter.wait(timeout)
     }
 
     pub fn waiter(&self) -> &FutexWaiterRef {
@@ -303,23 +665,56 @@ impl FutexItem {
     }
 }
 
-// The addr of a futex, it should be used to mark different futex word
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct FutexKey(Vaddr);
+/// The key to identify a futex word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FutexKey {
+    /// A futex that is private to an address space, identified by the virtual address.
+    Private { vmar_id: usize, addr: Vaddr },
+    /// A futex that may be shared between address spaces, identified by the pages of the VMO
+    /// and the offset within them.
+    Shared { pages_id: usize, offset: usize },
+}
 
 impl FutexKey {
-    pub fn new(futex_addr: Vaddr) -> Self {
-        FutexKey(futex_addr as _)
-    }
+    /// Creates the key for the futex word at `futex_addr` in the current address space.
+    ///
+    /// Unless `is_private` is true, the key of a futex word in a shared mapping is resolved
+    /// through the VMO behind the address, so that the same futex word mapped to different
+    /// address spaces has the same key. The physical address is not used, since the page may
+    /// be moved to another frame after being swapped out or evicted.
+    pub fn new(futex_addr: Vaddr, is_private: bool) -> Result<Self> {
+        if futex_addr % core::mem::align_of::<u32>() != 0 {
+            return_errno_with_message!(Errno::EINVAL, "the futex address is not aligned");
+        }
+
+        let current = current!();
+        let root_vmar = current.root_vmar();
+        let private_key = Self::Private {
+            vmar_id: root_vmar.id(),
+            addr: futex_addr,
+        };
+        if is_private {
+            return Ok(private_key);
+        }
+
+        let vm_mapping = root_vmar.get_vm_mapping(futex_addr)?;
+        // The pages of a private mapping are never visible to other address spaces once written,
+        // so the virtual address is enough to identify the futex word.
+        if !vm_mapping.is_shared() {
+            return Ok(private_key);
+        }
 
-    pub fn load_val(&self) -> i32 {
-        // FIXME: how to implement a atomic load?
-        warn!("implement an atomic load");
-        read_val_from_user(self.0).unwrap()
+        let vmo_offset = futex_addr - vm_mapping.map_to_addr() + vm_mapping.vmo_offset();
+        let (pages_id, offset) = vm_mapping.vmo().offset_key(vmo_offset);
+        Ok(Self::Shared { pages_id, offset })
     }
 
-    pub fn addr(&self) -> Vaddr {
-        self.0
+    /// Returns the address that is used to select the bucket.
+    pub fn hash_addr(&self) -> usize {
+        match self {
+            Self::Private { addr, .. } => *addr,
+            Self::Shared { pages_id, offset } => pages_id.wrapping_add(*offset),
+        }
     }
 }
 
@@ -339,6 +734,8 @@ pub enum FutexOp {
     FUTEX_TRYLOCK_PI = 8,
     FUTEX_WAIT_BITSET = 9,
     FUTEX_WAKE_BITSET = 10,
+    FUTEX_WAIT_REQUEUE_PI = 11,
+    FUTEX_CMP_REQUEUE_PI = 12,
 }
 
 impl FutexOp {
@@ -355,6 +752,8 @@ impl FutexOp {
             8 => Ok(FutexOp::FUTEX_TRYLOCK_PI),
             9 => Ok(FutexOp::FUTEX_WAIT_BITSET),
             10 => Ok(FutexOp::FUTEX_WAKE_BITSET),
+            11 => Ok(FutexOp::FUTEX_WAIT_REQUEUE_PI),
+            12 => Ok(FutexOp::FUTEX_CMP_REQUEUE_PI),
             _ => return_errno_with_message!(Errno::EINVAL, "Unknown futex op"),
         }
     }
@@ -388,43 +787,57 @@ pub fn futex_op_and_flags_from_u32(bits: u32) -> Result<(FutexOp, FutexFlags)> {
 
 type FutexWaiterRef = Arc<FutexWaiter>;
 
-#[derive(Debug)]
 struct FutexWaiter {
-    is_woken: AtomicBool,
     tid: Tid,
+    is_woken: AtomicBool,
+    is_requeued_to_pi: AtomicBool,
+    pauser: Arc<Pauser>,
 }
 
-impl PartialEq for FutexWaiter {
-    fn eq(&self, other: &Self) -> bool {
-        self.tid == other.tid
+impl Debug for FutexWaiter {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("FutexWaiter")
+            .field("tid", &self.tid)
+            .field("is_woken", &self.is_woken())
+            .finish()
     }
 }
 
 impl FutexWaiter {
     pub fn new() -> Self {
         Self {
-            is_woken: AtomicBool::new(false),
             tid: current_thread!().tid(),
+            is_woken: AtomicBool::new(false),
+            is_requeued_to_pi: AtomicBool::new(false),
+            pauser: Pauser::new(),
         }
     }
 
-    pub fn wait(&self) {
-        let current_thread = current_thread!();
-        if current_thread.tid() != self.tid {
-            return;
-        }
-        self.is_woken.store(false, Ordering::SeqCst);
-        while !self.is_woken() {
-            // debug!("futex is wait for waken, tid = {}", self.tid);
-            Thread::yield_now();
+    /// Waits until the waiter is woken up.
+    ///
+    /// Returns `EINTR` if the waiting is interrupted by a signal, or `ETIMEDOUT` if the timeout
+    /// expires.
+    pub fn wait(&self, timeout: &Option<FutexTimeout>) -> Result<()> {
+        let cond = || self.is_woken().then_some(());
+        let Some(timeout) = timeout else {
+            return self.pauser.pause_until(cond);
+        };
+
+        match self
+            .pauser
+            .pause_until_or_deadline(cond, timeout.clock_id, &timeout.deadline)
+        {
+            Err(err) if err.error() == Errno::ETIME => {
+                return_errno_with_message!(Errno::ETIMEDOUT, "the futex wait timed out")
+            }
+            res => res,
         }
-        // debug!("futex is waken, tid = {}", self.tid);
     }
 
     pub fn wake(&self) {
         if !self.is_woken() {
-            // debug!("wake up futex, tid = {}", self.tid);
             self.is_woken.store(true, Ordering::SeqCst);
+            self.pauser.resume_all();
         }
     }
 
@@ -432,9 +845,221 @@ impl FutexWaiter {
         self.is_woken.load(Ordering::SeqCst)
     }
 
+    pub fn set_requeued_to_pi(&self) {
+        self.is_requeued_to_pi.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requeued_to_pi(&self) -> bool {
+        self.is_requeued_to_pi.load(Ordering::SeqCst)
+    }
+
     pub fn batch_wake(waiters: &[&FutexWaiterRef]) {
         waiters.iter().for_each(|waiter| {
             waiter.wake();
         });
     }
 }
+
+/// The maximum length of a chain of PI futexes that priorities are propagated along.
+const MAX_PI_CHAIN_LEN: usize = 1024;
+
+/// The kernel states of the contended PI futexes.
+///
+/// A PI futex has a kernel state only if there are waiters blocked on it. The owner of a PI
+/// futex inherits the highest priority of its waiters, and the priority is further propagated
+/// if the owner itself is blocked on another PI futex.
+struct PiFutexTable {
+    futexes: BTreeMap<FutexKey, PiFutex>,
+}
+
+struct PiFutex {
+    owner: Arc<Thread>,
+    waiters: Vec<PiWaiter>,
+}
+
+struct PiWaiter {
+    thread: Arc<Thread>,
+    waiter: FutexWaiterRef,
+}
+
+impl PiFutexTable {
+    fn new() -> Self {
+        Self {
+            futexes: BTreeMap::new(),
+        }
+    }
+
+    /// Tries to lock the PI futex for `thread`.
+    ///
+    /// Returns `None` if the futex is locked successfully, or the owner of the futex otherwise.
+    /// In the latter case, `FUTEX_WAITERS` is set in the futex word, so that the owner will
+    /// unlock it through the kernel.
+    fn lock_or_get_owner(
+        &mut self,
+        futex_addr: Vaddr,
+        key: FutexKey,
+        thread: &Arc<Thread>,
+    ) -> Result<Option<Arc<Thread>>> {
+        // FIXME: the futex word should be updated with atomic operations.
+        let futex_val = load_futex_val(futex_addr)? as u32;
+
+        if let Some(pi_futex) = self.futexes.get(&key) {
+            if Arc::ptr_eq(&pi_futex.owner, thread) {
+                return_errno_with_message!(Errno::EDEADLK, "the futex is already locked");
+            }
+            if futex_val & FUTEX_WAITERS == 0 {
+                write_val_to_user(futex_addr, &(futex_val | FUTEX_WAITERS))?;
+            }
+            return Ok(Some(pi_futex.owner.clone()));
+        }
+
+        let owner_tid = futex_val & FUTEX_TID_MASK;
+        if owner_tid == 0 {
+            let new_val = thread.tid() | (futex_val & FUTEX_OWNER_DIED);
+            write_val_to_user(futex_addr, &new_val)?;
+            return Ok(None);
+        }
+        if owner_tid == thread.tid() {
+            return_errno_with_message!(Errno::EDEADLK, "the futex is already locked");
+        }
+
+        let Some(owner) = thread_table::get_thread(owner_tid).filter(|owner| !owner.is_exited())
+        else {
+            return_errno_with_message!(Errno::ESRCH, "the owner of the futex does not exist");
+        };
+        write_val_to_user(futex_addr, &(futex_val | FUTEX_WAITERS))?;
+        Ok(Some(owner))
+    }
+
+    /// Enqueues a waiter to the PI futex owned by `owner`, creating the kernel state of the
+    /// futex if it does not exist.
+    fn enqueue_waiter(
+        &mut self,
+        key: FutexKey,
+        owner: &Arc<Thread>,
+        thread: Arc<Thread>,
+        waiter: FutexWaiterRef,
+    ) {
+        let pi_futex = self.futexes.entry(key).or_insert_with(|| PiFutex {
+            owner: owner.clone(),
+            waiters: Vec::new(),
+        });
+        pi_futex.waiters.push(PiWaiter { thread, waiter });
+    }
+
+    /// Dequeues a waiter that gives up waiting, and restores the priority of the owner.
+    fn dequeue_waiter(&mut self, key: FutexKey, waiter: &FutexWaiterRef) {
+        let Some(pi_futex) = self.futexes.get_mut(&key) else {
+            return;
+        };
+        pi_futex
+            .waiters
+            .retain(|pi_waiter| !Arc::ptr_eq(&pi_waiter.waiter, waiter));
+
+        let owner = pi_futex.owner.clone();
+        if pi_futex.waiters.is_empty() {
+            self.futexes.remove(&key);
+        }
+        self.update_priority(&owner);
+    }
+
+    /// Hands off the PI futex owned by the thread of `owner_tid` to the waiter with the
+    /// highest priority, and wakes it up.
+    ///
+    /// Returns the new owner, or `None` if there are no waiters.
+    fn hand_off(&mut self, key: FutexKey, owner_tid: Tid) -> Option<Arc<Thread>> {
+        let pi_futex = self.futexes.get_mut(&key)?;
+        if pi_futex.owner.tid() != owner_tid {
+            return None;
+        }
+
+        let new_owner = pi_futex.pop_top_waiter().map(|pi_waiter| {
+            pi_waiter.waiter.wake();
+            pi_waiter.thread
+        });
+        match new_owner.as_ref() {
+            Some(new_owner) if !pi_futex.waiters.is_empty() => {
+                pi_futex.owner = new_owner.clone();
+            }
+            _ => {
+                self.futexes.remove(&key);
+            }
+        }
+        new_owner
+    }
+
+    /// Writes the TID of the new owner to the futex word after the futex is handed off.
+    fn fixup_owner(&self, futex_addr: Vaddr, key: FutexKey, tid: Tid) -> Result<()> {
+        let futex_val = load_futex_val(futex_addr)? as u32;
+        let mut new_val = tid | (futex_val & FUTEX_OWNER_DIED);
+        if self.futexes.contains_key(&key) {
+            new_val |= FUTEX_WAITERS;
+        }
+        write_val_to_user(futex_addr, &new_val)
+    }
+
+    /// Updates the priority of the thread according to the PI futexes it owns, and propagates
+    /// the change along the chain of PI futexes it is blocked on.
+    fn update_priority(&self, thread: &Arc<Thread>) {
+        let mut thread = thread.clone();
+        for _ in 0..MAX_PI_CHAIN_LEN {
+            let priority = self.inherited_priority(&thread);
+            if thread.task().priority() == priority {
+                return;
+            }
+            thread.task().set_priority(priority);
+
+            let Some(owner) = self.blocking_owner(&thread) else {
+                return;
+            };
+            thread = owner;
+        }
+    }
+
+    /// Returns the priority that the thread should run with, which is the highest among its
+    /// base priority and the priorities of the waiters of the PI futexes it owns.
+    fn inherited_priority(&self, thread: &Arc<Thread>) -> Priority {
+        self.futexes
+            .values()
+            .filter(|pi_futex| Arc::ptr_eq(&pi_futex.owner, thread))
+            .flat_map(|pi_futex| pi_futex.waiters.iter())
+            .map(|pi_waiter| pi_waiter.thread.task().priority())
+            .fold(
+                thread.task().base_priority(),
+                |priority, waiter_priority| {
+                    if waiter_priority.get() < priority.get() {
+                        waiter_priority
+                    } else {
+                        priority
+                    }
+                },
+            )
+    }
+
+    /// Returns the owner of the PI futex that the thread is blocked on, if any.
+    fn blocking_owner(&self, thread: &Arc<Thread>) -> Option<Arc<Thread>> {
+        self.futexes
+            .values()
+            .find(|pi_futex| {
+                pi_futex
+                    .waiters
+                    .iter()
+                    .any(|pi_waiter| Arc::ptr_eq(&pi_waiter.thread, thread))
+            })
+            .map(|pi_futex| pi_futex.owner.clone())
+    }
+}
+
+impl PiFutex {
+    /// Removes the waiter with the highest priority. Waiters with the same priority are
+    /// removed in FIFO order.
+    fn pop_top_waiter(&mut self) -> Option<PiWaiter> {
+        let top_idx = self
+            .waiters
+            .iter()
+            .enumerate()
+            .min_by_key(|(idx, pi_waiter)| (pi_waiter.thread.task().priority().get(), *idx))?
+            .0;
+        Some(self.waiters.remove(top_idx))
+    }
+}
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::time::Duration;
+
 use aster_rights::{ReadOp, WriteOp};
-use futex::futex_wake;
+use futex::{exit_pi_futexes, futex_wake};
 use robust_list::wake_robust_futex;
 
 use super::{
@@ -18,6 +20,7 @@ use crate::{
     prelude::*,
     process::signal::constants::SIGCONT,
     thread::{thread_table, Tid},
+    time::CpuClock,
     util::write_val_to_user,
 };
 
@@ -26,13 +29,11 @@ pub mod futex;
 mod name;
 mod posix_thread_ext;
 mod robust_list;
-mod timer;
 
 pub use builder::PosixThreadBuilder;
 pub use name::{ThreadName, MAX_THREAD_NAME_LEN};
 pub use posix_thread_ext::PosixThreadExt;
 pub use robust_list::RobustListHead;
-pub use timer::RealTimer;
 
 pub struct PosixThread {
     // Immutable part
@@ -52,8 +53,8 @@ pub struct PosixThread {
     /// Process credentials. At the kernel level, credentials are a per-thread attribute.
     credentials: Credentials,
 
-    /// The timer counts down in real (i.e., wall clock) time
-    real_timer: Mutex<RealTimer>,
+    /// The CPU time consumed by the thread
+    cpu_clock: Arc<CpuClock>,
 
     // Signal
     /// Blocked signals
@@ -71,6 +72,10 @@ impl PosixThread {
         self.process.upgrade().unwrap()
     }
 
+    pub fn weak_process(&self) -> Weak<Process> {
+        self.process.clone()
+    }
+
     pub fn thread_name(&self) -> &Mutex<Option<ThreadName>> {
         &self.name
     }
@@ -146,18 +151,43 @@ impl PosixThread {
         return_errno_with_message!(Errno::EPERM, "sending signal to the thread is not allowed.");
     }
 
-    pub fn real_timer(&self) -> &Mutex<RealTimer> {
-        &self.real_timer
+    /// Returns the CPU clock, which measures the CPU time consumed by the thread.
+    pub fn cpu_clock(&self) -> &Arc<CpuClock> {
+        &self.cpu_clock
+    }
+
+    /// Charges the CPU time that is spent in the user mode or in the kernel mode to the thread
+    /// and its process, and then fires the timers on the CPU clocks that expire.
+    pub fn charge_cpu_time(&self, time: Duration, is_user: bool) {
+        let process = self.process();
+        if is_user {
+            self.cpu_clock.add_user_time(time);
+            process.cpu_clock().add_user_time(time);
+        } else {
+            self.cpu_clock.add_system_time(time);
+            process.cpu_clock().add_system_time(time);
+        }
+        process.timers().check_cpu_timers();
     }
 
     pub(in crate::process) fn enqueue_signal(&self, signal: Box<dyn Signal>) {
         self.sig_queues.lock().enqueue(signal);
     }
 
+    /// Returns the set of the signals that are pending on the thread.
+    pub fn sig_pending(&self) -> SigMask {
+        self.sig_queues.lock().pending_mask()
+    }
+
     pub fn dequeue_signal(&self, mask: &SigMask) -> Option<Box<dyn Signal>> {
         self.sig_queues.lock().dequeue(mask)
     }
 
+    /// Returns whether there are pending signals that are not blocked by `blocked`.
+    pub fn has_pending_signal_unblocked_by(&self, blocked: &SigMask) -> bool {
+        self.sig_queues.lock().has_pending(blocked)
+    }
+
     pub fn register_sigqueue_observer(
         &self,
         observer: Weak<dyn Observer<SigEvents>>,
@@ -209,9 +239,9 @@ impl PosixThread {
             Some(robust_list_head) => robust_list_head,
         };
         debug!("wake the rubust_list: {:?}", list_head);
-        for futex_addr in list_head.futexes() {
+        for (futex_addr, is_pi) in list_head.futexes() {
             // debug!("futex addr = 0x{:x}", futex_addr);
-            wake_robust_futex(futex_addr, tid).unwrap();
+            let _ = wake_robust_futex(futex_addr, is_pi, tid);
         }
         debug!("wake robust futex success");
         *robust_list = None;
@@ -224,7 +254,7 @@ impl PosixThread {
         debug!("wake up ctid");
         if *clear_ctid != 0 {
             debug!("futex wake");
-            futex_wake(*clear_ctid, 1)?;
+            futex_wake(*clear_ctid, 1, false)?;
             debug!("write ctid");
             // FIXME: the correct write length?
             debug!("ctid = 0x{:x}", *clear_ctid);
@@ -235,6 +265,8 @@ impl PosixThread {
         debug!("wake up ctid succeeds");
         // exit the robust list: walk the robust list; mark futex words as dead and do futex wake
         self.wake_robust_list(tid);
+        // hand off the PI futexes still owned by the thread to their waiters
+        exit_pi_futexes(tid);
 
         if tid != self.process().pid() {
             // If the thread is not main thread. We don't remove main thread.
@@ -242,8 +274,6 @@ impl PosixThread {
             thread_table::remove_thread(tid);
         }
 
-        self.real_timer.lock().clear();
-
         if self.is_main_thread() || self.is_last_thread() {
             // exit current process.
             debug!("self is main thread or last thread");
@@ -252,7 +282,7 @@ impl PosixThread {
             do_exit_group(term_status);
         }
         debug!("perform futex wake");
-        futex_wake(Arc::as_ptr(&self.process()) as Vaddr, 1)?;
+        futex_wake(Arc::as_ptr(&self.process()) as Vaddr, 1, true)?;
         Ok(())
     }
 
@@ -4,7 +4,10 @@
 
 use crate::{
     prelude::*,
-    process::{posix_thread::futex::futex_wake, Pid},
+    process::{
+        posix_thread::futex::{futex_wake, FUTEX_OWNER_DIED, FUTEX_TID_MASK, FUTEX_WAITERS},
+        Pid,
+    },
     util::{read_val_from_user, write_val_to_user},
 };
 
@@ -34,18 +37,20 @@ pub struct RobustListHead {
 impl RobustListHead {
     /// Return an iterator for all futexes in the robust list.
     ///
+    /// Each item is the address of the futex and whether it is a PI futex.
     /// The futex refered to by `list_op_pending`, if any, will be returned as
     /// the last item.
     pub fn futexes(&self) -> FutexIter<'_> {
         FutexIter::new(self)
     }
 
-    /// Return the pending futex address if exist
-    fn pending_futex_addr(&self) -> Option<Vaddr> {
-        if self.list_op_pending == 0 {
+    /// Return the pending futex if exist
+    fn pending_futex(&self) -> Option<(Vaddr, bool)> {
+        let (entry_ptr, is_pi) = split_entry_ptr(self.list_op_pending);
+        if entry_ptr == 0 {
             None
         } else {
-            Some(self.futex_addr(self.list_op_pending))
+            Some((self.futex_addr(entry_ptr), is_pi))
         }
     }
 
@@ -84,47 +89,62 @@ impl<'a> FutexIter<'a> {
 
 const ROBUST_LIST_LIMIT: isize = 2048;
 
+/// The lowest bit of an entry pointer indicates that the futex is a PI futex.
+const ROBUST_ENTRY_PI_FLAG: Vaddr = 1;
+
+/// Splits an entry pointer into the address of the entry and whether the futex is a PI futex.
+fn split_entry_ptr(entry_ptr: Vaddr) -> (Vaddr, bool) {
+    (
+        entry_ptr & !ROBUST_ENTRY_PI_FLAG,
+        entry_ptr & ROBUST_ENTRY_PI_FLAG != 0,
+    )
+}
+
 impl<'a> Iterator for FutexIter<'a> {
-    type Item = Vaddr;
+    type Item = (Vaddr, bool);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.is_end() {
             return None;
         }
 
-        while self.entry_ptr != &self.robust_list.list as *const _ as usize {
+        let (pending_ptr, _) = split_entry_ptr(self.robust_list.list_op_pending);
+        loop {
+            let (entry_ptr, is_pi) = split_entry_ptr(self.entry_ptr);
+            if entry_ptr == &self.robust_list.list as *const _ as usize {
+                break;
+            }
             if self.count == ROBUST_LIST_LIMIT {
                 break;
             }
-            if self.entry_ptr == 0 {
+            if entry_ptr == 0 {
                 return None;
             }
-            let futex_addr = if self.entry_ptr != self.robust_list.list_op_pending {
-                Some(self.robust_list.futex_addr(self.entry_ptr))
+            let futex = if entry_ptr != pending_ptr {
+                Some((self.robust_list.futex_addr(entry_ptr), is_pi))
             } else {
                 None
             };
-            let Ok(robust_list) = read_val_from_user::<RobustList>(self.entry_ptr) else {
+            let Ok(robust_list) = read_val_from_user::<RobustList>(entry_ptr) else {
                 return None;
             };
             self.entry_ptr = robust_list.next;
             self.count += 1;
-            if futex_addr.is_some() {
-                return futex_addr;
+            if futex.is_some() {
+                return futex;
             }
         }
         self.set_end();
-        self.robust_list.pending_futex_addr()
+        self.robust_list.pending_futex()
     }
 }
 
-const FUTEX_WAITERS: u32 = 0x8000_0000;
-const FUTEX_OWNER_DIED: u32 = 0x4000_0000;
-const FUTEX_TID_MASK: u32 = 0x3FFF_FFFF;
-
 /// Wakeup one robust futex owned by the thread
+///
+/// The waiters of a PI futex are not woken up here. Instead, the futex is handed off to one
+/// of them when the PI futexes of the thread are released.
 /// FIXME: requires atomic operations here
-pub fn wake_robust_futex(futex_addr: Vaddr, tid: Pid) -> Result<()> {
+pub fn wake_robust_futex(futex_addr: Vaddr, is_pi: bool, tid: Pid) -> Result<()> {
     let futex_val = {
         if futex_addr == 0 {
             return_errno_with_message!(Errno::EINVAL, "invalid futext addr");
@@ -146,9 +166,9 @@ pub fn wake_robust_futex(futex_addr: Vaddr, tid: Pid) -> Result<()> {
             continue;
         }
         // Wakeup one waiter
-        if cur_val & FUTEX_WAITERS != 0 {
+        if cur_val & FUTEX_WAITERS != 0 && !is_pi {
             debug!("wake robust futex addr: {:?}", futex_addr);
-            futex_wake(futex_addr, 1)?;
+            futex_wake(futex_addr, 1, false)?;
         }
         break;
     }
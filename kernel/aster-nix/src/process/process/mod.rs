@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::sync::atomic::AtomicI16;
+
 use super::{
     posix_thread::PosixThreadExt,
     process_table,
@@ -10,6 +12,7 @@ use super::{
         Pauser,
     },
     status::ProcessStatus,
+    timer::ProcessTimers,
     Credentials, TermStatus,
 };
 use crate::{
@@ -18,6 +21,7 @@ use crate::{
     prelude::*,
     sched::nice::Nice,
     thread::{allocate_tid, Thread},
+    time::CpuClock,
     vm::vmar::Vmar,
 };
 
@@ -78,10 +82,20 @@ pub struct Process {
     /// According to POSIX.1, the nice value is a per-process attribute,
     /// the threads in a process should share a nice value.
     nice: Atomic<Nice>,
+    /// The adjustment to the badness of the process when the OOM killer chooses a victim
+    oom_score_adj: AtomicI16,
 
     // Signal
     /// Sig dispositions
     sig_dispositions: Arc<Mutex<SigDispositions>>,
+
+    // Time
+    /// The CPU time consumed by all the threads of the process
+    cpu_clock: Arc<CpuClock>,
+    /// The CPU time consumed by the reaped children, including their reaped descendants
+    children_cpu_clock: CpuClock,
+    /// The interval timers and the POSIX timers
+    timers: ProcessTimers,
 }
 
 impl Process {
@@ -98,7 +112,8 @@ impl Process {
         sig_dispositions: Arc<Mutex<SigDispositions>>,
         resource_limits: ResourceLimits,
         nice: Nice,
-    ) -> Self {
+        oom_score_adj: i16,
+    ) -> Arc<Self> {
         let children_pauser = {
             // SIGCHID does not interrupt pauser. Child process will
             // resume paused parent when doing exit.
@@ -106,7 +121,9 @@ impl Process {
             Pauser::new_with_mask(sigmask)
         };
 
-        Self {
+        let cpu_clock = Arc::new(CpuClock::new());
+
+        Arc::new_cyclic(|weak_process| Self {
             pid,
             threads: Mutex::new(threads),
             executable_path: RwLock::new(executable_path),
@@ -122,7 +139,11 @@ impl Process {
             sig_dispositions,
             resource_limits: Mutex::new(resource_limits),
             nice: Atomic::new(nice),
-        }
+            oom_score_adj: AtomicI16::new(oom_score_adj),
+            timers: ProcessTimers::new(weak_process.clone(), &cpu_clock),
+            cpu_clock,
+            children_cpu_clock: CpuClock::new(),
+        })
     }
 
     /// init a user process and run the process
@@ -218,6 +239,10 @@ impl Process {
         &self.nice
     }
 
+    pub fn oom_score_adj(&self) -> &AtomicI16 {
+        &self.oom_score_adj
+    }
+
     pub fn main_thread(&self) -> Option<Arc<Thread>> {
         self.threads
             .lock()
@@ -556,6 +581,23 @@ impl Process {
         posix_thread.enqueue_signal(Box::new(signal));
     }
 
+    // ******************* Time ********************
+
+    /// Returns the CPU clock, which measures the CPU time consumed by all the threads.
+    pub fn cpu_clock(&self) -> &Arc<CpuClock> {
+        &self.cpu_clock
+    }
+
+    /// Returns the CPU clock of the reaped children, which measures the CPU time consumed by
+    /// the children that have been waited for, including their reaped descendants.
+    pub fn children_cpu_clock(&self) -> &CpuClock {
+        &self.children_cpu_clock
+    }
+
+    pub fn timers(&self) -> &ProcessTimers {
+        &self.timers
+    }
+
     // ******************* Status ********************
 
     fn set_runnable(&self) {
@@ -603,7 +645,7 @@ mod test {
         } else {
             Weak::new()
         };
-        Arc::new(Process::new(
+        Process::new(
             pid,
             parent,
             vec![],
@@ -615,7 +657,8 @@ mod test {
             Arc::new(Mutex::new(SigDispositions::default())),
             ResourceLimits::default(),
             Nice::default(),
-        ))
+            0,
+        )
     }
 
     fn new_process_in_session(parent: Option<Arc<Process>>) -> Arc<Process> {
@@ -8,10 +8,12 @@
 
 pub mod user_heap;
 
+use core::ops::Range;
+
 use aster_rights::Full;
 use user_heap::UserHeap;
 
-use crate::vm::vmar::Vmar;
+use crate::{prelude::*, vm::vmar::Vmar};
 
 /*
  * The user's virtual memory space layout looks like below.
@@ -54,6 +56,18 @@ use crate::vm::vmar::Vmar;
 pub struct ProcessVm {
     user_heap: UserHeap,
     root_vmar: Vmar<Full>,
+    init_stack_layout: Mutex<InitStackLayout>,
+}
+
+/// The layout of the init stack, which is recorded when a program is loaded.
+#[derive(Debug, Clone, Default)]
+pub struct InitStackLayout {
+    /// The address range of the init stack.
+    pub stack: Range<Vaddr>,
+    /// The address range of the null-terminated argument strings.
+    pub argv: Range<Vaddr>,
+    /// The address range of the null-terminated environment strings.
+    pub envp: Range<Vaddr>,
 }
 
 impl Clone for ProcessVm {
@@ -61,6 +75,7 @@ impl Clone for ProcessVm {
         Self {
             root_vmar: self.root_vmar.dup().unwrap(),
             user_heap: self.user_heap.clone(),
+            init_stack_layout: Mutex::new(self.init_stack_layout()),
         }
     }
 }
@@ -73,14 +88,17 @@ impl ProcessVm {
         ProcessVm {
             user_heap,
             root_vmar,
+            init_stack_layout: Mutex::new(InitStackLayout::default()),
         }
     }
 
-    pub fn new(user_heap: UserHeap, root_vmar: Vmar<Full>) -> Self {
-        Self {
-            user_heap,
-            root_vmar,
-        }
+    /// Forks a new copy-on-write process vm from the parent.
+    pub fn fork_from(parent: &ProcessVm) -> Result<Self> {
+        Ok(Self {
+            user_heap: parent.user_heap.clone(),
+            root_vmar: Vmar::<Full>::fork_from(&parent.root_vmar)?,
+            init_stack_layout: Mutex::new(parent.init_stack_layout()),
+        })
     }
 
     pub fn user_heap(&self) -> &UserHeap {
@@ -91,9 +109,19 @@ impl ProcessVm {
         &self.root_vmar
     }
 
+    /// Returns the layout of the init stack.
+    pub fn init_stack_layout(&self) -> InitStackLayout {
+        self.init_stack_layout.lock().clone()
+    }
+
+    pub(in crate::process) fn set_init_stack_layout(&self, layout: InitStackLayout) {
+        *self.init_stack_layout.lock() = layout;
+    }
+
     /// Set user vm to the init status
     pub fn clear(&self) {
         self.root_vmar.clear().unwrap();
         self.user_heap.set_default(&self.root_vmar);
+        self.set_init_stack_layout(InitStackLayout::default());
     }
 }
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use align_ext::AlignExt;
 use aster_rights::{Full, Rights};
@@ -72,6 +75,11 @@ impl UserHeap {
         }
     }
 
+    /// Returns the range of the heap that is in use, i.e., from the heap base to the program break.
+    pub fn heap_range(&self) -> Range<Vaddr> {
+        self.heap_base..self.current_heap_end.load(Ordering::Relaxed)
+    }
+
     /// Set heap to the default status. i.e., point the heap end to heap base.
     /// This function will we called in execve.
     pub fn set_default(&self, root_vmar: &Vmar<Full>) {
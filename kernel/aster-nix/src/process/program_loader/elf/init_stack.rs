@@ -4,7 +4,7 @@
 //! The process initial stack, contains arguments, environmental variables and auxiliary vectors
 //! The data layout of init stack can be seen in Figure 3.9 in <https://uclibc.org/docs/psABI-x86_64.pdf>
 
-use core::mem;
+use core::{mem, ops::Range};
 
 use align_ext::AlignExt;
 use aster_frame::vm::{VmIo, VmPerm, MAX_USERSPACE_VADDR};
@@ -17,6 +17,7 @@ use super::{
 };
 use crate::{
     prelude::*,
+    process::process_vm::InitStackLayout,
     vm::{perms::VmPerms, vmar::Vmar, vmo::VmoOptions},
 };
 
@@ -77,6 +78,10 @@ pub struct InitStack {
     argv: Vec<CString>,
     /// Environmental variables
     envp: Vec<CString>,
+    /// The address range of the argument strings
+    argv_range: Range<Vaddr>,
+    /// The address range of the environment strings
+    envp_range: Range<Vaddr>,
 }
 
 impl InitStack {
@@ -93,6 +98,8 @@ impl InitStack {
             pos: init_stack_top,
             argv,
             envp,
+            argv_range: 0..0,
+            envp_range: 0..0,
         }
     }
 
@@ -185,9 +192,13 @@ impl InitStack {
         // FIXME: Some other OSes put the first page of excutable file here.
         self.write_bytes(&[0u8; PAGE_SIZE], root_vmar)?;
         // write envp string
+        let envp_end = self.pos;
         let envp_pointers = self.write_envp_strings(root_vmar)?;
+        self.envp_range = self.pos..envp_end;
         // write argv string
+        let argv_end = self.pos;
         let argv_pointers = self.write_argv_strings(root_vmar)?;
+        self.argv_range = self.pos..argv_end;
         // write random value
         let random_value = generate_random_for_aux_vec();
         let random_value_pointer = self.write_bytes(&random_value, root_vmar)?;
@@ -209,10 +220,13 @@ impl InitStack {
     fn write_envp_strings(&mut self, root_vmar: &Vmar<Full>) -> Result<Vec<u64>> {
         let envp = self.envp.to_vec();
         let mut envp_pointers = Vec::with_capacity(envp.len());
-        for envp in envp.iter() {
+        // Write the strings in the reverse order, so that they are placed in order in memory,
+        // just as the argument strings.
+        for envp in envp.iter().rev() {
             let pointer = self.write_cstring(envp, root_vmar)?;
             envp_pointers.push(pointer);
         }
+        envp_pointers.reverse();
         Ok(envp_pointers)
     }
 
@@ -295,6 +309,15 @@ impl InitStack {
         0
     }
 
+    /// Returns the layout of the init stack, which is valid after the init stack is written.
+    pub fn layout(&self) -> InitStackLayout {
+        InitStackLayout {
+            stack: self.user_stack_bottom()..self.init_stack_top,
+            argv: self.argv_range.clone(),
+            envp: self.envp_range.clone(),
+        }
+    }
+
     /// returns the top address of init stack.
     /// It should points to a fixed address.
     pub const fn init_stack_top(&self) -> Vaddr {
@@ -94,7 +94,11 @@ fn lookup_and_parse_ldso(
     Ok((ldso_file, ldso_elf))
 }
 
-fn load_ldso(root_vmar: &Vmar<Full>, ldso_file: &Dentry, ldso_elf: &Elf) -> Result<LdsoLoadInfo> {
+fn load_ldso(
+    root_vmar: &Vmar<Full>,
+    ldso_file: &Arc<Dentry>,
+    ldso_elf: &Elf,
+) -> Result<LdsoLoadInfo> {
     let map_addr = map_segment_vmos(ldso_elf, root_vmar, ldso_file)?;
     Ok(LdsoLoadInfo::new(
         ldso_elf.entry_point() + map_addr,
@@ -106,7 +110,7 @@ fn init_and_map_vmos(
     process_vm: &ProcessVm,
     ldso: Option<(Arc<Dentry>, Elf)>,
     elf: &Elf,
-    elf_file: &Dentry,
+    elf_file: &Arc<Dentry>,
     argv: Vec<CString>,
     envp: Vec<CString>,
     vdso_text_base: Vaddr,
@@ -124,6 +128,7 @@ fn init_and_map_vmos(
     let mut aux_vec = init_aux_vec(elf, map_addr, vdso_text_base)?;
     let mut init_stack = InitStack::new_default_config(argv, envp);
     init_stack.init(root_vmar, elf, &ldso_load_info, &mut aux_vec)?;
+    process_vm.set_init_stack_layout(init_stack.layout());
     let entry_point = if let Some(ldso_load_info) = ldso_load_info {
         // Normal shared object
         ldso_load_info.entry_point()
@@ -184,7 +189,11 @@ impl ElfLoadInfo {
 }
 
 /// init vmo for each segment and then map segment to root vmar
-pub fn map_segment_vmos(elf: &Elf, root_vmar: &Vmar<Full>, elf_file: &Dentry) -> Result<Vaddr> {
+pub fn map_segment_vmos(
+    elf: &Elf,
+    root_vmar: &Vmar<Full>,
+    elf_file: &Arc<Dentry>,
+) -> Result<Vaddr> {
     // all segments of the shared object must be mapped to a continuous vm range
     // to ensure the relative offset of each segment not changed.
     let base_addr = if elf.is_shared_object() {
@@ -206,6 +215,7 @@ pub fn map_segment_vmos(elf: &Elf, root_vmar: &Vmar<Full>, elf_file: &Dentry) ->
                 anonymous_map_size,
                 root_vmar,
                 base_addr,
+                elf_file,
             )?;
         }
     }
@@ -244,6 +254,7 @@ fn map_segment_vmo(
     anonymous_map_size: usize,
     root_vmar: &Vmar<Full>,
     base_addr: Vaddr,
+    elf_file: &Arc<Dentry>,
 ) -> Result<()> {
     let perms = VmPerms::from(parse_segment_perm(program_header.flags));
     let offset = (program_header.virtual_addr as Vaddr).align_down(PAGE_SIZE);
@@ -254,7 +265,11 @@ fn map_segment_vmo(
         perms
     );
     let vmo_size = vmo.size();
-    let mut vm_map_options = root_vmar.new_map(vmo, perms)?.can_overwrite(true);
+    let file_offset = (program_header.offset as usize).align_down(PAGE_SIZE);
+    let mut vm_map_options = root_vmar
+        .new_map(vmo, perms)?
+        .can_overwrite(true)
+        .file(elf_file.clone(), file_offset);
     let offset = base_addr + offset;
     vm_map_options = vm_map_options.offset(offset);
     let map_addr = vm_map_options.build()?;
@@ -24,6 +24,9 @@ impl Default for ResourceLimits {
         let stack_size = RLimit64::new(INIT_STACK_SIZE as u64);
         let heap_size = RLimit64::new(USER_HEAP_SIZE_LIMIT as u64);
         let open_files = RLimit64::new(1024);
+        let locked_memory = RLimit64::new(8 * 1024 * 1024);
+        // Unprivileged processes cannot use real-time policies by default.
+        let rt_priority = RLimit64::new(0);
 
         let mut rlimits = Self {
             rlimits: [RLimit64::default(); RLIMIT_COUNT],
@@ -31,6 +34,8 @@ impl Default for ResourceLimits {
         *rlimits.get_rlimit_mut(ResourceType::RLIMIT_STACK) = stack_size;
         *rlimits.get_rlimit_mut(ResourceType::RLIMIT_DATA) = heap_size;
         *rlimits.get_rlimit_mut(ResourceType::RLIMIT_NOFILE) = open_files;
+        *rlimits.get_rlimit_mut(ResourceType::RLIMIT_MEMLOCK) = locked_memory;
+        *rlimits.get_rlimit_mut(ResourceType::RLIMIT_RTPRIO) = rt_priority;
         rlimits
     }
 }
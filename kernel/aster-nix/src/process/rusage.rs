@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(non_camel_case_types)]
+
+use core::time::Duration;
+
+use crate::{prelude::*, time::timeval_t};
+
+/// The target whose resource usage is measured by `getrusage`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+pub enum RusageTarget {
+    /// The calling process, i.e., all of its threads.
+    RUSAGE_SELF = 0,
+    /// The children of the calling process that have been waited for.
+    RUSAGE_CHILDREN = -1,
+    /// The calling thread.
+    RUSAGE_THREAD = 1,
+}
+
+/// The resource usage reported by `getrusage` and `wait4`.
+///
+/// Only the CPU time is tracked now, so the other fields are always zero.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+pub struct rusage_t {
+    /// The user time
+    pub ru_utime: timeval_t,
+    /// The system time
+    pub ru_stime: timeval_t,
+    /// The maximum resident set size
+    pub ru_maxrss: i64,
+    /// The integral shared memory size
+    pub ru_ixrss: i64,
+    /// The integral unshared data size
+    pub ru_idrss: i64,
+    /// The integral unshared stack size
+    pub ru_isrss: i64,
+    /// The number of page reclaims (soft page faults)
+    pub ru_minflt: i64,
+    /// The number of page faults (hard page faults)
+    pub ru_majflt: i64,
+    /// The number of swaps
+    pub ru_nswap: i64,
+    /// The number of block input operations
+    pub ru_inblock: i64,
+    /// The number of block output operations
+    pub ru_oublock: i64,
+    /// The number of IPC messages sent
+    pub ru_msgsnd: i64,
+    /// The number of IPC messages received
+    pub ru_msgrcv: i64,
+    /// The number of signals received
+    pub ru_nsignals: i64,
+    /// The number of voluntary context switches
+    pub ru_nvcsw: i64,
+    /// The number of involuntary context switches
+    pub ru_nivcsw: i64,
+}
+
+impl rusage_t {
+    pub fn new(user_time: Duration, system_time: Duration) -> Self {
+        Self {
+            ru_utime: timeval_t::from(user_time),
+            ru_stime: timeval_t::from(system_time),
+            ..Default::default()
+        }
+    }
+}
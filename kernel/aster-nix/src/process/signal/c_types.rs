@@ -56,6 +56,37 @@ impl siginfo_t {
         // let siginfo = *self;
         read_union_fields!(self.siginfo_fields.sigfault.addr)
     }
+
+    /// Sets the PID and the real UID of the sender.
+    pub fn set_si_pid_uid(&mut self, pid: Pid, uid: Uid) {
+        self.siginfo_fields.common.first.piduid = siginfo_piduid_t { pid, uid };
+    }
+
+    pub fn si_pid(&self) -> Pid {
+        read_union_fields!(self.siginfo_fields.common.first.piduid.pid)
+    }
+
+    pub fn si_uid(&self) -> Uid {
+        read_union_fields!(self.siginfo_fields.common.first.piduid.uid)
+    }
+
+    pub fn set_si_timer(&mut self, timerid: i32, overrun: i32) {
+        self.siginfo_fields.common.first.timer = siginfo_timer_t { timerid, overrun };
+    }
+
+    /// Returns the ID and the overrun count of the timer.
+    pub fn si_timer(&self) -> (i32, i32) {
+        let timer: siginfo_timer_t = read_union_fields!(self.siginfo_fields.common.first.timer);
+        (timer.timerid, timer.overrun)
+    }
+
+    pub fn set_si_value(&mut self, value: sigval_t) {
+        self.siginfo_fields.common.second.value = value;
+    }
+
+    pub fn si_value(&self) -> sigval_t {
+        read_union_fields!(self.siginfo_fields.common.second.value)
+    }
 }
 
 #[derive(Clone, Copy, Pod)]
@@ -76,7 +107,7 @@ impl siginfo_fields_t {
 
 #[derive(Clone, Copy, Pod)]
 #[repr(C)]
-union siginfo_common_t {
+struct siginfo_common_t {
     first: siginfo_common_first_t,
     second: siginfo_common_second_t,
 }
@@ -116,6 +147,47 @@ pub union sigval_t {
     sigval_ptr: Vaddr, //*mut c_void
 }
 
+impl sigval_t {
+    pub fn from_int(sigval_int: i32) -> Self {
+        Self { sigval_int }
+    }
+
+    pub fn from_ptr(sigval_ptr: Vaddr) -> Self {
+        Self { sigval_ptr }
+    }
+
+    pub fn as_int(&self) -> i32 {
+        read_union_fields!(self.sigval_int)
+    }
+
+    /// Returns the value as a pointer, which covers all the bytes of the union.
+    pub fn as_ptr(&self) -> Vaddr {
+        read_union_fields!(self.sigval_ptr)
+    }
+}
+
+impl Debug for sigval_t {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("sigval_t")
+            .field("sigval_ptr", &self.as_ptr())
+            .finish()
+    }
+}
+
+/// The structure that specifies how to notify a process of an event, e.g., the expiration of a
+/// POSIX timer.
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+pub struct sigevent_t {
+    pub sigev_value: sigval_t,
+    pub sigev_signo: i32,
+    pub sigev_notify: i32,
+    /// The thread ID for `SIGEV_THREAD_ID`, which shares the space with the function and the
+    /// attributes of the thread for `SIGEV_THREAD`.
+    pub sigev_tid: i32,
+    _pad: [i32; 11],
+}
+
 #[derive(Clone, Copy, Pod)]
 #[repr(C)]
 union siginfo_sigchild_t {
@@ -8,7 +8,12 @@ use core::{
 use aster_frame::sync::WaitQueue;
 
 use super::{sig_mask::SigMask, SigEvents, SigEventsFilter};
-use crate::{events::Observer, prelude::*, process::posix_thread::PosixThreadExt};
+use crate::{
+    events::Observer,
+    prelude::*,
+    process::posix_thread::PosixThreadExt,
+    time::{ClockID, ClockTimer},
+};
 
 /// A `Pauser` allows pausing the execution of the current thread until certain conditions are reached.
 ///
@@ -101,6 +106,45 @@ impl Pauser {
         self.do_pause(cond, Some(timeout))
     }
 
+    /// Pause the execution of current thread until the `cond` is met ( i.e., `cond()` returns
+    /// `Some(_)` ), or some signal is received by current thread or process, or the clock of
+    /// `clock_id` reaches `deadline`.
+    ///
+    /// Unlike a timeout, the deadline is still reached at the right time if the clock is set
+    /// while the current thread is paused. If `deadline` is reached before the `cond` is met or
+    /// some signal is received, it will returns `Err(ETIME)`.
+    pub fn pause_until_or_deadline<F, R>(
+        self: &Arc<Self>,
+        mut cond: F,
+        clock_id: ClockID,
+        deadline: &Duration,
+    ) -> Result<R>
+    where
+        F: FnMut() -> Option<R>,
+    {
+        let is_expired = Arc::new(AtomicBool::new(false));
+        let timer = {
+            let is_expired = is_expired.clone();
+            let pauser = Arc::downgrade(self);
+            ClockTimer::new(clock_id, move || {
+                is_expired.store(true, Ordering::Release);
+                if let Some(pauser) = pauser.upgrade() {
+                    pauser.resume_all();
+                }
+            })
+        };
+        timer.set_deadline(*deadline)?;
+
+        let res = self.pause_until(|| {
+            if let Some(res) = cond() {
+                return Some(Some(res));
+            }
+            is_expired.load(Ordering::Acquire).then_some(None)
+        })?;
+
+        res.ok_or_else(|| Error::with_message(Errno::ETIME, "the deadline is reached"))
+    }
+
     fn do_pause<F, R>(self: &Arc<Self>, mut cond: F, timeout: Option<&Duration>) -> Result<R>
     where
         F: FnMut() -> Option<R>,
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{constants::*, sig_action::SigAction, sig_num::SigNum};
+use super::{constants::*, sig_action::SigAction, sig_mask::SigMask, sig_num::SigNum};
 
 #[derive(Copy, Clone)]
 pub struct SigDispositions {
@@ -48,6 +48,26 @@ impl SigDispositions {
         }
     }
 
+    /// Returns the set of signals that are ignored.
+    pub fn ignored_mask(&self) -> SigMask {
+        self.mask_of(|sigaction| matches!(sigaction, SigAction::Ign))
+    }
+
+    /// Returns the set of signals that are caught by user-given handlers.
+    pub fn caught_mask(&self) -> SigMask {
+        self.mask_of(|sigaction| matches!(sigaction, SigAction::User { .. }))
+    }
+
+    fn mask_of(&self, predicate: impl Fn(&SigAction) -> bool) -> SigMask {
+        let mut mask = SigMask::new_empty();
+        for (idx, sigaction) in self.map.iter().enumerate() {
+            if predicate(sigaction) {
+                mask.add_signal(SigNum::from_u8(idx as u8 + MIN_STD_SIG_NUM));
+            }
+        }
+        mask
+    }
+
     fn num_to_idx(num: SigNum) -> usize {
         (num.as_u8() - MIN_STD_SIG_NUM) as usize
     }
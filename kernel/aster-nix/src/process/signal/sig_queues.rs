@@ -131,6 +131,44 @@ impl SigQueues {
         None
     }
 
+    /// Returns whether there are pending signals that are not blocked by `blocked`.
+    pub fn has_pending(&self, blocked: &SigMask) -> bool {
+        // Fast path for the common case of no pending signals
+        if self.is_empty() {
+            return false;
+        }
+
+        (MIN_STD_SIG_NUM..=MAX_RT_SIG_NUM).any(|num| {
+            let signum = SigNum::from_u8(num);
+            if blocked.contains(signum) {
+                return false;
+            }
+
+            if signum.is_std() {
+                self.std_queues[(num - MIN_STD_SIG_NUM) as usize].is_some()
+            } else {
+                !self.rt_queues[(num - MIN_RT_SIG_NUM) as usize].is_empty()
+            }
+        })
+    }
+
+    /// Returns the set of the pending signals.
+    pub fn pending_mask(&self) -> SigMask {
+        let mut pending = SigMask::new_empty();
+        for num in MIN_STD_SIG_NUM..=MAX_RT_SIG_NUM {
+            let signum = SigNum::from_u8(num);
+            let is_pending = if signum.is_std() {
+                self.std_queues[(num - MIN_STD_SIG_NUM) as usize].is_some()
+            } else {
+                !self.rt_queues[(num - MIN_RT_SIG_NUM) as usize].is_empty()
+            };
+            if is_pending {
+                pending.add_signal(signum);
+            }
+        }
+        pending
+    }
+
     fn get_std_queue_mut(&mut self, signum: SigNum) -> &mut Option<Box<dyn Signal>> {
         debug_assert!(signum.is_std());
         let idx = (signum.as_u8() - MIN_STD_SIG_NUM) as usize;
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+
+use super::Signal;
+use crate::{
+    prelude::*,
+    process::signal::{
+        c_types::{siginfo_t, sigval_t},
+        constants::SI_TIMER,
+        sig_num::SigNum,
+    },
+};
+
+/// The signal that is sent when a POSIX timer expires.
+#[derive(Debug)]
+pub struct TimerSignal {
+    num: SigNum,
+    timer_id: i32,
+    value: sigval_t,
+    overrun: Arc<TimerOverrun>,
+    /// Whether the signal has been dequeued, i.e., its overruns have been reported.
+    is_dequeued: AtomicBool,
+}
+
+impl TimerSignal {
+    pub fn new(num: SigNum, timer_id: i32, value: sigval_t, overrun: Arc<TimerOverrun>) -> Self {
+        Self {
+            num,
+            timer_id,
+            value,
+            overrun,
+            is_dequeued: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Signal for TimerSignal {
+    fn num(&self) -> SigNum {
+        self.num
+    }
+
+    fn to_info(&self) -> siginfo_t {
+        // The signal is only converted to `siginfo_t` after it is dequeued.
+        self.is_dequeued.store(true, Ordering::Relaxed);
+        let overrun = self.overrun.take();
+
+        let mut info = siginfo_t::new(self.num, SI_TIMER);
+        info.set_si_timer(self.timer_id, overrun);
+        info.set_si_value(self.value);
+        info
+    }
+}
+
+impl Drop for TimerSignal {
+    fn drop(&mut self) {
+        // The signal may be discarded without being dequeued, e.g., if the same standard signal
+        // is already pending. The timer should still be able to queue the next signal.
+        if !self.is_dequeued.load(Ordering::Relaxed) {
+            self.overrun.clear_signal_pending();
+        }
+    }
+}
+
+/// The overrun count of a POSIX timer.
+///
+/// A timer queues at most one signal at a time. The expirations that occur while the signal is
+/// still pending are counted as overruns, which are reported when the signal is delivered.
+#[derive(Debug, Default)]
+pub struct TimerOverrun {
+    /// The overruns that have not been reported.
+    pending: AtomicU64,
+    /// The overruns that are reported by the last delivered signal.
+    last: AtomicI32,
+    /// Whether a signal of the timer is queued and has not been dequeued.
+    is_signal_pending: AtomicBool,
+}
+
+impl TimerOverrun {
+    /// Marks that a signal of the timer is queued, returning whether the last signal is still
+    /// pending.
+    ///
+    /// If the last signal is still pending, no more signal should be queued.
+    pub fn set_signal_pending(&self) -> bool {
+        self.is_signal_pending.swap(true, Ordering::AcqRel)
+    }
+
+    /// Adds overruns that will be reported by the next delivered signal.
+    pub fn add(&self, overrun: u64) {
+        self.pending.fetch_add(overrun, Ordering::Relaxed);
+    }
+
+    /// Returns the overruns that are reported by the last delivered signal.
+    pub fn last(&self) -> i32 {
+        self.last.load(Ordering::Relaxed)
+    }
+
+    fn clear_signal_pending(&self) {
+        self.is_signal_pending.store(false, Ordering::Release);
+    }
+
+    fn take(&self) -> i32 {
+        // The signal is dequeued, so the next expiration can queue a new signal.
+        self.clear_signal_pending();
+
+        let overrun = self.pending.swap(0, Ordering::Relaxed);
+        // The count is saturated as `DELAYTIMER_MAX` in Linux.
+        let overrun = i32::try_from(overrun).unwrap_or(i32::MAX);
+        self.last.store(overrun, Ordering::Relaxed);
+        overrun
+    }
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use aster_frame::timer::{Timer, TIMER_FREQ};
+
+use crate::{
+    prelude::*,
+    process::{posix_thread::PosixThreadExt, process_table, Pid},
+    thread::{
+        thread_table,
+        work_queue::{submit_work_item, work_item::WorkItem, WorkPriority},
+        Tid,
+    },
+    time::{clockid_t, now_as_duration, ClockID, CpuClock},
+};
+
+/// The clock that an interval timer counts down on.
+///
+/// The clock can also be read directly, e.g., by `clock_gettime`.
+pub enum TimerClock {
+    /// A clock of the system, e.g., `CLOCK_REALTIME` or `CLOCK_MONOTONIC`.
+    System(ClockID),
+    /// The user time of a thread or a process.
+    CpuUser(Arc<CpuClock>),
+    /// The total CPU time of a thread or a process.
+    CpuTotal(Arc<CpuClock>),
+}
+
+impl TimerClock {
+    /// Returns the clock with the given ID.
+    ///
+    /// Besides the IDs of [`ClockID`], a negative ID refers to a CPU clock of a process or a
+    /// thread, which is made by `clock_getcpuclockid` or `pthread_getcpuclockid` in the C library.
+    pub fn from_clockid(clockid: clockid_t) -> Result<Self> {
+        if clockid >= 0 {
+            let clock = match ClockID::try_from(clockid)? {
+                ClockID::CLOCK_PROCESS_CPUTIME_ID => Self::CpuTotal(current!().cpu_clock().clone()),
+                ClockID::CLOCK_THREAD_CPUTIME_ID => {
+                    let current_thread = current_thread!();
+                    let posix_thread = current_thread.as_posix_thread().unwrap();
+                    Self::CpuTotal(posix_thread.cpu_clock().clone())
+                }
+                clock_id => Self::System(clock_id),
+            };
+            return Ok(clock);
+        }
+
+        // The ID is encoded in the same way as Linux, i.e., the bitwise NOT of the PID or the TID
+        // is stored in the high bits, and the type of the CPU clock is stored in the low bits.
+        let id = !(clockid >> 3);
+        let cpu_clock = if clockid & CPUCLOCK_PERTHREAD_MASK != 0 {
+            cpu_clock_of_thread(id as Tid)?
+        } else {
+            cpu_clock_of_process(id as Pid)?
+        };
+        match clockid & CPUCLOCK_CLOCK_MASK {
+            CPUCLOCK_PROF | CPUCLOCK_SCHED => Ok(Self::CpuTotal(cpu_clock)),
+            CPUCLOCK_VIRT => Ok(Self::CpuUser(cpu_clock)),
+            _ => return_errno_with_message!(Errno::EINVAL, "invalid CPU clock type"),
+        }
+    }
+
+    /// Returns the current time of the clock.
+    pub fn now(&self) -> Result<Duration> {
+        match self {
+            Self::System(clock_id) => now_as_duration(clock_id),
+            Self::CpuUser(cpu_clock) => Ok(cpu_clock.user_time()),
+            Self::CpuTotal(cpu_clock) => Ok(cpu_clock.total_time()),
+        }
+    }
+
+    /// Returns whether the clock measures the CPU time.
+    pub fn is_cpu_clock(&self) -> bool {
+        !matches!(self, Self::System(_))
+    }
+
+    /// Returns the resolution of the clock.
+    pub fn resolution(&self) -> Duration {
+        match self {
+            // The coarse clocks are updated only once per tick.
+            Self::System(ClockID::CLOCK_REALTIME_COARSE | ClockID::CLOCK_MONOTONIC_COARSE) => {
+                Duration::from_nanos(1_000_000_000 / TIMER_FREQ)
+            }
+            _ => Duration::from_nanos(1),
+        }
+    }
+}
+
+const CPUCLOCK_PROF: clockid_t = 0;
+const CPUCLOCK_VIRT: clockid_t = 1;
+const CPUCLOCK_SCHED: clockid_t = 2;
+const CPUCLOCK_CLOCK_MASK: clockid_t = 3;
+const CPUCLOCK_PERTHREAD_MASK: clockid_t = 4;
+
+/// Returns the CPU clock of a process, where zero means the current process.
+fn cpu_clock_of_process(pid: Pid) -> Result<Arc<CpuClock>> {
+    let process = if pid == 0 {
+        current!()
+    } else {
+        process_table::get_process(&pid)
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the process does not exist"))?
+    };
+    Ok(process.cpu_clock().clone())
+}
+
+/// Returns the CPU clock of a thread in the current process, where zero means the current thread.
+fn cpu_clock_of_thread(tid: Tid) -> Result<Arc<CpuClock>> {
+    let thread = if tid == 0 {
+        current_thread!()
+    } else {
+        thread_table::get_thread(tid)
+            .filter(|thread| {
+                thread
+                    .as_posix_thread()
+                    .is_some_and(|posix_thread| Arc::ptr_eq(&posix_thread.process(), &current!()))
+            })
+            .ok_or_else(|| {
+                Error::with_message(Errno::EINVAL, "the thread does not exist in the process")
+            })?
+    };
+    Ok(thread.as_posix_thread().unwrap().cpu_clock().clone())
+}
+
+/// A timer that expires at a given time and then periodically at a given interval.
+///
+/// A timer on a system clock is driven by the timer interrupts, and its expirations are handled
+/// in the process context. A timer on a CPU clock is not driven by itself. Instead, it expires
+/// when it is checked by [`IntervalTimer::check`] after the CPU time is charged to the clock.
+pub struct IntervalTimer {
+    clock: TimerClock,
+    /// The timer that fires at the expiration time, which exists only for a system clock.
+    timer: Option<Arc<Timer>>,
+    state: Mutex<TimerState>,
+    /// The function that is called with the number of elapsed periods when the timer expires.
+    on_expire: Box<dyn Fn(u64) + Send + Sync>,
+}
+
+#[derive(Default)]
+struct TimerState {
+    /// The next expiration time measured by the clock, or `None` if the timer is disarmed.
+    expire_time: Option<Duration>,
+    /// The interval of a periodic timer, or zero if the timer is one-shot.
+    interval: Duration,
+}
+
+impl IntervalTimer {
+    /// Creates a disarmed timer.
+    ///
+    /// Note that `on_expire` is not called in the context of the timer's creator, so macros such
+    /// as `current` and `current_thread` should **NOT** be used in it.
+    pub fn new<F>(clock: TimerClock, on_expire: F) -> Arc<Self>
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        Arc::new_cyclic(|weak_self: &Weak<Self>| {
+            let timer = if let TimerClock::System(_) = clock {
+                let work_item = {
+                    let weak_self = weak_self.clone();
+                    Arc::new(WorkItem::new(Box::new(move || {
+                        if let Some(interval_timer) = weak_self.upgrade() {
+                            interval_timer.check();
+                        }
+                    })))
+                };
+                // The callback of `Timer` runs in the interrupt context, so the expiration is
+                // handled later in the process context.
+                let timer = Timer::new(move |_| {
+                    submit_work_item(work_item.clone(), WorkPriority::High);
+                })
+                .unwrap();
+                Some(timer)
+            } else {
+                None
+            };
+
+            Self {
+                clock,
+                timer,
+                state: Mutex::new(TimerState::default()),
+                on_expire: Box::new(on_expire),
+            }
+        })
+    }
+
+    /// Returns the clock of the timer.
+    pub fn clock(&self) -> &TimerClock {
+        &self.clock
+    }
+
+    /// Arms or disarms the timer, returning the old setting.
+    ///
+    /// A zero `value` disarms the timer. Otherwise, the timer expires after `value`, or at
+    /// `value` if `is_abs_time` is true, and then every `interval` if `interval` is not zero.
+    pub fn set(
+        &self,
+        value: Duration,
+        interval: Duration,
+        is_abs_time: bool,
+    ) -> Result<(Duration, Duration)> {
+        let now = self.clock.now()?;
+
+        let mut state = self.state.lock();
+        let old_setting = state.get(now);
+
+        if value.is_zero() {
+            *state = TimerState::default();
+            if let Some(timer) = &self.timer {
+                timer.clear();
+            }
+            return Ok(old_setting);
+        }
+
+        let expire_time = if is_abs_time {
+            value
+        } else {
+            now.checked_add(value)
+                .ok_or_else(|| Error::with_message(Errno::EINVAL, "the time is too large"))?
+        };
+        state.expire_time = Some(expire_time);
+        state.interval = interval;
+        if let Some(timer) = &self.timer {
+            timer.set(expire_time.saturating_sub(now));
+        }
+
+        Ok(old_setting)
+    }
+
+    /// Returns the time until the next expiration and the interval.
+    pub fn get(&self) -> Result<(Duration, Duration)> {
+        let now = self.clock.now()?;
+        Ok(self.state.lock().get(now))
+    }
+
+    /// Returns whether the timer is armed.
+    pub fn is_armed(&self) -> bool {
+        self.state.lock().expire_time.is_some()
+    }
+
+    /// Checks whether the timer has expired, and handles the expirations if so.
+    pub fn check(&self) {
+        let Ok(now) = self.clock.now() else {
+            return;
+        };
+
+        let mut state = self.state.lock();
+        let Some(expire_time) = state.expire_time else {
+            return;
+        };
+
+        if now < expire_time {
+            // The timer interrupts and the clock may not agree exactly, so the timer may fire
+            // slightly earlier than expected.
+            if let Some(timer) = &self.timer {
+                timer.set(expire_time - now);
+            }
+            return;
+        }
+
+        let periods = if state.interval.is_zero() {
+            state.expire_time = None;
+            1
+        } else {
+            // Count all the periods that have elapsed, including the ones that were missed.
+            let interval_nanos = state.interval.as_nanos();
+            let periods = ((now - expire_time).as_nanos() / interval_nanos + 1) as u64;
+            let next_expire_time =
+                expire_time + Duration::from_nanos((interval_nanos * periods as u128) as u64);
+            state.expire_time = Some(next_expire_time);
+            if let Some(timer) = &self.timer {
+                timer.set(next_expire_time - now);
+            }
+            periods
+        };
+        drop(state);
+
+        (self.on_expire)(periods);
+    }
+}
+
+impl TimerState {
+    fn get(&self, now: Duration) -> (Duration, Duration) {
+        // An armed timer that is about to expire does not report a zero time, which would mean
+        // that the timer is disarmed.
+        let remain = self.expire_time.map_or(Duration::ZERO, |expire_time| {
+            expire_time.saturating_sub(now).max(Duration::from_nanos(1))
+        });
+        (remain, self.interval)
+    }
+}
+
+impl Drop for IntervalTimer {
+    fn drop(&mut self) {
+        if let Some(timer) = &self.timer {
+            timer.clear();
+        }
+    }
+}
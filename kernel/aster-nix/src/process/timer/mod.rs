@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The timers of a process, including the interval timers set by `setitimer` (and `alarm`)
+//! and the POSIX timers created by `timer_create`.
+
+mod interval_timer;
+mod posix_timer;
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+pub use interval_timer::{IntervalTimer, TimerClock};
+pub use posix_timer::{PosixTimer, TimerId, TimerNotify};
+
+use super::{
+    signal::{
+        constants::{SIGALRM, SIGPROF, SIGVTALRM},
+        sig_num::SigNum,
+        signals::kernel::KernelSignal,
+    },
+    Process,
+};
+use crate::{
+    prelude::*,
+    time::{ClockID, CpuClock},
+};
+
+/// The type of an interval timer set by `setitimer`.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+#[repr(i32)]
+pub enum ITimerType {
+    /// Counts down in real (i.e., wall clock) time and sends `SIGALRM`.
+    ITIMER_REAL = 0,
+    /// Counts down in the user time of the process and sends `SIGVTALRM`.
+    ITIMER_VIRTUAL = 1,
+    /// Counts down in the total CPU time of the process and sends `SIGPROF`.
+    ITIMER_PROF = 2,
+}
+
+/// The timers of a process.
+pub struct ProcessTimers {
+    process: Weak<Process>,
+    /// The interval timers, indexed by `ITimerType`.
+    itimers: [Arc<IntervalTimer>; 3],
+    posix_timers: Mutex<BTreeMap<TimerId, Arc<PosixTimer>>>,
+    /// Whether there may be armed timers on CPU clocks, which need to be checked when the CPU
+    /// time is charged.
+    has_cpu_timers: AtomicBool,
+}
+
+impl ProcessTimers {
+    pub(super) fn new(process: Weak<Process>, cpu_clock: &Arc<CpuClock>) -> Self {
+        let new_itimer = |clock, num: SigNum| {
+            let process = process.clone();
+            IntervalTimer::new(clock, move |_| {
+                if let Some(process) = process.upgrade() {
+                    process.enqueue_signal(KernelSignal::new(num));
+                }
+            })
+        };
+        let itimers = [
+            new_itimer(TimerClock::System(ClockID::CLOCK_MONOTONIC), SIGALRM),
+            new_itimer(TimerClock::CpuUser(cpu_clock.clone()), SIGVTALRM),
+            new_itimer(TimerClock::CpuTotal(cpu_clock.clone()), SIGPROF),
+        ];
+
+        Self {
+            process,
+            itimers,
+            posix_timers: Mutex::new(BTreeMap::new()),
+            has_cpu_timers: AtomicBool::new(false),
+        }
+    }
+
+    /// Sets an interval timer, returning the old setting.
+    ///
+    /// A zero `value` disarms the timer.
+    pub fn set_itimer(
+        &self,
+        type_: ITimerType,
+        value: Duration,
+        interval: Duration,
+    ) -> Result<(Duration, Duration)> {
+        let itimer = &self.itimers[type_ as usize];
+        let old_setting = itimer.set(value, interval, false)?;
+        self.note_armed(itimer);
+        Ok(old_setting)
+    }
+
+    /// Returns the time until the next expiration and the interval of an interval timer.
+    pub fn get_itimer(&self, type_: ITimerType) -> Result<(Duration, Duration)> {
+        self.itimers[type_ as usize].get()
+    }
+
+    /// Creates a disarmed POSIX timer, returning its ID.
+    ///
+    /// The way to notify the process is given by `new_notify`, which is called with the ID of
+    /// the new timer.
+    pub fn create_posix_timer<F>(&self, clock: TimerClock, new_notify: F) -> Result<TimerId>
+    where
+        F: FnOnce(TimerId) -> TimerNotify,
+    {
+        let mut posix_timers = self.posix_timers.lock();
+        let Some(id) = (0..TimerId::MAX).find(|id| !posix_timers.contains_key(id)) else {
+            return_errno_with_message!(Errno::EAGAIN, "too many POSIX timers");
+        };
+        let posix_timer = PosixTimer::new(id, clock, self.process.clone(), new_notify(id));
+        posix_timers.insert(id, posix_timer);
+        Ok(id)
+    }
+
+    /// Returns the POSIX timer with the given ID.
+    pub fn posix_timer(&self, id: TimerId) -> Result<Arc<PosixTimer>> {
+        self.posix_timers
+            .lock()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the timer does not exist"))
+    }
+
+    /// Arms or disarms a POSIX timer, returning the old setting.
+    ///
+    /// See [`IntervalTimer::set`] for the meaning of the arguments.
+    pub fn set_posix_timer(
+        &self,
+        id: TimerId,
+        value: Duration,
+        interval: Duration,
+        is_abs_time: bool,
+    ) -> Result<(Duration, Duration)> {
+        let posix_timer = self.posix_timer(id)?;
+        let old_setting = posix_timer.timer().set(value, interval, is_abs_time)?;
+        self.note_armed(posix_timer.timer());
+        Ok(old_setting)
+    }
+
+    /// Deletes a POSIX timer, which is disarmed at the same time.
+    pub fn delete_posix_timer(&self, id: TimerId) -> Result<()> {
+        let posix_timer = self
+            .posix_timers
+            .lock()
+            .remove(&id)
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the timer does not exist"))?;
+        posix_timer
+            .timer()
+            .set(Duration::ZERO, Duration::ZERO, false)?;
+        Ok(())
+    }
+
+    /// Deletes all the POSIX timers, which happens when the process executes a new program.
+    pub fn delete_all_posix_timers(&self) {
+        let posix_timers = core::mem::take(&mut *self.posix_timers.lock());
+        for posix_timer in posix_timers.values() {
+            let _ = posix_timer
+                .timer()
+                .set(Duration::ZERO, Duration::ZERO, false);
+        }
+    }
+
+    /// Disarms all the timers, which happens when the process exits.
+    pub(super) fn disarm_all(&self) {
+        for itimer in self.itimers.iter() {
+            let _ = itimer.set(Duration::ZERO, Duration::ZERO, false);
+        }
+        self.delete_all_posix_timers();
+    }
+
+    /// Checks the timers on CPU clocks, which may expire after the CPU time is charged.
+    pub(super) fn check_cpu_timers(&self) {
+        if !self.has_cpu_timers.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // The flag is cleared before checking the timers, so a timer that is armed concurrently
+        // either is seen below or sets the flag again.
+        self.has_cpu_timers.store(false, Ordering::SeqCst);
+
+        let mut has_cpu_timers = false;
+        let mut check = |timer: &IntervalTimer| {
+            if timer.clock().is_cpu_clock() {
+                timer.check();
+                has_cpu_timers |= timer.is_armed();
+            }
+        };
+        for itimer in self.itimers.iter() {
+            check(itimer);
+        }
+        let posix_timers: Vec<_> = self.posix_timers.lock().values().cloned().collect();
+        for posix_timer in posix_timers.iter() {
+            check(posix_timer.timer());
+        }
+
+        if has_cpu_timers {
+            self.has_cpu_timers.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn note_armed(&self, timer: &IntervalTimer) {
+        if timer.clock().is_cpu_clock() && timer.is_armed() {
+            self.has_cpu_timers.store(true, Ordering::SeqCst);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::interval_timer::{IntervalTimer, TimerClock};
+use crate::{
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        signal::{
+            c_types::sigval_t,
+            sig_num::SigNum,
+            signals::timer::{TimerOverrun, TimerSignal},
+        },
+        Process,
+    },
+    thread::Thread,
+};
+
+/// The ID of a POSIX timer, which is unique in a process.
+pub type TimerId = i32;
+
+/// The way to notify the process when a POSIX timer expires.
+pub enum TimerNotify {
+    /// Nothing is sent.
+    None,
+    /// A signal is sent to the process.
+    Signal { num: SigNum, value: sigval_t },
+    /// A signal is sent to a thread of the process.
+    ThreadSignal {
+        num: SigNum,
+        value: sigval_t,
+        thread: Weak<Thread>,
+    },
+}
+
+/// A POSIX timer, which is created by `timer_create`.
+pub struct PosixTimer {
+    id: TimerId,
+    timer: Arc<IntervalTimer>,
+    process: Weak<Process>,
+    notify: TimerNotify,
+    /// The overrun count, which is shared with the signals queued by the timer.
+    overrun: Arc<TimerOverrun>,
+}
+
+impl PosixTimer {
+    pub(super) fn new(
+        id: TimerId,
+        clock: TimerClock,
+        process: Weak<Process>,
+        notify: TimerNotify,
+    ) -> Arc<Self> {
+        Arc::new_cyclic(|weak_self: &Weak<Self>| {
+            let weak_self = weak_self.clone();
+            let timer = IntervalTimer::new(clock, move |periods| {
+                if let Some(posix_timer) = weak_self.upgrade() {
+                    posix_timer.on_expire(periods);
+                }
+            });
+
+            Self {
+                id,
+                timer,
+                process,
+                notify,
+                overrun: Arc::new(TimerOverrun::default()),
+            }
+        })
+    }
+
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    pub fn timer(&self) -> &Arc<IntervalTimer> {
+        &self.timer
+    }
+
+    /// Returns the overrun count reported by the last delivered signal.
+    pub fn overrun(&self) -> i32 {
+        self.overrun.last()
+    }
+
+    fn on_expire(&self, periods: u64) {
+        let (num, value) = match &self.notify {
+            TimerNotify::None => return,
+            TimerNotify::Signal { num, value } | TimerNotify::ThreadSignal { num, value, .. } => {
+                (*num, *value)
+            }
+        };
+
+        // If the last signal is still pending, no more signal is queued and all the expirations
+        // are counted as overruns.
+        if self.overrun.set_signal_pending() {
+            self.overrun.add(periods);
+            return;
+        }
+        self.overrun.add(periods - 1);
+
+        let signal = TimerSignal::new(num, self.id, value, self.overrun.clone());
+        match &self.notify {
+            TimerNotify::ThreadSignal { thread, .. } => {
+                let Some(thread) = thread.upgrade() else {
+                    return;
+                };
+                if thread.is_exited() {
+                    return;
+                }
+                let posix_thread = thread.as_posix_thread().unwrap();
+                posix_thread.enqueue_signal(Box::new(signal));
+            }
+            _ => {
+                if let Some(process) = self.process.upgrade() {
+                    process.enqueue_signal(signal);
+                }
+            }
+        }
+    }
+}
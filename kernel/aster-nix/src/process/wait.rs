@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{process_filter::ProcessFilter, ExitCode, Pid, Process};
+use super::{process_filter::ProcessFilter, Pid, Process};
 use crate::{prelude::*, process::process_table, thread::thread_table};
 
 // The definition of WaitOptions is from Occlum
@@ -22,12 +22,16 @@ impl WaitOptions {
     }
 }
 
+/// Waits for a child to exit, and returns the child process.
+///
+/// The child is reaped unless `WNOWAIT` is specified. `None` is returned if `WNOHANG` is
+/// specified and no child has exited.
 pub fn wait_child_exit(
     child_filter: ProcessFilter,
     wait_options: WaitOptions,
-) -> Result<(Pid, ExitCode)> {
+) -> Result<Option<Arc<Process>>> {
     let current = current!();
-    let (pid, exit_code) = current.children_pauser().pause_until(|| {
+    current.children_pauser().pause_until(|| {
         let unwaited_children = current
             .children()
             .lock()
@@ -51,32 +55,36 @@ pub fn wait_child_exit(
         let zombie_child = unwaited_children.iter().find(|child| child.is_zombie());
 
         if let Some(zombie_child) = zombie_child {
-            let zombie_pid = zombie_child.pid();
-            let exit_code = zombie_child.exit_code().unwrap();
-            if wait_options.contains(WaitOptions::WNOWAIT) {
-                // does not reap child, directly return
-                return Some(Ok((zombie_pid, exit_code)));
-            } else {
-                let exit_code = reap_zombie_child(&current, zombie_pid);
-                return Some(Ok((zombie_pid, exit_code)));
+            if !wait_options.contains(WaitOptions::WNOWAIT) {
+                reap_zombie_child(&current, zombie_child.pid());
             }
+            return Some(Ok(Some(zombie_child.clone())));
         }
 
         if wait_options.contains(WaitOptions::WNOHANG) {
-            return Some(Ok((0, 0)));
+            return Some(Ok(None));
         }
 
         // wait
         None
-    })??;
-
-    Ok((pid, exit_code as _))
+    })?
 }
 
 /// Free zombie child with pid, returns the exit code of child process.
 fn reap_zombie_child(process: &Process, pid: Pid) -> u32 {
     let child_process = process.children().lock().remove(&pid).unwrap();
     assert!(child_process.is_zombie());
+
+    // The CPU time of the child, including its reaped children, is accumulated to the parent.
+    let children_cpu_clock = process.children_cpu_clock();
+    for cpu_clock in [
+        child_process.cpu_clock(),
+        child_process.children_cpu_clock(),
+    ] {
+        children_cpu_clock.add_user_time(cpu_clock.user_time());
+        children_cpu_clock.add_system_time(cpu_clock.system_time());
+    }
+
     child_process.root_vmar().destroy_all().unwrap();
     for thread in &*child_process.threads().lock() {
         thread_table::remove_thread(thread.tid());
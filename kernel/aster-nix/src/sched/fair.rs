@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The fair scheduling of normal tasks.
+//!
+//! Similar to the Completely Fair Scheduler (CFS) of Linux, each task accumulates a virtual
+//! runtime, which grows slower for tasks with larger weights (i.e., smaller nice values). The
+//! task with the smallest virtual runtime is picked to run next, and the running task is
+//! preempted once it has used up its time slice, which is a share of the scheduling latency
+//! in proportion to its weight.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use aster_frame::{
+    task::{SchedPolicy, Task},
+    timer::TIMER_FREQ,
+};
+
+use super::nice::Nice;
+use crate::{prelude::*, process::posix_thread::PosixThreadExt, thread::Thread};
+
+/// The period in which each runnable task should run at least once.
+const SCHED_LATENCY_NS: u64 = 6_000_000;
+/// The minimum time slice of a task.
+const MIN_GRANULARITY_NS: u64 = 750_000;
+/// The lead of the virtual runtime that a waking task needs to preempt the running task.
+const WAKEUP_GRANULARITY_NS: u64 = 1_000_000;
+/// The time that elapses in each timer tick.
+const TICK_NS: u64 = 1_000_000_000 / TIMER_FREQ;
+
+/// The weight of a task whose nice value is 0.
+const NICE_0_WEIGHT: u64 = 1024;
+
+/// The weights of the nice values from -20 to 19, which are the same as those of Linux.
+///
+/// Each increment of the nice value reduces the CPU share by about 10%.
+const NICE_TO_WEIGHT: [u64; 40] = [
+    88761, 71755, 56483, 46273, 36291, 29154, 23254, 18705, 14949, 11916, 9548, 7620, 6100, 4904,
+    3906, 3121, 2501, 1991, 1586, 1277, 1024, 820, 655, 526, 423, 335, 272, 215, 172, 137, 110, 87,
+    70, 56, 45, 36, 29, 23, 18, 15,
+];
+
+/// The weight of a task with the [`SchedPolicy::Idle`] policy, which is the same as that of Linux.
+const IDLE_POLICY_WEIGHT: u64 = 3;
+
+/// The scheduling states of a thread in the fair scheduler.
+pub struct SchedEntity {
+    /// The virtual runtime in nanoseconds.
+    vruntime: AtomicU64,
+    /// The time that the thread has run since it was picked to run.
+    slice_runtime: AtomicU64,
+}
+
+impl SchedEntity {
+    pub(crate) const fn new() -> Self {
+        Self {
+            vruntime: AtomicU64::new(0),
+            slice_runtime: AtomicU64::new(0),
+        }
+    }
+
+    fn vruntime(&self) -> u64 {
+        self.vruntime.load(Ordering::Relaxed)
+    }
+}
+
+/// The run queue of the normal tasks, which is ordered by the virtual runtime.
+pub(super) struct FairQueue {
+    tasks: BTreeMap<(u64, u64), (Arc<Task>, u64)>,
+    /// The sequence number to keep the tasks with the same virtual runtime in FIFO order.
+    next_seq: u64,
+    /// The monotonically increasing lower bound of the virtual runtime of runnable tasks.
+    min_vruntime: u64,
+    /// The total weight of the tasks in the queue.
+    total_weight: u64,
+}
+
+impl FairQueue {
+    pub(super) fn new() -> Self {
+        Self {
+            tasks: BTreeMap::new(),
+            next_seq: 0,
+            min_vruntime: 0,
+            total_weight: 0,
+        }
+    }
+
+    pub(super) fn enqueue(&mut self, task: Arc<Task>) {
+        let (vruntime, weight) = match thread_of(&task) {
+            Some(thread) => {
+                // A task that has slept for a long time should not monopolize the CPU after it
+                // wakes up, so its virtual runtime is limited to be slightly smaller than those
+                // of other tasks.
+                let entity = thread.sched_entity();
+                let vruntime = entity
+                    .vruntime()
+                    .max(self.min_vruntime.saturating_sub(SCHED_LATENCY_NS / 2));
+                entity.vruntime.store(vruntime, Ordering::Relaxed);
+                (vruntime, weight_of(&thread))
+            }
+            None => (self.min_vruntime, NICE_0_WEIGHT),
+        };
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.tasks.insert((vruntime, seq), (task, weight));
+        self.total_weight += weight;
+    }
+
+    pub(super) fn dequeue(&mut self) -> Option<Arc<Task>> {
+        let ((vruntime, _), (task, weight)) = self.tasks.pop_first()?;
+        self.total_weight -= weight;
+        self.min_vruntime = self.min_vruntime.max(vruntime);
+
+        if let Some(thread) = thread_of(&task) {
+            thread
+                .sched_entity()
+                .slice_runtime
+                .store(0, Ordering::Relaxed);
+        }
+        Some(task)
+    }
+
+    /// Puts a task that comes from the run queue of another CPU into the queue.
+    ///
+    /// The virtual runtimes in different queues are not comparable, so the virtual runtime of
+    /// the task is adjusted to keep its distance to the minimum virtual runtime, which is
+    /// `src_min_vruntime` in the original queue.
+    pub(super) fn enqueue_migrated(&mut self, task: Arc<Task>, src_min_vruntime: u64) {
+        if let Some(thread) = thread_of(&task) {
+            let entity = thread.sched_entity();
+            let lag = entity.vruntime().saturating_sub(src_min_vruntime);
+            entity
+                .vruntime
+                .store(self.min_vruntime + lag, Ordering::Relaxed);
+        }
+        self.enqueue(task);
+    }
+
+    /// Removes a task that is allowed to run on the given CPU for load balancing.
+    ///
+    /// The task with the largest virtual runtime is preferred since it is the last one to run
+    /// in this queue.
+    pub(super) fn steal(&mut self, cpu_id: u32) -> Option<Arc<Task>> {
+        let key = self
+            .tasks
+            .iter()
+            .rev()
+            .find(|(_, (task, _))| task.can_run_on(cpu_id))
+            .map(|(key, _)| *key)?;
+        let (task, weight) = self.tasks.remove(&key).unwrap();
+        self.total_weight -= weight;
+        Some(task)
+    }
+
+    /// Returns the monotonically increasing lower bound of the virtual runtime of runnable
+    /// tasks.
+    pub(super) fn min_vruntime(&self) -> u64 {
+        self.min_vruntime
+    }
+
+    /// Tells whether a task in the queue should preempt the current task, which is the case
+    /// if the virtual runtime of the current task is ahead by more than the wakeup granularity.
+    pub(super) fn should_preempt(&self, current: &Task) -> bool {
+        let Some(((min_vruntime, _), _)) = self.tasks.first_key_value() else {
+            return false;
+        };
+        let Some(thread) = thread_of(current) else {
+            return false;
+        };
+        let lead = thread
+            .sched_entity()
+            .vruntime()
+            .saturating_sub(*min_vruntime);
+        lead > WAKEUP_GRANULARITY_NS * NICE_0_WEIGHT / weight_of(&thread)
+    }
+
+    /// Charges the current task for a timer tick, and tells whether it has used up its time
+    /// slice.
+    pub(super) fn tick(&mut self, current: &Task) -> bool {
+        let Some(thread) = thread_of(current) else {
+            return false;
+        };
+        let entity = thread.sched_entity();
+        let weight = weight_of(&thread);
+
+        let delta_vruntime = TICK_NS * NICE_0_WEIGHT / weight;
+        let vruntime =
+            entity.vruntime.fetch_add(delta_vruntime, Ordering::Relaxed) + delta_vruntime;
+        let slice_runtime = entity.slice_runtime.fetch_add(TICK_NS, Ordering::Relaxed) + TICK_NS;
+
+        let Some(((min_vruntime, _), _)) = self.tasks.first_key_value() else {
+            self.min_vruntime = self.min_vruntime.max(vruntime);
+            return false;
+        };
+        self.min_vruntime = self.min_vruntime.max(vruntime.min(*min_vruntime));
+
+        let time_slice =
+            (SCHED_LATENCY_NS * weight / (self.total_weight + weight)).max(MIN_GRANULARITY_NS);
+        slice_runtime >= time_slice
+    }
+}
+
+fn thread_of(task: &Task) -> Option<Arc<Thread>> {
+    task.data().downcast_ref::<Weak<Thread>>()?.upgrade()
+}
+
+fn weight_of(thread: &Thread) -> u64 {
+    if thread.task().sched_policy() == SchedPolicy::Idle {
+        return IDLE_POLICY_WEIGHT;
+    }
+    // The process may have been reaped while the thread is exiting.
+    let nice = thread
+        .as_posix_thread()
+        .and_then(|posix_thread| posix_thread.weak_process().upgrade())
+        .map(|process| process.nice().load(Ordering::Relaxed))
+        .unwrap_or_default();
+    NICE_TO_WEIGHT[(nice.to_raw() - Nice::MIN.to_raw()) as usize]
+}
@@ -1,8 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod fair;
 pub mod nice;
 mod priority_scheduler;
+mod real_time;
+pub mod stats;
 
 // There may be multiple scheduling policies in the system,
 // and subsequent schedulers can be placed under this module.
-pub use self::priority_scheduler::init;
+pub use self::{
+    fair::SchedEntity,
+    priority_scheduler::{init, nr_running},
+    real_time::{RealTimeEntity, RR_TIME_SLICE_MS},
+};
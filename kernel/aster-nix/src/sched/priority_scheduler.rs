@@ -1,60 +1,293 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use aster_frame::task::{set_scheduler, Scheduler, Task, TaskAdapter};
-use intrusive_collections::LinkedList;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
+use aster_frame::{
+    cpu::{num_cpus, this_cpu},
+    task::{current_task, is_cpu_idle, set_scheduler, wake_up_cpu, Scheduler, Task, TaskStatus},
+};
+use spin::Once;
+
+use super::{fair::FairQueue, real_time::RealTimeQueue, stats};
 use crate::prelude::*;
 
+static PREEMPT_SCHEDULER: Once<&'static PreemptScheduler> = Once::new();
+
 pub fn init() {
-    let preempt_scheduler = Box::new(PreemptScheduler::new());
+    let preempt_scheduler = Box::new(PreemptScheduler::new(num_cpus()));
     let scheduler = Box::<PreemptScheduler>::leak(preempt_scheduler);
+    PREEMPT_SCHEDULER.call_once(|| scheduler);
     set_scheduler(scheduler);
 }
 
+/// Returns the number of running and runnable tasks on all the CPUs.
+pub fn nr_running() -> usize {
+    PREEMPT_SCHEDULER
+        .get()
+        .map_or(0, |scheduler| scheduler.nr_running())
+}
+
+/// The interval in timer ticks at which a busy CPU balances the load between the CPUs.
+const BALANCE_INTERVAL_TICKS: u64 = 4;
+
 /// The preempt scheduler
 ///
-/// Real-time tasks are placed in the `real_time_tasks` queue and
-/// are always prioritized during scheduling.
+/// Each CPU has its own run queue. In each run queue,
+/// real-time tasks are placed in the `real_time_tasks` queue and
+/// are always prioritized during scheduling. Real-time tasks are scheduled
+/// by their priorities and policies (i.e., FIFO or round-robin).
 /// Normal tasks are placed in the `normal_tasks` queue and are only
 /// scheduled for execution when there are no real-time tasks.
+/// Normal tasks share the CPU fairly according to their nice values.
+///
+/// A task is put into the run queue of the CPU that it ran on last time, unless another CPU
+/// that it is allowed to run on has fewer queued tasks. The load is balanced between the CPUs
+/// as follows:
+///  - A CPU that has no task to run steals a task from the CPU with the most queued tasks
+///    before it becomes idle. An idle CPU has no timer ticks, and is woken up when a task is
+///    put into its run queue.
+///  - A busy CPU periodically pulls a task from the CPU with the most queued tasks, if the
+///    latter has at least two more queued tasks.
 struct PreemptScheduler {
-    /// Tasks with a priority of less than 100 are regarded as real-time tasks.
-    real_time_tasks: SpinLock<LinkedList<TaskAdapter>>,
-    /// Tasks with a priority greater than or equal to 100 are regarded as normal tasks.
-    normal_tasks: SpinLock<LinkedList<TaskAdapter>>,
+    /// The run queues of the CPUs, indexed by the CPU IDs.
+    run_queues: Vec<RunQueue>,
 }
 
 impl PreemptScheduler {
-    pub fn new() -> Self {
+    fn new(num_cpus: u32) -> Self {
         Self {
-            real_time_tasks: SpinLock::new(LinkedList::new(TaskAdapter::new())),
-            normal_tasks: SpinLock::new(LinkedList::new(TaskAdapter::new())),
+            run_queues: (0..num_cpus).map(|_| RunQueue::new()).collect(),
+        }
+    }
+
+    fn nr_running(&self) -> usize {
+        self.run_queues
+            .iter()
+            .enumerate()
+            .map(|(cpu_id, run_queue)| run_queue.len() + usize::from(!is_cpu_idle(cpu_id as u32)))
+            .sum()
+    }
+
+    /// Selects the CPU whose run queue the task should be put into.
+    fn select_cpu(&self, task: &Task) -> u32 {
+        let last_cpu_id = task.cpu();
+        let mut selected: Option<(u32, usize)> = None;
+        for (cpu_id, run_queue) in self.run_queues.iter().enumerate() {
+            let cpu_id = cpu_id as u32;
+            if !task.can_run_on(cpu_id) {
+                continue;
+            }
+            let len = run_queue.len();
+            let is_better = selected.map_or(true, |(_, min_len)| {
+                len < min_len || (len == min_len && cpu_id == last_cpu_id)
+            });
+            if is_better {
+                selected = Some((cpu_id, len));
+            }
         }
+        // The task may not be allowed to run on any CPU, which should have been prevented by
+        // the callers of `Task::set_cpu_affinity`.
+        selected.map_or_else(this_cpu, |(cpu_id, _)| cpu_id)
+    }
+
+    /// Puts a task that is removed from the run queue of `src_cpu_id`, or that ran on
+    /// `src_cpu_id` last time, into the run queue of `dst_cpu_id`.
+    fn enqueue_to(&self, task: Arc<Task>, src_cpu_id: u32, dst_cpu_id: u32) {
+        // The virtual runtime of a normal task is relative to the run queue of its CPU.
+        let src_min_vruntime = if src_cpu_id != dst_cpu_id && !task.is_real_time() {
+            self.run_queues
+                .get(src_cpu_id as usize)
+                .map(|run_queue| run_queue.normal_tasks.lock_irq_disabled().min_vruntime())
+        } else {
+            None
+        };
+        self.run_queues[dst_cpu_id as usize].enqueue(task, src_min_vruntime);
+        // An idle CPU does not notice the task until it is woken up.
+        wake_up_cpu(dst_cpu_id);
+    }
+
+    /// Pulls a task from the CPU with the most queued tasks into the run queue of the given
+    /// CPU, if the former has at least `min_imbalance` more queued tasks.
+    ///
+    /// Returns whether a task is pulled.
+    fn pull_task(&self, cpu_id: u32, min_imbalance: usize) -> bool {
+        let this_len = self.run_queues[cpu_id as usize].len();
+        let Some((busiest_cpu_id, busiest)) = self
+            .run_queues
+            .iter()
+            .enumerate()
+            .filter(|(other_cpu_id, _)| *other_cpu_id != cpu_id as usize)
+            .max_by_key(|(_, run_queue)| run_queue.len())
+        else {
+            return false;
+        };
+        if busiest.len() < this_len + min_imbalance {
+            return false;
+        }
+
+        let Some(task) = busiest.steal(cpu_id) else {
+            return false;
+        };
+        self.enqueue_to(task, busiest_cpu_id as u32, cpu_id);
+        true
     }
 }
 
 impl Scheduler for PreemptScheduler {
     fn enqueue(&self, task: Arc<Task>) {
-        if task.is_real_time() {
-            self.real_time_tasks
+        let last_cpu_id = task.cpu();
+        let cpu_id = self.select_cpu(&task);
+        self.enqueue_to(task, last_cpu_id, cpu_id);
+    }
+
+    fn dequeue(&self) -> Option<Arc<Task>> {
+        let cpu_id = this_cpu();
+        let run_queue = &self.run_queues[cpu_id as usize];
+
+        // A real-time task goes on running when it yields, unless there are other tasks with
+        // the same or higher priorities.
+        if let Some(current) = current_task()
+            && current.status() == TaskStatus::Runnable
+            && current.is_real_time()
+            && current.can_run_on(cpu_id)
+        {
+            let current_priority = current.priority().get() as usize;
+            let highest_priority = run_queue
+                .real_time_tasks
                 .lock_irq_disabled()
-                .push_back(task.clone());
+                .highest_priority();
+            if !highest_priority.is_some_and(|priority| priority <= current_priority) {
+                return None;
+            }
+        }
+
+        while let Some(task) = run_queue.dequeue() {
+            if task.can_run_on(cpu_id) {
+                return Some(task);
+            }
+            // The CPU affinity of the task has been changed since it was enqueued.
+            let dst_cpu_id = self.select_cpu(&task);
+            if dst_cpu_id == cpu_id {
+                return Some(task);
+            }
+            self.enqueue_to(task, cpu_id, dst_cpu_id);
+        }
+
+        // The CPU is going to be idle, so it tries to take over a task from other CPUs.
+        if self.pull_task(cpu_id, 1) {
+            run_queue.dequeue()
         } else {
-            self.normal_tasks
+            None
+        }
+    }
+
+    fn should_preempt(&self, task: &Arc<Task>) -> bool {
+        let run_queue = &self.run_queues[this_cpu() as usize];
+        if task.is_real_time() {
+            return run_queue
+                .real_time_tasks
+                .lock_irq_disabled()
+                .should_preempt(task);
+        }
+        !run_queue.real_time_tasks.lock_irq_disabled().is_empty()
+            || run_queue
+                .normal_tasks
                 .lock_irq_disabled()
-                .push_back(task.clone());
+                .should_preempt(task)
+    }
+
+    fn tick(&self, current: &Arc<Task>) -> bool {
+        let cpu_id = this_cpu();
+        let run_queue = &self.run_queues[cpu_id as usize];
+
+        if run_queue.ticks.fetch_add(1, Ordering::Relaxed) % BALANCE_INTERVAL_TICKS == 0 {
+            self.pull_task(cpu_id, 2);
+        }
+        stats::update_load_avg(|| self.nr_running());
+
+        if current.is_real_time() {
+            return run_queue.real_time_tasks.lock_irq_disabled().tick(current);
+        }
+        let is_slice_used_up = run_queue.normal_tasks.lock_irq_disabled().tick(current);
+        is_slice_used_up || !run_queue.real_time_tasks.lock_irq_disabled().is_empty()
+    }
+}
+
+/// The run queue of a CPU.
+struct RunQueue {
+    /// Tasks with real-time policies or a priority of less than 100 are regarded as real-time
+    /// tasks.
+    real_time_tasks: SpinLock<RealTimeQueue>,
+    /// Tasks with a priority greater than or equal to 100 are regarded as normal tasks.
+    normal_tasks: SpinLock<FairQueue>,
+    /// The number of queued tasks, which can be read by other CPUs without locking the queues.
+    ///
+    /// It is increased before a task is queued and decreased after a task is removed, so it
+    /// may be temporarily larger than the actual number.
+    len: AtomicUsize,
+    /// The number of timer ticks that have occurred on the CPU.
+    ticks: AtomicU64,
+}
+
+impl RunQueue {
+    fn new() -> Self {
+        Self {
+            real_time_tasks: SpinLock::new(RealTimeQueue::new()),
+            normal_tasks: SpinLock::new(FairQueue::new()),
+            len: AtomicUsize::new(0),
+            ticks: AtomicU64::new(0),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Puts a task into the queue.
+    ///
+    /// If the task is a normal task that comes from another CPU, `src_min_vruntime` is the
+    /// minimum virtual runtime of the run queue of that CPU.
+    fn enqueue(&self, task: Arc<Task>, src_min_vruntime: Option<u64>) {
+        self.len.fetch_add(1, Ordering::Relaxed);
+        if task.is_real_time() {
+            self.real_time_tasks.lock_irq_disabled().enqueue(task);
+            return;
+        }
+        let mut normal_tasks = self.normal_tasks.lock_irq_disabled();
+        match src_min_vruntime {
+            Some(src_min_vruntime) => normal_tasks.enqueue_migrated(task, src_min_vruntime),
+            None => normal_tasks.enqueue(task),
         }
     }
 
     fn dequeue(&self) -> Option<Arc<Task>> {
-        if !self.real_time_tasks.lock_irq_disabled().is_empty() {
-            self.real_time_tasks.lock_irq_disabled().pop_front()
-        } else {
-            self.normal_tasks.lock_irq_disabled().pop_front()
+        let mut real_time_tasks = self.real_time_tasks.lock_irq_disabled();
+        let mut task = None;
+        while let Some(real_time_task) = real_time_tasks.dequeue() {
+            if real_time_task.is_real_time() {
+                task = Some(real_time_task);
+                break;
+            }
+            // The task has been changed to a normal policy after it was enqueued.
+            self.normal_tasks
+                .lock_irq_disabled()
+                .enqueue(real_time_task);
         }
+        drop(real_time_tasks);
+
+        let task = task.or_else(|| self.normal_tasks.lock_irq_disabled().dequeue())?;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some(task)
     }
 
-    fn should_preempt(&self, task: &Arc<Task>) -> bool {
-        !task.is_real_time() && !self.real_time_tasks.lock_irq_disabled().is_empty()
+    /// Removes a task that is allowed to run on the given CPU for load balancing.
+    fn steal(&self, cpu_id: u32) -> Option<Arc<Task>> {
+        let task = self
+            .real_time_tasks
+            .lock_irq_disabled()
+            .steal(cpu_id)
+            .or_else(|| self.normal_tasks.lock_irq_disabled().steal(cpu_id))?;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some(task)
     }
 }
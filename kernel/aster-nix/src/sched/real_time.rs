@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The scheduling of real-time tasks.
+//!
+//! Similar to Linux, a real-time task always runs before the real-time tasks with lower
+//! priorities. The tasks with the same priority are scheduled according to their policies:
+//!  - A [`SchedPolicy::Fifo`] task runs until it blocks, yields, or is preempted by a task with
+//!    a higher priority.
+//!  - A [`SchedPolicy::RoundRobin`] task additionally gives up the CPU to the tasks with the
+//!    same priority once it has used up its time slice.
+//!
+//! A task that is preempted by a task with a higher priority is put at the head of the queue
+//! of its priority, so it continues to run before the other tasks with the same priority.
+//! Otherwise, tasks are put at the tail of the queue.
+
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use aster_frame::{
+    task::{SchedPolicy, Task, TaskAdapter},
+    timer::TIMER_FREQ,
+};
+use intrusive_collections::LinkedList;
+
+use crate::{prelude::*, thread::Thread};
+
+/// The time slice of round-robin tasks, which is the same as the default one of Linux.
+pub const RR_TIME_SLICE_MS: u64 = 100;
+/// The time slice of round-robin tasks in timer ticks.
+const RR_TIME_SLICE_TICKS: u64 = RR_TIME_SLICE_MS * TIMER_FREQ / 1000;
+
+/// The number of real-time priorities, which range from 0 to 99.
+const NUM_PRIORITIES: usize = 100;
+
+/// The scheduling states of a thread in the real-time scheduler.
+pub struct RealTimeEntity {
+    /// The number of timer ticks that the thread has run in its current time slice.
+    slice_ticks: AtomicU64,
+    /// Whether the thread is preempted by a thread with a higher priority.
+    is_preempted: AtomicBool,
+}
+
+impl RealTimeEntity {
+    pub(crate) const fn new() -> Self {
+        Self {
+            slice_ticks: AtomicU64::new(0),
+            is_preempted: AtomicBool::new(false),
+        }
+    }
+}
+
+/// The run queue of the real-time tasks, which has a FIFO queue for each priority.
+pub(super) struct RealTimeQueue {
+    /// The queues of the tasks, indexed by their priorities.
+    queues: [LinkedList<TaskAdapter>; NUM_PRIORITIES],
+    /// The bitmap of the non-empty queues.
+    bitmap: u128,
+}
+
+impl RealTimeQueue {
+    pub(super) fn new() -> Self {
+        Self {
+            queues: core::array::from_fn(|_| LinkedList::new(TaskAdapter::new())),
+            bitmap: 0,
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.bitmap == 0
+    }
+
+    pub(super) fn enqueue(&mut self, task: Arc<Task>) {
+        let priority = task.priority().get() as usize;
+        let is_preempted = thread_of(&task).map_or(false, |thread| {
+            thread
+                .real_time_entity()
+                .is_preempted
+                .swap(false, Ordering::Relaxed)
+        });
+
+        if is_preempted {
+            self.queues[priority].push_front(task);
+        } else {
+            self.queues[priority].push_back(task);
+        }
+        self.bitmap |= 1 << priority;
+    }
+
+    /// Removes the first task with the highest priority.
+    ///
+    /// The returned task may no longer be a real-time task, since its policy may have been
+    /// changed after it was enqueued.
+    pub(super) fn dequeue(&mut self) -> Option<Arc<Task>> {
+        let priority = self.highest_priority()?;
+        let queue = &mut self.queues[priority];
+        let task = queue.pop_front();
+        if queue.is_empty() {
+            self.bitmap &= !(1 << priority);
+        }
+        task
+    }
+
+    /// Removes a task that is allowed to run on the given CPU for load balancing.
+    ///
+    /// The task with the lowest priority is preferred since it is the last one to run in this
+    /// queue.
+    pub(super) fn steal(&mut self, cpu_id: u32) -> Option<Arc<Task>> {
+        for priority in (0..NUM_PRIORITIES).rev() {
+            if self.bitmap & (1 << priority) == 0 {
+                continue;
+            }
+            let queue = &mut self.queues[priority];
+            let mut cursor = queue.back_mut();
+            while let Some(task) = cursor.get() {
+                if task.can_run_on(cpu_id) {
+                    let task = cursor.remove();
+                    if queue.is_empty() {
+                        self.bitmap &= !(1 << priority);
+                    }
+                    return task;
+                }
+                cursor.move_prev();
+            }
+        }
+        None
+    }
+
+    /// Tells whether a task in the queue should preempt the current real-time task, which is
+    /// the case if the former has a higher priority.
+    pub(super) fn should_preempt(&mut self, current: &Task) -> bool {
+        let Some(highest_priority) = self.highest_priority() else {
+            return false;
+        };
+        if highest_priority >= current.priority().get() as usize {
+            return false;
+        }
+        if let Some(thread) = thread_of(current) {
+            thread
+                .real_time_entity()
+                .is_preempted
+                .store(true, Ordering::Relaxed);
+        }
+        true
+    }
+
+    /// Charges the current real-time task for a timer tick, and tells whether it should be
+    /// preempted.
+    ///
+    /// A round-robin task is preempted if it has used up its time slice and there are other
+    /// tasks with the same priority.
+    pub(super) fn tick(&mut self, current: &Task) -> bool {
+        if self.should_preempt(current) {
+            return true;
+        }
+        if current.sched_policy() != SchedPolicy::RoundRobin {
+            return false;
+        }
+        let Some(thread) = thread_of(current) else {
+            return false;
+        };
+
+        let slice_ticks = &thread.real_time_entity().slice_ticks;
+        if slice_ticks.fetch_add(1, Ordering::Relaxed) + 1 < RR_TIME_SLICE_TICKS {
+            return false;
+        }
+        // The time slice is refilled, and the task is put at the tail of its queue if
+        // another task takes over the CPU.
+        slice_ticks.store(0, Ordering::Relaxed);
+        self.highest_priority() == Some(current.priority().get() as usize)
+    }
+
+    /// Returns the highest priority (i.e., the smallest priority value) of the queued tasks.
+    ///
+    /// The priorities of the tasks may have been changed after they were enqueued, so the
+    /// first task with the highest priority is moved to the queue of its current priority
+    /// until the priority is up to date.
+    pub(super) fn highest_priority(&mut self) -> Option<usize> {
+        loop {
+            if self.bitmap == 0 {
+                return None;
+            }
+            let priority = self.bitmap.trailing_zeros() as usize;
+            let queue = &mut self.queues[priority];
+            let task = queue.front().get().unwrap();
+            let new_priority = task.priority().get() as usize;
+            if new_priority == priority || !task.is_real_time() {
+                return Some(priority);
+            }
+
+            let task = queue.pop_front().unwrap();
+            if queue.is_empty() {
+                self.bitmap &= !(1 << priority);
+            }
+            self.queues[new_priority].push_back(task);
+            self.bitmap |= 1 << new_priority;
+        }
+    }
+}
+
+fn thread_of(task: &Task) -> Option<Arc<Thread>> {
+    task.data().downcast_ref::<Weak<Thread>>()?.upgrade()
+}
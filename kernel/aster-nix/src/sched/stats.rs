@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The statistics of the CPUs and the scheduler, e.g., the CPU time and the load average.
+
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use aster_frame::{
+    cpu::{this_cpu, MAX_CPUS},
+    task::cpu_idle_time,
+    timer::read_monotonic_nanos,
+};
+
+use crate::prelude::*;
+
+/// The CPU time spent by the threads on a CPU, in nanoseconds.
+struct CpuTimeStat {
+    user: AtomicU64,
+    system: AtomicU64,
+}
+
+impl CpuTimeStat {
+    const fn new() -> Self {
+        Self {
+            user: AtomicU64::new(0),
+            system: AtomicU64::new(0),
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_CPU_TIME_STAT: CpuTimeStat = CpuTimeStat::new();
+/// The CPU time, indexed by the CPU IDs.
+static CPU_TIME_STATS: [CpuTimeStat; MAX_CPUS] = [NEW_CPU_TIME_STAT; MAX_CPUS];
+
+/// The time that a CPU has spent in different modes since the system boots up.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpuTime {
+    /// The time spent by the threads in the user mode.
+    pub user: Duration,
+    /// The time spent by the threads in the kernel mode.
+    pub system: Duration,
+    /// The time spent in the idle task.
+    pub idle: Duration,
+}
+
+/// Charges the CPU time that is spent by a thread in the user mode or in the kernel mode to
+/// the current CPU.
+pub fn charge_cpu_time(time: Duration, is_user: bool) {
+    let stat = &CPU_TIME_STATS[this_cpu() as usize];
+    let counter = if is_user { &stat.user } else { &stat.system };
+    counter.fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// Returns the time that the given CPU has spent in different modes.
+pub fn cpu_time(cpu_id: u32) -> CpuTime {
+    let stat = &CPU_TIME_STATS[cpu_id as usize];
+    CpuTime {
+        user: Duration::from_nanos(stat.user.load(Ordering::Relaxed)),
+        system: Duration::from_nanos(stat.system.load(Ordering::Relaxed)),
+        idle: cpu_idle_time(cpu_id),
+    }
+}
+
+/// The number of fractional bits in the fixed-point load averages.
+pub const FSHIFT: u32 = 11;
+/// The fixed-point representation of 1.
+pub const FIXED_1: u64 = 1 << FSHIFT;
+/// The decay factors of the load averages over 1, 5 and 15 minutes, i.e.,
+/// `FIXED_1 / exp(LOAD_FREQ / period)`.
+const LOAD_EXP: [u64; 3] = [1884, 2014, 2037];
+/// The interval in nanoseconds at which the load averages are updated.
+const LOAD_FREQ: u64 = 5_000_000_000;
+
+static LOAD_AVG: SpinLock<LoadAvg> = SpinLock::new(LoadAvg::new());
+
+/// The exponentially-damped moving averages of the number of active tasks, i.e., the running
+/// and runnable tasks, just as Linux computes.
+struct LoadAvg {
+    /// The load averages over 1, 5 and 15 minutes, in fixed point.
+    loads: [u64; 3],
+    /// The time in nanoseconds of the monotonic clock when the next update is due.
+    next_update: u64,
+}
+
+impl LoadAvg {
+    const fn new() -> Self {
+        Self {
+            loads: [0; 3],
+            next_update: LOAD_FREQ,
+        }
+    }
+
+    /// Updates the load averages for the intervals that have elapsed by `now`.
+    ///
+    /// The timer tick is stopped on idle CPUs, so several intervals may have elapsed since the
+    /// last update. The number of active tasks is assumed to be the same in these intervals.
+    fn update(&mut self, now: u64, nr_active: impl FnOnce() -> usize) {
+        if now < self.next_update {
+            return;
+        }
+        let nr_intervals = (now - self.next_update) / LOAD_FREQ + 1;
+        self.next_update += nr_intervals * LOAD_FREQ;
+
+        let active = nr_active() as u64 * FIXED_1;
+        // The load averages converge long before this number of intervals.
+        for _ in 0..nr_intervals.min(MAX_CATCH_UP_INTERVALS) {
+            for (load, exp) in self.loads.iter_mut().zip(LOAD_EXP) {
+                *load = calc_load(*load, exp, active);
+            }
+        }
+    }
+}
+
+/// The maximum number of intervals that are caught up in one update.
+const MAX_CATCH_UP_INTERVALS: u64 = 1024;
+
+fn calc_load(load: u64, exp: u64, active: u64) -> u64 {
+    let mut new_load = load * exp + active * (FIXED_1 - exp);
+    if active >= load {
+        new_load += FIXED_1 - 1;
+    }
+    new_load / FIXED_1
+}
+
+/// Updates the load averages on a timer tick if an update is due.
+pub(super) fn update_load_avg(nr_active: impl FnOnce() -> usize) {
+    LOAD_AVG
+        .lock_irq_disabled()
+        .update(read_monotonic_nanos(), nr_active);
+}
+
+/// Returns the load averages over 1, 5 and 15 minutes, in fixed point with [`FSHIFT`]
+/// fractional bits.
+pub fn load_avg() -> [u64; 3] {
+    let mut load_avg = LOAD_AVG.lock_irq_disabled();
+    load_avg.update(read_monotonic_nanos(), super::nr_running);
+    load_avg.loads
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_ADJTIMEX, SYS_CLOCK_ADJTIME};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    time::{adjtimex, clockid_t, timex_t, ClockID},
+    util::{read_val_from_user, write_val_to_user},
+};
+
+pub fn sys_adjtimex(timex_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_ADJTIMEX);
+    do_adjtimex(timex_addr)
+}
+
+pub fn sys_clock_adjtime(clockid: clockid_t, timex_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_CLOCK_ADJTIME);
+    let clock_id = ClockID::try_from(clockid)?;
+    debug!("clock_id = {:?}", clock_id);
+
+    // Only the real time can be adjusted.
+    if clock_id != ClockID::CLOCK_REALTIME {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "the clock cannot be adjusted");
+    }
+
+    do_adjtimex(timex_addr)
+}
+
+fn do_adjtimex(timex_addr: Vaddr) -> Result<SyscallReturn> {
+    let mut timex = read_val_from_user::<timex_t>(timex_addr)?;
+    debug!("modes = 0x{:x}", timex.modes);
+
+    let state = adjtimex(&mut timex)?;
+    write_val_to_user(timex_addr, &timex)?;
+
+    Ok(SyscallReturn::Return(state as _))
+}
@@ -3,27 +3,25 @@
 use core::time::Duration;
 
 use super::{SyscallReturn, SYS_ALARM};
-use crate::{log_syscall_entry, prelude::*, process::posix_thread::PosixThreadExt};
+use crate::{log_syscall_entry, prelude::*, process::timer::ITimerType};
 
 pub fn sys_alarm(seconds: u32) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_ALARM);
     debug!("seconds = {}", seconds);
 
-    let current_thread = current_thread!();
-    let mut real_timer = {
-        let posix_thread = current_thread.as_posix_thread().unwrap();
-        posix_thread.real_timer().lock()
-    };
-
-    let remaining_secs = real_timer.remain().as_secs();
-
-    if seconds == 0 {
-        // Clear previous timer
-        real_timer.clear();
-        return Ok(SyscallReturn::Return(remaining_secs as _));
+    // The alarm shares the timer with `ITIMER_REAL`.
+    let (remain, _) = current!().timers().set_itimer(
+        ITimerType::ITIMER_REAL,
+        Duration::from_secs(seconds as u64),
+        Duration::ZERO,
+    )?;
+
+    // The remaining time is rounded to the nearest second, but a pending alarm never reports
+    // zero seconds.
+    let mut remaining_secs = remain.as_secs();
+    if (remaining_secs == 0 && !remain.is_zero()) || remain.subsec_micros() >= 500_000 {
+        remaining_secs += 1;
     }
 
-    real_timer.set(Duration::from_secs(seconds as u64))?;
-
     Ok(SyscallReturn::Return(remaining_secs as _))
 }
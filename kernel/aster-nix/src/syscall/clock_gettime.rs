@@ -1,22 +1,37 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{SyscallReturn, SYS_CLOCK_GETTIME};
+use super::{SyscallReturn, SYS_CLOCK_GETRES, SYS_CLOCK_GETTIME};
 use crate::{
     log_syscall_entry,
     prelude::*,
-    time::{clockid_t, now_as_duration, timespec_t, ClockID},
+    process::timer::TimerClock,
+    time::{clockid_t, timespec_t},
     util::write_val_to_user,
 };
 
 pub fn sys_clock_gettime(clockid: clockid_t, timespec_addr: Vaddr) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_CLOCK_GETTIME);
-    let clock_id = ClockID::try_from(clockid)?;
-    debug!("clockid = {:?}", clock_id);
+    debug!("clockid = {}", clockid);
 
-    let time_duration = now_as_duration(&clock_id)?;
+    let time_duration = TimerClock::from_clockid(clockid)?.now()?;
 
     let timespec = timespec_t::from(time_duration);
     write_val_to_user(timespec_addr, &timespec)?;
 
     Ok(SyscallReturn::Return(0))
 }
+
+pub fn sys_clock_getres(clockid: clockid_t, res_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_CLOCK_GETRES);
+    debug!("clockid = {}, res_addr = 0x{:x}", clockid, res_addr);
+
+    // The clock ID is checked even if the resolution is not needed, which is used by
+    // `clock_getcpuclockid` to check whether a process exists.
+    let resolution = TimerClock::from_clockid(clockid)?.resolution();
+
+    if res_addr != 0 {
+        write_val_to_user(res_addr, &timespec_t::from(resolution))?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
@@ -19,51 +19,72 @@ pub fn sys_clock_nanosleep(
 ) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_CLOCK_NANOSLEEP);
     let clock_id = ClockID::try_from(clockid)?;
-    let abs_time = if flags == 0 {
-        false
-    } else if flags == TIMER_ABSTIME {
-        true
-    } else {
-        unreachable!()
-    };
+    // Other flags are ignored, as Linux does.
+    let abs_time = flags & TIMER_ABSTIME != 0;
 
-    let duration = {
+    let request_time = {
         let timespec = read_val_from_user::<timespec_t>(request_timespec_addr)?;
-        if abs_time {
-            todo!("deal with abs time");
+        if !timespec.is_valid() {
+            return_errno_with_message!(Errno::EINVAL, "invalid time value");
         }
         Duration::from(timespec)
     };
 
     debug!(
-        "clockid = {:?}, abs_time = {}, duration = {:?}, remain_timespec_addr = 0x{:x}",
-        clock_id, abs_time, duration, remain_timespec_addr
+        "clockid = {:?}, abs_time = {}, request_time = {:?}, remain_timespec_addr = 0x{:x}",
+        clock_id, abs_time, request_time, remain_timespec_addr
     );
 
-    let start_time = now_as_duration(&clock_id)?;
+    // A relative sleep is measured by the monotonic clock, so that it is not affected if the
+    // clock is set during the sleep.
+    let (sleep_clock_id, end_time) = if abs_time {
+        if !matches!(
+            clock_id,
+            ClockID::CLOCK_REALTIME | ClockID::CLOCK_MONOTONIC | ClockID::CLOCK_BOOTTIME
+        ) {
+            return_errno_with_message!(
+                Errno::EOPNOTSUPP,
+                "the clock does not support absolute sleeps"
+            );
+        }
+        (clock_id, request_time)
+    } else {
+        let start_time = now_as_duration(&ClockID::CLOCK_MONOTONIC)?;
+        (ClockID::CLOCK_MONOTONIC, start_time + request_time)
+    };
 
     // FIXME: sleeping thread can only be interrupted by signals that will call signal handler or terminate
     // current process. i.e., the signals that should be ignored will not interrupt sleeping thread.
     let pauser = Pauser::new();
 
-    let res = pauser.pause_until_or_timeout(|| None, &duration);
-    match res {
-        Err(e) if e.error() == Errno::ETIME => Ok(SyscallReturn::Return(0)),
-        Err(e) if e.error() == Errno::EINTR => {
-            let end_time = now_as_duration(&clock_id)?;
+    loop {
+        let now = now_as_duration(&sleep_clock_id)?;
+        if now >= end_time {
+            return Ok(SyscallReturn::Return(0));
+        }
 
-            if end_time >= start_time + duration {
-                return Ok(SyscallReturn::Return(0));
-            }
+        // The sleep is armed on the timer of the clock, so an absolute sleep on `CLOCK_REALTIME`
+        // still ends at the right time if the clock is set. The clock is checked again after the
+        // deadline, since the real time may be slewed during the sleep.
+        let res = pauser.pause_until_or_deadline(|| None, sleep_clock_id, &end_time);
+        match res {
+            Err(e) if e.error() == Errno::ETIME => continue,
+            Err(e) if e.error() == Errno::EINTR => {
+                let now = now_as_duration(&sleep_clock_id)?;
+                if now >= end_time {
+                    return Ok(SyscallReturn::Return(0));
+                }
 
-            if remain_timespec_addr != 0 {
-                let remaining_duration = (start_time + duration) - end_time;
-                let remaining_timespec = timespec_t::from(remaining_duration);
-                write_val_to_user(remain_timespec_addr, &remaining_timespec)?;
-            }
+                // The remaining time is not reported for absolute sleeps, since the sleep can be
+                // restarted with the same absolute time.
+                if !abs_time && remain_timespec_addr != 0 {
+                    let remaining_timespec = timespec_t::from(end_time - now);
+                    write_val_to_user(remain_timespec_addr, &remaining_timespec)?;
+                }
 
-            return_errno_with_message!(Errno::EINTR, "sleep was interrupted");
+                return_errno_with_message!(Errno::EINTR, "sleep was interrupted");
+            }
+            Ok(()) | Err(_) => unreachable!(),
         }
-        Ok(()) | Err(_) => unreachable!(),
     }
 }
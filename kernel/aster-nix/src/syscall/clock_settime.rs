@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{SyscallReturn, SYS_CLOCK_SETTIME};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    time::{clockid_t, set_realtime, timespec_t, ClockID},
+    util::read_val_from_user,
+};
+
+pub fn sys_clock_settime(clockid: clockid_t, timespec_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_CLOCK_SETTIME);
+    let clock_id = ClockID::try_from(clockid)?;
+    let timespec = read_val_from_user::<timespec_t>(timespec_addr)?;
+    debug!("clock_id = {:?}, timespec = {:?}", clock_id, timespec);
+
+    // Only the real time can be set.
+    if clock_id != ClockID::CLOCK_REALTIME {
+        return_errno_with_message!(Errno::EINVAL, "the clock cannot be set");
+    }
+    if !timespec.is_valid() {
+        return_errno_with_message!(Errno::EINVAL, "invalid time value");
+    }
+
+    set_realtime(Duration::from(timespec))?;
+    Ok(SyscallReturn::Return(0))
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_EVENTFD, SYS_EVENTFD2};
+use crate::{
+    fs::{
+        eventfd::{EventFile, EventFileFlags},
+        file_table::FdFlags,
+    },
+    log_syscall_entry,
+    prelude::*,
+};
+
+pub fn sys_eventfd(init_val: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_EVENTFD);
+    do_sys_eventfd2(init_val, 0)
+}
+
+pub fn sys_eventfd2(init_val: u32, flags: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_EVENTFD2);
+    do_sys_eventfd2(init_val, flags)
+}
+
+fn do_sys_eventfd2(init_val: u32, flags: u32) -> Result<SyscallReturn> {
+    debug!("init_val = {}, flags = 0x{:x}", init_val, flags);
+
+    let flags = EventFileFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    let fd_flags = if flags.contains(EventFileFlags::EFD_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+
+    let event_file = Arc::new(EventFile::new(init_val as u64, flags));
+    let current = current!();
+    let fd = current.file_table().lock().insert(event_file, fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
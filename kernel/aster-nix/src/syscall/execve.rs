@@ -128,6 +128,8 @@ fn do_execve(
     current.set_executable_path(new_executable_path);
     // set signal disposition to default
     current.sig_dispositions().lock().inherit();
+    // POSIX timers are not preserved
+    current.timers().delete_all_posix_timers();
     // set cpu context to default
     let default_content = UserContext::default();
     *context.general_regs_mut() = *default_content.general_regs();
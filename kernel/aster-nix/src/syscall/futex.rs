@@ -4,10 +4,13 @@ use crate::{
     log_syscall_entry,
     prelude::*,
     process::posix_thread::futex::{
-        futex_op_and_flags_from_u32, futex_requeue, futex_wait, futex_wait_bitset, futex_wake,
-        futex_wake_bitset, FutexOp, FutexTimeout,
+        futex_cmp_requeue_pi, futex_lock_pi, futex_op_and_flags_from_u32, futex_requeue,
+        futex_trylock_pi, futex_unlock_pi, futex_wait, futex_wait_bitset, futex_wait_requeue_pi,
+        futex_wake, futex_wake_bitset, FutexFlags, FutexOp, FutexTimeout,
     },
     syscall::{SyscallReturn, SYS_FUTEX},
+    time::{timespec_t, ClockID},
+    util::read_val_from_user,
 };
 
 pub fn sys_futex(
@@ -19,13 +22,31 @@ pub fn sys_futex(
     bitset: u64,
 ) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_FUTEX);
-    // FIXME: we current ignore futex flags
-    let (futex_op, futex_flags) = futex_op_and_flags_from_u32(futex_op as _).unwrap();
+    let (futex_op, futex_flags) = futex_op_and_flags_from_u32(futex_op as _)?;
     debug!(
         "futex_op = {:?}, futex_flags = {:?}, futex_addr = 0x{:x}",
         futex_op, futex_flags, futex_addr
     );
 
+    let is_private = futex_flags.contains(FutexFlags::FUTEX_PRIVATE);
+    let clock_id = if futex_flags.contains(FutexFlags::FUTEX_CLOCK_REALTIME) {
+        if !matches!(
+            futex_op,
+            FutexOp::FUTEX_WAIT
+                | FutexOp::FUTEX_WAIT_BITSET
+                | FutexOp::FUTEX_WAIT_REQUEUE_PI
+                | FutexOp::FUTEX_LOCK_PI
+        ) {
+            return_errno_with_message!(
+                Errno::ENOSYS,
+                "FUTEX_CLOCK_REALTIME is not supported by the operation"
+            );
+        }
+        ClockID::CLOCK_REALTIME
+    } else {
+        ClockID::CLOCK_MONOTONIC
+    };
+
     let get_futex_val = |val: i32| -> Result<usize> {
         if val < 0 {
             return_errno_with_message!(Errno::EINVAL, "the futex val must not be negative");
@@ -33,45 +54,98 @@ pub fn sys_futex(
         Ok(val as usize)
     };
 
-    let get_futex_timeout = |timeout_addr| -> Result<Option<FutexTimeout>> {
+    // The timeout of `FUTEX_WAIT` is relative, while those of other operations are absolute.
+    let get_futex_timeout = |timeout_addr, clock_id, is_abs_time| -> Result<Option<FutexTimeout>> {
         if timeout_addr == 0 {
             return Ok(None);
         }
-        // TODO: parse a timeout
-        todo!()
+        let timeout = read_val_from_user::<timespec_t>(timeout_addr as _)?;
+        FutexTimeout::new(timeout, clock_id, is_abs_time).map(Some)
     };
 
     let res = match futex_op {
         FutexOp::FUTEX_WAIT => {
-            let timeout = get_futex_timeout(utime_addr).expect("Invalid time addr");
-            futex_wait(futex_addr as _, futex_val as _, &timeout).map(|_| 0)
+            let timeout = get_futex_timeout(utime_addr, clock_id, false)?;
+            futex_wait(futex_addr as _, futex_val as _, &timeout, is_private).map(|_| 0)
         }
         FutexOp::FUTEX_WAIT_BITSET => {
-            let timeout = get_futex_timeout(utime_addr).expect("Invalid time addr");
-            futex_wait_bitset(futex_addr as _, futex_val as _, &timeout, bitset as _).map(|_| 0)
+            let timeout = get_futex_timeout(utime_addr, clock_id, true)?;
+            futex_wait_bitset(
+                futex_addr as _,
+                futex_val as _,
+                &timeout,
+                bitset as _,
+                is_private,
+            )
+            .map(|_| 0)
         }
         FutexOp::FUTEX_WAKE => {
-            let max_count = get_futex_val(futex_val as i32).expect("Invalid futex val");
-            futex_wake(futex_addr as _, max_count).map(|count| count as isize)
+            let max_count = get_futex_val(futex_val as i32)?;
+            futex_wake(futex_addr as _, max_count, is_private).map(|count| count as isize)
         }
         FutexOp::FUTEX_WAKE_BITSET => {
-            let max_count = get_futex_val(futex_val as i32).expect("Invalid futex val");
-            futex_wake_bitset(futex_addr as _, max_count, bitset as _).map(|count| count as isize)
+            let max_count = get_futex_val(futex_val as i32)?;
+            futex_wake_bitset(futex_addr as _, max_count, bitset as _, is_private)
+                .map(|count| count as isize)
         }
-        FutexOp::FUTEX_REQUEUE => {
-            let max_nwakes = get_futex_val(futex_val as i32).expect("Invalid futex val");
-            let max_nrequeues = get_futex_val(utime_addr as i32).expect("Invalid utime addr");
+        FutexOp::FUTEX_REQUEUE | FutexOp::FUTEX_CMP_REQUEUE => {
+            let max_nwakes = get_futex_val(futex_val as i32)?;
+            let max_nrequeues = get_futex_val(utime_addr as i32)?;
+            let expected_val = if futex_op == FutexOp::FUTEX_CMP_REQUEUE {
+                Some(bitset as i32)
+            } else {
+                None
+            };
             futex_requeue(
                 futex_addr as _,
                 max_nwakes,
                 max_nrequeues,
                 futex_new_addr as _,
+                expected_val,
+                is_private,
             )
             .map(|nwakes| nwakes as _)
         }
-        _ => panic!("Unsupported futex operations"),
-    }
-    .unwrap();
+        FutexOp::FUTEX_LOCK_PI => {
+            // The timeout of `FUTEX_LOCK_PI` is always measured by `CLOCK_REALTIME`.
+            let timeout = get_futex_timeout(utime_addr, ClockID::CLOCK_REALTIME, true)?;
+            futex_lock_pi(futex_addr as _, &timeout, is_private).map(|_| 0)
+        }
+        FutexOp::FUTEX_TRYLOCK_PI => futex_trylock_pi(futex_addr as _, is_private).map(|_| 0),
+        FutexOp::FUTEX_UNLOCK_PI => futex_unlock_pi(futex_addr as _, is_private).map(|_| 0),
+        FutexOp::FUTEX_WAIT_REQUEUE_PI => {
+            let timeout = get_futex_timeout(utime_addr, clock_id, true)?;
+            futex_wait_requeue_pi(
+                futex_addr as _,
+                futex_val as _,
+                &timeout,
+                futex_new_addr as _,
+                is_private,
+            )
+            .map(|_| 0)
+        }
+        FutexOp::FUTEX_CMP_REQUEUE_PI => {
+            if futex_val != 1 {
+                return_errno_with_message!(
+                    Errno::EINVAL,
+                    "only one waiter can be woken up by FUTEX_CMP_REQUEUE_PI"
+                );
+            }
+            let max_nrequeues = get_futex_val(utime_addr as i32)?;
+            futex_cmp_requeue_pi(
+                futex_addr as _,
+                max_nrequeues,
+                futex_new_addr as _,
+                bitset as i32,
+                is_private,
+            )
+            .map(|count| count as _)
+        }
+        _ => {
+            warn!("futex op {:?} is not supported", futex_op);
+            return_errno_with_message!(Errno::ENOSYS, "unsupported futex operation");
+        }
+    }?;
 
     debug!("futex returns, tid= {} ", current_thread!().tid());
     Ok(SyscallReturn::Return(res as _))
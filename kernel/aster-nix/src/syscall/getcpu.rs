@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_frame::cpu::this_cpu;
+
+use super::{SyscallReturn, SYS_GETCPU};
+use crate::{log_syscall_entry, prelude::*, util::write_val_to_user};
+
+pub fn sys_getcpu(cpu_addr: Vaddr, node_addr: Vaddr, _tcache_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_GETCPU);
+    // The CPU may change as soon as the system call returns, which is fine for the callers.
+    let cpu = this_cpu();
+    debug!(
+        "cpu = {}, cpu_addr = 0x{:x}, node_addr = 0x{:x}",
+        cpu, cpu_addr, node_addr
+    );
+
+    if cpu_addr != 0 {
+        write_val_to_user(cpu_addr, &cpu)?;
+    }
+    // There is only one NUMA node.
+    if node_addr != 0 {
+        write_val_to_user(node_addr, &0u32)?;
+    }
+    Ok(SyscallReturn::Return(0))
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_GETRUSAGE};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::{posix_thread::PosixThreadExt, rusage_t, RusageTarget},
+    util::write_val_to_user,
+};
+
+pub fn sys_getrusage(target: i32, rusage_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_GETRUSAGE);
+    let target = RusageTarget::try_from(target)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "invalid target"))?;
+    debug!("target = {:?}, rusage_addr = 0x{:x}", target, rusage_addr);
+
+    let rusage = match target {
+        RusageTarget::RUSAGE_SELF => {
+            let current = current!();
+            let cpu_clock = current.cpu_clock();
+            rusage_t::new(cpu_clock.user_time(), cpu_clock.system_time())
+        }
+        RusageTarget::RUSAGE_CHILDREN => {
+            let current = current!();
+            let cpu_clock = current.children_cpu_clock();
+            rusage_t::new(cpu_clock.user_time(), cpu_clock.system_time())
+        }
+        RusageTarget::RUSAGE_THREAD => {
+            let current_thread = current_thread!();
+            let cpu_clock = current_thread.as_posix_thread().unwrap().cpu_clock();
+            rusage_t::new(cpu_clock.user_time(), cpu_clock.system_time())
+        }
+    };
+    write_val_to_user(rusage_addr, &rusage)?;
+
+    Ok(SyscallReturn::Return(0))
+}
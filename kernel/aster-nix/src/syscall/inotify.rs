@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_INOTIFY_ADD_WATCH, SYS_INOTIFY_INIT1, SYS_INOTIFY_RM_WATCH};
+use crate::{
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDescripter},
+        fs_resolver::{FsPath, AT_FDCWD},
+        notify::inotify::{InotifyFile, InotifyFlags, InotifyWatchFlags},
+        utils::PATH_MAX,
+    },
+    log_syscall_entry,
+    prelude::*,
+    util::read_cstring_from_user,
+};
+
+pub fn sys_inotify_init() -> Result<SyscallReturn> {
+    self::sys_inotify_init1(0)
+}
+
+pub fn sys_inotify_init1(flags: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_INOTIFY_INIT1);
+    debug!("flags = 0x{:x}", flags);
+
+    let flags = InotifyFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    let fd_flags = if flags.contains(InotifyFlags::IN_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+
+    let inotify_file = InotifyFile::new(flags);
+    let current = current!();
+    let fd = current.file_table().lock().insert(inotify_file, fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+pub fn sys_inotify_add_watch(
+    fd: FileDescripter,
+    path_ptr: Vaddr,
+    mask: u32,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_INOTIFY_ADD_WATCH);
+    let path = read_cstring_from_user(path_ptr, PATH_MAX)?;
+    debug!("fd = {}, path = {:?}, mask = 0x{:x}", fd, path, mask);
+
+    let current = current!();
+    let file = get_inotify_file(fd)?;
+    let dentry = {
+        let path = path.to_string_lossy();
+        if path.is_empty() {
+            return_errno_with_message!(Errno::ENOENT, "path is empty");
+        }
+        let fs_path = FsPath::new(AT_FDCWD, path.as_ref())?;
+        let fs = current.fs().read();
+        if InotifyWatchFlags::from_bits_truncate(mask).contains(InotifyWatchFlags::IN_DONT_FOLLOW) {
+            fs.lookup_no_follow(&fs_path)?
+        } else {
+            fs.lookup(&fs_path)?
+        }
+    };
+
+    let inotify_file = file.downcast_ref::<InotifyFile>().unwrap();
+    let wd = inotify_file.add_watch(&dentry, mask)?;
+    Ok(SyscallReturn::Return(wd as _))
+}
+
+pub fn sys_inotify_rm_watch(fd: FileDescripter, wd: i32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_INOTIFY_RM_WATCH);
+    debug!("fd = {}, wd = {}", fd, wd);
+
+    let file = get_inotify_file(fd)?;
+    let inotify_file = file.downcast_ref::<InotifyFile>().unwrap();
+    inotify_file.remove_watch(wd)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+fn get_inotify_file(fd: FileDescripter) -> Result<Arc<dyn FileLike>> {
+    let current = current!();
+    let file_table = current.file_table().lock();
+    let file = file_table.get_file(fd)?;
+    if file.downcast_ref::<InotifyFile>().is_none() {
+        return_errno_with_message!(Errno::EINVAL, "the file is not an inotify file");
+    }
+    Ok(file.clone())
+}
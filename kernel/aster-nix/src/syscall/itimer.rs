@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{SyscallReturn, SYS_GETITIMER, SYS_SETITIMER};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::timer::ITimerType,
+    time::{itimerval_t, timeval_t},
+    util::{read_val_from_user, write_val_to_user},
+};
+
+pub fn sys_setitimer(
+    which: i32,
+    new_value_addr: Vaddr,
+    old_value_addr: Vaddr,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SETITIMER);
+    debug!(
+        "which = {}, new_value_addr = 0x{:x}, old_value_addr = 0x{:x}",
+        which, new_value_addr, old_value_addr
+    );
+
+    let itimer_type = ITimerType::try_from(which)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "invalid timer type"))?;
+    // A null new value disarms the timer, which is an obsolete behavior of Linux.
+    let new_value = if new_value_addr == 0 {
+        itimerval_t::default()
+    } else {
+        read_val_from_user::<itimerval_t>(new_value_addr)?
+    };
+    if !new_value.it_value.is_valid() || !new_value.it_interval.is_valid() {
+        return_errno_with_message!(Errno::EINVAL, "invalid time value");
+    }
+
+    let (old_value, old_interval) = current!().timers().set_itimer(
+        itimer_type,
+        Duration::from(new_value.it_value),
+        Duration::from(new_value.it_interval),
+    )?;
+
+    if old_value_addr != 0 {
+        write_val_to_user(old_value_addr, &to_itimerval(old_value, old_interval))?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_getitimer(which: i32, curr_value_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_GETITIMER);
+    debug!(
+        "which = {}, curr_value_addr = 0x{:x}",
+        which, curr_value_addr
+    );
+
+    let itimer_type = ITimerType::try_from(which)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "invalid timer type"))?;
+    let (value, interval) = current!().timers().get_itimer(itimer_type)?;
+    write_val_to_user(curr_value_addr, &to_itimerval(value, interval))?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+fn to_itimerval(value: Duration, interval: Duration) -> itimerval_t {
+    // An armed timer reports at least one microsecond, since a zero value means that the timer
+    // is disarmed.
+    let value = if value.is_zero() {
+        value
+    } else {
+        value.max(Duration::from_micros(1))
+    };
+    itimerval_t {
+        it_interval: timeval_t::from(interval),
+        it_value: timeval_t::from(value),
+    }
+}
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use align_ext::AlignExt;
+
 use super::{SyscallReturn, SYS_MADVISE};
-use crate::{log_syscall_entry, prelude::*, util::read_bytes_from_user};
+use crate::{log_syscall_entry, prelude::*, vm::vmar::vm_mapping::VmMappingFlags};
 
 pub fn sys_madvise(start: Vaddr, len: usize, behavior: i32) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_MADVISE);
@@ -10,31 +12,59 @@ pub fn sys_madvise(start: Vaddr, len: usize, behavior: i32) -> Result<SyscallRet
         "start = 0x{:x}, len = 0x{:x}, behavior = {:?}",
         start, len, behavior
     );
+
+    if start % PAGE_SIZE != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the start address is not page-aligned");
+    }
+    let len = len.align_up(PAGE_SIZE);
+    if len == 0 {
+        return Ok(SyscallReturn::Return(0));
+    }
+    let advised_range = start..start + len;
+
+    let current = current!();
+    let root_vmar = current.root_vmar();
     match behavior {
+        // The hints of the access patterns are not used yet.
         MadviseBehavior::MADV_NORMAL
+        | MadviseBehavior::MADV_RANDOM
         | MadviseBehavior::MADV_SEQUENTIAL
-        | MadviseBehavior::MADV_WILLNEED => {
-            // perform a read at first
-            let mut buffer = vec![0u8; len];
-            read_bytes_from_user(start, &mut buffer)?;
+        | MadviseBehavior::MADV_COLD => (),
+        MadviseBehavior::MADV_PAGEOUT => root_vmar.swap_out(advised_range)?,
+        // There are no core dumps, so all the pages are excluded from them anyway.
+        MadviseBehavior::MADV_DONTDUMP | MadviseBehavior::MADV_DODUMP => (),
+        MadviseBehavior::MADV_WILLNEED => root_vmar.prefetch(advised_range)?,
+        MadviseBehavior::MADV_DONTNEED => root_vmar.discard(advised_range, false)?,
+        MadviseBehavior::MADV_FREE => root_vmar.discard(advised_range, true)?,
+        MadviseBehavior::MADV_REMOVE => root_vmar.remove(advised_range)?,
+        MadviseBehavior::MADV_DONTFORK => root_vmar.update_flags(
+            advised_range,
+            VmMappingFlags::DONT_FORK,
+            VmMappingFlags::empty(),
+        )?,
+        MadviseBehavior::MADV_DOFORK => root_vmar.update_flags(
+            advised_range,
+            VmMappingFlags::empty(),
+            VmMappingFlags::DONT_FORK,
+        )?,
+        MadviseBehavior::MADV_HUGEPAGE => root_vmar.update_flags(
+            advised_range,
+            VmMappingFlags::HUGE_PAGE,
+            VmMappingFlags::NO_HUGE_PAGE,
+        )?,
+        MadviseBehavior::MADV_NOHUGEPAGE => root_vmar.update_flags(
+            advised_range,
+            VmMappingFlags::NO_HUGE_PAGE,
+            VmMappingFlags::HUGE_PAGE,
+        )?,
+        _ => {
+            warn!("unsupported madvise behavior: {:?}", behavior);
+            return_errno_with_message!(Errno::EINVAL, "the behavior is not supported");
         }
-        MadviseBehavior::MADV_DONTNEED => madv_dontneed(start, len)?,
-        _ => todo!(),
     }
     Ok(SyscallReturn::Return(0))
 }
 
-fn madv_dontneed(start: Vaddr, len: usize) -> Result<()> {
-    debug_assert!(start % PAGE_SIZE == 0);
-    debug_assert!(len % PAGE_SIZE == 0);
-    let current = current!();
-    let root_vmar = current.root_vmar();
-    let advised_range = start..start + len;
-    // `destroy()` interface may require adjustment and replacement afterwards.
-    let _ = root_vmar.destroy(advised_range);
-    Ok(())
-}
-
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, TryFromInt)]
 #[allow(non_camel_case_types)]
@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_MINCORE};
+use crate::{log_syscall_entry, prelude::*, util::write_bytes_to_user};
+
+/// The number of pages that are checked at a time, so that a large range does not require
+/// a large buffer in the kernel.
+const NR_CHUNK_PAGES: usize = PAGE_SIZE;
+
+pub fn sys_mincore(addr: Vaddr, len: usize, vec: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MINCORE);
+    debug!("addr = 0x{:x}, len = 0x{:x}, vec = 0x{:x}", addr, len, vec);
+
+    if addr % PAGE_SIZE != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the address is not page-aligned");
+    }
+    let end = addr
+        .checked_add(len)
+        .and_then(|end| end.checked_next_multiple_of(PAGE_SIZE))
+        .ok_or(Error::with_message(Errno::ENOMEM, "the range overflows"))?;
+
+    let current = current!();
+    let root_vmar = current.root_vmar();
+    for chunk_start in (addr..end).step_by(NR_CHUNK_PAGES * PAGE_SIZE) {
+        let chunk_end = chunk_start + (end - chunk_start).min(NR_CHUNK_PAGES * PAGE_SIZE);
+        // The least significant bit of each byte tells whether the page is resident.
+        let resident_pages: Vec<u8> = root_vmar
+            .resident_pages(chunk_start..chunk_end)?
+            .into_iter()
+            .map(u8::from)
+            .collect();
+        write_bytes_to_user(vec + (chunk_start - addr) / PAGE_SIZE, &resident_pages)?;
+    }
+    Ok(SyscallReturn::Return(0))
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::ops::Range;
+
+use align_ext::AlignExt;
+
+use super::{SyscallReturn, SYS_MLOCK, SYS_MLOCKALL, SYS_MUNLOCK, SYS_MUNLOCKALL};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::{credentials, Process, ResourceType},
+    vm::vmar::{
+        get_intersected_range, is_intersected,
+        vm_mapping::{VmMapping, VmMappingFlags},
+    },
+};
+
+pub fn sys_mlock(addr: Vaddr, len: usize) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MLOCK);
+    debug!("addr = 0x{:x}, len = 0x{:x}", addr, len);
+
+    let range = page_range(addr, len)?;
+    let current = current!();
+    let lock_limit = memlock_limit(&current)?;
+
+    let root_vmar = current.root_vmar();
+    let vm_mappings = root_vmar.vm_mappings()?;
+    // The pages that are already locked are not counted twice.
+    let locked_size = locked_size(&vm_mappings, &(0..usize::MAX)) + range.len()
+        - locked_size(&vm_mappings, &range);
+    if locked_size > lock_limit {
+        return_errno_with_message!(Errno::ENOMEM, "the locked size exceeds RLIMIT_MEMLOCK");
+    }
+
+    root_vmar.lock(range, true, false)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_munlock(addr: Vaddr, len: usize) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MUNLOCK);
+    debug!("addr = 0x{:x}, len = 0x{:x}", addr, len);
+
+    let range = page_range(addr, len)?;
+    let current = current!();
+    let root_vmar = current.root_vmar();
+    root_vmar.lock(range, false, false)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_mlockall(flags: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MLOCKALL);
+    let flags = MlockallFlags::from_bits(flags)
+        .ok_or(Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    debug!("flags = {:?}", flags);
+
+    if !flags.intersects(MlockallFlags::MCL_CURRENT | MlockallFlags::MCL_FUTURE) {
+        return_errno_with_message!(Errno::EINVAL, "MCL_CURRENT or MCL_FUTURE is required");
+    }
+    let current = current!();
+    let lock_limit = memlock_limit(&current)?;
+    let on_fault = flags.contains(MlockallFlags::MCL_ONFAULT);
+
+    let root_vmar = current.root_vmar();
+    if flags.contains(MlockallFlags::MCL_CURRENT) {
+        let mapped_size: usize = root_vmar
+            .vm_mappings()?
+            .iter()
+            .map(|vm_mapping| vm_mapping.map_size())
+            .sum();
+        if mapped_size > lock_limit {
+            return_errno_with_message!(Errno::ENOMEM, "the mapped size exceeds RLIMIT_MEMLOCK");
+        }
+    }
+
+    // The previous setting for the future mappings is replaced.
+    if flags.contains(MlockallFlags::MCL_FUTURE) {
+        root_vmar.set_future_lock(Some(on_fault));
+    } else {
+        root_vmar.set_future_lock(None);
+    }
+    if flags.contains(MlockallFlags::MCL_CURRENT) {
+        root_vmar.lock_all(true, on_fault)?;
+    }
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_munlockall() -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MUNLOCKALL);
+    let current = current!();
+    let root_vmar = current.root_vmar();
+    root_vmar.set_future_lock(None);
+    root_vmar.lock_all(false, false)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Returns the range of the pages that contain the bytes in `addr..addr + len`.
+fn page_range(addr: Vaddr, len: usize) -> Result<Range<Vaddr>> {
+    let end = addr
+        .checked_add(len)
+        .ok_or(Error::with_message(Errno::EINVAL, "the range overflows"))?;
+    Ok(addr.align_down(PAGE_SIZE)..end.align_up(PAGE_SIZE))
+}
+
+/// Returns the maximum size of the memory that the process can lock, in bytes.
+///
+/// Privileged users are not limited by `RLIMIT_MEMLOCK`.
+fn memlock_limit(current: &Process) -> Result<usize> {
+    // TODO: Check `CAP_IPC_LOCK` instead once capabilities are supported.
+    if credentials().euid().is_root() {
+        return Ok(usize::MAX);
+    }
+
+    let lock_limit = current
+        .resource_limits()
+        .lock()
+        .get_rlimit(ResourceType::RLIMIT_MEMLOCK)
+        .get_cur();
+    if lock_limit == 0 {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "locking memory is not allowed by RLIMIT_MEMLOCK"
+        );
+    }
+    Ok(lock_limit.try_into().unwrap_or(usize::MAX))
+}
+
+/// Returns the size of the locked pages in the range.
+fn locked_size(vm_mappings: &[Arc<VmMapping>], range: &Range<Vaddr>) -> usize {
+    vm_mappings
+        .iter()
+        .filter(|vm_mapping| {
+            vm_mapping.flags().contains(VmMappingFlags::LOCKED)
+                && is_intersected(&vm_mapping.range(), range)
+        })
+        .map(|vm_mapping| get_intersected_range(&vm_mapping.range(), range).len())
+        .sum()
+}
+
+bitflags! {
+    struct MlockallFlags: u32 {
+        const MCL_CURRENT = 1 << 0;
+        const MCL_FUTURE  = 1 << 1;
+        const MCL_ONFAULT = 1 << 2;
+    }
+}
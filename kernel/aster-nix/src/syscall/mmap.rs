@@ -3,18 +3,18 @@
 //! This mod defines mmap flags and the handler to syscall mmap
 
 use align_ext::AlignExt;
-use aster_frame::vm::VmPerm;
+use aster_frame::vm::{VmPerm, HUGE_PAGE_SIZE};
 use aster_rights::Rights;
 
 use super::SyscallReturn;
 use crate::{
-    fs::file_table::FileDescripter,
+    fs::{file_table::FileDescripter, utils::Dentry},
     log_syscall_entry,
     prelude::*,
     syscall::SYS_MMAP,
     vm::{
         perms::VmPerms,
-        vmo::{Vmo, VmoChildOptions, VmoOptions, VmoRightsOp},
+        vmo::{Vmo, VmoChildOptions, VmoFlags, VmoOptions, VmoRightsOp},
     },
 };
 
@@ -53,32 +53,61 @@ fn do_sys_mmap(
         addr, len, vm_perm, option, fd, offset
     );
 
-    let len = len.align_up(PAGE_SIZE);
+    let is_huge = option.flags.contains(MMapFlags::MAP_HUGETLB);
+    let page_size = if is_huge { HUGE_PAGE_SIZE } else { PAGE_SIZE };
+    let len = len.align_up(page_size);
 
     if offset % PAGE_SIZE != 0 {
         return_errno_with_message!(Errno::EINVAL, "mmap only support page-aligned offset");
     }
+    if is_huge {
+        if !option.flags.contains(MMapFlags::MAP_ANONYMOUS) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "huge pages are only supported for anonymous mappings"
+            );
+        }
+        if option.flags.contains(MMapFlags::MAP_FIXED) && addr % HUGE_PAGE_SIZE != 0 {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the address is not aligned to the huge page size"
+            );
+        }
+    }
     let perms = VmPerms::from(vm_perm);
 
-    let vmo = if option.flags.contains(MMapFlags::MAP_ANONYMOUS) {
+    let (vmo, file) = if option.flags.contains(MMapFlags::MAP_ANONYMOUS) {
         if offset != 0 {
             return_errno_with_message!(Errno::EINVAL, "offset must be zero for anonymous mapping");
         }
-        alloc_anonyous_vmo(len)?
+        (alloc_anonyous_vmo(len, &option)?, None)
     } else {
-        alloc_filebacked_vmo(fd, len, offset, &option)?
+        let (vmo, dentry) = alloc_filebacked_vmo(fd, len, offset, &option)?;
+        (vmo, Some(dentry))
     };
 
     let current = current!();
     let root_vmar = current.root_vmar();
     let vm_map_options = {
-        let mut options = root_vmar.new_map(vmo.to_dyn(), perms)?;
+        let mut options = root_vmar
+            .new_map(vmo.to_dyn(), perms)?
+            .size(len)
+            .is_shared(option.typ() != MMapType::Private);
         let flags = option.flags;
         if flags.contains(MMapFlags::MAP_FIXED) {
             options = options.offset(addr).can_overwrite(true);
-        } else if flags.contains(MMapFlags::MAP_32BIT) {
-            // TODO: support MAP_32BIT. MAP_32BIT requires the map range to be below 2GB
-            warn!("MAP_32BIT is not supported");
+        } else {
+            if flags.contains(MMapFlags::MAP_32BIT) {
+                // TODO: support MAP_32BIT. MAP_32BIT requires the map range to be below 2GB
+                warn!("MAP_32BIT is not supported");
+            }
+            // Large anonymous mappings are aligned, so that they can be backed by huge pages.
+            if flags.contains(MMapFlags::MAP_ANONYMOUS) && len >= HUGE_PAGE_SIZE {
+                options = options.align(HUGE_PAGE_SIZE);
+            }
+        }
+        if let Some(dentry) = file {
+            options = options.file(dentry, offset);
         }
         options
     };
@@ -88,9 +117,16 @@ fn do_sys_mmap(
     Ok(map_addr)
 }
 
-fn alloc_anonyous_vmo(len: usize) -> Result<Vmo> {
-    let vmo_options: VmoOptions<Rights> = VmoOptions::new(len);
-    vmo_options.alloc()
+fn alloc_anonyous_vmo(len: usize, option: &MMapOptions) -> Result<Vmo> {
+    let mut vmo_flags = VmoFlags::empty();
+    if option.typ() != MMapType::Private {
+        // A shared mapping grows over its VMO when it is remapped with a larger size
+        vmo_flags |= VmoFlags::RESIZABLE;
+    }
+    if option.flags.contains(MMapFlags::MAP_HUGETLB) {
+        vmo_flags |= VmoFlags::HUGE_PAGE;
+    }
+    VmoOptions::<Rights>::new(len).flags(vmo_flags).alloc()
 }
 
 fn alloc_filebacked_vmo(
@@ -98,29 +134,29 @@ fn alloc_filebacked_vmo(
     len: usize,
     offset: usize,
     option: &MMapOptions,
-) -> Result<Vmo> {
+) -> Result<(Vmo, Arc<Dentry>)> {
     let current = current!();
-    let page_cache_vmo = {
-        let fs_resolver = current.fs().read();
-        let dentry = fs_resolver.lookup_from_fd(fd)?;
-        let inode = dentry.inode();
-        inode
-            .page_cache()
-            .ok_or(Error::with_message(
-                Errno::EBADF,
-                "File does not have page cache",
-            ))?
-            .to_dyn()
-    };
-
-    if option.typ() == MMapType::Private {
+    let dentry = current.fs().read().lookup_from_fd(fd)?;
+    let page_cache_vmo = dentry
+        .inode()
+        .page_cache()
+        .ok_or(Error::with_message(
+            Errno::EBADF,
+            "File does not have page cache",
+        ))?
+        .to_dyn();
+
+    let vmo = if option.typ() == MMapType::Private {
         // map private
-        VmoChildOptions::new_cow(page_cache_vmo, offset..(offset + len)).alloc()
+        VmoChildOptions::new_cow(page_cache_vmo, offset..(offset + len)).alloc()?
     } else {
         // map shared
-        // FIXME: map shared vmo can exceed parent range, but slice child cannot
-        VmoChildOptions::new_slice_rights(page_cache_vmo, offset..(offset + len)).alloc()
-    }
+        // The slice child cannot exceed the page cache, so the pages beyond the end of file
+        // are left out of the VMO, but they are still covered by the mapping.
+        let slice_end = (offset + len).min(page_cache_vmo.size()).max(offset);
+        VmoChildOptions::new_slice_rights(page_cache_vmo, offset..slice_end).alloc()?
+    };
+    Ok((vmo, dentry))
 }
 
 // Definition of MMap flags, conforming to the linux mmap interface:
@@ -132,6 +168,10 @@ fn alloc_filebacked_vmo(
 // The map type mask
 const MAP_TYPE: u32 = 0xf;
 
+// The bits that encode the base-2 logarithm of the huge page size with `MAP_HUGETLB`
+const MAP_HUGE_SHIFT: u32 = 26;
+const MAP_HUGE_MASK: u32 = 0x3f;
+
 #[derive(Copy, Clone, PartialEq, Debug, TryFromInt)]
 #[repr(u8)]
 pub enum MMapType {
@@ -173,7 +213,12 @@ impl TryFrom<u32> for MMapOptions {
         let typ_raw = (value & MAP_TYPE) as u8;
         let typ = MMapType::try_from(typ_raw)?;
 
-        let flags_raw = value & !MAP_TYPE;
+        let huge_page_shift = (value >> MAP_HUGE_SHIFT) & MAP_HUGE_MASK;
+        if huge_page_shift != 0 && huge_page_shift != HUGE_PAGE_SIZE.trailing_zeros() {
+            return_errno_with_message!(Errno::EINVAL, "the huge page size is not supported");
+        }
+
+        let flags_raw = value & !MAP_TYPE & !(MAP_HUGE_MASK << MAP_HUGE_SHIFT);
         let Some(flags) = MMapFlags::from_bits(flags_raw) else {
             return Err(Error::with_message(Errno::EINVAL, "unknown mmap flags"));
         };
@@ -20,18 +20,21 @@ use crate::{
     prelude::*,
     syscall::{
         access::sys_access,
+        adjtimex::{sys_adjtimex, sys_clock_adjtime},
         arch_prctl::sys_arch_prctl,
         brk::sys_brk,
         chdir::{sys_chdir, sys_fchdir},
         chmod::{sys_chmod, sys_fchmod, sys_fchmodat},
         chown::{sys_chown, sys_fchown, sys_fchownat, sys_lchown},
         chroot::sys_chroot,
-        clock_gettime::sys_clock_gettime,
+        clock_gettime::{sys_clock_getres, sys_clock_gettime},
         clock_nanosleep::sys_clock_nanosleep,
+        clock_settime::sys_clock_settime,
         clone::sys_clone,
         close::sys_close,
         dup::{sys_dup, sys_dup2},
         epoll::{sys_epoll_create, sys_epoll_create1, sys_epoll_ctl, sys_epoll_wait},
+        eventfd::{sys_eventfd, sys_eventfd2},
         execve::sys_execve,
         exit::sys_exit,
         exit_group::sys_exit_group,
@@ -39,6 +42,7 @@ use crate::{
         fork::sys_fork,
         fsync::sys_fsync,
         futex::sys_futex,
+        getcpu::sys_getcpu,
         getcwd::sys_getcwd,
         getdents64::sys_getdents64,
         getegid::sys_getegid,
@@ -48,43 +52,66 @@ use crate::{
         getpgrp::sys_getpgrp,
         getpid::sys_getpid,
         getppid::sys_getppid,
+        getrusage::sys_getrusage,
         gettid::sys_gettid,
         gettimeofday::sys_gettimeofday,
         getuid::sys_getuid,
+        inotify::{
+            sys_inotify_add_watch, sys_inotify_init, sys_inotify_init1, sys_inotify_rm_watch,
+        },
         ioctl::sys_ioctl,
+        itimer::{sys_getitimer, sys_setitimer},
         kill::sys_kill,
         link::{sys_link, sys_linkat},
         lseek::sys_lseek,
         madvise::sys_madvise,
+        mincore::sys_mincore,
         mkdir::{sys_mkdir, sys_mkdirat},
+        mlock::{sys_mlock, sys_mlockall, sys_munlock, sys_munlockall},
         mmap::sys_mmap,
         mprotect::sys_mprotect,
+        mremap::sys_mremap,
+        msync::sys_msync,
         munmap::sys_munmap,
         open::{sys_open, sys_openat},
         pause::sys_pause,
         pipe::{sys_pipe, sys_pipe2},
         poll::sys_poll,
+        posix_timer::{
+            sys_timer_create, sys_timer_delete, sys_timer_getoverrun, sys_timer_gettime,
+            sys_timer_settime,
+        },
         prctl::sys_prctl,
         prlimit64::sys_prlimit64,
         read::sys_read,
         readlink::{sys_readlink, sys_readlinkat},
+        recvmsg::sys_recvmsg,
         rename::{sys_rename, sys_renameat},
         rmdir::sys_rmdir,
         rt_sigaction::sys_rt_sigaction,
         rt_sigprocmask::sys_rt_sigprocmask,
         rt_sigreturn::sys_rt_sigreturn,
+        sched_affinity::{sys_sched_getaffinity, sys_sched_setaffinity},
+        sched_policy::{sys_sched_get_priority_max, sys_sched_get_priority_min, sys_sched_getparam, sys_sched_getscheduler, sys_sched_rr_get_interval, sys_sched_setparam, sys_sched_setscheduler},
         sched_yield::sys_sched_yield,
         select::sys_select,
+        sendmsg::sys_sendmsg,
         set_get_priority::{sys_get_priority, sys_set_priority},
         set_robust_list::sys_set_robust_list,
         set_tid_address::sys_set_tid_address,
         setpgid::sys_setpgid,
+        settimeofday::sys_settimeofday,
+        signalfd::{sys_signalfd, sys_signalfd4},
         stat::{sys_fstat, sys_fstatat, sys_lstat, sys_stat},
         statfs::{sys_fstatfs, sys_statfs},
+        swapon::{sys_swapoff, sys_swapon},
         symlink::{sys_symlink, sys_symlinkat},
         sync::sys_sync,
+            sys_inotify_add_watch, sys_inotify_init, sys_inotify_init1, sys_inotify_rm_watch,
         tgkill::sys_tgkill,
         time::sys_time,
+        timerfd::{sys_timerfd_create, sys_timerfd_gettime, sys_timerfd_settime},
+        times::sys_times,
         truncate::{sys_ftruncate, sys_truncate},
         umask::sys_umask,
         uname::sys_uname,
@@ -94,11 +121,13 @@ use crate::{
         waitid::sys_waitid,
         write::sys_write,
         writev::sys_writev,
+        },
     },
 };
 
 mod accept;
 mod access;
+mod adjtimex;
 mod alarm;
 mod arch_prctl;
 mod bind;
@@ -109,12 +138,14 @@ mod chown;
 mod chroot;
 mod clock_gettime;
 mod clock_nanosleep;
+mod clock_settime;
 mod clone;
 mod close;
 mod connect;
 mod constants;
 mod dup;
 mod epoll;
+mod eventfd;
 mod execve;
 mod exit;
 mod exit_group;
@@ -122,6 +153,7 @@ mod fcntl;
 mod fork;
 mod fsync;
 mod futex;
+mod getcpu;
 mod getcwd;
 mod getdents64;
 mod getegid;
@@ -136,39 +168,51 @@ mod getppid;
 mod getrandom;
 mod getresgid;
 mod getresuid;
+mod getrusage;
 mod getsid;
 mod getsockname;
 mod getsockopt;
 mod gettid;
 mod gettimeofday;
 mod getuid;
+mod inotify;
 mod ioctl;
+mod itimer;
 mod kill;
 mod link;
 mod listen;
 mod lseek;
 mod madvise;
+mod mincore;
 mod mkdir;
+mod mlock;
 mod mmap;
 mod mprotect;
+mod mremap;
+mod msync;
 mod munmap;
 mod open;
 mod pause;
 mod pipe;
 mod poll;
+mod posix_timer;
 mod prctl;
 mod pread64;
 mod prlimit64;
 mod read;
 mod readlink;
 mod recvfrom;
+mod recvmsg;
 mod rename;
 mod rmdir;
 mod rt_sigaction;
 mod rt_sigprocmask;
 mod rt_sigreturn;
+mod sched_affinity;
+mod sched_policy;
 mod sched_yield;
 mod select;
+mod sendmsg;
 mod sendto;
 mod set_get_priority;
 mod set_robust_list;
@@ -184,17 +228,22 @@ mod setresuid;
 mod setreuid;
 mod setsid;
 mod setsockopt;
+mod settimeofday;
 mod setuid;
 mod shutdown;
 mod sigaltstack;
+mod signalfd;
 mod socket;
 mod socketpair;
 mod stat;
 mod statfs;
+mod swapon;
 mod symlink;
 mod sync;
 mod tgkill;
 mod time;
+mod timerfd;
+mod times;
 mod truncate;
 mod umask;
 mod uname;
@@ -260,17 +309,24 @@ define_syscall_nums!(
     SYS_PIPE = 22,
     SYS_SELECT = 23,
     SYS_SCHED_YIELD = 24,
+    SYS_MREMAP = 25,
+    SYS_MSYNC = 26,
+    SYS_MINCORE = 27,
     SYS_MADVISE = 28,
     SYS_DUP = 32,
     SYS_DUP2 = 33,
     SYS_PAUSE = 34,
+    SYS_GETITIMER = 36,
     SYS_ALARM = 37,
+    SYS_SETITIMER = 38,
     SYS_GETPID = 39,
     SYS_SOCKET = 41,
     SYS_CONNECT = 42,
     SYS_ACCEPT = 43,
     SYS_SENDTO = 44,
     SYS_RECVFROM = 45,
+    SYS_SENDMSG = 46,
+    SYS_RECVMSG = 47,
     SYS_SHUTDOWN = 48,
     SYS_BIND = 49,
     SYS_LISTEN = 50,
@@ -307,6 +363,8 @@ define_syscall_nums!(
     SYS_LCHOWN = 94,
     SYS_UMASK = 95,
     SYS_GETTIMEOFDAY = 96,
+    SYS_GETRUSAGE = 98,
+    SYS_TIMES = 100,
     SYS_GETUID = 102,
     SYS_GETGID = 104,
     SYS_SETUID = 105,
@@ -333,23 +391,50 @@ define_syscall_nums!(
     SYS_FSTATFS = 138,
     SYS_GET_PRIORITY = 140,
     SYS_SET_PRIORITY = 141,
+    SYS_SCHED_SETPARAM = 142,
+    SYS_SCHED_GETPARAM = 143,
+    SYS_SCHED_SETSCHEDULER = 144,
+    SYS_SCHED_GETSCHEDULER = 145,
+    SYS_SCHED_GET_PRIORITY_MAX = 146,
+    SYS_SCHED_GET_PRIORITY_MIN = 147,
+    SYS_SCHED_RR_GET_INTERVAL = 148,
+    SYS_MLOCK = 149,
+    SYS_MUNLOCK = 150,
+    SYS_MLOCKALL = 151,
+    SYS_MUNLOCKALL = 152,
     SYS_PRCTL = 157,
     SYS_ARCH_PRCTL = 158,
+    SYS_ADJTIMEX = 159,
     SYS_CHROOT = 161,
     SYS_SYNC = 162,
+    SYS_SETTIMEOFDAY = 164,
+    SYS_SWAPON = 167,
+    SYS_SWAPOFF = 168,
     SYS_GETTID = 186,
     SYS_TIME = 201,
     SYS_FUTEX = 202,
+    SYS_SCHED_SETAFFINITY = 203,
+    SYS_SCHED_GETAFFINITY = 204,
     SYS_EPOLL_CREATE = 213,
     SYS_GETDENTS64 = 217,
     SYS_SET_TID_ADDRESS = 218,
+    SYS_TIMER_CREATE = 222,
+    SYS_TIMER_SETTIME = 223,
+    SYS_TIMER_GETTIME = 224,
+    SYS_TIMER_GETOVERRUN = 225,
+    SYS_TIMER_DELETE = 226,
+    SYS_CLOCK_SETTIME = 227,
     SYS_CLOCK_GETTIME = 228,
+    SYS_CLOCK_GETRES = 229,
     SYS_CLOCK_NANOSLEEP = 230,
     SYS_EXIT_GROUP = 231,
     SYS_EPOLL_WAIT = 232,
     SYS_EPOLL_CTL = 233,
     SYS_TGKILL = 234,
     SYS_WAITID = 247,
+    SYS_INOTIFY_INIT = 253,
+    SYS_INOTIFY_ADD_WATCH = 254,
+    SYS_INOTIFY_RM_WATCH = 255,
     SYS_OPENAT = 257,
     SYS_MKDIRAT = 258,
     SYS_FCHOWNAT = 260,
@@ -362,9 +447,19 @@ define_syscall_nums!(
     SYS_FCHMODAT = 268,
     SYS_SET_ROBUST_LIST = 273,
     SYS_UTIMENSAT = 280,
+    SYS_SIGNALFD = 282,
+    SYS_TIMERFD_CREATE = 283,
+    SYS_EVENTFD = 284,
+    SYS_TIMERFD_SETTIME = 286,
+    SYS_TIMERFD_GETTIME = 287,
+    SYS_SIGNALFD4 = 289,
+    SYS_EVENTFD2 = 290,
     SYS_EPOLL_CREATE1 = 291,
     SYS_PIPE2 = 293,
+    SYS_INOTIFY_INIT1 = 294,
     SYS_PRLIMIT64 = 302,
+    SYS_CLOCK_ADJTIME = 305,
+    SYS_GETCPU = 309,
     SYS_GETRANDOM = 318,
     SYS_EXECVEAT = 322
 );
@@ -393,7 +488,16 @@ define_syscall_nums!(
     SYS_ACCESS = 1003,
     SYS_PIPE = 1004,
     SYS_SELECT = 1005,
-    SYS_SCHED_YIELD = 125,
+    SYS_SCHED_YIELD = 124,
+    SYS_MREMAP = 216,
+    SYS_MSYNC = 227,
+    SYS_MLOCK = 228,
+    SYS_MUNLOCK = 229,
+    SYS_MLOCKALL = 230,
+    SYS_MUNLOCKALL = 231,
+    SYS_SWAPON = 224,
+    SYS_SWAPOFF = 225,
+    SYS_MINCORE = 232,
     SYS_MADVISE = 233,
     SYS_DUP = 23,
     SYS_DUP2 = 24, // dup3
@@ -478,7 +582,7 @@ define_syscall_nums!(
     SYS_EPOLL_CREATE = 1023,
     SYS_GETDENTS64 = 61,
     SYS_SET_TID_ADDRESS = 96,
-    SYS_CLOCK_GETTIME = 113,  // 403?
+    SYS_CLOCK_GETTIME = 113, // 403?
     SYS_CLOCK_NANOSLEEP = 407,
     SYS_EXIT_GROUP = 94,
     SYS_EPOLL_WAIT = 22, // pwait
@@ -501,7 +605,44 @@ define_syscall_nums!(
     SYS_PIPE2 = 59,
     SYS_PRLIMIT64 = 261,
     SYS_GETRANDOM = 278,
-    SYS_EXECVEAT = 281
+    SYS_EXECVEAT = 281,
+    SYS_SENDMSG = 211,
+    SYS_RECVMSG = 212,
+    SYS_EVENTFD = 1025,
+    SYS_EVENTFD2 = 19,
+    SYS_SIGNALFD = 1026,
+    SYS_SIGNALFD4 = 74,
+    SYS_TIMERFD_CREATE = 85,
+    SYS_TIMERFD_SETTIME = 86,
+    SYS_TIMERFD_GETTIME = 87,
+    SYS_INOTIFY_INIT = 1027,
+    SYS_INOTIFY_ADD_WATCH = 27,
+    SYS_INOTIFY_RM_WATCH = 28,
+    SYS_INOTIFY_INIT1 = 26,
+    SYS_SCHED_SETAFFINITY = 122,
+    SYS_SCHED_GETAFFINITY = 123,
+    SYS_GETCPU = 168,
+    SYS_SCHED_SETPARAM = 118,
+    SYS_SCHED_GETPARAM = 121,
+    SYS_SCHED_SETSCHEDULER = 119,
+    SYS_SCHED_GETSCHEDULER = 120,
+    SYS_SCHED_GET_PRIORITY_MAX = 125,
+    SYS_SCHED_GET_PRIORITY_MIN = 126,
+    SYS_SCHED_RR_GET_INTERVAL = 127,
+    SYS_GETITIMER = 102,
+    SYS_SETITIMER = 103,
+    SYS_TIMER_CREATE = 107,
+    SYS_TIMER_SETTIME = 110,
+    SYS_TIMER_GETTIME = 108,
+    SYS_TIMER_GETOVERRUN = 109,
+    SYS_TIMER_DELETE = 111,
+    SYS_GETRUSAGE = 165,
+    SYS_TIMES = 153,
+    SYS_CLOCK_GETRES = 114,
+    SYS_CLOCK_SETTIME = 112,
+    SYS_SETTIMEOFDAY = 170,
+    SYS_ADJTIMEX = 171,
+    SYS_CLOCK_ADJTIME = 266
 );
 
 pub struct SyscallArgument {
@@ -581,17 +722,24 @@ pub fn syscall_dispatch(
         SYS_PIPE => syscall_handler!(1, sys_pipe, args),
         SYS_SELECT => syscall_handler!(5, sys_select, args),
         SYS_SCHED_YIELD => syscall_handler!(0, sys_sched_yield),
+        SYS_MREMAP => syscall_handler!(5, sys_mremap, args),
+        SYS_MSYNC => syscall_handler!(3, sys_msync, args),
+        SYS_MINCORE => syscall_handler!(3, sys_mincore, args),
         SYS_MADVISE => syscall_handler!(3, sys_madvise, args),
         SYS_DUP => syscall_handler!(1, sys_dup, args),
         SYS_DUP2 => syscall_handler!(2, sys_dup2, args),
         SYS_PAUSE => syscall_handler!(0, sys_pause),
+        SYS_GETITIMER => syscall_handler!(2, sys_getitimer, args),
         SYS_ALARM => syscall_handler!(1, sys_alarm, args),
+        SYS_SETITIMER => syscall_handler!(3, sys_setitimer, args),
         SYS_GETPID => syscall_handler!(0, sys_getpid),
         SYS_SOCKET => syscall_handler!(3, sys_socket, args),
         SYS_CONNECT => syscall_handler!(3, sys_connect, args),
         SYS_ACCEPT => syscall_handler!(3, sys_accept, args),
         SYS_SENDTO => syscall_handler!(6, sys_sendto, args),
         SYS_RECVFROM => syscall_handler!(6, sys_recvfrom, args),
+        SYS_SENDMSG => syscall_handler!(3, sys_sendmsg, args),
+        SYS_RECVMSG => syscall_handler!(3, sys_recvmsg, args),
         SYS_SHUTDOWN => syscall_handler!(2, sys_shutdown, args),
         SYS_BIND => syscall_handler!(3, sys_bind, args),
         SYS_LISTEN => syscall_handler!(2, sys_listen, args),
@@ -604,7 +752,7 @@ pub fn syscall_dispatch(
         SYS_FORK => syscall_handler!(0, sys_fork, *context),
         SYS_EXECVE => syscall_handler!(3, sys_execve, args, context),
         SYS_EXIT => syscall_handler!(1, sys_exit, args),
-        SYS_WAIT4 => syscall_handler!(3, sys_wait4, args),
+        SYS_WAIT4 => syscall_handler!(4, sys_wait4, args),
         SYS_KILL => syscall_handler!(2, sys_kill, args),
         SYS_UNAME => syscall_handler!(1, sys_uname, args),
         SYS_FCNTL => syscall_handler!(3, sys_fcntl, args),
@@ -628,6 +776,8 @@ pub fn syscall_dispatch(
         SYS_LCHOWN => syscall_handler!(3, sys_lchown, args),
         SYS_UMASK => syscall_handler!(1, sys_umask, args),
         SYS_GETTIMEOFDAY => syscall_handler!(1, sys_gettimeofday, args),
+        SYS_GETRUSAGE => syscall_handler!(2, sys_getrusage, args),
+        SYS_TIMES => syscall_handler!(1, sys_times, args),
         SYS_GETUID => syscall_handler!(0, sys_getuid),
         SYS_GETGID => syscall_handler!(0, sys_getgid),
         SYS_SETUID => syscall_handler!(1, sys_setuid, args),
@@ -655,23 +805,50 @@ pub fn syscall_dispatch(
         SYS_FSTATFS => syscall_handler!(2, sys_fstatfs, args),
         SYS_GET_PRIORITY => syscall_handler!(2, sys_get_priority, args),
         SYS_SET_PRIORITY => syscall_handler!(3, sys_set_priority, args),
+        SYS_SCHED_SETPARAM => syscall_handler!(2, sys_sched_setparam, args),
+        SYS_SCHED_GETPARAM => syscall_handler!(2, sys_sched_getparam, args),
+        SYS_SCHED_SETSCHEDULER => syscall_handler!(3, sys_sched_setscheduler, args),
+        SYS_SCHED_GETSCHEDULER => syscall_handler!(1, sys_sched_getscheduler, args),
+        SYS_SCHED_GET_PRIORITY_MAX => syscall_handler!(1, sys_sched_get_priority_max, args),
+        SYS_SCHED_GET_PRIORITY_MIN => syscall_handler!(1, sys_sched_get_priority_min, args),
+        SYS_SCHED_RR_GET_INTERVAL => syscall_handler!(2, sys_sched_rr_get_interval, args),
+        SYS_MLOCK => syscall_handler!(2, sys_mlock, args),
+        SYS_MUNLOCK => syscall_handler!(2, sys_munlock, args),
+        SYS_MLOCKALL => syscall_handler!(1, sys_mlockall, args),
+        SYS_MUNLOCKALL => syscall_handler!(0, sys_munlockall),
         SYS_PRCTL => syscall_handler!(5, sys_prctl, args),
         SYS_ARCH_PRCTL => syscall_handler!(2, sys_arch_prctl, args, context),
+        SYS_ADJTIMEX => syscall_handler!(1, sys_adjtimex, args),
         SYS_CHROOT => syscall_handler!(1, sys_chroot, args),
         SYS_SYNC => syscall_handler!(0, sys_sync),
+        SYS_SETTIMEOFDAY => syscall_handler!(1, sys_settimeofday, args),
+        SYS_SWAPON => syscall_handler!(2, sys_swapon, args),
+        SYS_SWAPOFF => syscall_handler!(1, sys_swapoff, args),
         SYS_GETTID => syscall_handler!(0, sys_gettid),
         SYS_TIME => syscall_handler!(1, sys_time, args),
         SYS_FUTEX => syscall_handler!(6, sys_futex, args),
+        SYS_SCHED_SETAFFINITY => syscall_handler!(3, sys_sched_setaffinity, args),
+        SYS_SCHED_GETAFFINITY => syscall_handler!(3, sys_sched_getaffinity, args),
         SYS_EPOLL_CREATE => syscall_handler!(1, sys_epoll_create, args),
         SYS_GETDENTS64 => syscall_handler!(3, sys_getdents64, args),
         SYS_SET_TID_ADDRESS => syscall_handler!(1, sys_set_tid_address, args),
+        SYS_TIMER_CREATE => syscall_handler!(3, sys_timer_create, args),
+        SYS_TIMER_SETTIME => syscall_handler!(4, sys_timer_settime, args),
+        SYS_TIMER_GETTIME => syscall_handler!(2, sys_timer_gettime, args),
+        SYS_TIMER_GETOVERRUN => syscall_handler!(1, sys_timer_getoverrun, args),
+        SYS_TIMER_DELETE => syscall_handler!(1, sys_timer_delete, args),
+        SYS_CLOCK_SETTIME => syscall_handler!(2, sys_clock_settime, args),
         SYS_CLOCK_GETTIME => syscall_handler!(2, sys_clock_gettime, args),
+        SYS_CLOCK_GETRES => syscall_handler!(2, sys_clock_getres, args),
         SYS_CLOCK_NANOSLEEP => syscall_handler!(4, sys_clock_nanosleep, args),
         SYS_EXIT_GROUP => syscall_handler!(1, sys_exit_group, args),
         SYS_EPOLL_WAIT => syscall_handler!(4, sys_epoll_wait, args),
         SYS_EPOLL_CTL => syscall_handler!(4, sys_epoll_ctl, args),
         SYS_TGKILL => syscall_handler!(3, sys_tgkill, args),
         SYS_WAITID => syscall_handler!(5, sys_waitid, args),
+        SYS_INOTIFY_INIT => syscall_handler!(0, sys_inotify_init),
+        SYS_INOTIFY_ADD_WATCH => syscall_handler!(3, sys_inotify_add_watch, args),
+        SYS_INOTIFY_RM_WATCH => syscall_handler!(2, sys_inotify_rm_watch, args),
         SYS_OPENAT => syscall_handler!(4, sys_openat, args),
         SYS_MKDIRAT => syscall_handler!(3, sys_mkdirat, args),
         SYS_FCHOWNAT => syscall_handler!(5, sys_fchownat, args),
@@ -684,9 +861,19 @@ pub fn syscall_dispatch(
         SYS_FCHMODAT => syscall_handler!(3, sys_fchmodat, args),
         SYS_SET_ROBUST_LIST => syscall_handler!(2, sys_set_robust_list, args),
         SYS_UTIMENSAT => syscall_handler!(4, sys_utimensat, args),
+        SYS_SIGNALFD => syscall_handler!(3, sys_signalfd, args),
+        SYS_TIMERFD_CREATE => syscall_handler!(2, sys_timerfd_create, args),
+        SYS_EVENTFD => syscall_handler!(1, sys_eventfd, args),
+        SYS_TIMERFD_SETTIME => syscall_handler!(4, sys_timerfd_settime, args),
+        SYS_TIMERFD_GETTIME => syscall_handler!(2, sys_timerfd_gettime, args),
+        SYS_SIGNALFD4 => syscall_handler!(4, sys_signalfd4, args),
+        SYS_EVENTFD2 => syscall_handler!(2, sys_eventfd2, args),
         SYS_EPOLL_CREATE1 => syscall_handler!(1, sys_epoll_create1, args),
         SYS_PIPE2 => syscall_handler!(2, sys_pipe2, args),
+        SYS_INOTIFY_INIT1 => syscall_handler!(1, sys_inotify_init1, args),
         SYS_PRLIMIT64 => syscall_handler!(4, sys_prlimit64, args),
+        SYS_CLOCK_ADJTIME => syscall_handler!(2, sys_clock_adjtime, args),
+        SYS_GETCPU => syscall_handler!(3, sys_getcpu, args),
         SYS_GETRANDOM => syscall_handler!(3, sys_getrandom, args),
         SYS_EXECVEAT => syscall_handler!(5, sys_execveat, args, context),
         _ => {
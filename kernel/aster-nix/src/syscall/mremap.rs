@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_MREMAP};
+use crate::{log_syscall_entry, prelude::*};
+
+pub fn sys_mremap(
+    old_addr: Vaddr,
+    old_size: usize,
+    new_size: usize,
+    flags: u32,
+    new_addr: Vaddr,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MREMAP);
+    let flags = MremapFlags::from_bits(flags)
+        .ok_or(Error::with_message(Errno::EINVAL, "unknown mremap flags"))?;
+    debug!(
+        "old_addr = 0x{:x}, old_size = 0x{:x}, new_size = 0x{:x}, flags = {:?}, new_addr = 0x{:x}",
+        old_addr, old_size, new_size, flags, new_addr
+    );
+
+    if old_addr % PAGE_SIZE != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the old address is not page-aligned");
+    }
+    if new_size == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the new size cannot be zero");
+    }
+    // TODO: Support duplicating a shared mapping, which is requested by a zero old size.
+    if old_size == 0 {
+        return_errno_with_message!(Errno::EINVAL, "duplicating a mapping is not supported");
+    }
+    let (Some(old_size), Some(new_size)) = (
+        old_size.checked_next_multiple_of(PAGE_SIZE),
+        new_size.checked_next_multiple_of(PAGE_SIZE),
+    ) else {
+        return_errno_with_message!(Errno::EINVAL, "the size is too large");
+    };
+    let old_end = old_addr.checked_add(old_size).ok_or(Error::with_message(
+        Errno::EINVAL,
+        "the old range overflows",
+    ))?;
+
+    let new_addr = if flags.contains(MremapFlags::MREMAP_FIXED) {
+        if !flags.contains(MremapFlags::MREMAP_MAYMOVE) {
+            return_errno_with_message!(Errno::EINVAL, "MREMAP_FIXED requires MREMAP_MAYMOVE");
+        }
+        if new_addr % PAGE_SIZE != 0 {
+            return_errno_with_message!(Errno::EINVAL, "the new address is not page-aligned");
+        }
+        if new_addr.checked_add(new_size).is_none() {
+            return_errno_with_message!(Errno::EINVAL, "the new range overflows");
+        }
+        Some(new_addr)
+    } else {
+        None
+    };
+
+    let current = current!();
+    let root_vmar = current.root_vmar();
+    let addr = root_vmar.remap(
+        old_addr..old_end,
+        new_size,
+        new_addr,
+        flags.contains(MremapFlags::MREMAP_MAYMOVE),
+    )?;
+    debug!("remapped to 0x{:x} - 0x{:x}", addr, addr + new_size);
+    Ok(SyscallReturn::Return(addr as _))
+}
+
+bitflags! {
+    struct MremapFlags: u32 {
+        const MREMAP_MAYMOVE = 1 << 0;
+        const MREMAP_FIXED   = 1 << 1;
+    }
+}
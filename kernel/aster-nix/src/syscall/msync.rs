@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_MSYNC};
+use crate::{log_syscall_entry, prelude::*};
+
+pub fn sys_msync(addr: Vaddr, len: usize, flags: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_MSYNC);
+    let flags =
+        MsyncFlags::from_bits(flags).ok_or(Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    debug!(
+        "addr = 0x{:x}, len = 0x{:x}, flags = {:?}",
+        addr, len, flags
+    );
+
+    if addr % PAGE_SIZE != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the address is not page-aligned");
+    }
+    if flags.contains(MsyncFlags::MS_ASYNC | MsyncFlags::MS_SYNC) {
+        return_errno_with_message!(Errno::EINVAL, "MS_ASYNC and MS_SYNC are exclusive");
+    }
+    let end = addr
+        .checked_add(len)
+        .and_then(|end| end.checked_next_multiple_of(PAGE_SIZE))
+        .ok_or(Error::with_message(Errno::ENOMEM, "the range overflows"))?;
+
+    // The shared mappings of a file share the pages in the page cache, so the updates are
+    // always visible to the other mappings, and there is nothing to do for `MS_INVALIDATE`.
+    let current = current!();
+    let root_vmar = current.root_vmar();
+    root_vmar.sync(addr..end, flags.contains(MsyncFlags::MS_SYNC))?;
+    Ok(SyscallReturn::Return(0))
+}
+
+bitflags! {
+    struct MsyncFlags: u32 {
+        const MS_ASYNC      = 1 << 0;
+        const MS_INVALIDATE = 1 << 1;
+        const MS_SYNC       = 1 << 2;
+    }
+}
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{
+    SyscallReturn, SYS_TIMER_CREATE, SYS_TIMER_DELETE, SYS_TIMER_GETOVERRUN, SYS_TIMER_GETTIME,
+    SYS_TIMER_SETTIME,
+};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        signal::{
+            c_types::{sigevent_t, sigval_t},
+            constants::SIGALRM,
+            sig_num::SigNum,
+        },
+        timer::{TimerClock, TimerId, TimerNotify},
+        Process,
+    },
+    thread::{thread_table, Tid},
+    time::{clockid_t, itimerspec_t, timespec_t, ClockID, TIMER_ABSTIME},
+    util::{read_val_from_user, write_val_to_user},
+};
+
+pub fn sys_timer_create(
+    clockid: clockid_t,
+    sigevent_addr: Vaddr,
+    timer_id_addr: Vaddr,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMER_CREATE);
+    debug!(
+        "clockid = {}, sigevent_addr = 0x{:x}, timer_id_addr = 0x{:x}",
+        clockid, sigevent_addr, timer_id_addr
+    );
+
+    let clock_id = ClockID::try_from(clockid)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "invalid clock ID"))?;
+    let current = current!();
+    let clock = match clock_id {
+        ClockID::CLOCK_REALTIME | ClockID::CLOCK_MONOTONIC | ClockID::CLOCK_BOOTTIME => {
+            TimerClock::System(clock_id)
+        }
+        ClockID::CLOCK_PROCESS_CPUTIME_ID => TimerClock::CpuTotal(current.cpu_clock().clone()),
+        ClockID::CLOCK_THREAD_CPUTIME_ID => {
+            let current_thread = current_thread!();
+            let posix_thread = current_thread.as_posix_thread().unwrap();
+            TimerClock::CpuTotal(posix_thread.cpu_clock().clone())
+        }
+        _ => return_errno_with_message!(Errno::EOPNOTSUPP, "the clock is not supported by timers"),
+    };
+
+    let notify = if sigevent_addr == 0 {
+        None
+    } else {
+        let sigevent = read_val_from_user::<sigevent_t>(sigevent_addr)?;
+        Some(parse_sigevent(&sigevent, &current)?)
+    };
+
+    let timer_id = current.timers().create_posix_timer(clock, |timer_id| {
+        // Without a `sigevent`, `SIGALRM` is sent with the timer ID as the value.
+        notify.unwrap_or(TimerNotify::Signal {
+            num: SIGALRM,
+            value: sigval_t::from_int(timer_id),
+        })
+    })?;
+
+    if let Err(err) = write_val_to_user(timer_id_addr, &timer_id) {
+        let _ = current.timers().delete_posix_timer(timer_id);
+        return Err(err);
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_timer_settime(
+    timer_id: TimerId,
+    flags: i32,
+    new_value_addr: Vaddr,
+    old_value_addr: Vaddr,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMER_SETTIME);
+    debug!(
+        "timer_id = {}, flags = 0x{:x}, new_value_addr = 0x{:x}, old_value_addr = 0x{:x}",
+        timer_id, flags, new_value_addr, old_value_addr
+    );
+
+    if flags & !TIMER_ABSTIME != 0 {
+        return_errno_with_message!(Errno::EINVAL, "invalid flags");
+    }
+    let new_value = read_val_from_user::<itimerspec_t>(new_value_addr)?;
+    if !new_value.it_value.is_valid() || !new_value.it_interval.is_valid() {
+        return_errno_with_message!(Errno::EINVAL, "invalid time value");
+    }
+
+    let (old_value, old_interval) = current!().timers().set_posix_timer(
+        timer_id,
+        Duration::from(new_value.it_value),
+        Duration::from(new_value.it_interval),
+        flags & TIMER_ABSTIME != 0,
+    )?;
+
+    if old_value_addr != 0 {
+        write_val_to_user(old_value_addr, &to_itimerspec(old_value, old_interval))?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_timer_gettime(timer_id: TimerId, curr_value_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMER_GETTIME);
+    debug!(
+        "timer_id = {}, curr_value_addr = 0x{:x}",
+        timer_id, curr_value_addr
+    );
+
+    let posix_timer = current!().timers().posix_timer(timer_id)?;
+    let (value, interval) = posix_timer.timer().get()?;
+    write_val_to_user(curr_value_addr, &to_itimerspec(value, interval))?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_timer_getoverrun(timer_id: TimerId) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMER_GETOVERRUN);
+    debug!("timer_id = {}", timer_id);
+
+    let posix_timer = current!().timers().posix_timer(timer_id)?;
+    Ok(SyscallReturn::Return(posix_timer.overrun() as _))
+}
+
+pub fn sys_timer_delete(timer_id: TimerId) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMER_DELETE);
+    debug!("timer_id = {}", timer_id);
+
+    current!().timers().delete_posix_timer(timer_id)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+const SIGEV_SIGNAL: i32 = 0;
+const SIGEV_NONE: i32 = 1;
+const SIGEV_THREAD: i32 = 2;
+const SIGEV_THREAD_ID: i32 = 4;
+
+fn parse_sigevent(sigevent: &sigevent_t, current: &Arc<Process>) -> Result<TimerNotify> {
+    let parse_signum = || {
+        u8::try_from(sigevent.sigev_signo)
+            .ok()
+            .and_then(|signum| SigNum::try_from(signum).ok())
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid signal number"))
+    };
+
+    match sigevent.sigev_notify {
+        SIGEV_NONE => Ok(TimerNotify::None),
+        SIGEV_SIGNAL => Ok(TimerNotify::Signal {
+            num: parse_signum()?,
+            value: sigevent.sigev_value,
+        }),
+        SIGEV_THREAD_ID => {
+            // The thread must be in the same process as the caller.
+            let thread = thread_table::get_thread(sigevent.sigev_tid as Tid)
+                .filter(|thread| {
+                    thread
+                        .as_posix_thread()
+                        .is_some_and(|posix_thread| Arc::ptr_eq(&posix_thread.process(), current))
+                })
+                .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid thread ID"))?;
+            Ok(TimerNotify::ThreadSignal {
+                num: parse_signum()?,
+                value: sigevent.sigev_value,
+                thread: Arc::downgrade(&thread),
+            })
+        }
+        // `SIGEV_THREAD` is implemented by the C library on top of `SIGEV_THREAD_ID`.
+        SIGEV_THREAD => return_errno_with_message!(Errno::EINVAL, "SIGEV_THREAD is not supported"),
+        _ => return_errno_with_message!(Errno::EINVAL, "invalid notification type"),
+    }
+}
+
+fn to_itimerspec(value: Duration, interval: Duration) -> itimerspec_t {
+    itimerspec_t {
+        it_interval: timespec_t::from(interval),
+        it_value: timespec_t::from(value),
+    }
+}
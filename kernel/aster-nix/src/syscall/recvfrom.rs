@@ -30,7 +30,9 @@ pub fn sys_recvfrom(
 
     let (recv_size, socket_addr) = socket.recvfrom(&mut buffer, flags)?;
     if buf != 0 {
-        write_bytes_to_user(buf, &buffer[..recv_size])?;
+        // The received size can exceed the buffer length if `MSG_TRUNC` is specified.
+        let copy_size = recv_size.min(buffer.len());
+        write_bytes_to_user(buf, &buffer[..copy_size])?;
     }
     if src_addr != 0 {
         write_socket_addr_to_user(&socket_addr, src_addr, addrlen_ptr)?;
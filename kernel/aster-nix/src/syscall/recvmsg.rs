@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_RECVMSG};
+use crate::{
+    fs::file_table::FileDescripter,
+    log_syscall_entry,
+    net::socket::{SendRecvFlags, MAX_RECV_LEN},
+    prelude::*,
+    util::{
+        iovec::{scatter_to_iovs, total_len},
+        net::{close_received_fds, get_socket_from_fd, CUserMsgHdr},
+        read_val_from_user, write_val_to_user,
+    },
+};
+
+pub fn sys_recvmsg(
+    sockfd: FileDescripter,
+    user_msghdr_ptr: Vaddr,
+    flags: i32,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_RECVMSG);
+    let mut c_user_msghdr: CUserMsgHdr = read_val_from_user(user_msghdr_ptr)?;
+    let flags = SendRecvFlags::from_bits_truncate(flags);
+    debug!("sockfd = {sockfd}, user_msghdr = {c_user_msghdr:x?}, flags = {flags:?}");
+
+    let socket = get_socket_from_fd(sockfd)?;
+
+    let io_vecs = c_user_msghdr.copy_iovs_from_user()?;
+    let mut buffer = vec![0u8; total_len(&io_vecs)?.min(MAX_RECV_LEN)];
+
+    let (recv_size, message_header) = socket.recvmsg(&mut buffer, flags)?;
+    // The received size can exceed the buffer length if `MSG_TRUNC` is specified.
+    let copy_size = recv_size.min(buffer.len());
+    scatter_to_iovs(&io_vecs, &buffer[..copy_size])?;
+
+    // `MSG_CTRUNC` is added when the control messages are written if they are truncated.
+    c_user_msghdr.set_msg_flags(message_header.msg_flags());
+    let (addr, control_messages) = message_header.into_parts();
+    c_user_msghdr.write_socket_addr_to_user(addr.as_ref())?;
+    let received_fds = c_user_msghdr.write_control_messages_to_user(control_messages, flags)?;
+    if let Err(err) = write_val_to_user(user_msghdr_ptr, &c_user_msghdr) {
+        // The user never learns about the received fds, so they must not be leaked.
+        close_received_fds(&received_fds);
+        return Err(err);
+    }
+
+    Ok(SyscallReturn::Return(recv_size as _))
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_frame::cpu::{num_cpus, CpuSet, MAX_CPUS};
+
+use super::{SyscallReturn, SYS_SCHED_GETAFFINITY, SYS_SCHED_SETAFFINITY};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::{credentials, posix_thread::PosixThreadExt},
+    thread::{thread_table, Thread, Tid},
+    util::{read_bytes_from_user, write_bytes_to_user},
+};
+
+/// The size in bytes of the CPU masks used by the kernel.
+const CPU_MASK_SIZE: usize = MAX_CPUS / 8;
+
+pub fn sys_sched_setaffinity(tid: Tid, len: usize, mask_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_SETAFFINITY);
+    debug!(
+        "tid = {}, len = {}, mask_addr = 0x{:x}",
+        tid, len, mask_addr
+    );
+
+    // The CPUs beyond the user-provided mask are regarded as not set.
+    let mut mask = [0u8; CPU_MASK_SIZE];
+    let read_len = len.min(CPU_MASK_SIZE);
+    read_bytes_from_user(mask_addr, &mut mask[..read_len])?;
+    let mask = u64::from_le_bytes(mask);
+
+    let mut cpu_affinity = CpuSet::new_empty();
+    for cpu_id in 0..num_cpus() {
+        if mask & (1 << cpu_id) != 0 {
+            cpu_affinity.add(cpu_id);
+        }
+    }
+    if cpu_affinity.iter().next().is_none() {
+        return_errno_with_message!(Errno::EINVAL, "no online CPUs are allowed");
+    }
+
+    let thread = get_thread(tid)?;
+    check_affinity_perm(&thread)?;
+    thread.task().set_cpu_affinity(cpu_affinity);
+    // The current thread moves to an allowed CPU once it is scheduled.
+    if Arc::ptr_eq(&thread, &current_thread!()) {
+        Thread::yield_now();
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getaffinity(tid: Tid, len: usize, mask_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_GETAFFINITY);
+    debug!(
+        "tid = {}, len = {}, mask_addr = 0x{:x}",
+        tid, len, mask_addr
+    );
+
+    let Some(nr_bits) = len.checked_mul(8) else {
+        return_errno_with_message!(Errno::EINVAL, "the mask size is too large");
+    };
+    if nr_bits < num_cpus() as usize {
+        return_errno_with_message!(Errno::EINVAL, "the mask cannot hold all CPUs");
+    }
+    if len % core::mem::size_of::<u64>() != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the mask size is not aligned");
+    }
+
+    let thread = get_thread(tid)?;
+    let mask = thread
+        .task()
+        .cpu_affinity()
+        .iter()
+        .fold(0u64, |mask, cpu_id| mask | (1 << cpu_id));
+
+    // The size of the kernel mask is returned, so the rest of the user mask is left untouched.
+    let write_len = len.min(CPU_MASK_SIZE);
+    write_bytes_to_user(mask_addr, &mask.to_le_bytes()[..write_len])?;
+    Ok(SyscallReturn::Return(write_len as _))
+}
+
+/// Checks whether the current thread can change the CPU affinity of the target thread.
+///
+/// An unprivileged thread can only change the CPU affinities of the threads whose real or
+/// effective user ID equals its effective user ID.
+fn check_affinity_perm(thread: &Thread) -> Result<()> {
+    let euid = credentials().euid();
+    if euid.is_root() {
+        return Ok(());
+    }
+
+    let Some(posix_thread) = thread.as_posix_thread() else {
+        return_errno_with_message!(Errno::EPERM, "the target is a kernel thread");
+    };
+    let target_credentials = posix_thread.credentials();
+    if euid != target_credentials.ruid() && euid != target_credentials.euid() {
+        return_errno_with_message!(Errno::EPERM, "the target thread belongs to another user");
+    }
+    Ok(())
+}
+
+/// Returns the thread of the given TID, where zero means the current thread.
+pub(super) fn get_thread(tid: Tid) -> Result<Arc<Thread>> {
+    if tid == 0 {
+        return Ok(current_thread!());
+    }
+    thread_table::get_thread(tid).ok_or_else(|| Error::with_message(Errno::ESRCH, "no such thread"))
+}
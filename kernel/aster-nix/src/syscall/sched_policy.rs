@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use aster_frame::task::{Priority, SchedPolicy, Task};
+
+use super::{
+    sched_affinity::get_thread, SyscallReturn, SYS_SCHED_GETPARAM, SYS_SCHED_GETSCHEDULER,
+    SYS_SCHED_GET_PRIORITY_MAX, SYS_SCHED_GET_PRIORITY_MIN, SYS_SCHED_RR_GET_INTERVAL,
+    SYS_SCHED_SETPARAM, SYS_SCHED_SETSCHEDULER,
+};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    process::{
+        credentials,
+        posix_thread::{futex::update_pi_priority, PosixThreadExt},
+        ResourceType,
+    },
+    sched::RR_TIME_SLICE_MS,
+    thread::{Thread, Tid},
+    time::timespec_t,
+    util::{read_val_from_user, write_val_to_user},
+};
+
+/// The flag in the policy that resets the children to the normal policy.
+const SCHED_RESET_ON_FORK: i32 = 0x4000_0000;
+
+/// The lowest priority of the real-time policies.
+const MIN_RT_PRIORITY: i32 = 1;
+/// The highest priority of the real-time policies.
+const MAX_RT_PRIORITY: i32 = 99;
+
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+struct SchedParam {
+    sched_priority: i32,
+}
+
+pub fn sys_sched_setscheduler(tid: i32, policy: i32, param_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_SETSCHEDULER);
+    debug!(
+        "tid = {}, policy = {}, param_addr = 0x{:x}",
+        tid, policy, param_addr
+    );
+
+    let reset_on_fork = policy & SCHED_RESET_ON_FORK != 0;
+    let policy = policy_from_raw(policy & !SCHED_RESET_ON_FORK)?;
+    let param = read_sched_param(param_addr)?;
+    let thread = get_target_thread(tid)?;
+    set_scheduler(&thread, policy, param.sched_priority, reset_on_fork)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getscheduler(tid: i32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_GETSCHEDULER);
+    debug!("tid = {}", tid);
+
+    let thread = get_target_thread(tid)?;
+    let mut policy = thread.task().sched_policy() as i32;
+    if thread.sched_reset_on_fork() {
+        policy |= SCHED_RESET_ON_FORK;
+    }
+    Ok(SyscallReturn::Return(policy as _))
+}
+
+pub fn sys_sched_setparam(tid: i32, param_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_SETPARAM);
+    debug!("tid = {}, param_addr = 0x{:x}", tid, param_addr);
+
+    let param = read_sched_param(param_addr)?;
+    let thread = get_target_thread(tid)?;
+    let policy = thread.task().sched_policy();
+    set_scheduler(
+        &thread,
+        policy,
+        param.sched_priority,
+        thread.sched_reset_on_fork(),
+    )?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getparam(tid: i32, param_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_GETPARAM);
+    debug!("tid = {}, param_addr = 0x{:x}", tid, param_addr);
+
+    if param_addr == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the parameter address is null");
+    }
+    let thread = get_target_thread(tid)?;
+    let param = SchedParam {
+        sched_priority: rt_priority_of(thread.task()),
+    };
+    write_val_to_user(param_addr, &param)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_get_priority_max(policy: i32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_GET_PRIORITY_MAX);
+    debug!("policy = {}", policy);
+
+    let max_priority = if policy_from_raw(policy)?.is_real_time() {
+        MAX_RT_PRIORITY
+    } else {
+        0
+    };
+    Ok(SyscallReturn::Return(max_priority as _))
+}
+
+pub fn sys_sched_get_priority_min(policy: i32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_GET_PRIORITY_MIN);
+    debug!("policy = {}", policy);
+
+    let min_priority = if policy_from_raw(policy)?.is_real_time() {
+        MIN_RT_PRIORITY
+    } else {
+        0
+    };
+    Ok(SyscallReturn::Return(min_priority as _))
+}
+
+pub fn sys_sched_rr_get_interval(tid: i32, interval_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SCHED_RR_GET_INTERVAL);
+    debug!("tid = {}, interval_addr = 0x{:x}", tid, interval_addr);
+
+    let thread = get_target_thread(tid)?;
+    // FIXME: Linux reports the time slices of the tasks with the normal policies, which vary
+    // with the load of the CPUs. Here zero is reported for them like the FIFO tasks.
+    let interval = if thread.task().sched_policy() == SchedPolicy::RoundRobin {
+        Duration::from_millis(RR_TIME_SLICE_MS)
+    } else {
+        Duration::ZERO
+    };
+    write_val_to_user(interval_addr, &timespec_t::from(interval))?;
+    Ok(SyscallReturn::Return(0))
+}
+
+fn policy_from_raw(policy: i32) -> Result<SchedPolicy> {
+    let policy = match policy {
+        0 => SchedPolicy::Normal,
+        1 => SchedPolicy::Fifo,
+        2 => SchedPolicy::RoundRobin,
+        3 => SchedPolicy::Batch,
+        5 => SchedPolicy::Idle,
+        _ => return_errno_with_message!(Errno::EINVAL, "the policy is invalid"),
+    };
+    Ok(policy)
+}
+
+fn read_sched_param(param_addr: Vaddr) -> Result<SchedParam> {
+    if param_addr == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the parameter address is null");
+    }
+    read_val_from_user(param_addr)
+}
+
+fn get_target_thread(tid: i32) -> Result<Arc<Thread>> {
+    if tid < 0 {
+        return_errno_with_message!(Errno::EINVAL, "the thread ID is negative");
+    }
+    get_thread(tid as Tid)
+}
+
+/// Returns the real-time priority of the task, where a larger value represents a higher
+/// priority, or zero if the task has a normal policy.
+fn rt_priority_of(task: &Task) -> i32 {
+    if task.sched_policy().is_real_time() {
+        MAX_RT_PRIORITY - task.base_priority().get() as i32
+    } else {
+        0
+    }
+}
+
+fn set_scheduler(
+    thread: &Arc<Thread>,
+    policy: SchedPolicy,
+    rt_priority: i32,
+    reset_on_fork: bool,
+) -> Result<()> {
+    let is_valid_priority = if policy.is_real_time() {
+        (MIN_RT_PRIORITY..=MAX_RT_PRIORITY).contains(&rt_priority)
+    } else {
+        rt_priority == 0
+    };
+    if !is_valid_priority {
+        return_errno_with_message!(Errno::EINVAL, "the priority is invalid for the policy");
+    }
+    check_sched_perm(thread, policy, rt_priority, reset_on_fork)?;
+
+    let priority = if policy.is_real_time() {
+        Priority::new((MAX_RT_PRIORITY - rt_priority) as u16)
+    } else {
+        Priority::normal()
+    };
+    thread.task().set_sched_policy(policy, priority);
+    thread.set_sched_reset_on_fork(reset_on_fork);
+    // The thread may still inherit a higher priority from the PI futexes that it owns.
+    update_pi_priority(thread);
+    Ok(())
+}
+
+/// Checks whether the current thread can change the scheduling policy of the target thread.
+///
+/// An unprivileged thread can only change the policies of the threads whose real or
+/// effective user ID equals its effective user ID. In addition, it cannot raise the real-time
+/// priority beyond the `RLIMIT_RTPRIO` limit of the target, or clear `SCHED_RESET_ON_FORK`.
+fn check_sched_perm(
+    thread: &Thread,
+    policy: SchedPolicy,
+    rt_priority: i32,
+    reset_on_fork: bool,
+) -> Result<()> {
+    let euid = credentials().euid();
+    if euid.is_root() {
+        return Ok(());
+    }
+
+    let Some(posix_thread) = thread.as_posix_thread() else {
+        return_errno_with_message!(Errno::EPERM, "the target is a kernel thread");
+    };
+    let target_credentials = posix_thread.credentials();
+    if euid != target_credentials.ruid() && euid != target_credentials.euid() {
+        return_errno_with_message!(Errno::EPERM, "the target thread belongs to another user");
+    }
+
+    let task = thread.task();
+    if policy.is_real_time() {
+        let max_rt_priority = posix_thread
+            .process()
+            .resource_limits()
+            .lock()
+            .get_rlimit(ResourceType::RLIMIT_RTPRIO)
+            .get_cur();
+        if policy != task.sched_policy() && max_rt_priority == 0 {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "real-time policies are not allowed by RLIMIT_RTPRIO"
+            );
+        }
+        if rt_priority > rt_priority_of(task) && rt_priority as u64 > max_rt_priority {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "the priority exceeds the limit of RLIMIT_RTPRIO"
+            );
+        }
+    }
+
+    if thread.sched_reset_on_fork() && !reset_on_fork {
+        return_errno_with_message!(Errno::EPERM, "SCHED_RESET_ON_FORK cannot be cleared");
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_SENDMSG};
+use crate::{
+    fs::file_table::FileDescripter,
+    log_syscall_entry,
+    net::socket::{MessageHeader, SendRecvFlags},
+    prelude::*,
+    util::{
+        iovec::gather_from_iovs,
+        net::{get_socket_from_fd, CUserMsgHdr},
+        read_val_from_user,
+    },
+};
+
+pub fn sys_sendmsg(
+    sockfd: FileDescripter,
+    user_msghdr_ptr: Vaddr,
+    flags: i32,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SENDMSG);
+    let c_user_msghdr: CUserMsgHdr = read_val_from_user(user_msghdr_ptr)?;
+    let flags = SendRecvFlags::from_bits_truncate(flags);
+    debug!("sockfd = {sockfd}, user_msghdr = {c_user_msghdr:x?}, flags = {flags:?}");
+
+    let socket = get_socket_from_fd(sockfd)?;
+
+    let buffer = {
+        let io_vecs = c_user_msghdr.copy_iovs_from_user()?;
+        gather_from_iovs(&io_vecs)?
+    };
+
+    let message_header = {
+        let addr = c_user_msghdr.read_socket_addr_from_user()?;
+        let control_messages = c_user_msghdr.read_control_messages_from_user()?;
+        MessageHeader::new(addr, control_messages)
+    };
+
+    let send_size = socket.sendmsg(&buffer, message_header, flags)?;
+
+    Ok(SyscallReturn::Return(send_size as _))
+}
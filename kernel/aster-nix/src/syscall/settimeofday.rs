@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{SyscallReturn, SYS_SETTIMEOFDAY};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    time::{set_realtime, timeval_t},
+    util::read_val_from_user,
+};
+
+// The use of the timezone structure is obsolete, so it is ignored as in `gettimeofday`.
+pub fn sys_settimeofday(timeval_addr: Vaddr, /* timezone_addr: Vaddr */) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SETTIMEOFDAY);
+    if timeval_addr == 0 {
+        return Ok(SyscallReturn::Return(0));
+    }
+
+    let timeval = read_val_from_user::<timeval_t>(timeval_addr)?;
+    debug!("timeval = {:?}", timeval);
+    if !timeval.is_valid() {
+        return_errno_with_message!(Errno::EINVAL, "invalid time value");
+    }
+
+    set_realtime(Duration::from(timeval))?;
+    Ok(SyscallReturn::Return(0))
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{SyscallReturn, SYS_SIGNALFD, SYS_SIGNALFD4};
+use crate::{
+    fs::{
+        file_table::{FdFlags, FileDescripter},
+        signalfd::{SignalFile, SignalFileFlags},
+    },
+    log_syscall_entry,
+    prelude::*,
+    process::signal::{c_types::sigset_t, sig_mask::SigMask},
+    util::read_val_from_user,
+};
+
+pub fn sys_signalfd(
+    fd: FileDescripter,
+    mask_addr: Vaddr,
+    sizemask: usize,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SIGNALFD);
+    do_sys_signalfd4(fd, mask_addr, sizemask, 0)
+}
+
+pub fn sys_signalfd4(
+    fd: FileDescripter,
+    mask_addr: Vaddr,
+    sizemask: usize,
+    flags: u32,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SIGNALFD4);
+    do_sys_signalfd4(fd, mask_addr, sizemask, flags)
+}
+
+fn do_sys_signalfd4(
+    fd: FileDescripter,
+    mask_addr: Vaddr,
+    sizemask: usize,
+    flags: u32,
+) -> Result<SyscallReturn> {
+    debug!(
+        "fd = {}, mask_addr = 0x{:x}, sizemask = {}, flags = 0x{:x}",
+        fd, mask_addr, sizemask, flags
+    );
+
+    if sizemask != core::mem::size_of::<sigset_t>() {
+        return_errno_with_message!(Errno::EINVAL, "invalid size of the signal mask");
+    }
+    let flags = SignalFileFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    let mask = SigMask::from(read_val_from_user::<sigset_t>(mask_addr)?);
+
+    let current = current!();
+    let mut file_table = current.file_table().lock();
+
+    // An existing signal file is updated with the new mask. In that case, the flags are ignored.
+    if fd >= 0 {
+        let file = file_table.get_file(fd)?;
+        let Some(signal_file) = file.downcast_ref::<SignalFile>() else {
+            return_errno_with_message!(Errno::EINVAL, "the file is not a signal file");
+        };
+        signal_file.set_mask(mask);
+        return Ok(SyscallReturn::Return(fd as _));
+    }
+    if fd != -1 {
+        return_errno_with_message!(Errno::EBADF, "invalid file descriptor");
+    }
+
+    let fd_flags = if flags.contains(SignalFileFlags::SFD_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+    let signal_file = SignalFile::new(mask, flags);
+    let fd = file_table.insert(signal_file, fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
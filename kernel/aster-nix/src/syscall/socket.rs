@@ -4,9 +4,12 @@ use super::{SyscallReturn, SYS_SOCKET};
 use crate::{
     fs::{file_handle::FileLike, file_table::FdFlags},
     log_syscall_entry,
-    net::socket::{
-        ip::{DatagramSocket, StreamSocket},
-        unix::UnixStreamSocket,
+    net::{
+        iface::{IpProtocol, IpVersion},
+        socket::{
+            ip::{DatagramSocket, PingSocket, RawSocket, StreamSocket},
+            unix::{UnixDatagramSocket, UnixStreamSocket},
+        },
     },
     prelude::*,
     util::net::{CSocketAddrFamily, Protocol, SockFlags, SockType, SOCK_TYPE_MASK},
@@ -27,16 +30,47 @@ pub fn sys_socket(domain: i32, type_: i32, protocol: i32) -> Result<SyscallRetur
         (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM, _) => Arc::new(UnixStreamSocket::new(
             sock_flags.contains(SockFlags::SOCK_NONBLOCK),
         )) as Arc<dyn FileLike>,
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_SEQPACKET, _) => {
+            Arc::new(UnixStreamSocket::new_seqpacket(nonblocking)) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_DGRAM, _) => {
+            Arc::new(UnixDatagramSocket::new(nonblocking)) as Arc<dyn FileLike>
+        }
         (
             CSocketAddrFamily::AF_INET,
             SockType::SOCK_STREAM,
             Protocol::IPPROTO_IP | Protocol::IPPROTO_TCP,
-        ) => StreamSocket::new(nonblocking) as Arc<dyn FileLike>,
+        ) => StreamSocket::new(IpVersion::Ipv4, nonblocking) as Arc<dyn FileLike>,
         (
             CSocketAddrFamily::AF_INET,
             SockType::SOCK_DGRAM,
             Protocol::IPPROTO_IP | Protocol::IPPROTO_UDP,
-        ) => DatagramSocket::new(nonblocking) as Arc<dyn FileLike>,
+        ) => DatagramSocket::new(IpVersion::Ipv4, nonblocking) as Arc<dyn FileLike>,
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_DGRAM, Protocol::IPPROTO_ICMP) => {
+            PingSocket::new(nonblocking) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_RAW, Protocol::IPPROTO_IP) => {
+            return_errno_with_message!(
+                Errno::EPROTONOSUPPORT,
+                "a protocol must be specified for raw sockets"
+            )
+        }
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_RAW, _) => {
+            let Ok(ip_protocol) = u8::try_from(protocol as i32) else {
+                return_errno_with_message!(Errno::EINVAL, "the protocol is not an IP protocol");
+            };
+            RawSocket::new(IpProtocol::from(ip_protocol), nonblocking)? as Arc<dyn FileLike>
+        }
+        (
+            CSocketAddrFamily::AF_INET6,
+            SockType::SOCK_STREAM,
+            Protocol::IPPROTO_IP | Protocol::IPPROTO_TCP,
+        ) => StreamSocket::new(IpVersion::Ipv6, nonblocking) as Arc<dyn FileLike>,
+        (
+            CSocketAddrFamily::AF_INET6,
+            SockType::SOCK_DGRAM,
+            Protocol::IPPROTO_IP | Protocol::IPPROTO_UDP,
+        ) => DatagramSocket::new(IpVersion::Ipv6, nonblocking) as Arc<dyn FileLike>,
         _ => return_errno_with_message!(Errno::EAFNOSUPPORT, "unsupported domain"),
     };
     let fd = {
@@ -2,9 +2,12 @@
 
 use super::{SyscallReturn, SYS_SOCKETPAIR};
 use crate::{
-    fs::file_table::{FdFlags, FileDescripter},
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDescripter},
+    },
     log_syscall_entry,
-    net::socket::unix::UnixStreamSocket,
+    net::socket::unix::{UnixDatagramSocket, UnixStreamSocket},
     prelude::*,
     util::{
         net::{CSocketAddrFamily, Protocol, SockFlags, SockType, SOCK_TYPE_MASK},
@@ -25,9 +28,18 @@ pub fn sys_socketpair(domain: i32, type_: i32, protocol: i32, sv: Vaddr) -> Resu
     );
     // TODO: deal with all sock_flags and protocol
     let nonblocking = sock_flags.contains(SockFlags::SOCK_NONBLOCK);
-    let (socket_a, socket_b) = match (domain, sock_type) {
+    let (socket_a, socket_b): (Arc<dyn FileLike>, Arc<dyn FileLike>) = match (domain, sock_type) {
         (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM) => {
-            UnixStreamSocket::new_pair(nonblocking)?
+            let (socket_a, socket_b) = UnixStreamSocket::new_pair(nonblocking)?;
+            (socket_a, socket_b)
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_SEQPACKET) => {
+            let (socket_a, socket_b) = UnixStreamSocket::new_seqpacket_pair(nonblocking)?;
+            (socket_a, socket_b)
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_DGRAM) => {
+            let (socket_a, socket_b) = UnixDatagramSocket::new_pair(nonblocking)?;
+            (socket_a, socket_b)
         }
         _ => return_errno_with_message!(
             Errno::EAFNOSUPPORT,
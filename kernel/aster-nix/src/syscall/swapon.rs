@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_block::BlockDevice;
+
+use super::{SyscallReturn, SYS_SWAPOFF, SYS_SWAPON};
+use crate::{
+    device::block::get_block_device,
+    fs::{device::DeviceId, fs_resolver::FsPath, utils::InodeType},
+    log_syscall_entry,
+    prelude::*,
+    process::credentials,
+    syscall::constants::MAX_FILENAME_LEN,
+    util::read_cstring_from_user,
+    vm::swap,
+};
+
+pub fn sys_swapon(path_addr: Vaddr, flags: i32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SWAPON);
+    check_permission()?;
+    let path = read_cstring_from_user(path_addr, MAX_FILENAME_LEN)?;
+    debug!("path = {:?}, flags = 0x{:x}", path, flags);
+
+    let flags = SwapFlags::from_bits(flags as u32)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    // The discards are hints for the SSDs, which are not needed by the block devices yet.
+    let priority = if flags.contains(SwapFlags::SWAP_FLAG_PREFER) {
+        Some((flags & SwapFlags::SWAP_FLAG_PRIO_MASK).bits() as i32)
+    } else {
+        None
+    };
+
+    let (path, id, block_device) = lookup_block_device(&path)?;
+    swap::swap_on(path, id, block_device, priority)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_swapoff(path_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_SWAPOFF);
+    check_permission()?;
+    let path = read_cstring_from_user(path_addr, MAX_FILENAME_LEN)?;
+    debug!("path = {:?}", path);
+
+    let (_, id, _) = lookup_block_device(&path)?;
+    swap::swap_off(id)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+fn check_permission() -> Result<()> {
+    // TODO: Check `CAP_SYS_ADMIN` instead once capabilities are supported.
+    if !credentials().euid().is_root() {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "only privileged processes can turn swap on or off"
+        );
+    }
+    Ok(())
+}
+
+/// Looks up the block device at the path, returning its absolute path and device ID as well.
+fn lookup_block_device(path: &CStr) -> Result<(String, DeviceId, Arc<dyn BlockDevice>)> {
+    let dentry = {
+        let path = path.to_string_lossy();
+        if path.is_empty() {
+            return_errno_with_message!(Errno::ENOENT, "path is empty");
+        }
+        let fs_path = FsPath::try_from(path.as_ref())?;
+        current!().fs().read().lookup(&fs_path)?
+    };
+    if dentry.type_() != InodeType::BlockDevice {
+        return_errno_with_message!(Errno::EINVAL, "swap files are not supported");
+    }
+    let id = dentry
+        .inode()
+        .as_device()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a device"))?
+        .id();
+    let (_, block_device) = get_block_device(id)
+        .ok_or_else(|| Error::with_message(Errno::ENODEV, "the block device does not exist"))?;
+    Ok((dentry.abs_path(), id, block_device))
+}
+
+bitflags! {
+    struct SwapFlags: u32 {
+        const SWAP_FLAG_PREFER = 0x8000;
+        const SWAP_FLAG_PRIO_MASK = 0x7fff;
+        const SWAP_FLAG_DISCARD = 0x10000;
+        const SWAP_FLAG_DISCARD_ONCE = 0x20000;
+        const SWAP_FLAG_DISCARD_PAGES = 0x40000;
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{SyscallReturn, SYS_TIMERFD_CREATE, SYS_TIMERFD_GETTIME, SYS_TIMERFD_SETTIME};
+use crate::{
+    fs::{
+        file_table::{FdFlags, FileDescripter},
+        timerfd::{TimerFile, TimerFileFlags, TimerSetTimeFlags},
+    },
+    log_syscall_entry,
+    prelude::*,
+    time::{clockid_t, itimerspec_t, timespec_t, ClockID},
+    util::{read_val_from_user, write_val_to_user},
+};
+
+pub fn sys_timerfd_create(clockid: clockid_t, flags: u32) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMERFD_CREATE);
+    debug!("clockid = {}, flags = 0x{:x}", clockid, flags);
+
+    let clock_id = ClockID::try_from(clockid)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "invalid clock ID"))?;
+    let flags = TimerFileFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    let fd_flags = if flags.contains(TimerFileFlags::TFD_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+
+    let timer_file = TimerFile::new(clock_id, flags)?;
+    let current = current!();
+    let fd = current.file_table().lock().insert(timer_file, fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+pub fn sys_timerfd_settime(
+    fd: FileDescripter,
+    flags: u32,
+    new_value_addr: Vaddr,
+    old_value_addr: Vaddr,
+) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMERFD_SETTIME);
+    debug!(
+        "fd = {}, flags = 0x{:x}, new_value_addr = 0x{:x}, old_value_addr = 0x{:x}",
+        fd, flags, new_value_addr, old_value_addr
+    );
+
+    let flags = TimerSetTimeFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid flags"))?;
+    let new_value = read_val_from_user::<itimerspec_t>(new_value_addr)?;
+    if !new_value.it_value.is_valid() || !new_value.it_interval.is_valid() {
+        return_errno_with_message!(Errno::EINVAL, "invalid time value");
+    }
+
+    let current = current!();
+    let file = current.file_table().lock().get_file(fd)?.clone();
+    let timer_file = file
+        .downcast_ref::<TimerFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a timer file"))?;
+    let (old_value, old_interval) = timer_file.set_time(
+        Duration::from(new_value.it_value),
+        Duration::from(new_value.it_interval),
+        flags.contains(TimerSetTimeFlags::TFD_TIMER_ABSTIME),
+    )?;
+
+    if old_value_addr != 0 {
+        write_val_to_user(old_value_addr, &to_itimerspec(old_value, old_interval))?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_timerfd_gettime(fd: FileDescripter, curr_value_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMERFD_GETTIME);
+    debug!("fd = {}, curr_value_addr = 0x{:x}", fd, curr_value_addr);
+
+    let current = current!();
+    let file = current.file_table().lock().get_file(fd)?.clone();
+    let timer_file = file
+        .downcast_ref::<TimerFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a timer file"))?;
+    let (value, interval) = timer_file.get_time()?;
+    write_val_to_user(curr_value_addr, &to_itimerspec(value, interval))?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+fn to_itimerspec(value: Duration, interval: Duration) -> itimerspec_t {
+    itimerspec_t {
+        it_interval: timespec_t::from(interval),
+        it_value: timespec_t::from(value),
+    }
+}
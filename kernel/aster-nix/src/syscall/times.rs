@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_time::read_monotonic_time;
+
+use super::{SyscallReturn, SYS_TIMES};
+use crate::{
+    log_syscall_entry,
+    prelude::*,
+    time::{clock_t, to_clock_ticks},
+    util::write_val_to_user,
+};
+
+pub fn sys_times(tms_addr: Vaddr) -> Result<SyscallReturn> {
+    log_syscall_entry!(SYS_TIMES);
+    debug!("tms_addr = 0x{:x}", tms_addr);
+
+    if tms_addr != 0 {
+        let current = current!();
+        let cpu_clock = current.cpu_clock();
+        let children_cpu_clock = current.children_cpu_clock();
+        let tms = tms_t {
+            tms_utime: to_clock_ticks(cpu_clock.user_time()),
+            tms_stime: to_clock_ticks(cpu_clock.system_time()),
+            tms_cutime: to_clock_ticks(children_cpu_clock.user_time()),
+            tms_cstime: to_clock_ticks(children_cpu_clock.system_time()),
+        };
+        write_val_to_user(tms_addr, &tms)?;
+    }
+
+    // The return value is the number of clock ticks since an arbitrary point in the past, which
+    // is the boot time here.
+    Ok(SyscallReturn::Return(
+        to_clock_ticks(read_monotonic_time()) as _
+    ))
+}
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct tms_t {
+    tms_utime: clock_t,
+    tms_stime: clock_t,
+    tms_cutime: clock_t,
+    tms_cstime: clock_t,
+}
@@ -4,24 +4,42 @@ use super::SyscallReturn;
 use crate::{
     log_syscall_entry,
     prelude::*,
-    process::{wait_child_exit, ProcessFilter, WaitOptions},
+    process::{rusage_t, wait_child_exit, ProcessFilter, WaitOptions},
     syscall::SYS_WAIT4,
     util::write_val_to_user,
 };
 
-pub fn sys_wait4(wait_pid: u64, exit_status_ptr: u64, wait_options: u32) -> Result<SyscallReturn> {
+pub fn sys_wait4(
+    wait_pid: u64,
+    exit_status_ptr: u64,
+    wait_options: u32,
+    rusage_addr: Vaddr,
+) -> Result<SyscallReturn> {
     log_syscall_entry!(SYS_WAIT4);
     let wait_options = WaitOptions::from_bits(wait_options)
         .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown wait option"))?;
     debug!(
-        "pid = {}, exit_status_ptr = {}, wait_options: {:?}",
-        wait_pid as i32, exit_status_ptr, wait_options
+        "pid = {}, exit_status_ptr = {}, wait_options: {:?}, rusage_addr = 0x{:x}",
+        wait_pid as i32, exit_status_ptr, wait_options, rusage_addr
     );
     debug!("wait4 current pid = {}", current!().pid());
     let process_filter = ProcessFilter::from_id(wait_pid as _);
-    let (return_pid, exit_code) = wait_child_exit(process_filter, wait_options)?;
-    if return_pid != 0 && exit_status_ptr != 0 {
-        write_val_to_user(exit_status_ptr as _, &exit_code)?;
+    let Some(child) = wait_child_exit(process_filter, wait_options)? else {
+        return Ok(SyscallReturn::Return(0));
+    };
+
+    if exit_status_ptr != 0 {
+        write_val_to_user(exit_status_ptr as _, &child.exit_code().unwrap())?;
+    }
+    if rusage_addr != 0 {
+        // The resource usage of the child includes that of its reaped children.
+        let cpu_clock = child.cpu_clock();
+        let children_cpu_clock = child.children_cpu_clock();
+        let rusage = rusage_t::new(
+            cpu_clock.user_time() + children_cpu_clock.user_time(),
+            cpu_clock.system_time() + children_cpu_clock.system_time(),
+        );
+        write_val_to_user(rusage_addr, &rusage)?;
     }
-    Ok(SyscallReturn::Return(return_pid as _))
+    Ok(SyscallReturn::Return(child.pid() as _))
 }
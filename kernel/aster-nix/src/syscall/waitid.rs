@@ -18,6 +18,7 @@ pub fn sys_waitid(
     log_syscall_entry!(SYS_WAITID);
     let process_filter = ProcessFilter::from_which_and_id(which, upid);
     let wait_options = WaitOptions::from_bits(options as u32).expect("Unknown wait options");
-    let (exit_code, pid) = wait_child_exit(process_filter, wait_options)?;
+    let waited_child = wait_child_exit(process_filter, wait_options)?;
+    let pid = waited_child.map_or(0, |child| child.pid());
     Ok(SyscallReturn::Return(pid as _))
 }
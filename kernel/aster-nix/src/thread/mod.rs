@@ -2,12 +2,15 @@
 
 //! Posix thread implementation
 
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use aster_frame::task::Task;
 
 use self::status::ThreadStatus;
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    sched::{RealTimeEntity, SchedEntity},
+};
 
 pub mod exception;
 pub mod kernel_thread;
@@ -32,6 +35,12 @@ pub struct Thread {
 
     // mutable part
     status: Mutex<ThreadStatus>,
+    /// Scheduling states of the fair scheduler
+    sched_entity: SchedEntity,
+    /// Scheduling states of the real-time scheduler
+    real_time_entity: RealTimeEntity,
+    /// Whether the children of the thread are reset to the normal policy
+    sched_reset_on_fork: AtomicBool,
 }
 
 impl Thread {
@@ -47,6 +56,9 @@ impl Thread {
             task,
             data: Box::new(data),
             status: Mutex::new(status),
+            sched_entity: SchedEntity::new(),
+            real_time_entity: RealTimeEntity::new(),
+            sched_reset_on_fork: AtomicBool::new(false),
         }
     }
 
@@ -90,6 +102,30 @@ impl Thread {
         self.tid
     }
 
+    /// Returns the task of the thread.
+    pub fn task(&self) -> &Arc<Task> {
+        &self.task
+    }
+
+    pub fn sched_entity(&self) -> &SchedEntity {
+        &self.sched_entity
+    }
+
+    pub fn real_time_entity(&self) -> &RealTimeEntity {
+        &self.real_time_entity
+    }
+
+    /// Returns whether the children created by the thread are reset to the normal scheduling
+    /// policy, i.e., whether `SCHED_RESET_ON_FORK` is set.
+    pub fn sched_reset_on_fork(&self) -> bool {
+        self.sched_reset_on_fork.load(Ordering::Relaxed)
+    }
+
+    pub fn set_sched_reset_on_fork(&self, reset_on_fork: bool) {
+        self.sched_reset_on_fork
+            .store(reset_on_fork, Ordering::Relaxed);
+    }
+
     // The return type must be borrowed box, otherwise the downcast_ref will fail
     #[allow(clippy::borrowed_box)]
     pub fn data(&self) -> &Box<dyn Send + Sync + Any> {
@@ -101,3 +137,9 @@ impl Thread {
 pub fn allocate_tid() -> Tid {
     TID_ALLOCATOR.fetch_add(1, Ordering::SeqCst)
 }
+
+/// Returns the number of TIDs that have been allocated, i.e., the number of threads that have
+/// been created since the system boots up.
+pub fn nr_allocated_tids() -> u32 {
+    TID_ALLOCATOR.load(Ordering::Relaxed)
+}
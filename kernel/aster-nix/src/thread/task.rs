@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::cell::Cell;
+
 use aster_frame::{
     cpu::UserContext,
     task::{preempt, Task, TaskOptions},
@@ -8,7 +10,10 @@ use aster_frame::{
 
 use super::Thread;
 use crate::{
-    prelude::*, process::signal::handle_pending_signal, syscall::handle_syscall,
+    prelude::*,
+    process::{posix_thread::PosixThreadExt, signal::handle_pending_signal},
+    sched,
+    syscall::handle_syscall,
     thread::exception::handle_exception,
 };
 
@@ -30,12 +35,30 @@ pub fn create_new_user_task(user_space: Arc<UserSpace>, thread_ref: Weak<Thread>
             "[Task entry] rax = 0x{:x}",
             user_mode.context().syscall_ret()
         );
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        // The CPU time of the task that has been charged to the thread.
+        let charged_cpu_time = Cell::new(cur.cpu_time());
+        let charge_cpu_time = |is_user| {
+            let cpu_time = cur.cpu_time();
+            let elapsed = cpu_time.saturating_sub(charged_cpu_time.replace(cpu_time));
+            posix_thread.charge_cpu_time(elapsed, is_user);
+            sched::stats::charge_cpu_time(elapsed, is_user);
+        };
         loop {
-            let user_event = user_mode.execute();
+            // The time since the last return from the user mode is spent in the kernel.
+            charge_cpu_time(false);
+            let user_event = user_mode.execute(|| {
+                // The thread is interrupted in the user mode. The pending signals, e.g., the
+                // ones sent by the timers on the CPU clocks, are handled in the kernel.
+                charge_cpu_time(true);
+                let sig_mask = *posix_thread.sig_mask().lock();
+                posix_thread.has_pending_signal_unblocked_by(&sig_mask)
+            });
+            charge_cpu_time(true);
             let context = user_mode.context_mut();
             // handle user event:
             handle_user_event(user_event, context);
-            let current_thread = current_thread!();
             // should be do this comparison before handle signal?
             if current_thread.status().lock().is_exited() {
                 break;
@@ -70,5 +93,6 @@ fn handle_user_event(user_event: UserEvent, context: &mut UserContext) {
     match user_event {
         UserEvent::Syscall => handle_syscall(context),
         UserEvent::Exception => handle_exception(context),
+        UserEvent::KernelEvent => (),
     }
 }
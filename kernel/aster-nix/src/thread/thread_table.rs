@@ -19,3 +19,8 @@ pub fn remove_thread(tid: Tid) {
 pub fn get_thread(tid: Tid) -> Option<Arc<Thread>> {
     THREAD_TABLE.lock().get(&tid).cloned()
 }
+
+/// Returns the number of threads.
+pub fn nr_threads() -> usize {
+    THREAD_TABLE.lock().len()
+}
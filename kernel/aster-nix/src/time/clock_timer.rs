@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Timers that expire when clocks reach absolute deadlines.
+
+use core::time::Duration;
+
+use aster_frame::timer::Timer;
+
+use super::{now_as_duration, ClockID};
+use crate::prelude::*;
+
+/// A timer that expires when a clock reaches a deadline.
+///
+/// The underlying [`Timer`] counts down the monotonic time. A timer on `CLOCK_REALTIME` is
+/// re-armed whenever the real time is set, so that it still expires when the real time reaches
+/// its deadline. The real time is not re-checked when it is slewed or its frequency is changed,
+/// so the timer may expire slightly earlier or later. Users of the timer should check the clock
+/// after the timer expires.
+pub struct ClockTimer {
+    clock_id: ClockID,
+    timer: Arc<Timer>,
+    /// The deadline measured by the clock, or `None` if the timer is disarmed.
+    deadline: SpinLock<Option<Duration>>,
+}
+
+impl ClockTimer {
+    /// Creates a disarmed timer on the clock of `clock_id`.
+    ///
+    /// The function `on_expire` is called in the interrupt context when the timer expires, so it
+    /// should not take too much time.
+    pub fn new<F>(clock_id: ClockID, on_expire: F) -> Arc<Self>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let timer = Timer::new(move |_| on_expire()).unwrap();
+        let clock_timer = Arc::new(Self {
+            clock_id,
+            timer,
+            deadline: SpinLock::new(None),
+        });
+
+        if clock_timer.is_realtime() {
+            REALTIME_TIMERS
+                .lock_irq_disabled()
+                .insert(clock_timer.key(), Arc::downgrade(&clock_timer));
+        }
+        clock_timer
+    }
+
+    /// Arms the timer to expire when the clock reaches `deadline`.
+    ///
+    /// If the timer is already armed, the old deadline is replaced.
+    pub fn set_deadline(&self, deadline: Duration) -> Result<()> {
+        let now = now_as_duration(&self.clock_id)?;
+
+        let mut old_deadline = self.deadline.lock_irq_disabled();
+        *old_deadline = Some(deadline);
+        self.timer.set(deadline.saturating_sub(now));
+        Ok(())
+    }
+
+    /// Disarms the timer.
+    pub fn clear(&self) {
+        let mut deadline = self.deadline.lock_irq_disabled();
+        *deadline = None;
+        self.timer.clear();
+    }
+
+    /// Arms the timer again with the current time of the clock, which has been set.
+    fn rearm(&self) {
+        let Ok(now) = now_as_duration(&self.clock_id) else {
+            return;
+        };
+
+        let deadline = self.deadline.lock_irq_disabled();
+        let Some(deadline) = *deadline else {
+            return;
+        };
+        // A timer that has expired is not armed again.
+        if !self.timer.remain().is_zero() {
+            self.timer.set(deadline.saturating_sub(now));
+        }
+    }
+
+    fn is_realtime(&self) -> bool {
+        matches!(
+            self.clock_id,
+            ClockID::CLOCK_REALTIME | ClockID::CLOCK_REALTIME_COARSE
+        )
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as usize
+    }
+}
+
+impl Drop for ClockTimer {
+    fn drop(&mut self) {
+        if self.is_realtime() {
+            REALTIME_TIMERS.lock_irq_disabled().remove(&self.key());
+        }
+        self.timer.clear();
+    }
+}
+
+/// The timers on `CLOCK_REALTIME`, which are keyed by their addresses.
+static REALTIME_TIMERS: SpinLock<BTreeMap<usize, Weak<ClockTimer>>> =
+    SpinLock::new(BTreeMap::new());
+
+/// Re-arms the timers on `CLOCK_REALTIME` after the real time is set.
+pub(super) fn rearm_realtime_timers() {
+    // The timers are collected first, since a timer cannot be dropped while the lock is held.
+    let timers: Vec<Arc<ClockTimer>> = REALTIME_TIMERS
+        .lock_irq_disabled()
+        .values()
+        .filter_map(Weak::upgrade)
+        .collect();
+
+    for timer in timers {
+        timer.rearm();
+    }
+}
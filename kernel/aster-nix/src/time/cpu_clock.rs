@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// A clock that measures the CPU time consumed by a thread or a process.
+///
+/// The CPU time is split into the user time, which is spent in the user mode, and the system
+/// time, which is spent in the kernel on behalf of the user.
+#[derive(Debug, Default)]
+pub struct CpuClock {
+    /// The user time in nanoseconds.
+    user: AtomicU64,
+    /// The system time in nanoseconds.
+    system: AtomicU64,
+}
+
+impl CpuClock {
+    pub const fn new() -> Self {
+        Self {
+            user: AtomicU64::new(0),
+            system: AtomicU64::new(0),
+        }
+    }
+
+    /// Adds the time that is spent in the user mode.
+    pub fn add_user_time(&self, time: Duration) {
+        self.user
+            .fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Adds the time that is spent in the kernel mode.
+    pub fn add_system_time(&self, time: Duration) {
+        self.system
+            .fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the time that is spent in the user mode.
+    pub fn user_time(&self) -> Duration {
+        Duration::from_nanos(self.user.load(Ordering::Relaxed))
+    }
+
+    /// Returns the time that is spent in the kernel mode.
+    pub fn system_time(&self) -> Duration {
+        Duration::from_nanos(self.system.load(Ordering::Relaxed))
+    }
+
+    /// Returns the total CPU time, i.e., the sum of the user time and the system time.
+    pub fn total_time(&self) -> Duration {
+        self.user_time() + self.system_time()
+    }
+}
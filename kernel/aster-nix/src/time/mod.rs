@@ -7,8 +7,14 @@ use aster_time::read_monotonic_time;
 
 use crate::prelude::*;
 
+mod clock_timer;
+mod cpu_clock;
+mod realtime;
 mod system_time;
 
+pub use clock_timer::ClockTimer;
+pub use cpu_clock::CpuClock;
+pub use realtime::{adjtimex, set_realtime, timex_t};
 pub use system_time::SystemTime;
 
 pub type clockid_t = i32;
@@ -61,6 +67,22 @@ impl From<timespec_t> for Duration {
     }
 }
 
+impl timespec_t {
+    /// Returns whether the `timespec_t` is a valid non-negative time value.
+    pub fn is_valid(&self) -> bool {
+        self.sec >= 0 && (0..NSEC_PER_SEC).contains(&self.nsec)
+    }
+}
+
+const NSEC_PER_SEC: i64 = 1_000_000_000;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Pod)]
+pub struct itimerspec_t {
+    pub it_interval: timespec_t,
+    pub it_value: timespec_t,
+}
+
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone, Pod)]
 pub struct timeval_t {
@@ -83,6 +105,30 @@ impl From<timeval_t> for Duration {
     }
 }
 
+impl timeval_t {
+    /// Returns whether the `timeval_t` is a valid non-negative time value.
+    pub fn is_valid(&self) -> bool {
+        self.sec >= 0 && (0..USEC_PER_SEC).contains(&self.usec)
+    }
+}
+
+const USEC_PER_SEC: i64 = 1_000_000;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Pod)]
+pub struct itimerval_t {
+    pub it_interval: timeval_t,
+    pub it_value: timeval_t,
+}
+
+/// The number of clock ticks per second that are reported to the user space, e.g., by `times`.
+pub const USER_HZ: u64 = 100;
+
+/// Converts a duration to the number of clock ticks reported to the user space.
+pub fn to_clock_ticks(duration: Duration) -> clock_t {
+    (duration.as_nanos() * USER_HZ as u128 / NSEC_PER_SEC as u128) as clock_t
+}
+
 /// The various flags for setting POSIX.1b interval timers:
 pub const TIMER_ABSTIME: i32 = 0x01;
 
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Setting and adjusting the real time, i.e., `CLOCK_REALTIME`.
+//!
+//! The real time itself is maintained by `aster_time`. This module checks the permission and the
+//! arguments from the user space, and keeps the NTP state that is reported by `adjtimex`.
+//!
+//! Leap seconds and the PLL/FLL of the kernel NTP discipline are not supported. An offset passed
+//! by `ADJ_OFFSET` is slewed in the same way as `ADJ_OFFSET_SINGLESHOT`, and the tick length set by
+//! `ADJ_TICK` is recorded but not applied.
+
+use core::time::Duration;
+
+use super::{
+    clock_timer::rearm_realtime_timers, timeval_t, SystemTime, NSEC_PER_SEC, USEC_PER_SEC, USER_HZ,
+};
+use crate::{prelude::*, process::credentials};
+
+/// The `timex` structure used by `adjtimex` and `clock_adjtime`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Pod)]
+pub struct timex_t {
+    pub modes: u32,
+    _pad0: u32,
+    pub offset: i64,
+    pub freq: i64,
+    pub maxerror: i64,
+    pub esterror: i64,
+    pub status: i32,
+    _pad1: u32,
+    pub constant: i64,
+    pub precision: i64,
+    pub tolerance: i64,
+    pub time: timeval_t,
+    pub tick: i64,
+    pub ppsfreq: i64,
+    pub jitter: i64,
+    pub shift: i32,
+    _pad2: u32,
+    pub stabil: i64,
+    pub jitcnt: i64,
+    pub calcnt: i64,
+    pub errcnt: i64,
+    pub stbcnt: i64,
+    pub tai: i32,
+    _reserved: [i32; 11],
+}
+
+bitflags! {
+    struct AdjtimexModes: u32 {
+        const ADJ_OFFSET    = 0x0001;
+        const ADJ_FREQUENCY = 0x0002;
+        const ADJ_MAXERROR  = 0x0004;
+        const ADJ_ESTERROR  = 0x0008;
+        const ADJ_STATUS    = 0x0010;
+        const ADJ_TIMECONST = 0x0020;
+        const ADJ_TAI       = 0x0080;
+        const ADJ_SETOFFSET = 0x0100;
+        const ADJ_MICRO     = 0x1000;
+        const ADJ_NANO      = 0x2000;
+        const ADJ_TICK      = 0x4000;
+    }
+}
+
+/// The mode bit of the old `adjtime` interface, which is only valid in the following modes.
+const ADJ_ADJTIME: u32 = 0x8000;
+const ADJ_OFFSET_SINGLESHOT: u32 = 0x8001;
+const ADJ_OFFSET_SS_READ: u32 = 0xa001;
+
+const STA_INS: i32 = 0x0010;
+const STA_DEL: i32 = 0x0020;
+const STA_UNSYNC: i32 = 0x0040;
+const STA_NANO: i32 = 0x2000;
+/// The status bits that cannot be changed by `ADJ_STATUS`.
+const STA_RONLY: i32 = 0xff00;
+
+const TIME_OK: i32 = 0;
+const TIME_INS: i32 = 1;
+const TIME_DEL: i32 = 2;
+const TIME_ERROR: i32 = 5;
+
+/// The maximum offset that can be adjusted by `ADJ_OFFSET`, in nanoseconds.
+const MAX_PHASE: i64 = 500_000_000;
+/// The maximum error, in microseconds.
+const NTP_PHASE_LIMIT: i64 = 16_000_000;
+/// The maximum time constant.
+const MAX_TIME_CONSTANT: i64 = 10;
+/// The nominal tick length, in microseconds.
+const NOMINAL_TICK: i64 = 1_000_000 / USER_HZ as i64;
+
+static NTP_STATE: Mutex<NtpState> = Mutex::new(NtpState::new());
+
+/// The NTP state that is only reported to the user space.
+struct NtpState {
+    status: i32,
+    maxerror: i64,
+    esterror: i64,
+    constant: i64,
+    tick: i64,
+    tai: i32,
+}
+
+impl NtpState {
+    const fn new() -> Self {
+        Self {
+            status: STA_UNSYNC,
+            maxerror: NTP_PHASE_LIMIT,
+            esterror: NTP_PHASE_LIMIT,
+            constant: 2,
+            tick: NOMINAL_TICK,
+            tai: 0,
+        }
+    }
+
+    /// Marks the clock as unsynchronized, which happens when the time is set.
+    fn clear(&mut self) {
+        self.status |= STA_UNSYNC;
+        self.maxerror = NTP_PHASE_LIMIT;
+        self.esterror = NTP_PHASE_LIMIT;
+    }
+}
+
+/// Sets the real time to `time` since the UNIX epoch.
+pub fn set_realtime(time: Duration) -> Result<()> {
+    check_permission()?;
+    if SystemTime::UNIX_EPOCH.checked_add(time).is_none() {
+        return_errno_with_message!(Errno::EINVAL, "the time is out of range");
+    }
+
+    aster_time::set_realtime(time);
+    NTP_STATE.lock().clear();
+    rearm_realtime_timers();
+    Ok(())
+}
+
+/// Reads and adjusts the real time as `adjtimex`, and returns the clock state.
+///
+/// The current state is written back to `timex`.
+pub fn adjtimex(timex: &mut timex_t) -> Result<i32> {
+    if timex.modes & ADJ_ADJTIME != 0 {
+        return adjtime(timex);
+    }
+
+    let modes = AdjtimexModes::from_bits(timex.modes)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid modes"))?;
+    if !modes.is_empty() {
+        check_permission()?;
+    }
+    if modes.contains(AdjtimexModes::ADJ_TICK)
+        && !(NOMINAL_TICK * 9 / 10..=NOMINAL_TICK * 11 / 10).contains(&timex.tick)
+    {
+        return_errno_with_message!(Errno::EINVAL, "the tick length is out of range");
+    }
+    let set_offset = if modes.contains(AdjtimexModes::ADJ_SETOFFSET) {
+        // The microsecond field contains nanoseconds if `ADJ_NANO` is set.
+        let subsec_nanos = if modes.contains(AdjtimexModes::ADJ_NANO) {
+            (0..NSEC_PER_SEC)
+                .contains(&timex.time.usec)
+                .then_some(timex.time.usec)
+        } else {
+            (0..USEC_PER_SEC)
+                .contains(&timex.time.usec)
+                .then_some(timex.time.usec * 1000)
+        };
+        let Some(subsec_nanos) = subsec_nanos else {
+            return_errno_with_message!(Errno::EINVAL, "invalid time offset");
+        };
+        let offset = timex
+            .time
+            .sec
+            .checked_mul(NSEC_PER_SEC)
+            .and_then(|nanos| nanos.checked_add(subsec_nanos))
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the time offset is too large"))?;
+        Some(offset)
+    } else {
+        None
+    };
+
+    let mut ntp_state = NTP_STATE.lock();
+
+    if let Some(offset) = set_offset {
+        aster_time::shift_realtime(offset);
+        rearm_realtime_timers();
+    }
+    if modes.contains(AdjtimexModes::ADJ_STATUS) {
+        ntp_state.status = (ntp_state.status & STA_RONLY) | (timex.status & !STA_RONLY);
+    }
+    if modes.contains(AdjtimexModes::ADJ_NANO) {
+        ntp_state.status |= STA_NANO;
+    }
+    if modes.contains(AdjtimexModes::ADJ_MICRO) {
+        ntp_state.status &= !STA_NANO;
+    }
+    if modes.contains(AdjtimexModes::ADJ_FREQUENCY) {
+        aster_time::set_realtime_freq(timex.freq);
+    }
+    if modes.contains(AdjtimexModes::ADJ_MAXERROR) {
+        ntp_state.maxerror = timex.maxerror.clamp(0, NTP_PHASE_LIMIT);
+    }
+    if modes.contains(AdjtimexModes::ADJ_ESTERROR) {
+        ntp_state.esterror = timex.esterror.clamp(0, NTP_PHASE_LIMIT);
+    }
+    if modes.contains(AdjtimexModes::ADJ_TIMECONST) {
+        ntp_state.constant = timex.constant.clamp(0, MAX_TIME_CONSTANT);
+    }
+    if modes.contains(AdjtimexModes::ADJ_TAI) && timex.tai >= 0 {
+        ntp_state.tai = timex.tai;
+    }
+    if modes.contains(AdjtimexModes::ADJ_TICK) {
+        ntp_state.tick = timex.tick;
+    }
+    if modes.contains(AdjtimexModes::ADJ_OFFSET) {
+        let offset = if ntp_state.status & STA_NANO != 0 {
+            timex.offset
+        } else {
+            timex.offset.saturating_mul(1000)
+        };
+        aster_time::slew_realtime(offset.clamp(-MAX_PHASE, MAX_PHASE));
+    }
+
+    let remaining = aster_time::realtime_slew_remaining();
+    timex.offset = if ntp_state.status & STA_NANO != 0 {
+        remaining
+    } else {
+        remaining / 1000
+    };
+    Ok(fill_timex(timex, &ntp_state))
+}
+
+/// Handles the modes of the old `adjtime` interface.
+fn adjtime(timex: &mut timex_t) -> Result<i32> {
+    match timex.modes {
+        ADJ_OFFSET_SS_READ => (),
+        ADJ_OFFSET_SINGLESHOT => check_permission()?,
+        _ => return_errno_with_message!(Errno::EINVAL, "invalid modes"),
+    }
+
+    let ntp_state = NTP_STATE.lock();
+
+    // The offset is always in microseconds, and the old remaining offset is returned.
+    let old_remaining = if timex.modes == ADJ_OFFSET_SINGLESHOT {
+        aster_time::slew_realtime(timex.offset.saturating_mul(1000))
+    } else {
+        aster_time::realtime_slew_remaining()
+    };
+    timex.offset = old_remaining / 1000;
+    Ok(fill_timex(timex, &ntp_state))
+}
+
+/// Writes the state except the offset to `timex`, and returns the clock state.
+fn fill_timex(timex: &mut timex_t, ntp_state: &NtpState) -> i32 {
+    timex.freq = aster_time::realtime_freq();
+    timex.maxerror = ntp_state.maxerror;
+    timex.esterror = ntp_state.esterror;
+    timex.status = ntp_state.status;
+    timex.constant = ntp_state.constant;
+    timex.precision = 1;
+    timex.tolerance = aster_time::MAX_FREQ_SCALED;
+    timex.tick = ntp_state.tick;
+    timex.tai = ntp_state.tai;
+
+    let now = aster_time::read_realtime();
+    timex.time = if ntp_state.status & STA_NANO != 0 {
+        timeval_t {
+            sec: now.as_secs() as _,
+            usec: now.subsec_nanos() as _,
+        }
+    } else {
+        timeval_t::from(now)
+    };
+
+    if ntp_state.status & STA_UNSYNC != 0 {
+        TIME_ERROR
+    } else if ntp_state.status & STA_INS != 0 {
+        TIME_INS
+    } else if ntp_state.status & STA_DEL != 0 {
+        TIME_DEL
+    } else {
+        TIME_OK
+    }
+}
+
+fn check_permission() -> Result<()> {
+    // TODO: Check `CAP_SYS_TIME` instead once capabilities are supported.
+    if !credentials().euid().is_root() {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "only privileged processes can change the time"
+        );
+    }
+    Ok(())
+}
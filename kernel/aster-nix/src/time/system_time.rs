@@ -2,8 +2,8 @@
 
 use core::time::Duration;
 
-use aster_time::{read_monotonic_time, read_start_time};
-use time::{Date, Month, PrimitiveDateTime, Time};
+use aster_time::read_realtime;
+use time::{Date, PrimitiveDateTime, Time};
 
 use crate::prelude::*;
 
@@ -29,13 +29,8 @@ impl SystemTime {
 
     /// Returns the current system time
     pub fn now() -> Self {
-        let start = read_start_time();
-
-        // The get real time result should always be valid
-        convert_system_time(start)
-            .unwrap()
-            .checked_add(read_monotonic_time())
-            .unwrap()
+        // The real time should always be valid
+        Self::UNIX_EPOCH.checked_add(read_realtime()).unwrap()
     }
 
     /// Add a duration to self. If the result does not exceed inner bounds return Some(t), else return None.
@@ -71,28 +66,6 @@ impl SystemTime {
     }
 }
 
-/// convert aster_frame::time::Time to System time
-fn convert_system_time(system_time: aster_time::SystemTime) -> Result<SystemTime> {
-    let month = match Month::try_from(system_time.month) {
-        Ok(month) => month,
-        Err(_) => return_errno_with_message!(Errno::EINVAL, "unknown month in system time"),
-    };
-    let date = match Date::from_calendar_date(system_time.year as _, month, system_time.day) {
-        Ok(date) => date,
-        Err(_) => return_errno_with_message!(Errno::EINVAL, "Invalid system date"),
-    };
-    let time_ = match Time::from_hms_nano(
-        system_time.hour,
-        system_time.minute,
-        system_time.second,
-        system_time.nanos.try_into().unwrap(),
-    ) {
-        Ok(time_) => time_,
-        Err(_) => return_errno_with_message!(Errno::EINVAL, "Invalid system time"),
-    };
-    Ok(SystemTime(PrimitiveDateTime::new(date, time_)))
-}
-
 /// FIXME: need to further check precision loss
 /// convert core::time::Duration to time::Duration
 const fn convert_to_time_duration(duration: Duration) -> time::Duration {
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    prelude::*,
+    util::{read_bytes_from_user, read_val_from_user, write_bytes_to_user},
+};
+
+/// The maximum number of `IoVec`s that can be passed in a single syscall.
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/uio.h.
+pub const IOVEC_MAX: usize = 1024;
+
+/// A user space buffer described by its start address and length.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct IoVec {
+    base: Vaddr,
+    len: usize,
+}
+
+impl IoVec {
+    pub fn base(&self) -> Vaddr {
+        self.base
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0 || self.base == 0
+    }
+
+    /// Reads exactly `dst.len()` bytes from the user buffer.
+    /// The length of `dst` should not exceed the length of the `IoVec`.
+    pub fn read_exact_from_user(&self, dst: &mut [u8]) -> Result<()> {
+        debug_assert!(dst.len() <= self.len);
+        read_bytes_from_user(self.base, dst)
+    }
+
+    /// Writes exactly `src.len()` bytes to the user buffer.
+    /// The length of `src` should not exceed the length of the `IoVec`.
+    pub fn write_exact_to_user(&self, src: &[u8]) -> Result<()> {
+        debug_assert!(src.len() <= self.len);
+        write_bytes_to_user(self.base, src)
+    }
+}
+
+/// Copies `count` `IoVec`s from the user space, starting at `start_addr`.
+pub fn copy_iovs_from_user(start_addr: Vaddr, count: usize) -> Result<Box<[IoVec]>> {
+    if count > IOVEC_MAX {
+        return_errno_with_message!(Errno::EINVAL, "too many iovecs");
+    }
+
+    let mut io_vecs = Vec::with_capacity(count);
+    for idx in 0..count {
+        let addr = start_addr + idx * core::mem::size_of::<IoVec>();
+        let io_vec = read_val_from_user::<IoVec>(addr)?;
+        io_vecs.push(io_vec);
+    }
+
+    Ok(io_vecs.into_boxed_slice())
+}
+
+/// Returns the total length of the user buffers in `io_vecs`.
+///
+/// Like Linux, the total length must not exceed `isize::MAX`, since it may be returned to the
+/// user as a signed number.
+pub fn total_len(io_vecs: &[IoVec]) -> Result<usize> {
+    io_vecs
+        .iter()
+        .try_fold(0usize, |total_len, io_vec| {
+            total_len.checked_add(io_vec.len())
+        })
+        .filter(|total_len| *total_len <= isize::MAX as usize)
+        .ok_or(Error::with_message(
+            Errno::EINVAL,
+            "the total length of iovecs is too large",
+        ))
+}
+
+/// Gathers the contents of all user buffers in `io_vecs` into one kernel buffer.
+pub fn gather_from_iovs(io_vecs: &[IoVec]) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; total_len(io_vecs)?];
+
+    let mut offset = 0;
+    for io_vec in io_vecs.iter().filter(|io_vec| !io_vec.is_empty()) {
+        let len = io_vec.len();
+        io_vec.read_exact_from_user(&mut buffer[offset..offset + len])?;
+        offset += len;
+    }
+    buffer.truncate(offset);
+
+    Ok(buffer)
+}
+
+/// Scatters the bytes in `buf` into the user buffers in `io_vecs` in order.
+///
+/// Returns the number of bytes written.
+pub fn scatter_to_iovs(io_vecs: &[IoVec], buf: &[u8]) -> Result<usize> {
+    let mut offset = 0;
+    for io_vec in io_vecs.iter().filter(|io_vec| !io_vec.is_empty()) {
+        if offset >= buf.len() {
+            break;
+        }
+
+        let len = io_vec.len().min(buf.len() - offset);
+        io_vec.write_exact_to_user(&buf[offset..offset + len])?;
+        offset += len;
+    }
+
+    Ok(offset)
+}
@@ -5,6 +5,7 @@ use core::mem;
 use aster_frame::vm::VmIo;
 
 use crate::prelude::*;
+pub mod iovec;
 pub mod net;
 
 /// Read bytes into the `dest` buffer
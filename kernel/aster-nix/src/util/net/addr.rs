@@ -2,11 +2,11 @@
 
 use crate::{
     net::{
-        iface::Ipv4Address,
+        iface::{Ipv4Address, Ipv6Address},
         socket::{unix::UnixSocketAddr, SocketAddr},
     },
     prelude::*,
-    util::{read_bytes_from_user, read_val_from_user, write_val_to_user},
+    util::{read_bytes_from_user, read_val_from_user, write_bytes_to_user, write_val_to_user},
 };
 
 pub fn read_socket_addr_from_user(addr: Vaddr, addr_len: usize) -> Result<SocketAddr> {
@@ -22,21 +22,24 @@ pub fn read_socket_addr_from_user(addr: Vaddr, addr_len: usize) -> Result<Socket
             debug_assert!(sa_family == CSocketAddrFamily::AF_UNIX as u16);
 
             let bytes = {
-                let bytes_len = addr_len - core::mem::size_of::<u16>();
+                let bytes_len = (addr_len - core::mem::size_of::<u16>()).min(SOCKET_ADDR_UNIX_LEN);
                 let mut bytes = vec![0u8; bytes_len];
                 read_bytes_from_user(addr + core::mem::size_of::<u16>(), &mut bytes)?;
                 bytes
             };
 
-            let unix_socket_addr = if bytes.starts_with(&[0]) {
-                // Abstract unix socket addr
-                let cstr = CStr::from_bytes_until_nul(&bytes[1..])?;
-                let abstract_path = cstr.to_string_lossy().to_string();
-                UnixSocketAddr::Abstract(abstract_path)
+            let unix_socket_addr = if let Some(name) = bytes.strip_prefix(&[0]) {
+                // Abstract unix socket addr. The name is not null-terminated
+                // and its length is determined by the address length.
+                UnixSocketAddr::Abstract(name.to_vec())
             } else {
-                // Normal unix sockket addr
-                let cstr = CStr::from_bytes_until_nul(&bytes)?;
-                let path = cstr.to_string_lossy().to_string();
+                // Normal unix sockket addr. The path may not be null-terminated
+                // if it occupies the whole address.
+                let path_len = bytes
+                    .iter()
+                    .position(|byte| *byte == 0)
+                    .unwrap_or(bytes.len());
+                let path = String::from_utf8_lossy(&bytes[..path_len]).to_string();
                 UnixSocketAddr::Path(path)
             };
 
@@ -50,7 +53,7 @@ pub fn read_socket_addr_from_user(addr: Vaddr, addr_len: usize) -> Result<Socket
         CSocketAddrFamily::AF_INET6 => {
             debug_assert!(addr_len >= core::mem::size_of::<CSocketAddrInet6>());
             let sock_addr_in6: CSocketAddrInet6 = read_val_from_user(addr)?;
-            todo!()
+            SocketAddr::from(sock_addr_in6)
         }
         _ => {
             return_errno_with_message!(Errno::EAFNOSUPPORT, "cannot support address for the family")
@@ -69,28 +72,46 @@ pub fn write_socket_addr_to_user(
         return_errno_with_message!(Errno::EINVAL, "must provide the addrlen ptr");
     }
     let max_len = read_val_from_user::<i32>(addrlen_ptr)? as usize;
+    let write_size = write_socket_addr_with_max_len(socket_addr, dest, max_len)?;
+    if addrlen_ptr != 0 {
+        write_val_to_user(addrlen_ptr, &write_size)?;
+    }
+    Ok(())
+}
+
+/// Write a socket address to the user space, whose buffer is at most `max_len` bytes.
+///
+/// Returns the length of the written socket address.
+pub fn write_socket_addr_with_max_len(
+    socket_addr: &SocketAddr,
+    dest: Vaddr,
+    max_len: usize,
+) -> Result<i32> {
+    // Like Linux, the address is truncated if the buffer is too small, but the real length is
+    // returned.
+    let write_truncated = |bytes: &[u8]| -> Result<i32> {
+        let copy_size = bytes.len().min(max_len);
+        write_bytes_to_user(dest, &bytes[..copy_size])?;
+        Ok(bytes.len() as i32)
+    };
     let write_size = match socket_addr {
-        SocketAddr::Unix(path) => {
-            let sock_addr_unix = CSocketAddrUnix::try_from(path)?;
-            let write_size = core::mem::size_of::<CSocketAddrUnix>();
-            debug_assert!(max_len >= write_size);
-            write_val_to_user(dest, &sock_addr_unix)?;
-            write_size as i32
+        SocketAddr::Unix(unix_socket_addr) => {
+            let sock_addr_unix = CSocketAddrUnix::try_from(unix_socket_addr)?;
+            let write_size = unix_socket_addr_len(unix_socket_addr);
+            write_truncated(&sock_addr_unix.as_bytes()[..write_size])?
         }
         SocketAddr::IPv4(addr, port) => {
             let in_addr = CInetAddr::from(*addr);
             let sock_addr_in = CSocketAddrInet::new(*port, in_addr);
-            let write_size = core::mem::size_of::<CSocketAddrInet>();
-            debug_assert!(max_len >= write_size);
-            write_val_to_user(dest, &sock_addr_in)?;
-            write_size as i32
+            write_truncated(sock_addr_in.as_bytes())?
+        }
+        SocketAddr::IPv6(addr, port) => {
+            let in6_addr = CInet6Addr::from(*addr);
+            let sock_addr_in6 = CSocketAddrInet6::new(*port, in6_addr);
+            write_truncated(sock_addr_in6.as_bytes())?
         }
-        SocketAddr::IPv6 => todo!(),
     };
-    if addrlen_ptr != 0 {
-        write_val_to_user(addrlen_ptr, &write_size)?;
-    }
-    Ok(())
+    Ok(write_size)
 }
 
 /// PlaceHolder
@@ -189,6 +210,13 @@ impl CInet6Addr {
     pub fn as_bytes(&self) -> &[u8] {
         &self.s6_addr
     }
+
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        debug_assert!(bytes.len() == 16);
+        let mut s6_addr = [0u8; 16];
+        s6_addr.copy_from_slice(bytes);
+        Self { s6_addr }
+    }
 }
 
 /// IPv6 socket address
@@ -207,6 +235,19 @@ pub struct CSocketAddrInet6 {
     sin6_scope_id: u32,
 }
 
+impl CSocketAddrInet6 {
+    pub fn new(port: u16, addr: CInet6Addr) -> Self {
+        let port = CPortNum::from_u16(port);
+        Self {
+            sin6_family: CSocketAddrFamily::AF_INET6 as _,
+            sin6_port: port,
+            sin6_flowinfo: 0,
+            sin6_addr: addr,
+            sin6_scope_id: 0,
+        }
+    }
+}
+
 /// Address family. The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h.
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
@@ -290,6 +331,28 @@ impl From<CSocketAddrInet> for SocketAddr {
     }
 }
 
+impl From<CInet6Addr> for Ipv6Address {
+    fn from(value: CInet6Addr) -> Self {
+        let addr = value.as_bytes();
+        Ipv6Address::from_bytes(addr)
+    }
+}
+
+impl From<Ipv6Address> for CInet6Addr {
+    fn from(value: Ipv6Address) -> Self {
+        let bytes = value.as_bytes();
+        CInet6Addr::from_bytes(bytes)
+    }
+}
+
+impl From<CSocketAddrInet6> for SocketAddr {
+    fn from(value: CSocketAddrInet6) -> Self {
+        let port = value.sin6_port.as_u16();
+        let addr = Ipv6Address::from(value.sin6_addr);
+        SocketAddr::IPv6(addr, port)
+    }
+}
+
 impl TryFrom<&UnixSocketAddr> for CSocketAddrUnix {
     type Error = Error;
 
@@ -305,7 +368,28 @@ impl TryFrom<&UnixSocketAddr> for CSocketAddrUnix {
                     sun_path,
                 })
             }
-            UnixSocketAddr::Abstract(_) => todo!(),
+            UnixSocketAddr::Abstract(name) => {
+                // The first byte of `sun_path` is always zero for an abstract address.
+                let copy_len = name.len().min(SOCKET_ADDR_UNIX_LEN - 1);
+                sun_path[1..copy_len + 1].copy_from_slice(&name[..copy_len]);
+                Ok(CSocketAddrUnix {
+                    sun_family: CSocketAddrFamily::AF_UNIX as u16,
+                    sun_path,
+                })
+            }
         }
     }
 }
+
+/// Returns the length of the meaningful part of a unix socket address.
+///
+/// An unnamed address only has the family field. A path includes the terminating
+/// null byte, while an abstract name includes the leading null byte.
+fn unix_socket_addr_len(unix_socket_addr: &UnixSocketAddr) -> usize {
+    let path_len = match unix_socket_addr {
+        UnixSocketAddr::Path(path) if path.is_empty() => 0,
+        UnixSocketAddr::Path(path) => path.len().min(SOCKET_ADDR_UNIX_LEN - 1) + 1,
+        UnixSocketAddr::Abstract(name) => name.len().min(SOCKET_ADDR_UNIX_LEN - 1) + 1,
+    };
+    core::mem::size_of::<u16>() + path_len
+}
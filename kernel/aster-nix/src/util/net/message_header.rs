@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::mem::size_of;
+
+use align_ext::AlignExt;
+
+use super::{read_socket_addr_from_user, write_socket_addr_with_max_len, CSocketOptionLevel};
+use crate::{
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDescripter},
+    },
+    net::socket::{ControlMessage, SendRecvFlags, SocketAddr, UnixCredentials},
+    prelude::*,
+    process::credentials,
+    util::{
+        iovec::{copy_iovs_from_user, IoVec},
+        read_val_from_user, write_bytes_to_user, write_val_to_user,
+    },
+};
+
+/// Message header used for sendmsg/recvmsg.
+///
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h#L50.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CUserMsgHdr {
+    /// Pointer to socket address structure
+    msg_name: Vaddr,
+    /// Size of socket address
+    msg_namelen: i32,
+    /// Scatter/Gather iov array
+    msg_iov: Vaddr,
+    /// The # of elements in msg_iov
+    msg_iovlen: usize,
+    /// Ancillary data
+    msg_control: Vaddr,
+    /// Ancillary data buffer length
+    msg_controllen: usize,
+    /// Flags on received message
+    msg_flags: i32,
+}
+
+impl CUserMsgHdr {
+    pub fn read_socket_addr_from_user(&self) -> Result<Option<SocketAddr>> {
+        if self.msg_name == 0 {
+            return Ok(None);
+        }
+
+        let socket_addr = read_socket_addr_from_user(self.msg_name, self.msg_namelen as usize)?;
+        Ok(Some(socket_addr))
+    }
+
+    pub fn write_socket_addr_to_user(&mut self, addr: Option<&SocketAddr>) -> Result<()> {
+        if self.msg_name == 0 {
+            return Ok(());
+        }
+
+        let Some(addr) = addr else {
+            self.msg_namelen = 0;
+            return Ok(());
+        };
+
+        let write_size =
+            write_socket_addr_with_max_len(addr, self.msg_name, self.msg_namelen as usize)?;
+        self.msg_namelen = write_size;
+        Ok(())
+    }
+
+    pub fn copy_iovs_from_user(&self) -> Result<Box<[IoVec]>> {
+        copy_iovs_from_user(self.msg_iov, self.msg_iovlen)
+    }
+
+    pub fn set_msg_flags(&mut self, flags: SendRecvFlags) {
+        self.msg_flags = flags.bits();
+    }
+
+    /// Reads the control messages from the user space.
+    ///
+    /// The files passed with `SCM_RIGHTS` are looked up in the file table of the current process.
+    pub fn read_control_messages_from_user(&self) -> Result<Vec<ControlMessage>> {
+        let mut control_messages = Vec::new();
+        if self.msg_control == 0 {
+            return Ok(control_messages);
+        }
+
+        let header_len = size_of::<CControlHeader>();
+        let mut offset = 0;
+        while offset + header_len <= self.msg_controllen {
+            let header: CControlHeader = read_val_from_user(self.msg_control + offset)?;
+            if header.cmsg_len < header_len || header.cmsg_len > self.msg_controllen - offset {
+                return_errno_with_message!(Errno::EINVAL, "the control message length is invalid");
+            }
+
+            let payload_addr = self.msg_control + offset + header_len;
+            let payload_len = header.cmsg_len - header_len;
+            let control_message =
+                read_control_message_from_user(&header, payload_addr, payload_len)?;
+            control_messages.push(control_message);
+
+            offset += cmsg_align(header.cmsg_len);
+        }
+
+        Ok(control_messages)
+    }
+
+    /// Writes the control messages to the user space, returning the received fds.
+    ///
+    /// The files received with `SCM_RIGHTS` are installed into the file table of the current
+    /// process. If the control buffer is too small, the control messages that cannot fit are
+    /// discarded and `MSG_CTRUNC` is set in `msg_flags`.
+    ///
+    /// The received fds are closed if this fails. If the caller fails later, it should close
+    /// them with `close_received_fds`, since the user never learns about them.
+    pub fn write_control_messages_to_user(
+        &mut self,
+        control_messages: Vec<ControlMessage>,
+        flags: SendRecvFlags,
+    ) -> Result<Vec<FileDescripter>> {
+        let mut writer = ControlMessageWriter::new(self.msg_control, self.msg_controllen);
+        if let Err(err) = writer.write_all(control_messages, flags) {
+            close_received_fds(&writer.received_fds);
+            return Err(err);
+        }
+
+        self.msg_controllen = writer.written_len();
+        if writer.is_truncated() {
+            self.msg_flags |= SendRecvFlags::MSG_CTRUNC.bits();
+        }
+        Ok(writer.received_fds)
+    }
+}
+
+/// Closes the fds received with `SCM_RIGHTS` that cannot be reported to the user.
+pub fn close_received_fds(fds: &[FileDescripter]) {
+    let current = current!();
+    let mut file_table = current.file_table().lock();
+    for fd in fds {
+        if let Some(file) = file_table.close_file(*fd) {
+            let _ = file.clean_for_close();
+        }
+    }
+}
+
+/// Control message header.
+///
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h#L105.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CControlHeader {
+    /// Data byte count, including the header
+    cmsg_len: usize,
+    /// Originating protocol
+    cmsg_level: i32,
+    /// Protocol-specific type
+    cmsg_type: i32,
+}
+
+/// Transfer file descriptors
+const SCM_RIGHTS: i32 = 1;
+/// Transfer process credentials
+const SCM_CREDENTIALS: i32 = 2;
+
+/// The maximum number of file descriptors that can be passed in one control message
+const SCM_MAX_FD: usize = 253;
+
+fn cmsg_align(len: usize) -> usize {
+    len.align_up(size_of::<usize>())
+}
+
+fn read_control_message_from_user(
+    header: &CControlHeader,
+    payload_addr: Vaddr,
+    payload_len: usize,
+) -> Result<ControlMessage> {
+    if header.cmsg_level != CSocketOptionLevel::SOL_SOCKET as i32 {
+        return_errno_with_message!(Errno::EINVAL, "the control message level is not supported");
+    }
+
+    match header.cmsg_type {
+        SCM_RIGHTS => {
+            let num_fds = payload_len / size_of::<FileDescripter>();
+            if num_fds > SCM_MAX_FD {
+                return_errno_with_message!(Errno::EINVAL, "too many file descriptors");
+            }
+
+            let mut fds = Vec::with_capacity(num_fds);
+            for idx in 0..num_fds {
+                let fd: FileDescripter =
+                    read_val_from_user(payload_addr + idx * size_of::<FileDescripter>())?;
+                fds.push(fd);
+            }
+
+            let files = {
+                let current = current!();
+                let file_table = current.file_table().lock();
+                fds.into_iter()
+                    .map(|fd| file_table.get_file(fd).cloned())
+                    .collect::<Result<Vec<_>>>()?
+            };
+            Ok(ControlMessage::Rights(files))
+        }
+        SCM_CREDENTIALS => {
+            if payload_len < size_of::<UnixCredentials>() {
+                return_errno_with_message!(Errno::EINVAL, "the credentials are truncated");
+            }
+
+            let unix_credentials: UnixCredentials = read_val_from_user(payload_addr)?;
+            check_credentials(&unix_credentials)?;
+            Ok(ControlMessage::Credentials(unix_credentials))
+        }
+        _ => return_errno_with_message!(Errno::EINVAL, "the control message type is not supported"),
+    }
+}
+
+/// Checks whether the current process can send the credentials.
+///
+/// A process can only send its own PID, UIDs and GIDs, unless it is privileged.
+fn check_credentials(unix_credentials: &UnixCredentials) -> Result<()> {
+    let credentials = credentials();
+    if credentials.euid().is_root() {
+        return Ok(());
+    }
+
+    let is_pid_valid = unix_credentials.pid() == current!().pid();
+    let is_uid_valid = {
+        let uid = unix_credentials.uid();
+        uid == credentials.ruid() || uid == credentials.euid() || uid == credentials.suid()
+    };
+    let is_gid_valid = {
+        let gid = unix_credentials.gid();
+        gid == credentials.rgid() || gid == credentials.egid() || gid == credentials.sgid()
+    };
+
+    if !is_pid_valid || !is_uid_valid || !is_gid_valid {
+        return_errno_with_message!(Errno::EPERM, "the credentials cannot be sent");
+    }
+    Ok(())
+}
+
+struct ControlMessageWriter {
+    addr: Vaddr,
+    len: usize,
+    offset: usize,
+    is_truncated: bool,
+    /// The fds installed for the files received with `SCM_RIGHTS`.
+    received_fds: Vec<FileDescripter>,
+}
+
+impl ControlMessageWriter {
+    fn new(addr: Vaddr, len: usize) -> Self {
+        Self {
+            addr,
+            len,
+            offset: 0,
+            is_truncated: false,
+            received_fds: Vec::new(),
+        }
+    }
+
+    fn write_all(
+        &mut self,
+        control_messages: Vec<ControlMessage>,
+        flags: SendRecvFlags,
+    ) -> Result<()> {
+        for control_message in control_messages {
+            match control_message {
+                ControlMessage::Rights(files) => {
+                    let fd_flags = if flags.contains(SendRecvFlags::MSG_CMSG_CLOEXEC) {
+                        FdFlags::CLOEXEC
+                    } else {
+                        FdFlags::empty()
+                    };
+                    self.write_rights(files, fd_flags)?;
+                }
+                ControlMessage::Credentials(credentials) => {
+                    self.write(SCM_CREDENTIALS, credentials.as_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn written_len(&self) -> usize {
+        self.offset
+    }
+
+    fn is_truncated(&self) -> bool {
+        self.is_truncated
+    }
+
+    fn available_payload_len(&self) -> usize {
+        if self.addr == 0 {
+            return 0;
+        }
+        (self.len - self.offset).saturating_sub(size_of::<CControlHeader>())
+    }
+
+    fn write_rights(&mut self, files: Vec<Arc<dyn FileLike>>, fd_flags: FdFlags) -> Result<()> {
+        let max_fds = self.available_payload_len() / size_of::<FileDescripter>();
+        if max_fds == 0 {
+            self.is_truncated = true;
+            return Ok(());
+        }
+        if files.len() > max_fds {
+            // The files that cannot be received are closed by dropping them.
+            self.is_truncated = true;
+        }
+
+        let fds = {
+            let current = current!();
+            let mut file_table = current.file_table().lock();
+            files
+                .into_iter()
+                .take(max_fds)
+                .map(|file| file_table.insert(file, fd_flags))
+                .collect::<Vec<_>>()
+        };
+        // The fds are recorded first, so that they can be closed if anything fails.
+        self.received_fds.extend_from_slice(&fds);
+
+        let payload = fds
+            .iter()
+            .flat_map(|fd| fd.to_ne_bytes())
+            .collect::<Vec<_>>();
+        self.write(SCM_RIGHTS, &payload)
+    }
+
+    fn write(&mut self, cmsg_type: i32, payload: &[u8]) -> Result<()> {
+        if payload.len() > self.available_payload_len() {
+            self.is_truncated = true;
+            return Ok(());
+        }
+
+        let header = CControlHeader {
+            cmsg_len: size_of::<CControlHeader>() + payload.len(),
+            cmsg_level: CSocketOptionLevel::SOL_SOCKET as i32,
+            cmsg_type,
+        };
+        write_val_to_user(self.addr + self.offset, &header)?;
+        write_bytes_to_user(
+            self.addr + self.offset + size_of::<CControlHeader>(),
+            payload,
+        )?;
+
+        self.offset = (self.offset + cmsg_align(header.cmsg_len)).min(self.len);
+        Ok(())
+    }
+}
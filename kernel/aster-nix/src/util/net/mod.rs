@@ -1,10 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod addr;
+mod message_header;
 mod options;
 mod socket;
 
-pub use addr::{read_socket_addr_from_user, write_socket_addr_to_user, CSocketAddrFamily};
+pub use addr::{
+    read_socket_addr_from_user, write_socket_addr_to_user, write_socket_addr_with_max_len,
+    CSocketAddrFamily,
+};
+pub use message_header::{close_received_fds, CUserMsgHdr};
 pub use options::{new_raw_socket_option, CSocketOptionLevel};
 pub use socket::{Protocol, SockFlags, SockType, SOCK_TYPE_MASK};
 
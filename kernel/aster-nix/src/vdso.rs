@@ -8,10 +8,11 @@
 //! necessary time-related information, and a Virtual Memory Object (VMO) that encapsulates both the data and the
 //! VDSO routines. The VMO is intended to be mapped into the address space of every user space process for efficient access.
 //!
-//! The module is initialized with `init`, which sets up the `START_SECS_COUNT` and prepares the VDSO instance for
-//! use. It also hooks up the VDSO data update routine to the time management subsystem for periodic updates.
+//! The module is initialized with `init`, which prepares the VDSO instance for use. It also hooks up the VDSO data
+//! update routine to the time management subsystem for periodic updates.
 
 use alloc::{boxed::Box, sync::Arc};
+use core::time::Duration;
 
 use aster_frame::{
     sync::Mutex,
@@ -25,7 +26,7 @@ use spin::Once;
 
 use crate::{
     fs::fs_resolver::{FsPath, FsResolver, AT_FDCWD},
-    time::{ClockID, SystemTime, ALL_SUPPORTED_CLOCK_IDS},
+    time::{ClockID, ALL_SUPPORTED_CLOCK_IDS},
     vm::vmo::{Vmo, VmoOptions},
 };
 
@@ -33,7 +34,6 @@ const CLOCK_TAI: usize = 11;
 const VDSO_BASES: usize = CLOCK_TAI + 1;
 const DEFAULT_CLOCK_MODE: VdsoClockMode = VdsoClockMode::Tsc;
 
-static START_SECS_COUNT: Once<u64> = Once::new();
 static VDSO: Once<Arc<Vdso>> = Once::new();
 
 #[derive(Debug, Copy, Clone)]
@@ -138,17 +138,14 @@ impl VdsoData {
         self.last_cycles = instant_cycles;
         const REALTIME_IDS: [ClockID; 2] =
             [ClockID::CLOCK_REALTIME, ClockID::CLOCK_REALTIME_COARSE];
+        let realtime = aster_time::realtime_of(Duration::new(instant.secs(), instant.nanos()));
         for clock_id in ALL_SUPPORTED_CLOCK_IDS {
-            let secs = if REALTIME_IDS.contains(&clock_id) {
-                instant.secs() + START_SECS_COUNT.get().unwrap()
+            let (secs, nanos) = if REALTIME_IDS.contains(&clock_id) {
+                (realtime.as_secs(), realtime.subsec_nanos())
             } else {
-                instant.secs()
+                (instant.secs(), instant.nanos())
             };
-            self.update_clock_instant(
-                clock_id as usize,
-                secs,
-                (instant.nanos() as u64) << self.shift as u64,
-            );
+            self.update_clock_instant(clock_id as usize, secs, (nanos as u64) << self.shift as u64);
         }
     }
 }
@@ -238,13 +235,6 @@ fn update_vdso_instant(instant: Instant, instant_cycles: u64) {
     VDSO.get().unwrap().update_instant(instant, instant_cycles);
 }
 
-/// Init `START_SECS_COUNT`, which is used to record the seconds passed since 1970-01-01 00:00:00.
-fn init_start_secs_count() {
-    let now = SystemTime::now();
-    let time_duration = now.duration_since(&SystemTime::UNIX_EPOCH).unwrap();
-    START_SECS_COUNT.call_once(|| time_duration.as_secs());
-}
-
 fn init_vdso() {
     let vdso = Vdso::new();
     VDSO.call_once(|| Arc::new(vdso));
@@ -252,7 +242,6 @@ fn init_vdso() {
 
 /// Init vdso module.
 pub(super) fn init() {
-    init_start_secs_count();
     init_vdso();
     aster_time::VDSO_DATA_UPDATE.call_once(|| Arc::new(update_vdso_instant));
 }
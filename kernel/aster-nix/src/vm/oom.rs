@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The out-of-memory (OOM) killer.
+//!
+//! When the memory is short and nothing can be reclaimed, the OOM killer chooses the process
+//! that uses the most memory as the victim, and kills it with `SIGKILL` to free its memory.
+//! The choice can be adjusted for each process by `/proc/[pid]/oom_score_adj`, e.g., to
+//! protect the critical daemons from being killed.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use aster_frame::vm::total_frames;
+
+use super::swap::swap_pages;
+use crate::{
+    prelude::*,
+    process::{
+        process_table,
+        signal::{constants::SIGKILL, signals::kernel::KernelSignal},
+        Pid, Process,
+    },
+};
+
+/// The minimum `oom_score_adj`, which prevents the process from being killed.
+pub const OOM_SCORE_ADJ_MIN: i16 = -1000;
+/// The maximum `oom_score_adj`, which makes the process killed first.
+pub const OOM_SCORE_ADJ_MAX: i16 = 1000;
+
+/// The PID of the last victim, or zero if no process has been killed.
+static VICTIM_PID: AtomicU32 = AtomicU32::new(0);
+
+/// Kills the process that is the most likely to free enough memory, returning whether a process
+/// is killed or is being killed.
+///
+/// No more processes are killed until the last victim exits, since its memory is about to be
+/// freed.
+pub fn out_of_memory() -> bool {
+    let victim_pid: Pid = VICTIM_PID.load(Ordering::Relaxed);
+    if let Some(victim) = process_table::get_process(&victim_pid)
+        && !victim.is_zombie()
+    {
+        return true;
+    }
+
+    let nr_total_pages = nr_total_pages();
+    let processes: Vec<Arc<Process>> = process_table::process_table().iter().cloned().collect();
+    let Some((victim, points)) = processes
+        .iter()
+        .filter_map(|process| Some((process, badness(process, nr_total_pages)?)))
+        .max_by_key(|(_, points)| *points)
+    else {
+        error!("out of memory, but no process can be killed");
+        return false;
+    };
+
+    warn!(
+        "out of memory: killed process {} ({}), resident pages: {}, oom_score_adj: {}",
+        victim.pid(),
+        victim.executable_path(),
+        nr_resident_pages(victim),
+        victim.oom_score_adj().load(Ordering::Relaxed)
+    );
+    VICTIM_PID.store(victim.pid(), Ordering::Relaxed);
+    victim.enqueue_signal(KernelSignal::new(SIGKILL));
+    true
+}
+
+/// Returns the score shown in `/proc/[pid]/oom_score`.
+///
+/// The higher the score is, the more likely the process is killed. The badness is scaled in
+/// the same way as Linux, and the processes that cannot be killed are scored zero.
+pub fn oom_score(process: &Process) -> u32 {
+    let nr_total_pages = nr_total_pages();
+    let Some(points) = badness(process, nr_total_pages) else {
+        return 0;
+    };
+    ((1000 + points * 1000 / nr_total_pages as i64) * 2 / 3).max(0) as u32
+}
+
+/// Returns the badness of the process, or `None` if the process cannot be killed.
+///
+/// The badness is the number of the resident pages, which is adjusted by `oom_score_adj` in
+/// thousandths of the total pages, as Linux does.
+fn badness(process: &Process, nr_total_pages: usize) -> Option<i64> {
+    let oom_score_adj = process.oom_score_adj().load(Ordering::Relaxed);
+    if oom_score_adj == OOM_SCORE_ADJ_MIN || process.is_init_process() || process.is_zombie() {
+        return None;
+    }
+    let adjustment = oom_score_adj as i64 * nr_total_pages as i64 / 1000;
+    Some(nr_resident_pages(process) as i64 + adjustment)
+}
+
+fn nr_resident_pages(process: &Process) -> usize {
+    process.root_vmar().vm_space().nr_mapped_pages()
+}
+
+/// Returns the number of the pages in the memory and on the swap devices.
+fn nr_total_pages() -> usize {
+    let (nr_swap_pages, _) = swap_pages();
+    total_frames() + nr_swap_pages
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Reclaiming memory when it is short.
+//!
+//! When a page cannot be allocated, the least recently used pages in the page caches are
+//! evicted first, since they can be read back from the file systems at any time. Then the
+//! anonymous pages that are not recently accessed are swapped out, if there are swap devices.
+//! If nothing can be reclaimed, the OOM killer kills a process to free its memory.
+
+use aster_frame::vm::{VmAllocOptions, VmFrame};
+
+use super::{oom, swap};
+use crate::{fs::utils::reclaim_page_caches, prelude::*, thread::Thread};
+
+/// The number of pages that are reclaimed each time the memory allocation fails.
+pub const NR_RECLAIM_PAGES: usize = 32;
+
+/// Allocates a page with `options`, reclaiming some pages to make room for it if the memory
+/// is short.
+///
+/// If nothing can be reclaimed, the OOM killer is invoked, and the allocation is retried after
+/// the victim has a chance to run. The memory of the victim is freed only after it exits, so
+/// the allocation may still fail with `ENOMEM`.
+pub fn alloc_page(options: &VmAllocOptions) -> Result<VmFrame> {
+    if let Ok(page) = options.alloc_single() {
+        return Ok(page);
+    }
+    if reclaim(NR_RECLAIM_PAGES) == 0 && oom::out_of_memory() {
+        Thread::yield_now();
+    }
+    Ok(options.alloc_single()?)
+}
+
+/// Reclaims at most `nr_pages` pages, returning the number of the pages reclaimed.
+///
+/// The page caches and the mappings that are being used are skipped, so this can be called
+/// when allocating memory for them.
+pub fn reclaim(nr_pages: usize) -> usize {
+    let nr_evicted = reclaim_page_caches(nr_pages);
+    if nr_evicted >= nr_pages {
+        return nr_evicted;
+    }
+    nr_evicted + swap::reclaim(nr_pages - nr_evicted)
+}
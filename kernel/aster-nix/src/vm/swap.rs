@@ -0,0 +1,363 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Swapping anonymous pages out to block devices.
+//!
+//! When the memory is short, the private anonymous pages (including the private copies of
+//! file pages) that are not recently accessed are written to a swap device, and they are read
+//! back on the next access. A swap device is a block device formatted by `mkswap`, which is
+//! enabled by `swapon` and disabled by `swapoff`.
+
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicUsize, Ordering};
+
+use aster_block::{bio::BioStatus, id::Bid, BlockDevice, SECTOR_SIZE};
+use aster_frame::vm::{VmFrame, VmIo};
+use bitvec::prelude::*;
+
+use crate::{
+    fs::device::DeviceId,
+    prelude::*,
+    process::{process_table, Process},
+};
+
+/// The magic at the end of the first page of a swap device formatted by `mkswap`.
+const SWAP_MAGIC: &[u8] = b"SWAPSPACE2";
+/// The offset of `SwapHeader` in the first page, which follows the boot sectors.
+const SWAP_HEADER_OFFSET: usize = 1024;
+/// The offset of the list of bad pages in the first page.
+const SWAP_BAD_PAGES_OFFSET: usize = 1536;
+/// The maximum number of bad pages that can be listed before the magic.
+const MAX_SWAP_BAD_PAGES: usize =
+    (PAGE_SIZE - SWAP_MAGIC.len() - SWAP_BAD_PAGES_OFFSET) / core::mem::size_of::<u32>();
+
+/// The header of a swap device, which is written by `mkswap`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct SwapHeader {
+    version: u32,
+    /// The index of the last page that can be used for swapping.
+    last_page: u32,
+    nr_bad_pages: u32,
+}
+
+/// A block device that the pages can be swapped out to.
+pub struct SwapDevice {
+    /// The path of the device, which is shown in `/proc/swaps`.
+    path: String,
+    id: DeviceId,
+    block_device: Arc<dyn BlockDevice>,
+    /// The devices of higher priorities are used first.
+    priority: i32,
+    /// The slots for the pages on the device, where the used ones are set.
+    ///
+    /// The first slot holds the header, so it is never used for swapping, nor are the slots
+    /// of the bad pages.
+    slots: SpinLock<BitVec>,
+    /// The number of the slots that can be used for swapping.
+    nr_pages: usize,
+    nr_used_pages: AtomicUsize,
+    /// Whether new pages can be swapped out to the device, which is cleared during `swapoff`.
+    is_active: AtomicBool,
+}
+
+impl SwapDevice {
+    /// Opens the block device as a swap device, checking the header written by `mkswap`.
+    fn open(
+        path: String,
+        id: DeviceId,
+        block_device: Arc<dyn BlockDevice>,
+        priority: i32,
+    ) -> Result<Self> {
+        let mut magic = [0u8; SWAP_MAGIC.len()];
+        block_device.read_bytes(PAGE_SIZE - SWAP_MAGIC.len(), &mut magic)?;
+        if magic != SWAP_MAGIC {
+            return_errno_with_message!(Errno::EINVAL, "the device is not formatted by mkswap");
+        }
+
+        let header = block_device.read_val::<SwapHeader>(SWAP_HEADER_OFFSET)?;
+        if header.version != 1 {
+            return_errno_with_message!(Errno::EINVAL, "the swap version is not supported");
+        }
+        let nr_bad_pages = header.nr_bad_pages as usize;
+        if nr_bad_pages > MAX_SWAP_BAD_PAGES {
+            return_errno_with_message!(Errno::EINVAL, "there are too many bad pages");
+        }
+
+        let device_pages = block_device.nr_sectors() * SECTOR_SIZE / PAGE_SIZE;
+        let nr_slots = (header.last_page as usize + 1).min(device_pages);
+        if nr_slots < 2 {
+            return_errno_with_message!(Errno::EINVAL, "the swap device is too small");
+        }
+        let mut slots = bitvec![0; nr_slots];
+        slots.set(0, true);
+        for i in 0..nr_bad_pages {
+            let offset = SWAP_BAD_PAGES_OFFSET + i * core::mem::size_of::<u32>();
+            let bad_page = block_device.read_val::<u32>(offset)? as usize;
+            if bad_page > 0 && bad_page < nr_slots {
+                slots.set(bad_page, true);
+            }
+        }
+        let nr_pages = slots.count_zeros();
+
+        Ok(Self {
+            path,
+            id,
+            block_device,
+            priority,
+            slots: SpinLock::new(slots),
+            nr_pages,
+            nr_used_pages: AtomicUsize::new(0),
+            is_active: AtomicBool::new(true),
+        })
+    }
+
+    /// Returns the path of the device.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the priority of the device.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Returns the number of pages that can be swapped out to the device.
+    pub fn nr_pages(&self) -> usize {
+        self.nr_pages
+    }
+
+    /// Returns the number of pages that are swapped out to the device.
+    pub fn nr_used_pages(&self) -> usize {
+        self.nr_used_pages.load(Ordering::Relaxed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_active.load(Ordering::Relaxed)
+    }
+
+    fn alloc_slot(&self) -> Option<usize> {
+        let mut slots = self.slots.lock_irq_disabled();
+        let slot = slots.first_zero()?;
+        slots.set(slot, true);
+        self.nr_used_pages.fetch_add(1, Ordering::Relaxed);
+        Some(slot)
+    }
+
+    fn free_slot(&self, slot: usize) {
+        let mut slots = self.slots.lock_irq_disabled();
+        debug_assert!(slots[slot]);
+        slots.set(slot, false);
+        self.nr_used_pages.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Debug for SwapDevice {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("SwapDevice")
+            .field("path", &self.path)
+            .field("priority", &self.priority)
+            .field("nr_pages", &self.nr_pages)
+            .field("nr_used_pages", &self.nr_used_pages())
+            .finish()
+    }
+}
+
+/// A slot on a swap device where a page is swapped out to.
+///
+/// The slot is freed when the entry is dropped.
+#[derive(Debug)]
+pub struct SwapEntry {
+    device: Arc<SwapDevice>,
+    slot: usize,
+}
+
+impl SwapEntry {
+    /// Writes the page to the slot.
+    fn write(&self, frame: &VmFrame) -> Result<()> {
+        let status = self
+            .device
+            .block_device
+            .write_block_sync(Bid::new(self.slot as u64), frame)?;
+        match status {
+            BioStatus::Complete => Ok(()),
+            err_status => Err(Error::from(err_status)),
+        }
+    }
+
+    /// Reads the page back from the slot.
+    pub fn read(&self, frame: &VmFrame) -> Result<()> {
+        let status = self
+            .device
+            .block_device
+            .read_block_sync(Bid::new(self.slot as u64), frame)?;
+        match status {
+            BioStatus::Complete => Ok(()),
+            err_status => Err(Error::from(err_status)),
+        }
+    }
+
+    /// Returns whether the slot is on the device.
+    pub fn is_on(&self, device: &Arc<SwapDevice>) -> bool {
+        Arc::ptr_eq(&self.device, device)
+    }
+}
+
+impl Drop for SwapEntry {
+    fn drop(&mut self) {
+        self.device.free_slot(self.slot);
+    }
+}
+
+/// The enabled swap devices, sorted by the priorities in the descending order.
+static SWAP_DEVICES: Mutex<Vec<Arc<SwapDevice>>> = Mutex::new(Vec::new());
+
+/// The priority of the last device that is enabled without a specified priority.
+static LEAST_PRIORITY: AtomicI32 = AtomicI32::new(-1);
+
+/// Enables swapping to the block device.
+///
+/// If `priority` is `None`, the device is used after all the enabled devices.
+pub fn swap_on(
+    path: String,
+    id: DeviceId,
+    block_device: Arc<dyn BlockDevice>,
+    priority: Option<i32>,
+) -> Result<()> {
+    let mut devices = SWAP_DEVICES.lock();
+    if devices
+        .iter()
+        .any(|device| u64::from(device.id) == u64::from(id))
+    {
+        return_errno_with_message!(Errno::EBUSY, "the device is already used for swapping");
+    }
+
+    let priority = priority.unwrap_or_else(|| LEAST_PRIORITY.fetch_sub(1, Ordering::Relaxed) - 1);
+    let device = Arc::new(SwapDevice::open(path, id, block_device, priority)?);
+    let pos = devices.partition_point(|other| other.priority >= priority);
+    devices.insert(pos, device);
+    Ok(())
+}
+
+/// Disables swapping to the block device, reading all the pages on it back to the memory.
+pub fn swap_off(id: DeviceId) -> Result<()> {
+    let device = SWAP_DEVICES
+        .lock()
+        .iter()
+        .find(|device| u64::from(device.id) == u64::from(id))
+        .cloned();
+    let Some(device) = device else {
+        return_errno_with_message!(Errno::EINVAL, "the device is not used for swapping");
+    };
+    // Another `swapoff` may be in progress on the same device.
+    if !device.is_active.swap(false, Ordering::Relaxed) {
+        return_errno_with_message!(Errno::EINVAL, "the device is not used for swapping");
+    }
+
+    let processes: Vec<Arc<Process>> = process_table::process_table().iter().cloned().collect();
+    for process in processes {
+        let Ok(vm_mappings) = process.root_vmar().vm_mappings() else {
+            continue;
+        };
+        for vm_mapping in vm_mappings {
+            if let Err(err) = vm_mapping.vmo().swap_in_from(&device) {
+                device.is_active.store(true, Ordering::Relaxed);
+                return Err(err);
+            }
+        }
+    }
+
+    // The pages of the vmos that are no longer mapped cannot be found.
+    if device.nr_used_pages() > 0 {
+        device.is_active.store(true, Ordering::Relaxed);
+        return_errno_with_message!(Errno::EBUSY, "some pages cannot be swapped in");
+    }
+    SWAP_DEVICES
+        .lock()
+        .retain(|other| !Arc::ptr_eq(other, &device));
+    Ok(())
+}
+
+/// Returns the enabled swap devices.
+pub fn swap_devices() -> Vec<Arc<SwapDevice>> {
+    SWAP_DEVICES.lock().clone()
+}
+
+/// Returns the total number of pages and the number of free pages on the swap devices.
+pub fn swap_pages() -> (usize, usize) {
+    let devices = SWAP_DEVICES.lock();
+    let nr_pages: usize = devices.iter().map(|device| device.nr_pages()).sum();
+    let nr_used_pages: usize = devices.iter().map(|device| device.nr_used_pages()).sum();
+    (nr_pages, nr_pages - nr_used_pages)
+}
+
+/// Allocates a slot on the active swap device of the highest priority that is not full.
+fn alloc_entry() -> Option<SwapEntry> {
+    let devices = SWAP_DEVICES.try_lock()?;
+    devices
+        .iter()
+        .filter(|device| device.is_active())
+        .find_map(|device| {
+            let slot = device.alloc_slot()?;
+            Some(SwapEntry {
+                device: device.clone(),
+                slot,
+            })
+        })
+}
+
+/// Swaps out the page, returning the slot that the page is written to.
+///
+/// `None` is returned if all the swap devices are full.
+pub fn swap_out(frame: &VmFrame) -> Result<Option<SwapEntry>> {
+    let Some(entry) = alloc_entry() else {
+        return Ok(None);
+    };
+    entry.write(frame)?;
+    Ok(Some(entry))
+}
+
+/// Returns whether there are free slots on the swap devices.
+pub fn can_swap_out() -> bool {
+    let Some(devices) = SWAP_DEVICES.try_lock() else {
+        return false;
+    };
+    devices
+        .iter()
+        .any(|device| device.is_active() && device.nr_used_pages() < device.nr_pages())
+}
+
+/// The process to start the next reclaim with.
+static NEXT_RECLAIM_PID: AtomicU32 = AtomicU32::new(0);
+
+/// Swaps out at most `nr_pages` pages that are not recently accessed, returning the number
+/// of the pages swapped out.
+///
+/// The processes are scanned in turn, starting from where the last reclaim stops. The pages
+/// accessed since the last scan are given a second chance, so they are only swapped out in
+/// the second pass if they are not accessed in between. The mappings that are being used
+/// are skipped, so this can be called when allocating memory for them.
+pub fn reclaim(nr_pages: usize) -> usize {
+    if !can_swap_out() {
+        return 0;
+    }
+
+    let next_pid = NEXT_RECLAIM_PID.load(Ordering::Relaxed);
+    let processes: Vec<Arc<Process>> = {
+        let mut processes: Vec<Arc<Process>> =
+            process_table::process_table().iter().cloned().collect();
+        let pos = processes.partition_point(|process| process.pid() < next_pid);
+        processes.rotate_left(pos);
+        processes
+    };
+
+    let mut nr_reclaimed = 0;
+    for _ in 0..2 {
+        for process in processes.iter() {
+            nr_reclaimed += process.root_vmar().reclaim(nr_pages - nr_reclaimed);
+            if nr_reclaimed >= nr_pages {
+                NEXT_RECLAIM_PID.store(process.pid() + 1, Ordering::Relaxed);
+                return nr_reclaimed;
+            }
+        }
+    }
+    nr_reclaimed
+}
@@ -16,7 +16,7 @@ use aster_rights::Rights;
 
 use self::{
     interval::{Interval, IntervalSet},
-    vm_mapping::VmMapping,
+    vm_mapping::{VmMapping, VmMappingFlags},
 };
 use super::page_fault_handler::PageFaultHandler;
 use crate::{prelude::*, vm::perms::VmPerms};
@@ -111,6 +111,9 @@ struct VmarInner {
     vm_mappings: BTreeMap<Vaddr, Arc<VmMapping>>,
     /// Free regions that can be used for creating child vmar or mapping vmos
     free_regions: BTreeMap<Vaddr, FreeRegion>,
+    /// Whether the mappings added in the future are locked, and if so, whether their pages
+    /// are populated only on faults
+    future_lock: Option<bool>,
 }
 
 impl VmarInner {
@@ -120,6 +123,7 @@ impl VmarInner {
             child_vmar_s: BTreeMap::new(),
             vm_mappings: BTreeMap::new(),
             free_regions: BTreeMap::new(),
+            future_lock: None,
         }
     }
 }
@@ -165,6 +169,7 @@ impl Vmar_ {
             child_vmar_s: BTreeMap::new(),
             vm_mappings: BTreeMap::new(),
             free_regions,
+            future_lock: None,
         };
         Vmar_::new(vmar_inner, VmSpace::new(), 0, ROOT_VMAR_CAP_ADDR, None)
     }
@@ -279,11 +284,13 @@ impl Vmar_ {
         if !self.is_root_vmar() {
             return_errno_with_message!(Errno::EACCES, "The vmar is not root vmar");
         }
+        self.unlock_all()?;
         self.vm_space.clear();
         let mut inner = self.inner.lock();
         inner.child_vmar_s.clear();
         inner.vm_mappings.clear();
         inner.free_regions.clear();
+        inner.future_lock = None;
         let root_region = FreeRegion::new(ROOT_VMAR_LOWEST_ADDR..ROOT_VMAR_CAP_ADDR);
         inner.free_regions.insert(root_region.start(), root_region);
         Ok(())
@@ -302,6 +309,9 @@ impl Vmar_ {
         inner.free_regions.append(&mut free_regions);
 
         for vm_mapping in inner.vm_mappings.values() {
+            if vm_mapping.flags().contains(VmMappingFlags::LOCKED) {
+                vm_mapping.set_pages_locked(&vm_mapping.range(), false)?;
+            }
             vm_mapping.unmap(&vm_mapping.range(), true)?;
             let free_region = FreeRegion::new(vm_mapping.range());
             free_regions.insert(free_region.start(), free_region);
@@ -386,6 +396,280 @@ impl Vmar_ {
         Ok(())
     }
 
+    /// Resizes the mapped pages in `old_range` to `new_size` bytes, and returns the new address.
+    ///
+    /// The pages are moved to `new_addr` if it is specified, or to a free region if they cannot
+    /// be resized in place and `may_move` is true.
+    pub fn remap(
+        &self,
+        old_range: Range<usize>,
+        new_size: usize,
+        new_addr: Option<Vaddr>,
+        may_move: bool,
+    ) -> Result<Vaddr> {
+        debug_assert!(old_range.start % PAGE_SIZE == 0);
+        debug_assert!(old_range.end % PAGE_SIZE == 0);
+        debug_assert!(new_size % PAGE_SIZE == 0);
+
+        let vm_mapping = self.get_vm_mapping(old_range.start)?;
+        if old_range.end > vm_mapping.range().end {
+            return_errno_with_message!(Errno::EFAULT, "the old range is not in a single mapping");
+        }
+
+        if let Some(new_addr) = new_addr {
+            let new_range = new_addr..(new_addr + new_size);
+            if is_intersected(&old_range, &new_range) {
+                return_errno_with_message!(
+                    Errno::EINVAL,
+                    "the new range overlaps with the old range"
+                );
+            }
+            self.destroy(new_range.clone())?;
+            if self
+                .find_free_region_for_child(Some(new_addr), new_size, PAGE_SIZE)
+                .is_err()
+            {
+                return_errno_with_message!(Errno::EINVAL, "the new range cannot be mapped");
+            }
+            self.move_mapping(&vm_mapping, old_range, new_range)?;
+            return Ok(new_addr);
+        }
+
+        if new_size <= old_range.len() {
+            if new_size < old_range.len() {
+                self.destroy((old_range.start + new_size)..old_range.end)?;
+            }
+            return Ok(old_range.start);
+        }
+
+        // The mapping can grow in place only if the pages after it are free.
+        let can_grow_in_place = old_range.end == vm_mapping.range().end
+            && old_range.start.checked_add(new_size).is_some()
+            && self
+                .find_free_region_for_child(
+                    Some(old_range.end),
+                    new_size - old_range.len(),
+                    PAGE_SIZE,
+                )
+                .is_ok();
+        let new_addr = if can_grow_in_place {
+            old_range.start
+        } else if may_move {
+            let (_, new_addr) = self
+                .find_free_region_for_child(None, new_size, PAGE_SIZE)
+                .map_err(|_| {
+                    Error::with_message(Errno::ENOMEM, "no free region for the mapping")
+                })?;
+            new_addr
+        } else {
+            return_errno_with_message!(Errno::ENOMEM, "the mapping cannot grow in place");
+        };
+        self.move_mapping(&vm_mapping, old_range, new_addr..(new_addr + new_size))?;
+        Ok(new_addr)
+    }
+
+    /// Moves the pages in `old_range` of the mapping to `new_range`, which must be free except
+    /// for the part overlapped with `old_range`.
+    fn move_mapping(
+        &self,
+        vm_mapping: &Arc<VmMapping>,
+        old_range: Range<usize>,
+        new_range: Range<usize>,
+    ) -> Result<()> {
+        let new_mapping = vm_mapping.new_remapped(&old_range, new_range.clone())?;
+        self.destroy(old_range)?;
+        self.allocate_free_region_for_vmo(
+            new_range.len(),
+            new_range.len(),
+            Some(new_range.start),
+            PAGE_SIZE,
+            false,
+        )?;
+        // The locks of the old pages are released when they are destroyed, so the moved pages
+        // need to be locked again.
+        let is_locked = new_mapping.flags().contains(VmMappingFlags::LOCKED);
+        self.add_mapping(new_mapping.clone())?;
+        if is_locked {
+            new_mapping.set_pages_locked(&new_range, true)?;
+            new_mapping.populate(&new_range)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the updates of the shared file mappings in the range back to the files.
+    ///
+    /// If `wait` is false, the updated pages are only marked, and they will be written back
+    /// later.
+    pub fn sync(&self, range: Range<usize>, wait: bool) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.sync(&intersected_range, wait)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether each page in the range is resident in memory.
+    pub fn resident_pages(&self, range: Range<usize>) -> Result<Vec<bool>> {
+        // The range is checked before allocating memory for it, since it is from the user.
+        let vm_mappings = self.mappings_in_range(&range)?;
+        let mut resident_pages = Vec::with_capacity(range.len() / PAGE_SIZE);
+        for vm_mapping in vm_mappings {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            for addr in intersected_range.step_by(PAGE_SIZE) {
+                resident_pages.push(vm_mapping.is_page_resident(addr));
+            }
+        }
+        Ok(resident_pages)
+    }
+
+    /// Locks the pages in the range in memory, or unlocks them if `locked` is false.
+    ///
+    /// The locked pages are also populated, unless `on_fault` is true.
+    pub fn lock(&self, range: Range<usize>, locked: bool, on_fault: bool) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.set_locked(&intersected_range, locked, on_fault)?;
+        }
+        Ok(())
+    }
+
+    /// Locks all the pages in memory, or unlocks them if `locked` is false.
+    ///
+    /// The locked pages are also populated, unless `on_fault` is true.
+    pub fn lock_all(&self, locked: bool, on_fault: bool) -> Result<()> {
+        let mut vm_mappings = Vec::new();
+        self.collect_vm_mappings(&mut vm_mappings);
+        for vm_mapping in vm_mappings {
+            vm_mapping.set_locked(&vm_mapping.range(), locked, on_fault)?;
+        }
+        Ok(())
+    }
+
+    /// Releases the locks of all the pages, without changing the flags of the mappings.
+    fn unlock_all(&self) -> Result<()> {
+        let mut vm_mappings = Vec::new();
+        self.collect_vm_mappings(&mut vm_mappings);
+        for vm_mapping in vm_mappings {
+            if vm_mapping.flags().contains(VmMappingFlags::LOCKED) {
+                vm_mapping.set_pages_locked(&vm_mapping.range(), false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets whether the mappings added in the future are locked, and if so, whether their
+    /// pages are populated only on faults.
+    pub fn set_future_lock(&self, future_lock: Option<bool>) {
+        self.inner.lock().future_lock = future_lock;
+    }
+
+    /// Sets or clears the flags for the mappings in the range.
+    pub fn update_flags(
+        &self,
+        range: Range<usize>,
+        set_flags: VmMappingFlags,
+        clear_flags: VmMappingFlags,
+    ) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.update_flags(&intersected_range, set_flags, clear_flags)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the pages in the range of the file mappings from the files in advance.
+    pub fn prefetch(&self, range: Range<usize>) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.prefetch(&intersected_range)?;
+        }
+        Ok(())
+    }
+
+    /// Discards the pages in the range, which may be freed lazily if `lazy` is true.
+    pub fn discard(&self, range: Range<usize>, lazy: bool) -> Result<()> {
+        let vm_mappings = self.mappings_in_range(&range)?;
+        // Check all the mappings first, so that no pages are discarded on failures.
+        for vm_mapping in &vm_mappings {
+            if vm_mapping.flags().contains(VmMappingFlags::LOCKED) {
+                return_errno_with_message!(Errno::EINVAL, "the pages in the range are locked");
+            }
+        }
+        for vm_mapping in vm_mappings {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.discard(&intersected_range, lazy)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the pages in the range of the shared mappings, together with their contents.
+    pub fn remove(&self, range: Range<usize>) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.remove(&intersected_range)?;
+        }
+        Ok(())
+    }
+
+    /// Swaps out the pages in the range that can be swapped out.
+    pub fn swap_out(&self, range: Range<usize>) -> Result<()> {
+        for vm_mapping in self.mappings_in_range(&range)? {
+            let intersected_range = get_intersected_range(&vm_mapping.range(), &range);
+            vm_mapping.swap_out(&intersected_range)?;
+        }
+        Ok(())
+    }
+
+    /// Swaps out at most `nr_pages` pages that are not recently accessed, returning the number
+    /// of pages swapped out.
+    ///
+    /// The vmars and the mappings that are being used are skipped without waiting.
+    fn reclaim(&self, nr_pages: usize) -> usize {
+        let Some(inner) = self.inner.try_lock() else {
+            return 0;
+        };
+        let vm_mappings: Vec<Arc<VmMapping>> = inner.vm_mappings.values().cloned().collect();
+        let child_vmars: Vec<Arc<Vmar_>> = inner.child_vmar_s.values().cloned().collect();
+        drop(inner);
+
+        let mut nr_reclaimed = 0;
+        for vm_mapping in vm_mappings {
+            if nr_reclaimed >= nr_pages {
+                return nr_reclaimed;
+            }
+            nr_reclaimed += vm_mapping.reclaim(nr_pages - nr_reclaimed);
+        }
+        for child_vmar in child_vmars {
+            if nr_reclaimed >= nr_pages {
+                return nr_reclaimed;
+            }
+            nr_reclaimed += child_vmar.reclaim(nr_pages - nr_reclaimed);
+        }
+        nr_reclaimed
+    }
+
+    /// Returns the mappings that intersect with the range, which must be fully mapped.
+    fn mappings_in_range(&self, range: &Range<usize>) -> Result<Vec<Arc<VmMapping>>> {
+        let inner = self.inner.lock();
+        let vm_mappings: Vec<Arc<VmMapping>> =
+            inner.vm_mappings.find(range).into_iter().cloned().collect();
+
+        // The mappings are sorted by the address, so there is a hole if a mapping does not
+        // start where the previous one ends.
+        let mut mapped_end = range.start;
+        for vm_mapping in &vm_mappings {
+            if vm_mapping.map_to_addr() > mapped_end {
+                break;
+            }
+            mapped_end = vm_mapping.range().end;
+        }
+        if mapped_end < range.end {
+            return_errno_with_message!(Errno::ENOMEM, "the range is not fully mapped");
+        }
+
+        Ok(vm_mappings)
+    }
+
     fn is_destroyed(&self) -> bool {
         self.inner.lock().is_destroyed
     }
@@ -497,6 +781,7 @@ impl Vmar_ {
             child_vmar_s: BTreeMap::new(),
             vm_mappings: BTreeMap::new(),
             free_regions: child_regions,
+            future_lock: None,
         };
         let child_vmar_ = Vmar_::new(
             child_vmar_inner,
@@ -582,11 +867,20 @@ impl Vmar_ {
     }
 
     /// Map a vmo to this vmar.
-    pub fn add_mapping(&self, mapping: Arc<VmMapping>) {
-        self.inner
-            .lock()
-            .vm_mappings
-            .insert(mapping.map_to_addr(), mapping);
+    ///
+    /// The mapping is locked if the locking of future mappings is requested.
+    pub fn add_mapping(&self, mapping: Arc<VmMapping>) -> Result<()> {
+        let future_lock = {
+            let mut inner = self.inner.lock();
+            inner
+                .vm_mappings
+                .insert(mapping.map_to_addr(), mapping.clone());
+            inner.future_lock
+        };
+        if let Some(on_fault) = future_lock {
+            mapping.set_locked(&mapping.range(), true, on_fault)?;
+        }
+        Ok(())
     }
 
     fn allocate_free_region_for_vmo(
@@ -727,6 +1021,17 @@ impl Vmar_ {
 
         // Clone vm mappings.
         for (vm_mapping_base, vm_mapping) in &inner.vm_mappings {
+            // The range of a mapping that is not inherited becomes free in the child.
+            if vm_mapping.flags().contains(VmMappingFlags::DONT_FORK) {
+                vm_mapping.unmap_from_copied(new_vmar_.vm_space())?;
+                let free_region = FreeRegion::new(vm_mapping.range());
+                new_vmar_
+                    .inner
+                    .lock()
+                    .free_regions
+                    .insert(free_region.start(), free_region);
+                continue;
+            }
             let new_mapping = Arc::new(vm_mapping.new_cow(&new_vmar_)?);
             new_vmar_
                 .inner
@@ -734,9 +1039,20 @@ impl Vmar_ {
                 .vm_mappings
                 .insert(*vm_mapping_base, new_mapping);
         }
+        drop(inner);
+        new_vmar_.merge_continuous_regions();
         Ok(new_vmar_)
     }
 
+    /// Collects the mappings in the vmar and its descendants.
+    fn collect_vm_mappings(&self, vm_mappings: &mut Vec<Arc<VmMapping>>) {
+        let inner = self.inner.lock();
+        vm_mappings.extend(inner.vm_mappings.values().cloned());
+        for child_vmar in inner.child_vmar_s.values() {
+            child_vmar.collect_vm_mappings(vm_mappings);
+        }
+    }
+
     /// get mapped vmo at given offset
     fn get_vm_mapping(&self, offset: Vaddr) -> Result<Arc<VmMapping>> {
         let inner = self.inner.lock();
@@ -764,6 +1080,11 @@ impl<R> Vmar<R> {
         self.0.size
     }
 
+    /// Returns an identifier of the VMAR, which is unique among all the alive VMARs.
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+
     /// Get mapped vmo at given offset.
     /// TODO: improve the searching algorithm.
     pub fn get_vm_mapping(&self, offset: Vaddr) -> Result<Arc<VmMapping>> {
@@ -771,6 +1092,127 @@ impl<R> Vmar<R> {
         self.check_rights(rights)?;
         self.0.get_vm_mapping(offset)
     }
+
+    /// Returns all the mappings in the VMAR and its child VMARs, sorted by the address.
+    pub fn vm_mappings(&self) -> Result<Vec<Arc<VmMapping>>> {
+        let rights = Rights::all();
+        self.check_rights(rights)?;
+        let mut vm_mappings = Vec::new();
+        self.0.collect_vm_mappings(&mut vm_mappings);
+        vm_mappings.sort_by_key(|vm_mapping| vm_mapping.map_to_addr());
+        Ok(vm_mappings)
+    }
+
+    /// Resizes the mapped pages in `old_range` to `new_size` bytes, possibly moving them, and
+    /// returns the new address.
+    ///
+    /// The old range must be within a single mapping. The pages are moved to `new_addr` if it is
+    /// specified, replacing any mappings there. Otherwise, they are moved to a free region only
+    /// if they cannot be resized in place and `may_move` is true.
+    ///
+    /// All the addresses and sizes must be page-aligned.
+    pub fn remap(
+        &self,
+        old_range: Range<usize>,
+        new_size: usize,
+        new_addr: Option<Vaddr>,
+        may_move: bool,
+    ) -> Result<Vaddr> {
+        self.0.remap(old_range, new_size, new_addr, may_move)
+    }
+
+    /// Writes the updates of the shared file mappings in the specified range back to the
+    /// files.
+    ///
+    /// The range must be page-aligned and completely mapped. If `wait` is false, the updated
+    /// pages are scheduled to be written back instead.
+    pub fn sync(&self, range: Range<usize>, wait: bool) -> Result<()> {
+        self.0.sync(range, wait)
+    }
+
+    /// Returns whether each page in the specified range is resident in memory.
+    ///
+    /// The range must be page-aligned and completely mapped.
+    pub fn resident_pages(&self, range: Range<usize>) -> Result<Vec<bool>> {
+        self.0.resident_pages(range)
+    }
+
+    /// Locks the pages in the specified range in memory, or unlocks them if `locked` is false.
+    ///
+    /// The range must be page-aligned and completely mapped. The locked pages are also
+    /// populated, unless `on_fault` is true.
+    pub fn lock(&self, range: Range<usize>, locked: bool, on_fault: bool) -> Result<()> {
+        self.0.lock(range, locked, on_fault)
+    }
+
+    /// Locks all the pages in memory, or unlocks them if `locked` is false.
+    ///
+    /// The locked pages are also populated, unless `on_fault` is true.
+    pub fn lock_all(&self, locked: bool, on_fault: bool) -> Result<()> {
+        self.0.lock_all(locked, on_fault)
+    }
+
+    /// Sets whether the mappings created in the future are locked, and if so, whether their
+    /// pages are populated only on faults.
+    ///
+    /// The setting is cleared when the VMAR is cleared, e.g., on `execve`.
+    pub fn set_future_lock(&self, future_lock: Option<bool>) {
+        self.0.set_future_lock(future_lock)
+    }
+
+    /// Sets `set_flags` and clears `clear_flags` for the mappings in the specified range.
+    ///
+    /// The range must be page-aligned and completely mapped.
+    pub fn update_flags(
+        &self,
+        range: Range<usize>,
+        set_flags: VmMappingFlags,
+        clear_flags: VmMappingFlags,
+    ) -> Result<()> {
+        self.0.update_flags(range, set_flags, clear_flags)
+    }
+
+    /// Reads the pages of the file mappings in the specified range from the files in advance.
+    ///
+    /// The range must be page-aligned and completely mapped.
+    pub fn prefetch(&self, range: Range<usize>) -> Result<()> {
+        self.0.prefetch(range)
+    }
+
+    /// Discards the pages in the specified range.
+    ///
+    /// The range must be page-aligned and completely mapped, and none of the pages may be
+    /// locked. The later accesses to the pages of private mappings will see zero-filled pages
+    /// or the contents of the files. If `lazy` is true, the pages may be freed later, which is
+    /// only allowed for private anonymous mappings.
+    pub fn discard(&self, range: Range<usize>, lazy: bool) -> Result<()> {
+        self.0.discard(range, lazy)
+    }
+
+    /// Removes the pages in the specified range of shared mappings, so that the later accesses
+    /// will see zero-filled pages.
+    ///
+    /// The range must be page-aligned and completely mapped by writable shared mappings.
+    pub fn remove(&self, range: Range<usize>) -> Result<()> {
+        self.0.remove(range)
+    }
+
+    /// Swaps out the pages in the specified range.
+    ///
+    /// The range must be page-aligned and completely mapped. The pages that cannot be swapped
+    /// out, e.g., the locked pages or the pages shared with others, are skipped.
+    pub fn swap_out(&self, range: Range<usize>) -> Result<()> {
+        self.0.swap_out(range)
+    }
+
+    /// Swaps out at most `nr_pages` pages that are not recently accessed, returning the number
+    /// of pages swapped out.
+    ///
+    /// The mappings that are being used are skipped, so this can be called when the memory is
+    /// short, even if the VMAR is being used by the caller.
+    pub fn reclaim(&self, nr_pages: usize) -> usize {
+        self.0.reclaim(nr_pages)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -2,15 +2,18 @@
 
 use core::ops::Range;
 
-use aster_frame::vm::{VmFrame, VmFrameVec, VmIo, VmMapOptions, VmPerm, VmSpace};
+use align_ext::AlignExt;
+use aster_frame::vm::{VmFrame, VmFrameVec, VmIo, VmMapOptions, VmPerm, VmSpace, HUGE_PAGE_SIZE};
 
-use super::{interval::Interval, is_intersected, Vmar, Vmar_};
+use super::{get_intersected_range, interval::Interval, is_intersected, Vmar, Vmar_};
 use crate::{
+    fs::utils::Dentry,
     prelude::*,
     vm::{
         perms::VmPerms,
+        swap,
         vmar::Rights,
-        vmo::{get_page_idx_range, Vmo, VmoChildOptions, VmoRightsOp},
+        vmo::{get_page_idx_range, Vmo, VmoChildOptions, VmoFlags, VmoOptions, VmoRightsOp},
     },
 };
 
@@ -24,6 +27,33 @@ pub struct VmMapping {
     parent: Weak<Vmar_>,
     /// The mapped vmo. The mapped vmo is with dynamic capability.
     vmo: Vmo<Rights>,
+    /// Whether the mapping is shared among processes, i.e., the changes are visible to other
+    /// processes mapping the same vmo, and carried through fork.
+    is_shared: bool,
+    /// The file that the vmo is mapped from, if any.
+    file: Option<MappedFile>,
+}
+
+/// The file that a mapping is backed by.
+#[derive(Clone)]
+struct MappedFile {
+    dentry: Arc<Dentry>,
+    /// The offset in the file where the vmo starts, in bytes.
+    offset: usize,
+}
+
+impl MappedFile {
+    fn page_cache_vmo(&self) -> Result<Vmo<Rights>> {
+        Ok(self
+            .dentry
+            .inode()
+            .page_cache()
+            .ok_or(Error::with_message(
+                Errno::EBADF,
+                "File does not have page cache",
+            ))?
+            .to_dyn())
+    }
 }
 
 impl VmMapping {
@@ -34,6 +64,8 @@ impl VmMapping {
             inner: Mutex::new(inner),
             parent: self.parent.clone(),
             vmo,
+            is_shared: self.is_shared,
+            file: self.file.clone(),
         })
     }
 }
@@ -54,6 +86,22 @@ struct VmMappingInner {
     /// The permission of pages in the mapping.
     /// All pages within the same VmMapping have the same permission.
     perm: VmPerm,
+    /// The flags of the mapping, which are usually set by `madvise` or `mlock`.
+    flags: VmMappingFlags,
+}
+
+bitflags! {
+    /// The flags of a mapping.
+    pub struct VmMappingFlags: u32 {
+        /// The mapping is not inherited by the child process after fork.
+        const DONT_FORK = 1 << 0;
+        /// The pages of the mapping are locked in memory.
+        const LOCKED = 1 << 1;
+        /// The mapping is worth backing with huge pages.
+        const HUGE_PAGE = 1 << 2;
+        /// The mapping is not worth backing with huge pages.
+        const NO_HUGE_PAGE = 1 << 3;
+    }
 }
 
 impl Interval<usize> for Arc<VmMapping> {
@@ -73,6 +121,8 @@ impl VmMapping {
             offset,
             align,
             can_overwrite,
+            is_shared,
+            file,
         } = option;
         let Vmar(parent_vmar, _) = parent;
         let vmo_size = vmo.size();
@@ -96,12 +146,15 @@ impl VmMapping {
             is_destroyed: false,
             mapped_pages: BTreeSet::new(),
             perm: VmPerm::from(perms),
+            flags: VmMappingFlags::empty(),
         };
 
         Ok(Self {
             inner: Mutex::new(vm_mapping_inner),
             parent: Arc::downgrade(&parent_vmar),
             vmo: vmo.to_dyn(),
+            is_shared,
+            file,
         })
     }
 
@@ -110,20 +163,10 @@ impl VmMapping {
     ///
     /// Note: Since such new mappings will intersect with the current mapping,
     /// making sure that when adding the new mapping into a Vmar, the current mapping in the Vmar will be removed.
-    fn clone_partial(
-        &self,
-        range: Range<usize>,
-        new_perm: Option<VmPerm>,
-    ) -> Result<Arc<VmMapping>> {
+    fn clone_partial(&self, range: Range<usize>) -> Result<Arc<VmMapping>> {
         let partial_mapping = Arc::new(self.try_clone()?);
-        // Adjust the mapping range and the permission.
-        {
-            let mut inner = partial_mapping.inner.lock();
-            inner.shrink_to(range);
-            if let Some(perm) = new_perm {
-                inner.perm = perm;
-            }
-        }
+        // Adjust the mapping range.
+        partial_mapping.inner.lock().shrink_to(range);
         Ok(partial_mapping)
     }
 
@@ -131,20 +174,30 @@ impl VmMapping {
         &self.vmo
     }
 
+    /// Returns whether the mapping is shared among processes.
+    pub fn is_shared(&self) -> bool {
+        self.is_shared
+    }
+
+    /// Returns the file that the mapping is backed by, and the offset in the file where the
+    /// mapping starts.
+    pub fn file(&self) -> Option<(&Arc<Dentry>, usize)> {
+        self.file
+            .as_ref()
+            .map(|file| (&file.dentry, file.offset + self.vmo_offset()))
+    }
+
     /// Set the entries in the page table associated with the current `VmMapping` to read-only.
     pub(super) fn set_pt_read_only(&self, vm_space: &VmSpace) -> Result<()> {
         let map_inner = self.inner.lock();
-        let mapped_addr = &map_inner.mapped_pages;
         let perm = map_inner.perm;
-        if !perm.contains(VmPerm::W) {
+        // The pages of a shared mapping are not copied on write, so they can stay writable.
+        if !perm.contains(VmPerm::W) || self.is_shared {
             return Ok(());
         }
 
-        for page_idx in mapped_addr {
-            let map_addr = map_inner.page_map_addr(*page_idx);
-            vm_space.protect(&(map_addr..map_addr + PAGE_SIZE), perm - VmPerm::W)?;
-        }
-        Ok(())
+        // The whole range is protected at once, so that the huge pages are not split.
+        vm_space.protect(&map_inner.range(), perm - VmPerm::W)
     }
 
     /// Add a new committed page and map it to vmspace. If copy on write is set, it's allowed to unmap the page at the same address.
@@ -184,6 +237,21 @@ impl VmMapping {
         self.inner.lock().vmo_offset
     }
 
+    /// Returns the permission of the pages in the mapping.
+    pub fn perm(&self) -> VmPerm {
+        self.inner.lock().perm
+    }
+
+    /// Returns the flags of the mapping.
+    pub fn flags(&self) -> VmMappingFlags {
+        self.inner.lock().flags
+    }
+
+    /// Returns the number of pages that are mapped to the page table.
+    pub fn nr_mapped_pages(&self) -> usize {
+        self.inner.lock().mapped_pages.len()
+    }
+
     pub fn read_bytes(&self, offset: usize, buf: &mut [u8]) -> Result<()> {
         let vmo_read_offset = self.vmo_offset() + offset;
 
@@ -257,14 +325,50 @@ impl VmMapping {
         let required_perm = if write { VmPerm::W } else { VmPerm::R };
         self.check_perm(&page_idx, &required_perm)?;
 
-        let frame = self.vmo.get_committed_frame(page_idx, write)?;
-
         // If read access to cow vmo triggers page fault, the map should be readonly.
         // If user next tries to write to the frame, another page fault will be triggered.
         let is_readonly = self.vmo.is_cow_vmo() && !write;
+        if self.map_huge_page(page_fault_addr, write, is_readonly)? {
+            return Ok(());
+        }
+
+        let frame = self.vmo.get_committed_frame(page_idx, write)?;
         self.map_one_page(page_idx, frame, is_readonly)
     }
 
+    /// Maps the whole huge page that contains the address, which is done opportunistically
+    /// on page faults.
+    ///
+    /// Returns whether the huge page is mapped. If not, the faulting page should be mapped
+    /// alone. A huge page is only mapped if it is fully inside the mapping and none of its
+    /// pages are mapped yet.
+    fn map_huge_page(&self, addr: Vaddr, write: bool, is_readonly: bool) -> Result<bool> {
+        let parent = self.parent.upgrade().unwrap();
+        let vm_space = parent.vm_space();
+        let mut inner = self.inner.lock();
+        if inner.flags.contains(VmMappingFlags::NO_HUGE_PAGE) {
+            return Ok(false);
+        }
+
+        let huge_page_addr = addr.align_down(HUGE_PAGE_SIZE);
+        let huge_page_range = huge_page_addr..(huge_page_addr + HUGE_PAGE_SIZE);
+        let range = inner.range();
+        if huge_page_range.start < range.start || huge_page_range.end > range.end {
+            return Ok(false);
+        }
+        let start_page_idx = (huge_page_addr - inner.map_to_addr + inner.vmo_offset) / PAGE_SIZE;
+        let page_idx_range = start_page_idx..(start_page_idx + HUGE_PAGE_SIZE / PAGE_SIZE);
+        if inner.mapped_pages.range(page_idx_range).next().is_some() {
+            return Ok(false);
+        }
+
+        let Some(frames) = self.vmo.get_committed_huge_page(start_page_idx, write)? else {
+            return Ok(false);
+        };
+        inner.map_pages(&self.vmo, vm_space, start_page_idx, frames, is_readonly)?;
+        Ok(true)
+    }
+
     /// Protect a specified range of pages in the mapping to the target perms.
     /// The VmMapping will split to maintain its property.
     ///
@@ -280,7 +384,8 @@ impl VmMapping {
         let rights = Rights::from(new_perms);
         self.vmo().check_rights(rights)?;
         // Protect permission for the perm in the VmMapping.
-        self.protect_with_subdivision(&range, VmPerm::from(new_perms))?;
+        let perm = VmPerm::from(new_perms);
+        self.update_with_subdivision(&range, |inner| inner.perm = perm)?;
         // Protect permission in the VmSpace.
         let vmar = self.parent.upgrade().unwrap();
         let vm_space = vmar.vm_space();
@@ -292,7 +397,11 @@ impl VmMapping {
     pub(super) fn new_cow(&self, new_parent: &Arc<Vmar_>) -> Result<VmMapping> {
         let VmMapping { inner, vmo, .. } = self;
 
-        let child_vmo = {
+        // A shared mapping keeps sharing the same vmo with the child, while a private mapping
+        // becomes copy-on-write.
+        let child_vmo = if self.is_shared {
+            vmo.dup()?
+        } else {
             let parent_vmo = vmo.dup().unwrap();
             let vmo_size = parent_vmo.size();
             VmoChildOptions::new_cow(parent_vmo, 0..vmo_size).alloc()?
@@ -307,6 +416,8 @@ impl VmMapping {
                 is_destroyed: inner.is_destroyed,
                 mapped_pages: BTreeSet::new(),
                 perm: inner.perm,
+                // Memory locks are not inherited by the child.
+                flags: inner.flags - VmMappingFlags::LOCKED,
             }
         };
 
@@ -314,17 +425,356 @@ impl VmMapping {
             inner: Mutex::new(new_inner),
             parent: Arc::downgrade(new_parent),
             vmo: child_vmo,
+            is_shared: self.is_shared,
+            file: self.file.clone(),
         })
     }
 
+    /// Creates a new mapping that maps the pages in `range` of the current mapping to `new_range`.
+    /// The new mapping is not added to the parent vmar yet.
+    ///
+    /// If the new range is larger, a shared mapping grows over its vmo, just as if it had been
+    /// mapped with the larger size. The pages of a private mapping are instead moved to a new
+    /// vmo of the larger size, so that the updates made through other private mappings of the
+    /// same vmo are invisible.
+    pub(super) fn new_remapped(
+        &self,
+        range: &Range<Vaddr>,
+        new_range: Range<Vaddr>,
+    ) -> Result<Arc<VmMapping>> {
+        let vmo_offset = self.vmo_offset() + (range.start - self.map_to_addr());
+        let old_size = range.len();
+        let new_size = new_range.len();
+        let mut file = self.file.clone();
+
+        let (vmo, vmo_offset) = if new_size <= old_size
+            || (self.is_shared && vmo_offset + new_size <= self.vmo.size())
+        {
+            (self.vmo.dup()?, vmo_offset)
+        } else if !self.is_shared {
+            // The pages are moved rather than shared with a COW child of the old vmo, so that
+            // they are not copied on write, and the old vmo does not keep them alive.
+            let new_vmo = if let Some(file) = &mut file {
+                file.offset += vmo_offset;
+                let page_cache_vmo = file.page_cache_vmo()?;
+                VmoChildOptions::new_cow(page_cache_vmo, file.offset..(file.offset + new_size))
+                    .alloc()?
+            } else {
+                VmoOptions::<Rights>::new(new_size)
+                    .flags(self.vmo.flags() & VmoFlags::HUGE_PAGE)
+                    .alloc()?
+            };
+            self.vmo
+                .move_pages_to(vmo_offset..(vmo_offset + old_size), &new_vmo, 0)?;
+            (new_vmo, 0)
+        } else if let Some(file) = &mut file {
+            let page_cache_vmo = file.page_cache_vmo()?;
+            file.offset += vmo_offset;
+            let slice_end = (file.offset + new_size)
+                .min(page_cache_vmo.size())
+                .max(file.offset);
+            let slice_vmo =
+                VmoChildOptions::new_slice_rights(page_cache_vmo, file.offset..slice_end)
+                    .alloc()?;
+            (slice_vmo, 0)
+        } else {
+            if !self.vmo.flags().contains(VmoFlags::RESIZABLE) {
+                return_errno_with_message!(Errno::ENOMEM, "the shared vmo cannot grow");
+            }
+            self.vmo.resize(vmo_offset + new_size)?;
+            (self.vmo.dup()?, vmo_offset)
+        };
+
+        let new_inner = VmMappingInner {
+            vmo_offset,
+            map_size: new_size,
+            map_to_addr: new_range.start,
+            is_destroyed: false,
+            mapped_pages: BTreeSet::new(),
+            perm: self.perm(),
+            flags: self.flags(),
+        };
+
+        Ok(Arc::new(VmMapping {
+            inner: Mutex::new(new_inner),
+            parent: self.parent.clone(),
+            vmo,
+            is_shared: self.is_shared,
+            file,
+        }))
+    }
+
+    /// Writes the updates of the pages in the range back to the file that the mapping is
+    /// backed by.
+    ///
+    /// The writes through the page table bypass the vmo, so the pages mapped as writable are
+    /// marked as updated first. If `wait` is false, the pages are only marked, and they will be
+    /// written back later.
+    pub(super) fn sync(&self, range: &Range<Vaddr>, wait: bool) -> Result<()> {
+        // The updates of private mappings are never written back.
+        if !self.is_shared || self.file.is_none() {
+            return Ok(());
+        }
+
+        let vmo_range = {
+            let inner = self.inner.lock();
+            let vmo_range = (range.start - inner.map_to_addr + inner.vmo_offset)
+                ..(range.end - inner.map_to_addr + inner.vmo_offset);
+            let parent = self.parent.upgrade().unwrap();
+            let vm_space = parent.vm_space();
+            for page_idx in inner.mapped_pages.range(get_page_idx_range(&vmo_range)) {
+                if vm_space.is_writable(inner.page_map_addr(*page_idx)) {
+                    self.vmo.update_page(*page_idx)?;
+                }
+            }
+            vmo_range
+        };
+
+        if wait {
+            self.vmo.sync(vmo_range)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the page at the address is resident in memory.
+    pub fn is_page_resident(&self, addr: Vaddr) -> bool {
+        let page_idx = (self.vmo_offset() + addr - self.map_to_addr()) / PAGE_SIZE;
+        page_idx * PAGE_SIZE < self.vmo.size() && self.vmo.is_page_committed(page_idx)
+    }
+
+    /// Sets or clears the flags for the pages in the range.
+    ///
+    /// Since this method will modify the `vm_mappings` in the vmar,
+    /// it should not be called during the direct iteration of the `vm_mappings`.
+    pub(super) fn update_flags(
+        &self,
+        range: &Range<Vaddr>,
+        set_flags: VmMappingFlags,
+        clear_flags: VmMappingFlags,
+    ) -> Result<()> {
+        let old_flags = self.flags();
+        let new_flags = (old_flags | set_flags) - clear_flags;
+        if old_flags == new_flags {
+            return Ok(());
+        }
+
+        self.update_with_subdivision(range, |inner| inner.flags = new_flags)
+    }
+
+    /// Locks the pages in the range in memory, or unlocks them if `locked` is false.
+    ///
+    /// The locked pages are also populated, unless `on_fault` is true.
+    ///
+    /// Since this method will modify the `vm_mappings` in the vmar,
+    /// it should not be called during the direct iteration of the `vm_mappings`.
+    pub(super) fn set_locked(
+        &self,
+        range: &Range<Vaddr>,
+        locked: bool,
+        on_fault: bool,
+    ) -> Result<()> {
+        if self.flags().contains(VmMappingFlags::LOCKED) != locked {
+            self.update_with_subdivision(range, |inner| {
+                inner.flags.set(VmMappingFlags::LOCKED, locked)
+            })?;
+            self.set_pages_locked(range, locked)?;
+        }
+
+        if locked && !on_fault {
+            self.populate(range)?;
+        }
+        Ok(())
+    }
+
+    /// Asks the vmo to keep the pages in the range in memory, or to stop keeping them if
+    /// `locked` is false.
+    ///
+    /// The flags of the mapping are not changed.
+    pub(super) fn set_pages_locked(&self, range: &Range<Vaddr>, locked: bool) -> Result<()> {
+        let vmo_range = self.vmo_range(range);
+        self.vmo.set_pages_locked(vmo_range, locked)
+    }
+
+    /// Commits the pages in the range, so that they are resident in memory.
+    pub(super) fn populate(&self, range: &Range<Vaddr>) -> Result<()> {
+        let vmo_range = self.vmo_range(range);
+        let vmo_size = self.vmo.size();
+        for page_idx in get_page_idx_range(&(vmo_range.start..vmo_range.end.min(vmo_size))) {
+            self.vmo.get_committed_frame(page_idx, false)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the pages in the range of a file mapping from the file in advance, so that the
+    /// later accesses do not need to wait for the I/O.
+    pub(super) fn prefetch(&self, range: &Range<Vaddr>) -> Result<()> {
+        // The pages of anonymous mappings are allocated quickly on demand.
+        if self.file.is_none() {
+            return Ok(());
+        }
+        self.populate(range)
+    }
+
+    /// Discards the pages in the range, as the user does not need their contents anymore.
+    ///
+    /// The pages of a private mapping are freed, so the later accesses will see zero-filled
+    /// pages or the contents of the file. The pages of a shared mapping are only unmapped, so
+    /// their contents are kept.
+    ///
+    /// If `lazy` is true, the pages are allowed to be freed later under memory pressure,
+    /// which is only supported for private anonymous mappings.
+    pub(super) fn discard(&self, range: &Range<Vaddr>, lazy: bool) -> Result<()> {
+        if self.flags().contains(VmMappingFlags::LOCKED) {
+            return_errno_with_message!(Errno::EINVAL, "the pages of the mapping are locked");
+        }
+        if lazy && (self.is_shared || self.file.is_some()) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "only private anonymous mappings can be freed lazily"
+            );
+        }
+
+        self.unmap(range, false)?;
+        // TODO: Keep the pages of lazily freed mappings until there is memory pressure.
+        if !self.is_shared {
+            let vmo_range = self.vmo_range(range);
+            let vmo_size = self.vmo.size();
+            if vmo_range.start < vmo_size {
+                self.vmo
+                    .decommit(vmo_range.start..vmo_range.end.min(vmo_size))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the pages in the range of a shared mapping, so that the later accesses through
+    /// any mappings will see zero-filled pages.
+    pub(super) fn remove(&self, range: &Range<Vaddr>) -> Result<()> {
+        if !self.is_shared {
+            return_errno_with_message!(Errno::EINVAL, "the mapping is not shared");
+        }
+        if self.flags().contains(VmMappingFlags::LOCKED) {
+            return_errno_with_message!(Errno::EINVAL, "the pages of the mapping are locked");
+        }
+        if !self.perm().contains(VmPerm::W) {
+            return_errno_with_message!(Errno::EACCES, "the mapping is not writable");
+        }
+
+        // The pages are cleared in place instead of being decommitted, since they may still be
+        // mapped by other processes.
+        let vmo_range = self.vmo_range(range);
+        let vmo_size = self.vmo.size();
+        if vmo_range.start >= vmo_size {
+            return Ok(());
+        }
+        let vmo_range = vmo_range.start..vmo_range.end.min(vmo_size);
+        if self.file.is_some() {
+            // TODO: Deallocate the blocks of the file.
+            return self.vmo.clear(vmo_range);
+        }
+        for page_idx in get_page_idx_range(&vmo_range) {
+            if self.vmo.is_page_committed(page_idx) || self.vmo.is_page_swapped(page_idx) {
+                self.vmo
+                    .clear((page_idx * PAGE_SIZE)..((page_idx + 1) * PAGE_SIZE))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Swaps out the mapped pages in the range, as the user does not expect to access them soon.
+    ///
+    /// The pages of locked or shared mappings, and the pages that cannot be swapped out (e.g.,
+    /// the pages shared with other processes or the page cache) are skipped.
+    pub(super) fn swap_out(&self, range: &Range<Vaddr>) -> Result<()> {
+        let mut inner = self.inner.lock();
+        let vmo_range = (range.start - inner.map_to_addr + inner.vmo_offset)
+            ..(range.end - inner.map_to_addr + inner.vmo_offset);
+        let page_idxs: Vec<usize> = inner
+            .mapped_pages
+            .range(get_page_idx_range(&vmo_range))
+            .cloned()
+            .collect();
+        self.swap_out_pages(&mut inner, page_idxs, usize::MAX, false)?;
+        Ok(())
+    }
+
+    /// Swaps out at most `nr_pages` mapped pages that are not accessed since they are last
+    /// checked, returning the number of pages swapped out.
+    ///
+    /// The accessed pages are only marked as not accessed, so they will be swapped out next
+    /// time if they are not accessed in between. The mapping is skipped without waiting if it
+    /// is being used.
+    pub(super) fn reclaim(&self, nr_pages: usize) -> usize {
+        let Some(mut inner) = self.inner.try_lock() else {
+            return 0;
+        };
+        let page_idxs: Vec<usize> = inner.mapped_pages.iter().cloned().collect();
+        self.swap_out_pages(&mut inner, page_idxs, nr_pages, true)
+            .unwrap_or(0)
+    }
+
+    fn swap_out_pages(
+        &self,
+        inner: &mut VmMappingInner,
+        page_idxs: Vec<usize>,
+        nr_pages: usize,
+        only_cold: bool,
+    ) -> Result<usize> {
+        // Only the private anonymous pages are swapped out. The pages of a shared mapping are
+        // not private even if no other process maps them at the moment.
+        if inner.is_destroyed
+            || self.is_shared
+            || inner.flags.contains(VmMappingFlags::LOCKED)
+            || !swap::can_swap_out()
+        {
+            return Ok(0);
+        }
+        let Some(parent) = self.parent.upgrade() else {
+            return Ok(0);
+        };
+        let vm_space = parent.vm_space();
+
+        let mut nr_swapped = 0;
+        for page_idx in page_idxs {
+            if nr_swapped >= nr_pages {
+                break;
+            }
+            if only_cold && vm_space.clear_accessed(inner.page_map_addr(page_idx)) {
+                continue;
+            }
+            if self
+                .vmo
+                .swap_out_page(page_idx, || inner.unmap_one_page(vm_space, page_idx))?
+            {
+                nr_swapped += 1;
+            }
+        }
+        Ok(nr_swapped)
+    }
+
+    /// Unmaps the pages of the mapping from the vmspace, which is a copy of the vmspace of the
+    /// parent vmar, e.g., in the child process after fork.
+    pub(super) fn unmap_from_copied(&self, vm_space: &VmSpace) -> Result<()> {
+        let inner = self.inner.lock();
+        vm_space.unmap(&inner.range())
+    }
+
     pub fn range(&self) -> Range<usize> {
         self.map_to_addr()..self.map_to_addr() + self.map_size()
     }
 
-    /// Protect the current `VmMapping` to enforce new permissions within a specified range.
+    /// Returns the range in the vmo that corresponds to the range of addresses.
+    fn vmo_range(&self, range: &Range<Vaddr>) -> Range<usize> {
+        let inner = self.inner.lock();
+        (range.start - inner.map_to_addr + inner.vmo_offset)
+            ..(range.end - inner.map_to_addr + inner.vmo_offset)
+    }
+
+    /// Update the properties (e.g., the permission or the flags) of the current `VmMapping`
+    /// within a specified range.
     ///
     /// Due to the property of `VmMapping`, this operation may require subdividing the current
-    /// `VmMapping`. In this condition, it will generate a new `VmMapping` with the specified `perm` to protect the
+    /// `VmMapping`. In this condition, it will generate a new `VmMapping` with the updated properties for the
     /// target range, as well as additional `VmMappings` to preserve the mappings in the remaining ranges.
     ///
     /// There are four conditions:
@@ -333,41 +783,44 @@ impl VmMapping {
     /// 3. |--------old perm--------| -> |-old-| + |-new-| + |-old-|
     /// 4. |--------old perm--------| -> |---------new perm--------|
     ///
-    /// Generally, this function is only used in `protect()` method.
+    /// Generally, this function is only used in `protect()` and the methods that update flags.
     /// This method modifies the parent `Vmar` in the end if subdividing is required.
     /// It removes current mapping and add splitted mapping to the Vmar.
-    fn protect_with_subdivision(&self, intersect_range: &Range<usize>, perm: VmPerm) -> Result<()> {
+    fn update_with_subdivision(
+        &self,
+        intersect_range: &Range<usize>,
+        update: impl FnOnce(&mut VmMappingInner),
+    ) -> Result<()> {
         let mut additional_mappings = Vec::new();
         let range = self.range();
         // Condition 4, the `additional_mappings` will be empty.
         if range.start == intersect_range.start && range.end == intersect_range.end {
-            self.inner.lock().perm = perm;
+            update(&mut self.inner.lock());
             return Ok(());
         }
         // Condition 1 or 3, which needs an additional new VmMapping with range (range.start..intersect_range.start)
         if range.start < intersect_range.start {
-            let additional_left_mapping =
-                self.clone_partial(range.start..intersect_range.start, None)?;
+            let additional_left_mapping = self.clone_partial(range.start..intersect_range.start)?;
             additional_mappings.push(additional_left_mapping);
         }
         // Condition 2 or 3, which needs an additional new VmMapping with range (intersect_range.end..range.end).
         if range.end > intersect_range.end {
-            let additional_right_mapping =
-                self.clone_partial(intersect_range.end..range.end, None)?;
+            let additional_right_mapping = self.clone_partial(intersect_range.end..range.end)?;
             additional_mappings.push(additional_right_mapping);
         }
-        // The protected VmMapping must exist and its range is `intersect_range`.
-        let protected_mapping = self.clone_partial(intersect_range.clone(), Some(perm))?;
+        // The updated VmMapping must exist and its range is `intersect_range`.
+        let updated_mapping = self.clone_partial(intersect_range.clone())?;
+        update(&mut updated_mapping.inner.lock());
 
         // Begin to modify the `Vmar`.
         let vmar = self.parent.upgrade().unwrap();
         let mut vmar_inner = vmar.inner.lock();
         // Remove the original mapping.
         vmar_inner.vm_mappings.remove(&self.map_to_addr());
-        // Add protected mappings to the vmar.
+        // Add updated mappings to the vmar.
         vmar_inner
             .vm_mappings
-            .insert(protected_mapping.map_to_addr(), protected_mapping);
+            .insert(updated_mapping.map_to_addr(), updated_mapping);
         // Add additional mappings to the vmar.
         for mapping in additional_mappings {
             vmar_inner
@@ -398,6 +851,9 @@ impl VmMapping {
         if !is_intersected(&range, trim_range) {
             return Ok(());
         }
+        if self.flags().contains(VmMappingFlags::LOCKED) {
+            self.set_pages_locked(&get_intersected_range(&range, trim_range), false)?;
+        }
         if trim_range.start <= map_to_addr && trim_range.end >= map_to_addr + map_size {
             // Fast path: the whole mapping was trimed.
             self.unmap(&range, true)?;
@@ -456,7 +912,28 @@ impl VmMappingInner {
         frame: VmFrame,
         is_readonly: bool,
     ) -> Result<()> {
-        let map_addr = self.page_map_addr(page_idx);
+        self.map_pages(
+            vmo,
+            vm_space,
+            page_idx,
+            VmFrameVec::from_one_frame(frame),
+            is_readonly,
+        )
+    }
+
+    /// Maps the frames to the pages starting from `start_page_idx`.
+    ///
+    /// The frames that form an aligned huge page are mapped as a huge page by the vmspace.
+    fn map_pages(
+        &mut self,
+        vmo: &Vmo<Rights>,
+        vm_space: &VmSpace,
+        start_page_idx: usize,
+        frames: VmFrameVec,
+        is_readonly: bool,
+    ) -> Result<()> {
+        let map_addr = self.page_map_addr(start_page_idx);
+        let nr_pages = frames.len();
 
         let vm_perm = {
             let mut perm = self.perm;
@@ -474,13 +951,13 @@ impl VmMappingInner {
             options
         };
 
-        // Cow child allows unmapping the mapped page.
-        if vmo.is_cow_vmo() && vm_space.is_mapped(map_addr) {
-            vm_space.unmap(&(map_addr..(map_addr + PAGE_SIZE))).unwrap();
-        }
+        // The pages may have been mapped, e.g., as read-only pages of a cow child, or in the
+        // vmspace copied from the parent process. They are replaced by the new mapping.
+        vm_space.unmap(&(map_addr..(map_addr + nr_pages * PAGE_SIZE)))?;
 
-        vm_space.map(VmFrameVec::from_one_frame(frame), &vm_map_options)?;
-        self.mapped_pages.insert(page_idx);
+        vm_space.map(frames, &vm_map_options)?;
+        self.mapped_pages
+            .extend(start_page_idx..(start_page_idx + nr_pages));
         Ok(())
     }
 
@@ -500,9 +977,13 @@ impl VmMappingInner {
         let vmo_map_range = (range.start - map_to_addr + self.vmo_offset)
             ..(range.end - map_to_addr + self.vmo_offset);
         let page_idx_range = get_page_idx_range(&vmo_map_range);
-        for page_idx in page_idx_range {
-            self.unmap_one_page(vm_space, page_idx)?;
-        }
+        // The pages are unmapped at once, so that the huge pages fully inside the range are
+        // not split.
+        let unmap_range =
+            self.page_map_addr(page_idx_range.start)..self.page_map_addr(page_idx_range.end);
+        vm_space.unmap(&unmap_range)?;
+        self.mapped_pages
+            .retain(|page_idx| !page_idx_range.contains(page_idx));
         if may_destroy && *range == self.range() {
             self.is_destroyed = false;
         }
@@ -521,17 +1002,10 @@ impl VmMappingInner {
     ) -> Result<()> {
         debug_assert!(range.start % PAGE_SIZE == 0);
         debug_assert!(range.end % PAGE_SIZE == 0);
-        let start_page = (range.start - self.map_to_addr + self.vmo_offset) / PAGE_SIZE;
-        let end_page = (range.end - self.map_to_addr + self.vmo_offset) / PAGE_SIZE;
+        // Only the mapped pages are protected in the page table. A huge page that is partially
+        // inside the range is split.
         let perm = VmPerm::from(perms);
-        for page_idx in start_page..end_page {
-            let page_addr = self.page_map_addr(page_idx);
-            if vm_space.is_mapped(page_addr) {
-                // If the page is already mapped, we will modify page table
-                let page_range = page_addr..(page_addr + PAGE_SIZE);
-                vm_space.protect(&page_range, perm)?;
-            }
-        }
+        vm_space.protect(&range, perm)?;
         Ok(())
     }
 
@@ -617,6 +1091,8 @@ pub struct VmarMapOptions<R1, R2> {
     offset: Option<usize>,
     align: usize,
     can_overwrite: bool,
+    is_shared: bool,
+    file: Option<MappedFile>,
 }
 
 impl<R1, R2> VmarMapOptions<R1, R2> {
@@ -637,6 +1113,8 @@ impl<R1, R2> VmarMapOptions<R1, R2> {
             offset: None,
             align: PAGE_SIZE,
             can_overwrite: false,
+            is_shared: false,
+            file: None,
         }
     }
 
@@ -706,6 +1184,28 @@ impl<R1, R2> VmarMapOptions<R1, R2> {
         self
     }
 
+    /// Sets whether the mapping is shared among processes.
+    ///
+    /// The pages of a shared mapping are still shared with the child process after fork, instead
+    /// of being copied on write.
+    ///
+    /// The default value is false.
+    pub fn is_shared(mut self, is_shared: bool) -> Self {
+        self.is_shared = is_shared;
+        self
+    }
+
+    /// Sets the file that the VMO is mapped from, and the offset in the file where the VMO
+    /// starts.
+    ///
+    /// The file is only used to describe the mapping, e.g., in `/proc/[pid]/maps`.
+    ///
+    /// The default value is none, i.e., an anonymous mapping.
+    pub fn file(mut self, dentry: Arc<Dentry>, offset: usize) -> Self {
+        self.file = Some(MappedFile { dentry, offset });
+        self
+    }
+
     /// Creates the mapping.
     ///
     /// All options will be checked at this point.
@@ -717,7 +1217,7 @@ impl<R1, R2> VmarMapOptions<R1, R2> {
         let vmo_ = self.vmo.0.clone();
         let vm_mapping = Arc::new(VmMapping::build_mapping(self)?);
         let map_to_addr = vm_mapping.map_to_addr();
-        parent_vmar.add_mapping(vm_mapping);
+        parent_vmar.add_mapping(vm_mapping)?;
         Ok(map_to_addr)
     }
 
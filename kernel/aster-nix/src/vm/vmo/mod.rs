@@ -7,11 +7,17 @@ use core::ops::Range;
 use align_ext::AlignExt;
 use aster_frame::{
     collections::xarray::{CursorMut, XArray, XMark},
-    vm::{VmAllocOptions, VmFrame, VmFrameVec, VmIo},
+    vm::{VmAllocOptions, VmFrame, VmFrameVec, VmIo, HUGE_PAGE_SIZE},
 };
 use aster_rights::Rights;
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    vm::{
+        reclaim,
+        swap::{self, SwapDevice, SwapEntry},
+    },
+};
 
 mod dyn_cap;
 mod options;
@@ -80,6 +86,18 @@ use self::options::ChildType;
 ///
 pub struct Vmo<R = Rights>(pub(super) Arc<Vmo_>, R);
 
+/// A weak reference to a VMO, which does not keep the VMO alive.
+///
+/// It can be upgraded to a VMO with the same access rights.
+pub struct WeakVmo<R = Rights>(Weak<Vmo_>, R);
+
+impl<R: Copy> WeakVmo<R> {
+    /// Returns the VMO if it is still alive.
+    pub fn upgrade(&self) -> Option<Vmo<R>> {
+        Some(Vmo(self.0.upgrade()?, self.1))
+    }
+}
+
 /// Functions exist both for static capbility and dynamic capibility
 pub trait VmoRightsOp {
     /// Returns the access rights.
@@ -129,6 +147,11 @@ bitflags! {
         /// Set this flag if a VMO is backed by memory pages that supports
         /// Direct Memory Access (DMA) by devices.
         const DMA        = 1 << 2;
+        /// Set this flag if a VMO is backed by huge pages whenever possible.
+        ///
+        /// The memory pages of such a VMO are committed in the units of
+        /// huge pages, even if only one of them is accessed.
+        const HUGE_PAGE  = 1 << 3;
     }
 }
 
@@ -154,26 +177,92 @@ impl From<VmoMark> for XMark {
     }
 }
 
+/// The pages of a VMO, including the committed pages and the pages swapped out.
+#[derive(Clone)]
+pub(super) struct VmoPages {
+    /// The committed pages.
+    frames: XArray<VmFrame, VmoMark>,
+    /// The slots on the swap devices where the pages are swapped out to, indexed by the page
+    /// index.
+    ///
+    /// A slot is shared by the COW children created after the page is swapped out, and the
+    /// page is copied to each of them when it is swapped in.
+    swapped: BTreeMap<usize, Arc<SwapEntry>>,
+}
+
+impl VmoPages {
+    pub(super) fn new(frames: XArray<VmFrame, VmoMark>) -> Self {
+        Self {
+            frames,
+            swapped: BTreeMap::new(),
+        }
+    }
+}
+
 /// `Pages` is the struct that manages the `VmFrame`s stored in `Vmo_`.
 pub(super) enum Pages {
     /// `Pages` that cannot be resized. This kind of `Pages` will have a constant size.
-    Nonresizable(Arc<Mutex<XArray<VmFrame, VmoMark>>>, usize),
-    /// `Pages` that can be resized and have a variable size, and such `Pages` cannot
-    /// be shared between different VMOs.
-    Resizable(Mutex<(XArray<VmFrame, VmoMark>, usize)>),
+    Nonresizable(Arc<Mutex<VmoPages>>, usize),
+    /// `Pages` that can be resized and have a variable size. The size is owned by the
+    /// resizable VMO, while the pages can be shared with its slice children.
+    Resizable(Arc<Mutex<VmoPages>>, Mutex<usize>),
 }
 
+type SwappedPages = BTreeMap<usize, Arc<SwapEntry>>;
+
 impl Pages {
     fn with<R, F>(&self, func: F) -> R
     where
         F: FnOnce(&mut XArray<VmFrame, VmoMark>, usize) -> R,
+    {
+        self.with_swapped(|frames, swapped, size| func(frames, size))
+    }
+
+    /// Works like `with`, but the pages swapped out are also provided.
+    fn with_swapped<R, F>(&self, func: F) -> R
+    where
+        F: FnOnce(&mut XArray<VmFrame, VmoMark>, &mut SwappedPages, usize) -> R,
+    {
+        match self {
+            Self::Nonresizable(pages, size) => {
+                let mut pages = pages.lock();
+                let VmoPages { frames, swapped } = &mut *pages;
+                func(frames, swapped, *size)
+            }
+            Self::Resizable(pages, size) => {
+                let size = size.lock();
+                let mut pages = pages.lock();
+                let VmoPages { frames, swapped } = &mut *pages;
+                func(frames, swapped, *size)
+            }
+        }
+    }
+
+    /// Returns an identifier of the pages, which is unique among all the alive pages and is
+    /// the same for the VMOs sharing them.
+    fn id(&self) -> usize {
+        match self {
+            Self::Nonresizable(pages, _) | Self::Resizable(pages, _) => Arc::as_ptr(pages) as usize,
+        }
+    }
+
+    /// Works like `with_swapped`, but returns `None` without waiting if the pages are being
+    /// used.
+    fn try_with_swapped<R, F>(&self, func: F) -> Option<R>
+    where
+        F: FnOnce(&mut XArray<VmFrame, VmoMark>, &mut SwappedPages, usize) -> R,
     {
         match self {
-            Self::Nonresizable(pages, size) => func(&mut pages.lock(), *size),
-            Self::Resizable(pages) => {
-                let mut lock = pages.lock();
-                let size = lock.1;
-                func(&mut lock.0, size)
+            Self::Nonresizable(pages, size) => {
+                let mut pages = pages.try_lock()?;
+                let VmoPages { frames, swapped } = &mut *pages;
+                Some(func(frames, swapped, *size))
+            }
+            Self::Resizable(pages, size) => {
+                let size = size.try_lock()?;
+                let mut pages = pages.try_lock()?;
+                let VmoPages { frames, swapped } = &mut *pages;
+                Some(func(frames, swapped, *size))
             }
         }
     }
@@ -194,12 +283,33 @@ pub(super) struct Vmo_ {
     pages: Pages,
 }
 
+/// The number of frames in a huge page.
+const NR_HUGE_PAGE_FRAMES: usize = HUGE_PAGE_SIZE / PAGE_SIZE;
+
+/// Allocates a new page, reclaiming some pages to make room for it if the memory is short.
+fn alloc_page() -> Result<VmFrame> {
+    reclaim::alloc_page(&VmAllocOptions::new(1))
+}
+
 fn clone_page(page: &VmFrame) -> Result<VmFrame> {
-    let new_page = VmAllocOptions::new(1).alloc_single()?;
+    let new_page = alloc_page()?;
     new_page.copy_from_frame(page);
     Ok(new_page)
 }
 
+/// Returns whether the frames are physically contiguous and aligned to the huge page size.
+fn is_huge_page(frames: &VmFrameVec) -> bool {
+    let Some(first_frame) = frames.get(0) else {
+        return false;
+    };
+    let start_paddr = first_frame.start_paddr();
+    start_paddr % HUGE_PAGE_SIZE == 0
+        && frames
+            .iter()
+            .enumerate()
+            .all(|(idx, frame)| frame.start_paddr() == start_paddr + idx * PAGE_SIZE)
+}
+
 impl Vmo_ {
     /// Prepare a new `VmFrame` for the target index in pages, returning the new page as well as
     /// whether this page needs to be marked as exclusive.
@@ -221,7 +331,7 @@ impl Vmo_ {
             None => {
                 // Condition 1. The new anonymous page only need to be marked as `ExclusivePage`
                 // when current VMO is a cow VMO, otherwise this mark is meaningless.
-                (VmAllocOptions::new(1).alloc_single()?, is_cow_vmo)
+                (alloc_page()?, is_cow_vmo)
             }
             Some(pager) => {
                 let page = pager.commit_page(page_idx)?;
@@ -245,6 +355,7 @@ impl Vmo_ {
     fn commit_with_cursor(
         &self,
         cursor: &mut CursorMut<'_, VmFrame, VmoMark>,
+        swapped: &mut SwappedPages,
         is_cow_vmo: bool,
         will_write: bool,
     ) -> Result<VmFrame> {
@@ -261,6 +372,13 @@ impl Vmo_ {
                 }
 
                 (clone_page(&committed_page)?, true)
+            } else if let Some(entry) = swapped.get(&(cursor.index() as usize)) {
+                // The page read back from the swap device is not shared with others, so it
+                // only needs to be marked as exclusive in a COW VMO.
+                let page = alloc_page()?;
+                entry.read(&page)?;
+                swapped.remove(&(cursor.index() as usize));
+                (page, is_cow_vmo)
             } else {
                 self.prepare_page(cursor.index() as usize, is_cow_vmo, will_write)?
             }
@@ -278,17 +396,90 @@ impl Vmo_ {
     /// During the commit process, the Copy-On-Write (COW) mechanism may be triggered depending on the circumstances.
     pub fn commit_page(&self, offset: usize, will_write: bool) -> Result<VmFrame> {
         let page_idx = offset / PAGE_SIZE + self.page_idx_offset;
-        self.pages.with(|pages, size| {
+        if self.flags.contains(VmoFlags::HUGE_PAGE)
+            && let Some(frames) = self.commit_huge_page(offset, will_write)?
+        {
+            let frame = frames.get(page_idx % NR_HUGE_PAGE_FRAMES).unwrap();
+            return Ok(frame.clone());
+        }
+        self.pages.with_swapped(|pages, swapped, size| {
+            let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
+            let mut cursor = pages.cursor_mut(page_idx as u64);
+            self.commit_with_cursor(&mut cursor, swapped, is_cow_vmo, will_write)
+        })
+    }
+
+    /// Commit the huge page that contains the target offset in the VMO, and return its pages if
+    /// they are aligned and physically contiguous, so that they can be mapped as a huge page.
+    ///
+    /// If none of the pages in an anonymous VMO are committed, a new huge page is allocated.
+    /// `None` is returned if the huge page exceeds the VMO, if the pages cannot be used as a
+    /// huge page (e.g., some pages are not committed, need to be copied on write, or are swapped
+    /// out), or if there is no contiguous memory for a new huge page. The caller can commit the
+    /// single page instead.
+    pub fn commit_huge_page(&self, offset: usize, will_write: bool) -> Result<Option<VmFrameVec>> {
+        let page_idx = (offset / PAGE_SIZE + self.page_idx_offset).align_down(NR_HUGE_PAGE_FRAMES);
+        if page_idx < self.page_idx_offset {
+            return Ok(None);
+        }
+        self.pages.with_swapped(|pages, swapped, size| {
+            if (page_idx - self.page_idx_offset + NR_HUGE_PAGE_FRAMES) * PAGE_SIZE > size {
+                return Ok(None);
+            }
+            if swapped
+                .range(page_idx..(page_idx + NR_HUGE_PAGE_FRAMES))
+                .next()
+                .is_some()
+            {
+                return Ok(None);
+            }
+
             let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
+            let mut frames = VmFrameVec::new_with_capacity(NR_HUGE_PAGE_FRAMES);
             let mut cursor = pages.cursor_mut(page_idx as u64);
-            self.commit_with_cursor(&mut cursor, is_cow_vmo, will_write)
+            for _ in 0..NR_HUGE_PAGE_FRAMES {
+                if let Some(committed_page) = cursor.load() {
+                    if is_cow_vmo && will_write && !cursor.is_marked(VmoMark::ExclusivePage) {
+                        return Ok(None);
+                    }
+                    frames.push(committed_page.clone());
+                }
+                cursor.next();
+            }
+            drop(cursor);
+
+            if frames.is_empty() && self.pager.is_none() {
+                let Ok(frames) = VmAllocOptions::new(NR_HUGE_PAGE_FRAMES)
+                    .is_contiguous(true)
+                    .align(HUGE_PAGE_SIZE)
+                    .alloc()
+                else {
+                    return Ok(None);
+                };
+                let mut cursor = pages.cursor_mut(page_idx as u64);
+                for frame in frames.iter() {
+                    cursor.store(frame.clone());
+                    // The new anonymous pages only need to be marked as exclusive in a COW VMO.
+                    if is_cow_vmo {
+                        cursor.set_mark(VmoMark::ExclusivePage).unwrap();
+                    }
+                    cursor.next();
+                }
+                return Ok(Some(frames));
+            }
+
+            if frames.len() != NR_HUGE_PAGE_FRAMES || !is_huge_page(&frames) {
+                return Ok(None);
+            }
+            Ok(Some(frames))
         })
     }
 
     /// Decommit the page corresponding to the target offset in the VMO.
     fn decommit_page(&mut self, offset: usize) -> Result<()> {
         let page_idx = offset / PAGE_SIZE + self.page_idx_offset;
-        self.pages.with(|pages, size| {
+        self.pages.with_swapped(|pages, swapped, size| {
+            swapped.remove(&page_idx);
             let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
             let mut cursor = pages.cursor_mut(page_idx as u64);
             if cursor.remove().is_some()
@@ -303,7 +494,7 @@ impl Vmo_ {
 
     /// Commit a range of pages in the VMO, returns the pages in this range.
     pub fn commit(&self, range: Range<usize>, will_write: bool) -> Result<VmFrameVec> {
-        self.pages.with(|pages, size| {
+        self.pages.with_swapped(|pages, swapped, size| {
             if range.end > size {
                 return_errno_with_message!(Errno::EINVAL, "operated range exceeds the vmo size");
             }
@@ -317,7 +508,7 @@ impl Vmo_ {
             let mut cursor = pages.cursor_mut(page_idx_range.start as u64);
             for page_idx in page_idx_range {
                 let committed_page =
-                    self.commit_with_cursor(&mut cursor, is_cow_vmo, will_write)?;
+                    self.commit_with_cursor(&mut cursor, swapped, is_cow_vmo, will_write)?;
                 frames.push(committed_page);
                 cursor.next();
             }
@@ -327,8 +518,8 @@ impl Vmo_ {
 
     /// Decommit a range of pages in the VMO.
     pub fn decommit(&self, range: Range<usize>) -> Result<()> {
-        self.pages.with(|pages, size| {
-            self.decommit_pages(pages, range)?;
+        self.pages.with_swapped(|pages, swapped, size| {
+            self.decommit_pages(pages, swapped, range)?;
             Ok(())
         })
     }
@@ -415,11 +606,9 @@ impl Vmo_ {
                     );
                 }
 
-                let Pages::Nonresizable(ref pages, size) = self.pages else {
-                    return_errno_with_message!(
-                        Errno::EINVAL,
-                        "a resizable VMO cannot have a slice child"
-                    );
+                let (pages, size) = match self.pages {
+                    Pages::Nonresizable(ref pages, size) => (pages, size),
+                    Pages::Resizable(ref pages, ref size) => (pages, *size.lock()),
                 };
 
                 // A slice child should be inside parent VMO's range
@@ -434,7 +623,7 @@ impl Vmo_ {
                 Ok(Pages::Nonresizable(pages.clone(), range.len()))
             }
             ChildType::Cow => {
-                let new_pages = self.pages.with(|pages, size| {
+                let new_pages = self.pages.with_swapped(|pages, swapped, size| {
                     // A Copy-on-Write child should intersect with parent VMO
                     debug_assert!(child_vmo_start <= size);
                     if child_vmo_start > size {
@@ -448,22 +637,34 @@ impl Vmo_ {
                     if self_is_cow {
                         // Condition 2.
                         pages.unset_mark_all(VmoMark::ExclusivePage);
-                        return Ok(pages.clone());
+                        return Ok(VmoPages {
+                            frames: pages.clone(),
+                            swapped: swapped.clone(),
+                        });
                     }
 
                     if self.pager.is_some() {
                         // Condition 3.
                         let mut cloned_pages = pages.clone();
                         cloned_pages.set_mark(VmoMark::CowVmo);
-                        return Ok(cloned_pages);
+                        return Ok(VmoPages {
+                            frames: cloned_pages,
+                            swapped: swapped.clone(),
+                        });
                     }
 
                     // Condition 4.
                     pages.set_mark(VmoMark::CowVmo);
-                    Ok(pages.clone())
+                    Ok(VmoPages {
+                        frames: pages.clone(),
+                        swapped: swapped.clone(),
+                    })
                 })?;
                 if child_flags.contains(VmoFlags::RESIZABLE) {
-                    Ok(Pages::Resizable(Mutex::new((new_pages, range.len()))))
+                    Ok(Pages::Resizable(
+                        Arc::new(Mutex::new(new_pages)),
+                        Mutex::new(range.len()),
+                    ))
                 } else {
                     Ok(Pages::Nonresizable(
                         Arc::new(Mutex::new(new_pages)),
@@ -479,30 +680,34 @@ impl Vmo_ {
         assert!(self.flags.contains(VmoFlags::RESIZABLE));
         let new_size = new_size.align_up(PAGE_SIZE);
 
-        let Pages::Resizable(ref pages) = self.pages else {
+        let Pages::Resizable(ref pages, ref size) = self.pages else {
             return_errno_with_message!(Errno::EINVAL, "current VMO is not resizable");
         };
 
-        let mut lock = pages.lock();
-        let old_size = lock.1;
+        let mut size = size.lock();
+        let old_size = *size;
         if new_size == old_size {
             return Ok(());
         }
         if new_size < old_size {
-            self.decommit_pages(&mut lock.0, new_size..old_size)?;
+            let mut pages = pages.lock();
+            let VmoPages { frames, swapped } = &mut *pages;
+            self.decommit_pages(frames, swapped, new_size..old_size)?;
         }
-        lock.1 = new_size;
+        *size = new_size;
         Ok(())
     }
 
     fn decommit_pages(
         &self,
         pages: &mut XArray<VmFrame, VmoMark>,
+        swapped: &mut SwappedPages,
         range: Range<usize>,
     ) -> Result<()> {
         let raw_page_idx_range = get_page_idx_range(&range);
         let page_idx_range = (raw_page_idx_range.start + self.page_idx_offset)
             ..(raw_page_idx_range.end + self.page_idx_offset);
+        swapped.retain(|page_idx, _| !page_idx_range.contains(page_idx));
         let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
         let mut cursor = pages.cursor_mut(page_idx_range.start as u64);
         for page_idx in page_idx_range {
@@ -517,6 +722,67 @@ impl Vmo_ {
         Ok(())
     }
 
+    /// Notify the pager that the committed page at the target index has been updated
+    /// bypassing the VMO, e.g., through a memory mapping.
+    pub fn update_page(&self, page_idx: usize) -> Result<()> {
+        let page_idx = page_idx + self.page_idx_offset;
+        self.pages.with(|pages, size| {
+            if let Some(pager) = &self.pager
+                && !pages.is_marked(VmoMark::CowVmo)
+                && pages.load(page_idx as u64).is_some()
+            {
+                pager.update_page(page_idx)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Write the updated pages in the target range back through the pager.
+    pub fn sync(&self, range: Range<usize>) -> Result<()> {
+        self.pages.with(|pages, size| {
+            // The pages of a COW VMO are never written back.
+            let Some(pager) = &self.pager else {
+                return Ok(());
+            };
+            if pages.is_marked(VmoMark::CowVmo) {
+                return Ok(());
+            }
+
+            let raw_page_idx_range = get_page_idx_range(&(range.start..range.end.min(size)));
+            for raw_page_idx in raw_page_idx_range {
+                let page_idx = raw_page_idx + self.page_idx_offset;
+                if pages.load(page_idx as u64).is_some() {
+                    pager.sync_page(page_idx)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Ask the pager to keep the pages in the target range in memory, or to stop keeping them
+    /// if `locked` is false.
+    pub fn set_pages_locked(&self, range: Range<usize>, locked: bool) -> Result<()> {
+        // The pages of a COW VMO are not shared with the pager once committed, so they are
+        // kept in memory by the VMO itself.
+        let Some(pager) = &self.pager else {
+            return Ok(());
+        };
+        if self.is_cow_vmo() {
+            return Ok(());
+        }
+
+        let raw_page_idx_range = get_page_idx_range(&range);
+        for raw_page_idx in raw_page_idx_range {
+            let page_idx = raw_page_idx + self.page_idx_offset;
+            if locked {
+                pager.lock_page(page_idx)?;
+            } else {
+                pager.unlock_page(page_idx)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Determine whether a page is committed.
     pub fn is_page_committed(&self, page_idx: usize) -> bool {
         self.pages.with(|pages, size| {
@@ -526,6 +792,172 @@ impl Vmo_ {
         })
     }
 
+    /// Determine whether a page is swapped out.
+    pub fn is_page_swapped(&self, page_idx: usize) -> bool {
+        self.pages.with_swapped(|pages, swapped, size| {
+            swapped.contains_key(&(page_idx + self.page_idx_offset))
+        })
+    }
+
+    /// Swap out the committed page at the target index, returning whether it is swapped out.
+    ///
+    /// Only the pages that are private to the VMO can be swapped out, i.e., the pages of an
+    /// anonymous VMO, or the pages copied on write in a COW VMO. The caller must not swap out
+    /// the pages of the VMOs mapped as shared, e.g., by `MAP_SHARED | MAP_ANONYMOUS`, since
+    /// they are not private even if no process maps them at the moment. The `unmap` callback is
+    /// invoked to unmap the page before it is swapped out, so that it is not accessed through
+    /// the page tables. The page is kept if it is still used elsewhere, e.g., mapped by other
+    /// processes.
+    ///
+    /// The page is skipped without waiting if the pages of the VMO are being used.
+    pub fn swap_out_page(
+        &self,
+        page_idx: usize,
+        unmap: impl FnOnce() -> Result<()>,
+    ) -> Result<bool> {
+        if self.flags.intersects(VmoFlags::CONTIGUOUS | VmoFlags::DMA) {
+            return Ok(false);
+        }
+
+        let page_idx = page_idx + self.page_idx_offset;
+        self.pages
+            .try_with_swapped(|pages, swapped, size| {
+                let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
+                let mut cursor = pages.cursor_mut(page_idx as u64);
+                let is_private = if is_cow_vmo {
+                    // The pages not marked as exclusive may be shared with other COW VMOs.
+                    cursor.is_marked(VmoMark::ExclusivePage)
+                } else {
+                    self.pager.is_none()
+                };
+                if !is_private {
+                    return Ok(false);
+                }
+                let Some(page) = cursor.load() else {
+                    return Ok(false);
+                };
+
+                unmap()?;
+                if page.is_shared() {
+                    return Ok(false);
+                }
+                let Some(entry) = swap::swap_out(&page)? else {
+                    return Ok(false);
+                };
+                drop(page);
+                cursor.remove();
+                swapped.insert(page_idx, Arc::new(entry));
+                Ok(true)
+            })
+            .unwrap_or(Ok(false))
+    }
+
+    /// Decommit the page at the target index that is committed from the pager without notifying
+    /// the pager, returning whether the page is not committed any more.
+    ///
+    /// This is used by the pager to evict its page, which is kept if it is still used elsewhere,
+    /// e.g., mapped by processes or shared with COW VMOs. The page is skipped without waiting
+    /// if the pages of the VMO are being used.
+    pub fn evict_page(&self, page_idx: usize) -> bool {
+        if self.pager.is_none() {
+            return false;
+        }
+
+        let page_idx = page_idx + self.page_idx_offset;
+        self.pages
+            .try_with_swapped(|pages, swapped, size| {
+                if pages.is_marked(VmoMark::CowVmo) {
+                    return false;
+                }
+                let mut cursor = pages.cursor_mut(page_idx as u64);
+                let Some(page) = cursor.load() else {
+                    return true;
+                };
+                // The page is referred to by both the VMO and the pager.
+                if page.reference_count() > 2 {
+                    return false;
+                }
+                drop(page);
+                cursor.remove();
+                true
+            })
+            .unwrap_or(false)
+    }
+
+    /// Swap in all the pages that are swapped out to the swap device.
+    pub fn swap_in_from(&self, device: &Arc<SwapDevice>) -> Result<()> {
+        self.pages.with_swapped(|pages, swapped, size| {
+            let page_idxs: Vec<usize> = swapped
+                .iter()
+                .filter(|(_, entry)| entry.is_on(device))
+                .map(|(page_idx, _)| *page_idx)
+                .collect();
+            let is_cow_vmo = pages.is_marked(VmoMark::CowVmo);
+            for page_idx in page_idxs {
+                let mut cursor = pages.cursor_mut(page_idx as u64);
+                self.commit_with_cursor(&mut cursor, swapped, is_cow_vmo, false)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Move the committed pages and the pages swapped out in the range to `dst`, starting at
+    /// `dst_offset`.
+    ///
+    /// The pages keep whether they are exclusive, so the pages that may be shared with other
+    /// COW VMOs are still copied on write in `dst`. The pages in `dst` that are committed from
+    /// the pager are kept if there are no pages to replace them.
+    pub fn move_pages_to(&self, range: Range<usize>, dst: &Vmo_, dst_offset: usize) -> Result<()> {
+        debug_assert!(range.start % PAGE_SIZE == 0);
+        debug_assert!(dst_offset % PAGE_SIZE == 0);
+        let src_page_idx = range.start / PAGE_SIZE + self.page_idx_offset;
+        let dst_page_idx = dst_offset / PAGE_SIZE + dst.page_idx_offset;
+        let nr_pages = get_page_idx_range(&range).len();
+
+        self.pages.with_swapped(|src_pages, src_swapped, src_size| {
+            if range.end > src_size {
+                return_errno_with_message!(Errno::EINVAL, "operated range exceeds the vmo size");
+            }
+            dst.pages.with_swapped(|dst_pages, dst_swapped, dst_size| {
+                if dst_offset + range.len() > dst_size {
+                    return_errno_with_message!(
+                        Errno::EINVAL,
+                        "operated range exceeds the destination vmo size"
+                    );
+                }
+
+                let src_is_cow = src_pages.is_marked(VmoMark::CowVmo);
+                if src_is_cow {
+                    dst_pages.set_mark(VmoMark::CowVmo);
+                }
+                let dst_is_cow = dst_pages.is_marked(VmoMark::CowVmo);
+
+                let mut src_cursor = src_pages.cursor_mut(src_page_idx as u64);
+                let mut dst_cursor = dst_pages.cursor_mut(dst_page_idx as u64);
+                for idx in 0..nr_pages {
+                    if let Some(entry) = src_swapped.remove(&(src_page_idx + idx)) {
+                        dst_cursor.remove();
+                        dst_swapped.insert(dst_page_idx + idx, entry);
+                    } else if let Some(page) = src_cursor.load().cloned() {
+                        let is_exclusive =
+                            !src_is_cow || src_cursor.is_marked(VmoMark::ExclusivePage);
+                        src_cursor.remove();
+                        dst_swapped.remove(&(dst_page_idx + idx));
+                        dst_cursor.store(page);
+                        if dst_is_cow && is_exclusive {
+                            dst_cursor.set_mark(VmoMark::ExclusivePage).unwrap();
+                        } else {
+                            dst_cursor.unset_mark(VmoMark::ExclusivePage).unwrap();
+                        }
+                    }
+                    src_cursor.next();
+                    dst_cursor.next();
+                }
+                Ok(())
+            })
+        })
+    }
+
     /// Return the flags of current VMO.
     pub fn flags(&self) -> VmoFlags {
         self.flags
@@ -536,6 +968,17 @@ impl Vmo_ {
         self.pages
             .with(|pages, size| pages.is_marked(VmoMark::CowVmo))
     }
+
+    fn offset_key(&self, offset: usize) -> (usize, usize) {
+        (self.pages.id(), self.page_idx_offset * PAGE_SIZE + offset)
+    }
+}
+
+impl<R: Copy> Vmo<R> {
+    /// Returns a weak reference to the VMO.
+    pub fn downgrade(&self) -> WeakVmo<R> {
+        WeakVmo(Arc::downgrade(&self.0), self.1)
+    }
 }
 
 impl<R> Vmo<R> {
@@ -554,10 +997,85 @@ impl<R> Vmo<R> {
         self.0.is_page_committed(page_idx)
     }
 
+    /// Returns whether a page is swapped out.
+    pub fn is_page_swapped(&self, page_idx: usize) -> bool {
+        self.0.is_page_swapped(page_idx)
+    }
+
+    /// Swaps out the committed page at the index after unmapping it by `unmap`, returning
+    /// whether it is swapped out.
+    ///
+    /// See `Vmo_::swap_out_page` for the pages that can be swapped out.
+    pub fn swap_out_page(
+        &self,
+        page_idx: usize,
+        unmap: impl FnOnce() -> Result<()>,
+    ) -> Result<bool> {
+        self.0.swap_out_page(page_idx, unmap)
+    }
+
+    /// Moves the pages in the range to `dst`, starting at `dst_offset`.
+    ///
+    /// See `Vmo_::move_pages_to` for how the pages are moved.
+    pub fn move_pages_to(&self, range: Range<usize>, dst: &Self, dst_offset: usize) -> Result<()> {
+        self.0.move_pages_to(range, &dst.0, dst_offset)
+    }
+
+    /// Swaps in all the pages that are swapped out to the swap device.
+    pub fn swap_in_from(&self, device: &Arc<SwapDevice>) -> Result<()> {
+        self.0.swap_in_from(device)
+    }
+
+    /// Decommits the page at the index on behalf of the pager, returning whether the page is
+    /// not committed any more.
+    ///
+    /// See `Vmo_::evict_page` for the pages that are kept.
+    pub fn evict_page(&self, page_idx: usize) -> bool {
+        self.0.evict_page(page_idx)
+    }
+
+    /// Notifies the pager that a committed page has been updated bypassing the VMO,
+    /// e.g., through a memory mapping.
+    pub fn update_page(&self, page_idx: usize) -> Result<()> {
+        self.0.update_page(page_idx)
+    }
+
+    /// Writes the updated pages in the range back through the pager, if there is one.
+    pub fn sync(&self, range: Range<usize>) -> Result<()> {
+        self.0.sync(range)
+    }
+
+    /// Asks the pager to keep the pages in the range in memory, or to stop keeping them if
+    /// `locked` is false.
+    pub fn set_pages_locked(&self, range: Range<usize>, locked: bool) -> Result<()> {
+        self.0.set_pages_locked(range, locked)
+    }
+
+    /// Returns the key that identifies the byte at the offset, which is the same for all the
+    /// VMOs sharing the page (e.g., the slice children).
+    ///
+    /// Unlike the physical address, the key does not change when the page is swapped out or
+    /// evicted and then committed again.
+    pub fn offset_key(&self, offset: usize) -> (usize, usize) {
+        self.0.offset_key(offset)
+    }
+
     pub fn get_committed_frame(&self, page_idx: usize, write_page: bool) -> Result<VmFrame> {
         self.0.commit_page(page_idx * PAGE_SIZE, write_page)
     }
 
+    /// Commits the huge page that contains the page at the index, returning its pages if they
+    /// can be mapped as a huge page.
+    ///
+    /// See `Vmo_::commit_huge_page` for the cases where `None` is returned.
+    pub fn get_committed_huge_page(
+        &self,
+        page_idx: usize,
+        write_page: bool,
+    ) -> Result<Option<VmFrameVec>> {
+        self.0.commit_huge_page(page_idx * PAGE_SIZE, write_page)
+    }
+
     pub fn is_cow_vmo(&self) -> bool {
         self.0.is_cow_vmo()
     }
@@ -13,7 +13,7 @@ use aster_rights::{Dup, Rights, TRightSet, TRights, Write};
 use aster_rights_proc::require;
 use typeflags_util::{SetExtend, SetExtendOp};
 
-use super::{Pager, Pages, Vmo, VmoFlags, VmoMark, VmoRightsOp};
+use super::{Pager, Pages, Vmo, VmoFlags, VmoMark, VmoPages, VmoRightsOp};
 use crate::{prelude::*, vm::vmo::Vmo_};
 
 /// Options for allocating a root VMO.
@@ -125,9 +125,9 @@ impl<R: TRights> VmoOptions<TRightSet<R>> {
 fn alloc_vmo_(size: usize, flags: VmoFlags, pager: Option<Arc<dyn Pager>>) -> Result<Vmo_> {
     let size = size.align_up(PAGE_SIZE);
     let pages = {
-        let pages = committed_pages_if_continuous(flags, size)?;
+        let pages = VmoPages::new(committed_pages_if_continuous(flags, size)?);
         if flags.contains(VmoFlags::RESIZABLE) {
-            Pages::Resizable(Mutex::new((pages, size)))
+            Pages::Resizable(Arc::new(Mutex::new(pages)), Mutex::new(size))
         } else {
             Pages::Nonresizable(Arc::new(Mutex::new(pages)), size)
         }
@@ -228,7 +228,7 @@ fn committed_pages_if_continuous(flags: VmoFlags, size: usize) -> Result<XArray<
 ///
 /// One can set VMO flags for a child VMO. Currently, the only flag that is
 /// valid when creating VMO children is `VmoFlags::RESIZABLE`.
-/// Note that a slice VMO child and its parent cannot not be resizable.
+/// Note that a slice VMO child cannot be resizable, though its parent can.
 ///
 /// ```rust
 /// use aster_std::vm::{PAGE_SIZE, VmoOptions};
@@ -285,7 +285,7 @@ impl VmoChildOptions<Rights, VmoSliceChild> {
             .check_rights(Rights::DUP)
             .expect("function new_slice_rights should called with rights Dup");
         Self {
-            flags: parent.flags(),
+            flags: parent.flags() & Self::PARENT_FLAGS_MASK,
             parent,
             range,
             marker: PhantomData,
@@ -295,8 +295,10 @@ impl VmoChildOptions<Rights, VmoSliceChild> {
 
 impl<R> VmoChildOptions<R, VmoSliceChild> {
     /// Flags that a VMO child inherits from its parent.
-    pub const PARENT_FLAGS_MASK: VmoFlags =
-        VmoFlags::from_bits(VmoFlags::CONTIGUOUS.bits | VmoFlags::DMA.bits).unwrap();
+    pub const PARENT_FLAGS_MASK: VmoFlags = VmoFlags::from_bits(
+        VmoFlags::CONTIGUOUS.bits | VmoFlags::DMA.bits | VmoFlags::HUGE_PAGE.bits,
+    )
+    .unwrap();
     /// Flags that a VMO child may differ from its parent.
     pub const CHILD_FLAGS_MASK: VmoFlags = VmoFlags::empty();
 
@@ -317,8 +319,10 @@ impl<R> VmoChildOptions<R, VmoSliceChild> {
 
 impl<R> VmoChildOptions<R, VmoCowChild> {
     /// Flags that a VMO child inherits from its parent.
-    pub const PARENT_FLAGS_MASK: VmoFlags =
-        VmoFlags::from_bits(VmoFlags::CONTIGUOUS.bits | VmoFlags::DMA.bits).unwrap();
+    pub const PARENT_FLAGS_MASK: VmoFlags = VmoFlags::from_bits(
+        VmoFlags::CONTIGUOUS.bits | VmoFlags::DMA.bits | VmoFlags::HUGE_PAGE.bits,
+    )
+    .unwrap();
     /// Flags that a VMO child may differ from its parent.
     pub const CHILD_FLAGS_MASK: VmoFlags = VmoFlags::RESIZABLE;
     /// Creates a default set of options for creating a copy-on-write (COW)
@@ -474,7 +478,7 @@ impl VmoChildType for VmoCowChild {}
 
 #[cfg(ktest)]
 mod test {
-    use aster_frame::vm::VmIo;
+    use aster_frame::vm::{VmIo, HUGE_PAGE_SIZE};
     use aster_rights::Full;
 
     use super::*;
@@ -525,6 +529,29 @@ mod test {
         assert_eq!(parent.read_val::<u32>(99).unwrap(), 0x1234);
     }
 
+    #[ktest]
+    fn slice_child_of_resizable() {
+        let parent = VmoOptions::<Full>::new(PAGE_SIZE)
+            .flags(VmoFlags::RESIZABLE)
+            .alloc()
+            .unwrap();
+        parent.resize(2 * PAGE_SIZE).unwrap();
+        let parent_dup = parent.dup().unwrap();
+        let slice_child = VmoChildOptions::new_slice(parent_dup, PAGE_SIZE..2 * PAGE_SIZE)
+            .alloc()
+            .unwrap();
+        // The child shares the pages with the parent
+        parent.write_val(PAGE_SIZE + 1, &42u8).unwrap();
+        assert_eq!(slice_child.read_val::<u8>(1).unwrap(), 42);
+        slice_child.write_val(2, &16u8).unwrap();
+        assert_eq!(parent.read_val::<u8>(PAGE_SIZE + 2).unwrap(), 16);
+        // The child cannot exceed the parent
+        let parent_dup = parent.dup().unwrap();
+        assert!(VmoChildOptions::new_slice(parent_dup, 0..3 * PAGE_SIZE)
+            .alloc()
+            .is_err());
+    }
+
     #[ktest]
     fn cow_child() {
         let parent = VmoOptions::<Full>::new(2 * PAGE_SIZE).alloc().unwrap();
@@ -557,6 +584,33 @@ mod test {
         assert_eq!(cow_child.read_val::<u32>(2).unwrap(), 0x1234);
     }
 
+    #[ktest]
+    fn huge_page_vmo() {
+        let vmo = VmoOptions::<Full>::new(2 * HUGE_PAGE_SIZE)
+            .flags(VmoFlags::HUGE_PAGE)
+            .alloc()
+            .unwrap();
+        // Accessing one page commits the whole huge page
+        vmo.write_val(HUGE_PAGE_SIZE + PAGE_SIZE, &42u8).unwrap();
+        let huge_page_idx = HUGE_PAGE_SIZE / PAGE_SIZE;
+        assert!(vmo.is_page_committed(huge_page_idx));
+        assert!(vmo.is_page_committed(2 * huge_page_idx - 1));
+        assert!(!vmo.is_page_committed(0));
+        let frames = vmo
+            .get_committed_huge_page(huge_page_idx, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(frames.get(0).unwrap().start_paddr() % HUGE_PAGE_SIZE, 0);
+        // A huge page that is partially committed cannot be used as a whole
+        vmo.decommit(HUGE_PAGE_SIZE..HUGE_PAGE_SIZE + PAGE_SIZE)
+            .unwrap();
+        assert!(vmo
+            .get_committed_huge_page(huge_page_idx, false)
+            .unwrap()
+            .is_none());
+        assert_eq!(vmo.read_val::<u8>(HUGE_PAGE_SIZE + PAGE_SIZE).unwrap(), 42);
+    }
+
     #[ktest]
     fn resize() {
         let vmo = VmoOptions::<Full>::new(PAGE_SIZE)
@@ -11,6 +11,8 @@ use crate::prelude::*;
 /// which should then provide frames whose data have been initialized properly.
 /// Any time a frame is updated through the VMO, the VMO will
 /// notify the attached pager that the frame has been updated.
+/// The updated frames can be written back on demand (i.e., on syncs),
+/// and the frames can be locked so that they are always kept in memory.
 /// Finally, when a frame is no longer needed (i.e., on decommits),
 /// the frame pager will also be notified.
 pub trait Pager: Send + Sync {
@@ -40,6 +42,31 @@ pub trait Pager: Send + Sync {
     /// call or return an error.
     fn update_page(&self, idx: usize) -> Result<()>;
 
+    /// Ask the pager to write back the frame at a specified index if it has been updated.
+    ///
+    /// Unlike decommitting, the frame stays committed after being written back.
+    ///
+    /// The VMO will not call this method for an uncommitted page.
+    /// But a robust implementation of `Pager` should not make
+    /// such an assumption for its correctness; instead, it should simply ignore the
+    /// call or return an error.
+    fn sync_page(&self, idx: usize) -> Result<()>;
+
+    /// Ask the pager to keep the frame at a specified index in memory.
+    ///
+    /// A locked frame shall not be evicted by the pager until it is unlocked as many
+    /// times as it has been locked. The frame does not need to be committed, in which
+    /// case it is kept in memory after being committed.
+    fn lock_page(&self, idx: usize) -> Result<()>;
+
+    /// Notify the pager that the frame at a specified index is unlocked once.
+    ///
+    /// The VMO will not call this method for a page that is not locked.
+    /// But a robust implementation of `Pager` should not make
+    /// such an assumption for its correctness; instead, it should simply ignore the
+    /// call or return an error.
+    fn unlock_page(&self, idx: usize) -> Result<()>;
+
     /// Notify the pager that the frame at the specified index has been decommitted.
     ///
     /// Knowing that a frame is no longer needed, the pager (e.g., an inode)
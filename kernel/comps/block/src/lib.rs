@@ -56,6 +56,8 @@ pub trait BlockDevice: Send + Sync + Any + Debug {
     /// Enqueues a new `SubmittedBio` to the block device.
     fn enqueue(&self, bio: SubmittedBio) -> Result<(), BioEnqueueError>;
     fn handle_irq(&self);
+    /// Returns the number of sectors in the block device, each of which is `SECTOR_SIZE` bytes.
+    fn nr_sectors(&self) -> usize;
 }
 
 impl dyn BlockDevice {
@@ -25,6 +25,8 @@ pub enum InputEvent {
 pub trait InputDevice: Send + Sync + Any + Debug {
     fn handle_irq(&self) -> Option<()>;
     fn register_callbacks(&self, function: &'static (dyn Fn(InputEvent) + Send + Sync));
+    /// Returns the name reported by the device, e.g., "QEMU Virtio Keyboard".
+    fn name(&self) -> &str;
 }
 
 pub fn register_device(name: String, device: Arc<dyn InputDevice>) {
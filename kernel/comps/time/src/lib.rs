@@ -17,9 +17,15 @@ use rtc::{get_cmos, is_updating, CENTURY_REGISTER};
 use spin::Once;
 
 mod clocksource;
+mod realtime;
 mod rtc;
 mod tsc;
 
+pub use realtime::{
+    read_realtime, realtime_freq, realtime_of, realtime_slew_remaining, set_realtime,
+    set_realtime_freq, shift_realtime, slew_realtime, MAX_FREQ_SCALED, MAX_SLEW_PPM,
+};
+
 pub const NANOS_PER_SECOND: u32 = 1_000_000_000;
 pub static VDSO_DATA_UPDATE: Once<Arc<dyn Fn(Instant, u64) + Sync + Send>> = Once::new();
 
@@ -104,6 +110,19 @@ impl SystemTime {
     pub(crate) fn modify_year(&mut self) {
         self.year += self.century as u16 * 100;
     }
+
+    /// Return the seconds since the UNIX epoch, ignoring the nanoseconds.
+    /// ref: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    pub(crate) fn unix_secs(&self) -> i64 {
+        let month = self.month as i64;
+        let year = self.year as i64 - (month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146097 + day_of_era - 719468;
+        days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
 }
 
 pub(crate) static READ_TIME: Mutex<SystemTime> = Mutex::new(SystemTime::zero());
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module maintains the real time, i.e., the wall clock time.
+//!
+//! The real time is derived from the `START_TIME` read from the RTC and the monotonic time of the
+//! TSC clocksource, plus an offset. The offset changes when the real time is set or shifted, and
+//! it also drifts when the real time is being slewed or its frequency is adjusted, which is how
+//! NTP-like daemons discipline the clock without making it jump.
+
+use core::time::Duration;
+
+use aster_frame::sync::SpinLock;
+
+use crate::{read_monotonic_time, read_start_time, tsc, NANOS_PER_SECOND};
+
+/// The maximum rate to slew the real time, in parts per million.
+pub const MAX_SLEW_PPM: i64 = 500;
+
+/// The maximum frequency adjustment, in parts per million with a 16-bit fractional part.
+pub const MAX_FREQ_SCALED: i64 = 500 << 16;
+
+static ADJUSTMENT: SpinLock<Adjustment> = SpinLock::new(Adjustment::new());
+
+/// The adjustment of the real time.
+///
+/// The drift caused by the slewing and the frequency adjustment is not accumulated into `offset`
+/// on every read, since a short elapsed time would be rounded down to no drift at all. Instead,
+/// the drift is calculated from `last_update`, which is only moved when the adjustment changes.
+struct Adjustment {
+    /// The offset of the real time at `last_update`, in nanoseconds.
+    offset: i64,
+    /// The offset that remains to be slewed since `last_update`, in nanoseconds.
+    slew_remaining: i64,
+    /// The frequency adjustment, in parts per million with a 16-bit fractional part.
+    freq: i64,
+    /// The monotonic time when the adjustment is changed last time.
+    last_update: Duration,
+}
+
+impl Adjustment {
+    const fn new() -> Self {
+        Self {
+            offset: 0,
+            slew_remaining: 0,
+            freq: 0,
+            last_update: Duration::ZERO,
+        }
+    }
+
+    /// Returns the offset and the slewed offset at the monotonic time `now`.
+    fn offset_at(&self, now: Duration) -> (i64, i64) {
+        let elapsed = now.saturating_sub(self.last_update).as_nanos() as i128;
+        let drift = (elapsed * self.freq as i128 / (1_000_000 << 16)) as i64;
+        let max_slew = (elapsed * MAX_SLEW_PPM as i128 / 1_000_000).min(i64::MAX as i128) as i64;
+        let slewed = self.slew_remaining.clamp(-max_slew, max_slew);
+        (self.offset + drift + slewed, slewed)
+    }
+
+    /// Moves `last_update` to the monotonic time `now`.
+    fn update(&mut self, now: Duration) {
+        let (offset, slewed) = self.offset_at(now);
+        self.offset = offset;
+        self.slew_remaining -= slewed;
+        self.last_update = self.last_update.max(now);
+    }
+
+    fn is_adjusting(&self) -> bool {
+        self.slew_remaining != 0 || self.freq != 0
+    }
+}
+
+/// Returns the current real time, i.e., the duration since the UNIX epoch.
+pub fn read_realtime() -> Duration {
+    realtime_of(read_monotonic_time())
+}
+
+/// Returns the real time at the given monotonic time.
+pub fn realtime_of(monotonic: Duration) -> Duration {
+    let (offset, _) = ADJUSTMENT.lock_irq_disabled().offset_at(monotonic);
+    let nanos = start_nanos() + monotonic.as_nanos() as i128 + offset as i128;
+    nanos_to_duration(nanos)
+}
+
+/// Sets the real time, which also stops the slewing of the real time.
+pub fn set_realtime(time: Duration) {
+    let now = read_monotonic_time();
+    {
+        let mut adjustment = ADJUSTMENT.lock_irq_disabled();
+        adjustment.update(now);
+        let offset = time.as_nanos() as i128 - start_nanos() - now.as_nanos() as i128;
+        adjustment.offset = offset as i64;
+        adjustment.slew_remaining = 0;
+    }
+    tsc::refresh_realtime();
+}
+
+/// Shifts the real time by `delta` nanoseconds immediately.
+pub fn shift_realtime(delta: i64) {
+    {
+        let mut adjustment = ADJUSTMENT.lock_irq_disabled();
+        adjustment.update(read_monotonic_time());
+        adjustment.offset = adjustment.offset.saturating_add(delta);
+    }
+    tsc::refresh_realtime();
+}
+
+/// Slews the real time by `delta` nanoseconds gradually, at the rate of [`MAX_SLEW_PPM`].
+///
+/// The slewing that has not finished is replaced, and its remaining offset is returned.
+pub fn slew_realtime(delta: i64) -> i64 {
+    let old_remaining = {
+        let mut adjustment = ADJUSTMENT.lock_irq_disabled();
+        adjustment.update(read_monotonic_time());
+        core::mem::replace(&mut adjustment.slew_remaining, delta)
+    };
+    tsc::refresh_realtime();
+    old_remaining
+}
+
+/// Returns the offset that remains to be slewed, in nanoseconds.
+pub fn realtime_slew_remaining() -> i64 {
+    let now = read_monotonic_time();
+    let adjustment = ADJUSTMENT.lock_irq_disabled();
+    let (_, slewed) = adjustment.offset_at(now);
+    adjustment.slew_remaining - slewed
+}
+
+/// Sets the frequency adjustment of the real time, in parts per million with a 16-bit
+/// fractional part.
+///
+/// The adjustment is clamped to [`MAX_FREQ_SCALED`].
+pub fn set_realtime_freq(freq: i64) {
+    {
+        let mut adjustment = ADJUSTMENT.lock_irq_disabled();
+        adjustment.update(read_monotonic_time());
+        adjustment.freq = freq.clamp(-MAX_FREQ_SCALED, MAX_FREQ_SCALED);
+    }
+    tsc::refresh_realtime();
+}
+
+/// Returns the frequency adjustment of the real time, in parts per million with a 16-bit
+/// fractional part.
+pub fn realtime_freq() -> i64 {
+    ADJUSTMENT.lock_irq_disabled().freq
+}
+
+/// Returns whether the real time is being slewed or its frequency is adjusted.
+pub(crate) fn is_adjusting() -> bool {
+    ADJUSTMENT.lock_irq_disabled().is_adjusting()
+}
+
+/// Returns `START_TIME` in nanoseconds since the UNIX epoch.
+fn start_nanos() -> i128 {
+    read_start_time().unix_secs() as i128 * NANOS_PER_SECOND as i128
+}
+
+/// Converts nanoseconds to a `Duration`, where a negative value is clamped to zero.
+fn nanos_to_duration(nanos: i128) -> Duration {
+    let nanos = nanos.max(0) as u128;
+    let secs = (nanos / NANOS_PER_SECOND as u128) as u64;
+    Duration::new(secs, (nanos % NANOS_PER_SECOND as u128) as u32)
+}
@@ -6,22 +6,28 @@
 use alloc::sync::Arc;
 use core::time::Duration;
 
-use aster_frame::{
-    arch::read_tsc,
-    timer::Timer,
-};
+use aster_frame::{arch::read_tsc, timer::Timer, trap::disable_local};
 use spin::Once;
 
 use crate::{
     clocksource::{ClockSource, Instant},
-    START_TIME, VDSO_DATA_UPDATE,
+    realtime, START_TIME, VDSO_DATA_UPDATE,
 };
 
 /// A instance of TSC clocksource.
 pub static CLOCK: Once<Arc<ClockSource>> = Once::new();
 
+/// The timer to update the TSC clocksource periodically.
+static UPDATE_TIMER: Once<Arc<Timer>> = Once::new();
+
 const MAX_DELAY_SECS: u64 = 100;
 
+/// The update interval while the real time is being adjusted.
+///
+/// The vdso extrapolates the real time at the rate of the clocksource, so it must be updated
+/// frequently to follow the slewing and the frequency adjustment of the real time.
+const ADJUSTING_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Init tsc clocksource module.
 pub(super) fn init() {
     init_clock();
@@ -49,7 +55,6 @@ fn calibrate() {
 
 /// Read an `Instant` of tsc clocksource.
 pub(super) fn read_instant() -> Instant {
-    return Instant::new(0, 0);
     let clock = CLOCK.get().unwrap();
     clock.read_instant()
 }
@@ -62,13 +67,31 @@ fn update_clocksource(timer: Arc<Timer>) {
     if VDSO_DATA_UPDATE.is_completed() {
         VDSO_DATA_UPDATE.get().unwrap()(clock.last_instant(), clock.last_cycles());
     }
+    if realtime::is_adjusting() {
+        timer.set(ADJUSTING_UPDATE_INTERVAL);
+        return;
+    }
     // Setting the timer as `clock.max_delay_secs() - 1` is to avoid
     // the actual delay time is greater than the maximum delay seconds due to the latency of execution.
     timer.set(Duration::from_secs(clock.max_delay_secs() - 1));
 }
 
+/// Update the vdso data after the real time is changed.
+pub(super) fn refresh_realtime() {
+    // The vdso data is also updated in the timer callback, which must not interrupt this update.
+    let _guard = disable_local();
+    let clock = CLOCK.get().unwrap();
+    if VDSO_DATA_UPDATE.is_completed() {
+        VDSO_DATA_UPDATE.get().unwrap()(clock.last_instant(), clock.last_cycles());
+    }
+    if realtime::is_adjusting() {
+        UPDATE_TIMER.get().unwrap().set(ADJUSTING_UPDATE_INTERVAL);
+    }
+}
+
 fn init_timer() {
     let timer = Timer::new(update_clocksource).unwrap();
+    UPDATE_TIMER.call_once(|| timer.clone());
     // The initial timer should be set as `clock.max_delay_secs() >> 1` or something much smaller than `max_delay_secs`.
     // This is because the initialization of this timer occurs during system startup,
     // and the system will also undergo other initialization processes, during which time interrupts are disabled.
@@ -10,11 +10,12 @@ use aster_block::{
 };
 use aster_frame::{
     io_mem::IoMem,
+    offset_of,
     sync::SpinLock,
     trap::TrapFrame,
     vm::{DmaDirection, DmaStream, DmaStreamSlice, VmAllocOptions, VmIo},
 };
-use aster_util::safe_ptr::SafePtr;
+use aster_util::{field_ptr, safe_ptr::SafePtr};
 use log::info;
 use pod::Pod;
 
@@ -139,6 +140,13 @@ impl aster_block::BlockDevice for BlockDevice {
     fn handle_irq(&self) {
         info!("Virtio block device handle irq");
     }
+
+    fn nr_sectors(&self) -> usize {
+        // The capacity is always in 512-byte sectors, regardless of the block size.
+        field_ptr!(&self.device.config, VirtioBlockConfig, capacity)
+            .read()
+            .unwrap() as usize
+    }
 }
 
 #[derive(Debug)]
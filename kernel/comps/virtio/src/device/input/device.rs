@@ -71,6 +71,8 @@ pub struct InputDevice {
     #[allow(clippy::type_complexity)]
     callbacks: SpinLock<Vec<Arc<dyn Fn(InputEvent) + Send + Sync + 'static>>>,
     transport: Box<dyn VirtioTransport>,
+    /// The name reported by the device.
+    name: String,
 }
 
 impl InputDevice {
@@ -103,12 +105,14 @@ impl InputDevice {
             event_buf: SpinLock::new(event_buf),
             transport,
             callbacks: SpinLock::new(Vec::new()),
+            name: String::new(),
         };
 
         let mut raw_name: [u8; 128] = [0; 128];
-        device.query_config_select(InputConfigSelect::IdName, 0, &mut raw_name);
-        let name = String::from_utf8(raw_name.to_vec()).unwrap();
+        let name_len = device.query_config_select(InputConfigSelect::IdName, 0, &mut raw_name);
+        let name = String::from_utf8(raw_name[..name_len as usize].to_vec()).unwrap();
         info!("Virtio input device name:{}", name);
+        device.name = name;
 
         let mut prop: [u8; 128] = [0; 128];
         device.query_config_select(InputConfigSelect::PropBits, 0, &mut prop);
@@ -220,6 +224,10 @@ impl aster_input::InputDevice for InputDevice {
     fn register_callbacks(&self, function: &'static (dyn Fn(InputEvent) + Send + Sync)) {
         self.callbacks.lock().push(Arc::new(function))
     }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 impl Debug for InputDevice {
@@ -230,6 +238,7 @@ impl Debug for InputDevice {
             .field("status_queue", &self.status_queue)
             .field("event_buf", &self.event_buf)
             .field("transport", &self.transport)
+            .field("name", &self.name)
             .finish()
     }
 }
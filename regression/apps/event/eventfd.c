@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <stdint.h>
+#include <unistd.h>
+#include <poll.h>
+#include <sys/epoll.h>
+#include <sys/eventfd.h>
+
+#include "../network/test.h"
+
+FN_TEST(counter)
+{
+	int fd;
+	uint64_t val;
+
+	fd = TEST_SUCC(eventfd(2, EFD_NONBLOCK));
+
+	val = 3;
+	TEST_RES(write(fd, &val, sizeof(val)), _ret == sizeof(val));
+	TEST_RES(read(fd, &val, sizeof(val)), _ret == sizeof(val) && val == 5);
+	TEST_ERRNO(read(fd, &val, sizeof(val)), EAGAIN);
+
+	// Short buffers and the maximum value are rejected
+	TEST_ERRNO(read(fd, &val, sizeof(val) - 1), EINVAL);
+	val = UINT64_MAX;
+	TEST_ERRNO(write(fd, &val, sizeof(val)), EINVAL);
+
+	// The counter cannot overflow
+	val = UINT64_MAX - 1;
+	TEST_RES(write(fd, &val, sizeof(val)), _ret == sizeof(val));
+	val = 1;
+	TEST_ERRNO(write(fd, &val, sizeof(val)), EAGAIN);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(semaphore)
+{
+	int fd;
+	uint64_t val;
+
+	fd = TEST_SUCC(eventfd(2, EFD_SEMAPHORE | EFD_NONBLOCK));
+
+	TEST_RES(read(fd, &val, sizeof(val)), _ret == sizeof(val) && val == 1);
+	TEST_RES(read(fd, &val, sizeof(val)), _ret == sizeof(val) && val == 1);
+	TEST_ERRNO(read(fd, &val, sizeof(val)), EAGAIN);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(epoll)
+{
+	int fd, epfd;
+	uint64_t val;
+	struct epoll_event event = { .events = EPOLLIN };
+
+	fd = TEST_SUCC(eventfd(0, EFD_CLOEXEC));
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, fd, &event));
+
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	val = 1;
+	TEST_RES(write(fd, &val, sizeof(val)), _ret == sizeof(val));
+	TEST_RES(epoll_wait(epfd, &event, 1, 1000),
+		 _ret == 1 && event.events == EPOLLIN);
+
+	TEST_RES(read(fd, &val, sizeof(val)), _ret == sizeof(val) && val == 1);
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	TEST_SUCC(close(epfd));
+	TEST_SUCC(close(fd));
+}
+END_TEST()
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <limits.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/epoll.h>
+#include <sys/inotify.h>
+#include <sys/stat.h>
+
+#include "../network/test.h"
+
+#define DIR_PATH "/tmp/inotify_test"
+#define FILE_PATH DIR_PATH "/file"
+#define NEW_FILE_PATH DIR_PATH "/new_file"
+
+#define BUF_LEN (16 * (sizeof(struct inotify_event) + NAME_MAX + 1))
+
+static int inotify_fd;
+static char buf[BUF_LEN] __attribute__((aligned(8)));
+
+FN_SETUP(init)
+{
+	CHECK(mkdir(DIR_PATH, 0755));
+	inotify_fd = CHECK(inotify_init1(IN_NONBLOCK | IN_CLOEXEC));
+}
+END_SETUP()
+
+static struct inotify_event *event_at(size_t offset)
+{
+	return (struct inotify_event *)(buf + offset);
+}
+
+static size_t next_event(size_t offset)
+{
+	return offset + sizeof(struct inotify_event) + event_at(offset)->len;
+}
+
+FN_TEST(invalid_args)
+{
+	TEST_ERRNO(inotify_add_watch(inotify_fd, DIR_PATH, 0), EINVAL);
+	TEST_ERRNO(inotify_add_watch(STDIN_FILENO, DIR_PATH, IN_ALL_EVENTS),
+		   EINVAL);
+	TEST_ERRNO(inotify_add_watch(inotify_fd, "/nonexistent", IN_ALL_EVENTS),
+		   ENOENT);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, 12345), EINVAL);
+	TEST_ERRNO(read(inotify_fd, buf, BUF_LEN), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(dir_events)
+{
+	int wd, fd;
+	size_t offset;
+	uint32_t cookie;
+
+	wd = TEST_SUCC(inotify_add_watch(inotify_fd, DIR_PATH,
+					 IN_CREATE | IN_MODIFY | IN_CLOSE_WRITE |
+						 IN_MOVE | IN_DELETE));
+	TEST_ERRNO(inotify_add_watch(inotify_fd, DIR_PATH,
+				     IN_CREATE | IN_MASK_CREATE),
+		   EEXIST);
+
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_WRONLY, 0644));
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_SUCC(close(fd));
+	TEST_SUCC(rename(FILE_PATH, NEW_FILE_PATH));
+	TEST_SUCC(unlink(NEW_FILE_PATH));
+
+	// The events should be reported in order
+	TEST_RES(read(inotify_fd, buf, BUF_LEN), _ret > 0);
+	offset = 0;
+	TEST_RES(0, event_at(offset)->wd == wd &&
+			    event_at(offset)->mask == IN_CREATE &&
+			    strcmp(event_at(offset)->name, "file") == 0);
+	offset = next_event(offset);
+	TEST_RES(0, event_at(offset)->mask == IN_MODIFY &&
+			    strcmp(event_at(offset)->name, "file") == 0);
+	offset = next_event(offset);
+	TEST_RES(0, event_at(offset)->mask == IN_CLOSE_WRITE &&
+			    strcmp(event_at(offset)->name, "file") == 0);
+	offset = next_event(offset);
+	cookie = event_at(offset)->cookie;
+	TEST_RES(0, event_at(offset)->mask == IN_MOVED_FROM && cookie != 0 &&
+			    strcmp(event_at(offset)->name, "file") == 0);
+	offset = next_event(offset);
+	TEST_RES(0, event_at(offset)->mask == IN_MOVED_TO &&
+			    event_at(offset)->cookie == cookie &&
+			    strcmp(event_at(offset)->name, "new_file") == 0);
+	offset = next_event(offset);
+	TEST_RES(0, event_at(offset)->mask == IN_DELETE &&
+			    strcmp(event_at(offset)->name, "new_file") == 0);
+
+	TEST_SUCC(inotify_rm_watch(inotify_fd, wd));
+	TEST_RES(read(inotify_fd, buf, BUF_LEN),
+		 _ret == sizeof(struct inotify_event) &&
+			 event_at(0)->wd == wd && event_at(0)->mask == IN_IGNORED);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, wd), EINVAL);
+}
+END_TEST()
+
+FN_TEST(file_events)
+{
+	int wd, fd;
+
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_WRONLY, 0644));
+	wd = TEST_SUCC(inotify_add_watch(inotify_fd, FILE_PATH,
+					 IN_MODIFY | IN_DELETE_SELF));
+	TEST_ERRNO(inotify_add_watch(inotify_fd, FILE_PATH,
+				     IN_MODIFY | IN_ONLYDIR),
+		   ENOTDIR);
+
+	// Identical successive events should be coalesced
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_RES(read(inotify_fd, buf, BUF_LEN),
+		 _ret == sizeof(struct inotify_event) &&
+			 event_at(0)->wd == wd && event_at(0)->mask == IN_MODIFY &&
+			 event_at(0)->len == 0);
+
+	// The watch is removed once the file is deleted
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(FILE_PATH));
+	TEST_RES(read(inotify_fd, buf, BUF_LEN),
+		 _ret == 2 * sizeof(struct inotify_event) &&
+			 event_at(0)->mask == IN_DELETE_SELF &&
+			 event_at(sizeof(struct inotify_event))->mask ==
+				 IN_IGNORED);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, wd), EINVAL);
+}
+END_TEST()
+
+FN_TEST(epoll)
+{
+	int wd, epfd, fd;
+	struct epoll_event event = { .events = EPOLLIN };
+
+	wd = TEST_SUCC(
+		inotify_add_watch(inotify_fd, DIR_PATH, IN_CREATE | IN_ONESHOT));
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, inotify_fd, &event));
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_WRONLY, 0644));
+	TEST_SUCC(close(fd));
+	TEST_RES(epoll_wait(epfd, &event, 1, 1000),
+		 _ret == 1 && event.events == EPOLLIN);
+
+	// The one-shot watch is removed after the first event
+	TEST_RES(read(inotify_fd, buf, BUF_LEN),
+		 _ret == 2 * sizeof(struct inotify_event) + 16 &&
+			 event_at(0)->mask == IN_CREATE &&
+			 event_at(32)->wd == wd &&
+			 event_at(32)->mask == IN_IGNORED);
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	TEST_SUCC(unlink(FILE_PATH));
+	TEST_ERRNO(read(inotify_fd, buf, BUF_LEN), EAGAIN);
+
+	TEST_SUCC(close(epfd));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(inotify_fd));
+	CHECK(rmdir(DIR_PATH));
+}
+END_SETUP()
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <signal.h>
+#include <time.h>
+#include <unistd.h>
+#include <sys/epoll.h>
+#include <sys/signalfd.h>
+
+#include "../network/test.h"
+
+static sigset_t mask;
+
+FN_SETUP(block_signals)
+{
+	sigemptyset(&mask);
+	sigaddset(&mask, SIGUSR1);
+	sigaddset(&mask, SIGUSR2);
+	CHECK(sigprocmask(SIG_BLOCK, &mask, NULL));
+}
+END_SETUP()
+
+FN_TEST(read_signals)
+{
+	int fd;
+	struct signalfd_siginfo info[2];
+
+	fd = TEST_SUCC(signalfd(-1, &mask, SFD_NONBLOCK));
+
+	TEST_ERRNO(read(fd, info, sizeof(info)), EAGAIN);
+	TEST_ERRNO(read(fd, info, sizeof(info[0]) - 1), EINVAL);
+
+	TEST_SUCC(raise(SIGUSR1));
+	TEST_SUCC(raise(SIGUSR2));
+	TEST_RES(read(fd, info, sizeof(info)),
+		 _ret == sizeof(info) && info[0].ssi_signo == SIGUSR1 &&
+			 info[1].ssi_signo == SIGUSR2);
+	TEST_ERRNO(read(fd, info, sizeof(info)), EAGAIN);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(update_mask)
+{
+	int fd;
+	sigset_t usr2_mask;
+	struct signalfd_siginfo info;
+
+	fd = TEST_SUCC(signalfd(-1, &mask, SFD_NONBLOCK | SFD_CLOEXEC));
+
+	// Only signals in the new mask can be read
+	sigemptyset(&usr2_mask);
+	sigaddset(&usr2_mask, SIGUSR2);
+	TEST_RES(signalfd(fd, &usr2_mask, 0), _ret == fd);
+
+	TEST_SUCC(raise(SIGUSR1));
+	TEST_ERRNO(read(fd, &info, sizeof(info)), EAGAIN);
+	TEST_SUCC(raise(SIGUSR2));
+	TEST_RES(read(fd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR2);
+
+	// Drain the pending SIGUSR1
+	TEST_SUCC(signalfd(fd, &mask, 0));
+	TEST_RES(read(fd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR1);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(epoll)
+{
+	int fd, epfd;
+	struct epoll_event event = { .events = EPOLLIN };
+	struct signalfd_siginfo info;
+
+	fd = TEST_SUCC(signalfd(-1, &mask, 0));
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, fd, &event));
+
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	TEST_SUCC(raise(SIGUSR1));
+	TEST_RES(epoll_wait(epfd, &event, 1, 1000),
+		 _ret == 1 && event.events == EPOLLIN);
+	TEST_RES(read(fd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR1);
+
+	TEST_SUCC(close(epfd));
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(siginfo)
+{
+	int fd;
+	timer_t timer;
+	struct signalfd_siginfo info;
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR2,
+				.sigev_value.sival_int = 42 };
+	struct itimerspec its = { .it_value.tv_nsec = 1000000 };
+
+	fd = TEST_SUCC(signalfd(-1, &mask, 0));
+
+	// The sender is reported for the signals sent by `kill`
+	TEST_SUCC(kill(getpid(), SIGUSR1));
+	TEST_RES(read(fd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR1 &&
+			 info.ssi_code == SI_USER &&
+			 info.ssi_pid == getpid() && info.ssi_uid == getuid());
+
+	// The value is reported for the signals sent by timers
+	TEST_SUCC(timer_create(CLOCK_MONOTONIC, &sev, &timer));
+	TEST_SUCC(timer_settime(timer, 0, &its, NULL));
+	TEST_RES(read(fd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR2 &&
+			 info.ssi_code == SI_TIMER && info.ssi_int == 42);
+	TEST_SUCC(timer_delete(timer));
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <stdint.h>
+#include <time.h>
+#include <unistd.h>
+#include <sys/epoll.h>
+#include <sys/timerfd.h>
+
+#include "../network/test.h"
+
+#define MSEC_TO_NSEC 1000000
+
+FN_TEST(one_shot)
+{
+	int fd;
+	uint64_t expirations;
+	struct itimerspec value = { .it_value.tv_nsec = 10 * MSEC_TO_NSEC };
+	struct itimerspec old_value;
+
+	fd = TEST_SUCC(timerfd_create(CLOCK_MONOTONIC, 0));
+
+	TEST_SUCC(timerfd_settime(fd, 0, &value, NULL));
+	TEST_RES(read(fd, &expirations, sizeof(expirations)),
+		 _ret == sizeof(expirations) && expirations == 1);
+
+	// The timer is disarmed after expiration
+	TEST_RES(timerfd_gettime(fd, &old_value),
+		 old_value.it_value.tv_sec == 0 &&
+			 old_value.it_value.tv_nsec == 0);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(periodic)
+{
+	int fd;
+	uint64_t expirations;
+	struct itimerspec value = {
+		.it_value.tv_nsec = 10 * MSEC_TO_NSEC,
+		.it_interval.tv_nsec = 10 * MSEC_TO_NSEC,
+	};
+	struct itimerspec curr_value;
+
+	fd = TEST_SUCC(timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK));
+
+	TEST_ERRNO(read(fd, &expirations, sizeof(expirations)), EAGAIN);
+
+	TEST_SUCC(timerfd_settime(fd, 0, &value, NULL));
+	TEST_RES(timerfd_gettime(fd, &curr_value),
+		 curr_value.it_interval.tv_nsec == 10 * MSEC_TO_NSEC);
+
+	// All the missed expirations are counted
+	TEST_SUCC(usleep(55 * 1000));
+	TEST_RES(read(fd, &expirations, sizeof(expirations)),
+		 _ret == sizeof(expirations) && expirations >= 4);
+
+	// Disarm the timer
+	value.it_value.tv_nsec = 0;
+	TEST_RES(timerfd_settime(fd, 0, &value, &curr_value),
+		 curr_value.it_interval.tv_nsec == 10 * MSEC_TO_NSEC);
+	TEST_SUCC(usleep(20 * 1000));
+	TEST_ERRNO(read(fd, &expirations, sizeof(expirations)), EAGAIN);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(abs_time_epoll)
+{
+	int fd, epfd;
+	uint64_t expirations;
+	struct timespec now;
+	struct itimerspec value = {};
+	struct epoll_event event = { .events = EPOLLIN };
+
+	fd = TEST_SUCC(timerfd_create(CLOCK_REALTIME, TFD_CLOEXEC));
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, fd, &event));
+
+	TEST_SUCC(clock_gettime(CLOCK_REALTIME, &now));
+	value.it_value.tv_sec = now.tv_sec + 1;
+	TEST_SUCC(timerfd_settime(fd, TFD_TIMER_ABSTIME, &value, NULL));
+
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+	TEST_RES(epoll_wait(epfd, &event, 1, 3000),
+		 _ret == 1 && event.events == EPOLLIN);
+	TEST_RES(read(fd, &expirations, sizeof(expirations)),
+		 _ret == sizeof(expirations) && expirations == 1);
+
+	// Invalid time values are rejected
+	value.it_value.tv_nsec = 1000 * MSEC_TO_NSEC;
+	TEST_ERRNO(timerfd_settime(fd, 0, &value, NULL), EINVAL);
+
+	TEST_SUCC(close(epfd));
+	TEST_SUCC(close(fd));
+}
+END_TEST()
@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define HUGE_PAGE_SIZE (2 * 1024 * 1024)
+#define NR_HUGE_PAGE_PAGES (HUGE_PAGE_SIZE / PAGE_SIZE)
+#define FILE_PATH "/tmp/huge_page_test"
+
+static char *addr;
+
+FN_SETUP(map)
+{
+	addr = mmap(NULL, HUGE_PAGE_SIZE * 2, PROT_READ | PROT_WRITE,
+		    MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK_WITH(addr != MAP_FAILED, _ret);
+	CHECK(madvise(addr, HUGE_PAGE_SIZE * 2, MADV_HUGEPAGE));
+}
+END_SETUP()
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+static int is_huge_aligned(char *ptr)
+{
+	return (unsigned long)ptr % HUGE_PAGE_SIZE == 0;
+}
+
+// Returns the number of resident pages in the huge page at `huge_addr`.
+static int nr_resident_pages(char *huge_addr)
+{
+	unsigned char vec[NR_HUGE_PAGE_PAGES];
+	int i, nr_pages = 0;
+
+	if (mincore(huge_addr, HUGE_PAGE_SIZE, vec) < 0)
+		return -1;
+	for (i = 0; i < NR_HUGE_PAGE_PAGES; i++)
+		nr_pages += vec[i] & 1;
+	return nr_pages;
+}
+
+// Checks that each of the first pages holds its index, except the page at
+// `skipped`, which holds `value`.
+static int check_pages(int nr_pages, int skipped, char value)
+{
+	int i;
+
+	for (i = 0; i < nr_pages; i++) {
+		if (addr[PAGE_SIZE * i] != (i == skipped ? value : (char)i))
+			return -1;
+	}
+	return 0;
+}
+
+FN_TEST(fault)
+{
+	int i;
+
+	// Large anonymous mappings are aligned to be backed by huge pages
+	TEST_RES(is_huge_aligned(addr), _ret);
+
+	// A single fault populates the whole huge page
+	TEST_RES(nr_resident_pages(addr), _ret == 0);
+	addr[0] = 0;
+	TEST_RES(nr_resident_pages(addr), _ret == NR_HUGE_PAGE_PAGES);
+	TEST_RES(nr_resident_pages(addr + HUGE_PAGE_SIZE), _ret == 0);
+
+	for (i = 0; i < NR_HUGE_PAGE_PAGES; i++)
+		addr[PAGE_SIZE * i] = i;
+	TEST_RES(check_pages(NR_HUGE_PAGE_PAGES, -1, 0), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(split_mprotect)
+{
+	TEST_SUCC(mprotect(addr + PAGE_SIZE, PAGE_SIZE, PROT_READ));
+	TEST_RES(check_pages(NR_HUGE_PAGE_PAGES, -1, 0), _ret == 0);
+
+	// The other pages are still writable
+	addr[0] = 0;
+	addr[PAGE_SIZE * 2] = 2;
+	TEST_SUCC(mprotect(addr + PAGE_SIZE, PAGE_SIZE,
+			   PROT_READ | PROT_WRITE));
+	addr[PAGE_SIZE] = 1;
+	TEST_RES(check_pages(NR_HUGE_PAGE_PAGES, -1, 0), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(split_munmap)
+{
+	unsigned char vec;
+
+	TEST_SUCC(munmap(addr + PAGE_SIZE * 2, PAGE_SIZE));
+	TEST_ERRNO(mincore(addr + PAGE_SIZE * 2, PAGE_SIZE, &vec), ENOMEM);
+	TEST_RES(addr[PAGE_SIZE], _ret == 1);
+	TEST_RES(addr[PAGE_SIZE * 3], _ret == 3);
+
+	// The hole can be mapped again
+	TEST_RES((long)mmap(addr + PAGE_SIZE * 2, PAGE_SIZE,
+			    PROT_READ | PROT_WRITE,
+			    MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0),
+		 _ret == (long)(addr + PAGE_SIZE * 2));
+	TEST_RES(check_pages(NR_HUGE_PAGE_PAGES, 2, 0), _ret == 0);
+	addr[PAGE_SIZE * 2] = 2;
+}
+END_TEST()
+
+FN_TEST(split_dontneed)
+{
+	TEST_SUCC(madvise(addr + PAGE_SIZE * 3, PAGE_SIZE, MADV_DONTNEED));
+	TEST_RES(check_pages(NR_HUGE_PAGE_PAGES, 3, 0), _ret == 0);
+	addr[PAGE_SIZE * 3] = 3;
+}
+END_TEST()
+
+FN_TEST(fork)
+{
+	pid_t pid;
+
+	addr[HUGE_PAGE_SIZE] = 'a';
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		// The writes of the child are not visible to the parent
+		addr[HUGE_PAGE_SIZE] = 'b';
+		addr[HUGE_PAGE_SIZE + PAGE_SIZE] = 'c';
+		if (check_pages(NR_HUGE_PAGE_PAGES, -1, 0) < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(wait_child(pid), _ret == 0);
+	TEST_RES(addr[HUGE_PAGE_SIZE], _ret == 'a');
+	TEST_RES(addr[HUGE_PAGE_SIZE + PAGE_SIZE], _ret == 0);
+}
+END_TEST()
+
+FN_TEST(hugetlb)
+{
+	char *huge_addr;
+
+	// The length is rounded up to the huge page size
+	huge_addr = mmap(NULL, HUGE_PAGE_SIZE + 1, PROT_READ | PROT_WRITE,
+			 MAP_PRIVATE | MAP_ANONYMOUS | MAP_HUGETLB, -1, 0);
+	TEST_RES(huge_addr != MAP_FAILED, _ret);
+	TEST_RES(is_huge_aligned(huge_addr), _ret);
+
+	huge_addr[HUGE_PAGE_SIZE] = 'a';
+	TEST_RES(nr_resident_pages(huge_addr + HUGE_PAGE_SIZE),
+		 _ret == NR_HUGE_PAGE_PAGES);
+	TEST_RES(huge_addr[HUGE_PAGE_SIZE * 2 - 1], _ret == 0);
+
+	TEST_SUCC(munmap(huge_addr, HUGE_PAGE_SIZE * 2));
+}
+END_TEST()
+
+FN_TEST(hugetlb_invalid)
+{
+	int fd;
+
+	// The address must be aligned to the huge page size
+	TEST_ERRNO((long)mmap(addr + PAGE_SIZE, HUGE_PAGE_SIZE,
+			      PROT_READ | PROT_WRITE,
+			      MAP_PRIVATE | MAP_ANONYMOUS | MAP_HUGETLB |
+				      MAP_FIXED,
+			      -1, 0),
+		   EINVAL);
+
+	// Huge pages are not supported for regular files
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	TEST_SUCC(ftruncate(fd, HUGE_PAGE_SIZE));
+	TEST_ERRNO((long)mmap(NULL, HUGE_PAGE_SIZE, PROT_READ,
+			      MAP_PRIVATE | MAP_HUGETLB, fd, 0),
+		   EINVAL);
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(FILE_PATH));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(munmap(addr, HUGE_PAGE_SIZE * 2));
+}
+END_SETUP()
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define NR_PAGES 4
+#define FILE_PATH "/tmp/madvise_test"
+
+static char *private_anon;
+static char *shared_anon;
+
+static long map_anon(int flags)
+{
+	return (long)mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ | PROT_WRITE,
+			  flags | MAP_ANONYMOUS, -1, 0);
+}
+
+FN_SETUP(map)
+{
+	private_anon = (char *)CHECK_WITH(map_anon(MAP_PRIVATE),
+					  _ret != (long)MAP_FAILED);
+	shared_anon = (char *)CHECK_WITH(map_anon(MAP_SHARED),
+					 _ret != (long)MAP_FAILED);
+}
+END_SETUP()
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+FN_TEST(dontneed)
+{
+	// The pages of private mappings are zero-filled again
+	private_anon[0] = 'a';
+	private_anon[PAGE_SIZE] = 'b';
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE, MADV_DONTNEED));
+	TEST_RES(private_anon[0], _ret == 0);
+	TEST_RES(private_anon[PAGE_SIZE], _ret == 'b');
+
+	// The pages of shared mappings keep their contents
+	shared_anon[0] = 'c';
+	TEST_SUCC(madvise(shared_anon, PAGE_SIZE * NR_PAGES, MADV_DONTNEED));
+	TEST_RES(shared_anon[0], _ret == 'c');
+}
+END_TEST()
+
+FN_TEST(free)
+{
+	private_anon[0] = 'a';
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_FREE));
+	TEST_ERRNO(madvise(shared_anon, PAGE_SIZE, MADV_FREE), EINVAL);
+}
+END_TEST()
+
+FN_TEST(remove)
+{
+	shared_anon[0] = 'a';
+	shared_anon[PAGE_SIZE] = 'b';
+	TEST_SUCC(madvise(shared_anon, PAGE_SIZE, MADV_REMOVE));
+	TEST_RES(shared_anon[0], _ret == 0);
+	TEST_RES(shared_anon[PAGE_SIZE], _ret == 'b');
+
+	TEST_ERRNO(madvise(private_anon, PAGE_SIZE, MADV_REMOVE), EINVAL);
+}
+END_TEST()
+
+// Checks whether the first page of the private mapping is mapped in a child.
+static int check_mapped_in_child(int is_mapped)
+{
+	unsigned char vec;
+	pid_t pid;
+
+	pid = fork();
+	if (pid < 0)
+		return -1;
+	if (pid == 0) {
+		if ((mincore(private_anon, PAGE_SIZE, &vec) == 0) != is_mapped)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	return wait_child(pid);
+}
+
+FN_TEST(dontfork)
+{
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_DONTFORK));
+	TEST_RES(check_mapped_in_child(0), _ret == 0);
+
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_DOFORK));
+	TEST_RES(check_mapped_in_child(1), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(hugepage)
+{
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_HUGEPAGE));
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE, MADV_NOHUGEPAGE));
+	TEST_SUCC(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_NORMAL));
+}
+END_TEST()
+
+FN_TEST(willneed)
+{
+	char data[PAGE_SIZE * NR_PAGES] = { 'a' };
+	char *addr;
+	int fd;
+
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	TEST_RES(write(fd, data, sizeof(data)), _ret == sizeof(data));
+	addr = mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ, MAP_PRIVATE, fd, 0);
+	TEST_RES(addr != MAP_FAILED, _ret);
+
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_WILLNEED));
+	TEST_RES(addr[0], _ret == 'a');
+
+	TEST_SUCC(munmap(addr, PAGE_SIZE * NR_PAGES));
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(FILE_PATH));
+}
+END_TEST()
+
+FN_TEST(madvise_invalid)
+{
+	TEST_ERRNO(madvise(private_anon + 1, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_ERRNO(madvise(private_anon, PAGE_SIZE, 0xff), EINVAL);
+	TEST_SUCC(madvise(private_anon, 0, MADV_DONTNEED));
+
+	// The range must be fully mapped
+	TEST_SUCC(munmap(private_anon + PAGE_SIZE * (NR_PAGES - 1), PAGE_SIZE));
+	TEST_ERRNO(madvise(private_anon, PAGE_SIZE * NR_PAGES, MADV_WILLNEED),
+		   ENOMEM);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(munmap(private_anon, PAGE_SIZE * (NR_PAGES - 1)));
+	CHECK(munmap(shared_anon, PAGE_SIZE * NR_PAGES));
+}
+END_SETUP()
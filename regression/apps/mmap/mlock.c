@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <sys/mman.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define NR_PAGES 4
+
+static char *addr;
+
+FN_SETUP(map)
+{
+	addr = mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ | PROT_WRITE,
+		    MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK_WITH(addr != MAP_FAILED, _ret);
+}
+END_SETUP()
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+// Returns the number of resident pages in the mapping.
+static int nr_resident_pages(void)
+{
+	unsigned char vec[NR_PAGES];
+	int i, nr_pages = 0;
+
+	if (mincore(addr, PAGE_SIZE * NR_PAGES, vec) < 0)
+		return -1;
+	for (i = 0; i < NR_PAGES; i++)
+		nr_pages += vec[i] & 1;
+	return nr_pages;
+}
+
+FN_TEST(mlock)
+{
+	// The locked pages are populated
+	TEST_RES(nr_resident_pages(), _ret == 0);
+	TEST_SUCC(mlock(addr + 1, PAGE_SIZE));
+	TEST_RES(nr_resident_pages(), _ret == 2);
+
+	// The locked pages cannot be discarded
+	TEST_ERRNO(madvise(addr, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(madvise(addr + PAGE_SIZE * 2, PAGE_SIZE, MADV_DONTNEED));
+
+	// Locking the pages again is harmless
+	TEST_SUCC(mlock(addr, PAGE_SIZE * 2));
+	TEST_SUCC(munlock(addr, PAGE_SIZE));
+	TEST_SUCC(madvise(addr, PAGE_SIZE, MADV_DONTNEED));
+	TEST_ERRNO(madvise(addr + PAGE_SIZE, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(munlock(addr, PAGE_SIZE * NR_PAGES));
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_DONTNEED));
+	TEST_RES(nr_resident_pages(), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(mlockall)
+{
+	TEST_SUCC(mlockall(MCL_CURRENT));
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+	TEST_ERRNO(madvise(addr, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(munlockall());
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_DONTNEED));
+
+	// The pages are locked on faults
+	TEST_SUCC(mlockall(MCL_CURRENT | MCL_ONFAULT));
+	TEST_RES(nr_resident_pages(), _ret == 0);
+	TEST_ERRNO(madvise(addr, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(munlockall());
+}
+END_TEST()
+
+FN_TEST(mlockall_future)
+{
+	char *new_addr;
+
+	TEST_SUCC(mlockall(MCL_FUTURE));
+	new_addr = mmap(NULL, PAGE_SIZE, PROT_READ | PROT_WRITE,
+			MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	TEST_RES(new_addr != MAP_FAILED, _ret);
+	TEST_ERRNO(madvise(new_addr, PAGE_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(madvise(addr, PAGE_SIZE, MADV_DONTNEED));
+	TEST_SUCC(munlockall());
+
+	TEST_SUCC(madvise(new_addr, PAGE_SIZE, MADV_DONTNEED));
+	TEST_SUCC(munmap(new_addr, PAGE_SIZE));
+}
+END_TEST()
+
+FN_TEST(mlock_invalid)
+{
+	TEST_ERRNO(mlockall(0), EINVAL);
+	TEST_ERRNO(mlockall(MCL_ONFAULT), EINVAL);
+	TEST_ERRNO(mlockall(0xff), EINVAL);
+
+	// The range must be fully mapped
+	TEST_SUCC(munmap(addr + PAGE_SIZE * (NR_PAGES - 1), PAGE_SIZE));
+	TEST_ERRNO(mlock(addr, PAGE_SIZE * NR_PAGES), ENOMEM);
+	TEST_ERRNO(munlock(addr, PAGE_SIZE * NR_PAGES), ENOMEM);
+}
+END_TEST()
+
+static int check_memlock_limit(void)
+{
+	struct rlimit limit = { .rlim_cur = PAGE_SIZE, .rlim_max = PAGE_SIZE };
+
+	// Privileged users are not limited
+	if (setrlimit(RLIMIT_MEMLOCK, &limit) < 0 || setuid(65534) < 0)
+		return -1;
+
+	// The locked size cannot exceed the limit
+	if (mlock(addr, PAGE_SIZE) < 0)
+		return -1;
+	if (mlock(addr, PAGE_SIZE) < 0)
+		return -1;
+	if (mlock(addr, PAGE_SIZE * 2) == 0 || errno != ENOMEM)
+		return -1;
+	if (mlockall(MCL_CURRENT) == 0 || errno != ENOMEM)
+		return -1;
+
+	// Locking is not allowed at all if the limit is zero
+	limit.rlim_cur = 0;
+	if (setrlimit(RLIMIT_MEMLOCK, &limit) < 0)
+		return -1;
+	if (mlock(addr, PAGE_SIZE) == 0 || errno != EPERM)
+		return -1;
+
+	return 0;
+}
+
+FN_TEST(memlock_limit)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_memlock_limit() < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(wait_child(pid), _ret == 0);
+}
+END_TEST()
+
+static int check_memlock_limit_privileged(void)
+{
+	struct rlimit limit = { .rlim_cur = 0, .rlim_max = 0 };
+
+	// Privileged users can lock memory even if the limit is zero
+	if (setrlimit(RLIMIT_MEMLOCK, &limit) < 0)
+		return -1;
+	if (mlock(addr, PAGE_SIZE * 2) < 0 || mlockall(MCL_CURRENT) < 0)
+		return -1;
+	return munlockall();
+}
+
+FN_TEST(memlock_limit_privileged)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_memlock_limit_privileged() < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(wait_child(pid), _ret == 0);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(munmap(addr, PAGE_SIZE * (NR_PAGES - 1)));
+}
+END_SETUP()
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <stdint.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define FILE_PATH "/tmp/mremap_test"
+
+// Maps anonymous pages at `addr`, or anywhere if `addr` is NULL.
+static long map_pages(char *addr, int nr_pages)
+{
+	int flags = MAP_PRIVATE | MAP_ANONYMOUS | (addr ? MAP_FIXED : 0);
+
+	return (long)mmap(addr, PAGE_SIZE * nr_pages, PROT_READ | PROT_WRITE,
+			  flags, -1, 0);
+}
+
+// Remaps the pages, with the sizes specified in pages.
+static long remap(char *addr, int old_pages, int new_pages, int flags,
+		  char *new_addr)
+{
+	return (long)mremap(addr, PAGE_SIZE * old_pages, PAGE_SIZE * new_pages,
+			    flags, new_addr);
+}
+
+// Checks the residency of the page at `addr`, which fails if it is unmapped.
+static int mincore_page(char *addr)
+{
+	unsigned char vec;
+
+	return mincore(addr, PAGE_SIZE, &vec);
+}
+
+static char *addr, *new_addr;
+
+FN_SETUP(map)
+{
+	// Leave two free pages after the mapping, so that it can grow in place
+	addr = (char *)CHECK_WITH(map_pages(NULL, 4),
+				  _ret != (long)MAP_FAILED);
+	CHECK(munmap(addr + PAGE_SIZE * 2, PAGE_SIZE * 2));
+	addr[0] = 'a';
+	addr[PAGE_SIZE] = 'b';
+}
+END_SETUP()
+
+FN_TEST(grow_in_place)
+{
+	TEST_RES(remap(addr, 2, 4, 0, NULL), _ret == (long)addr);
+	TEST_RES(addr[0], _ret == 'a');
+	TEST_RES(addr[PAGE_SIZE], _ret == 'b');
+	TEST_RES(addr[PAGE_SIZE * 3], _ret == 0);
+	addr[PAGE_SIZE * 3] = 'd';
+	TEST_RES(addr[PAGE_SIZE * 3], _ret == 'd');
+}
+END_TEST()
+
+FN_TEST(shrink)
+{
+	TEST_RES(remap(addr, 4, 2, 0, NULL), _ret == (long)addr);
+	TEST_RES(addr[PAGE_SIZE], _ret == 'b');
+	TEST_ERRNO(mincore_page(addr + PAGE_SIZE * 2), ENOMEM);
+}
+END_TEST()
+
+FN_TEST(grow_and_move)
+{
+	// Occupy the page after the mapping
+	TEST_RES(map_pages(addr + PAGE_SIZE * 2, 1),
+		 _ret == (long)addr + PAGE_SIZE * 2);
+
+	TEST_ERRNO(remap(addr, 2, 3, 0, NULL), ENOMEM);
+	new_addr = (char *)TEST_RES(remap(addr, 2, 3, MREMAP_MAYMOVE, NULL),
+				    _ret != (long)MAP_FAILED &&
+					    _ret != (long)addr);
+	TEST_RES(new_addr[0], _ret == 'a');
+	TEST_RES(new_addr[PAGE_SIZE], _ret == 'b');
+	TEST_RES(new_addr[PAGE_SIZE * 2], _ret == 0);
+	TEST_ERRNO(mincore_page(addr), ENOMEM);
+	TEST_SUCC(mincore_page(addr + PAGE_SIZE * 2));
+}
+END_TEST()
+
+FN_TEST(move_to_fixed)
+{
+	// Move the pages back to the old address, replacing the mapping there
+	TEST_RES(remap(new_addr, 3, 3, MREMAP_MAYMOVE | MREMAP_FIXED, addr),
+		 _ret == (long)addr);
+	TEST_RES(addr[0], _ret == 'a');
+	TEST_RES(addr[PAGE_SIZE], _ret == 'b');
+	TEST_ERRNO(mincore_page(new_addr), ENOMEM);
+}
+END_TEST()
+
+FN_TEST(invalid_args)
+{
+	TEST_ERRNO(remap(addr + 1, 1, 1, 0, NULL), EINVAL);
+	TEST_ERRNO(remap(addr, 1, 0, 0, NULL), EINVAL);
+	TEST_ERRNO(remap(addr, 1, 1, 0xff, NULL), EINVAL);
+	TEST_ERRNO(remap(addr, 1, 1, MREMAP_FIXED, new_addr), EINVAL);
+	TEST_ERRNO(remap(addr, 2, 2, MREMAP_MAYMOVE | MREMAP_FIXED,
+			 addr + PAGE_SIZE),
+		   EINVAL);
+	TEST_ERRNO(remap(new_addr, 1, 2, MREMAP_MAYMOVE, NULL), EFAULT);
+	TEST_ERRNO((long)mremap(addr, PAGE_SIZE, SIZE_MAX, MREMAP_MAYMOVE),
+		   EINVAL);
+	TEST_ERRNO(mincore(addr, SIZE_MAX, NULL), ENOMEM);
+	TEST_SUCC(munmap(addr, PAGE_SIZE * 3));
+}
+END_TEST()
+
+FN_TEST(shared_file)
+{
+	char data[PAGE_SIZE * 2];
+	int fd;
+
+	memset(data, 'x', PAGE_SIZE);
+	memset(data + PAGE_SIZE, 'y', PAGE_SIZE);
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	TEST_RES(write(fd, data, sizeof(data)), _ret == sizeof(data));
+
+	// Growing the mapping maps more pages of the file
+	addr = mmap(NULL, PAGE_SIZE, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+	TEST_RES(addr != MAP_FAILED, _ret);
+	addr = (char *)TEST_RES(remap(addr, 1, 2, MREMAP_MAYMOVE, NULL),
+				_ret != (long)MAP_FAILED);
+	TEST_RES(addr[0], _ret == 'x');
+	TEST_RES(addr[PAGE_SIZE], _ret == 'y');
+
+	// The updates are still shared with the file
+	addr[PAGE_SIZE] = 'z';
+	TEST_RES(pread(fd, data, 1, PAGE_SIZE), _ret == 1 && data[0] == 'z');
+
+	TEST_SUCC(munmap(addr, PAGE_SIZE * 2));
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(FILE_PATH));
+}
+END_TEST()
+
+FN_TEST(private_file)
+{
+	char data[PAGE_SIZE * 2];
+	int fd;
+
+	memset(data, 'x', PAGE_SIZE);
+	memset(data + PAGE_SIZE, 'y', PAGE_SIZE);
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	TEST_RES(write(fd, data, sizeof(data)), _ret == sizeof(data));
+
+	addr = mmap(NULL, PAGE_SIZE, PROT_READ | PROT_WRITE, MAP_PRIVATE, fd,
+		    0);
+	TEST_RES(addr != MAP_FAILED, _ret);
+	addr[0] = 'p';
+
+	// The private updates are kept, and the new pages are read from the file
+	addr = (char *)TEST_RES(remap(addr, 1, 2, MREMAP_MAYMOVE, NULL),
+				_ret != (long)MAP_FAILED);
+	TEST_RES(addr[0], _ret == 'p');
+	TEST_RES(addr[PAGE_SIZE], _ret == 'y');
+	addr[PAGE_SIZE] = 'q';
+	TEST_RES(pread(fd, data, sizeof(data), 0),
+		 _ret == sizeof(data) && data[0] == 'x' &&
+			 data[PAGE_SIZE] == 'y');
+
+	TEST_SUCC(munmap(addr, PAGE_SIZE * 2));
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(FILE_PATH));
+}
+END_TEST()
+
+FN_TEST(grow_after_fork)
+{
+	int status;
+	pid_t pid;
+
+	addr = (char *)TEST_RES(map_pages(NULL, 1), _ret != (long)MAP_FAILED);
+	addr[0] = 'a';
+
+	// The pages moved by the child are still copied on write
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		addr = mremap(addr, PAGE_SIZE, PAGE_SIZE * 2, MREMAP_MAYMOVE);
+		if (addr == MAP_FAILED || addr[0] != 'a' || addr[PAGE_SIZE])
+			_exit(EXIT_FAILURE);
+		addr[0] = 'c';
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+	TEST_RES(addr[0], _ret == 'a');
+
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+}
+END_TEST()
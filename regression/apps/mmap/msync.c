@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define NR_PAGES 4
+#define FILE_PATH "/tmp/msync_test"
+
+static int fd;
+static char *addr;
+
+FN_SETUP(map)
+{
+	char data[PAGE_SIZE * NR_PAGES] = {};
+
+	fd = CHECK(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	CHECK_WITH(write(fd, data, sizeof(data)), _ret == sizeof(data));
+	addr = mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ | PROT_WRITE,
+		    MAP_SHARED, fd, 0);
+	CHECK_WITH(addr != MAP_FAILED, _ret);
+}
+END_SETUP()
+
+// Reads a byte in the file at `offset`.
+static int read_byte(off_t offset)
+{
+	char byte;
+
+	if (pread(fd, &byte, 1, offset) != 1)
+		return -1;
+	return byte;
+}
+
+FN_TEST(msync)
+{
+	addr[0] = 'a';
+	TEST_SUCC(msync(addr, PAGE_SIZE, MS_SYNC));
+	TEST_RES(read_byte(0), _ret == 'a');
+
+	addr[PAGE_SIZE + 1] = 'b';
+	TEST_SUCC(msync(addr + PAGE_SIZE, PAGE_SIZE, MS_ASYNC));
+	TEST_RES(read_byte(PAGE_SIZE + 1), _ret == 'b');
+
+	addr[PAGE_SIZE * 2 + 2] = 'c';
+	TEST_SUCC(msync(addr, PAGE_SIZE * NR_PAGES, MS_SYNC | MS_INVALIDATE));
+	TEST_RES(read_byte(PAGE_SIZE * 2 + 2), _ret == 'c');
+
+	// The length is rounded up to the page size
+	TEST_SUCC(msync(addr, 1, MS_SYNC));
+	TEST_SUCC(msync(addr, 0, MS_SYNC));
+}
+END_TEST()
+
+FN_TEST(msync_invalid)
+{
+	TEST_ERRNO(msync(addr + 1, PAGE_SIZE, MS_SYNC), EINVAL);
+	TEST_ERRNO(msync(addr, PAGE_SIZE, MS_SYNC | MS_ASYNC), EINVAL);
+	TEST_ERRNO(msync(addr, PAGE_SIZE, 0xff), EINVAL);
+
+	// The range must be fully mapped
+	TEST_SUCC(munmap(addr + PAGE_SIZE * (NR_PAGES - 1), PAGE_SIZE));
+	TEST_ERRNO(msync(addr, PAGE_SIZE * NR_PAGES, MS_SYNC), ENOMEM);
+}
+END_TEST()
+
+FN_TEST(mincore)
+{
+	unsigned char vec[NR_PAGES];
+	char *anon;
+
+	anon = mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ | PROT_WRITE,
+		    MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	TEST_RES(anon != MAP_FAILED, _ret);
+
+	// Only the touched pages are resident
+	TEST_RES(mincore(anon, PAGE_SIZE * NR_PAGES, vec),
+		 !vec[0] && !vec[1] && !vec[2] && !vec[3]);
+	anon[0] = 1;
+	anon[PAGE_SIZE * 2] = 1;
+	TEST_RES(mincore(anon, PAGE_SIZE * NR_PAGES, vec),
+		 vec[0] && !vec[1] && vec[2] && !vec[3]);
+	TEST_RES(mincore(anon + PAGE_SIZE * 2, 1, vec), vec[0] == 1);
+
+	TEST_ERRNO(mincore(anon + 1, PAGE_SIZE, vec), EINVAL);
+	TEST_SUCC(munmap(anon + PAGE_SIZE, PAGE_SIZE));
+	TEST_ERRNO(mincore(anon, PAGE_SIZE * NR_PAGES, vec), ENOMEM);
+	TEST_SUCC(munmap(anon, PAGE_SIZE * NR_PAGES));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(munmap(addr, PAGE_SIZE * NR_PAGES));
+	CHECK(close(fd));
+	CHECK(unlink(FILE_PATH));
+}
+END_SETUP()
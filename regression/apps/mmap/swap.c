@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/swap.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define PAGE_SIZE 4096
+#define NR_PAGES 16
+#define SWAP_DEVICE "/dev/vswap"
+#define FILE_PATH "/tmp/swap_test"
+
+static char *addr;
+
+FN_SETUP(map)
+{
+	addr = mmap(NULL, PAGE_SIZE * NR_PAGES, PROT_READ | PROT_WRITE,
+		    MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK_WITH(addr != MAP_FAILED, _ret);
+}
+END_SETUP()
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+// Fills each page with a byte derived from its index and `seed`.
+static void fill_pages(char seed)
+{
+	int i;
+
+	for (i = 0; i < NR_PAGES; i++)
+		memset(addr + PAGE_SIZE * i, seed + i, PAGE_SIZE);
+}
+
+// Checks that the pages are filled by `fill_pages` with `seed`.
+static int check_pages(char seed)
+{
+	int i, j;
+
+	for (i = 0; i < NR_PAGES; i++) {
+		for (j = 0; j < PAGE_SIZE; j++) {
+			if (addr[PAGE_SIZE * i + j] != (char)(seed + i))
+				return -1;
+		}
+	}
+	return 0;
+}
+
+// Returns the number of resident pages in the mapping.
+static int nr_resident_pages(void)
+{
+	unsigned char vec[NR_PAGES];
+	int i, nr_pages = 0;
+
+	if (mincore(addr, PAGE_SIZE * NR_PAGES, vec) < 0)
+		return -1;
+	for (i = 0; i < NR_PAGES; i++)
+		nr_pages += vec[i] & 1;
+	return nr_pages;
+}
+
+// Returns whether the swap device is listed in `/proc/swaps`.
+static int is_swap_listed(void)
+{
+	char buf[1024];
+	ssize_t len;
+	int fd;
+
+	fd = open("/proc/swaps", O_RDONLY);
+	if (fd < 0)
+		return -1;
+	len = read(fd, buf, sizeof(buf) - 1);
+	close(fd);
+	if (len < 0)
+		return -1;
+	buf[len] = '\0';
+	return strstr(buf, SWAP_DEVICE " ") != NULL;
+}
+
+FN_TEST(swapon)
+{
+	int fd;
+
+	TEST_RES(is_swap_listed(), _ret == 0);
+	TEST_SUCC(swapon(SWAP_DEVICE, 0));
+	TEST_RES(is_swap_listed(), _ret == 1);
+
+	// The device cannot be enabled twice
+	TEST_ERRNO(swapon(SWAP_DEVICE, 0), EBUSY);
+
+	// Regular files are not formatted as swap devices
+	fd = TEST_SUCC(open(FILE_PATH, O_CREAT | O_RDWR | O_TRUNC, 0644));
+	TEST_SUCC(ftruncate(fd, PAGE_SIZE * NR_PAGES));
+	TEST_SUCC(close(fd));
+	TEST_ERRNO(swapon(FILE_PATH, 0), EINVAL);
+	TEST_SUCC(unlink(FILE_PATH));
+}
+END_TEST()
+
+static int check_unprivileged(void)
+{
+	if (setuid(65534) < 0)
+		return -1;
+
+	// Only privileged users can turn swap on or off
+	if (swapon(SWAP_DEVICE, 0) == 0 || errno != EPERM)
+		return -1;
+	if (swapoff(SWAP_DEVICE) == 0 || errno != EPERM)
+		return -1;
+
+	return 0;
+}
+
+FN_TEST(unprivileged)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_unprivileged() < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(wait_child(pid), _ret == 0);
+	TEST_RES(is_swap_listed(), _ret == 1);
+}
+END_TEST()
+
+FN_TEST(pageout)
+{
+	fill_pages('a');
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_PAGEOUT));
+	TEST_RES(nr_resident_pages(), _ret == 0);
+
+	// The pages are swapped in on accesses
+	TEST_RES(check_pages('a'), _ret == 0);
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+}
+END_TEST()
+
+FN_TEST(fork)
+{
+	pid_t pid;
+
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_PAGEOUT));
+	TEST_RES(nr_resident_pages(), _ret == 0);
+
+	// The pages swapped out before fork are copied to the child
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_pages('a') < 0)
+			_exit(EXIT_FAILURE);
+		fill_pages('b');
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(wait_child(pid), _ret == 0);
+	TEST_RES(check_pages('a'), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(locked)
+{
+	// The locked pages are not swapped out
+	TEST_SUCC(mlock(addr, PAGE_SIZE * NR_PAGES));
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_PAGEOUT));
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+	TEST_SUCC(munlock(addr, PAGE_SIZE * NR_PAGES));
+}
+END_TEST()
+
+FN_TEST(shared)
+{
+	unsigned char vec;
+	char *shared;
+
+	shared = (char *)TEST_RES((long)mmap(NULL, PAGE_SIZE,
+					     PROT_READ | PROT_WRITE,
+					     MAP_SHARED | MAP_ANONYMOUS, -1, 0),
+				  _ret != (long)MAP_FAILED);
+	memset(shared, 's', PAGE_SIZE);
+
+	// The shared anonymous pages are not swapped out
+	TEST_SUCC(madvise(shared, PAGE_SIZE, MADV_PAGEOUT));
+	TEST_RES(mincore(shared, PAGE_SIZE, &vec), vec & 1);
+	TEST_RES(shared[0], _ret == 's');
+
+	TEST_SUCC(munmap(shared, PAGE_SIZE));
+}
+END_TEST()
+
+FN_TEST(swapoff)
+{
+	fill_pages('c');
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_PAGEOUT));
+	TEST_RES(nr_resident_pages(), _ret == 0);
+
+	// The pages are swapped in when the device is disabled
+	TEST_SUCC(swapoff(SWAP_DEVICE));
+	TEST_RES(is_swap_listed(), _ret == 0);
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+	TEST_RES(check_pages('c'), _ret == 0);
+
+	TEST_ERRNO(swapoff(SWAP_DEVICE), EINVAL);
+
+	// Nothing is swapped out without swap devices
+	TEST_SUCC(madvise(addr, PAGE_SIZE * NR_PAGES, MADV_PAGEOUT));
+	TEST_RES(nr_resident_pages(), _ret == NR_PAGES);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(munmap(addr, PAGE_SIZE * NR_PAGES));
+}
+END_SETUP()
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <netinet/ip.h>
+#include <netinet/ip_icmp.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+#define PAYLOAD "ping"
+#define ECHO_ID 0x1234
+#define ECHO_SEQ 1
+
+struct echo_packet {
+	struct icmphdr hdr;
+	char payload[sizeof(PAYLOAD)];
+};
+
+static struct sockaddr_in loopback_addr;
+
+FN_SETUP(loopback_addr)
+{
+	loopback_addr.sin_family = AF_INET;
+	loopback_addr.sin_port = 0;
+	loopback_addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+}
+END_SETUP()
+
+static unsigned short checksum(const void *data, int len)
+{
+	const unsigned short *words = data;
+	unsigned int sum = 0;
+
+	for (; len > 1; len -= 2)
+		sum += *words++;
+	if (len == 1)
+		sum += *(const unsigned char *)words;
+	sum = (sum >> 16) + (sum & 0xffff);
+	sum += sum >> 16;
+	return ~sum;
+}
+
+static void fill_echo_request(struct echo_packet *packet)
+{
+	memset(packet, 0, sizeof(*packet));
+	packet->hdr.type = ICMP_ECHO;
+	packet->hdr.code = 0;
+	packet->hdr.un.echo.id = htons(ECHO_ID);
+	packet->hdr.un.echo.sequence = htons(ECHO_SEQ);
+	memcpy(packet->payload, PAYLOAD, sizeof(PAYLOAD));
+	packet->hdr.checksum = checksum(packet, sizeof(*packet));
+}
+
+static int is_echo_reply(const struct echo_packet *packet)
+{
+	return packet->hdr.type == ICMP_ECHOREPLY && packet->hdr.code == 0 &&
+	       packet->hdr.un.echo.sequence == htons(ECHO_SEQ) &&
+	       memcmp(packet->payload, PAYLOAD, sizeof(PAYLOAD)) == 0;
+}
+
+FN_TEST(ping_socket)
+{
+	int sk;
+	struct echo_packet request, reply;
+	struct sockaddr_in addr;
+	socklen_t addrlen = sizeof(addr);
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_DGRAM, IPPROTO_ICMP));
+
+	fill_echo_request(&request);
+	TEST_RES(sendto(sk, &request, sizeof(request), 0,
+			(struct sockaddr *)&loopback_addr, sizeof(loopback_addr)),
+		 _ret == sizeof(request));
+	TEST_RES(recvfrom(sk, &reply, sizeof(reply), 0,
+			  (struct sockaddr *)&addr, &addrlen),
+		 _ret == sizeof(reply) && is_echo_reply(&reply) &&
+			 addr.sin_family == AF_INET &&
+			 addr.sin_addr.s_addr == htonl(INADDR_LOOPBACK));
+
+	// The identifier is the port that the socket is bound to
+	addrlen = sizeof(addr);
+	TEST_RES(getsockname(sk, (struct sockaddr *)&addr, &addrlen),
+		 addr.sin_port == reply.hdr.un.echo.id);
+
+	// Only echo requests can be sent
+	request.hdr.type = ICMP_TIMESTAMP;
+	TEST_ERRNO(sendto(sk, &request, sizeof(request), 0,
+			  (struct sockaddr *)&loopback_addr,
+			  sizeof(loopback_addr)),
+		   EINVAL);
+	TEST_ERRNO(sendto(sk, &request, 4, 0,
+			  (struct sockaddr *)&loopback_addr,
+			  sizeof(loopback_addr)),
+		   EINVAL);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(raw_socket)
+{
+	int sk;
+	struct echo_packet request;
+	char buf[sizeof(struct iphdr) + sizeof(struct echo_packet)];
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct echo_packet *reply =
+		(struct echo_packet *)(buf + sizeof(struct iphdr));
+	struct sockaddr_in addr;
+	socklen_t addrlen;
+
+	TEST_ERRNO(socket(AF_INET, SOCK_RAW, 0), EPROTONOSUPPORT);
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_RAW, IPPROTO_ICMP));
+
+	fill_echo_request(&request);
+	TEST_RES(sendto(sk, &request, sizeof(request), 0,
+			(struct sockaddr *)&loopback_addr, sizeof(loopback_addr)),
+		 _ret == sizeof(request));
+
+	// A raw ICMP socket sees both the request and the reply, with IP headers
+	do {
+		addrlen = sizeof(addr);
+		TEST_RES(recvfrom(sk, buf, sizeof(buf), 0,
+				  (struct sockaddr *)&addr, &addrlen),
+			 _ret == sizeof(buf) && ip->version == 4 &&
+				 ip->protocol == IPPROTO_ICMP &&
+				 addr.sin_addr.s_addr ==
+					 htonl(INADDR_LOOPBACK));
+	} while (reply->hdr.type == ICMP_ECHO);
+	TEST_RES(0, is_echo_reply(reply) &&
+			    reply->hdr.un.echo.id == htons(ECHO_ID));
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+#define MESSAGE "hello"
+
+static void fill_addr6(struct sockaddr_in6 *addr, const char *ip, int port)
+{
+	memset(addr, 0, sizeof(*addr));
+	addr->sin6_family = AF_INET6;
+	addr->sin6_port = htons(port);
+	inet_pton(AF_INET6, ip, &addr->sin6_addr);
+}
+
+static void fill_addr4(struct sockaddr_in *addr, const char *ip, int port)
+{
+	memset(addr, 0, sizeof(*addr));
+	addr->sin_family = AF_INET;
+	addr->sin_port = htons(port);
+	inet_pton(AF_INET, ip, &addr->sin_addr);
+}
+
+static int is_addr6(struct sockaddr_in6 *addr, socklen_t addrlen,
+		    const char *ip, int port)
+{
+	struct in6_addr expected;
+
+	inet_pton(AF_INET6, ip, &expected);
+	return addrlen == sizeof(*addr) && addr->sin6_family == AF_INET6 &&
+	       (port < 0 || addr->sin6_port == htons(port)) &&
+	       memcmp(&addr->sin6_addr, &expected, sizeof(expected)) == 0;
+}
+
+FN_TEST(stream_loopback)
+{
+	int listener, client, accepted;
+	struct sockaddr_in6 addr, other;
+	socklen_t other_len;
+	char buf[16];
+
+	fill_addr6(&addr, "::1", 8090);
+
+	listener = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(listen(listener, 2));
+
+	other_len = sizeof(other);
+	TEST_RES(getsockname(listener, (struct sockaddr *)&other, &other_len),
+		 is_addr6(&other, other_len, "::1", 8090));
+
+	client = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr, sizeof(addr)));
+
+	other_len = sizeof(other);
+	accepted = TEST_RES(accept(listener, (struct sockaddr *)&other,
+				   &other_len),
+			    is_addr6(&other, other_len, "::1", -1));
+
+	other_len = sizeof(other);
+	TEST_RES(getpeername(client, (struct sockaddr *)&other, &other_len),
+		 is_addr6(&other, other_len, "::1", 8090));
+
+	TEST_RES(write(client, MESSAGE, sizeof(MESSAGE)),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(read(accepted, buf, sizeof(buf)),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0);
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(accepted));
+	TEST_SUCC(close(listener));
+}
+END_TEST()
+
+FN_TEST(stream_dual_stack)
+{
+	int listener, client, accepted;
+	struct sockaddr_in6 addr, other;
+	struct sockaddr_in addr4;
+	socklen_t other_len;
+
+	fill_addr6(&addr, "::", 8091);
+	fill_addr4(&addr4, "127.0.0.1", 8091);
+
+	listener = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(listen(listener, 2));
+
+	// An IPv4 client can connect to a dual-stack socket
+	client = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr4, sizeof(addr4)));
+
+	// The IPv4 peer is seen as an IPv4-mapped IPv6 address
+	other_len = sizeof(other);
+	accepted = TEST_RES(accept(listener, (struct sockaddr *)&other,
+				   &other_len),
+			    is_addr6(&other, other_len, "::ffff:127.0.0.1", -1));
+	other_len = sizeof(other);
+	TEST_RES(getsockname(accepted, (struct sockaddr *)&other, &other_len),
+		 is_addr6(&other, other_len, "::ffff:127.0.0.1", 8091));
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(accepted));
+	TEST_SUCC(close(listener));
+}
+END_TEST()
+
+FN_TEST(v6only)
+{
+	int sk, v6only;
+	socklen_t optlen = sizeof(v6only);
+	struct sockaddr_in6 addr;
+
+	sk = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+
+	TEST_RES(getsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6only, &optlen),
+		 v6only == 0);
+	v6only = 1;
+	TEST_SUCC(setsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6only,
+			     sizeof(v6only)));
+	TEST_RES(getsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6only, &optlen),
+		 v6only == 1);
+
+	// An IPv6-only socket cannot use IPv4-mapped addresses
+	fill_addr6(&addr, "::ffff:127.0.0.1", 8092);
+	TEST_ERRNO(bind(sk, (struct sockaddr *)&addr, sizeof(addr)), EINVAL);
+
+	// The option cannot be changed after the socket is bound
+	fill_addr6(&addr, "::1", 8092);
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+	v6only = 0;
+	TEST_ERRNO(setsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6only,
+			      sizeof(v6only)),
+		   EINVAL);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(stream_v6only)
+{
+	int listener, client, accepted;
+	int v6only = 1;
+	struct sockaddr_in6 addr;
+	struct sockaddr_in addr4;
+
+	fill_addr6(&addr, "::", 8096);
+	fill_addr4(&addr4, "127.0.0.1", 8096);
+
+	listener = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+	TEST_SUCC(setsockopt(listener, IPPROTO_IPV6, IPV6_V6ONLY, &v6only,
+			     sizeof(v6only)));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(listen(listener, 2));
+
+	// An IPv4 client is refused without completing the handshake
+	client = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	TEST_ERRNO(connect(client, (struct sockaddr *)&addr4, sizeof(addr4)),
+		   ECONNREFUSED);
+	TEST_SUCC(close(client));
+
+	// An IPv6 client is still accepted
+	fill_addr6(&addr, "::1", 8096);
+	client = TEST_SUCC(socket(AF_INET6, SOCK_STREAM, 0));
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr, sizeof(addr)));
+	accepted = TEST_SUCC(accept(listener, NULL, NULL));
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(accepted));
+	TEST_SUCC(close(listener));
+}
+END_TEST()
+
+FN_TEST(dgram_loopback)
+{
+	int server, client;
+	struct sockaddr_in6 addr, other;
+	socklen_t other_len = sizeof(other);
+	char buf[16];
+
+	fill_addr6(&addr, "::1", 8093);
+
+	server = TEST_SUCC(socket(AF_INET6, SOCK_DGRAM, 0));
+	client = TEST_SUCC(socket(AF_INET6, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(server, (struct sockaddr *)&addr, sizeof(addr)));
+
+	TEST_RES(sendto(client, MESSAGE, sizeof(MESSAGE), 0,
+			(struct sockaddr *)&addr, sizeof(addr)),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(recvfrom(server, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&other, &other_len),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0 &&
+			 is_addr6(&other, other_len, "::1", -1));
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(server));
+}
+END_TEST()
+
+FN_TEST(dgram_ipv4_mapped)
+{
+	int server, client;
+	struct sockaddr_in6 addr;
+	struct sockaddr_in addr4, other4;
+	socklen_t other_len;
+	char buf[16];
+
+	fill_addr4(&addr4, "127.0.0.1", 8094);
+	fill_addr6(&addr, "::ffff:127.0.0.1", 8094);
+
+	server = TEST_SUCC(socket(AF_INET, SOCK_DGRAM, 0));
+	client = TEST_SUCC(socket(AF_INET6, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(server, (struct sockaddr *)&addr4, sizeof(addr4)));
+
+	// An IPv6 socket can send to an IPv4 socket via the IPv4-mapped address
+	TEST_RES(sendto(client, MESSAGE, sizeof(MESSAGE), 0,
+			(struct sockaddr *)&addr, sizeof(addr)),
+		 _ret == sizeof(MESSAGE));
+	other_len = sizeof(other4);
+	TEST_RES(recvfrom(server, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&other4, &other_len),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0 &&
+			 other_len == sizeof(other4) &&
+			 other4.sin_family == AF_INET);
+
+	// An IPv4 socket cannot use IPv6 addresses
+	TEST_ERRNO(sendto(server, MESSAGE, sizeof(MESSAGE), 0,
+			  (struct sockaddr *)&addr, sizeof(addr)),
+		   EAFNOSUPPORT);
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(server));
+}
+END_TEST()
+
+FN_TEST(truncated_addr)
+{
+	int sk;
+	struct sockaddr_in6 addr, other;
+	socklen_t other_len;
+	char tail[sizeof(other) - sizeof(struct sockaddr_in)];
+
+	fill_addr6(&addr, "::1", 8095);
+
+	sk = TEST_SUCC(socket(AF_INET6, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+
+	// The address is truncated to fit the buffer, but the real length is
+	// returned
+	memset(&other, 0xff, sizeof(other));
+	memset(tail, 0xff, sizeof(tail));
+	other_len = sizeof(struct sockaddr_in);
+	TEST_RES(getsockname(sk, (struct sockaddr *)&other, &other_len),
+		 other_len == sizeof(other) && other.sin6_family == AF_INET6 &&
+			 memcmp((char *)&other + sizeof(struct sockaddr_in),
+				tail, sizeof(tail)) == 0);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
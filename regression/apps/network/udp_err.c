@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
+#include <limits.h>
 #include <unistd.h>
 #include <sys/signal.h>
 #include <sys/socket.h>
@@ -136,6 +137,33 @@ FN_TEST(send_and_recv)
 }
 END_TEST()
 
+FN_TEST(recvmsg_truncated)
+{
+	char buf[2] = { 'a', 'b' };
+	struct iovec iov = { .iov_base = buf, .iov_len = 1 };
+	struct msghdr msg = { .msg_iov = &iov, .msg_iovlen = 1 };
+
+	TEST_RES(send(sk_connected, buf, 2, 0), _ret == 2);
+
+	buf[0] = 0;
+	TEST_RES(recvmsg(sk_bound, &msg, 0),
+		 _ret == 1 && buf[0] == 'a' && (msg.msg_flags & MSG_TRUNC) != 0);
+}
+END_TEST()
+
+FN_TEST(recvmsg_invalid_iov)
+{
+	char buf[1];
+	struct iovec iov = {
+		.iov_base = buf,
+		.iov_len = (size_t)SSIZE_MAX + 1,
+	};
+	struct msghdr msg = { .msg_iov = &iov, .msg_iovlen = 1 };
+
+	TEST_ERRNO(recvmsg(sk_bound, &msg, MSG_DONTWAIT), EINVAL);
+}
+END_TEST()
+
 FN_TEST(bind)
 {
 	struct sockaddr *psaddr = (struct sockaddr *)&sk_addr;
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <stddef.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+
+#include "test.h"
+
+#define NAME "unix_abstract_test"
+#define MESSAGE "hello"
+
+static struct sockaddr_un addr;
+static socklen_t addrlen;
+
+FN_SETUP(addr)
+{
+	memset(&addr, 0, sizeof(addr));
+	addr.sun_family = AF_UNIX;
+	// The first byte of an abstract name is zero
+	memcpy(addr.sun_path + 1, NAME, strlen(NAME));
+	addrlen = offsetof(struct sockaddr_un, sun_path) + 1 + strlen(NAME);
+}
+END_SETUP()
+
+static int is_same_addr(struct sockaddr_un *other, socklen_t other_len)
+{
+	return other_len == addrlen && memcmp(other, &addr, addrlen) == 0;
+}
+
+FN_TEST(stream)
+{
+	int listener, client, accepted;
+	struct sockaddr_un other;
+	socklen_t other_len;
+	char buf[16];
+
+	listener = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, addrlen));
+	TEST_SUCC(listen(listener, 1));
+
+	other_len = sizeof(other);
+	TEST_RES(getsockname(listener, (struct sockaddr *)&other, &other_len),
+		 is_same_addr(&other, other_len));
+
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr, addrlen));
+	accepted = TEST_SUCC(accept(listener, NULL, NULL));
+
+	other_len = sizeof(other);
+	TEST_RES(getpeername(client, (struct sockaddr *)&other, &other_len),
+		 is_same_addr(&other, other_len));
+	other_len = sizeof(other);
+	TEST_RES(getsockname(accepted, (struct sockaddr *)&other, &other_len),
+		 is_same_addr(&other, other_len));
+
+	TEST_RES(write(client, MESSAGE, sizeof(MESSAGE)),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(read(accepted, buf, sizeof(buf)),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0);
+
+	TEST_SUCC(close(accepted));
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(listener));
+}
+END_TEST()
+
+FN_TEST(name_collision)
+{
+	int sk_a, sk_b;
+
+	sk_a = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	sk_b = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+
+	TEST_SUCC(bind(sk_a, (struct sockaddr *)&addr, addrlen));
+	TEST_ERRNO(bind(sk_b, (struct sockaddr *)&addr, addrlen), EADDRINUSE);
+
+	// The name is released after the socket is closed
+	TEST_SUCC(close(sk_a));
+	TEST_SUCC(bind(sk_b, (struct sockaddr *)&addr, addrlen));
+
+	TEST_SUCC(close(sk_b));
+}
+END_TEST()
+
+FN_TEST(rebind_after_listener_closed)
+{
+	int listener, client, accepted, sk;
+
+	listener = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, addrlen));
+	TEST_SUCC(listen(listener, 1));
+
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr, addrlen));
+	accepted = TEST_SUCC(accept(listener, NULL, NULL));
+
+	// The accepted socket does not keep the name in use
+	TEST_SUCC(close(listener));
+	sk = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, addrlen));
+
+	TEST_SUCC(close(sk));
+	TEST_SUCC(close(accepted));
+	TEST_SUCC(close(client));
+}
+END_TEST()
+
+FN_TEST(rebind_after_sender_closed)
+{
+	int server, client, sk;
+	struct sockaddr_un server_addr;
+
+	memcpy(&server_addr, &addr, sizeof(addr));
+	server_addr.sun_path[1] ^= 1;
+
+	server = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(server, (struct sockaddr *)&server_addr, addrlen));
+	TEST_SUCC(bind(client, (struct sockaddr *)&addr, addrlen));
+
+	TEST_RES(sendto(client, MESSAGE, sizeof(MESSAGE), 0,
+			(struct sockaddr *)&server_addr, addrlen),
+		 _ret == sizeof(MESSAGE));
+
+	// The queued message does not keep the name of its sender in use
+	TEST_SUCC(close(client));
+	sk = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, addrlen));
+
+	TEST_SUCC(close(sk));
+	TEST_SUCC(close(server));
+}
+END_TEST()
+
+FN_TEST(connect_refused)
+{
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&addr, addrlen),
+		   ECONNREFUSED);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(dgram)
+{
+	int server, client;
+	struct sockaddr_un other;
+	socklen_t other_len = sizeof(other);
+	char buf[16];
+
+	server = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+
+	TEST_SUCC(bind(server, (struct sockaddr *)&addr, addrlen));
+	TEST_RES(sendto(client, MESSAGE, sizeof(MESSAGE), 0,
+			(struct sockaddr *)&addr, addrlen),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(recv(server, buf, sizeof(buf), 0),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0);
+
+	TEST_SUCC(connect(client, (struct sockaddr *)&addr, addrlen));
+	TEST_RES(getpeername(client, (struct sockaddr *)&other, &other_len),
+		 is_same_addr(&other, other_len));
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(server));
+}
+END_TEST()
+
+FN_TEST(autobind)
+{
+	int sk;
+	struct sockaddr_un other;
+	socklen_t other_len = sizeof(other);
+
+	sk = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, sizeof(sa_family_t)));
+
+	// The socket is bound to an abstract name of five hexadecimal digits
+	TEST_RES(getsockname(sk, (struct sockaddr *)&other, &other_len),
+		 other_len == offsetof(struct sockaddr_un, sun_path) + 6 &&
+			 other.sun_path[0] == '\0');
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
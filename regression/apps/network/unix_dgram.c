@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+
+#include "test.h"
+
+#define MESSAGE_A "hello"
+#define MESSAGE_B "world!"
+
+#define SERVER_PATH "/tmp/unix_dgram_server"
+#define CLIENT_PATH "/tmp/unix_dgram_client"
+#define LISTEN_PATH "/tmp/unix_dgram_listen"
+
+static int dgram_pair[2];
+static int seqpacket_pair[2];
+
+FN_SETUP(socketpair)
+{
+	CHECK(socketpair(AF_UNIX, SOCK_DGRAM, 0, dgram_pair));
+	CHECK(socketpair(AF_UNIX, SOCK_SEQPACKET, 0, seqpacket_pair));
+}
+END_SETUP()
+
+static void fill_addr(struct sockaddr_un *addr, const char *path)
+{
+	memset(addr, 0, sizeof(*addr));
+	addr->sun_family = AF_UNIX;
+	strcpy(addr->sun_path, path);
+}
+
+// Receives a message with `recvmsg`, returning the flags in `msg_flags`.
+static int recv_flags(int sk, char *buf, size_t len, int *msg_flags)
+{
+	struct iovec iov = { .iov_base = buf, .iov_len = len };
+	struct msghdr msg = { .msg_iov = &iov, .msg_iovlen = 1 };
+	int ret;
+
+	ret = recvmsg(sk, &msg, 0);
+	*msg_flags = msg.msg_flags;
+	return ret;
+}
+
+FN_TEST(dgram_boundaries)
+{
+	char buf[16];
+
+	TEST_RES(send(dgram_pair[0], MESSAGE_A, sizeof(MESSAGE_A), 0),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(send(dgram_pair[0], MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+
+	TEST_RES(recv(dgram_pair[1], buf, sizeof(buf), 0),
+		 _ret == sizeof(MESSAGE_A) && strcmp(buf, MESSAGE_A) == 0);
+	TEST_RES(recv(dgram_pair[1], buf, sizeof(buf), 0),
+		 _ret == sizeof(MESSAGE_B) && strcmp(buf, MESSAGE_B) == 0);
+
+	TEST_ERRNO(recv(dgram_pair[1], buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+}
+END_TEST()
+
+FN_TEST(dgram_truncated)
+{
+	char buf[2];
+
+	TEST_RES(send(dgram_pair[0], MESSAGE_A, sizeof(MESSAGE_A), 0),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(recv(dgram_pair[1], buf, sizeof(buf), 0),
+		 _ret == sizeof(buf) && memcmp(buf, MESSAGE_A, 2) == 0);
+
+	TEST_RES(send(dgram_pair[0], MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+	TEST_RES(recv(dgram_pair[1], buf, sizeof(buf), MSG_TRUNC),
+		 _ret == sizeof(MESSAGE_B));
+
+	TEST_ERRNO(recv(dgram_pair[1], buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+}
+END_TEST()
+
+FN_TEST(dgram_msg_trunc)
+{
+	char buf[16];
+	int msg_flags;
+
+	TEST_RES(send(dgram_pair[0], MESSAGE_A, sizeof(MESSAGE_A), 0),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(recv_flags(dgram_pair[1], buf, 2, &msg_flags),
+		 _ret == 2 && (msg_flags & MSG_TRUNC) != 0);
+
+	TEST_RES(send(dgram_pair[0], MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+	TEST_RES(recv_flags(dgram_pair[1], buf, sizeof(buf), &msg_flags),
+		 _ret == sizeof(MESSAGE_B) && (msg_flags & MSG_TRUNC) == 0);
+}
+END_TEST()
+
+FN_TEST(dgram_sendto)
+{
+	int server, client;
+	struct sockaddr_un server_addr, client_addr, src_addr;
+	socklen_t addrlen = sizeof(src_addr);
+	char buf[16];
+
+	fill_addr(&server_addr, SERVER_PATH);
+	fill_addr(&client_addr, CLIENT_PATH);
+	unlink(SERVER_PATH);
+	unlink(CLIENT_PATH);
+
+	server = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+
+	TEST_ERRNO(sendto(client, MESSAGE_A, sizeof(MESSAGE_A), 0,
+			  (struct sockaddr *)&server_addr, sizeof(server_addr)),
+		   ENOENT);
+	TEST_ERRNO(send(client, MESSAGE_A, sizeof(MESSAGE_A), 0), ENOTCONN);
+
+	TEST_SUCC(bind(server, (struct sockaddr *)&server_addr,
+		       sizeof(server_addr)));
+	TEST_SUCC(bind(client, (struct sockaddr *)&client_addr,
+		       sizeof(client_addr)));
+
+	TEST_RES(sendto(client, MESSAGE_A, sizeof(MESSAGE_A), 0,
+			(struct sockaddr *)&server_addr, sizeof(server_addr)),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(recvfrom(server, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&src_addr, &addrlen),
+		 _ret == sizeof(MESSAGE_A) && strcmp(buf, MESSAGE_A) == 0 &&
+			 strcmp(src_addr.sun_path, CLIENT_PATH) == 0);
+
+	TEST_SUCC(connect(client, (struct sockaddr *)&server_addr,
+			  sizeof(server_addr)));
+	TEST_RES(send(client, MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+	TEST_RES(recv(server, buf, sizeof(buf), 0),
+		 _ret == sizeof(MESSAGE_B) && strcmp(buf, MESSAGE_B) == 0);
+
+	TEST_SUCC(close(server));
+	TEST_ERRNO(send(client, MESSAGE_B, sizeof(MESSAGE_B), 0),
+		   ECONNREFUSED);
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(unlink(SERVER_PATH));
+	TEST_SUCC(unlink(CLIENT_PATH));
+}
+END_TEST()
+
+FN_TEST(seqpacket_msg_trunc)
+{
+	char buf[16];
+	int msg_flags;
+
+	TEST_RES(send(seqpacket_pair[0], MESSAGE_A, sizeof(MESSAGE_A), 0),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(recv_flags(seqpacket_pair[1], buf, 2, &msg_flags),
+		 _ret == 2 && (msg_flags & MSG_TRUNC) != 0);
+
+	TEST_RES(send(seqpacket_pair[0], MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+	TEST_RES(recv_flags(seqpacket_pair[1], buf, sizeof(buf), &msg_flags),
+		 _ret == sizeof(MESSAGE_B) && (msg_flags & MSG_TRUNC) == 0);
+}
+END_TEST()
+
+FN_TEST(seqpacket_boundaries)
+{
+	char buf[16];
+
+	TEST_RES(send(seqpacket_pair[0], MESSAGE_A, sizeof(MESSAGE_A), 0),
+		 _ret == sizeof(MESSAGE_A));
+	TEST_RES(send(seqpacket_pair[0], MESSAGE_B, sizeof(MESSAGE_B), 0),
+		 _ret == sizeof(MESSAGE_B));
+
+	TEST_RES(recv(seqpacket_pair[1], buf, 2, 0),
+		 _ret == 2 && memcmp(buf, MESSAGE_A, 2) == 0);
+	TEST_RES(recv(seqpacket_pair[1], buf, sizeof(buf), 0),
+		 _ret == sizeof(MESSAGE_B) && strcmp(buf, MESSAGE_B) == 0);
+
+	TEST_SUCC(close(seqpacket_pair[0]));
+	TEST_RES(recv(seqpacket_pair[1], buf, sizeof(buf), 0), _ret == 0);
+	TEST_SUCC(close(seqpacket_pair[1]));
+}
+END_TEST()
+
+FN_TEST(seqpacket_type_mismatch)
+{
+	int listener, client;
+	struct sockaddr_un addr;
+
+	fill_addr(&addr, LISTEN_PATH);
+	unlink(LISTEN_PATH);
+
+	listener = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+	TEST_SUCC(bind(listener, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(listen(listener, 1));
+
+	client = TEST_SUCC(socket(AF_UNIX, SOCK_SEQPACKET, 0));
+	TEST_ERRNO(connect(client, (struct sockaddr *)&addr, sizeof(addr)),
+		   EPROTOTYPE);
+
+	TEST_SUCC(close(client));
+	TEST_SUCC(close(listener));
+	TEST_SUCC(unlink(LISTEN_PATH));
+}
+END_TEST()
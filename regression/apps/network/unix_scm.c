@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <sys/uio.h>
+
+#include "test.h"
+
+#define MESSAGE "hello"
+
+static int sk_pair[2];
+
+FN_SETUP(socketpair)
+{
+	CHECK(socketpair(AF_UNIX, SOCK_STREAM, 0, sk_pair));
+}
+END_SETUP()
+
+static int send_fd(int sk, int fd)
+{
+	char buf[] = MESSAGE;
+	struct iovec iov = { .iov_base = buf, .iov_len = sizeof(buf) };
+	char control[CMSG_SPACE(sizeof(int))] = { 0 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = sizeof(control),
+	};
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_RIGHTS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(int));
+	memcpy(CMSG_DATA(cmsg), &fd, sizeof(int));
+
+	return sendmsg(sk, &msg, 0);
+}
+
+static int recv_fd(int sk, int *fd, char *buf, size_t len, int *msg_flags)
+{
+	struct iovec iov = { .iov_base = buf, .iov_len = len };
+	char control[CMSG_SPACE(sizeof(int))] = { 0 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = sizeof(control),
+	};
+	struct cmsghdr *cmsg;
+	int ret;
+
+	ret = recvmsg(sk, &msg, 0);
+	if (ret < 0)
+		return ret;
+
+	*fd = -1;
+	*msg_flags = msg.msg_flags;
+	cmsg = CMSG_FIRSTHDR(&msg);
+	if (cmsg != NULL && cmsg->cmsg_level == SOL_SOCKET &&
+	    cmsg->cmsg_type == SCM_RIGHTS)
+		memcpy(fd, CMSG_DATA(cmsg), sizeof(int));
+
+	return ret;
+}
+
+FN_TEST(scm_rights)
+{
+	int pipe_fds[2];
+	int received_fd, msg_flags;
+	char buf[16];
+
+	CHECK(pipe(pipe_fds));
+
+	TEST_RES(send_fd(sk_pair[0], pipe_fds[1]), _ret == sizeof(MESSAGE));
+	TEST_RES(recv_fd(sk_pair[1], &received_fd, buf, sizeof(buf),
+			 &msg_flags),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0 &&
+			 received_fd >= 0 && received_fd != pipe_fds[1] &&
+			 (msg_flags & MSG_CTRUNC) == 0);
+
+	// The received file refers to the write end of the pipe
+	TEST_RES(write(received_fd, MESSAGE, sizeof(MESSAGE)),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(read(pipe_fds[0], buf, sizeof(buf)),
+		 _ret == sizeof(MESSAGE) && strcmp(buf, MESSAGE) == 0);
+
+	TEST_SUCC(close(received_fd));
+	TEST_SUCC(close(pipe_fds[0]));
+	TEST_SUCC(close(pipe_fds[1]));
+}
+END_TEST()
+
+FN_TEST(scm_rights_truncated)
+{
+	char buf[16];
+	struct iovec iov = { .iov_base = buf, .iov_len = sizeof(buf) };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+	};
+
+	TEST_RES(send_fd(sk_pair[0], STDOUT_FILENO), _ret == sizeof(MESSAGE));
+	TEST_RES(recvmsg(sk_pair[1], &msg, 0),
+		 _ret == sizeof(MESSAGE) && (msg.msg_flags & MSG_CTRUNC) != 0);
+}
+END_TEST()
+
+FN_TEST(scm_credentials)
+{
+	char buf[] = MESSAGE;
+	struct iovec iov = { .iov_base = buf, .iov_len = sizeof(buf) };
+	char control[CMSG_SPACE(sizeof(struct ucred))] = { 0 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = sizeof(control),
+	};
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+	struct ucred cred = {
+		.pid = getpid(),
+		.uid = getuid(),
+		.gid = getgid(),
+	};
+	struct ucred received_cred;
+	int enable = 1;
+
+	TEST_SUCC(setsockopt(sk_pair[1], SOL_SOCKET, SO_PASSCRED, &enable,
+			     sizeof(enable)));
+
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_CREDENTIALS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(struct ucred));
+	memcpy(CMSG_DATA(cmsg), &cred, sizeof(cred));
+
+	TEST_RES(sendmsg(sk_pair[0], &msg, 0), _ret == sizeof(MESSAGE));
+
+	memset(control, 0, sizeof(control));
+	TEST_RES(recvmsg(sk_pair[1], &msg, 0),
+		 _ret == sizeof(MESSAGE) &&
+			 (cmsg = CMSG_FIRSTHDR(&msg)) != NULL &&
+			 cmsg->cmsg_type == SCM_CREDENTIALS);
+	memcpy(&received_cred, CMSG_DATA(cmsg), sizeof(received_cred));
+	TEST_RES(received_cred.pid, _ret == cred.pid);
+	TEST_RES(received_cred.uid, _ret == cred.uid);
+}
+END_TEST()
+
+FN_TEST(scm_credentials_implicit)
+{
+	char buf[16];
+	struct iovec iov = { .iov_base = buf, .iov_len = sizeof(buf) };
+	char control[CMSG_SPACE(sizeof(struct ucred))] = { 0 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = sizeof(control),
+	};
+	struct cmsghdr *cmsg;
+	struct ucred received_cred;
+
+	// `SO_PASSCRED` is still enabled, so the credentials are received
+	// even if they are not sent explicitly
+	TEST_RES(send(sk_pair[0], MESSAGE, sizeof(MESSAGE), 0),
+		 _ret == sizeof(MESSAGE));
+	TEST_RES(recvmsg(sk_pair[1], &msg, 0),
+		 _ret == sizeof(MESSAGE) &&
+			 (cmsg = CMSG_FIRSTHDR(&msg)) != NULL &&
+			 cmsg->cmsg_type == SCM_CREDENTIALS);
+	memcpy(&received_cred, CMSG_DATA(cmsg), sizeof(received_cred));
+	TEST_RES(received_cred.pid, _ret == getpid());
+	TEST_RES(received_cred.uid, _ret == getuid());
+	TEST_RES(received_cred.gid, _ret == getgid());
+}
+END_TEST()
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <limits.h>
+#include <signal.h>
+#include <sys/mman.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+extern char **environ;
+
+static char buf[16384];
+
+// Reads the whole file into `buf`, and returns the length of the file.
+static long read_file(const char *path)
+{
+	long len = 0, n;
+	int fd;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+	while ((n = read(fd, buf + len, sizeof(buf) - 1 - len)) > 0)
+		len += n;
+	close(fd);
+	if (n < 0)
+		return -1;
+	buf[len] = '\0';
+	return len;
+}
+
+// Returns the line that starts with `prefix` in `buf`, or NULL if not found.
+static char *find_line(const char *prefix)
+{
+	char *line = buf;
+
+	while (line && *line) {
+		if (strncmp(line, prefix, strlen(prefix)) == 0)
+			return line;
+		line = strchr(line, '\n');
+		if (line)
+			line++;
+	}
+	return NULL;
+}
+
+// Returns the value of a field in `/proc/self/status`, which is parsed as
+// a number in `base`.
+static long status_field(const char *name, int base)
+{
+	char *line;
+
+	if (read_file("/proc/self/status") < 0)
+		return -1;
+	line = find_line(name);
+	if (!line)
+		return -1;
+	return strtol(line + strlen(name), NULL, base);
+}
+
+FN_TEST(status)
+{
+	struct sigaction sa = { .sa_handler = SIG_IGN };
+
+	TEST_RES(read_file("/proc/self/status"), find_line("Name:\tpid\n"));
+	TEST_RES(read_file("/proc/self/status"),
+		 find_line("State:\tR (running)\n"));
+	TEST_RES(status_field("Pid:", 10), _ret == getpid());
+	TEST_RES(status_field("Tgid:", 10), _ret == getpid());
+	TEST_RES(status_field("PPid:", 10), _ret == getppid());
+	TEST_RES(status_field("Uid:", 10), _ret == getuid());
+	TEST_RES(status_field("Gid:", 10), _ret == getgid());
+	TEST_RES(status_field("Threads:", 10), _ret == 1);
+	TEST_RES(status_field("VmSize:", 10), _ret > 0);
+	TEST_RES(status_field("VmStk:", 10), _ret > 0);
+
+	// The signal sets are shown in hexadecimal
+	TEST_SUCC(sigaction(SIGUSR1, &sa, NULL));
+	TEST_RES(status_field("SigIgn:", 16), _ret & (1L << (SIGUSR1 - 1)));
+	sa.sa_handler = SIG_DFL;
+	TEST_SUCC(sigaction(SIGUSR1, &sa, NULL));
+	TEST_RES(status_field("SigIgn:", 16),
+		 !(_ret & (1L << (SIGUSR1 - 1))));
+}
+END_TEST()
+
+static int pid, ppid;
+static char comm[32], state;
+
+// Parses the first four fields in `/proc/self/stat`.
+static int parse_stat(void)
+{
+	if (read_file("/proc/self/stat") < 0)
+		return -1;
+	return sscanf(buf, "%d (%31[^)]) %c %d", &pid, comm, &state, &ppid);
+}
+
+FN_TEST(stat)
+{
+	int nr_fields = 0;
+	char *fields;
+
+	TEST_RES(parse_stat(), _ret == 4);
+	TEST_RES(pid, _ret == getpid());
+	TEST_RES(strcmp(comm, "pid"), _ret == 0);
+	TEST_RES(state, _ret == 'R');
+	TEST_RES(ppid, _ret == getppid());
+
+	// There are 52 fields in total, which are separated by spaces
+	TEST_RES(read_file("/proc/self/stat"), buf[_ret - 1] == '\n');
+	fields = strrchr(buf, ')');
+	for (char *c = fields; c && *c; c++)
+		nr_fields += *c == ' ';
+	TEST_RES(nr_fields, _ret == 50);
+}
+END_TEST()
+
+FN_TEST(maps)
+{
+	char exe[PATH_MAX] = {}, range[64];
+	char *addr;
+
+	TEST_RES(read_file("/proc/self/maps"), strstr(buf, "[stack]\n"));
+	TEST_RES(readlink("/proc/self/exe", exe, sizeof(exe) - 1),
+		 _ret > 0);
+	TEST_RES(read_file("/proc/self/maps"), strstr(buf, exe));
+
+	// An anonymous mapping appears with its permission
+	addr = mmap(NULL, 4096 * 3, PROT_READ | PROT_WRITE,
+		    MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	TEST_RES(addr != MAP_FAILED, _ret);
+	snprintf(range, sizeof(range), "%08lx-%08lx rw-p 00000000 00:00 0",
+		 (unsigned long)addr, (unsigned long)addr + 4096 * 3);
+	TEST_RES(read_file("/proc/self/maps"), find_line(range));
+	TEST_SUCC(munmap(addr, 4096 * 3));
+	TEST_RES(read_file("/proc/self/maps"), !find_line(range));
+}
+END_TEST()
+
+FN_TEST(cmdline_environ)
+{
+	long len;
+
+	// The arguments and the environment variables are separated by NULs
+	len = read_file("/proc/self/cmdline");
+	TEST_RES(len, _ret > 0 && buf[_ret - 1] == '\0');
+	TEST_RES(strcmp(buf, program_invocation_name), _ret == 0);
+
+	len = read_file("/proc/self/environ");
+	TEST_RES(len, _ret >= 0);
+	if (environ[0]) {
+		TEST_RES(strcmp(buf, environ[0]), _ret == 0);
+	}
+}
+END_TEST()
+
+FN_TEST(cwd_root)
+{
+	char path[PATH_MAX] = {};
+
+	TEST_SUCC(chdir("/proc"));
+	TEST_RES(readlink("/proc/self/cwd", path, sizeof(path) - 1),
+		 _ret == 5 && strncmp(path, "/proc", 5) == 0);
+	TEST_SUCC(chdir("/"));
+	TEST_RES(readlink("/proc/self/cwd", path, sizeof(path) - 1),
+		 _ret == 1 && path[0] == '/');
+	TEST_RES(readlink("/proc/self/root", path, sizeof(path) - 1),
+		 _ret == 1 && path[0] == '/');
+}
+END_TEST()
+
+FN_TEST(mounts)
+{
+	TEST_RES(read_file("/proc/self/mounts"), strstr(buf, " /proc proc "));
+}
+END_TEST()
+
+// Parses a limit value in `/proc/self/limits`, which may be "unlimited".
+static unsigned long parse_limit(char **value)
+{
+	*value += strspn(*value, " ");
+	if (strncmp(*value, "unlimited", 9) == 0) {
+		*value += 9;
+		return RLIM_INFINITY;
+	}
+	return strtoul(*value, value, 10);
+}
+
+FN_TEST(limits)
+{
+	struct rlimit rlimit;
+	char *value;
+
+	TEST_RES(read_file("/proc/self/limits"), find_line("Limit "));
+	TEST_SUCC(getrlimit(RLIMIT_NOFILE, &rlimit));
+	value = find_line("Max open files ") + 25;
+	TEST_RES(parse_limit(&value), _ret == rlimit.rlim_cur);
+	TEST_RES(parse_limit(&value), _ret == rlimit.rlim_max);
+	TEST_RES(read_file("/proc/self/limits"),
+		 strstr(buf, "Max stack size            "));
+}
+END_TEST()
+
+// Writes `value` to the whole file, and returns the number of bytes written.
+static long write_file(const char *path, const char *value)
+{
+	long len;
+	int fd;
+
+	fd = open(path, O_WRONLY);
+	if (fd < 0)
+		return -1;
+	len = write(fd, value, strlen(value));
+	close(fd);
+	return len;
+}
+
+// Returns the number in the file, or -1 if the file cannot be read.
+static long read_number(const char *path)
+{
+	if (read_file(path) < 0)
+		return -1;
+	return strtol(buf, NULL, 10);
+}
+
+#define OOM_SCORE_ADJ "/proc/self/oom_score_adj"
+#define OOM_SCORE "/proc/self/oom_score"
+
+FN_TEST(oom_score_adj)
+{
+	long score;
+	pid_t pid;
+	int status;
+
+	TEST_RES(read_file(OOM_SCORE_ADJ), strcmp(buf, "0\n") == 0);
+	score = TEST_RES(read_number(OOM_SCORE), _ret > 0);
+
+	// The score grows with the adjustment
+	TEST_RES(write_file(OOM_SCORE_ADJ, "500\n"), _ret == 4);
+	TEST_RES(read_file(OOM_SCORE_ADJ), strcmp(buf, "500\n") == 0);
+	TEST_RES(read_number(OOM_SCORE), _ret > score);
+
+	// The processes that are protected are never chosen
+	TEST_RES(write_file(OOM_SCORE_ADJ, "-1000"), _ret == 5);
+	TEST_RES(read_number(OOM_SCORE_ADJ), _ret == -1000);
+	TEST_RES(read_number(OOM_SCORE), _ret == 0);
+
+	// The values out of range are rejected
+	TEST_ERRNO(write_file(OOM_SCORE_ADJ, "1001"), EINVAL);
+	TEST_ERRNO(write_file(OOM_SCORE_ADJ, "-1001"), EINVAL);
+	TEST_ERRNO(write_file(OOM_SCORE_ADJ, "abc"), EINVAL);
+	TEST_RES(read_number(OOM_SCORE_ADJ), _ret == -1000);
+
+	// The adjustment is inherited by the child
+	pid = TEST_SUCC(fork());
+	if (pid == 0)
+		_exit(read_number(OOM_SCORE_ADJ) == -1000 ? 0 : 1);
+	TEST_RES(waitpid(pid, &status, 0), _ret == pid);
+	TEST_RES(status, WIFEXITED(_ret) && WEXITSTATUS(_ret) == 0);
+
+	TEST_SUCC(write_file(OOM_SCORE_ADJ, "0"));
+}
+END_TEST()
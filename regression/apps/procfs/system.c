@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <sys/sysinfo.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+static char buf[65536];
+
+// Reads the whole file into `buf`, and returns the length of the file.
+static long read_file(const char *path)
+{
+	long len = 0, n;
+	int fd;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+	while ((n = read(fd, buf + len, sizeof(buf) - 1 - len)) > 0)
+		len += n;
+	close(fd);
+	if (n < 0)
+		return -1;
+	buf[len] = '\0';
+	return len;
+}
+
+// Returns the line that starts with `prefix` in `buf`, or NULL if not found.
+static char *find_line(const char *prefix)
+{
+	char *line = buf;
+
+	while (line && *line) {
+		if (strncmp(line, prefix, strlen(prefix)) == 0)
+			return line;
+		line = strchr(line, '\n');
+		if (line)
+			line++;
+	}
+	return NULL;
+}
+
+// Returns the value of a field in `/proc/meminfo`, in kilobytes.
+static long meminfo_field(const char *name)
+{
+	char *line;
+
+	if (read_file("/proc/meminfo") < 0)
+		return -1;
+	line = find_line(name);
+	if (!line)
+		return -1;
+	return strtol(line + strlen(name), NULL, 10);
+}
+
+FN_TEST(meminfo)
+{
+	long total;
+
+	total = TEST_RES(meminfo_field("MemTotal:"), _ret > 0);
+	TEST_RES(meminfo_field("MemFree:"), _ret > 0 && _ret <= total);
+	TEST_RES(meminfo_field("MemAvailable:"), _ret > 0 && _ret <= total);
+	TEST_RES(read_file("/proc/meminfo"), find_line("SwapTotal:"));
+}
+END_TEST()
+
+FN_TEST(cpuinfo)
+{
+	TEST_RES(read_file("/proc/cpuinfo"), find_line("processor\t: 0\n"));
+	TEST_RES(get_nprocs(), _ret >= 1);
+	TEST_RES(get_nprocs_conf(), _ret >= 1);
+}
+END_TEST()
+
+static double uptime, idle_time;
+
+// Parses the fields in `/proc/uptime`.
+static int parse_uptime(void)
+{
+	if (read_file("/proc/uptime") < 0)
+		return -1;
+	return sscanf(buf, "%lf %lf\n", &uptime, &idle_time);
+}
+
+FN_TEST(uptime)
+{
+	TEST_RES(parse_uptime(), _ret == 2);
+	TEST_RES(uptime > 0 && idle_time >= 0, _ret);
+}
+END_TEST()
+
+static double loads[3];
+static int nr_running, nr_threads, last_pid;
+
+// Parses the fields in `/proc/loadavg`.
+static int parse_loadavg(void)
+{
+	if (read_file("/proc/loadavg") < 0)
+		return -1;
+	return sscanf(buf, "%lf %lf %lf %d/%d %d\n", &loads[0], &loads[1],
+		      &loads[2], &nr_running, &nr_threads, &last_pid);
+}
+
+FN_TEST(loadavg)
+{
+	TEST_RES(parse_loadavg(), _ret == 6);
+	TEST_RES(loads[0] >= 0 && loads[1] >= 0 && loads[2] >= 0, _ret);
+	// At least the current thread is running
+	TEST_RES(nr_running, _ret >= 1 && _ret <= nr_threads);
+	TEST_RES(last_pid, _ret >= getpid());
+}
+END_TEST()
+
+FN_TEST(stat)
+{
+	TEST_RES(read_file("/proc/stat"), find_line("cpu  "));
+	TEST_RES(read_file("/proc/stat"), find_line("cpu0 "));
+	TEST_RES(read_file("/proc/stat"), find_line("ctxt "));
+	TEST_RES(read_file("/proc/stat"),
+		 strtol(find_line("btime ") + 6, NULL, 10) > 0);
+	TEST_RES(read_file("/proc/stat"),
+		 strtol(find_line("procs_running ") + 14, NULL, 10) >= 1);
+}
+END_TEST()
+
+FN_TEST(mounts)
+{
+	char path[16] = {};
+
+	TEST_RES(readlink("/proc/mounts", path, sizeof(path) - 1),
+		 _ret == 11 && strcmp(path, "self/mounts") == 0);
+	TEST_RES(read_file("/proc/mounts"), strstr(buf, " /proc proc "));
+}
+END_TEST()
+
+FN_TEST(filesystems)
+{
+	TEST_RES(read_file("/proc/filesystems"), find_line("nodev\tproc\n"));
+	TEST_RES(read_file("/proc/filesystems"), strstr(buf, "\text2\n"));
+}
+END_TEST()
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <linux/futex.h>
+#include <stdint.h>
+#include <sys/mman.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define MSEC_TO_NSEC 1000000
+
+static long futex(uint32_t *uaddr, int op, uint32_t val,
+		  const struct timespec *timeout, uint32_t val3)
+{
+	return syscall(SYS_futex, uaddr, op, val, timeout, NULL, val3);
+}
+
+static void add_msec(struct timespec *ts, long msec)
+{
+	ts->tv_nsec += msec * MSEC_TO_NSEC;
+	ts->tv_sec += ts->tv_nsec / 1000000000;
+	ts->tv_nsec %= 1000000000;
+}
+
+static uint32_t futex_word;
+
+FN_TEST(invalid_args)
+{
+	struct timespec timeout = { .tv_nsec = -1 };
+
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_PRIVATE, 1, NULL, 0), EAGAIN);
+	TEST_ERRNO(futex((uint32_t *)((char *)&futex_word + 1),
+			 FUTEX_WAKE_PRIVATE, 1, NULL, 0),
+		   EINVAL);
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_BITSET_PRIVATE, 0, NULL, 0),
+		   EINVAL);
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_PRIVATE, 0, &timeout, 0),
+		   EINVAL);
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAKE | FUTEX_CLOCK_REALTIME, 1,
+			 NULL, 0),
+		   ENOSYS);
+	TEST_RES(futex(&futex_word, FUTEX_WAKE_PRIVATE, 1, NULL, 0), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(relative_timeout)
+{
+	struct timespec timeout = { .tv_nsec = 10 * MSEC_TO_NSEC };
+
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_PRIVATE, 0, &timeout, 0),
+		   ETIMEDOUT);
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT, 0, &timeout, 0), ETIMEDOUT);
+}
+END_TEST()
+
+FN_TEST(absolute_timeout)
+{
+	struct timespec timeout;
+
+	TEST_SUCC(clock_gettime(CLOCK_MONOTONIC, &timeout));
+	add_msec(&timeout, 10);
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_BITSET_PRIVATE, 0, &timeout,
+			 FUTEX_BITSET_MATCH_ANY),
+		   ETIMEDOUT);
+
+	TEST_SUCC(clock_gettime(CLOCK_REALTIME, &timeout));
+	add_msec(&timeout, 10);
+	TEST_ERRNO(futex(&futex_word,
+			 FUTEX_WAIT_BITSET_PRIVATE | FUTEX_CLOCK_REALTIME, 0,
+			 &timeout, FUTEX_BITSET_MATCH_ANY),
+		   ETIMEDOUT);
+
+	// A deadline in the past expires immediately
+	TEST_SUCC(clock_gettime(CLOCK_MONOTONIC, &timeout));
+	TEST_ERRNO(futex(&futex_word, FUTEX_WAIT_BITSET_PRIVATE, 0, &timeout,
+			 FUTEX_BITSET_MATCH_ANY),
+		   ETIMEDOUT);
+}
+END_TEST()
+
+static uint32_t *shared_word;
+
+FN_SETUP(shared_word)
+{
+	shared_word = mmap(NULL, sizeof(*shared_word), PROT_READ | PROT_WRITE,
+			   MAP_SHARED | MAP_ANONYMOUS, -1, 0);
+	CHECK_WITH(shared_word == MAP_FAILED ? -1 : 0, _ret == 0);
+}
+END_SETUP()
+
+FN_TEST(process_shared)
+{
+	uint32_t *word = shared_word;
+	pid_t pid;
+	int status;
+
+	*word = 0;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		struct timespec timeout = { .tv_sec = 1 };
+
+		while (*word == 0) {
+			if (futex(word, FUTEX_WAIT, 0, &timeout, 0) < 0 &&
+			    errno == ETIMEDOUT)
+				_exit(EXIT_FAILURE);
+		}
+		*word = 2;
+		_exit(EXIT_SUCCESS);
+	}
+
+	// Give the child a chance to wait on the futex word
+	TEST_SUCC(usleep(50 * 1000));
+	*word = 1;
+	TEST_SUCC(futex(word, FUTEX_WAKE, 1, NULL, 0));
+
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+	TEST_RES(*word, _ret == 2);
+}
+END_TEST()
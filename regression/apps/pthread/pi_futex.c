@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <linux/futex.h>
+#include <pthread.h>
+#include <stdint.h>
+#include <sys/syscall.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+static long futex(uint32_t *uaddr, int op, uint32_t val, unsigned long val2,
+		  uint32_t *uaddr2, uint32_t val3)
+{
+	return syscall(SYS_futex, uaddr, op, val, val2, uaddr2, val3);
+}
+
+static uint32_t gettid_u32(void)
+{
+	return (uint32_t)syscall(SYS_gettid);
+}
+
+static uint32_t pi_word;
+
+FN_TEST(lock_unlock)
+{
+	uint32_t tid = gettid_u32();
+
+	pi_word = 0;
+	TEST_RES(futex(&pi_word, FUTEX_LOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		 _ret == 0 && pi_word == tid);
+	TEST_ERRNO(futex(&pi_word, FUTEX_LOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		   EDEADLK);
+	TEST_ERRNO(futex(&pi_word, FUTEX_TRYLOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		   EDEADLK);
+	TEST_RES(futex(&pi_word, FUTEX_UNLOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		 _ret == 0 && pi_word == 0);
+	TEST_ERRNO(futex(&pi_word, FUTEX_UNLOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		   EPERM);
+}
+END_TEST()
+
+static void *lock_and_hold(void *arg)
+{
+	futex(&pi_word, FUTEX_LOCK_PI_PRIVATE, 0, 0, NULL, 0);
+	usleep(100 * 1000);
+	futex(&pi_word, FUTEX_UNLOCK_PI_PRIVATE, 0, 0, NULL, 0);
+	return NULL;
+}
+
+FN_TEST(contended)
+{
+	pthread_t thread;
+	struct timespec timeout;
+
+	pi_word = 0;
+	TEST_SUCC(pthread_create(&thread, NULL, lock_and_hold, NULL));
+	TEST_SUCC(usleep(20 * 1000));
+
+	TEST_ERRNO(futex(&pi_word, FUTEX_TRYLOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		   EAGAIN);
+
+	// The timeout of FUTEX_LOCK_PI is measured by CLOCK_REALTIME
+	TEST_SUCC(clock_gettime(CLOCK_REALTIME, &timeout));
+	timeout.tv_nsec += 10 * 1000 * 1000;
+	if (timeout.tv_nsec >= 1000000000) {
+		timeout.tv_sec += 1;
+		timeout.tv_nsec -= 1000000000;
+	}
+	TEST_ERRNO(futex(&pi_word, FUTEX_LOCK_PI_PRIVATE, 0,
+			 (unsigned long)&timeout, NULL, 0),
+		   ETIMEDOUT);
+
+	// The lock is handed off to us when the owner unlocks it
+	TEST_RES(futex(&pi_word, FUTEX_LOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		 _ret == 0 && (pi_word & FUTEX_TID_MASK) == gettid_u32());
+	TEST_RES(futex(&pi_word, FUTEX_UNLOCK_PI_PRIVATE, 0, 0, NULL, 0),
+		 _ret == 0 && pi_word == 0);
+
+	TEST_SUCC(pthread_join(thread, NULL));
+}
+END_TEST()
+
+static uint32_t cond_word;
+static uint32_t requeue_owner;
+
+static void *wait_requeue_pi(void *arg)
+{
+	if (futex(&cond_word, FUTEX_WAIT_REQUEUE_PI_PRIVATE, 0, 0, &pi_word,
+		  0) == 0) {
+		requeue_owner = pi_word & FUTEX_TID_MASK;
+		futex(&pi_word, FUTEX_UNLOCK_PI_PRIVATE, 0, 0, NULL, 0);
+	}
+	return NULL;
+}
+
+FN_TEST(requeue_pi)
+{
+	pthread_t thread;
+
+	pi_word = 0;
+	cond_word = 0;
+	requeue_owner = 0;
+
+	TEST_ERRNO(futex(&cond_word, FUTEX_WAIT_REQUEUE_PI_PRIVATE, 0, 0,
+			 &cond_word, 0),
+		   EINVAL);
+	TEST_ERRNO(futex(&cond_word, FUTEX_CMP_REQUEUE_PI_PRIVATE, 2, 1,
+			 &pi_word, 0),
+		   EINVAL);
+
+	TEST_SUCC(pthread_create(&thread, NULL, wait_requeue_pi, NULL));
+	TEST_SUCC(usleep(20 * 1000));
+
+	TEST_ERRNO(futex(&cond_word, FUTEX_CMP_REQUEUE_PI_PRIVATE, 1, 1,
+			 &pi_word, 1),
+		   EAGAIN);
+	// The waiter acquires the free PI futex and is woken up
+	TEST_RES(futex(&cond_word, FUTEX_CMP_REQUEUE_PI_PRIVATE, 1, 1,
+		       &pi_word, 0),
+		 _ret == 1);
+
+	TEST_SUCC(pthread_join(thread, NULL));
+	TEST_RES(requeue_owner, _ret != 0 && _ret != gettid_u32());
+	TEST_RES(pi_word, _ret == 0);
+}
+END_TEST()
+
+static pthread_mutex_t robust_mutex;
+
+static void *lock_and_exit(void *arg)
+{
+	pthread_mutex_lock(&robust_mutex);
+	return NULL;
+}
+
+FN_TEST(owner_died)
+{
+	pthread_mutexattr_t attr;
+	pthread_t thread;
+
+	TEST_SUCC(pthread_mutexattr_init(&attr));
+	TEST_SUCC(pthread_mutexattr_setprotocol(&attr, PTHREAD_PRIO_INHERIT));
+	TEST_SUCC(pthread_mutexattr_setrobust(&attr, PTHREAD_MUTEX_ROBUST));
+	TEST_SUCC(pthread_mutex_init(&robust_mutex, &attr));
+
+	TEST_SUCC(pthread_create(&thread, NULL, lock_and_exit, NULL));
+	TEST_SUCC(pthread_join(thread, NULL));
+
+	TEST_RES(pthread_mutex_lock(&robust_mutex), _ret == EOWNERDEAD);
+	TEST_SUCC(pthread_mutex_consistent(&robust_mutex));
+	TEST_SUCC(pthread_mutex_unlock(&robust_mutex));
+
+	TEST_SUCC(pthread_mutex_destroy(&robust_mutex));
+	TEST_SUCC(pthread_mutexattr_destroy(&attr));
+}
+END_TEST()
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <sched.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+// The macros cannot be used in the test macros since they contain '%'
+static int cpu_isset(int cpu, cpu_set_t *set)
+{
+	return CPU_ISSET(cpu, set);
+}
+
+static int cpu_count(cpu_set_t *set)
+{
+	return CPU_COUNT(set);
+}
+
+static cpu_set_t orig_set;
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+FN_SETUP(orig_set)
+{
+	CHECK(sched_getaffinity(0, sizeof(orig_set), &orig_set));
+}
+END_SETUP()
+
+FN_TEST(invalid_args)
+{
+	cpu_set_t set;
+
+	CPU_ZERO(&set);
+	TEST_ERRNO(sched_setaffinity(0, sizeof(set), &set), EINVAL);
+	TEST_ERRNO(syscall(SYS_sched_getaffinity, 0, 0, &set), EINVAL);
+	TEST_ERRNO(syscall(SYS_sched_getaffinity, 0, 7, &set), EINVAL);
+	TEST_ERRNO(syscall(SYS_sched_getaffinity, 0, 1UL << 61, &set), EINVAL);
+	TEST_ERRNO(sched_getaffinity(0x7fffffff, sizeof(set), &set), ESRCH);
+}
+END_TEST()
+
+FN_TEST(get_affinity)
+{
+	cpu_set_t set;
+	int cpu;
+
+	// The raw system call returns the size of the kernel mask
+	TEST_RES(syscall(SYS_sched_getaffinity, 0, sizeof(set), &set),
+		 _ret > 0 && _ret <= sizeof(set) && (_ret & 7) == 0);
+
+	cpu = TEST_SUCC(sched_getcpu());
+	TEST_RES(cpu_count(&orig_set), _ret >= 1);
+	TEST_RES(cpu_isset(cpu, &orig_set), _ret);
+}
+END_TEST()
+
+FN_TEST(pin_to_each_cpu)
+{
+	cpu_set_t set;
+
+	for (int cpu = 0; cpu < CPU_SETSIZE; cpu++) {
+		if (!cpu_isset(cpu, &orig_set))
+			continue;
+
+		CPU_ZERO(&set);
+		CPU_SET(cpu, &set);
+		TEST_SUCC(sched_setaffinity(0, sizeof(set), &set));
+		// The thread migrates to the CPU immediately
+		TEST_RES(sched_getcpu(), _ret == cpu);
+
+		CPU_ZERO(&set);
+		TEST_SUCC(sched_getaffinity(0, sizeof(set), &set));
+		TEST_RES(cpu_count(&set), _ret == 1);
+		TEST_RES(cpu_isset(cpu, &set), _ret);
+	}
+
+	TEST_SUCC(sched_setaffinity(0, sizeof(orig_set), &orig_set));
+}
+END_TEST()
+
+FN_TEST(inherited_by_child)
+{
+	cpu_set_t set;
+	int cpu, status;
+	pid_t pid;
+
+	cpu = TEST_SUCC(sched_getcpu());
+	CPU_ZERO(&set);
+	CPU_SET(cpu, &set);
+	TEST_SUCC(sched_setaffinity(0, sizeof(set), &set));
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		CPU_ZERO(&set);
+		if (sched_getaffinity(0, sizeof(set), &set) < 0 ||
+		    cpu_count(&set) != 1 || !cpu_isset(cpu, &set) ||
+		    sched_getcpu() != cpu)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+
+	TEST_SUCC(sched_setaffinity(0, sizeof(orig_set), &orig_set));
+}
+END_TEST()
+
+static int check_unprivileged(pid_t parent)
+{
+	if (setuid(65534) < 0)
+		return -1;
+
+	// The affinity of the threads of another user cannot be changed
+	if (sched_setaffinity(parent, sizeof(orig_set), &orig_set) == 0 ||
+	    errno != EPERM)
+		return -1;
+	if (sched_setaffinity(0, sizeof(orig_set), &orig_set) < 0)
+		return -1;
+	return 0;
+}
+
+FN_TEST(unprivileged)
+{
+	pid_t parent, pid;
+
+	parent = getpid();
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_unprivileged(parent) < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_SUCC(wait_child(pid));
+}
+END_TEST()
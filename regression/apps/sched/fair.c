@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <signal.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+static pid_t spawn_spinner(int nice)
+{
+	pid_t pid = fork();
+
+	if (pid == 0) {
+		if (nice != 0 && setpriority(PRIO_PROCESS, 0, nice) < 0)
+			_exit(EXIT_FAILURE);
+		for (;;)
+			;
+	}
+	return pid;
+}
+
+static long elapsed_msec(const struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(CLOCK_MONOTONIC, &now);
+	return (now.tv_sec - start->tv_sec) * 1000 +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+FN_TEST(spinners_do_not_starve_others)
+{
+	pid_t pids[2];
+	struct timespec start;
+	int status;
+
+	// The children never trap into the kernel, so they must be preempted
+	// by the timer tick for the parent to run.
+	pids[0] = TEST_SUCC(spawn_spinner(0));
+	pids[1] = TEST_SUCC(spawn_spinner(19));
+
+	TEST_SUCC(clock_gettime(CLOCK_MONOTONIC, &start));
+	TEST_SUCC(usleep(50 * 1000));
+	TEST_RES(elapsed_msec(&start), _ret >= 50 && _ret < 1000);
+
+	for (int i = 0; i < 2; i++) {
+		TEST_SUCC(kill(pids[i], SIGKILL));
+		TEST_RES(waitpid(pids[i], &status, 0),
+			 _ret == pids[i] && WIFSIGNALED(status) &&
+				 WTERMSIG(status) == SIGKILL);
+	}
+}
+END_TEST()
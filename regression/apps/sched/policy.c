@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <pthread.h>
+#include <sched.h>
+#include <sys/resource.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define SCHED_POLICY_RESET_ON_FORK 0x40000000
+
+static int set_policy(int policy, int priority)
+{
+	struct sched_param param = { .sched_priority = priority };
+
+	return sched_setscheduler(0, policy, &param);
+}
+
+static int get_priority(void)
+{
+	struct sched_param param;
+
+	if (sched_getparam(0, &param) < 0)
+		return -1;
+	return param.sched_priority;
+}
+
+static int wait_child(pid_t pid)
+{
+	int status;
+
+	if (waitpid(pid, &status, 0) != pid)
+		return -1;
+	if (!WIFEXITED(status) || WEXITSTATUS(status) != EXIT_SUCCESS)
+		return -1;
+	return 0;
+}
+
+FN_SETUP(pin_to_one_cpu)
+{
+	cpu_set_t set;
+
+	// Real-time tasks on the same CPU run in the order of their priorities
+	CPU_ZERO(&set);
+	CPU_SET(sched_getcpu(), &set);
+	CHECK(sched_setaffinity(0, sizeof(set), &set));
+}
+END_SETUP()
+
+FN_TEST(priority_range)
+{
+	TEST_RES(sched_get_priority_min(SCHED_FIFO), _ret == 1);
+	TEST_RES(sched_get_priority_max(SCHED_FIFO), _ret == 99);
+	TEST_RES(sched_get_priority_min(SCHED_RR), _ret == 1);
+	TEST_RES(sched_get_priority_max(SCHED_RR), _ret == 99);
+	TEST_RES(sched_get_priority_min(SCHED_OTHER), _ret == 0);
+	TEST_RES(sched_get_priority_max(SCHED_OTHER), _ret == 0);
+	TEST_RES(sched_get_priority_max(SCHED_BATCH), _ret == 0);
+	TEST_RES(sched_get_priority_max(SCHED_IDLE), _ret == 0);
+	TEST_ERRNO(sched_get_priority_max(-1), EINVAL);
+	TEST_ERRNO(sched_get_priority_min(100), EINVAL);
+}
+END_TEST()
+
+FN_TEST(invalid_args)
+{
+	struct sched_param param = { .sched_priority = 0 };
+
+	TEST_ERRNO(set_policy(SCHED_FIFO, 0), EINVAL);
+	TEST_ERRNO(set_policy(SCHED_RR, 100), EINVAL);
+	TEST_ERRNO(set_policy(SCHED_OTHER, 1), EINVAL);
+	TEST_ERRNO(set_policy(100, 0), EINVAL);
+	TEST_ERRNO(sched_setscheduler(0, SCHED_OTHER, NULL), EINVAL);
+	TEST_ERRNO(sched_setscheduler(-1, SCHED_OTHER, &param), EINVAL);
+	TEST_ERRNO(sched_setscheduler(0x7fffffff, SCHED_OTHER, &param), ESRCH);
+	TEST_ERRNO(sched_getscheduler(0x7fffffff), ESRCH);
+	TEST_ERRNO(sched_getparam(0, NULL), EINVAL);
+}
+END_TEST()
+
+FN_TEST(set_and_get)
+{
+	struct timespec interval;
+
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+	TEST_RES(get_priority(), _ret == 0);
+
+	TEST_SUCC(set_policy(SCHED_FIFO, 10));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_FIFO);
+	TEST_RES(get_priority(), _ret == 10);
+	TEST_SUCC(sched_rr_get_interval(0, &interval));
+	TEST_RES(interval.tv_sec == 0 && interval.tv_nsec == 0, _ret);
+
+	TEST_SUCC(set_policy(SCHED_RR, 20));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_RR);
+	TEST_RES(get_priority(), _ret == 20);
+	TEST_SUCC(sched_rr_get_interval(0, &interval));
+	TEST_RES(interval.tv_sec == 0 && interval.tv_nsec > 0, _ret);
+
+	// `sched_setparam` keeps the policy
+	struct sched_param param = { .sched_priority = 30 };
+	TEST_SUCC(sched_setparam(0, &param));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_RR);
+	TEST_RES(get_priority(), _ret == 30);
+	param.sched_priority = 0;
+	TEST_ERRNO(sched_setparam(0, &param), EINVAL);
+
+	TEST_SUCC(set_policy(SCHED_BATCH, 0));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_BATCH);
+	TEST_SUCC(set_policy(SCHED_OTHER, 0));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+	TEST_RES(get_priority(), _ret == 0);
+}
+END_TEST()
+
+static volatile int has_run;
+
+static void *set_has_run(void *arg)
+{
+	has_run = 1;
+	return NULL;
+}
+
+FN_TEST(fifo_yield)
+{
+	pthread_t thread;
+
+	// The new thread inherits the policy and the priority
+	TEST_SUCC(set_policy(SCHED_FIFO, 10));
+	has_run = 0;
+	TEST_SUCC(pthread_create(&thread, NULL, set_has_run, NULL));
+
+	// The new thread cannot run until the current thread yields
+	TEST_RES(has_run, _ret == 0);
+	TEST_SUCC(sched_yield());
+	TEST_RES(has_run, _ret == 1);
+
+	TEST_SUCC(pthread_join(thread, NULL));
+	TEST_SUCC(set_policy(SCHED_OTHER, 0));
+}
+END_TEST()
+
+FN_TEST(higher_priority_preempts)
+{
+	pthread_attr_t attr;
+	struct sched_param param = { .sched_priority = 10 };
+	pthread_t thread;
+
+	TEST_SUCC(set_policy(SCHED_FIFO, 20));
+	has_run = 0;
+	TEST_SUCC(pthread_attr_init(&attr));
+	TEST_SUCC(pthread_attr_setinheritsched(&attr, PTHREAD_EXPLICIT_SCHED));
+	TEST_SUCC(pthread_attr_setschedpolicy(&attr, SCHED_FIFO));
+	TEST_SUCC(pthread_attr_setschedparam(&attr, &param));
+	TEST_SUCC(pthread_create(&thread, &attr, set_has_run, NULL));
+
+	// The new thread has a lower priority
+	TEST_SUCC(sched_yield());
+	TEST_RES(has_run, _ret == 0);
+
+	// The new thread preempts the current thread once the latter has a lower priority
+	TEST_SUCC(set_policy(SCHED_FIFO, 5));
+	TEST_RES(has_run, _ret == 1);
+
+	TEST_SUCC(pthread_join(thread, NULL));
+	TEST_SUCC(pthread_attr_destroy(&attr));
+	TEST_SUCC(set_policy(SCHED_OTHER, 0));
+}
+END_TEST()
+
+FN_TEST(reset_on_fork)
+{
+	pid_t pid;
+
+	// The child inherits the policy and the priority
+	TEST_SUCC(set_policy(SCHED_FIFO, 10));
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (sched_getscheduler(0) != SCHED_FIFO || get_priority() != 10)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_SUCC(wait_child(pid));
+
+	// The child is reset to the normal policy
+	TEST_SUCC(set_policy(SCHED_FIFO | SCHED_POLICY_RESET_ON_FORK, 10));
+	TEST_RES(sched_getscheduler(0),
+		 _ret == (SCHED_FIFO | SCHED_POLICY_RESET_ON_FORK));
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (sched_getscheduler(0) != SCHED_OTHER || get_priority() != 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_SUCC(wait_child(pid));
+
+	TEST_SUCC(set_policy(SCHED_OTHER, 0));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+}
+END_TEST()
+
+static int check_rtprio_limit(void)
+{
+	struct rlimit limit = { .rlim_cur = 10, .rlim_max = 10 };
+
+	if (setrlimit(RLIMIT_RTPRIO, &limit) < 0 || setuid(65534) < 0)
+		return -1;
+
+	// The priority cannot exceed the limit
+	if (set_policy(SCHED_FIFO, 10) < 0)
+		return -1;
+	if (set_policy(SCHED_FIFO, 11) == 0 || errno != EPERM)
+		return -1;
+	if (set_policy(SCHED_OTHER, 0) < 0)
+		return -1;
+
+	// The real-time policies cannot be used without the limit
+	limit.rlim_cur = limit.rlim_max = 0;
+	if (setrlimit(RLIMIT_RTPRIO, &limit) < 0)
+		return -1;
+	if (set_policy(SCHED_RR, 1) == 0 || errno != EPERM)
+		return -1;
+	return 0;
+}
+
+FN_TEST(rtprio_limit)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (check_rtprio_limit() < 0)
+			_exit(EXIT_FAILURE);
+		_exit(EXIT_SUCCESS);
+	}
+	TEST_SUCC(wait_child(pid));
+}
+END_TEST()
@@ -16,6 +16,11 @@ echo "Start network test......"
 ./unix_server &
 ./unix_client
 ./socketpair
+./unix_scm
+./unix_dgram
+./unix_abstract
+./ipv6
+./icmp
 ./sockoption
 ./listen_backlog
 # ./send_buf_full
@@ -8,7 +8,7 @@ SCRIPT_DIR=/regression
 cd ${SCRIPT_DIR}/..
 
 echo "Start process test......"
-tests="hello_world/hello_world fork/fork execve/execve fork_c/fork signal_c/signal_test pthread/pthread_test hello_pie/hello pty/open_pty"
+tests="hello_world/hello_world fork/fork execve/execve fork_c/fork signal_c/signal_test pthread/pthread_test pthread/futex pthread/pi_futex hello_pie/hello pty/open_pty event/eventfd event/signalfd event/timerfd event/inotify sched/fair sched/affinity sched/policy time/nanosleep time/itimer time/cputime time/settime procfs/pid procfs/system sysfs/sysfs mmap/mremap mmap/msync mmap/madvise mmap/mlock mmap/huge_page mmap/swap"
 for testcase in ${tests}
 do 
     echo "Running test ${testcase}......"
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <dirent.h>
+#include <fcntl.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+static char buf[4096];
+
+// Reads the whole file into `buf`, and returns the length of the file.
+static long read_file(const char *path)
+{
+	long len = 0, n;
+	int fd;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+	while ((n = read(fd, buf + len, sizeof(buf) - 1 - len)) > 0)
+		len += n;
+	close(fd);
+	if (n < 0)
+		return -1;
+	buf[len] = '\0';
+	return len;
+}
+
+// Reads the attribute of an entry in `dir`, and parses it as a number in
+// `base`.
+static long read_attr(const char *dir, const char *name, const char *attr,
+		      int base)
+{
+	char path[256];
+
+	snprintf(path, sizeof(path), "%s/%s/%s", dir, name, attr);
+	if (read_file(path) < 0)
+		return -1;
+	return strtol(buf, NULL, base);
+}
+
+FN_TEST(mounts)
+{
+	TEST_RES(read_file("/proc/mounts"), strstr(buf, " /sys sysfs "));
+	TEST_RES(read_file("/proc/filesystems"), strstr(buf, "\tsysfs\n"));
+}
+END_TEST()
+
+FN_TEST(net)
+{
+	TEST_RES(read_attr("/sys/class/net", "lo", "ifindex", 10), _ret > 0);
+	TEST_RES(read_attr("/sys/class/net", "lo", "mtu", 10), _ret > 0);
+	TEST_RES(read_attr("/sys/class/net", "lo", "type", 10), _ret == 772);
+	TEST_RES(read_attr("/sys/class/net", "lo", "flags", 16), _ret & 0x9);
+	TEST_RES(read_file("/sys/class/net/lo/address"),
+		 strcmp(buf, "00:00:00:00:00:00\n") == 0);
+	TEST_RES(read_file("/sys/class/net/lo/uevent"),
+		 strstr(buf, "INTERFACE=lo\n"));
+	TEST_ERRNO(read_file("/sys/class/net/no_such_iface/mtu"), ENOENT);
+}
+END_TEST()
+
+// Checks all the entries in `dir` by `check`, and returns the number of the
+// entries that fail the check.
+static int check_entries(const char *dir, int (*check)(const char *))
+{
+	struct dirent *entry;
+	int nr_failures = 0;
+	DIR *d;
+
+	d = opendir(dir);
+	if (!d)
+		return -1;
+	while ((entry = readdir(d))) {
+		if (entry->d_name[0] == '.')
+			continue;
+		nr_failures += !check(entry->d_name);
+	}
+	closedir(d);
+	return nr_failures;
+}
+
+static int check_block(const char *name)
+{
+	return read_attr("/sys/block", name, "size", 10) >= 0;
+}
+
+static int check_pci(const char *name)
+{
+	return read_attr("/sys/bus/pci/devices", name, "vendor", 16) > 0 &&
+	       read_attr("/sys/bus/pci/devices", name, "class", 16) >= 0 &&
+	       strncmp(buf, "0x", 2) == 0;
+}
+
+static int check_input(const char *name)
+{
+	char path[256];
+
+	snprintf(path, sizeof(path), "/sys/class/input/%s/name", name);
+	return read_file(path) > 0;
+}
+
+FN_TEST(devices)
+{
+	TEST_RES(check_entries("/sys/block", check_block), _ret == 0);
+	TEST_RES(check_entries("/sys/bus/pci/devices", check_pci), _ret == 0);
+	TEST_RES(check_entries("/sys/class/input", check_input), _ret == 0);
+}
+END_TEST()
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <fcntl.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <sys/times.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define NSEC_PER_SEC 1000000000L
+
+static long to_ns(const struct timespec *ts)
+{
+	return ts->tv_sec * NSEC_PER_SEC + ts->tv_nsec;
+}
+
+static long tv_to_us(const struct timeval *tv)
+{
+	return tv->tv_sec * 1000000L + tv->tv_usec;
+}
+
+// Returns the total CPU time in microseconds.
+static long rusage_us(const struct rusage *usage)
+{
+	return tv_to_us(&usage->ru_utime) + tv_to_us(&usage->ru_stime);
+}
+
+static long clock_ns(clockid_t clock)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clock, &ts) < 0)
+		return -1;
+	return to_ns(&ts);
+}
+
+// Spins in the user space until the given CPU clock advances by `ns`.
+static void spin_for(clockid_t clock, long ns)
+{
+	long start = clock_ns(clock);
+
+	while (clock_ns(clock) - start < ns)
+		;
+}
+
+FN_TEST(cpu_clocks)
+{
+	struct timespec res;
+	long process_start, thread_start, wall_start;
+
+	process_start = clock_ns(CLOCK_PROCESS_CPUTIME_ID);
+	thread_start = clock_ns(CLOCK_THREAD_CPUTIME_ID);
+	wall_start = clock_ns(CLOCK_MONOTONIC);
+	spin_for(CLOCK_THREAD_CPUTIME_ID, 50 * 1000 * 1000);
+
+	TEST_RES(clock_ns(CLOCK_THREAD_CPUTIME_ID),
+		 _ret - thread_start >= 50 * 1000 * 1000);
+	TEST_RES(clock_ns(CLOCK_PROCESS_CPUTIME_ID),
+		 _ret - process_start >= 50 * 1000 * 1000);
+	// The CPU time cannot exceed the elapsed time with one thread
+	TEST_RES(clock_ns(CLOCK_PROCESS_CPUTIME_ID),
+		 _ret - process_start <=
+			 clock_ns(CLOCK_MONOTONIC) - wall_start);
+
+	TEST_RES(clock_getres(CLOCK_PROCESS_CPUTIME_ID, &res),
+		 res.tv_sec == 0 && res.tv_nsec > 0);
+	TEST_RES(clock_getres(CLOCK_MONOTONIC_COARSE, &res),
+		 res.tv_sec == 0 && res.tv_nsec > 1);
+}
+END_TEST()
+
+FN_TEST(clock_getcpuclockid)
+{
+	clockid_t clock;
+	long start;
+
+	TEST_RES(clock_getcpuclockid(0, &clock), _ret == 0);
+	start = clock_ns(clock);
+	TEST_RES(start, _ret >= 0);
+	spin_for(CLOCK_PROCESS_CPUTIME_ID, 10 * 1000 * 1000);
+	TEST_RES(clock_ns(clock), _ret - start >= 10 * 1000 * 1000);
+
+	TEST_RES(clock_getcpuclockid(getpid(), &clock), _ret == 0);
+	TEST_RES(clock_ns(clock), _ret >= start);
+
+	TEST_RES(pthread_getcpuclockid(pthread_self(), &clock), _ret == 0);
+	TEST_RES(clock_ns(clock), _ret > 0);
+
+	// The process does not exist
+	TEST_RES(clock_getcpuclockid(0x7fffff, &clock), _ret == ESRCH);
+}
+END_TEST()
+
+static void *spin_thread(void *arg)
+{
+	spin_for(CLOCK_THREAD_CPUTIME_ID, 50 * 1000 * 1000);
+	return NULL;
+}
+
+FN_TEST(getrusage_and_times)
+{
+	struct rusage self_before, self_after, thread_usage;
+	struct tms tms;
+	pthread_t thread;
+	long thread_start;
+
+	TEST_SUCC(getrusage(RUSAGE_SELF, &self_before));
+	thread_start = clock_ns(CLOCK_THREAD_CPUTIME_ID);
+
+	// The time of other threads counts in the process, not in this thread
+	TEST_SUCC(pthread_create(&thread, NULL, spin_thread, NULL));
+	TEST_SUCC(pthread_join(thread, NULL));
+	TEST_SUCC(getrusage(RUSAGE_SELF, &self_after));
+	TEST_RES(rusage_us(&self_after) - rusage_us(&self_before),
+		 _ret >= 50 * 1000);
+	TEST_RES(getrusage(RUSAGE_THREAD, &thread_usage),
+		 rusage_us(&thread_usage) < rusage_us(&self_after));
+	TEST_RES(clock_ns(CLOCK_THREAD_CPUTIME_ID),
+		 _ret - thread_start < 50 * 1000 * 1000);
+
+	TEST_RES(times(&tms), _ret > 0 && tms.tms_utime + tms.tms_stime >= 5);
+	TEST_ERRNO(getrusage(2, &thread_usage), EINVAL);
+}
+END_TEST()
+
+FN_TEST(children_usage)
+{
+	struct rusage child_usage, children_before, children_after;
+	struct tms tms;
+	int status;
+	pid_t pid;
+
+	TEST_SUCC(getrusage(RUSAGE_CHILDREN, &children_before));
+
+	pid = CHECK(fork());
+	if (pid == 0) {
+		spin_for(CLOCK_PROCESS_CPUTIME_ID, 100 * 1000 * 1000);
+		_exit(0);
+	}
+
+	TEST_RES(wait4(pid, &status, 0, &child_usage),
+		 _ret == pid && WIFEXITED(status) &&
+			 rusage_us(&child_usage) >= 100 * 1000);
+
+	// The time of the reaped child is accumulated to the parent
+	TEST_SUCC(getrusage(RUSAGE_CHILDREN, &children_after));
+	TEST_RES(rusage_us(&children_after) - rusage_us(&children_before),
+		 _ret >= 100 * 1000);
+	TEST_RES(times(&tms), tms.tms_cutime + tms.tms_cstime >= 5);
+}
+END_TEST()
+
+// The PID, the command, the state, the PPID, and then the user time and the
+// system time after nine fields
+static const char *stat_format =
+	"%d (cputime) %c %d %*d %*d %*d %*d %*u %*u %*u %*u %*u %lu %lu";
+
+FN_TEST(proc_stat)
+{
+	char path[64];
+	char buf[1024] = {};
+	char state;
+	int fd, pid, ppid;
+	unsigned long utime, stime;
+
+	snprintf(path, sizeof(path), "/proc/%d/stat", getpid());
+	fd = TEST_SUCC(open(path, O_RDONLY));
+	TEST_RES(read(fd, buf, sizeof(buf) - 1),
+		 _ret > 0 && buf[_ret - 1] == '\n');
+	TEST_SUCC(close(fd));
+
+	TEST_RES(sscanf(buf, stat_format, &pid, &state, &ppid, &utime, &stime),
+		 _ret == 5 && pid == getpid() && ppid == getppid() &&
+			 state == 'R' && utime + stime >= 10);
+}
+END_TEST()
@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <pthread.h>
+#include <signal.h>
+#include <sys/syscall.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define NSEC_PER_SEC 1000000000L
+#define TIMEOUT_NS (5 * NSEC_PER_SEC)
+
+static volatile sig_atomic_t sig_count;
+static volatile int last_signo;
+static volatile int last_code;
+static volatile int last_value;
+static volatile int last_overrun;
+static volatile int first_overrun;
+static volatile pid_t last_tid;
+
+static void handler(int signo, siginfo_t *info, void *ucontext)
+{
+	last_signo = signo;
+	last_code = info->si_code;
+	last_value = info->si_value.sival_int;
+	last_overrun = info->si_overrun;
+	last_tid = syscall(SYS_gettid);
+	if (sig_count == 0)
+		first_overrun = info->si_overrun;
+	sig_count++;
+}
+
+static long now_ns(void)
+{
+	struct timespec ts;
+
+	clock_gettime(CLOCK_MONOTONIC, &ts);
+	return ts.tv_sec * NSEC_PER_SEC + ts.tv_nsec;
+}
+
+// Sleeps until the given number of signals are received, and returns the
+// number of signals received.
+static int sleep_for_signals(int count)
+{
+	long start = now_ns();
+
+	while (sig_count < count && now_ns() - start < TIMEOUT_NS)
+		usleep(1000);
+	// The sleeps are interrupted by the signals
+	errno = 0;
+	return sig_count;
+}
+
+// Spins in the user space until the given number of signals are received, and
+// returns the number of signals received.
+static int spin_for_signals(int count)
+{
+	long start = now_ns();
+	unsigned long i;
+
+	for (i = 1; sig_count < count; ++i) {
+		// Check the time rarely, so that the signals are mostly delivered
+		// when the user space is interrupted rather than after syscalls.
+		if (i % (1UL << 26) == 0 && now_ns() - start >= TIMEOUT_NS)
+			break;
+	}
+	return sig_count;
+}
+
+FN_SETUP(handlers)
+{
+	struct sigaction sa = { .sa_sigaction = handler,
+				.sa_flags = SA_SIGINFO | SA_RESTART };
+
+	CHECK(sigaction(SIGALRM, &sa, NULL));
+	CHECK(sigaction(SIGVTALRM, &sa, NULL));
+	CHECK(sigaction(SIGPROF, &sa, NULL));
+	CHECK(sigaction(SIGUSR1, &sa, NULL));
+	CHECK(sigaction(SIGUSR2, &sa, NULL));
+}
+END_SETUP()
+
+FN_TEST(itimer_real)
+{
+	struct itimerval value = { .it_value = { .tv_usec = 50 * 1000 } };
+	struct itimerval curr;
+	long start;
+
+	sig_count = 0;
+	start = now_ns();
+	TEST_SUCC(setitimer(ITIMER_REAL, &value, NULL));
+	TEST_RES(getitimer(ITIMER_REAL, &curr),
+		 curr.it_value.tv_sec == 0 && curr.it_value.tv_usec > 0 &&
+			 curr.it_value.tv_usec <= 50 * 1000);
+	TEST_RES(sleep_for_signals(1),
+		 _ret == 1 && last_signo == SIGALRM &&
+			 now_ns() - start >= 50 * 1000 * 1000);
+	TEST_RES(getitimer(ITIMER_REAL, &curr),
+		 curr.it_value.tv_sec == 0 && curr.it_value.tv_usec == 0);
+}
+END_TEST()
+
+FN_TEST(alarm_shares_itimer_real)
+{
+	struct itimerval value = { .it_value = { .tv_sec = 2,
+						 .tv_usec = 600 * 1000 } };
+	struct itimerval curr;
+
+	TEST_SUCC(setitimer(ITIMER_REAL, &value, NULL));
+	// The remaining time is rounded to the nearest second
+	TEST_RES(alarm(0), _ret == 3);
+	TEST_RES(getitimer(ITIMER_REAL, &curr),
+		 curr.it_value.tv_sec == 0 && curr.it_value.tv_usec == 0);
+
+	TEST_RES(alarm(10), _ret == 0);
+	TEST_RES(getitimer(ITIMER_REAL, &curr),
+		 curr.it_value.tv_sec > 8 && curr.it_value.tv_sec <= 10);
+	TEST_RES(alarm(0), _ret == 10);
+}
+END_TEST()
+
+FN_TEST(itimer_virtual_and_prof)
+{
+	struct itimerval value = { .it_value = { .tv_usec = 10 * 1000 },
+				   .it_interval = { .tv_usec = 10 * 1000 } };
+	struct itimerval zero = {};
+	struct itimerval old;
+
+	sig_count = 0;
+	TEST_SUCC(setitimer(ITIMER_VIRTUAL, &value, NULL));
+	TEST_RES(spin_for_signals(3), _ret == 3 && last_signo == SIGVTALRM);
+	TEST_RES(setitimer(ITIMER_VIRTUAL, &zero, &old),
+		 old.it_interval.tv_usec == 10 * 1000);
+
+	sig_count = 0;
+	TEST_SUCC(setitimer(ITIMER_PROF, &value, NULL));
+	TEST_RES(spin_for_signals(3), _ret == 3 && last_signo == SIGPROF);
+	TEST_RES(setitimer(ITIMER_PROF, &zero, &old),
+		 old.it_interval.tv_usec == 10 * 1000);
+
+	TEST_ERRNO(setitimer(3, &value, NULL), EINVAL);
+}
+END_TEST()
+
+FN_TEST(posix_timer_signal)
+{
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR1,
+				.sigev_value.sival_int = 42 };
+	struct itimerspec value = { .it_value = { .tv_nsec = 20 * 1000 * 1000 } };
+	struct itimerspec curr;
+	timer_t timer;
+
+	sig_count = 0;
+	TEST_SUCC(timer_create(CLOCK_MONOTONIC, &sev, &timer));
+	TEST_SUCC(timer_settime(timer, 0, &value, NULL));
+	TEST_RES(timer_gettime(timer, &curr),
+		 curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec > 0);
+	TEST_RES(sleep_for_signals(1),
+		 _ret == 1 && last_signo == SIGUSR1 && last_code == SI_TIMER &&
+			 last_value == 42);
+	TEST_RES(timer_gettime(timer, &curr),
+		 curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec == 0);
+	TEST_SUCC(timer_delete(timer));
+	TEST_ERRNO(timer_gettime(timer, &curr), EINVAL);
+	TEST_ERRNO(timer_delete(timer), EINVAL);
+}
+END_TEST()
+
+FN_TEST(posix_timer_abs_time)
+{
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR1 };
+	struct itimerspec value = {};
+	timer_t timer;
+	long deadline;
+
+	sig_count = 0;
+	TEST_SUCC(timer_create(CLOCK_MONOTONIC, &sev, &timer));
+	deadline = now_ns() + 20 * 1000 * 1000;
+	value.it_value.tv_sec = deadline / NSEC_PER_SEC;
+	value.it_value.tv_nsec = deadline % NSEC_PER_SEC;
+	TEST_SUCC(timer_settime(timer, TIMER_ABSTIME, &value, NULL));
+	TEST_RES(sleep_for_signals(1), _ret == 1 && now_ns() >= deadline);
+	TEST_SUCC(timer_delete(timer));
+}
+END_TEST()
+
+static volatile pid_t target_tid;
+
+static void *wait_for_signal(void *arg)
+{
+	target_tid = syscall(SYS_gettid);
+	sleep_for_signals(1);
+	return NULL;
+}
+
+FN_TEST(posix_timer_thread_id)
+{
+	struct sigevent sev = { .sigev_notify = SIGEV_THREAD_ID,
+				.sigev_signo = SIGUSR1,
+				.sigev_value.sival_int = 7 };
+	struct itimerspec value = { .it_value = { .tv_nsec = 20 * 1000 * 1000 } };
+	pthread_t thread;
+	timer_t timer;
+
+	sig_count = 0;
+	target_tid = 0;
+	TEST_SUCC(pthread_create(&thread, NULL, wait_for_signal, NULL));
+	while (target_tid == 0)
+		usleep(1000);
+
+	sev._sigev_un._tid = target_tid;
+	TEST_SUCC(timer_create(CLOCK_MONOTONIC, &sev, &timer));
+	TEST_SUCC(timer_settime(timer, 0, &value, NULL));
+	TEST_SUCC(pthread_join(thread, NULL));
+	TEST_RES(sig_count,
+		 _ret == 1 && last_tid == target_tid && last_value == 7);
+	TEST_SUCC(timer_delete(timer));
+
+	// The thread must be in the same process
+	sev._sigev_un._tid = getppid();
+	TEST_ERRNO(timer_create(CLOCK_MONOTONIC, &sev, &timer), EINVAL);
+}
+END_TEST()
+
+FN_TEST(posix_timer_overrun)
+{
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR2 };
+	struct itimerspec value = { .it_value = { .tv_nsec = 1000 * 1000 },
+				    .it_interval = { .tv_nsec = 1000 * 1000 } };
+	struct itimerspec zero = {};
+	int count, overrun;
+	sigset_t set;
+	timer_t timer;
+
+	sigemptyset(&set);
+	sigaddset(&set, SIGUSR2);
+
+	sig_count = 0;
+	TEST_SUCC(timer_create(CLOCK_MONOTONIC, &sev, &timer));
+	TEST_RES(timer_getoverrun(timer), _ret == 0);
+
+	// While the signal is blocked, the expirations are counted as overruns
+	TEST_SUCC(sigprocmask(SIG_BLOCK, &set, NULL));
+	TEST_SUCC(timer_settime(timer, 0, &value, NULL));
+	TEST_SUCC(usleep(30 * 1000));
+	TEST_SUCC(sigprocmask(SIG_UNBLOCK, &set, NULL));
+	TEST_RES(sig_count, _ret >= 1 && first_overrun > 10);
+
+	// The overruns reported by the last delivered signal are returned,
+	// unless more signals are delivered in the meantime
+	do {
+		count = sig_count;
+		overrun = timer_getoverrun(timer);
+	} while (count != sig_count);
+	TEST_RES(overrun, _ret == last_overrun);
+
+	TEST_SUCC(timer_settime(timer, 0, &zero, NULL));
+	TEST_SUCC(timer_delete(timer));
+}
+END_TEST()
+
+FN_TEST(posix_timer_cpu_clock)
+{
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR1 };
+	struct itimerspec value = { .it_value = { .tv_nsec = 20 * 1000 * 1000 } };
+	timer_t timer;
+
+	sig_count = 0;
+	TEST_SUCC(timer_create(CLOCK_PROCESS_CPUTIME_ID, &sev, &timer));
+	TEST_SUCC(timer_settime(timer, 0, &value, NULL));
+	TEST_RES(spin_for_signals(1), _ret == 1 && last_code == SI_TIMER);
+	TEST_SUCC(timer_delete(timer));
+
+	sig_count = 0;
+	TEST_SUCC(timer_create(CLOCK_THREAD_CPUTIME_ID, &sev, &timer));
+	TEST_SUCC(timer_settime(timer, 0, &value, NULL));
+	TEST_RES(spin_for_signals(1), _ret == 1 && last_code == SI_TIMER);
+	TEST_SUCC(timer_delete(timer));
+
+	TEST_ERRNO(timer_create(CLOCK_MONOTONIC_RAW, &sev, &timer), EOPNOTSUPP);
+}
+END_TEST()
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <time.h>
+
+#include "../network/test.h"
+
+#define NSEC_PER_SEC 1000000000L
+
+static long elapsed_ns(const struct timespec *start, const struct timespec *end)
+{
+	return (end->tv_sec - start->tv_sec) * NSEC_PER_SEC +
+	       (end->tv_nsec - start->tv_nsec);
+}
+
+// Sleeps for the given nanoseconds, and returns the time actually slept or -1
+// if the sleep is too short.
+static long sleep_ns(long ns)
+{
+	struct timespec req = { .tv_sec = ns / NSEC_PER_SEC,
+				.tv_nsec = ns % NSEC_PER_SEC };
+	struct timespec start, end;
+	long elapsed;
+
+	if (clock_gettime(CLOCK_MONOTONIC, &start) < 0 ||
+	    clock_nanosleep(CLOCK_MONOTONIC, 0, &req, NULL) != 0 ||
+	    clock_gettime(CLOCK_MONOTONIC, &end) < 0)
+		return -1;
+
+	elapsed = elapsed_ns(&start, &end);
+	return elapsed >= ns ? elapsed : -1;
+}
+
+// Returns the shortest time slept in the given number of tries, or -1 if any
+// of the sleeps is too short.
+static long min_sleep_ns(long ns, int tries)
+{
+	long min = -1;
+	long elapsed;
+	int i;
+
+	for (i = 0; i < tries; ++i) {
+		elapsed = sleep_ns(ns);
+		if (elapsed < 0)
+			return -1;
+		if (min < 0 || elapsed < min)
+			min = elapsed;
+	}
+	return min;
+}
+
+FN_TEST(never_too_short)
+{
+	TEST_RES(min_sleep_ns(1, 10), _ret > 0);
+	TEST_RES(min_sleep_ns(1500 * 1000, 5), _ret > 0);
+	TEST_RES(min_sleep_ns(20 * 1000 * 1000, 2), _ret > 0);
+}
+END_TEST()
+
+FN_TEST(sub_millisecond)
+{
+	// The sleeps are not rounded up to the timer ticks, whose interval is
+	// one millisecond
+	TEST_RES(min_sleep_ns(100 * 1000, 10), _ret > 0 && _ret < 900 * 1000);
+	TEST_RES(min_sleep_ns(300 * 1000, 10), _ret > 0 && _ret < 900 * 1000);
+}
+END_TEST()
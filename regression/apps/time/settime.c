@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <sys/syscall.h>
+#include <sys/time.h>
+#include <sys/timex.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+#define NSEC_PER_SEC 1000000000L
+
+static long clock_ns(clockid_t clock)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clock, &ts) < 0)
+		return -1;
+	return ts.tv_sec * NSEC_PER_SEC + ts.tv_nsec;
+}
+
+static struct timespec to_timespec(long ns)
+{
+	struct timespec ts = { .tv_sec = ns / NSEC_PER_SEC,
+			       .tv_nsec = ns % NSEC_PER_SEC };
+
+	return ts;
+}
+
+// Sleeps until `ns` after the current time of the clock with an absolute
+// deadline, and returns how late the clock is after the deadline.
+static long sleep_until(clockid_t clock, long ns)
+{
+	long deadline = clock_ns(clock) + ns;
+	struct timespec ts = to_timespec(deadline);
+
+	if (clock_nanosleep(clock, TIMER_ABSTIME, &ts, NULL) != 0)
+		return -1;
+	return clock_ns(clock) - deadline;
+}
+
+FN_TEST(absolute_sleep)
+{
+	struct timespec ts;
+
+	TEST_RES(sleep_until(CLOCK_MONOTONIC, 20 * 1000 * 1000), _ret >= 0);
+	TEST_RES(sleep_until(CLOCK_REALTIME, 20 * 1000 * 1000), _ret >= 0);
+	TEST_RES(sleep_until(CLOCK_BOOTTIME, 20 * 1000 * 1000), _ret >= 0);
+
+	// A deadline in the past returns immediately
+	ts = to_timespec(clock_ns(CLOCK_MONOTONIC) - NSEC_PER_SEC);
+	TEST_RES(clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &ts, NULL),
+		 _ret == 0);
+
+	// The errors are returned instead of set in `errno`
+	ts.tv_nsec = NSEC_PER_SEC;
+	TEST_RES(clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &ts, NULL),
+		 _ret == EINVAL);
+}
+END_TEST()
+
+FN_TEST(clock_settime)
+{
+	long realtime, monotonic, elapsed;
+	struct timespec ts;
+
+	realtime = clock_ns(CLOCK_REALTIME);
+	monotonic = clock_ns(CLOCK_MONOTONIC);
+
+	// Set the real time forward, which does not affect the monotonic time
+	ts = to_timespec(realtime + 1000 * NSEC_PER_SEC);
+	TEST_SUCC(clock_settime(CLOCK_REALTIME, &ts));
+	TEST_RES(clock_ns(CLOCK_REALTIME),
+		 _ret - realtime >= 1000 * NSEC_PER_SEC);
+	TEST_RES(clock_ns(CLOCK_REALTIME_COARSE),
+		 _ret - realtime >= 999 * NSEC_PER_SEC);
+	TEST_RES(clock_ns(CLOCK_MONOTONIC),
+		 _ret - monotonic < 100 * NSEC_PER_SEC);
+
+	// Restore the real time
+	elapsed = clock_ns(CLOCK_MONOTONIC) - monotonic;
+	ts = to_timespec(realtime + elapsed);
+	TEST_SUCC(clock_settime(CLOCK_REALTIME, &ts));
+	TEST_RES(clock_ns(CLOCK_REALTIME) - realtime,
+		 _ret >= elapsed && _ret < elapsed + NSEC_PER_SEC);
+
+	TEST_ERRNO(clock_settime(CLOCK_MONOTONIC, &ts), EINVAL);
+	ts.tv_nsec = -1;
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EINVAL);
+}
+END_TEST()
+
+FN_TEST(settimeofday)
+{
+	struct timeval tv;
+
+	TEST_SUCC(gettimeofday(&tv, NULL));
+	TEST_SUCC(settimeofday(&tv, NULL));
+	// The C library converts the time value without checking NULL
+	TEST_SUCC(syscall(SYS_settimeofday, NULL, NULL));
+
+	tv.tv_usec = 1000 * 1000;
+	TEST_ERRNO(settimeofday(&tv, NULL), EINVAL);
+}
+END_TEST()
+
+FN_TEST(adjtime)
+{
+	struct timeval delta = { .tv_sec = 0, .tv_usec = 1000 };
+	struct timeval zero = {};
+	struct timeval old;
+
+	// Slew the real time, which finishes in a few seconds
+	TEST_SUCC(adjtime(&delta, NULL));
+	TEST_RES(adjtime(NULL, &old), old.tv_sec == 0 && old.tv_usec > 0 &&
+					      old.tv_usec <= 1000);
+
+	// Cancel the slewing
+	TEST_RES(adjtime(&zero, &old), old.tv_sec == 0 && old.tv_usec > 0 &&
+					       old.tv_usec <= 1000);
+	TEST_RES(adjtime(NULL, &old), old.tv_sec == 0 && old.tv_usec == 0);
+}
+END_TEST()
+
+FN_TEST(adjtimex)
+{
+	struct timex tx = {};
+	long realtime;
+
+	TEST_RES(adjtimex(&tx), _ret >= 0 && tx.tolerance > 0 &&
+					tx.tick == 10000 && tx.time.tv_sec > 0);
+
+	tx.modes = ADJ_FREQUENCY;
+	tx.freq = 100 << 16;
+	TEST_SUCC(adjtimex(&tx));
+	tx.modes = 0;
+	TEST_RES(adjtimex(&tx), tx.freq == 100 << 16);
+	tx.modes = ADJ_FREQUENCY;
+	tx.freq = 0;
+	TEST_SUCC(adjtimex(&tx));
+
+	// Shift the real time forward and backward
+	realtime = clock_ns(CLOCK_REALTIME);
+	tx.modes = ADJ_SETOFFSET;
+	tx.time.tv_sec = 100;
+	tx.time.tv_usec = 0;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(clock_ns(CLOCK_REALTIME) - realtime,
+		 _ret >= 100 * NSEC_PER_SEC);
+	tx.modes = ADJ_SETOFFSET | ADJ_NANO;
+	tx.time.tv_sec = -101;
+	tx.time.tv_usec = NSEC_PER_SEC - 1;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(clock_ns(CLOCK_REALTIME) - realtime,
+		 _ret >= 0 && _ret < NSEC_PER_SEC);
+	tx.modes = ADJ_MICRO;
+	TEST_SUCC(adjtimex(&tx));
+
+	tx.modes = ADJ_TICK;
+	tx.tick = 1;
+	TEST_ERRNO(adjtimex(&tx), EINVAL);
+	tx.modes = 0x8000;
+	TEST_ERRNO(adjtimex(&tx), EINVAL);
+
+	tx.modes = 0;
+	TEST_RES(clock_adjtime(CLOCK_REALTIME, &tx), _ret >= 0);
+	TEST_ERRNO(clock_adjtime(CLOCK_MONOTONIC, &tx), EOPNOTSUPP);
+	TEST_ERRNO(clock_adjtime(100, &tx), EINVAL);
+}
+END_TEST()